@@ -1,55 +1,100 @@
-#[cfg(not(feature = "library"))]
-use cosmwasm_std::entry_point;
-use cosmwasm_std::{ensure, Deps, DepsMut, Empty, Env, MessageInfo, QueryResponse, Response};
+use cosmwasm_std::{
+    ensure, ensure_ne, to_json_binary, Addr, CustomQuery, Deps, DepsMut, Empty, Env, MessageInfo,
+    QueryResponse, Reply, Response, SubMsgResult,
+};
 
+use hpl_error::HplError;
 use hpl_interface::{
-    core::mailbox::{ExecuteMsg, InstantiateMsg, MailboxHookQueryMsg, MailboxQueryMsg, QueryMsg},
+    core::mailbox::{
+        EventBodyMode, ExecuteMsg, InstantiateMsg, MailboxHookQueryMsg, MailboxQuery,
+        MailboxQueryMsg, MultiQueryResponse, QueryMsg, SudoMsg,
+    },
     to_binary,
+    types::Message,
 };
 
 use crate::{
     error::ContractError,
-    event::emit_instantiated,
-    state::{Config, CONFIG, NONCE},
-    CONTRACT_NAME, CONTRACT_VERSION,
+    event::{emit_deliveries_migrated, emit_instantiated},
+    state::{Config, CONFIG, NONCE, PROCESS_GUARD},
+    CONTRACT_NAME, CONTRACT_VERSION, DEFAULT_MAX_BODY_SIZE, MAX_MULTI_QUERY_SIZE,
+    REPLY_ID_POST_DISPATCH, REPLY_ID_PROCESS,
 };
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
+const MIGRATE_DELIVERIES_BATCH: u32 = 100;
+
+/// Sender reported in events of sudo calls
+pub const SUDO_ACTOR: &str = "sudo";
+
+/// Upper bound of a bech32 human readable part
+const MAX_HRP_LENGTH: usize = 83;
+
+pub(crate) fn validate_hrp(hrp: &str) -> Result<(), ContractError> {
+    ensure!(
+        !hrp.is_empty() && hrp.len() <= MAX_HRP_LENGTH,
+        ContractError::invalid_config("hrp length must be between 1 and 83")
+    );
+    // bech32 allows printable ascii, lowercase only to keep addresses canonical
+    ensure!(
+        hrp.chars()
+            .all(|v| v.is_ascii_graphic() && !v.is_ascii_uppercase()),
+        ContractError::invalid_config("hrp must be lowercase bech32 characters")
+    );
+
+    Ok(())
+}
+
+pub fn instantiate<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    // check hrp is lowercase
-    ensure!(
-        msg.hrp.chars().all(|v| v.is_lowercase()),
-        ContractError::invalid_config("hrp must be lowercase")
+    ensure_ne!(
+        msg.domain,
+        0,
+        ContractError::invalid_config("domain must not be zero")
     );
+    validate_hrp(&msg.hrp)?;
+
+    let owner = deps.api.addr_validate(&msg.owner)?;
 
     let config = Config {
         hrp: msg.hrp,
+        alt_hrps: vec![],
         local_domain: msg.domain,
-        default_ism: None,
-        default_hook: None,
+        default_ism: msg
+            .default_ism
+            .map(|v| deps.api.addr_validate(&v))
+            .transpose()?,
+        default_hook: msg
+            .default_hook
+            .map(|v| deps.api.addr_validate(&v))
+            .transpose()?,
         required_hook: None,
+        max_body_size: Some(DEFAULT_MAX_BODY_SIZE),
+        process_restricted: false,
+        recipients_restricted: false,
+        process_rewards: false,
+        event_body_mode: EventBodyMode::Full,
+        dispatched_ids_retention: None,
     };
 
-    let owner = deps.api.addr_validate(&msg.owner)?;
-
     CONFIG.save(deps.storage, &config)?;
     NONCE.save(deps.storage, &0u32)?;
 
     hpl_ownable::initialize(deps.storage, &owner)?;
 
-    Ok(Response::new().add_event(emit_instantiated(owner)))
+    Ok(Response::new()
+        .add_event(emit_instantiated(owner, &config))
+        .set_data(to_json_binary(&config)?))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
+#[hpl_ownable::route_ownable]
+pub fn execute<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
@@ -58,66 +103,324 @@ pub fn execute(
     use ExecuteMsg::*;
 
     match msg {
-        Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
-
-        SetDefaultIsm { ism } => execute::set_default_ism(deps, info, ism),
-        SetDefaultHook { hook } => execute::set_default_hook(deps, info, hook),
-        SetRequiredHook { hook } => execute::set_required_hook(deps, info, hook),
+        SetDefaultIsm { ism, unchecked } => execute::set_default_ism(deps, info, ism, unchecked),
+        SetDefaultHook { hook, unchecked } => {
+            execute::set_default_hook(deps, info, hook, unchecked)
+        }
+        SetRequiredHook { hook, unchecked } => {
+            execute::set_required_hook(deps, info, hook, unchecked)
+        }
+        SetMaxBodySize { max_body_size } => execute::set_max_body_size(deps, info, max_body_size),
+        SetContextIsm { ism, enabled } => execute::set_context_ism(deps, info, ism, enabled),
+        SetApprovedDispatcher {
+            dispatcher,
+            approved,
+        } => execute::set_approved_dispatcher(deps, info, dispatcher, approved),
+        SetProcessAuthorization { mode } => execute::set_process_authorization(deps, info, mode),
+        SetRecipientAllowlist { recipients } => {
+            execute::set_recipient_allowlist(deps, info, recipients)
+        }
+        SetDomainName { domain, name } => execute::set_domain_name(deps, info, domain, name),
+        InvalidateIsmCache { recipient } => execute::invalidate_ism_cache(deps, info, recipient),
+        SetAltHrps { hrps } => execute::set_alt_hrps(deps, info, hrps),
+        SetCodeIdIsm { code_id, ism } => execute::set_code_id_ism(deps, info, code_id, ism),
+        SetIsmLookupOptOut { opt_out } => execute::set_ism_lookup_opt_out(deps, info, opt_out),
+        SetIsmCaching { enabled } => execute::set_ism_caching(deps, info, enabled),
+        SetEventBodyMode { mode } => execute::set_event_body_mode(deps, info, mode),
+        SetProcessRewardsEnabled { enabled } => {
+            execute::set_process_rewards_enabled(deps, info, enabled)
+        }
+        SetProcessReward { reward } => execute::set_process_reward(deps, info, reward),
 
         Dispatch(msg) => execute::dispatch(deps, info, msg),
         Process { metadata, message } => execute::process(deps, env, info, metadata, message),
+        ProcessBatch { items } => execute::process_batch(deps, env, info, items),
+        PruneDispatchedIds { below_nonce, limit } => {
+            execute::prune_dispatched_ids(deps, info, below_nonce, limit)
+        }
+        SetDispatchedIdsRetention { retention } => {
+            execute::set_dispatched_ids_retention(deps, info, retention)
+        }
+        MigrateDeliveries { limit } => execute::migrate_deliveries(deps, info, limit),
+        ImportDeliveries { ids } => execute::import_deliveries(deps, info, ids),
+        SnapshotConfig {} => execute::snapshot_config(deps, env, info),
+    }
+}
+
+/// Sudo is only reachable by the chain itself, so there's no owner check.
+/// Events report `SUDO_ACTOR` as the sender.
+pub fn sudo<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    _env: Env,
+    msg: SudoMsg,
+) -> Result<Response, ContractError> {
+    use crate::execute;
+
+    let actor = Addr::unchecked(SUDO_ACTOR);
+
+    match msg {
+        SudoMsg::SetDefaultIsm { ism, unchecked } => {
+            execute::store_default_ism(deps, actor, ism, unchecked)
+        }
+        SudoMsg::SetDefaultHook { hook, unchecked } => {
+            execute::store_default_hook(deps, actor, hook, unchecked)
+        }
+    }
+}
+
+pub fn reply<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    _env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
+    match msg.id {
+        REPLY_ID_PROCESS => {
+            PROCESS_GUARD.exit(deps.storage);
+
+            Ok(Response::new())
+        }
+
+        // hooks are only replied to on error
+        REPLY_ID_POST_DISPATCH => match msg.result {
+            SubMsgResult::Ok(_) => Ok(Response::new()),
+            SubMsgResult::Err(reason) => Err(ContractError::HookFailed { reason }),
+        },
+
+        _ => Err(HplError::InvalidReplyId {}.into()),
     }
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+#[hpl_ownable::route_ownable]
+pub fn query<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    env: Env,
+    msg: QueryMsg,
+) -> Result<QueryResponse, ContractError> {
+    match msg {
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
+        QueryMsg::Hook(msg) => query_hook(deps, msg),
+        QueryMsg::Mailbox(msg) => query_mailbox(deps, msg),
+    }
+}
+
+fn query_hook<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    msg: MailboxHookQueryMsg,
+) -> Result<QueryResponse, ContractError> {
+    use crate::query::*;
+
+    match msg {
+        MailboxHookQueryMsg::QuoteDispatch { sender, msg } => {
+            to_binary(quote_dispatch(deps, sender, msg))
+        }
+    }
+}
+
+fn query_mailbox<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    msg: MailboxQueryMsg,
+) -> Result<QueryResponse, ContractError> {
     use crate::query::*;
     use MailboxQueryMsg::*;
 
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
-        QueryMsg::Hook(msg) => match msg {
-            MailboxHookQueryMsg::QuoteDispatch { sender, msg } => {
-                to_binary(quote_dispatch(deps, sender, msg))
-            }
-        },
-        QueryMsg::Mailbox(msg) => match msg {
-            Hrp {} => to_binary(get_hrp(deps)),
-            LocalDomain {} => to_binary(get_local_domain(deps)),
-            DefaultIsm {} => to_binary(get_default_ism(deps)),
-            DefaultHook {} => to_binary(get_default_hook(deps)),
-            RequiredHook {} => to_binary(get_required_hook(deps)),
-            MessageDelivered { id } => to_binary(get_delivered(deps, id)),
-            RecipientIsm { recipient_addr } => to_binary(get_recipient_ism(deps, recipient_addr)),
-            Nonce {} => to_binary(get_nonce(deps)),
-            LatestDispatchId {} => to_binary(get_latest_dispatch_id(deps)),
-        },
+        Hrp {} => to_binary(get_hrp(deps)),
+        LocalDomain {} => to_binary(get_local_domain(deps)),
+        DefaultIsm {} => to_binary(get_default_ism(deps)),
+        DefaultHook {} => to_binary(get_default_hook(deps)),
+        RequiredHook {} => to_binary(get_required_hook(deps)),
+        MaxBodySize {} => to_binary(get_max_body_size(deps)),
+        DispatchedIdsRetention {} => to_binary(get_dispatched_ids_retention(deps)),
+        Config {} => to_binary(get_config(deps)),
+        Ready {} => to_binary(get_ready(deps)),
+        MessageDelivered { id } => to_binary(get_delivered(deps, id)),
+        ExportDeliveries { start_after, limit } => {
+            to_binary(get_deliveries(deps, start_after, limit))
+        }
+        RecipientIsm { recipient_addr } => to_binary(get_recipient_ism(deps, recipient_addr)),
+        ContextIsms {} => to_binary(get_context_isms(deps)),
+        CodeIdIsm { code_id } => to_binary(get_code_id_ism(deps, code_id)),
+        ApprovedDispatchers {} => to_binary(get_approved_dispatchers(deps)),
+        ProcessAuthorization {} => to_binary(get_process_authorization(deps)),
+        RecipientAllowlist {} => to_binary(get_recipient_allowlist(deps)),
+        ProcessReward { recipient } => to_binary(get_process_reward(deps, recipient)),
+        EventBodyMode {} => to_binary(get_event_body_mode(deps)),
+        DomainName { domain } => to_binary(get_domain_name(deps, domain)),
+        DomainNames {} => to_binary(get_domain_names(deps)),
+        Verify { metadata, message } => to_binary(verify(deps, metadata, message)),
+        Nonce {} => to_binary(get_nonce(deps)),
+        LatestDispatchId {} => to_binary(get_latest_dispatch_id(deps)),
+        MessageId {
+            version,
+            nonce,
+            origin_domain,
+            sender,
+            dest_domain,
+            recipient,
+            body,
+        } => to_binary(get_message_id(Message {
+            version,
+            nonce,
+            origin_domain,
+            sender,
+            dest_domain,
+            recipient,
+            body,
+        })),
+        DispatchedIdAtNonce { nonce } => to_binary(get_dispatched_id_at_nonce(deps, nonce)),
+        DispatchedIds { start_after, limit } => {
+            to_binary(get_dispatched_ids(deps, start_after, limit))
+        }
+        Snapshot { id } => to_binary(get_snapshot(deps, id)),
+        Snapshots { start_after, limit } => to_binary(get_snapshots(deps, start_after, limit)),
+        MultiQuery { queries } => to_binary(multi_query(deps, queries)),
     }
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
-    Ok(Response::default())
+fn multi_query<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    queries: Vec<MailboxQuery>,
+) -> Result<MultiQueryResponse, ContractError> {
+    ensure!(
+        queries.len() <= MAX_MULTI_QUERY_SIZE,
+        ContractError::MultiQueryTooLarge {
+            size: queries.len(),
+            max: MAX_MULTI_QUERY_SIZE,
+        }
+    );
+
+    let results = queries
+        .into_iter()
+        .map(|query| match query {
+            MailboxQuery::Hook(msg) => query_hook(deps, msg),
+            MailboxQuery::Mailbox(MailboxQueryMsg::MultiQuery { .. }) => {
+                Err(ContractError::NestedMultiQuery {})
+            }
+            MailboxQuery::Mailbox(msg) => query_mailbox(deps, msg),
+        })
+        .map(|res| res.map_err(|err| err.to_string()))
+        .collect();
+
+    Ok(MultiQueryResponse { results })
+}
+
+pub fn migrate<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    _env: Env,
+    _msg: Empty,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    crate::execute::migrate_latest_dispatched_id(deps.storage)?;
+    crate::execute::migrate_latest_dispatched_nonce(deps.storage)?;
+    crate::execute::migrate_max_body_size(deps.storage)?;
+
+    // remaining records can be migrated with `MigrateDeliveries`
+    let (migrated, finished) =
+        crate::execute::migrate_deliveries_batch(deps.storage, MIGRATE_DELIVERIES_BATCH)?;
+
+    Ok(Response::new().add_event(emit_deliveries_migrated(migrated, finished)))
+}
+
+/// Entry points of the shipped wasm, which doesn't use custom queries
+#[cfg(not(feature = "library"))]
+pub mod entry {
+    use cosmwasm_std::{
+        entry_point, Deps, DepsMut, Empty, Env, MessageInfo, QueryResponse, Reply, Response,
+    };
+    use hpl_interface::core::mailbox::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg};
+
+    use crate::ContractError;
+
+    #[entry_point]
+    pub fn instantiate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        super::instantiate(deps, env, info, msg)
+    }
+
+    #[entry_point]
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        super::execute(deps, env, info, msg)
+    }
+
+    #[entry_point]
+    pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+        super::sudo(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        super::reply(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+        super::query(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn migrate(deps: DepsMut, env: Env, msg: Empty) -> Result<Response, ContractError> {
+        super::migrate(deps, env, msg)
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        from_json,
+        testing::{mock_dependencies, mock_env, mock_info},
+    };
+    use hpl_interface::ownable::OwnableMsg;
+    use hpl_ownable::OwnableError;
     use rstest::rstest;
 
     use super::*;
 
+    use crate::event::{emit_default_hook_set, emit_default_ism_set};
+
+    fn init_msg(hrp: &str, domain: u32) -> InstantiateMsg {
+        InstantiateMsg {
+            hrp: hrp.to_string(),
+            owner: "owner".to_string(),
+            domain,
+            default_ism: None,
+            default_hook: None,
+        }
+    }
+
     #[rstest]
-    #[case("owner", "osmo", 1)]
-    #[case("owner", "neutron", 2)]
-    fn init(#[case] owner: String, #[case] hrp: String, #[case] domain: u32) {
+    #[case("owner", "osmo", 1, None, None)]
+    #[case("owner", "neutron", 2, Some("default_ism"), Some("default_hook"))]
+    #[case("owner", "inj1", 3, Some("default_ism"), None)]
+    fn init(
+        #[case] owner: String,
+        #[case] hrp: String,
+        #[case] domain: u32,
+        #[case] default_ism: Option<&str>,
+        #[case] default_hook: Option<&str>,
+    ) {
         let mut deps = mock_dependencies();
 
-        instantiate(
+        let res = instantiate(
             deps.as_mut(),
             mock_env(),
             mock_info("owner", &[]),
-            InstantiateMsg { owner, hrp, domain },
+            InstantiateMsg {
+                owner: owner.clone(),
+                hrp,
+                domain,
+                default_ism: default_ism.map(String::from),
+                default_hook: default_hook.map(String::from),
+            },
         )
         .unwrap();
 
@@ -132,7 +435,220 @@ mod test {
 
         let config = CONFIG.load(deps.as_ref().storage).unwrap();
 
-        assert_eq!(config.default_hook, None);
-        assert_eq!(config.default_ism, None);
+        assert_eq!(config.default_ism, default_ism.map(Addr::unchecked));
+        assert_eq!(config.default_hook, default_hook.map(Addr::unchecked));
+        assert_eq!(config.required_hook, None);
+
+        assert_eq!(
+            res.events,
+            vec![emit_instantiated(Addr::unchecked(owner), &config)]
+        );
+        assert_eq!(from_json::<Config>(res.data.unwrap()).unwrap(), config);
+    }
+
+    #[rstest]
+    #[case(init_msg("osmo", 0), "domain must not be zero")]
+    #[case(init_msg("", 1), "hrp length must be between 1 and 83")]
+    #[case(init_msg(&"a".repeat(84), 1), "hrp length must be between 1 and 83")]
+    #[case(init_msg("Osmo", 1), "hrp must be lowercase bech32 characters")]
+    #[case(init_msg("os mo", 1), "hrp must be lowercase bech32 characters")]
+    #[case(init_msg("osmo\u{e9}", 1), "hrp must be lowercase bech32 characters")]
+    fn init_invalid_config(#[case] msg: InstantiateMsg, #[case] reason: &str) {
+        let mut deps = mock_dependencies();
+
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+
+        assert_eq!(err, ContractError::invalid_config(reason));
+    }
+
+    #[rstest]
+    #[case(InstantiateMsg { owner: "".to_string(), ..init_msg("osmo", 1) })]
+    #[case(InstantiateMsg { default_ism: Some("".to_string()), ..init_msg("osmo", 1) })]
+    #[case(InstantiateMsg { default_hook: Some("".to_string()), ..init_msg("osmo", 1) })]
+    fn init_invalid_address(#[case] msg: InstantiateMsg) {
+        let mut deps = mock_dependencies();
+
+        let err = instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap_err();
+
+        assert!(matches!(err, ContractError::Hpl(HplError::Std(_))));
+        assert!(CONFIG.may_load(deps.as_ref().storage).unwrap().is_none());
+    }
+
+    #[rstest]
+    #[case("owner", None)]
+    #[case("someone", Some(OwnableError::Unauthorized {}))]
+    fn test_ownership_transfer(#[case] sender: &str, #[case] expected: Option<OwnableError>) {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            init_msg("osmo", 1),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::Ownable(OwnableMsg::InitOwnershipTransfer {
+                next_owner: "next_owner".to_string(),
+                deadline: None,
+            }),
+        );
+
+        match expected {
+            Some(err) => assert_eq!(res.unwrap_err(), ContractError::Hpl(HplError::Ownable(err))),
+            None => {
+                res.unwrap();
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(SudoMsg::SetDefaultIsm { ism: "sudo_ism".to_string(), unchecked: true })]
+    #[case(SudoMsg::SetDefaultHook { hook: "sudo_hook".to_string(), unchecked: true })]
+    fn test_sudo(#[case] msg: SudoMsg) {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            init_msg("osmo", 1),
+        )
+        .unwrap();
+
+        let res = sudo(deps.as_mut(), mock_env(), msg.clone()).unwrap();
+        let sudo_actor = Addr::unchecked(SUDO_ACTOR);
+
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+        match msg.clone() {
+            SudoMsg::SetDefaultIsm { ism, .. } => {
+                assert_eq!(config.default_ism, Some(Addr::unchecked(&ism)));
+                assert_eq!(
+                    res.events,
+                    vec![emit_default_ism_set(sudo_actor, Addr::unchecked(ism))]
+                );
+            }
+            SudoMsg::SetDefaultHook { hook, .. } => {
+                assert_eq!(config.default_hook, Some(Addr::unchecked(&hook)));
+                assert_eq!(
+                    res.events,
+                    vec![emit_default_hook_set(sudo_actor, Addr::unchecked(hook))]
+                );
+            }
+        }
+
+        // the execute path still requires the owner
+        let execute_msg = match msg {
+            SudoMsg::SetDefaultIsm { ism, unchecked } => {
+                ExecuteMsg::SetDefaultIsm { ism, unchecked }
+            }
+            SudoMsg::SetDefaultHook { hook, unchecked } => {
+                ExecuteMsg::SetDefaultHook { hook, unchecked }
+            }
+        };
+        for sender in ["someone", SUDO_ACTOR] {
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                execute_msg.clone(),
+            )
+            .unwrap_err();
+
+            assert_eq!(err, ContractError::Hpl(HplError::Unauthorized {}));
+        }
+    }
+
+    // codes are part of the client interface, never change or reuse one
+    #[rstest]
+    #[case(
+        ContractError::InsufficientDispatchFunds { required: vec![], provided: vec![] },
+        100,
+        "[E100] insufficient dispatch funds: required [], provided []"
+    )]
+    #[case(
+        ContractError::InvalidMessageVersion { version: 2 },
+        101,
+        "[E101] invalid message version: 2"
+    )]
+    #[case(
+        ContractError::InvalidDestinationDomain { domain: 1 },
+        102,
+        "[E102] invalid destination domain: 1"
+    )]
+    #[case(
+        ContractError::BodyTooLarge { size: 2, max: 1 },
+        103,
+        "[E103] message body too large: 2 > 1"
+    )]
+    #[case(
+        ContractError::AlreadyDeliveredMessage {},
+        104,
+        "[E104] message already delivered"
+    )]
+    #[case(
+        ContractError::InvalidMessageIdLength { len: 20 },
+        105,
+        "[E105] invalid message id length: 20"
+    )]
+    #[case(
+        ContractError::ImportTooLarge { size: 2, max: 1 },
+        106,
+        "[E106] too many deliveries to import: 2 > 1"
+    )]
+    #[case(
+        ContractError::MultiQueryTooLarge { size: 2, max: 1 },
+        107,
+        "[E107] too many queries: 2 > 1"
+    )]
+    #[case(
+        ContractError::NestedMultiQuery {},
+        108,
+        "[E108] nested multi query is not allowed"
+    )]
+    #[case(
+        ContractError::InvalidBatchSize { size: 21, max: 20 },
+        112,
+        "[E112] invalid process batch size: 21, expected 1 to 20"
+    )]
+    #[case(
+        ContractError::RecipientNotAllowed { recipient: "osmo1recipient".into() },
+        113,
+        "[E113] recipient not allowed: osmo1recipient"
+    )]
+    #[case(
+        ContractError::DispatchNotCommitted { nonce: 3 },
+        114,
+        "[E114] dispatch of nonce 3 isn't committed"
+    )]
+    #[case(
+        ContractError::NotAnIsm { address: "osmo1hook".into(), error: "unknown variant".into() },
+        115,
+        "[E115] osmo1hook is not an ism: unknown variant"
+    )]
+    #[case(
+        ContractError::NotAHook { address: "osmo1ism".into(), error: "unknown variant".into() },
+        116,
+        "[E116] osmo1ism is not a hook: unknown variant"
+    )]
+    #[case(
+        ContractError::RemoteMsgValue { domain: 26657 },
+        117,
+        "[E117] message value can't be sent to remote domain 26657"
+    )]
+    #[case(
+        ContractError::HookNotSet { hook: "default_hook".into() },
+        118,
+        "[E118] default_hook not set"
+    )]
+    #[case(HplError::VerifyFailed {}.into(), 16, "[E016] verify failed")]
+    fn test_error_codes(#[case] err: ContractError, #[case] code: u16, #[case] golden: &str) {
+        assert_eq!(err.code(), code);
+        assert_eq!(err.to_string(), golden);
+        assert!(golden.starts_with(&hpl_error::code_prefix(code)));
     }
 }
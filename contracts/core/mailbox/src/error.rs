@@ -1,49 +1,107 @@
-use cosmwasm_std::{Coin, StdError};
+use cosmwasm_std::Coin;
+use hpl_error::HplError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
+    #[error(transparent)]
+    Hpl(#[from] HplError),
 
-    #[error("{0}")]
-    Payment(#[from] cw_utils::PaymentError),
-
-    #[error("insufficient hook payment: wanted {wanted:?}, received {received:?}")]
-    HookPayment {
-        wanted: Vec<Coin>,
-        received: Vec<Coin>,
+    /// Funds sent with `dispatch` don't cover the required and the default or custom hook quotes
+    #[error("[E100] insufficient dispatch funds: required {required:?}, provided {provided:?}")]
+    InsufficientDispatchFunds {
+        required: Vec<Coin>,
+        provided: Vec<Coin>,
     },
 
-    #[error("{0}")]
-    CoinsError(#[from] cosmwasm_std::CoinsError),
+    #[error("[E101] invalid message version: {version:?}")]
+    InvalidMessageVersion { version: u8 },
 
-    #[error("unauthorized")]
-    Unauthorized {},
+    #[error("[E102] invalid destination domain: {domain:?}")]
+    InvalidDestinationDomain { domain: u32 },
 
-    #[error("ism verify failed")]
-    VerifyFailed {},
+    #[error("[E103] message body too large: {size} > {max}")]
+    BodyTooLarge { size: u64, max: u64 },
 
-    #[error("invalid config. reason: {reason:?}")]
-    InvalidConfig { reason: String },
+    #[error("[E104] message already delivered")]
+    AlreadyDeliveredMessage {},
 
-    #[error("invalid address length: {len:?}")]
-    InvalidAddressLength { len: usize },
+    #[error("[E105] invalid message id length: {len:?}")]
+    InvalidMessageIdLength { len: usize },
 
-    #[error("invalid message version: {version:?}")]
-    InvalidMessageVersion { version: u8 },
+    #[error("[E106] too many deliveries to import: {size} > {max}")]
+    ImportTooLarge { size: usize, max: usize },
 
-    #[error("invalid destination domain: {domain:?}")]
-    InvalidDestinationDomain { domain: u32 },
+    #[error("[E107] too many queries: {size} > {max}")]
+    MultiQueryTooLarge { size: usize, max: usize },
 
-    #[error("message already delivered")]
-    AlreadyDeliveredMessage {},
+    #[error("[E108] nested multi query is not allowed")]
+    NestedMultiQuery {},
+
+    /// A hook's `post_dispatch` failed. On chain the reason is the redacted error of the hook
+    #[error("[E109] hook failed: {reason}")]
+    HookFailed { reason: String },
+
+    #[error("[E110] unauthorized relayer: {sender}")]
+    UnauthorizedRelayer { sender: String },
+
+    #[error("[E111] invalid message")]
+    InvalidMessage {},
+
+    #[error("[E112] invalid process batch size: {size}, expected 1 to {max}")]
+    InvalidBatchSize { size: usize, max: usize },
+
+    #[error("[E113] recipient not allowed: {recipient}")]
+    RecipientNotAllowed { recipient: String },
+
+    /// `dispatch` built the hook calls of a message it didn't commit, see `invariant`
+    #[error("[E114] dispatch of nonce {nonce} isn't committed")]
+    DispatchNotCommitted { nonce: u32 },
+
+    #[error("[E115] {address} is not an ism: {error}")]
+    NotAnIsm { address: String, error: String },
+
+    #[error("[E116] {address} is not a hook: {error}")]
+    NotAHook { address: String, error: String },
+
+    /// Value is escrowed by this mailbox, so only a local delivery can release it
+    #[error("[E117] message value can't be sent to remote domain {domain}")]
+    RemoteMsgValue { domain: u32 },
+
+    /// Dispatching needs both the default and the required hook of the mailbox
+    #[error("[E118] {hook} not set")]
+    HookNotSet { hook: String },
 }
 
+hpl_error::impl_from_hpl!(ContractError);
+
 impl ContractError {
-    pub fn invalid_config(reason: &str) -> Self {
-        Self::InvalidConfig {
-            reason: reason.to_string(),
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Hpl(err) => err.code(),
+            Self::InsufficientDispatchFunds { .. } => 100,
+            Self::InvalidMessageVersion { .. } => 101,
+            Self::InvalidDestinationDomain { .. } => 102,
+            Self::BodyTooLarge { .. } => 103,
+            Self::AlreadyDeliveredMessage {} => 104,
+            Self::InvalidMessageIdLength { .. } => 105,
+            Self::ImportTooLarge { .. } => 106,
+            Self::MultiQueryTooLarge { .. } => 107,
+            Self::NestedMultiQuery {} => 108,
+            Self::HookFailed { .. } => 109,
+            Self::UnauthorizedRelayer { .. } => 110,
+            Self::InvalidMessage {} => 111,
+            Self::InvalidBatchSize { .. } => 112,
+            Self::RecipientNotAllowed { .. } => 113,
+            Self::DispatchNotCommitted { .. } => 114,
+            Self::NotAnIsm { .. } => 115,
+            Self::NotAHook { .. } => 116,
+            Self::RemoteMsgValue { .. } => 117,
+            Self::HookNotSet { .. } => 118,
         }
     }
+
+    pub fn invalid_config(reason: &str) -> Self {
+        HplError::invalid_config(reason).into()
+    }
 }
@@ -1,47 +1,207 @@
-use cosmwasm_std::{Addr, Event, HexBinary};
-use hpl_interface::types::Message;
+use cosmwasm_std::{Addr, Coin, Event, HexBinary, Uint128};
+use hpl_interface::core::mailbox::EventBodyMode;
 
-pub fn emit_instantiated(owner: Addr) -> Event {
-    Event::new("mailbox_instantiated").add_attribute("owner", owner)
+use crate::state::Config;
+
+fn or_none(v: Option<impl ToString>) -> String {
+    v.map_or("none".to_string(), |v| v.to_string())
+}
+
+pub fn emit_instantiated(owner: Addr, config: &Config) -> Event {
+    Event::new("mailbox_instantiated")
+        .add_attribute("owner", owner)
+        .add_attribute("hrp", &config.hrp)
+        .add_attribute("local_domain", config.local_domain.to_string())
+        .add_attribute("default_ism", or_none(config.default_ism.as_ref()))
+        .add_attribute("default_hook", or_none(config.default_hook.as_ref()))
+        .add_attribute("required_hook", or_none(config.required_hook.as_ref()))
+        .add_attribute("max_body_size", or_none(config.max_body_size))
+}
+
+pub fn emit_default_ism_set(sender: Addr, new_default_ism: Addr) -> Event {
+    hpl_events::ism_set(
+        Event::new("mailbox_default_ism_set"),
+        &sender,
+        &[new_default_ism],
+    )
+}
+
+pub fn emit_default_hook_set(sender: Addr, new_default_hook: Addr) -> Event {
+    hpl_events::hook_set(
+        Event::new("mailbox_default_hook_set"),
+        &sender,
+        &[new_default_hook],
+    )
+}
+
+pub fn emit_required_hook_set(sender: Addr, new_required_hook: Addr) -> Event {
+    hpl_events::hook_set(
+        Event::new("mailbox_required_hook_set"),
+        &sender,
+        &[new_required_hook],
+    )
+}
+
+pub fn emit_context_ism_set(sender: Addr, ism: Addr, enabled: bool) -> Event {
+    hpl_events::ism_set(Event::new("mailbox_context_ism_set"), &sender, &[ism])
+        .add_attribute("enabled", enabled.to_string())
+}
+
+pub fn emit_approved_dispatcher_set(sender: Addr, dispatcher: Addr, approved: bool) -> Event {
+    Event::new("mailbox_approved_dispatcher_set")
+        .add_attribute("sender", sender)
+        .add_attribute("dispatcher", dispatcher)
+        .add_attribute("approved", approved.to_string())
+}
+
+/// `relayers` is `None` in open mode
+pub fn emit_process_authorization_set(sender: Addr, relayers: Option<&[Addr]>) -> Event {
+    Event::new("mailbox_process_authorization_set")
+        .add_attribute("sender", sender)
+        .add_attribute("restricted", relayers.is_some().to_string())
+        .add_attribute(
+            "relayers",
+            relayers
+                .unwrap_or_default()
+                .iter()
+                .map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+}
+
+/// `recipients` is `None` once the restriction is lifted
+pub fn emit_recipient_allowlist_set(sender: Addr, recipients: Option<&[Addr]>) -> Event {
+    Event::new("mailbox_recipient_allowlist_set")
+        .add_attribute("sender", sender)
+        .add_attribute("restricted", recipients.is_some().to_string())
+        .add_attribute(
+            "recipients",
+            recipients
+                .unwrap_or_default()
+                .iter()
+                .map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+}
+
+pub fn emit_domain_name_set(sender: Addr, domain: u32, name: Option<&str>) -> Event {
+    Event::new("mailbox_domain_name_set")
+        .add_attribute("sender", sender)
+        .add_attribute("domain", domain.to_string())
+        .add_attribute("name", or_none(name))
+}
+
+pub fn emit_ism_cache_invalidated(sender: Addr, recipient: Addr) -> Event {
+    Event::new("mailbox_ism_cache_invalidated")
+        .add_attribute("sender", sender)
+        .add_attribute("recipient", recipient)
 }
 
-pub fn emit_default_ism_set(owner: Addr, new_default_ism: Addr) -> Event {
-    Event::new("mailbox_default_ism_set")
+pub fn emit_code_id_ism_set(sender: Addr, code_id: u64, ism: Option<&Addr>) -> Event {
+    Event::new("mailbox_code_id_ism_set")
+        .add_attribute("sender", sender)
+        .add_attribute("code_id", code_id.to_string())
+        .add_attribute("ism", or_none(ism))
+}
+
+pub fn emit_ism_caching_set(recipient: Addr, enabled: bool) -> Event {
+    Event::new("mailbox_ism_caching_set")
+        .add_attribute("recipient", recipient)
+        .add_attribute("enabled", enabled.to_string())
+}
+
+pub fn emit_ism_lookup_opt_out_set(recipient: Addr, opt_out: bool) -> Event {
+    Event::new("mailbox_ism_lookup_opt_out_set")
+        .add_attribute("recipient", recipient)
+        .add_attribute("opt_out", opt_out.to_string())
+}
+
+pub fn emit_max_body_size_set(owner: Addr, max_body_size: Option<u64>) -> Event {
+    Event::new("mailbox_max_body_size_set")
+        .add_attribute("owner", owner)
+        .add_attribute("max_body_size", or_none(max_body_size))
+}
+
+pub fn emit_alt_hrps_set(owner: Addr, hrps: &[String]) -> Event {
+    Event::new("mailbox_alt_hrps_set")
+        .add_attribute("owner", owner)
+        .add_attribute("alt_hrps", hrps.join(","))
+}
+
+pub fn emit_event_body_mode_set(owner: Addr, mode: &EventBodyMode) -> Event {
+    Event::new("mailbox_event_body_mode_set")
         .add_attribute("owner", owner)
-        .add_attribute("new_default_ism", new_default_ism)
+        .add_attribute("mode", mode.to_string())
 }
 
-pub fn emit_default_hook_set(owner: Addr, new_default_hook: Addr) -> Event {
-    Event::new("mailbox_default_hook_set")
+pub fn emit_process_rewards_enabled_set(owner: Addr, enabled: bool) -> Event {
+    Event::new("mailbox_process_rewards_enabled_set")
         .add_attribute("owner", owner)
-        .add_attribute("new_default_hook", new_default_hook)
+        .add_attribute("enabled", enabled.to_string())
+}
+
+pub fn emit_process_reward_set(recipient: Addr, reward: Option<&Coin>, balance: Uint128) -> Event {
+    Event::new("mailbox_process_reward_set")
+        .add_attribute("recipient", recipient)
+        .add_attribute("reward", or_none(reward))
+        .add_attribute("balance", balance)
 }
 
-pub fn emit_required_hook_set(owner: Addr, new_required_hook: Addr) -> Event {
-    Event::new("mailbox_required_hook_set")
+pub fn emit_process_reward_paid(message_id: &HexBinary, relayer: &Addr, reward: &Coin) -> Event {
+    Event::new("mailbox_process_reward_paid")
+        .add_attribute("message_id", message_id.to_hex())
+        .add_attribute("relayer", relayer)
+        .add_attribute("reward", reward.to_string())
+}
+
+pub fn emit_value_escrowed(message_id: &HexBinary, value: &Coin) -> Event {
+    Event::new("mailbox_value_escrowed")
+        .add_attribute("message_id", message_id.to_hex())
+        .add_attribute("value", value.to_string())
+}
+
+pub fn emit_value_released(message_id: &HexBinary, value: &Coin) -> Event {
+    Event::new("mailbox_value_released")
+        .add_attribute("message_id", message_id.to_hex())
+        .add_attribute("value", value.to_string())
+}
+
+pub fn emit_config_snapshot(owner: Addr, id: u64, height: u64) -> Event {
+    Event::new("mailbox_config_snapshot")
         .add_attribute("owner", owner)
-        .add_attribute("new_required_hook", new_required_hook)
+        .add_attribute("id", id.to_string())
+        .add_attribute("height", height.to_string())
 }
 
-pub fn emit_dispatch_id(id: HexBinary) -> Event {
-    Event::new("mailbox_dispatch_id").add_attribute("message_id", id.to_hex())
+pub fn emit_dispatched_ids_pruned(
+    owner: Addr,
+    below_nonce: u32,
+    pruned: usize,
+    finished: bool,
+) -> Event {
+    Event::new("mailbox_dispatched_ids_pruned")
+        .add_attribute("owner", owner)
+        .add_attribute("below_nonce", below_nonce.to_string())
+        .add_attribute("pruned", pruned.to_string())
+        .add_attribute("finished", finished.to_string())
 }
 
-pub fn emit_dispatch(msg: Message) -> Event {
-    Event::new("mailbox_dispatch")
-        .add_attribute("sender", msg.sender.clone().to_hex())
-        .add_attribute("destination", msg.dest_domain.to_string())
-        .add_attribute("recipient", msg.recipient.clone().to_hex())
-        .add_attribute("message", HexBinary::from(msg).to_hex())
+pub fn emit_dispatched_ids_retention_set(owner: Addr, retention: Option<u32>) -> Event {
+    Event::new("mailbox_dispatched_ids_retention_set")
+        .add_attribute("owner", owner)
+        .add_attribute("retention", or_none(retention))
 }
 
-pub fn emit_process_id(id: HexBinary) -> Event {
-    Event::new("mailbox_process_id").add_attribute("message_id", id.to_hex())
+pub fn emit_deliveries_imported(sender: Addr, imported: usize) -> Event {
+    Event::new("mailbox_deliveries_imported")
+        .add_attribute("sender", sender)
+        .add_attribute("imported", imported.to_string())
 }
 
-pub fn emit_process(origin: u32, sender: HexBinary, recipient: HexBinary) -> Event {
-    Event::new("mailbox_process")
-        .add_attribute("origin", format!("{origin}"))
-        .add_attribute("sender", sender.to_hex())
-        .add_attribute("recipient", recipient.to_hex())
+pub fn emit_deliveries_migrated(migrated: u32, finished: bool) -> Event {
+    Event::new("mailbox_deliveries_migrated")
+        .add_attribute("migrated", migrated.to_string())
+        .add_attribute("finished", finished.to_string())
 }
@@ -1,42 +1,109 @@
 use cosmwasm_std::{
-    ensure, ensure_eq, to_json_binary, wasm_execute, Coin, Coins, DepsMut, Env,
-    HexBinary, MessageInfo, Response,
+    ensure, ensure_eq, from_json, to_json_binary, wasm_execute, Addr, BankMsg, Coin, Coins,
+    CustomQuery, DepsMut, Env, Event, HexBinary, MessageInfo, Order, QuerierWrapper, Response,
+    StdResult, Storage, SubMsg, Uint128, WasmMsg,
 };
-use cw_utils::PaymentError::MissingDenom;
+use cw_storage_plus::Bound;
+use hpl_error::HplError;
 use hpl_interface::{
     core::{
-        mailbox::{DispatchMsg, DispatchResponse},
+        mailbox::{
+            ConfigSnapshot, DispatchMsg, EventBodyMode, ProcessAuthorizationMode, ProcessItem,
+        },
         HandleMsg,
     },
-    hook::{post_dispatch, quote_dispatch},
+    hook::{hook_type, quote_dispatch, split_funds},
     ism,
     types::Message,
 };
 
 use hpl_ownable::get_owner;
+use hpl_reentrancy::exit_on_reply;
+use serde::{Deserialize, Deserializer};
 
 use crate::{
+    contract::validate_hrp,
     event::{
-        emit_default_hook_set, emit_default_ism_set, emit_dispatch, emit_dispatch_id, emit_process,
-        emit_process_id, emit_required_hook_set,
+        emit_alt_hrps_set, emit_approved_dispatcher_set, emit_code_id_ism_set,
+        emit_config_snapshot, emit_context_ism_set, emit_default_hook_set, emit_default_ism_set,
+        emit_deliveries_imported, emit_deliveries_migrated, emit_dispatched_ids_pruned,
+        emit_dispatched_ids_retention_set, emit_domain_name_set, emit_event_body_mode_set,
+        emit_ism_cache_invalidated, emit_ism_caching_set, emit_ism_lookup_opt_out_set,
+        emit_max_body_size_set, emit_process_authorization_set, emit_process_reward_paid,
+        emit_process_reward_set, emit_process_rewards_enabled_set, emit_recipient_allowlist_set,
+        emit_required_hook_set, emit_value_escrowed, emit_value_released,
+    },
+    invariant::{commit_dispatch, dispatch_response},
+    query::fallback_ism,
+    state::{
+        Config, ProcessReward, ALLOWED_RECIPIENTS, APPROVED_DISPATCHERS, CODE_ID_ISM, CONFIG,
+        CONTEXT_ISMS, DELIVERIES, DELIVERIES_MIGRATION_CURSOR, DISPATCHED_IDS,
+        DISPATCHED_IDS_PRUNE_CURSOR, DOMAIN_NAMES, LATEST_DISPATCHED_ID, LATEST_DISPATCHED_NONCE,
+        LEGACY_DELIVERIES, LEGACY_LATEST_DISPATCHED_ID, MESSAGE_VALUES, NONCE, PROCESS_GUARD,
+        PROCESS_RELAYERS, PROCESS_REWARDS, RECIPIENT_ISM_CACHE, RECIPIENT_ISM_CACHING,
+        RECIPIENT_ISM_OPTOUT, SNAPSHOTS, SNAPSHOT_ID,
     },
-    state::{Delivery, CONFIG, DELIVERIES, LATEST_DISPATCHED_ID, NONCE},
-    ContractError, MAILBOX_VERSION,
+    ContractError, DEFAULT_MAX_BODY_SIZE, MAILBOX_VERSION, MAX_IMPORT_DELIVERIES,
+    MAX_PROCESS_BATCH, MAX_SNAPSHOTS, REPLY_ID_PROCESS,
 };
 
-pub fn set_default_ism(
-    deps: DepsMut,
+/// Fails unless `ism` answers `ModuleType` with a known type
+fn ensure_ism<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    ism: &Addr,
+    unchecked: bool,
+) -> Result<(), ContractError> {
+    if !unchecked {
+        ism::module_type(querier, ism).map_err(|e| ContractError::NotAnIsm {
+            address: ism.to_string(),
+            error: e.to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Fails unless `hook` answers `HookType` with a known type
+fn ensure_hook<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    hook: &Addr,
+    unchecked: bool,
+) -> Result<(), ContractError> {
+    if !unchecked {
+        hook_type(querier, hook).map_err(|e| ContractError::NotAHook {
+            address: hook.to_string(),
+            error: e.to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+pub fn set_default_ism<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     info: MessageInfo,
     new_default_ism: String,
+    unchecked: bool,
 ) -> Result<Response, ContractError> {
     ensure_eq!(
         get_owner(deps.storage)?,
         info.sender,
-        ContractError::Unauthorized {}
+        HplError::Unauthorized {}
     );
 
+    store_default_ism(deps, info.sender, new_default_ism, unchecked)
+}
+
+/// Skips the owner check, callers must authorize `actor` themselves
+pub fn store_default_ism<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    actor: Addr,
+    new_default_ism: String,
+    unchecked: bool,
+) -> Result<Response, ContractError> {
     let new_default_ism = deps.api.addr_validate(&new_default_ism)?;
-    let event = emit_default_ism_set(info.sender, new_default_ism.clone());
+    ensure_ism(&deps.querier, &new_default_ism, unchecked)?;
+    let event = emit_default_ism_set(actor, new_default_ism.clone());
 
     CONFIG.update(deps.storage, |mut config| -> Result<_, ContractError> {
         config.default_ism = Some(new_default_ism);
@@ -47,19 +114,31 @@ pub fn set_default_ism(
     Ok(Response::new().add_event(event))
 }
 
-pub fn set_default_hook(
-    deps: DepsMut,
+pub fn set_default_hook<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     info: MessageInfo,
     new_default_hook: String,
+    unchecked: bool,
 ) -> Result<Response, ContractError> {
     ensure_eq!(
         get_owner(deps.storage)?,
         info.sender,
-        ContractError::Unauthorized {}
+        HplError::Unauthorized {}
     );
 
+    store_default_hook(deps, info.sender, new_default_hook, unchecked)
+}
+
+/// Skips the owner check, callers must authorize `actor` themselves
+pub fn store_default_hook<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    actor: Addr,
+    new_default_hook: String,
+    unchecked: bool,
+) -> Result<Response, ContractError> {
     let new_default_hook = deps.api.addr_validate(&new_default_hook)?;
-    let event = emit_default_hook_set(info.sender, new_default_hook.clone());
+    ensure_hook(&deps.querier, &new_default_hook, unchecked)?;
+    let event = emit_default_hook_set(actor, new_default_hook.clone());
 
     CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
         config.default_hook = Some(new_default_hook);
@@ -70,18 +149,20 @@ pub fn set_default_hook(
     Ok(Response::new().add_event(event))
 }
 
-pub fn set_required_hook(
-    deps: DepsMut,
+pub fn set_required_hook<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     info: MessageInfo,
     new_required_hook: String,
+    unchecked: bool,
 ) -> Result<Response, ContractError> {
     ensure_eq!(
         get_owner(deps.storage)?,
         info.sender,
-        ContractError::Unauthorized {}
+        HplError::Unauthorized {}
     );
 
     let new_required_hook = deps.api.addr_validate(&new_required_hook)?;
+    ensure_hook(&deps.querier, &new_required_hook, unchecked)?;
     let event = emit_required_hook_set(info.sender, new_required_hook.clone());
 
     CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
@@ -93,573 +174,3513 @@ pub fn set_required_hook(
     Ok(Response::new().add_event(event))
 }
 
-pub fn dispatch(
-    deps: DepsMut,
+pub fn set_max_body_size<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     info: MessageInfo,
-    dispatch_msg: DispatchMsg,
+    max_body_size: Option<u64>,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    let nonce = NONCE.load(deps.storage)?;
-
-    ensure!(
-        dispatch_msg.recipient_addr.len() == 32,
-        ContractError::InvalidAddressLength {
-            len: dispatch_msg.recipient_addr.len()
-        }
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
     );
 
-    // build hyperlane message
-    let msg =
-        dispatch_msg
-            .clone()
-            .to_msg(MAILBOX_VERSION, nonce, config.local_domain, &info.sender)?;
-    let metadata = dispatch_msg.clone().metadata.unwrap_or_default();
-    let hook = dispatch_msg.get_hook_addr(deps.api, config.get_default_hook())?;
+    CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
+        config.max_body_size = max_body_size;
 
-    // assert gas received satisfies required gas
-    let required_hook = config.get_required_hook();
-    let required_hook_fees: Vec<Coin> =
-        quote_dispatch(&deps.querier, &required_hook, metadata.clone(), msg.clone())?.fees;
+        Ok(config)
+    })?;
 
-    let mut funds = Coins::try_from(info.funds.clone())?;
-    for coin in required_hook_fees.iter() {
-        if let Err(_) = funds.sub(coin.clone()) {
-            return Err(ContractError::HookPayment {
-                wanted: required_hook_fees,
-                received: info.funds,
-            });
-        }
+    Ok(Response::new().add_event(emit_max_body_size_set(info.sender, max_body_size)))
+}
+
+pub fn set_alt_hrps<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    hrps: Vec<String>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    for (i, hrp) in hrps.iter().enumerate() {
+        validate_hrp(hrp)?;
+        ensure!(
+            *hrp != config.hrp && !hrps[..i].contains(hrp),
+            ContractError::invalid_config("alternate hrps must be unique and differ from hrp")
+        );
     }
 
-    // commit to message
-    let msg_id = msg.id();
-    NONCE.save(deps.storage, &(nonce + 1))?;
-    LATEST_DISPATCHED_ID.save(deps.storage, &msg_id.to_vec())?;
-
-    // build post dispatch calls
-    let post_dispatch_msgs = vec![
-        post_dispatch(
-            required_hook,
-            metadata.clone(),
-            msg.clone(),
-            Some(required_hook_fees),
-        )?,
-        post_dispatch(hook, metadata, msg.clone(), Some(funds.to_vec()))?,
-    ];
+    config.alt_hrps = hrps;
+    CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::new()
-        .add_event(emit_dispatch_id(msg_id.clone()))
-        .add_event(emit_dispatch(msg))
-        .set_data(to_json_binary(&DispatchResponse { message_id: msg_id })?)
-        .add_messages(post_dispatch_msgs))
+    Ok(Response::new().add_event(emit_alt_hrps_set(info.sender, &config.alt_hrps)))
 }
 
-pub fn process(
-    deps: DepsMut,
-    env: Env,
+pub fn set_event_body_mode<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     info: MessageInfo,
-    metadata: HexBinary,
-    message: HexBinary,
+    mode: EventBodyMode,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
 
-    let decoded_msg: Message = message.into();
-    let recipient = decoded_msg.recipient_addr(&config.hrp)?;
+    CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
+        config.event_body_mode = mode.clone();
+
+        Ok(config)
+    })?;
+
+    Ok(Response::new().add_event(emit_event_body_mode_set(info.sender, &mode)))
+}
 
+pub fn set_process_rewards_enabled<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
     ensure_eq!(
-        decoded_msg.version,
-        MAILBOX_VERSION,
-        ContractError::InvalidMessageVersion {
-            version: decoded_msg.version
-        }
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
     );
+
+    CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
+        config.process_rewards = enabled;
+
+        Ok(config)
+    })?;
+
+    Ok(Response::new().add_event(emit_process_rewards_enabled_set(info.sender, enabled)))
+}
+
+pub fn set_context_ism<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    ism: String,
+    enabled: bool,
+) -> Result<Response, ContractError> {
     ensure_eq!(
-        decoded_msg.dest_domain,
-        config.local_domain,
-        ContractError::InvalidDestinationDomain {
-            domain: decoded_msg.dest_domain
-        }
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
     );
 
-    let id = decoded_msg.id();
-    let ism = ism::recipient(&deps.querier, &recipient)?.unwrap_or(config.get_default_ism());
+    let ism = deps.api.addr_validate(&ism)?;
 
-    ensure!(
-        !DELIVERIES.has(deps.storage, id.to_vec()),
-        ContractError::AlreadyDeliveredMessage {}
+    if enabled {
+        CONTEXT_ISMS.save(deps.storage, ism.clone(), &())?;
+    } else {
+        CONTEXT_ISMS.remove(deps.storage, ism.clone());
+    }
+
+    Ok(Response::new().add_event(emit_context_ism_set(info.sender, ism, enabled)))
+}
+
+pub fn set_approved_dispatcher<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    dispatcher: String,
+    approved: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
     );
 
-    DELIVERIES.save(
-        deps.storage,
-        id.to_vec(),
-        &Delivery {
-            sender: info.sender,
-            block_number: env.block.height,
-        },
-    )?;
+    let dispatcher = deps.api.addr_validate(&dispatcher)?;
 
-    let verify = ism::verify(&deps.querier, ism, metadata, decoded_msg.clone().into())?;
+    if approved {
+        APPROVED_DISPATCHERS.save(deps.storage, dispatcher.clone(), &())?;
+    } else {
+        APPROVED_DISPATCHERS.remove(deps.storage, dispatcher.clone());
+    }
 
-    deps.api
-        .debug(&format!("mailbox::process: verify: {}", verify));
+    Ok(Response::new().add_event(emit_approved_dispatcher_set(
+        info.sender,
+        dispatcher,
+        approved,
+    )))
+}
 
-    ensure!(verify, ContractError::VerifyFailed {});
+pub fn set_process_authorization<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    mode: ProcessAuthorizationMode,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
 
-    let handle_msg = wasm_execute(
-        recipient,
-        &HandleMsg {
-            origin: decoded_msg.origin_domain,
-            sender: decoded_msg.sender.clone(),
-            body: decoded_msg.body,
+    let relayers = match mode {
+        ProcessAuthorizationMode::Open => None,
+        ProcessAuthorizationMode::Restricted { relayers } => {
+            ensure!(
+                !relayers.is_empty(),
+                HplError::invalid_args("restricted mode needs at least one relayer")
+            );
+
+            Some(
+                relayers
+                    .iter()
+                    .map(|v| deps.api.addr_validate(v))
+                    .collect::<StdResult<Vec<_>>>()?,
+            )
         }
-        .wrap(),
-        vec![],
-    )?;
+    };
+
+    PROCESS_RELAYERS.clear(deps.storage);
+    for relayer in relayers.iter().flatten() {
+        PROCESS_RELAYERS.save(deps.storage, relayer.clone(), &())?;
+    }
+
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.process_restricted = relayers.is_some();
+        Ok(config)
+    })?;
 
-    Ok(Response::new().add_message(handle_msg).add_events(vec![
-        emit_process_id(id),
-        emit_process(
-            config.local_domain,
-            decoded_msg.sender,
-            decoded_msg.recipient,
-        ),
-    ]))
+    Ok(Response::new().add_event(emit_process_authorization_set(
+        info.sender,
+        relayers.as_deref(),
+    )))
 }
 
-#[cfg(test)]
-mod tests {
-    use std::collections::HashMap;
+pub fn set_recipient_allowlist<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    recipients: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
 
-    use cosmwasm_std::{
-        coin, from_json,
-        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-        to_json_binary, Addr, ContractResult, CosmosMsg, OwnedDeps, QuerierResult,
-        SystemResult, WasmMsg, WasmQuery,
-    };
+    let config = CONFIG.load(deps.storage)?;
 
-    use hpl_interface::{
-        core::mailbox::InstantiateMsg,
-        hook::{ExpectedHookQueryMsg, HookQueryMsg, PostDispatchMsg, QuoteDispatchResponse},
-        ism::IsmQueryMsg,
-        types::bech32_encode,
-    };
-    use ibcx_test_utils::{addr, gen_bz};
-    use rstest::{fixture, rstest};
+    let recipients = recipients
+        .map(|recipients| {
+            ensure!(
+                !recipients.is_empty(),
+                HplError::invalid_args("allowlist needs at least one recipient")
+            );
+
+            recipients
+                .iter()
+                .map(|v| config.addr_validate(deps.api, v))
+                .collect::<StdResult<Vec<_>>>()
+                .map_err(ContractError::from)
+        })
+        .transpose()?;
+
+    ALLOWED_RECIPIENTS.clear(deps.storage);
+    for recipient in recipients.iter().flatten() {
+        ALLOWED_RECIPIENTS.save(deps.storage, recipient.clone(), &())?;
+    }
 
-    use super::*;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            recipients_restricted: recipients.is_some(),
+            ..config
+        },
+    )?;
 
-    use crate::{contract::instantiate, state::Config};
+    Ok(Response::new().add_event(emit_recipient_allowlist_set(
+        info.sender,
+        recipients.as_deref(),
+    )))
+}
 
-    const OWNER: &str = "owner";
-    const NOT_OWNER: &str = "not_owner";
+pub fn set_domain_name<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    domain: u32,
+    name: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
 
-    const LOCAL_DOMAIN: u32 = 26657;
-    const DEST_DOMAIN: u32 = 11155111;
+    match &name {
+        Some(name) => {
+            ensure!(
+                !name.trim().is_empty(),
+                HplError::invalid_args("domain name must not be empty")
+            );
+            DOMAIN_NAMES.save(deps.storage, domain, name)?;
+        }
+        None => DOMAIN_NAMES.remove(deps.storage, domain),
+    }
 
-    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+    Ok(Response::new().add_event(emit_domain_name_set(info.sender, domain, name.as_deref())))
+}
 
-    fn mock_query_handler(
-        req: &WasmQuery,
-        addr_fees: &Option<HashMap<String, Vec<Coin>>>,
-    ) -> QuerierResult {
-        let (req, addr) = match req {
-            WasmQuery::Smart { msg, contract_addr } => (from_json(msg).unwrap(), contract_addr),
-            _ => unreachable!("wrong query type"),
-        };
+pub fn invalidate_ism_cache<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let recipient = CONFIG
+        .load(deps.storage)?
+        .addr_validate(deps.api, &recipient)?;
 
-        let req = match req {
-            ExpectedHookQueryMsg::Hook(HookQueryMsg::QuoteDispatch(msg)) => msg,
-            _ => unreachable!("wrong query type"),
-        };
+    ensure!(
+        info.sender == recipient || info.sender == get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
 
-        let mut fees = match addr_fees {
-            Some(fees) => fees.get(addr).unwrap_or(&vec![]).clone(),
-            None => vec![],
-        };
+    RECIPIENT_ISM_CACHE.remove(deps.storage, recipient.clone());
 
-        if !req.metadata.is_empty() {
-            let parsed_fee = u32::from_be_bytes(req.metadata.as_slice().try_into().unwrap());
+    Ok(Response::new().add_event(emit_ism_cache_invalidated(info.sender, recipient)))
+}
 
-            fees = vec![coin(parsed_fee as u128, "utest")];
-        }
+pub fn set_code_id_ism<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    code_id: u64,
+    ism: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
 
-        let res = QuoteDispatchResponse { fees };
-        let res = to_json_binary(&res).unwrap();
+    let ism = ism.map(|v| deps.api.addr_validate(&v)).transpose()?;
 
-        SystemResult::Ok(ContractResult::Ok(res))
+    match &ism {
+        Some(ism) => CODE_ID_ISM.save(deps.storage, code_id, ism)?,
+        None => CODE_ID_ISM.remove(deps.storage, code_id),
     }
 
-    #[fixture]
-    fn deps(#[default("deployer")] sender: &str) -> TestDeps {
-        let mut deps = mock_dependencies();
+    Ok(Response::new().add_event(emit_code_id_ism_set(info.sender, code_id, ism.as_ref())))
+}
 
-        instantiate(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(sender, &[]),
-            InstantiateMsg {
-                hrp: "osmo".to_string(),
-                owner: OWNER.to_string(),
-                domain: LOCAL_DOMAIN,
-            },
-        )
-        .unwrap();
+pub fn set_ism_lookup_opt_out<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    opt_out: bool,
+) -> Result<Response, ContractError> {
+    if opt_out {
+        RECIPIENT_ISM_OPTOUT.save(deps.storage, info.sender.clone(), &())?;
+    } else {
+        RECIPIENT_ISM_OPTOUT.remove(deps.storage, info.sender.clone());
+    }
 
-        deps
+    Ok(Response::new().add_event(emit_ism_lookup_opt_out_set(info.sender, opt_out)))
+}
+
+pub fn set_ism_caching<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    if enabled {
+        RECIPIENT_ISM_CACHING.save(deps.storage, info.sender.clone(), &())?;
+    } else {
+        RECIPIENT_ISM_CACHING.remove(deps.storage, info.sender.clone());
+        RECIPIENT_ISM_CACHE.remove(deps.storage, info.sender.clone());
     }
 
-    #[rstest]
-    fn test_init(deps: TestDeps) {
-        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+    Ok(Response::new().add_event(emit_ism_caching_set(info.sender, enabled)))
+}
 
-        assert_eq!(config.hrp, "osmo");
-        assert_eq!(config.local_domain, LOCAL_DOMAIN);
-        assert_eq!(config.default_ism, None);
-        assert_eq!(config.default_hook, None);
-        assert_eq!(config.required_hook, None);
+pub fn set_process_reward<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    reward: Option<Coin>,
+) -> Result<Response, ContractError> {
+    let prev = PROCESS_REWARDS.may_load(deps.storage, info.sender.clone())?;
 
-        let nonce = NONCE.load(deps.as_ref().storage).unwrap();
-        assert_eq!(nonce, 0u32);
-    }
+    let Some(reward) = reward else {
+        cw_utils::nonpayable(&info)?;
+        PROCESS_REWARDS.remove(deps.storage, info.sender.clone());
 
-    #[rstest]
-    #[case(addr(OWNER), addr("default_ism"))]
-    #[should_panic(expected = "unauthorized")]
-    #[case(addr(NOT_OWNER), addr("default_ism"))]
-    fn test_set_default_ism(
-        mut deps: TestDeps,
-        #[case] sender: Addr,
-        #[case] new_default_ism: Addr,
-    ) {
+        return Ok(Response::new()
+            .add_messages(refund_process_reward(&info.sender, prev))
+            .add_event(emit_process_reward_set(info.sender, None, Uint128::zero())));
+    };
+
+    ensure!(
+        !reward.amount.is_zero(),
+        HplError::invalid_args("reward should be positive")
+    );
+
+    let paid = if info.funds.is_empty() {
+        Uint128::zero()
+    } else {
+        cw_utils::must_pay(&info, &reward.denom)?
+    };
+
+    // the balance is kept only while the denom stays the same
+    let (balance, refund) = match prev {
+        Some(prev) if prev.reward.denom == reward.denom => (prev.balance + paid, None),
+        prev => (paid, prev),
+    };
+
+    PROCESS_REWARDS.save(
+        deps.storage,
+        info.sender.clone(),
+        &ProcessReward {
+            reward: reward.clone(),
+            balance,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_messages(refund_process_reward(&info.sender, refund))
+        .add_event(emit_process_reward_set(info.sender, Some(&reward), balance)))
+}
+
+fn refund_process_reward(recipient: &Addr, process_reward: Option<ProcessReward>) -> Vec<BankMsg> {
+    process_reward
+        .filter(|v| !v.balance.is_zero())
+        .map(|v| BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin::new(v.balance.u128(), v.reward.denom)],
+        })
+        .into_iter()
+        .collect()
+}
+
+pub fn dispatch<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    dispatch_msg: DispatchMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let nonce = NONCE.load(deps.storage)?;
+
+    ensure!(
+        dispatch_msg.recipient_addr.len() == 32,
+        HplError::InvalidAddressLength {
+            len: dispatch_msg.recipient_addr.len()
+        }
+    );
+
+    if let Some(max) = config.max_body_size {
+        let size = dispatch_msg.msg_body.len() as u64;
+        ensure!(size <= max, ContractError::BodyTooLarge { size, max });
+    }
+
+    let sender = match dispatch_msg.sender_override.as_ref() {
+        Some(sender_override) => {
+            ensure!(
+                APPROVED_DISPATCHERS.has(deps.storage, info.sender.clone()),
+                HplError::Unauthorized {}
+            );
+
+            deps.api.addr_validate(sender_override)?
+        }
+        None => info.sender.clone(),
+    };
+
+    // build hyperlane message
+    let msg = dispatch_msg
+        .clone()
+        .to_msg(MAILBOX_VERSION, nonce, config.local_domain, &sender)?;
+    let metadata = dispatch_msg.clone().metadata.unwrap_or_default();
+    let hook = dispatch_msg.get_hook_addr(deps.api, config.get_default_hook()?)?;
+    let msg_value = dispatch_msg.msg_value.filter(|v| !v.amount.is_zero());
+    ensure!(
+        msg_value.is_none() || dispatch_msg.dest_domain == config.local_domain,
+        ContractError::RemoteMsgValue {
+            domain: dispatch_msg.dest_domain
+        }
+    );
+
+    // assert funds received satisfy both quotes and the value, refund the rest
+    let required_hook = config.get_required_hook()?;
+    let required_hook_fees: Vec<Coin> =
+        quote_dispatch(&deps.querier, &required_hook, metadata.clone(), msg.clone())?.fees;
+    let hook_fees: Vec<Coin> =
+        quote_dispatch(&deps.querier, &hook, metadata.clone(), msg.clone())?.fees;
+
+    let quote = [
+        required_hook_fees.clone(),
+        hook_fees.clone(),
+        msg_value.clone().into_iter().collect(),
+    ]
+    .concat();
+    let Some((_, refund)) = split_funds(&quote, &info.funds) else {
+        let mut required = Coins::default();
+        for fee in quote {
+            required.add(fee)?;
+        }
+
+        return Err(ContractError::InsufficientDispatchFunds {
+            required: required.into_vec(),
+            provided: info.funds,
+        });
+    };
+
+    // commit to message, hooks are only handed committed messages
+    let msg_id = msg.id();
+    let commit = commit_dispatch(deps.storage, &msg, config.dispatched_ids_retention)?;
+    if let Some(value) = msg_value.as_ref() {
+        MESSAGE_VALUES.save(deps.storage, msg_id.to_vec(), value)?;
+    }
+
+    let mut resp = dispatch_response(
+        &commit,
+        msg,
+        metadata,
+        vec![(required_hook, required_hook_fees), (hook, hook_fees)],
+        &config.event_body_mode,
+    )?
+    .add_events(msg_value.map(|v| emit_value_escrowed(&msg_id, &v)));
+
+    if !refund.is_empty() {
+        resp = resp.add_message(BankMsg::Send {
+            to_address: info.sender.into(),
+            amount: refund,
+        });
+    }
+
+    Ok(resp)
+}
+
+pub fn snapshot_config<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = get_owner(deps.storage)?;
+    ensure_eq!(owner, info.sender, HplError::Unauthorized {});
+
+    let config = CONFIG.load(deps.storage)?;
+    let snapshot = ConfigSnapshot {
+        height: env.block.height,
+        time: env.block.time,
+        owner,
+        hrp: config.hrp,
+        alt_hrps: config.alt_hrps,
+        local_domain: config.local_domain,
+        default_ism: config.default_ism,
+        default_hook: config.default_hook,
+        required_hook: config.required_hook,
+        max_body_size: config.max_body_size,
+        process_restricted: config.process_restricted,
+        recipients_restricted: config.recipients_restricted,
+        process_rewards: config.process_rewards,
+        event_body_mode: config.event_body_mode,
+        nonce: NONCE.load(deps.storage)?,
+    };
+
+    let id = SNAPSHOT_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+    SNAPSHOT_ID.save(deps.storage, &id)?;
+    SNAPSHOTS.save(deps.storage, id, &to_json_binary(&snapshot)?)?;
+
+    if id > MAX_SNAPSHOTS {
+        SNAPSHOTS.remove(deps.storage, id - MAX_SNAPSHOTS);
+    }
+
+    Ok(Response::new().add_event(emit_config_snapshot(info.sender, id, env.block.height)))
+}
+
+pub fn prune_dispatched_ids<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    below_nonce: u32,
+    limit: u32,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
+    ensure!(
+        limit > 0,
+        ContractError::invalid_config("limit must be positive")
+    );
+
+    let cursor = DISPATCHED_IDS_PRUNE_CURSOR.may_load(deps.storage)?;
+    let nonces = DISPATCHED_IDS
+        .keys(
+            deps.storage,
+            cursor.map(Bound::inclusive),
+            Some(Bound::exclusive(below_nonce)),
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for nonce in nonces.iter() {
+        DISPATCHED_IDS.remove(deps.storage, *nonce);
+    }
+
+    let finished = nonces.len() < limit as usize;
+    let next = match nonces.last() {
+        Some(nonce) if !finished => nonce + 1,
+        _ => below_nonce,
+    };
+    if cursor.is_none_or(|cursor| cursor < next) {
+        DISPATCHED_IDS_PRUNE_CURSOR.save(deps.storage, &next)?;
+    }
+
+    Ok(Response::new().add_event(emit_dispatched_ids_pruned(
+        info.sender,
+        below_nonce,
+        nonces.len(),
+        finished,
+    )))
+}
+
+pub fn set_dispatched_ids_retention<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    retention: Option<u32>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
+    ensure!(
+        retention != Some(0),
+        ContractError::invalid_config("retention must be positive")
+    );
+
+    CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
+        config.dispatched_ids_retention = retention;
+
+        Ok(config)
+    })?;
+
+    Ok(Response::new().add_event(emit_dispatched_ids_retention_set(info.sender, retention)))
+}
+
+pub fn migrate_deliveries<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    limit: u32,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
+    ensure!(
+        limit > 0,
+        ContractError::invalid_config("limit must be positive")
+    );
+
+    let (migrated, finished) = migrate_deliveries_batch(deps.storage, limit)?;
+
+    Ok(Response::new().add_event(emit_deliveries_migrated(migrated, finished)))
+}
+
+pub fn import_deliveries<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    ids: Vec<HexBinary>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        get_owner(deps.storage)?,
+        info.sender,
+        HplError::Unauthorized {}
+    );
+    ensure!(
+        ids.len() <= MAX_IMPORT_DELIVERIES,
+        ContractError::ImportTooLarge {
+            size: ids.len(),
+            max: MAX_IMPORT_DELIVERIES,
+        }
+    );
+
+    for id in ids.iter() {
+        ensure_eq!(
+            id.len(),
+            32,
+            ContractError::InvalidMessageIdLength { len: id.len() }
+        );
+
+        DELIVERIES.save(deps.storage, id.to_vec(), &())?;
+    }
+
+    Ok(Response::new().add_event(emit_deliveries_imported(info.sender, ids.len())))
+}
+
+/// Rewrites `LATEST_DISPATCHED_ID` from a byte array to hex.
+/// Hex values don't parse as a byte array, so they're left as is
+pub fn migrate_latest_dispatched_id(storage: &mut dyn Storage) -> StdResult<()> {
+    if let Ok(Some(id)) = LEGACY_LATEST_DISPATCHED_ID.may_load(storage) {
+        LATEST_DISPATCHED_ID.save(storage, &id.into())?;
+    }
+
+    Ok(())
+}
+
+/// Sets `LATEST_DISPATCHED_NONCE` from `NONCE` on mailboxes that dispatched before it was tracked
+pub fn migrate_latest_dispatched_nonce(storage: &mut dyn Storage) -> StdResult<()> {
+    if LATEST_DISPATCHED_NONCE.exists(storage) {
+        return Ok(());
+    }
+
+    match NONCE.may_load(storage)? {
+        Some(nonce) if nonce > 0 => LATEST_DISPATCHED_NONCE.save(storage, &(nonce - 1)),
+        _ => Ok(()),
+    }
+}
+
+/// Sets `DEFAULT_MAX_BODY_SIZE` on mailboxes stored before the body size limit.
+/// A limit removed by the owner is stored as `null` and stays removed
+pub fn migrate_max_body_size(storage: &mut dyn Storage) -> StdResult<()> {
+    #[derive(Deserialize)]
+    struct StoredConfig {
+        // `None` if the field is missing, `Some(None)` if it is `null`
+        #[serde(default, deserialize_with = "present")]
+        max_body_size: Option<Option<u64>>,
+    }
+
+    fn present<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Option<u64>>, D::Error> {
+        Option::deserialize(d).map(Some)
+    }
+
+    let Some(raw) = storage.get(CONFIG.as_slice()) else {
+        return Ok(());
+    };
+
+    if from_json::<StoredConfig>(&raw)?.max_body_size.is_none() {
+        CONFIG.update(storage, |mut config| -> StdResult<_> {
+            config.max_body_size = Some(DEFAULT_MAX_BODY_SIZE);
+            Ok(config)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Rewrites up to `limit` legacy delivery records as unit values, resuming from the last batch.
+/// Returns the number of rewritten records and whether every record has been visited.
+pub fn migrate_deliveries_batch(storage: &mut dyn Storage, limit: u32) -> StdResult<(u32, bool)> {
+    let cursor = DELIVERIES_MIGRATION_CURSOR.may_load(storage)?;
+
+    let entries = LEGACY_DELIVERIES
+        .range(
+            storage,
+            cursor.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut migrated = 0;
+    for (id, delivery) in entries.iter() {
+        if delivery.is_some() {
+            DELIVERIES.save(storage, id.clone(), &())?;
+            migrated += 1;
+        }
+    }
+
+    let finished = entries.len() < limit as usize;
+    match entries.last() {
+        Some((id, _)) if !finished => DELIVERIES_MIGRATION_CURSOR.save(storage, id)?,
+        _ => DELIVERIES_MIGRATION_CURSOR.remove(storage),
+    }
+
+    Ok((migrated, finished))
+}
+
+pub fn process<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    env: Env,
+    info: MessageInfo,
+    metadata: HexBinary,
+    message: HexBinary,
+) -> Result<Response, ContractError> {
+    process_batch(deps, env, info, vec![ProcessItem { metadata, message }])
+}
+
+/// Processes every item like `process`, aborting the whole batch if any of them fails.
+/// The handles run in order, and the guard is held until the last one returns
+pub fn process_batch<C: CustomQuery>(
+    mut deps: DepsMut<'_, C>,
+    _env: Env,
+    info: MessageInfo,
+    items: Vec<ProcessItem>,
+) -> Result<Response, ContractError> {
+    ensure!(
+        !items.is_empty() && items.len() <= MAX_PROCESS_BATCH,
+        ContractError::InvalidBatchSize {
+            size: items.len(),
+            max: MAX_PROCESS_BATCH,
+        }
+    );
+
+    // recipients must not process another message while handling this one
+    PROCESS_GUARD.enter(deps.storage)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    ensure!(
+        !config.process_restricted || PROCESS_RELAYERS.has(deps.storage, info.sender.clone()),
+        ContractError::UnauthorizedRelayer {
+            sender: info.sender.into()
+        }
+    );
+
+    let last = items.len() - 1;
+    let mut resp = Response::new();
+
+    for (i, item) in items.into_iter().enumerate() {
+        let (handle_msg, reward_msg, events) = process_item(
+            deps.branch(),
+            &config,
+            &info.sender,
+            item.metadata,
+            item.message,
+        )?;
+
+        resp = resp
+            .add_messages(reward_msg)
+            .add_events(events)
+            .add_submessage(if i == last {
+                exit_on_reply(handle_msg, REPLY_ID_PROCESS)
+            } else {
+                SubMsg::new(handle_msg)
+            });
+    }
+
+    Ok(resp)
+}
+
+fn process_item<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    config: &Config,
+    relayer: &Addr,
+    metadata: HexBinary,
+    message: HexBinary,
+) -> Result<(WasmMsg, Option<BankMsg>, Vec<Event>), ContractError> {
+    let decoded_msg = Message::try_from(message).map_err(|_| ContractError::InvalidMessage {})?;
+    let recipient = decoded_msg.recipient_addr(&config.hrp)?;
+
+    ensure_eq!(
+        decoded_msg.version,
+        MAILBOX_VERSION,
+        ContractError::InvalidMessageVersion {
+            version: decoded_msg.version
+        }
+    );
+    ensure_eq!(
+        decoded_msg.dest_domain,
+        config.local_domain,
+        ContractError::InvalidDestinationDomain {
+            domain: decoded_msg.dest_domain
+        }
+    );
+
+    ensure!(
+        !config.recipients_restricted || ALLOWED_RECIPIENTS.has(deps.storage, recipient.clone()),
+        ContractError::RecipientNotAllowed {
+            recipient: recipient.to_string()
+        }
+    );
+
+    let id = decoded_msg.id();
+    // recipients that opted out are never queried and follow the code id or default ism.
+    // only recipients that enabled caching have their ism cached
+    let ism = if RECIPIENT_ISM_OPTOUT.has(deps.storage, recipient.clone()) {
+        None
+    } else if RECIPIENT_ISM_CACHING.has(deps.storage, recipient.clone()) {
+        match RECIPIENT_ISM_CACHE.may_load(deps.storage, recipient.clone())? {
+            Some(ism) => Some(ism),
+            None => {
+                let ism = ism::recipient(&deps.querier, &recipient)?;
+                if let Some(ism) = &ism {
+                    RECIPIENT_ISM_CACHE.save(deps.storage, recipient.clone(), ism)?;
+                }
+                ism
+            }
+        }
+    } else {
+        ism::recipient(&deps.querier, &recipient)?
+    };
+    let ism = match ism {
+        Some(ism) => ism,
+        None => fallback_ism(deps.as_ref(), config, &recipient)?,
+    };
+
+    // insert if absent. legacy records read as `Some(Some(_))` and
+    // the `None` saved here is stored like the unit values of `DELIVERIES`
+    LEGACY_DELIVERIES.update(deps.storage, id.to_vec(), |delivery| match delivery {
+        Some(_) => Err(ContractError::AlreadyDeliveredMessage {}),
+        None => Ok(None),
+    })?;
+
+    let verify = if CONTEXT_ISMS.has(deps.storage, ism.clone()) {
+        ism::verify_with_context(
+            &deps.querier,
+            ism,
+            metadata,
+            decoded_msg.clone().into(),
+            relayer,
+        )?
+    } else {
+        ism::verify(&deps.querier, ism, metadata, decoded_msg.clone().into())?
+    };
+
+    deps.api
+        .debug(&format!("mailbox::process: verify: {}", verify));
+
+    ensure!(verify, HplError::VerifyFailed {});
+
+    // value escrowed by a dispatch of this message goes along with it
+    let msg_value = MESSAGE_VALUES.may_load(deps.storage, id.to_vec())?;
+    if msg_value.is_some() {
+        MESSAGE_VALUES.remove(deps.storage, id.to_vec());
+    }
+
+    // the recipient pays the relayer while its reward balance covers it
+    let mut reward_paid = None;
+    if config.process_rewards {
+        if let Some(mut process_reward) =
+            PROCESS_REWARDS.may_load(deps.storage, recipient.clone())?
+        {
+            if process_reward.balance >= process_reward.reward.amount {
+                process_reward.balance -= process_reward.reward.amount;
+                PROCESS_REWARDS.save(deps.storage, recipient.clone(), &process_reward)?;
+
+                reward_paid = Some(process_reward.reward);
+            }
+        }
+    }
+
+    let handle_msg = wasm_execute(
+        recipient,
+        &HandleMsg {
+            origin: decoded_msg.origin_domain,
+            sender: decoded_msg.sender.clone(),
+            body: decoded_msg.body,
+        }
+        .wrap(),
+        msg_value.clone().into_iter().collect(),
+    )?;
+
+    let events = msg_value
+        .map(|v| emit_value_released(&id, &v))
+        .into_iter()
+        .chain([
+            hpl_events::process_id(&id, relayer),
+            hpl_events::process(
+                config.local_domain,
+                &decoded_msg.sender,
+                &decoded_msg.recipient,
+            ),
+        ])
+        .chain(
+            reward_paid
+                .as_ref()
+                .map(|v| emit_process_reward_paid(&id, relayer, v)),
+        )
+        .collect();
+
+    let reward_msg = reward_paid.map(|v| BankMsg::Send {
+        to_address: relayer.to_string(),
+        amount: vec![v],
+    });
+
+    Ok((handle_msg, reward_msg, events))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        collections::HashMap,
+        marker::PhantomData,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+    };
+
+    use cosmwasm_std::{
+        coin, from_json,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        to_json_binary, Addr, ContractResult, CosmosMsg, OwnedDeps, QuerierResult, Record, ReplyOn,
+        SystemResult, WasmMsg, WasmQuery,
+    };
+
+    use hpl_interface::{
+        core::mailbox::{DispatchResponse, DispatchedId, InstantiateMsg},
+        hook::{ExpectedHookQueryMsg, HookQueryMsg, PostDispatchMsg, QuoteDispatchResponse},
+        ism::IsmQueryMsg,
+        types::{bech32_encode, keccak256_hash, pad32},
+    };
+    use ibcx_test_utils::{addr, gen_bz};
+    use rstest::{fixture, rstest};
+
+    use super::*;
+
+    use crate::{
+        contract::instantiate,
+        state::{Config, Delivery},
+        REPLY_ID_POST_DISPATCH,
+    };
+
+    const OWNER: &str = "owner";
+    const NOT_OWNER: &str = "not_owner";
+
+    const LOCAL_DOMAIN: u32 = 26657;
+    const DEST_DOMAIN: u32 = 11155111;
+
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    /// Counts storage calls, pinning the storage cost of hot paths
+    #[derive(Default)]
+    struct CountingStorage {
+        inner: MockStorage,
+        reads: Cell<u32>,
+        writes: u32,
+        written_bytes: usize,
+    }
+
+    impl CountingStorage {
+        /// (reads, writes, written bytes)
+        fn counts(&self) -> (u32, u32, usize) {
+            (self.reads.get(), self.writes, self.written_bytes)
+        }
+    }
+
+    impl Storage for CountingStorage {
+        fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+            self.reads.set(self.reads.get() + 1);
+            self.inner.get(key)
+        }
+
+        fn range<'a>(
+            &'a self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            order: Order,
+        ) -> Box<dyn Iterator<Item = Record> + 'a> {
+            self.reads.set(self.reads.get() + 1);
+            self.inner.range(start, end, order)
+        }
+
+        fn set(&mut self, key: &[u8], value: &[u8]) {
+            self.writes += 1;
+            self.written_bytes += value.len();
+            self.inner.set(key, value)
+        }
+
+        fn remove(&mut self, key: &[u8]) {
+            self.writes += 1;
+            self.inner.remove(key)
+        }
+    }
+
+    /// Moves `deps` onto a `CountingStorage` with zeroed counts
+    fn counting(deps: TestDeps) -> OwnedDeps<CountingStorage, MockApi, MockQuerier> {
+        OwnedDeps {
+            storage: CountingStorage {
+                inner: deps.storage,
+                ..Default::default()
+            },
+            api: deps.api,
+            querier: deps.querier,
+            custom_query_type: PhantomData,
+        }
+    }
+
+    fn mock_query_handler(
+        req: &WasmQuery,
+        addr_fees: &Option<HashMap<String, Vec<Coin>>>,
+    ) -> QuerierResult {
+        let (req, addr) = match req {
+            WasmQuery::Smart { msg, contract_addr } => (from_json(msg).unwrap(), contract_addr),
+            _ => unreachable!("wrong query type"),
+        };
+
+        let req = match req {
+            ExpectedHookQueryMsg::Hook(HookQueryMsg::QuoteDispatch(msg)) => msg,
+            _ => unreachable!("wrong query type"),
+        };
+
+        let mut fees = match addr_fees {
+            Some(fees) => fees.get(addr).unwrap_or(&vec![]).clone(),
+            None => vec![],
+        };
+
+        if !req.metadata.is_empty() {
+            let parsed_fee = u32::from_be_bytes(req.metadata.as_slice().try_into().unwrap());
+
+            fees = vec![coin(parsed_fee as u128, "utest")];
+        }
+
+        let res = QuoteDispatchResponse { fees };
+        let res = to_json_binary(&res).unwrap();
+
+        SystemResult::Ok(ContractResult::Ok(res))
+    }
+
+    #[fixture]
+    fn deps(#[default("deployer")] sender: &str) -> TestDeps {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            InstantiateMsg {
+                hrp: "osmo".to_string(),
+                owner: OWNER.to_string(),
+                domain: LOCAL_DOMAIN,
+                default_ism: None,
+                default_hook: None,
+            },
+        )
+        .unwrap();
+
+        deps
+    }
+
+    #[rstest]
+    fn test_init(deps: TestDeps) {
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+
+        assert_eq!(config.hrp, "osmo");
+        assert_eq!(config.local_domain, LOCAL_DOMAIN);
+        assert_eq!(config.default_ism, None);
+        assert_eq!(config.default_hook, None);
+        assert_eq!(config.required_hook, None);
+        assert_eq!(config.max_body_size, Some(crate::DEFAULT_MAX_BODY_SIZE));
+
+        let nonce = NONCE.load(deps.as_ref().storage).unwrap();
+        assert_eq!(nonce, 0u32);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), addr("default_ism"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), addr("default_ism"))]
+    fn test_set_default_ism(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] new_default_ism: Addr,
+    ) {
         let res = set_default_ism(
             deps.as_mut(),
-            mock_info(sender.as_str(), &[]),
-            new_default_ism.to_string(),
+            mock_info(sender.as_str(), &[]),
+            new_default_ism.to_string(),
+            true,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_default_ism_set(sender, new_default_ism))
+        );
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), addr("default_hook"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), addr("default_hook"))]
+    fn test_set_default_hook(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] new_default_hook: Addr,
+    ) {
+        let res = set_default_hook(
+            deps.as_mut(),
+            mock_info(sender.as_str(), &[]),
+            new_default_hook.to_string(),
+            true,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_default_hook_set(sender, new_default_hook))
+        )
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), addr("required_hook"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), addr("required_hook"))]
+    fn test_set_required_hook(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] new_required_hook: Addr,
+    ) {
+        let res = set_required_hook(
+            deps.as_mut(),
+            mock_info(sender.as_str(), &[]),
+            new_required_hook.to_string(),
+            true,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_required_hook_set(sender, new_required_hook))
+        )
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), Some(1024))]
+    #[case(addr(OWNER), None)]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), Some(1024))]
+    fn test_set_max_body_size(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] max_body_size: Option<u64>,
+    ) {
+        let res = set_max_body_size(
+            deps.as_mut(),
+            mock_info(sender.as_str(), &[]),
+            max_body_size,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_max_body_size_set(sender, max_body_size))
+        );
+        assert_eq!(
+            CONFIG.load(deps.as_ref().storage).unwrap().max_body_size,
+            max_body_size
+        );
+    }
+
+    #[rstest]
+    #[case(Some(256), 256)]
+    #[should_panic(expected = "message body too large: 257 > 256")]
+    #[case(Some(256), 257)]
+    #[case(None, crate::DEFAULT_MAX_BODY_SIZE as usize + 1)]
+    fn test_dispatch_max_body_size(
+        mut deps: TestDeps,
+        #[case] max_body_size: Option<u64>,
+        #[case] body_size: usize,
+    ) {
+        let sender = bech32_encode("osmo", gen_bz(20).as_slice()).unwrap();
+
+        deps.querier.update_wasm(|q| mock_query_handler(q, &None));
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+            true,
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+            true,
+        )
+        .unwrap();
+        set_max_body_size(deps.as_mut(), mock_info(OWNER, &[]), max_body_size).unwrap();
+
+        let dispatch_msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(body_size));
+
+        dispatch(deps.as_mut(), mock_info(sender.as_str(), &[]), dispatch_msg)
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        assert_eq!(NONCE.load(deps.as_ref().storage).unwrap(), 1u32);
+    }
+
+    #[rstest]
+    #[case(false, true, "[E118] default_hook not set")]
+    #[case(true, false, "[E118] required_hook not set")]
+    fn test_dispatch_hook_not_set(
+        mut deps: TestDeps,
+        #[case] default_hook: bool,
+        #[case] required_hook: bool,
+        #[case] expected: &str,
+    ) {
+        let sender = bech32_encode("osmo", gen_bz(20).as_slice()).unwrap();
+
+        deps.querier.update_wasm(|q| mock_query_handler(q, &None));
+
+        if default_hook {
+            set_default_hook(
+                deps.as_mut(),
+                mock_info(OWNER, &[]),
+                "default_hook".into(),
+                true,
+            )
+            .unwrap();
+        }
+        if required_hook {
+            set_required_hook(
+                deps.as_mut(),
+                mock_info(OWNER, &[]),
+                "required_hook".into(),
+                true,
+            )
+            .unwrap();
+        }
+
+        let dispatch_msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(12));
+
+        let err =
+            dispatch(deps.as_mut(), mock_info(sender.as_str(), &[]), dispatch_msg).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), EventBodyMode::HashOnly)]
+    #[case(addr(OWNER), EventBodyMode::Full)]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), EventBodyMode::HashOnly)]
+    fn test_set_event_body_mode(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] mode: EventBodyMode,
+    ) {
+        let res = set_event_body_mode(deps.as_mut(), mock_info(sender.as_str(), &[]), mode.clone())
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_event_body_mode_set(sender, &mode))
+        );
+        assert_eq!(
+            CONFIG.load(deps.as_ref().storage).unwrap().event_body_mode,
+            mode
+        );
+    }
+
+    #[rstest]
+    fn test_dispatch_event_body_mode(
+        mut deps: TestDeps,
+        #[values(EventBodyMode::Full, EventBodyMode::HashOnly)] mode: EventBodyMode,
+    ) {
+        let sender = bech32_encode("osmo", gen_bz(20).as_slice()).unwrap();
+
+        deps.querier.update_wasm(|q| mock_query_handler(q, &None));
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+            true,
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+            true,
+        )
+        .unwrap();
+        set_event_body_mode(deps.as_mut(), mock_info(OWNER, &[]), mode.clone()).unwrap();
+
+        let dispatch_msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123));
+        let msg = dispatch_msg
+            .clone()
+            .to_msg(MAILBOX_VERSION, 0, LOCAL_DOMAIN, &sender)
+            .unwrap();
+
+        let res = dispatch(deps.as_mut(), mock_info(sender.as_str(), &[]), dispatch_msg).unwrap();
+
+        // the message id is reported the same way in both modes
+        let data: DispatchResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(data.message_id, msg.id());
+        assert_eq!(res.events[0], hpl_events::dispatch_id(&msg.id()));
+
+        let event = &res.events[1];
+        assert_eq!(event.ty, hpl_events::DISPATCH);
+
+        let attr = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|v| v.key == key)
+                .map(|v| v.value.clone())
+        };
+        match mode {
+            EventBodyMode::Full => {
+                assert_eq!(*event, hpl_events::dispatch(msg.clone()));
+                assert_eq!(attr("message"), Some(HexBinary::from(msg).to_hex()));
+                assert_eq!(attr("body_hash"), None);
+            }
+            EventBodyMode::HashOnly => {
+                assert_eq!(*event, hpl_events::dispatch_body_hash(msg.clone()));
+                assert_eq!(attr("message"), None);
+                assert_eq!(attr("nonce"), Some("0".to_string()));
+                assert_eq!(attr("body_hash"), Some(keccak256_hash(&msg.body).to_hex()));
+                // no attribute carries the body bytes
+                assert!(event
+                    .attributes
+                    .iter()
+                    .all(|v| !v.value.contains(&msg.body.to_hex())));
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(DEST_DOMAIN, gen_bz(20), gen_bz(32))]
+    #[case(DEST_DOMAIN, gen_bz(20), pad32(&gen_bz(20)).unwrap().to_vec().into())]
+    #[case(DEST_DOMAIN, gen_bz(32), pad32(&gen_bz(31)).unwrap().to_vec().into())]
+    #[should_panic(expected = "invalid address length: 20")]
+    #[case(DEST_DOMAIN, gen_bz(20), gen_bz(20))]
+    #[should_panic(expected = "invalid address length: 33")]
+    #[case(DEST_DOMAIN, gen_bz(20), gen_bz(33))]
+    fn test_dispatch(
+        #[values("osmo", "neutron")] hrp: &str,
+        #[case] dest_domain: u32,
+        #[case] sender: HexBinary,
+        #[case] recipient_addr: HexBinary,
+    ) {
+        let sender = bech32_encode(hrp, sender.as_slice()).unwrap();
+        let msg_body = gen_bz(123);
+
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(|q| mock_query_handler(q, &None));
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            InstantiateMsg {
+                hrp: hrp.to_string(),
+                owner: OWNER.to_string(),
+                domain: LOCAL_DOMAIN,
+                default_ism: None,
+                default_hook: None,
+            },
+        )
+        .unwrap();
+
+        set_default_ism(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "default_ism".into(),
+            true,
+        )
+        .unwrap();
+        set_default_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+            true,
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+            true,
+        )
+        .unwrap();
+
+        let dispatch_msg = DispatchMsg::new(dest_domain, recipient_addr, msg_body)
+            .with_metadata(1500u32.to_be_bytes().to_vec());
+
+        let msg = dispatch_msg
+            .clone()
+            .to_msg(
+                MAILBOX_VERSION,
+                NONCE.load(deps.as_ref().storage).unwrap(),
+                LOCAL_DOMAIN,
+                &sender,
+            )
+            .unwrap();
+
+        let res = dispatch(
+            deps.as_mut(),
+            // both hooks quote the fee in metadata
+            mock_info(sender.as_str(), &[coin(3000, "utest")]),
+            dispatch_msg,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(NONCE.load(deps.as_ref().storage).unwrap(), 1u32);
+        assert_eq!(
+            LATEST_DISPATCHED_ID.load(deps.as_ref().storage).unwrap(),
+            msg.id()
+        );
+
+        // routing context is readable without decoding the message
+        let event = &res.events[1];
+        assert_eq!(event.ty, hpl_events::DISPATCH);
+
+        let attr = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|v| v.key == key)
+                .map(|v| v.value.clone())
+        };
+        assert_eq!(attr("sender"), Some(msg.sender.to_hex()));
+        assert_eq!(attr("destination"), Some(dest_domain.to_string()));
+        assert_eq!(attr("recipient"), Some(msg.recipient.to_hex()));
+        assert_eq!(attr("origin"), Some(LOCAL_DOMAIN.to_string()));
+        assert_eq!(attr("message"), Some(HexBinary::from(msg).to_hex()));
+    }
+
+    #[rstest]
+    #[case(true)]
+    #[should_panic(expected = "unauthorized")]
+    #[case(false)]
+    fn test_dispatch_sender_override(mut deps: TestDeps, #[case] approved: bool) {
+        let dispatcher = bech32_encode("osmo", gen_bz(20).as_slice()).unwrap();
+        let router = bech32_encode("osmo", gen_bz(20).as_slice()).unwrap();
+
+        deps.querier.update_wasm(|q| mock_query_handler(q, &None));
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+            true,
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+            true,
+        )
+        .unwrap();
+
+        if approved {
+            set_approved_dispatcher(
+                deps.as_mut(),
+                mock_info(OWNER, &[]),
+                dispatcher.to_string(),
+                true,
+            )
+            .unwrap();
+        }
+
+        let dispatch_msg =
+            DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123)).with_sender_override(&router);
+
+        let msg = dispatch_msg
+            .clone()
+            .to_msg(MAILBOX_VERSION, 0, LOCAL_DOMAIN, &router)
+            .unwrap();
+
+        let res = dispatch(
+            deps.as_mut(),
+            mock_info(dispatcher.as_str(), &[]),
+            dispatch_msg,
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(res.events[1], hpl_events::dispatch(msg.clone()));
+        assert_eq!(
+            LATEST_DISPATCHED_ID.load(deps.as_ref().storage).unwrap(),
+            msg.id()
+        );
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER))]
+    fn test_set_approved_dispatcher(mut deps: TestDeps, #[case] sender: Addr) {
+        let dispatcher = addr("dispatcher");
+
+        for approved in [true, false] {
+            let res = set_approved_dispatcher(
+                deps.as_mut(),
+                mock_info(sender.as_str(), &[]),
+                dispatcher.to_string(),
+                approved,
+            )
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+            assert_eq!(
+                res,
+                Response::new().add_event(emit_approved_dispatcher_set(
+                    sender.clone(),
+                    dispatcher.clone(),
+                    approved
+                ))
+            );
+            assert_eq!(
+                APPROVED_DISPATCHERS.has(deps.as_ref().storage, dispatcher.clone()),
+                approved
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER))]
+    fn test_set_domain_name(mut deps: TestDeps, #[case] sender: Addr) {
+        for name in [Some("osmosis".to_string()), None] {
+            let res = set_domain_name(
+                deps.as_mut(),
+                mock_info(sender.as_str(), &[]),
+                1,
+                name.clone(),
+            )
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+            assert_eq!(
+                res,
+                Response::new().add_event(emit_domain_name_set(sender.clone(), 1, name.as_deref()))
+            );
+            assert_eq!(
+                DOMAIN_NAMES.may_load(deps.as_ref().storage, 1).unwrap(),
+                name
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER))]
+    fn test_set_code_id_ism(mut deps: TestDeps, #[case] sender: Addr) {
+        for ism in [Some(addr("code_id_ism")), None] {
+            let res = set_code_id_ism(
+                deps.as_mut(),
+                mock_info(sender.as_str(), &[]),
+                1,
+                ism.as_ref().map(Addr::to_string),
+            )
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+            assert_eq!(
+                res,
+                Response::new().add_event(emit_code_id_ism_set(sender.clone(), 1, ism.as_ref()))
+            );
+            assert_eq!(CODE_ID_ISM.may_load(deps.as_ref().storage, 1).unwrap(), ism);
+        }
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER))]
+    fn test_snapshot_config(mut deps: TestDeps, #[case] sender: Addr) {
+        let mut env = mock_env();
+        let snapshot = |deps: &mut TestDeps, env: &Env| {
+            snapshot_config(deps.as_mut(), env.clone(), mock_info(sender.as_str(), &[]))
+                .map_err(|e| e.to_string())
+                .unwrap()
+        };
+
+        let res = snapshot(&mut deps, &env);
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_config_snapshot(sender.clone(), 1, env.block.height))
+        );
+
+        set_default_ism(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "default_ism".into(),
+            true,
+        )
+        .unwrap();
+
+        env.block.height += 1;
+        snapshot(&mut deps, &env);
+
+        let res = crate::query::get_snapshots(deps.as_ref(), None, None).unwrap();
+        let snapshots = res
+            .snapshots
+            .into_iter()
+            .map(|v| (v.id, from_json::<ConfigSnapshot>(&v.snapshot).unwrap()))
+            .collect::<Vec<_>>();
+
+        let before = ConfigSnapshot {
+            height: mock_env().block.height,
+            time: mock_env().block.time,
+            owner: addr(OWNER),
+            hrp: "osmo".to_string(),
+            alt_hrps: vec![],
+            local_domain: LOCAL_DOMAIN,
+            default_ism: None,
+            default_hook: None,
+            required_hook: None,
+            max_body_size: Some(crate::DEFAULT_MAX_BODY_SIZE),
+            process_restricted: false,
+            recipients_restricted: false,
+            process_rewards: false,
+            event_body_mode: EventBodyMode::Full,
+            nonce: 0,
+        };
+        let after = ConfigSnapshot {
+            height: env.block.height,
+            default_ism: Some(addr("default_ism")),
+            ..before.clone()
+        };
+        assert_eq!(snapshots, vec![(1, before), (2, after)]);
+    }
+
+    #[rstest]
+    fn test_snapshot_config_pruning(mut deps: TestDeps) {
+        for _ in 0..MAX_SNAPSHOTS + 2 {
+            snapshot_config(deps.as_mut(), mock_env(), mock_info(OWNER, &[])).unwrap();
+        }
+
+        // only the latest are kept
+        let ids = SNAPSHOTS
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(ids, (3..=MAX_SNAPSHOTS + 2).collect::<Vec<_>>());
+
+        assert!(crate::query::get_snapshot(deps.as_ref(), 2).is_err());
+        let res = crate::query::get_snapshot(deps.as_ref(), 3).unwrap();
+        assert_eq!(res.id, 3);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER))]
+    fn test_set_process_authorization(mut deps: TestDeps, #[case] sender: Addr) {
+        let relayers = vec![addr("relayer_a"), addr("relayer_b")];
+
+        let res = set_process_authorization(
+            deps.as_mut(),
+            mock_info(sender.as_str(), &[]),
+            ProcessAuthorizationMode::Restricted {
+                relayers: relayers.iter().map(|v| v.to_string()).collect(),
+            },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_process_authorization_set(
+                sender.clone(),
+                Some(&relayers)
+            ))
+        );
+        assert!(
+            CONFIG
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .process_restricted
+        );
+        assert!(PROCESS_RELAYERS.has(deps.as_ref().storage, addr("relayer_a")));
+
+        // switching back to open clears the list
+        set_process_authorization(
+            deps.as_mut(),
+            mock_info(sender.as_str(), &[]),
+            ProcessAuthorizationMode::Open,
+        )
+        .unwrap();
+
+        assert!(
+            !CONFIG
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .process_restricted
+        );
+        assert!(PROCESS_RELAYERS.is_empty(deps.as_ref().storage));
+    }
+
+    #[rstest]
+    fn test_set_process_authorization_empty(mut deps: TestDeps) {
+        let err = set_process_authorization(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            ProcessAuthorizationMode::Restricted { relayers: vec![] },
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            HplError::invalid_args("restricted mode needs at least one relayer").into()
+        );
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER))]
+    fn test_set_recipient_allowlist(mut deps: TestDeps, #[case] sender: Addr) {
+        let recipients = vec![addr("recipient_a"), addr("recipient_b")];
+
+        let res = set_recipient_allowlist(
+            deps.as_mut(),
+            mock_info(sender.as_str(), &[]),
+            Some(recipients.iter().map(|v| v.to_string()).collect()),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_recipient_allowlist_set(
+                sender.clone(),
+                Some(&recipients)
+            ))
+        );
+        assert!(
+            CONFIG
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .recipients_restricted
+        );
+        assert!(ALLOWED_RECIPIENTS.has(deps.as_ref().storage, addr("recipient_a")));
+
+        // lifting the restriction clears the list
+        set_recipient_allowlist(deps.as_mut(), mock_info(sender.as_str(), &[]), None).unwrap();
+
+        assert!(
+            !CONFIG
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .recipients_restricted
+        );
+        assert!(ALLOWED_RECIPIENTS.is_empty(deps.as_ref().storage));
+    }
+
+    #[rstest]
+    fn test_set_recipient_allowlist_empty(mut deps: TestDeps) {
+        let err = set_recipient_allowlist(deps.as_mut(), mock_info(OWNER, &[]), Some(vec![]))
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            HplError::invalid_args("allowlist needs at least one recipient").into()
+        );
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER))]
+    fn test_set_process_rewards_enabled(mut deps: TestDeps, #[case] sender: Addr) {
+        let res = set_process_rewards_enabled(deps.as_mut(), mock_info(sender.as_str(), &[]), true)
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_process_rewards_enabled_set(sender, true))
+        );
+        assert!(CONFIG.load(deps.as_ref().storage).unwrap().process_rewards);
+    }
+
+    #[rstest]
+    fn test_set_process_reward(mut deps: TestDeps) {
+        let recipient = addr("recipient");
+
+        let mut set_reward = |reward: Option<Coin>, funds: &[Coin]| {
+            let res = set_process_reward(
+                deps.as_mut(),
+                mock_info(recipient.as_str(), funds),
+                reward.clone(),
+            )
+            .unwrap();
+
+            let process_reward = PROCESS_REWARDS
+                .may_load(deps.as_ref().storage, recipient.clone())
+                .unwrap();
+            assert_eq!(process_reward.as_ref().map(|v| &v.reward), reward.as_ref());
+            assert_eq!(
+                res.events,
+                vec![emit_process_reward_set(
+                    recipient.clone(),
+                    reward.as_ref(),
+                    process_reward
+                        .as_ref()
+                        .map(|v| v.balance)
+                        .unwrap_or_default()
+                )]
+            );
+
+            (
+                process_reward.map(|v| v.balance.u128()),
+                res.messages
+                    .into_iter()
+                    .map(|v| v.msg)
+                    .collect::<Vec<CosmosMsg>>(),
+            )
+        };
+        let refund = |amount: Coin| {
+            vec![CosmosMsg::Bank(BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: vec![amount],
+            })]
+        };
+
+        // funds top up the balance while the denom stays the same
+        assert_eq!(
+            set_reward(Some(coin(10, "uosmo")), &[coin(30, "uosmo")]),
+            (Some(30), vec![])
+        );
+        assert_eq!(
+            set_reward(Some(coin(5, "uosmo")), &[coin(20, "uosmo")]),
+            (Some(50), vec![])
+        );
+
+        // changing the denom refunds the previous balance
+        assert_eq!(
+            set_reward(Some(coin(1, "uatom")), &[]),
+            (Some(0), refund(coin(50, "uosmo")))
+        );
+        assert_eq!(
+            set_reward(Some(coin(1, "uatom")), &[coin(7, "uatom")]),
+            (Some(7), vec![])
+        );
+
+        // removing the reward refunds the balance
+        assert_eq!(set_reward(None, &[]), (None, refund(coin(7, "uatom"))));
+        assert_eq!(set_reward(None, &[]), (None, vec![]));
+    }
+
+    #[rstest]
+    #[case(
+        Some(coin(0, "uosmo")),
+        vec![],
+        HplError::invalid_args("reward should be positive").into()
+    )]
+    #[case(
+        Some(coin(10, "uosmo")),
+        vec![coin(10, "uatom")],
+        cw_utils::PaymentError::MissingDenom("uosmo".to_string()).into()
+    )]
+    #[case(None, vec![coin(10, "uosmo")], cw_utils::PaymentError::NonPayable {}.into())]
+    fn test_set_process_reward_invalid(
+        mut deps: TestDeps,
+        #[case] reward: Option<Coin>,
+        #[case] funds: Vec<Coin>,
+        #[case] expected: ContractError,
+    ) {
+        let err =
+            set_process_reward(deps.as_mut(), mock_info("recipient", &funds), reward).unwrap_err();
+
+        assert_eq!(err, expected);
+    }
+
+    #[rstest]
+    fn test_set_domain_name_empty(mut deps: TestDeps) {
+        let err = set_domain_name(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            1,
+            Some(" ".to_string()),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            HplError::invalid_args("domain name must not be empty").into()
+        );
+    }
+
+    #[rstest]
+    fn test_dispatch_storage_ops() {
+        let mut deps = counting(deps_with_hook_fees(vec![], vec![]));
+
+        let sender = bech32_encode("osmo", &[1; 32]).unwrap();
+        dispatch(
+            deps.as_mut(),
+            mock_info(sender.as_str(), &[]),
+            DispatchMsg::new(DEST_DOMAIN, vec![2; 32], vec![3; 32]),
+        )
+        .unwrap();
+
+        // reads CONFIG and NONCE.
+        // writes NONCE, LATEST_DISPATCHED_ID, LATEST_DISPATCHED_NONCE and DISPATCHED_IDS.
+        // 236 bytes while LATEST_DISPATCHED_ID was stored as a byte array
+        assert_eq!(deps.storage.counts(), (2, 4, 185));
+    }
+
+    #[rstest]
+    fn test_dispatch_latest_tracking() {
+        let hrp = "osmo";
+        let sender = bech32_encode(hrp, gen_bz(20).as_slice()).unwrap();
+
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(|q| mock_query_handler(q, &None));
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            InstantiateMsg {
+                hrp: hrp.to_string(),
+                owner: OWNER.to_string(),
+                domain: LOCAL_DOMAIN,
+                default_ism: None,
+                default_hook: None,
+            },
+        )
+        .unwrap();
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+            true,
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+            true,
+        )
+        .unwrap();
+
+        for expected_nonce in 0..2u32 {
+            let dispatch_msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123));
+
+            let msg = dispatch_msg
+                .clone()
+                .to_msg(MAILBOX_VERSION, expected_nonce, LOCAL_DOMAIN, &sender)
+                .unwrap();
+
+            dispatch(deps.as_mut(), mock_info(sender.as_str(), &[]), dispatch_msg).unwrap();
+
+            let res = crate::query::get_latest_dispatch_id(deps.as_ref()).unwrap();
+            assert_eq!(res.message_id, msg.id());
+            assert_eq!(res.nonce, expected_nonce);
+        }
+
+        assert_eq!(NONCE.load(deps.as_ref().storage).unwrap(), 2u32);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER))]
+    fn test_dispatched_ids(#[case] pruner: Addr) {
+        let hrp = "osmo";
+        let sender = bech32_encode(hrp, gen_bz(20).as_slice()).unwrap();
+
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(|q| mock_query_handler(q, &None));
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            InstantiateMsg {
+                hrp: hrp.to_string(),
+                owner: OWNER.to_string(),
+                domain: LOCAL_DOMAIN,
+                default_ism: None,
+                default_hook: None,
+            },
+        )
+        .unwrap();
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+            true,
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+            true,
+        )
+        .unwrap();
+
+        let mut ids = vec![];
+        for nonce in 0..3u32 {
+            let dispatch_msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123));
+
+            let msg = dispatch_msg
+                .clone()
+                .to_msg(MAILBOX_VERSION, nonce, LOCAL_DOMAIN, &sender)
+                .unwrap();
+
+            dispatch(deps.as_mut(), mock_info(sender.as_str(), &[]), dispatch_msg).unwrap();
+
+            ids.push(msg.id());
+        }
+
+        for (nonce, id) in ids.iter().enumerate() {
+            let res =
+                crate::query::get_dispatched_id_at_nonce(deps.as_ref(), nonce as u32).unwrap();
+            assert_eq!(res.message_id, Some(id.clone()));
+        }
+
+        let res = crate::query::get_dispatched_ids(deps.as_ref(), Some(0), Some(1)).unwrap();
+        assert_eq!(
+            res.ids,
+            vec![DispatchedId {
+                nonce: 1,
+                message_id: ids[1].clone()
+            }]
+        );
+
+        prune_dispatched_ids(deps.as_mut(), mock_info(pruner.as_str(), &[]), 2, 10)
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        for nonce in 0..2u32 {
+            let res = crate::query::get_dispatched_id_at_nonce(deps.as_ref(), nonce).unwrap();
+            assert_eq!(res.message_id, None);
+        }
+
+        let res = crate::query::get_dispatched_ids(deps.as_ref(), None, None).unwrap();
+        assert_eq!(
+            res.ids,
+            vec![DispatchedId {
+                nonce: 2,
+                message_id: ids[2].clone()
+            }]
+        );
+
+        // latest dispatched id is kept
+        let res = crate::query::get_latest_dispatch_id(deps.as_ref()).unwrap();
+        assert_eq!(res.message_id, ids[2]);
+    }
+
+    fn dispatched_nonces(deps: &TestDeps) -> Vec<u32> {
+        DISPATCHED_IDS
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap()
+    }
+
+    fn dispatch_n(deps: &mut TestDeps, n: usize) {
+        let sender = bech32_encode("osmo", &[1; 32]).unwrap();
+        for _ in 0..n {
+            dispatch(
+                deps.as_mut(),
+                mock_info(sender.as_str(), &[]),
+                DispatchMsg::new(DEST_DOMAIN, vec![2; 32], vec![3; 32]),
+            )
+            .unwrap();
+        }
+    }
+
+    #[rstest]
+    fn test_prune_dispatched_ids_limit() {
+        let mut deps = deps_with_hook_fees(vec![], vec![]);
+        dispatch_n(&mut deps, 5);
+
+        let res = prune_dispatched_ids(deps.as_mut(), mock_info(OWNER, &[]), 4, 3).unwrap();
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_dispatched_ids_pruned(addr(OWNER), 4, 3, false))
+        );
+        assert_eq!(dispatched_nonces(&deps), vec![3, 4]);
+        assert_eq!(
+            DISPATCHED_IDS_PRUNE_CURSOR
+                .load(deps.as_ref().storage)
+                .unwrap(),
+            3
+        );
+
+        // resumes from the cursor
+        let res = prune_dispatched_ids(deps.as_mut(), mock_info(OWNER, &[]), 4, 3).unwrap();
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_dispatched_ids_pruned(addr(OWNER), 4, 1, true))
+        );
+        assert_eq!(dispatched_nonces(&deps), vec![4]);
+        assert_eq!(
+            DISPATCHED_IDS_PRUNE_CURSOR
+                .load(deps.as_ref().storage)
+                .unwrap(),
+            4
+        );
+
+        // a lower bound doesn't move the cursor back
+        prune_dispatched_ids(deps.as_mut(), mock_info(OWNER, &[]), 1, 3).unwrap();
+        assert_eq!(
+            DISPATCHED_IDS_PRUNE_CURSOR
+                .load(deps.as_ref().storage)
+                .unwrap(),
+            4
+        );
+    }
+
+    #[rstest]
+    #[should_panic(expected = "limit must be positive")]
+    fn test_prune_dispatched_ids_zero_limit() {
+        let mut deps = deps_with_hook_fees(vec![], vec![]);
+
+        prune_dispatched_ids(deps.as_mut(), mock_info(OWNER, &[]), 1, 0)
+            .map_err(|e| e.to_string())
+            .unwrap();
+    }
+
+    #[rstest]
+    #[case(addr(OWNER), Some(2))]
+    #[case(addr(OWNER), None)]
+    #[should_panic(expected = "retention must be positive")]
+    #[case(addr(OWNER), Some(0))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER), Some(2))]
+    fn test_set_dispatched_ids_retention(#[case] sender: Addr, #[case] retention: Option<u32>) {
+        let mut deps = deps_with_hook_fees(vec![], vec![]);
+
+        let res =
+            set_dispatched_ids_retention(deps.as_mut(), mock_info(sender.as_str(), &[]), retention)
+                .map_err(|e| e.to_string())
+                .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_dispatched_ids_retention_set(sender, retention))
+        );
+        assert_eq!(
+            crate::query::get_dispatched_ids_retention(deps.as_ref())
+                .unwrap()
+                .retention,
+            retention
+        );
+
+        dispatch_n(&mut deps, 4);
+        let kept = match retention {
+            Some(_) => vec![2, 3],
+            None => vec![0, 1, 2, 3],
+        };
+        assert_eq!(dispatched_nonces(&deps), kept);
+    }
+
+    /// mailbox with a required and a default hook quoting the given fees
+    fn deps_with_hook_fees(
+        required_hook_fees: Vec<Coin>,
+        default_hook_fees: Vec<Coin>,
+    ) -> TestDeps {
+        let mut deps = mock_dependencies();
+
+        let mut hook_fees = HashMap::new();
+        hook_fees.insert("required_hook".into(), required_hook_fees);
+        hook_fees.insert("default_hook".into(), default_hook_fees);
+
+        let opt = Some(hook_fees);
+
+        deps.querier
+            .update_wasm(move |q| mock_query_handler(q, &opt));
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            InstantiateMsg {
+                hrp: "osmo".to_string(),
+                owner: OWNER.to_string(),
+                domain: LOCAL_DOMAIN,
+                default_ism: None,
+                default_hook: None,
+            },
+        )
+        .unwrap();
+
+        set_default_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "default_hook".into(),
+            true,
+        )
+        .unwrap();
+        set_required_hook(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            "required_hook".into(),
+            true,
+        )
+        .unwrap();
+
+        deps
+    }
+
+    #[rstest]
+    #[case(vec![coin(100, "usd")], vec![], vec![coin(100, "usd")], vec![])]
+    #[should_panic(expected = "insufficient dispatch funds")]
+    #[case(vec![coin(100, "usd")], vec![], vec![coin(50, "usd")], vec![])]
+    #[should_panic(expected = "insufficient dispatch funds")]
+    #[case(vec![coin(100, "usdt")], vec![], vec![coin(100, "usd")], vec![])]
+    #[case(vec![coin(50, "usd")], vec![], vec![coin(100, "usd")], vec![coin(50, "usd")])]
+    #[case(
+        vec![coin(50, "usd")],
+        vec![coin(30, "usd"), coin(10, "uatom")],
+        vec![coin(100, "usd"), coin(20, "uatom")],
+        vec![coin(10, "uatom"), coin(20, "usd")]
+    )]
+    #[should_panic(expected = "insufficient dispatch funds")]
+    #[case(vec![coin(50, "usd")], vec![coin(60, "usd")], vec![coin(100, "usd")], vec![])]
+    fn test_post_dispatch(
+        #[case] required_hook_fees: Vec<Coin>,
+        #[case] default_hook_fees: Vec<Coin>,
+        #[case] funds: Vec<Coin>,
+        #[case] refund: Vec<Coin>,
+    ) {
+        let mut deps = deps_with_hook_fees(required_hook_fees.clone(), default_hook_fees.clone());
+
+        let dispatch_msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123));
+
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+
+        let msg = dispatch_msg
+            .clone()
+            .to_msg(
+                MAILBOX_VERSION,
+                NONCE.load(deps.as_ref().storage).unwrap(),
+                LOCAL_DOMAIN,
+                &sender,
+            )
+            .unwrap();
+
+        let post_dispatch_msg = to_json_binary(
+            &PostDispatchMsg {
+                metadata: HexBinary::default(),
+                message: msg.into(),
+            }
+            .wrap(), // not sure why I need this
+        )
+        .unwrap();
+
+        let res = dispatch(
+            deps.as_mut(),
+            mock_info(sender.as_str(), &funds),
+            dispatch_msg.clone(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        // hooks report back only on failure
+        for sub in &res.messages[..2] {
+            assert_eq!(sub.id, REPLY_ID_POST_DISPATCH);
+            assert_eq!(sub.reply_on, ReplyOn::Error);
+        }
+
+        let msgs: Vec<_> = res.messages.into_iter().map(|v| v.msg).collect();
+
+        assert_eq!(
+            msgs[0],
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "required_hook".to_string(),
+                msg: post_dispatch_msg.clone(),
+                funds: required_hook_fees.clone()
+            },)
+        );
+
+        assert_eq!(
+            msgs[1],
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "default_hook".to_string(),
+                msg: post_dispatch_msg,
+                funds: default_hook_fees
+            })
+        );
+
+        // unquoted funds go back to the sender
+        if refund.is_empty() {
+            assert_eq!(msgs.len(), 2);
+        } else {
+            assert_eq!(
+                msgs[2],
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: sender.to_string(),
+                    amount: refund
+                })
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::none(vec![], vec![coin(10, "uatom"), coin(120, "usd")])]
+    #[case::short(
+        vec![coin(110, "usd"), coin(5, "uatom")],
+        vec![coin(10, "uatom"), coin(120, "usd")]
+    )]
+    #[case::wrong_denom(vec![coin(200, "usdt")], vec![coin(10, "uatom"), coin(120, "usd")])]
+    fn test_dispatch_insufficient_funds(#[case] funds: Vec<Coin>, #[case] required: Vec<Coin>) {
+        let mut deps = deps_with_hook_fees(
+            vec![coin(50, "usd")],
+            vec![coin(70, "usd"), coin(10, "uatom")],
+        );
+
+        let sender = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+
+        let err = dispatch(
+            deps.as_mut(),
+            mock_info(sender.as_str(), &funds),
+            DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123)),
+        )
+        .unwrap_err();
+
+        // quotes of both hooks are summed up per denom
+        assert_eq!(
+            err,
+            ContractError::InsufficientDispatchFunds {
+                required,
+                provided: funds,
+            }
+        );
+        assert_eq!(NONCE.load(deps.as_ref().storage).unwrap(), 0);
+    }
+
+    fn test_process_query_handler(query: &WasmQuery) -> QuerierResult {
+        match query {
+            WasmQuery::Smart { contract_addr, msg } => {
+                if let Ok(req) = cosmwasm_std::from_json::<ism::ExpectedIsmSpecifierQueryMsg>(msg) {
+                    match req {
+                        hpl_interface::ism::ExpectedIsmSpecifierQueryMsg::IsmSpecifier(
+                            ism::IsmSpecifierQueryMsg::InterchainSecurityModule(),
+                        ) => {
+                            return SystemResult::Ok(
+                                to_json_binary(&ism::InterchainSecurityModuleResponse {
+                                    ism: Some(addr("default_ism")),
+                                })
+                                .into(),
+                            );
+                        }
+                    }
+                }
+
+                if let Ok(req) = cosmwasm_std::from_json::<ism::ExpectedIsmQueryMsg>(msg) {
+                    assert_eq!(contract_addr, &addr("default_ism"));
+
+                    match req {
+                        ism::ExpectedIsmQueryMsg::Ism(IsmQueryMsg::Verify { metadata, .. }) => {
+                            return SystemResult::Ok(
+                                to_json_binary(&ism::VerifyResponse {
+                                    verified: metadata[0] == 1,
+                                })
+                                .into(),
+                            );
+                        }
+                        _ => unreachable!("not in test coverage"),
+                    }
+                }
+
+                if let Ok(req) = cosmwasm_std::from_json::<ism::ExpectedIsmContextQueryMsg>(msg) {
+                    assert_eq!(contract_addr, &addr("default_ism"));
+
+                    let ism::ExpectedIsmContextQueryMsg::IsmContext(
+                        ism::IsmContextQueryMsg::VerifyWithContext { relayer, .. },
+                    ) = req;
+
+                    // only the trusted relayer passes, regardless of metadata
+                    return SystemResult::Ok(
+                        to_json_binary(&ism::VerifyResponse {
+                            verified: relayer == addr("trusted_relayer").as_str(),
+                        })
+                        .into(),
+                    );
+                }
+
+                unreachable!("not in test coverage")
+            }
+            _ => unimplemented!("only for smart query"),
+        }
+    }
+
+    #[rstest]
+    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), false, true)]
+    #[should_panic(expected = "invalid message version: 99")]
+    #[case(99, LOCAL_DOMAIN, gen_bz(32), false, true)]
+    #[should_panic(expected = "message already delivered")]
+    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), true, true)]
+    #[should_panic(expected = "invalid destination domain: 11155111")]
+    #[case(MAILBOX_VERSION, DEST_DOMAIN, gen_bz(32), false, true)]
+    #[should_panic(expected = "[E016] verify failed")]
+    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), false, false)]
+    fn test_process(
+        #[values("osmo", "neutron")] hrp: &str,
+        #[case] version: u8,
+        #[case] dest_domain: u32,
+        #[case] recipient_addr: HexBinary,
+        #[case] duplicate: bool,
+        #[case] verified: bool,
+    ) {
+        let sender = gen_bz(32);
+        let sender_addr = bech32_encode(hrp, &sender).unwrap();
+        let msg_body = gen_bz(123);
+
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(test_process_query_handler);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new(hrp, LOCAL_DOMAIN)
+                    .with_hook(addr("default_hook"), addr("required_hook"))
+                    .with_ism(addr("default_ism")),
+            )
+            .unwrap();
+
+        let msg = Message {
+            version,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender,
+            dest_domain,
+            recipient: recipient_addr,
+            body: msg_body,
+        };
+        let msg_id = msg.id();
+
+        if duplicate {
+            DELIVERIES
+                .save(deps.as_mut().storage, msg.id().to_vec(), &())
+                .unwrap();
+        }
+
+        let res = process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender_addr.as_str(), &[]),
+            vec![verified.into()].into(),
+            msg.into(),
+        )
+        .map_err(|v| v.to_string())
+        .unwrap();
+
+        assert!(DELIVERIES.has(deps.as_ref().storage, msg_id.to_vec()));
+        assert_eq!(
+            res.events[0],
+            hpl_events::process_id(&msg_id, &Addr::unchecked(sender_addr))
+        );
+    }
+
+    #[rstest]
+    fn test_process_storage_ops() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(test_process_query_handler);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", LOCAL_DOMAIN).with_ism(addr("default_ism")),
+            )
+            .unwrap();
+
+        let mut deps = counting(deps);
+
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 0,
+            origin_domain: DEST_DOMAIN,
+            sender: vec![1; 32].into(),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: vec![2; 32].into(),
+            body: vec![3; 32].into(),
+        };
+        process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            vec![1].into(),
+            msg.into(),
+        )
+        .unwrap();
+
+        // reads PROCESS_GUARD, CONFIG, RECIPIENT_ISM_OPTOUT, RECIPIENT_ISM_CACHING,
+        // DELIVERIES, CONTEXT_ISMS and MESSAGE_VALUES.
+        // writes PROCESS_GUARD and DELIVERIES
+        assert_eq!(deps.storage.counts(), (7, 2, 8));
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(NOT_OWNER))]
+    fn test_set_context_ism(mut deps: TestDeps, #[case] sender: Addr) {
+        let ism = addr("context_ism");
+
+        for enabled in [true, false] {
+            let res = set_context_ism(
+                deps.as_mut(),
+                mock_info(sender.as_str(), &[]),
+                ism.to_string(),
+                enabled,
+            )
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+            assert_eq!(
+                res,
+                Response::new().add_event(emit_context_ism_set(
+                    sender.clone(),
+                    ism.clone(),
+                    enabled
+                ))
+            );
+            assert_eq!(
+                CONTEXT_ISMS.has(deps.as_ref().storage, ism.clone()),
+                enabled
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(addr("trusted_relayer"), true)]
+    #[should_panic(expected = "[E016] verify failed")]
+    #[case(addr("untrusted_relayer"), true)]
+    #[case(addr("untrusted_relayer"), false)]
+    fn test_process_context_ism(#[case] relayer: Addr, #[case] context: bool) {
+        let mut deps = mock_dependencies();
+
+        deps.querier.update_wasm(test_process_query_handler);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", LOCAL_DOMAIN)
+                    .with_hook(addr("default_hook"), addr("required_hook"))
+                    .with_ism(addr("default_ism")),
+            )
+            .unwrap();
+
+        if context {
+            CONTEXT_ISMS
+                .save(deps.as_mut().storage, addr("default_ism"), &())
+                .unwrap();
+        }
+
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body: gen_bz(123),
+        };
+
+        // metadata passes the plain `Verify`, so only the relayer decides for context isms
+        process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(relayer.as_str(), &[]),
+            vec![1].into(),
+            msg.into(),
         )
-        .map_err(|e| e.to_string())
+        .map_err(|v| v.to_string())
         .unwrap();
+    }
+
+    #[rstest]
+    fn test_process_ism_cache() {
+        let recipient_queries = Arc::new(AtomicU32::new(0));
+
+        let mut deps = mock_dependencies();
+
+        let counter = recipient_queries.clone();
+        deps.querier.update_wasm(move |query| {
+            if let WasmQuery::Smart { msg, .. } = query {
+                if from_json::<ism::ExpectedIsmSpecifierQueryMsg>(msg).is_ok() {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            test_process_query_handler(query)
+        });
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", LOCAL_DOMAIN)
+                    .with_hook(addr("default_hook"), addr("required_hook"))
+                    .with_ism(addr("default_ism")),
+            )
+            .unwrap();
+        hpl_ownable::initialize(deps.as_mut().storage, &addr(OWNER)).unwrap();
+
+        let recipient = gen_bz(32);
+        let recipient_addr = bech32_encode("osmo", &recipient).unwrap();
+
+        let process_new = |deps: &mut TestDeps| {
+            let msg = Message {
+                version: MAILBOX_VERSION,
+                nonce: 123,
+                origin_domain: DEST_DOMAIN,
+                sender: gen_bz(32),
+                dest_domain: LOCAL_DOMAIN,
+                recipient: recipient.clone(),
+                body: gen_bz(123),
+            };
+
+            process(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("relayer", &[]),
+                vec![1].into(),
+                msg.into(),
+            )
+            .unwrap();
+
+            // as the reply of the recipient's `Handle` would
+            PROCESS_GUARD.exit(deps.as_mut().storage);
+        };
 
+        // without caching the recipient is queried every time
+        process_new(&mut deps);
+        process_new(&mut deps);
+        assert_eq!(recipient_queries.load(Ordering::SeqCst), 2);
+        assert!(!RECIPIENT_ISM_CACHE.has(deps.as_ref().storage, recipient_addr.clone()));
+
+        set_ism_caching(deps.as_mut(), mock_info(recipient_addr.as_str(), &[]), true).unwrap();
+
+        // first resolution queries the recipient, then the cache is hit
+        process_new(&mut deps);
+        process_new(&mut deps);
+        assert_eq!(recipient_queries.load(Ordering::SeqCst), 3);
         assert_eq!(
-            res,
-            Response::new().add_event(emit_default_ism_set(sender, new_default_ism))
+            RECIPIENT_ISM_CACHE
+                .load(deps.as_ref().storage, recipient_addr.clone())
+                .unwrap(),
+            addr("default_ism")
         );
+
+        invalidate_ism_cache(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            recipient_addr.to_string(),
+        )
+        .unwrap();
+        assert!(!RECIPIENT_ISM_CACHE.has(deps.as_ref().storage, recipient_addr.clone()));
+
+        process_new(&mut deps);
+        assert_eq!(recipient_queries.load(Ordering::SeqCst), 4);
+
+        // disabling drops the cached ism
+        set_ism_caching(
+            deps.as_mut(),
+            mock_info(recipient_addr.as_str(), &[]),
+            false,
+        )
+        .unwrap();
+        assert!(!RECIPIENT_ISM_CACHE.has(deps.as_ref().storage, recipient_addr));
+
+        process_new(&mut deps);
+        assert_eq!(recipient_queries.load(Ordering::SeqCst), 5);
     }
 
     #[rstest]
-    #[case(addr(OWNER), addr("default_hook"))]
+    fn test_set_ism_caching(mut deps: TestDeps) {
+        let recipient = addr("recipient");
+
+        for enabled in [true, false] {
+            let res = set_ism_caching(deps.as_mut(), mock_info(recipient.as_str(), &[]), enabled)
+                .unwrap();
+
+            assert_eq!(
+                res,
+                Response::new().add_event(emit_ism_caching_set(recipient.clone(), enabled))
+            );
+            assert_eq!(
+                RECIPIENT_ISM_CACHING.has(deps.as_ref().storage, recipient.clone()),
+                enabled
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[case(addr("recipient"))]
     #[should_panic(expected = "unauthorized")]
-    #[case(addr(NOT_OWNER), addr("default_hook"))]
-    fn test_set_default_hook(
-        mut deps: TestDeps,
-        #[case] sender: Addr,
-        #[case] new_default_hook: Addr,
-    ) {
-        let res = set_default_hook(
+    #[case(addr(NOT_OWNER))]
+    fn test_invalidate_ism_cache(mut deps: TestDeps, #[case] sender: Addr) {
+        let recipient = addr("recipient");
+
+        RECIPIENT_ISM_CACHE
+            .save(deps.as_mut().storage, recipient.clone(), &addr("ism"))
+            .unwrap();
+
+        let res = invalidate_ism_cache(
             deps.as_mut(),
             mock_info(sender.as_str(), &[]),
-            new_default_hook.to_string(),
+            recipient.to_string(),
         )
         .map_err(|e| e.to_string())
         .unwrap();
 
         assert_eq!(
             res,
-            Response::new().add_event(emit_default_hook_set(sender, new_default_hook))
-        )
+            Response::new().add_event(emit_ism_cache_invalidated(sender, recipient.clone()))
+        );
+        assert!(!RECIPIENT_ISM_CACHE.has(deps.as_ref().storage, recipient));
     }
 
     #[rstest]
-    #[case(addr(OWNER), addr("required_hook"))]
+    #[case(addr(OWNER), vec!["osmoold", "osmosis"])]
+    #[case(addr(OWNER), vec![])]
     #[should_panic(expected = "unauthorized")]
-    #[case(addr(NOT_OWNER), addr("required_hook"))]
-    fn test_set_required_hook(
+    #[case(addr(NOT_OWNER), vec!["osmoold"])]
+    fn test_set_alt_hrps(mut deps: TestDeps, #[case] sender: Addr, #[case] hrps: Vec<&str>) {
+        let hrps = hrps.into_iter().map(String::from).collect::<Vec<_>>();
+
+        let res = set_alt_hrps(deps.as_mut(), mock_info(sender.as_str(), &[]), hrps.clone())
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_alt_hrps_set(sender, &hrps))
+        );
+        assert_eq!(CONFIG.load(deps.as_ref().storage).unwrap().alt_hrps, hrps);
+    }
+
+    #[rstest]
+    #[case(vec!["osmo"], "alternate hrps must be unique and differ from hrp")]
+    #[case(vec!["old", "old"], "alternate hrps must be unique and differ from hrp")]
+    #[case(vec!["Old"], "hrp must be lowercase bech32 characters")]
+    #[case(vec![""], "hrp length must be between 1 and 83")]
+    fn test_set_alt_hrps_invalid(
         mut deps: TestDeps,
-        #[case] sender: Addr,
-        #[case] new_required_hook: Addr,
+        #[case] hrps: Vec<&str>,
+        #[case] reason: &str,
     ) {
-        let res = set_required_hook(
+        let err = set_alt_hrps(
             deps.as_mut(),
-            mock_info(sender.as_str(), &[]),
-            new_required_hook.to_string(),
+            mock_info(OWNER, &[]),
+            hrps.into_iter().map(String::from).collect(),
         )
-        .map_err(|e| e.to_string())
-        .unwrap();
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::invalid_config(reason));
+    }
+
+    #[rstest]
+    fn test_alt_hrps(mut deps: TestDeps) {
+        let raw_addr = gen_bz(20);
+        let primary = bech32_encode("osmo", &raw_addr).unwrap();
+        let alternate = bech32_encode("osmoold", &raw_addr).unwrap();
+
+        set_alt_hrps(deps.as_mut(), mock_info(OWNER, &[]), vec!["osmoold".into()]).unwrap();
+        let config = CONFIG.load(deps.as_ref().storage).unwrap();
+
+        // recipients of messages are encoded with the primary hrp
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 0,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: pad32(&raw_addr).unwrap().to_vec().into(),
+            body: gen_bz(123),
+        };
+        assert_eq!(msg.recipient_addr(&config.hrp).unwrap(), primary);
+
+        // both prefixes validate to the primary address
+        let api = deps.as_ref().api;
+        assert_eq!(
+            config.addr_validate(api, primary.as_str()).unwrap(),
+            primary
+        );
+        assert_eq!(
+            config.addr_validate(api, alternate.as_str()).unwrap(),
+            primary
+        );
+
+        // other prefixes are left as they are
+        let other = bech32_encode("juno", &raw_addr).unwrap();
+        assert_eq!(config.addr_validate(api, other.as_str()).unwrap(), other);
+
+        RECIPIENT_ISM_CACHE
+            .save(deps.as_mut().storage, primary.clone(), &addr("ism"))
+            .unwrap();
+
+        let res =
+            invalidate_ism_cache(deps.as_mut(), mock_info(OWNER, &[]), alternate.into()).unwrap();
 
         assert_eq!(
             res,
-            Response::new().add_event(emit_required_hook_set(sender, new_required_hook))
+            Response::new().add_event(emit_ism_cache_invalidated(addr(OWNER), primary.clone()))
+        );
+        assert!(!RECIPIENT_ISM_CACHE.has(deps.as_ref().storage, primary));
+    }
+
+    #[rstest]
+    fn test_set_ism_lookup_opt_out(mut deps: TestDeps) {
+        let recipient = addr("recipient");
+
+        CONFIG
+            .update(deps.as_mut().storage, |v| {
+                StdResult::Ok(v.with_ism(addr("default_ism")))
+            })
+            .unwrap();
+        RECIPIENT_ISM_CACHING
+            .save(deps.as_mut().storage, recipient.clone(), &())
+            .unwrap();
+        RECIPIENT_ISM_CACHE
+            .save(deps.as_mut().storage, recipient.clone(), &addr("ism"))
+            .unwrap();
+
+        let set_opt_out = |deps: &mut TestDeps, opt_out: bool| {
+            let res =
+                set_ism_lookup_opt_out(deps.as_mut(), mock_info(recipient.as_str(), &[]), opt_out)
+                    .unwrap();
+
+            assert_eq!(
+                res,
+                Response::new().add_event(emit_ism_lookup_opt_out_set(recipient.clone(), opt_out))
+            );
+            assert_eq!(
+                RECIPIENT_ISM_OPTOUT.has(deps.as_ref().storage, recipient.clone()),
+                opt_out
+            );
+
+            crate::query::get_recipient_ism(deps.as_ref(), recipient.to_string())
+                .unwrap()
+                .ism
+        };
+
+        // opted out recipients follow the default ism, whatever is cached
+        assert_eq!(set_opt_out(&mut deps, true), "default_ism");
+        assert_eq!(set_opt_out(&mut deps, false), "ism");
+    }
+
+    #[rstest]
+    fn test_import_deliveries() {
+        let mut old = deps("deployer");
+        let mut new = deps("deployer");
+
+        // every message delivered by the old mailbox, over more than one export page
+        let ids: Vec<HexBinary> = (0..15).map(|_| gen_bz(32)).collect();
+        for id in ids.iter() {
+            DELIVERIES
+                .save(old.as_mut().storage, id.to_vec(), &())
+                .unwrap();
+        }
+
+        let mut exported = vec![];
+        loop {
+            let res =
+                crate::query::get_deliveries(old.as_ref(), exported.last().cloned(), None).unwrap();
+            if res.ids.is_empty() {
+                break;
+            }
+            exported.extend(res.ids);
+        }
+
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(exported, sorted);
+
+        let res = import_deliveries(new.as_mut(), mock_info(OWNER, &[]), exported.clone()).unwrap();
+        assert_eq!(
+            res,
+            Response::new().add_event(emit_deliveries_imported(addr(OWNER), 15))
+        );
+
+        for id in ids.iter() {
+            assert!(DELIVERIES.has(new.as_ref().storage, id.to_vec()));
+        }
+    }
+
+    #[rstest]
+    fn test_process_imported_delivery(mut deps: TestDeps) {
+        deps.querier.update_wasm(test_process_query_handler);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", LOCAL_DOMAIN)
+                    .with_hook(addr("default_hook"), addr("required_hook"))
+                    .with_ism(addr("default_ism")),
+            )
+            .unwrap();
+
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body: gen_bz(123),
+        };
+
+        import_deliveries(deps.as_mut(), mock_info(OWNER, &[]), vec![msg.id()]).unwrap();
+
+        let err = process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            vec![1].into(),
+            msg.into(),
         )
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::AlreadyDeliveredMessage {});
     }
 
     #[rstest]
-    #[case(DEST_DOMAIN, gen_bz(20), gen_bz(32))]
-    #[should_panic(expected = "invalid address length: 33")]
-    #[case(DEST_DOMAIN, gen_bz(20), gen_bz(33))]
-    fn test_dispatch(
-        #[values("osmo", "neutron")] hrp: &str,
-        #[case] dest_domain: u32,
-        #[case] sender: HexBinary,
-        #[case] recipient_addr: HexBinary,
-    ) {
-        let sender = bech32_encode(hrp, sender.as_slice()).unwrap();
-        let msg_body = gen_bz(123);
+    #[case(None, "stranger")]
+    #[case(Some(vec!["relayer"]), "relayer")]
+    #[should_panic(expected = "unauthorized relayer: stranger")]
+    #[case(Some(vec!["relayer"]), "stranger")]
+    fn test_process_authorization(#[case] relayers: Option<Vec<&str>>, #[case] sender: &str) {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(test_process_query_handler);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", LOCAL_DOMAIN).with_ism(addr("default_ism")),
+            )
+            .unwrap();
+        hpl_ownable::initialize(deps.as_mut().storage, &addr(OWNER)).unwrap();
+
+        if let Some(relayers) = relayers {
+            set_process_authorization(
+                deps.as_mut(),
+                mock_info(OWNER, &[]),
+                ProcessAuthorizationMode::Restricted {
+                    relayers: relayers.into_iter().map(String::from).collect(),
+                },
+            )
+            .unwrap();
+        }
+
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body: gen_bz(123),
+        };
+
+        process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            vec![1].into(),
+            msg.into(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(vec![[0xab; 32]]))]
+    #[should_panic(expected = "[E113] recipient not allowed")]
+    #[case(Some(vec![[0xcd; 32]]))]
+    fn test_process_recipient_allowlist(#[case] recipients: Option<Vec<[u8; 32]>>) {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(test_process_query_handler);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", LOCAL_DOMAIN).with_ism(addr("default_ism")),
+            )
+            .unwrap();
+        hpl_ownable::initialize(deps.as_mut().storage, &addr(OWNER)).unwrap();
+
+        if let Some(recipients) = recipients {
+            set_recipient_allowlist(
+                deps.as_mut(),
+                mock_info(OWNER, &[]),
+                Some(
+                    recipients
+                        .iter()
+                        .map(|v| bech32_encode("osmo", v).unwrap().into())
+                        .collect(),
+                ),
+            )
+            .unwrap();
+        }
+
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: vec![0xab; 32].into(),
+            body: gen_bz(123),
+        };
+
+        process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            vec![1].into(),
+            msg.into(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+    }
 
+    #[rstest]
+    #[case(true, Some(10), 25, true)]
+    #[case(true, None, 0, false)]
+    #[case(true, Some(10), 5, false)]
+    #[case(false, Some(10), 25, false)]
+    fn test_process_reward(
+        #[case] enabled: bool,
+        #[case] reward: Option<u128>,
+        #[case] balance: u128,
+        #[case] paid: bool,
+    ) {
         let mut deps = mock_dependencies();
+        deps.querier.update_wasm(test_process_query_handler);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config {
+                    process_rewards: enabled,
+                    ..Config::new("osmo", LOCAL_DOMAIN).with_ism(addr("default_ism"))
+                },
+            )
+            .unwrap();
+
+        let recipient = Addr::unchecked(bech32_encode("osmo", &[0xab; 32]).unwrap());
+        if let Some(reward) = reward {
+            PROCESS_REWARDS
+                .save(
+                    deps.as_mut().storage,
+                    recipient.clone(),
+                    &ProcessReward {
+                        reward: coin(reward, "uosmo"),
+                        balance: balance.into(),
+                    },
+                )
+                .unwrap();
+        }
 
-        deps.querier.update_wasm(|q| mock_query_handler(q, &None));
+        let msg = Message {
+            version: MAILBOX_VERSION,
+            nonce: 123,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: vec![0xab; 32].into(),
+            body: gen_bz(123),
+        };
+        let id = msg.id();
 
-        instantiate(
+        let res = process(
             deps.as_mut(),
             mock_env(),
-            mock_info(OWNER, &[]),
-            InstantiateMsg {
-                hrp: hrp.to_string(),
-                owner: OWNER.to_string(),
-                domain: LOCAL_DOMAIN,
-            },
+            mock_info("relayer", &[]),
+            vec![1].into(),
+            msg.into(),
         )
         .unwrap();
 
-        set_default_ism(deps.as_mut(), mock_info(OWNER, &[]), "default_ism".into()).unwrap();
-        set_default_hook(deps.as_mut(), mock_info(OWNER, &[]), "default_hook".into()).unwrap();
-        set_required_hook(deps.as_mut(), mock_info(OWNER, &[]), "required_hook".into()).unwrap();
-
-        let dispatch_msg = DispatchMsg::new(dest_domain, recipient_addr, msg_body)
-            .with_metadata(1500u32.to_be_bytes().to_vec());
+        let paid_event = emit_process_reward_paid(&id, &addr("relayer"), &coin(10, "uosmo"));
+        assert_eq!(res.events.contains(&paid_event), paid);
+
+        // the reward goes out ahead of the handle
+        if paid {
+            assert_eq!(res.messages.len(), 2);
+            assert_eq!(
+                res.messages[0].msg,
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "relayer".to_string(),
+                    amount: vec![coin(10, "uosmo")],
+                })
+            );
+        } else {
+            assert_eq!(res.messages.len(), 1);
+        }
 
-        let msg = dispatch_msg
-            .clone()
-            .to_msg(
-                MAILBOX_VERSION,
-                NONCE.load(deps.as_ref().storage).unwrap(),
-                LOCAL_DOMAIN,
-                &sender,
-            )
+        let process_reward = PROCESS_REWARDS
+            .may_load(deps.as_ref().storage, recipient)
             .unwrap();
-
-        let _res = dispatch(
-            deps.as_mut(),
-            mock_info(sender.as_str(), &[coin(1500, "utest")]),
-            dispatch_msg,
-        )
-        .map_err(|e| e.to_string())
-        .unwrap();
-
-        assert_eq!(NONCE.load(deps.as_ref().storage).unwrap(), 1u32);
         assert_eq!(
-            LATEST_DISPATCHED_ID.load(deps.as_ref().storage).unwrap(),
-            msg.id().to_vec()
+            process_reward.map(|v| v.balance.u128()),
+            reward.map(|_| if paid { balance - 10 } else { balance })
         );
     }
 
     #[rstest]
-    #[case(vec![coin(100, "usd")], vec![coin(100, "usd")])]
-    #[should_panic]
-    #[case(vec![coin(100, "usd")], vec![coin(50, "usd")])]
-    #[should_panic]
-    #[case(vec![coin(100, "usdt")], vec![coin(100, "usd")])]
-    #[case(vec![coin(50, "usd")], vec![coin(100, "usd")])]
-    fn test_post_dispatch(#[case] required_hook_fees: Vec<Coin>, #[case] funds: Vec<Coin>) {
+    #[case(0)]
+    #[case(76)]
+    fn test_process_invalid_message(#[case] len: usize) {
         let mut deps = mock_dependencies();
 
-        let mut hook_fees = HashMap::new();
-        hook_fees.insert("required_hook".into(), required_hook_fees.clone());
-
-        // not enforced by mailbox
-        // hook_fees.insert("default_hook".into(), default_hook_fees);
-
-        let opt = Some(hook_fees);
-
-        deps.querier
-            .update_wasm(move |q| mock_query_handler(q, &opt));
-
-        let hrp = "osmo";
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", LOCAL_DOMAIN).with_ism(addr("default_ism")),
+            )
+            .unwrap();
 
-        instantiate(
+        let err = process(
             deps.as_mut(),
             mock_env(),
-            mock_info(OWNER, &[]),
-            InstantiateMsg {
-                hrp: "osmo".to_string(),
-                owner: OWNER.to_string(),
-                domain: LOCAL_DOMAIN,
-            },
+            mock_info("relayer", &[]),
+            vec![1].into(),
+            gen_bz(len),
         )
-        .unwrap();
+        .unwrap_err();
 
-        set_default_hook(deps.as_mut(), mock_info(OWNER, &[]), "default_hook".into()).unwrap();
-        set_required_hook(deps.as_mut(), mock_info(OWNER, &[]), "required_hook".into()).unwrap();
+        assert_eq!(err, ContractError::InvalidMessage {});
+    }
 
-        let dispatch_msg = DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(123));
+    fn batch_message(nonce: u32) -> Message {
+        Message {
+            version: MAILBOX_VERSION,
+            nonce,
+            origin_domain: DEST_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body: gen_bz(123),
+        }
+    }
 
-        let sender = bech32_encode(hrp, gen_bz(32).as_slice()).unwrap();
+    #[rstest]
+    fn test_process_batch() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(test_process_query_handler);
 
-        let msg = dispatch_msg
-            .clone()
-            .to_msg(
-                MAILBOX_VERSION,
-                NONCE.load(deps.as_ref().storage).unwrap(),
-                LOCAL_DOMAIN,
-                &sender,
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", LOCAL_DOMAIN).with_ism(addr("default_ism")),
             )
             .unwrap();
 
-        let post_dispatch_msg = to_json_binary(
-            &PostDispatchMsg {
-                metadata: HexBinary::default(),
-                message: msg.into(),
-            }
-            .wrap(), // not sure why I need this
-        )
-        .unwrap();
+        let msgs = (0..3).map(batch_message).collect::<Vec<_>>();
+        let items = msgs
+            .iter()
+            .map(|msg| ProcessItem {
+                metadata: vec![1].into(),
+                message: msg.clone().into(),
+            })
+            .collect();
 
-        let res = dispatch(
+        let relayer = addr("relayer");
+        let res = process_batch(
             deps.as_mut(),
-            mock_info(sender.as_str(), &funds),
-            dispatch_msg.clone(),
+            mock_env(),
+            mock_info(relayer.as_str(), &[]),
+            items,
         )
         .map_err(|e| e.to_string())
         .unwrap();
 
-        let msgs: Vec<_> = res.messages.into_iter().map(|v| v.msg).collect();
+        // same events as processing each message on its own
+        let events = msgs
+            .iter()
+            .flat_map(|msg| {
+                [
+                    hpl_events::process_id(&msg.id(), &relayer),
+                    hpl_events::process(LOCAL_DOMAIN, &msg.sender, &msg.recipient),
+                ]
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(res.events, events);
 
+        // handles run in order and only the last one releases the guard
+        let reply_on = res.messages.iter().map(|v| v.reply_on.clone());
         assert_eq!(
-            msgs[0],
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "required_hook".to_string(),
-                msg: post_dispatch_msg.clone(),
-                funds: required_hook_fees.clone()
-            },)
+            reply_on.collect::<Vec<_>>(),
+            vec![ReplyOn::Never, ReplyOn::Never, ReplyOn::Success]
         );
+        assert_eq!(res.messages[2].id, REPLY_ID_PROCESS);
+        for (sub, msg) in res.messages.iter().zip(&msgs) {
+            let CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) = &sub.msg else {
+                unreachable!("handle is a wasm execute")
+            };
+            assert_eq!(contract_addr, msg.recipient_addr("osmo").unwrap().as_str());
+        }
 
-        // subtract required_hook_fees from funds
-        let mut remaining_funds = Coins::try_from(funds).unwrap();
-        for coin in required_hook_fees {
-            remaining_funds.sub(coin).unwrap();
+        for msg in msgs {
+            assert!(DELIVERIES.has(deps.as_ref().storage, msg.id().to_vec()));
         }
+        assert!(PROCESS_GUARD.is_entered(deps.as_ref().storage).unwrap());
+    }
 
-        assert_eq!(
-            msgs[1],
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: "default_hook".to_string(),
-                msg: post_dispatch_msg,
-                funds: remaining_funds.into_vec() // forward all remaining funds
+    #[rstest]
+    #[case(vec![0, 1, 0])]
+    #[case(vec![0, 0])]
+    fn test_process_batch_duplicate(#[case] nonces: Vec<u32>) {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(test_process_query_handler);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", LOCAL_DOMAIN).with_ism(addr("default_ism")),
+            )
+            .unwrap();
+
+        let msgs = [batch_message(0), batch_message(1)];
+        let items = nonces
+            .into_iter()
+            .map(|nonce| ProcessItem {
+                metadata: vec![1].into(),
+                message: msgs[nonce as usize].clone().into(),
             })
-        );
+            .collect();
+
+        // the error reverts the whole transaction, earlier items included
+        let err =
+            process_batch(deps.as_mut(), mock_env(), mock_info("relayer", &[]), items).unwrap_err();
+
+        assert_eq!(err, ContractError::AlreadyDeliveredMessage {});
     }
 
-    fn test_process_query_handler(query: &WasmQuery) -> QuerierResult {
-        match query {
-            WasmQuery::Smart { contract_addr, msg } => {
-                if let Ok(req) = cosmwasm_std::from_json::<ism::ExpectedIsmSpecifierQueryMsg>(msg) {
-                    match req {
-                        hpl_interface::ism::ExpectedIsmSpecifierQueryMsg::IsmSpecifier(
-                            ism::IsmSpecifierQueryMsg::InterchainSecurityModule(),
-                        ) => {
-                            return SystemResult::Ok(
-                                to_json_binary(&ism::InterchainSecurityModuleResponse {
-                                    ism: Some(addr("default_ism")),
-                                })
-                                .into(),
-                            );
-                        }
-                    }
-                }
+    #[rstest]
+    #[case(0)]
+    #[case(MAX_PROCESS_BATCH + 1)]
+    fn test_process_batch_size(#[case] size: usize) {
+        let mut deps = mock_dependencies();
 
-                if let Ok(req) = cosmwasm_std::from_json::<ism::ExpectedIsmQueryMsg>(msg) {
-                    assert_eq!(contract_addr, &addr("default_ism"));
+        let items = (0..size as u32)
+            .map(|nonce| ProcessItem {
+                metadata: vec![1].into(),
+                message: batch_message(nonce).into(),
+            })
+            .collect();
 
-                    match req {
-                        ism::ExpectedIsmQueryMsg::Ism(IsmQueryMsg::Verify { metadata, .. }) => {
-                            return SystemResult::Ok(
-                                to_json_binary(&ism::VerifyResponse {
-                                    verified: metadata[0] == 1,
-                                })
-                                .into(),
-                            );
-                        }
-                        _ => unreachable!("not in test coverage"),
-                    }
-                }
+        let err =
+            process_batch(deps.as_mut(), mock_env(), mock_info("relayer", &[]), items).unwrap_err();
 
-                unreachable!("not in test coverage")
+        assert_eq!(
+            err,
+            ContractError::InvalidBatchSize {
+                size,
+                max: MAX_PROCESS_BATCH
             }
-            _ => unimplemented!("only for smart query"),
-        }
+        );
+        // rejected before the guard is entered
+        assert!(!PROCESS_GUARD.is_entered(deps.as_ref().storage).unwrap());
     }
 
     #[rstest]
-    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), false, true)]
-    #[should_panic(expected = "invalid message version: 99")]
-    #[case(99, LOCAL_DOMAIN, gen_bz(32), false, true)]
-    #[should_panic(expected = "message already delivered")]
-    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), true, true)]
-    #[should_panic(expected = "invalid destination domain: 11155111")]
-    #[case(MAILBOX_VERSION, DEST_DOMAIN, gen_bz(32), false, true)]
-    #[should_panic(expected = "ism verify failed")]
-    #[case(MAILBOX_VERSION, LOCAL_DOMAIN, gen_bz(32), false, false)]
-    fn test_process(
-        #[values("osmo", "neutron")] hrp: &str,
-        #[case] version: u8,
-        #[case] dest_domain: u32,
-        #[case] recipient_addr: HexBinary,
-        #[case] duplicate: bool,
-        #[case] verified: bool,
-    ) {
-        let sender = gen_bz(32);
-        let sender_addr = bech32_encode(hrp, &sender).unwrap();
-        let msg_body = gen_bz(123);
-
+    fn test_process_legacy_delivery() {
         let mut deps = mock_dependencies();
-
         deps.querier.update_wasm(test_process_query_handler);
 
         CONFIG
             .save(
                 deps.as_mut().storage,
-                &Config::new(hrp, LOCAL_DOMAIN)
-                    .with_hook(addr("default_hook"), addr("required_hook"))
-                    .with_ism(addr("default_ism")),
+                &Config::new("osmo", LOCAL_DOMAIN).with_ism(addr("default_ism")),
             )
             .unwrap();
 
         let msg = Message {
-            version,
+            version: MAILBOX_VERSION,
             nonce: 123,
             origin_domain: DEST_DOMAIN,
-            sender,
-            dest_domain,
-            recipient: recipient_addr,
-            body: msg_body,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: gen_bz(32),
+            body: gen_bz(123),
         };
-        let msg_id = msg.id();
 
-        if duplicate {
-            DELIVERIES
+        LEGACY_DELIVERIES
+            .save(
+                deps.as_mut().storage,
+                msg.id().to_vec(),
+                &Some(Delivery {
+                    sender: addr("relayer"),
+                    block_number: 123,
+                }),
+            )
+            .unwrap();
+
+        let err = process(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("relayer", &[]),
+            vec![1].into(),
+            msg.into(),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, ContractError::AlreadyDeliveredMessage {});
+    }
+
+    #[rstest]
+    #[should_panic(expected = "unauthorized")]
+    #[case(NOT_OWNER, 1, 32)]
+    #[should_panic(expected = "too many deliveries to import: 101 > 100")]
+    #[case(OWNER, 101, 32)]
+    #[should_panic(expected = "invalid message id length: 20")]
+    #[case(OWNER, 1, 20)]
+    fn test_import_deliveries_invalid(
+        mut deps: TestDeps,
+        #[case] sender: &str,
+        #[case] count: usize,
+        #[case] len: usize,
+    ) {
+        let ids = (0..count).map(|_| gen_bz(len)).collect();
+
+        import_deliveries(deps.as_mut(), mock_info(sender, &[]), ids)
+            .map_err(|e| e.to_string())
+            .unwrap();
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(10)]
+    fn test_migrate_deliveries(mut deps: TestDeps, #[case] limit: u32) {
+        let legacy_ids: Vec<HexBinary> = (0..5).map(|_| gen_bz(32)).collect();
+        let new_ids: Vec<HexBinary> = (0..3).map(|_| gen_bz(32)).collect();
+
+        // mixed-format storage
+        for id in legacy_ids.iter() {
+            LEGACY_DELIVERIES
                 .save(
                     deps.as_mut().storage,
-                    msg.id().to_vec(),
-                    &Delivery {
-                        sender: sender_addr.clone(),
-                        block_number: mock_env().block.height,
-                    },
+                    id.to_vec(),
+                    &Some(Delivery {
+                        sender: addr("relayer"),
+                        block_number: 123,
+                    }),
                 )
                 .unwrap();
         }
+        for id in new_ids.iter() {
+            DELIVERIES
+                .save(deps.as_mut().storage, id.to_vec(), &())
+                .unwrap();
+        }
 
-        let _res = process(
-            deps.as_mut(),
-            mock_env(),
-            mock_info(sender_addr.as_str(), &[]),
-            vec![verified.into()].into(),
-            msg.into(),
-        )
-        .map_err(|v| v.to_string())
-        .unwrap();
+        let all_ids = [legacy_ids.clone(), new_ids].concat();
+        let assert_delivered = |deps: &TestDeps| {
+            for id in all_ids.iter() {
+                let res = crate::query::get_delivered(deps.as_ref(), id.clone()).unwrap();
+                assert!(res.delivered);
+            }
+        };
+
+        assert_delivered(&deps);
+
+        let err = migrate_deliveries(deps.as_mut(), mock_info(NOT_OWNER, &[]), limit).unwrap_err();
+        assert_eq!(err, ContractError::Hpl(HplError::Unauthorized {}));
+
+        let mut migrated = 0;
+        let mut batches = 0;
+        loop {
+            let res = migrate_deliveries(deps.as_mut(), mock_info(OWNER, &[]), limit).unwrap();
+            batches += 1;
 
-        let delivery = DELIVERIES
-            .load(deps.as_ref().storage, msg_id.to_vec())
+            let attr = |key: &str| {
+                res.events[0]
+                    .attributes
+                    .iter()
+                    .find(|v| v.key == key)
+                    .unwrap()
+                    .value
+                    .clone()
+            };
+            migrated += attr("migrated").parse::<u32>().unwrap();
+
+            assert_delivered(&deps);
+
+            if attr("finished") == "true" {
+                break;
+            }
+        }
+
+        assert_eq!(migrated, legacy_ids.len() as u32);
+        assert_eq!(batches, all_ids.len() as u32 / limit + 1);
+        assert!(!DELIVERIES_MIGRATION_CURSOR.exists(deps.as_ref().storage));
+
+        let remaining = LEGACY_DELIVERIES
+            .range(deps.as_ref().storage, None, None, Order::Ascending)
+            .filter(|v| v.as_ref().unwrap().1.is_some())
+            .count();
+        assert_eq!(remaining, 0);
+    }
+
+    #[rstest]
+    fn test_migrate_latest_dispatched_id(mut deps: TestDeps) {
+        let id = gen_bz(32);
+
+        LEGACY_LATEST_DISPATCHED_ID
+            .save(deps.as_mut().storage, &id.to_vec())
+            .unwrap();
+
+        // migrating twice keeps the hex value
+        for _ in 0..2 {
+            migrate_latest_dispatched_id(deps.as_mut().storage).unwrap();
+            assert_eq!(
+                LATEST_DISPATCHED_ID.load(deps.as_ref().storage).unwrap(),
+                id
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(0, None)]
+    #[case(5, Some(4))]
+    fn test_migrate_latest_dispatched_nonce(
+        mut deps: TestDeps,
+        #[case] nonce: u32,
+        #[case] expected: Option<u32>,
+    ) {
+        LATEST_DISPATCHED_NONCE.remove(deps.as_mut().storage);
+        NONCE.save(deps.as_mut().storage, &nonce).unwrap();
+
+        migrate_latest_dispatched_nonce(deps.as_mut().storage).unwrap();
+        assert_eq!(
+            LATEST_DISPATCHED_NONCE
+                .may_load(deps.as_ref().storage)
+                .unwrap(),
+            expected
+        );
+
+        // a tracked nonce is kept
+        NONCE.save(deps.as_mut().storage, &9).unwrap();
+        LATEST_DISPATCHED_NONCE
+            .save(deps.as_mut().storage, &7)
             .unwrap();
-        assert_eq!(delivery.sender, sender_addr);
+        migrate_latest_dispatched_nonce(deps.as_mut().storage).unwrap();
+        assert_eq!(
+            LATEST_DISPATCHED_NONCE.load(deps.as_ref().storage).unwrap(),
+            7
+        );
+    }
+
+    #[rstest]
+    #[case(
+        r#"{"hrp":"osmo","local_domain":1}"#,
+        Some(crate::DEFAULT_MAX_BODY_SIZE)
+    )]
+    #[case(r#"{"hrp":"osmo","local_domain":1,"max_body_size":null}"#, None)]
+    #[case(r#"{"hrp":"osmo","local_domain":1,"max_body_size":512}"#, Some(512))]
+    fn test_migrate_max_body_size(
+        mut deps: TestDeps,
+        #[case] stored: &str,
+        #[case] expected: Option<u64>,
+    ) {
+        deps.storage.set(CONFIG.as_slice(), stored.as_bytes());
+
+        migrate_max_body_size(deps.as_mut().storage).unwrap();
+        assert_eq!(
+            CONFIG.load(deps.as_ref().storage).unwrap().max_body_size,
+            expected
+        );
     }
 }
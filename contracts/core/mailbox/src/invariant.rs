@@ -0,0 +1,195 @@
+//! Ordering guarantees of `dispatch`.
+//!
+//! Hooks must only be handed a message once its nonce and id are committed, so hooks reading
+//! the mailbox back agree with the message they're given. `dispatch_response` only builds the
+//! hook calls from the `DispatchCommit` of the same message, which `commit_dispatch` returns.
+
+use cosmwasm_std::{
+    ensure, to_json_binary, Addr, Coin, HexBinary, Response, StdResult, Storage, SubMsg,
+};
+use hpl_interface::{
+    core::mailbox::{DispatchResponse, EventBodyMode},
+    hook::post_dispatch,
+    types::Message,
+};
+
+use crate::{
+    state::{DISPATCHED_IDS, LATEST_DISPATCHED_ID, LATEST_DISPATCHED_NONCE, NONCE},
+    ContractError, REPLY_ID_POST_DISPATCH,
+};
+
+/// What `commit_dispatch` stored for a message
+#[derive(Debug)]
+pub struct DispatchCommit {
+    /// `NONCE` after the commit
+    nonce: u32,
+    /// `LATEST_DISPATCHED_ID` after the commit
+    id: HexBinary,
+}
+
+/// Stores `msg` as the latest dispatched message and advances `NONCE` past it.
+/// With a `retention`, the id falling out of the last `retention` dispatched ids is removed
+pub fn commit_dispatch(
+    storage: &mut dyn Storage,
+    msg: &Message,
+    retention: Option<u32>,
+) -> StdResult<DispatchCommit> {
+    let id = msg.id();
+    let nonce = msg.nonce + 1;
+
+    NONCE.save(storage, &nonce)?;
+    LATEST_DISPATCHED_ID.save(storage, &id)?;
+    LATEST_DISPATCHED_NONCE.save(storage, &msg.nonce)?;
+    DISPATCHED_IDS.save(storage, msg.nonce, &id.to_vec())?;
+
+    if let Some(expired) = retention.and_then(|r| msg.nonce.checked_sub(r)) {
+        DISPATCHED_IDS.remove(storage, expired);
+    }
+
+    Ok(DispatchCommit { nonce, id })
+}
+
+/// Fails unless `commit` is the one of `msg`, i.e. `msg.nonce == NONCE - 1` and
+/// `msg.id() == LATEST_DISPATCHED_ID`. Panics instead in debug builds
+pub fn ensure_committed(commit: &DispatchCommit, msg: &Message) -> Result<(), ContractError> {
+    let committed = commit.nonce.checked_sub(1) == Some(msg.nonce) && commit.id == msg.id();
+    debug_assert!(
+        committed,
+        "dispatch of nonce {} isn't committed: {commit:?}",
+        msg.nonce
+    );
+
+    ensure!(
+        committed,
+        ContractError::DispatchNotCommitted { nonce: msg.nonce }
+    );
+
+    Ok(())
+}
+
+/// Builds the response of `dispatch` for a committed message: its events and data,
+/// and the `post_dispatch` calls of `hooks` paying their quoted fees
+pub fn dispatch_response(
+    commit: &DispatchCommit,
+    msg: Message,
+    metadata: HexBinary,
+    hooks: Vec<(Addr, Vec<Coin>)>,
+    event_body_mode: &EventBodyMode,
+) -> Result<Response, ContractError> {
+    ensure_committed(commit, &msg)?;
+
+    // failures come back as `HookFailed` through the reply
+    let post_dispatch_msgs = hooks
+        .into_iter()
+        .map(|(hook, fees)| {
+            let msg = post_dispatch(hook, metadata.clone(), msg.clone(), Some(fees))?;
+
+            Ok(SubMsg::reply_on_error(msg, REPLY_ID_POST_DISPATCH))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let dispatch_event = match event_body_mode {
+        EventBodyMode::Full => hpl_events::dispatch(msg),
+        EventBodyMode::HashOnly => hpl_events::dispatch_body_hash(msg),
+    };
+
+    Ok(Response::new()
+        .add_event(hpl_events::dispatch_id(&commit.id))
+        .add_event(dispatch_event)
+        .set_data(to_json_binary(&DispatchResponse {
+            message_id: commit.id.clone(),
+        })?)
+        .add_submessages(post_dispatch_msgs))
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockStorage;
+    use ibcx_test_utils::{addr, gen_bz};
+    use rstest::rstest;
+
+    use super::*;
+
+    fn message(nonce: u32) -> Message {
+        Message {
+            version: crate::MAILBOX_VERSION,
+            nonce,
+            origin_domain: 26657,
+            sender: gen_bz(32),
+            dest_domain: 1,
+            recipient: gen_bz(32),
+            body: gen_bz(123),
+        }
+    }
+
+    fn build(commit: &DispatchCommit, msg: Message) -> Result<Response, ContractError> {
+        dispatch_response(
+            commit,
+            msg,
+            HexBinary::default(),
+            vec![(addr("required_hook"), vec![]), (addr("hook"), vec![])],
+            &EventBodyMode::Full,
+        )
+    }
+
+    #[rstest]
+    fn test_dispatch_response() {
+        let mut storage = MockStorage::new();
+        let msg = message(0);
+
+        let commit = commit_dispatch(&mut storage, &msg, None).unwrap();
+        let res = build(&commit, msg.clone()).unwrap();
+
+        assert_eq!(NONCE.load(&storage).unwrap(), 1);
+        assert_eq!(LATEST_DISPATCHED_ID.load(&storage).unwrap(), msg.id());
+        assert_eq!(res.messages.len(), 2);
+        assert_eq!(res.events[0], hpl_events::dispatch_id(&msg.id()));
+    }
+
+    #[rstest]
+    fn test_commit_dispatch_retention() {
+        let mut storage = MockStorage::new();
+
+        for nonce in 0..4 {
+            commit_dispatch(&mut storage, &message(nonce), Some(2)).unwrap();
+        }
+
+        let nonces = DISPATCHED_IDS
+            .keys(&storage, None, None, cosmwasm_std::Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(nonces, vec![2, 3]);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "dispatch of nonce 1 isn't committed")]
+    fn test_dispatch_response_before_commit() {
+        let mut storage = MockStorage::new();
+
+        // the next message goes to the hooks before it's committed
+        let commit = commit_dispatch(&mut storage, &message(0), None).unwrap();
+        let _ = build(&commit, message(1));
+    }
+
+    #[rstest]
+    #[should_panic(expected = "dispatch of nonce 0 isn't committed")]
+    fn test_dispatch_response_after_next_commit() {
+        let mut storage = MockStorage::new();
+
+        // the hooks of a message are built once the next one is committed
+        let msg = message(0);
+        commit_dispatch(&mut storage, &msg, None).unwrap();
+        let commit = commit_dispatch(&mut storage, &message(1), None).unwrap();
+        let _ = build(&commit, msg);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "dispatch of nonce 0 isn't committed")]
+    fn test_dispatch_response_other_message() {
+        let mut storage = MockStorage::new();
+
+        // same nonce, but not the committed message
+        let commit = commit_dispatch(&mut storage, &message(0), None).unwrap();
+        let _ = build(&commit, message(0));
+    }
+}
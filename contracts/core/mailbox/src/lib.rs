@@ -2,13 +2,35 @@ pub mod contract;
 mod error;
 mod event;
 pub mod execute;
+mod invariant;
 pub mod query;
 mod state;
 
+#[cfg(test)]
+mod multitest;
+
 pub use crate::error::ContractError;
 
 pub const MAILBOX_VERSION: u8 = 3;
 
+pub const REPLY_ID_PROCESS: u64 = 0;
+pub const REPLY_ID_POST_DISPATCH: u64 = 1;
+
+/// default upper bound of dispatched message bodies (10 KiB)
+pub const DEFAULT_MAX_BODY_SIZE: u64 = 10 * 1024;
+
+/// upper bound of sub-queries in a single `MultiQuery`
+pub const MAX_MULTI_QUERY_SIZE: usize = 30;
+
+/// upper bound of message ids in a single `ImportDeliveries`
+pub const MAX_IMPORT_DELIVERIES: usize = 100;
+
+/// upper bound of messages in a single `ProcessBatch`
+pub const MAX_PROCESS_BATCH: usize = 20;
+
+/// number of config snapshots kept, older ones are pruned
+pub const MAX_SNAPSHOTS: u64 = 50;
+
 // version info for migration info
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
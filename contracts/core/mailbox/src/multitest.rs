@@ -0,0 +1,1095 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coin, to_json_binary, wasm_execute, Addr, Api, Binary, BlockInfo, Coin, CustomQuery, Deps,
+    DepsMut, Empty, Env, HexBinary, MessageInfo, Querier, QueryRequest, Response, StdError,
+    StdResult, Storage,
+};
+use cw_multi_test::{
+    addons::{MockAddressGenerator, MockApiBech32},
+    App, AppBuilder, AppResponse, BankKeeper, BasicAppBuilder, Contract, ContractWrapper,
+    CosmosRouter, Executor, Module, WasmKeeper,
+};
+use cw_storage_plus::Item;
+use hpl_interface::{
+    core::{
+        mailbox::{
+            DefaultIsmResponse, DispatchMsg, ExecuteMsg, InstantiateMsg, MailboxHookQueryMsg,
+            MailboxQueryMsg, MessageDeliveredResponse, ProcessItem, QueryMsg, RecipientIsmResponse,
+        },
+        HandleMsg,
+    },
+    hook::{
+        ExpectedHookMsg, ExpectedHookQueryMsg, HookQueryMsg, HookType, HookTypeResponse,
+        QuoteDispatchResponse,
+    },
+    ism::{
+        ExpectedIsmQueryMsg, ExpectedIsmSpecifierQueryMsg, InterchainSecurityModuleResponse,
+        VerifyResponse,
+    },
+    types::{bech32_to_h256, Message},
+};
+use ibcx_test_utils::gen_bz;
+
+use crate::{
+    contract::{execute, instantiate, query, reply},
+    state::PROCESS_GUARD_KEY,
+    ContractError, MAILBOX_VERSION, MAX_PROCESS_BATCH,
+};
+
+type TestApp = App<BankKeeper, MockApiBech32>;
+
+const HRP: &str = "osmo";
+const LOCAL_DOMAIN: u32 = 26657;
+const ORIGIN_DOMAIN: u32 = 1;
+const DENOM: &str = "uosmo";
+
+/// message the malicious recipient re-submits to the mailbox from its `Handle`
+const REPLAY: Item<(Addr, HexBinary, HexBinary)> = Item::new("replay");
+
+#[cw_serde]
+enum RecipientMsg {
+    Handle(HandleMsg),
+    SetReplay {
+        mailbox: String,
+        metadata: HexBinary,
+        message: HexBinary,
+    },
+}
+
+fn recipient_instantiate<Q: CustomQuery>(
+    _deps: DepsMut<Q>,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn recipient_execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: RecipientMsg,
+) -> StdResult<Response> {
+    match msg {
+        RecipientMsg::Handle(_) => {
+            match REPLAY.may_load(deps.storage)? {
+                Some((mailbox, metadata, message)) => Ok(Response::new().add_message(
+                    wasm_execute(mailbox, &ExecuteMsg::Process { metadata, message }, vec![])?,
+                )),
+                None => Ok(Response::new()),
+            }
+        }
+        RecipientMsg::SetReplay {
+            mailbox,
+            metadata,
+            message,
+        } => {
+            let mailbox = deps.api.addr_validate(&mailbox)?;
+            REPLAY.save(deps.storage, &(mailbox, metadata, message))?;
+
+            Ok(Response::new())
+        }
+    }
+}
+
+fn recipient_query(_deps: Deps, _env: Env, msg: ExpectedIsmSpecifierQueryMsg) -> StdResult<Binary> {
+    match msg {
+        // fall back to the mailbox default ism
+        ExpectedIsmSpecifierQueryMsg::IsmSpecifier(_) => {
+            to_json_binary(&InterchainSecurityModuleResponse { ism: None })
+        }
+    }
+}
+
+fn recipient() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        recipient_execute,
+        recipient_instantiate,
+        recipient_query,
+    ))
+}
+
+#[cw_serde]
+enum PlainRecipientQueryMsg {
+    Ping {},
+}
+
+fn plain_recipient_query(_deps: Deps, _env: Env, msg: PlainRecipientQueryMsg) -> StdResult<Binary> {
+    match msg {
+        PlainRecipientQueryMsg::Ping {} => to_json_binary(&Empty {}),
+    }
+}
+
+/// Recipient that doesn't implement `InterchainSecurityModule`
+fn plain_recipient() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        recipient_execute,
+        recipient_instantiate,
+        plain_recipient_query,
+    ))
+}
+
+/// ism the recipient specifies, set on instantiation
+const OWN_ISM: Item<Addr> = Item::new("own_ism");
+
+fn ism_recipient_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    ism: String,
+) -> StdResult<Response> {
+    OWN_ISM.save(deps.storage, &deps.api.addr_validate(&ism)?)?;
+
+    Ok(Response::new())
+}
+
+fn ism_recipient_query(
+    deps: Deps,
+    _env: Env,
+    msg: ExpectedIsmSpecifierQueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        ExpectedIsmSpecifierQueryMsg::IsmSpecifier(_) => {
+            to_json_binary(&InterchainSecurityModuleResponse {
+                ism: Some(OWN_ISM.load(deps.storage)?),
+            })
+        }
+    }
+}
+
+/// Recipient that specifies its own ism
+fn ism_recipient() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        recipient_execute,
+        ism_recipient_instantiate,
+        ism_recipient_query,
+    ))
+}
+
+fn broken_recipient_query(
+    _deps: Deps,
+    _env: Env,
+    _msg: ExpectedIsmSpecifierQueryMsg,
+) -> StdResult<Binary> {
+    Err(StdError::generic_err("recipient state is corrupted"))
+}
+
+/// Recipient whose `InterchainSecurityModule` fails
+fn broken_recipient() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        recipient_execute,
+        recipient_instantiate,
+        broken_recipient_query,
+    ))
+}
+
+fn hook_execute<Q: CustomQuery>(
+    _deps: DepsMut<Q>,
+    _env: Env,
+    _info: MessageInfo,
+    msg: ExpectedHookMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExpectedHookMsg::PostDispatch(_) => Ok(Response::new()),
+    }
+}
+
+fn failing_hook_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: ExpectedHookMsg,
+) -> StdResult<Response> {
+    Err(StdError::generic_err("hook is out of service"))
+}
+
+fn hook_query(_deps: Deps, _env: Env, msg: ExpectedHookQueryMsg) -> StdResult<Binary> {
+    match msg {
+        ExpectedHookQueryMsg::Hook(HookQueryMsg::QuoteDispatch(_)) => {
+            to_json_binary(&QuoteDispatchResponse { fees: vec![] })
+        }
+        ExpectedHookQueryMsg::Hook(HookQueryMsg::HookType {}) => {
+            to_json_binary(&HookTypeResponse {
+                typ: HookType::Unused,
+            })
+        }
+        ExpectedHookQueryMsg::Hook(HookQueryMsg::Mailbox {}) => unimplemented!(),
+    }
+}
+
+/// Free hook accepting every message
+fn hook() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        hook_execute,
+        recipient_instantiate,
+        hook_query,
+    ))
+}
+
+/// Free hook rejecting every message
+fn failing_hook() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        failing_hook_execute,
+        recipient_instantiate,
+        hook_query,
+    ))
+}
+
+fn mailbox() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+fn mock_ism() -> Box<dyn Contract<Empty>> {
+    use hpl_test_mock_ism::contract::{execute, instantiate, query};
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn rejecting_ism_query(_deps: Deps, _env: Env, _msg: ExpectedIsmQueryMsg) -> StdResult<Binary> {
+    to_json_binary(&VerifyResponse { verified: false })
+}
+
+/// Ism that rejects every message
+fn rejecting_ism() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        recipient_execute,
+        recipient_instantiate,
+        rejecting_ism_query,
+    ))
+}
+
+struct Suite {
+    app: TestApp,
+    owner: Addr,
+    mailbox: Addr,
+    recipient: Addr,
+}
+
+fn setup() -> anyhow::Result<Suite> {
+    let mut app = AppBuilder::default()
+        .with_api(MockApiBech32::new(HRP))
+        .with_wasm(WasmKeeper::default().with_address_generator(MockAddressGenerator))
+        .build(|router, _, storage| {
+            let owner = MockApiBech32::new(HRP).addr_make("owner");
+            router
+                .bank
+                .init_balance(storage, &owner, vec![coin(1_000, DENOM)])
+                .unwrap();
+        });
+
+    let owner = app.api().addr_make("owner");
+
+    let ism_code_id = app.store_code(mock_ism());
+    let ism = app.instantiate_contract(
+        ism_code_id,
+        owner.clone(),
+        &hpl_test_mock_ism::contract::InstantiateMsg {},
+        &[],
+        "ism",
+        None,
+    )?;
+
+    let mailbox_code_id = app.store_code(mailbox());
+    let mailbox = app.instantiate_contract(
+        mailbox_code_id,
+        owner.clone(),
+        &InstantiateMsg {
+            hrp: HRP.into(),
+            owner: owner.to_string(),
+            domain: LOCAL_DOMAIN,
+            default_ism: None,
+            default_hook: None,
+        },
+        &[],
+        "mailbox",
+        None,
+    )?;
+
+    app.execute_contract(
+        owner.clone(),
+        mailbox.clone(),
+        &ExecuteMsg::SetDefaultIsm {
+            ism: ism.to_string(),
+            unchecked: false,
+        },
+        &[],
+    )?;
+
+    let recipient_code_id = app.store_code(recipient());
+    let recipient = app.instantiate_contract(
+        recipient_code_id,
+        owner.clone(),
+        &Empty {},
+        &[],
+        "recipient",
+        None,
+    )?;
+
+    Ok(Suite {
+        app,
+        owner,
+        mailbox,
+        recipient,
+    })
+}
+
+impl Suite {
+    fn message(&self, nonce: u32) -> HexBinary {
+        self.message_to(&self.recipient, nonce)
+    }
+
+    fn message_to(&self, recipient: &Addr, nonce: u32) -> HexBinary {
+        Message {
+            version: MAILBOX_VERSION,
+            nonce,
+            origin_domain: ORIGIN_DOMAIN,
+            sender: gen_bz(32),
+            dest_domain: LOCAL_DOMAIN,
+            recipient: bech32_to_h256(recipient.as_str()).unwrap().into(),
+            body: gen_bz(32),
+        }
+        .into()
+    }
+
+    fn instantiate_recipient(
+        &mut self,
+        contract: Box<dyn Contract<Empty>>,
+    ) -> anyhow::Result<Addr> {
+        let code_id = self.app.store_code(contract);
+
+        self.app.instantiate_contract(
+            code_id,
+            self.owner.clone(),
+            &Empty {},
+            &[],
+            "recipient",
+            None,
+        )
+    }
+
+    fn set_hooks(&mut self, hook: &Addr, required_hook: &Addr) -> anyhow::Result<()> {
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.mailbox.clone(),
+            &ExecuteMsg::SetDefaultHook {
+                hook: hook.to_string(),
+                unchecked: false,
+            },
+            &[],
+        )?;
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.mailbox.clone(),
+            &ExecuteMsg::SetRequiredHook {
+                hook: required_hook.to_string(),
+                unchecked: false,
+            },
+            &[],
+        )?;
+
+        Ok(())
+    }
+
+    fn set_default_ism(&mut self, ism: &Addr, unchecked: bool) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.mailbox.clone(),
+            &ExecuteMsg::SetDefaultIsm {
+                ism: ism.to_string(),
+                unchecked,
+            },
+            &[],
+        )
+    }
+
+    fn default_ism(&self) -> anyhow::Result<Addr> {
+        let res: DefaultIsmResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(&self.mailbox, &MailboxQueryMsg::DefaultIsm {}.wrap())?;
+
+        Ok(Addr::unchecked(res.default_ism))
+    }
+
+    fn dispatch(&mut self) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.mailbox.clone(),
+            &ExecuteMsg::Dispatch(DispatchMsg::new(ORIGIN_DOMAIN, gen_bz(32), gen_bz(32))),
+            &[],
+        )
+    }
+
+    fn set_replay(&mut self, message: &HexBinary) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.recipient.clone(),
+            &RecipientMsg::SetReplay {
+                mailbox: self.mailbox.to_string(),
+                metadata: HexBinary::default(),
+                message: message.clone(),
+            },
+            &[],
+        )
+    }
+
+    fn process(&mut self, message: &HexBinary) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.mailbox.clone(),
+            &ExecuteMsg::Process {
+                metadata: HexBinary::default(),
+                message: message.clone(),
+            },
+            &[],
+        )
+    }
+
+    fn process_batch(&mut self, messages: &[HexBinary]) -> anyhow::Result<AppResponse> {
+        let items = messages
+            .iter()
+            .map(|message| ProcessItem {
+                metadata: HexBinary::default(),
+                message: message.clone(),
+            })
+            .collect();
+
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.mailbox.clone(),
+            &ExecuteMsg::ProcessBatch { items },
+            &[],
+        )
+    }
+
+    fn set_code_id_ism(&mut self, code_id: u64, ism: Option<&Addr>) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.mailbox.clone(),
+            &ExecuteMsg::SetCodeIdIsm {
+                code_id,
+                ism: ism.map(Addr::to_string),
+            },
+            &[],
+        )
+    }
+
+    fn recipient_ism(&self, recipient: &Addr) -> anyhow::Result<Addr> {
+        let res: RecipientIsmResponse = self.app.wrap().query_wasm_smart(
+            &self.mailbox,
+            &MailboxQueryMsg::RecipientIsm {
+                recipient_addr: recipient.to_string(),
+            }
+            .wrap(),
+        )?;
+
+        Ok(Addr::unchecked(res.ism))
+    }
+
+    fn code_id(&self, contract: &Addr) -> anyhow::Result<u64> {
+        Ok(self.app.wrap().query_wasm_contract_info(contract)?.code_id)
+    }
+
+    fn is_delivered(&self, message: &HexBinary) -> bool {
+        let id = Message::try_from(message.clone()).unwrap().id();
+
+        self.app
+            .wrap()
+            .query_wasm_smart::<MessageDeliveredResponse>(
+                &self.mailbox,
+                &MailboxQueryMsg::MessageDelivered { id }.wrap(),
+            )
+            .unwrap()
+            .delivered
+    }
+
+    fn is_guarded(&self) -> bool {
+        self.app
+            .wrap()
+            .query_wasm_raw(&self.mailbox, PROCESS_GUARD_KEY.as_bytes())
+            .unwrap()
+            .is_some()
+    }
+}
+
+#[test]
+fn test_process() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let message = suite.message(0);
+    suite.process(&message)?;
+
+    assert!(suite.is_delivered(&message));
+    assert!(!suite.is_guarded());
+
+    // guard is cleared, so following messages go through
+    let message = suite.message(1);
+    suite.process(&message)?;
+
+    assert!(suite.is_delivered(&message));
+    assert!(!suite.is_guarded());
+
+    Ok(())
+}
+
+#[test]
+fn test_process_batch() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let messages = (0..3).map(|v| suite.message(v)).collect::<Vec<_>>();
+    suite.process_batch(&messages)?;
+
+    for message in &messages {
+        assert!(suite.is_delivered(message));
+    }
+    assert!(!suite.is_guarded());
+
+    Ok(())
+}
+
+#[test]
+fn test_process_batch_duplicate() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let delivered = suite.message(0);
+    suite.process(&delivered)?;
+
+    // the duplicate comes last, so the items before it are reverted as well
+    let messages = vec![suite.message(1), suite.message(2), delivered];
+    let err = suite.process_batch(&messages).unwrap_err();
+    assert!(format!("{err:?}").contains("message already delivered"));
+
+    assert!(!suite.is_delivered(&messages[0]));
+    assert!(!suite.is_delivered(&messages[1]));
+    assert!(!suite.is_guarded());
+
+    Ok(())
+}
+
+#[test]
+fn test_process_batch_reentrant() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    // the first handle tries to process another message before the batch is done
+    let nested = suite.message(2);
+    suite.set_replay(&nested)?;
+
+    let messages = vec![suite.message(0), suite.message(1)];
+    let err = suite.process_batch(&messages).unwrap_err();
+    assert!(format!("{err:?}").contains("reentrant call"));
+
+    assert!(!suite.is_delivered(&messages[0]));
+    assert!(!suite.is_delivered(&nested));
+    assert!(!suite.is_guarded());
+
+    Ok(())
+}
+
+#[test]
+fn test_process_batch_too_large() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let messages = (0..=MAX_PROCESS_BATCH as u32)
+        .map(|v| suite.message(v))
+        .collect::<Vec<_>>();
+    let err = suite.process_batch(&messages).unwrap_err();
+    assert!(format!("{err:?}").contains("invalid process batch size: 21"));
+
+    assert!(!suite.is_delivered(&messages[0]));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_reentrant_same_id() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let message = suite.message(0);
+    suite.set_replay(&message)?;
+
+    let err = suite.process(&message).unwrap_err();
+    assert!(format!("{err:?}").contains("reentrant call"));
+
+    assert!(!suite.is_delivered(&message));
+    assert!(!suite.is_guarded());
+
+    Ok(())
+}
+
+#[test]
+fn test_process_reentrant_other_id() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let message = suite.message(0);
+    let nested = suite.message(1);
+    suite.set_replay(&nested)?;
+
+    let err = suite.process(&message).unwrap_err();
+    assert!(format!("{err:?}").contains("reentrant call"));
+
+    assert!(!suite.is_delivered(&message));
+    assert!(!suite.is_delivered(&nested));
+    assert!(!suite.is_guarded());
+
+    Ok(())
+}
+
+#[test]
+fn test_process_recipient_ism_lookup() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    // answers `None`, follows the default ism
+    let message = suite.message(0);
+    suite.process(&message)?;
+    assert!(suite.is_delivered(&message));
+
+    // doesn't implement the query, follows the default ism
+    let plain = suite.instantiate_recipient(plain_recipient())?;
+    let message = suite.message_to(&plain, 1);
+    suite.process(&message)?;
+    assert!(suite.is_delivered(&message));
+
+    // any other failure is not taken as `None`
+    let broken = suite.instantiate_recipient(broken_recipient())?;
+    let message = suite.message_to(&broken, 2);
+    let err = suite.process(&message).unwrap_err();
+    assert!(format!("{err:?}").contains("recipient state is corrupted"));
+    assert!(!suite.is_delivered(&message));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_ism_lookup_opt_out() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let broken = suite.instantiate_recipient(broken_recipient())?;
+    let set_opt_out = |suite: &mut Suite, opt_out: bool| {
+        suite.app.execute_contract(
+            broken.clone(),
+            suite.mailbox.clone(),
+            &ExecuteMsg::SetIsmLookupOptOut { opt_out },
+            &[],
+        )
+    };
+
+    // never queried once opted out
+    set_opt_out(&mut suite, true)?;
+    let message = suite.message_to(&broken, 0);
+    suite.process(&message)?;
+    assert!(suite.is_delivered(&message));
+
+    set_opt_out(&mut suite, false)?;
+    let message = suite.message_to(&broken, 1);
+    let err = suite.process(&message).unwrap_err();
+    assert!(format!("{err:?}").contains("recipient state is corrupted"));
+
+    Ok(())
+}
+
+#[test]
+fn test_process_code_id_ism() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let recipient = suite.recipient.clone();
+    let default_ism = suite.recipient_ism(&recipient)?;
+    let rejecting = suite.instantiate_recipient(rejecting_ism())?;
+
+    // a recipient without its own ism follows the one of its code id
+    let code_id = suite.code_id(&recipient)?;
+    suite.set_code_id_ism(code_id, Some(&rejecting))?;
+    assert_eq!(suite.recipient_ism(&recipient)?, rejecting);
+
+    let message = suite.message(0);
+    let err = suite.process(&message).unwrap_err();
+    assert!(format!("{err:?}").contains("verify failed"));
+
+    // recipients of other code ids follow the default ism
+    let plain = suite.instantiate_recipient(plain_recipient())?;
+    assert_eq!(suite.recipient_ism(&plain)?, default_ism);
+
+    let message = suite.message_to(&plain, 1);
+    suite.process(&message)?;
+    assert!(suite.is_delivered(&message));
+
+    // the recipient's own ism comes before the one of its code id
+    let own_ism = suite.instantiate_recipient(mock_ism())?;
+    let ism_recipient_code_id = suite.app.store_code(ism_recipient());
+    let specifying = suite.app.instantiate_contract(
+        ism_recipient_code_id,
+        suite.owner.clone(),
+        &own_ism.to_string(),
+        &[],
+        "recipient",
+        None,
+    )?;
+    suite.set_code_id_ism(ism_recipient_code_id, Some(&rejecting))?;
+    assert_eq!(suite.recipient_ism(&specifying)?, own_ism);
+
+    let message = suite.message_to(&specifying, 2);
+    suite.process(&message)?;
+    assert!(suite.is_delivered(&message));
+
+    // removed, back to the default ism
+    suite.set_code_id_ism(code_id, None)?;
+    assert_eq!(suite.recipient_ism(&recipient)?, default_ism);
+
+    let message = suite.message(0);
+    suite.process(&message)?;
+    assert!(suite.is_delivered(&message));
+
+    Ok(())
+}
+
+#[test]
+fn test_dispatch_hook() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let hook = suite.instantiate_recipient(hook())?;
+    suite.set_hooks(&hook, &hook)?;
+
+    suite.dispatch()?;
+
+    Ok(())
+}
+
+#[test]
+fn test_dispatch_hook_failed() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let hook = suite.instantiate_recipient(hook())?;
+    let failing_hook = suite.instantiate_recipient(failing_hook())?;
+
+    // either hook failing aborts the dispatch
+    for (default_hook, required_hook) in [(&failing_hook, &hook), (&hook, &failing_hook)] {
+        suite.set_hooks(default_hook, required_hook)?;
+
+        let err = suite.dispatch().unwrap_err();
+        match err.downcast_ref::<ContractError>() {
+            Some(ContractError::HookFailed { reason }) => {
+                assert!(reason.contains("hook is out of service"), "{reason}")
+            }
+            _ => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_dispatch_msg_value() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let hook = suite.instantiate_recipient(hook())?;
+    suite.set_hooks(&hook, &hook)?;
+
+    // dispatched to the local domain, so the same mailbox delivers it
+    let dispatch_msg = DispatchMsg::new(
+        LOCAL_DOMAIN,
+        bech32_to_h256(suite.recipient.as_str())?,
+        gen_bz(32),
+    )
+    .with_msg_value(coin(100, DENOM));
+    let message: HexBinary = dispatch_msg
+        .clone()
+        .to_msg(MAILBOX_VERSION, 0, LOCAL_DOMAIN, &suite.owner)?
+        .into();
+
+    // funds must cover the value on top of the (free) hooks
+    let err = suite
+        .app
+        .execute_contract(
+            suite.owner.clone(),
+            suite.mailbox.clone(),
+            &ExecuteMsg::Dispatch(dispatch_msg.clone()),
+            &[coin(99, DENOM)],
+        )
+        .unwrap_err();
+    match err.downcast_ref::<ContractError>() {
+        Some(ContractError::InsufficientDispatchFunds { required, .. }) => {
+            assert_eq!(required, &vec![coin(100, DENOM)])
+        }
+        _ => panic!("unexpected error: {err:?}"),
+    }
+
+    // overpayment is refunded, the value stays in escrow
+    suite.app.execute_contract(
+        suite.owner.clone(),
+        suite.mailbox.clone(),
+        &ExecuteMsg::Dispatch(dispatch_msg),
+        &[coin(150, DENOM)],
+    )?;
+
+    let balance = |suite: &Suite, addr: &Addr| {
+        suite
+            .app
+            .wrap()
+            .query_balance(addr, DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    };
+    assert_eq!(balance(&suite, &suite.owner), 900);
+    assert_eq!(balance(&suite, &suite.mailbox), 100);
+
+    // released to the recipient on delivery
+    suite.process(&message)?;
+
+    assert!(suite.is_delivered(&message));
+    assert_eq!(balance(&suite, &suite.mailbox), 0);
+    assert_eq!(balance(&suite, &suite.recipient), 100);
+
+    Ok(())
+}
+
+#[test]
+fn test_dispatch_msg_value_remote() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let hook = suite.instantiate_recipient(hook())?;
+    suite.set_hooks(&hook, &hook)?;
+
+    // only this mailbox could release the escrow, so remote values are rejected
+    let dispatch_msg = DispatchMsg::new(
+        ORIGIN_DOMAIN,
+        bech32_to_h256(suite.recipient.as_str())?,
+        gen_bz(32),
+    )
+    .with_msg_value(coin(100, DENOM));
+
+    let err = suite
+        .app
+        .execute_contract(
+            suite.owner.clone(),
+            suite.mailbox.clone(),
+            &ExecuteMsg::Dispatch(dispatch_msg),
+            &[coin(100, DENOM)],
+        )
+        .unwrap_err();
+    match err.downcast_ref::<ContractError>() {
+        Some(ContractError::RemoteMsgValue { domain }) => assert_eq!(*domain, ORIGIN_DOMAIN),
+        _ => panic!("unexpected error: {err:?}"),
+    }
+
+    let balance = suite.app.wrap().query_balance(&suite.mailbox, DENOM)?;
+    assert!(balance.amount.is_zero());
+
+    Ok(())
+}
+
+#[test]
+fn test_set_default_ism_checked() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let ism = suite.instantiate_recipient(mock_ism())?;
+    let hook = suite.instantiate_recipient(hook())?;
+
+    suite.set_default_ism(&ism, false)?;
+    assert_eq!(suite.default_ism()?, ism);
+
+    // a hook doesn't answer `ModuleType`
+    let err = suite.set_default_ism(&hook, false).unwrap_err();
+    match err.downcast_ref::<ContractError>() {
+        Some(ContractError::NotAnIsm { address, .. }) => assert_eq!(address, hook.as_str()),
+        _ => panic!("unexpected error: {err:?}"),
+    }
+    assert_eq!(suite.default_ism()?, ism);
+
+    // unless the check is skipped
+    suite.set_default_ism(&hook, true)?;
+    assert_eq!(suite.default_ism()?, hook);
+
+    Ok(())
+}
+
+#[test]
+fn test_set_hooks_checked() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    let ism = suite.instantiate_recipient(mock_ism())?;
+    let hook = suite.instantiate_recipient(hook())?;
+
+    // an ism doesn't answer `HookType`
+    for msg in [
+        ExecuteMsg::SetDefaultHook {
+            hook: ism.to_string(),
+            unchecked: false,
+        },
+        ExecuteMsg::SetRequiredHook {
+            hook: ism.to_string(),
+            unchecked: false,
+        },
+    ] {
+        let err = suite
+            .app
+            .execute_contract(suite.owner.clone(), suite.mailbox.clone(), &msg, &[])
+            .unwrap_err();
+        match err.downcast_ref::<ContractError>() {
+            Some(ContractError::NotAHook { address, .. }) => assert_eq!(address, ism.as_str()),
+            _ => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    suite.set_hooks(&hook, &hook)?;
+
+    Ok(())
+}
+
+/// Query only the chain understands, answered by `ChainModule`
+#[cw_serde]
+enum ChainQuery {
+    DispatchFee {},
+}
+
+impl CustomQuery for ChainQuery {}
+
+/// Stub of the chain's custom query handler
+struct ChainModule;
+
+impl Module for ChainModule {
+    type ExecT = Empty;
+    type QueryT = ChainQuery;
+    type SudoT = Empty;
+
+    fn execute<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _sender: Addr,
+        _msg: Empty,
+    ) -> anyhow::Result<AppResponse> {
+        anyhow::bail!("custom messages are not supported")
+    }
+
+    fn query(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: ChainQuery,
+    ) -> anyhow::Result<Binary> {
+        match request {
+            ChainQuery::DispatchFee {} => Ok(to_json_binary(&coin(10, DENOM))?),
+        }
+    }
+
+    fn sudo<ExecC, QueryC>(
+        &self,
+        _api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        _block: &BlockInfo,
+        _msg: Empty,
+    ) -> anyhow::Result<AppResponse> {
+        anyhow::bail!("custom sudo is not supported")
+    }
+}
+
+/// Hook quoting the fee the chain reports
+fn chain_hook_query(
+    deps: Deps<ChainQuery>,
+    _env: Env,
+    msg: ExpectedHookQueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        ExpectedHookQueryMsg::Hook(HookQueryMsg::QuoteDispatch(_)) => {
+            let fee: Coin = deps
+                .querier
+                .query(&QueryRequest::Custom(ChainQuery::DispatchFee {}))?;
+
+            to_json_binary(&QuoteDispatchResponse { fees: vec![fee] })
+        }
+        ExpectedHookQueryMsg::Hook(HookQueryMsg::HookType {}) => {
+            to_json_binary(&HookTypeResponse {
+                typ: HookType::Unused,
+            })
+        }
+        ExpectedHookQueryMsg::Hook(HookQueryMsg::Mailbox {}) => unimplemented!(),
+    }
+}
+
+#[test]
+fn test_custom_query_chain() -> anyhow::Result<()> {
+    let mut app = BasicAppBuilder::<Empty, ChainQuery>::new_custom()
+        .with_api(MockApiBech32::new(HRP))
+        .with_custom(ChainModule)
+        .with_wasm(WasmKeeper::default().with_address_generator(MockAddressGenerator))
+        .build(|router, _, storage| {
+            let owner = MockApiBech32::new(HRP).addr_make("owner");
+            router
+                .bank
+                .init_balance(storage, &owner, vec![coin(1_000, DENOM)])
+                .unwrap();
+        });
+
+    let owner = app.api().addr_make("owner");
+
+    let mailbox_code_id = app.store_code(Box::new(
+        ContractWrapper::new(
+            execute::<ChainQuery>,
+            instantiate::<ChainQuery>,
+            query::<ChainQuery>,
+        )
+        .with_reply(reply::<ChainQuery>),
+    ));
+    let mailbox = app.instantiate_contract(
+        mailbox_code_id,
+        owner.clone(),
+        &InstantiateMsg {
+            hrp: HRP.into(),
+            owner: owner.to_string(),
+            domain: LOCAL_DOMAIN,
+            default_ism: None,
+            default_hook: None,
+        },
+        &[],
+        "mailbox",
+        None,
+    )?;
+
+    let hook_code_id = app.store_code(Box::new(ContractWrapper::new(
+        hook_execute::<ChainQuery>,
+        recipient_instantiate::<ChainQuery>,
+        chain_hook_query,
+    )));
+    let hook =
+        app.instantiate_contract(hook_code_id, owner.clone(), &Empty {}, &[], "hook", None)?;
+
+    for msg in [
+        ExecuteMsg::SetDefaultHook {
+            hook: hook.to_string(),
+            unchecked: false,
+        },
+        ExecuteMsg::SetRequiredHook {
+            hook: hook.to_string(),
+            unchecked: false,
+        },
+    ] {
+        app.execute_contract(owner.clone(), mailbox.clone(), &msg, &[])?;
+    }
+
+    // both hooks quote through the chain
+    let dispatch_msg = DispatchMsg::new(ORIGIN_DOMAIN, gen_bz(32), gen_bz(32));
+    let quote: QuoteDispatchResponse = app.wrap().query_wasm_smart(
+        &mailbox,
+        &QueryMsg::Hook(MailboxHookQueryMsg::QuoteDispatch {
+            sender: owner.to_string(),
+            msg: dispatch_msg.clone(),
+        }),
+    )?;
+    assert_eq!(quote.fees, vec![coin(20, DENOM)]);
+
+    app.execute_contract(
+        owner.clone(),
+        mailbox.clone(),
+        &ExecuteMsg::Dispatch(dispatch_msg),
+        &quote.fees,
+    )?;
+    assert_eq!(app.wrap().query_balance(&hook, DENOM)?.amount.u128(), 20);
+
+    Ok(())
+}
@@ -1,26 +1,47 @@
-use cosmwasm_std::{Coins, Deps, HexBinary, StdResult};
+use cosmwasm_std::{ensure, Addr, Coins, CustomQuery, Deps, HexBinary, Order, StdResult};
+use cw_storage_plus::Bound;
+use hpl_error::HplError;
 use hpl_interface::{
     core::mailbox::{
-        DefaultHookResponse, DefaultIsmResponse, DispatchMsg, HrpResponse,
-        LatestDispatchedIdResponse, LocalDomainResponse, MessageDeliveredResponse, NonceResponse,
-        RecipientIsmResponse, RequiredHookResponse,
+        ApprovedDispatchersResponse, CodeIdIsmResponse, ConfigResponse, ContextIsmsResponse,
+        DefaultHookResponse, DefaultIsmResponse, DispatchMsg, DispatchedId,
+        DispatchedIdAtNonceResponse, DispatchedIdsResponse, DispatchedIdsRetentionResponse,
+        DomainName, DomainNameResponse, DomainNamesResponse, EventBodyModeResponse,
+        ExportDeliveriesResponse, HrpResponse, LatestDispatchedIdResponse, LocalDomainResponse,
+        MaxBodySizeResponse, MessageDeliveredResponse, MessageIdResponse, NonceResponse,
+        ProcessAuthorizationMode, ProcessAuthorizationResponse, ProcessRewardResponse,
+        ReadyResponse, RecipientAllowlistResponse, RecipientIsmResponse, RequiredHookResponse,
+        SnapshotResponse, SnapshotsResponse,
     },
+    get_and_check_limit,
     hook::{self, QuoteDispatchResponse},
-    ism,
+    ism::{self, IsmQueryMsg, VerifyResponse},
+    types::Message,
+    DEFAULT_LIMIT, MAX_LIMIT,
 };
 
 use crate::{
-    state::{CONFIG, DELIVERIES, LATEST_DISPATCHED_ID, NONCE},
+    state::{
+        Config, ALLOWED_RECIPIENTS, APPROVED_DISPATCHERS, CODE_ID_ISM, CONFIG, CONTEXT_ISMS,
+        DELIVERIES, DISPATCHED_IDS, DOMAIN_NAMES, LATEST_DISPATCHED_ID, LATEST_DISPATCHED_NONCE,
+        NONCE, PROCESS_RELAYERS, PROCESS_REWARDS, RECIPIENT_ISM_CACHE, RECIPIENT_ISM_CACHING,
+        RECIPIENT_ISM_OPTOUT, SNAPSHOTS,
+    },
     ContractError, MAILBOX_VERSION,
 };
 
-pub fn get_hrp(deps: Deps) -> Result<HrpResponse, ContractError> {
+pub fn get_hrp<C: CustomQuery>(deps: Deps<'_, C>) -> Result<HrpResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    Ok(HrpResponse { hrp: config.hrp })
+    Ok(HrpResponse {
+        hrp: config.hrp,
+        alt_hrps: config.alt_hrps,
+    })
 }
 
-pub fn get_local_domain(deps: Deps) -> Result<LocalDomainResponse, ContractError> {
+pub fn get_local_domain<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<LocalDomainResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     Ok(LocalDomainResponse {
@@ -28,7 +49,9 @@ pub fn get_local_domain(deps: Deps) -> Result<LocalDomainResponse, ContractError
     })
 }
 
-pub fn get_default_ism(deps: Deps) -> Result<DefaultIsmResponse, ContractError> {
+pub fn get_default_ism<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<DefaultIsmResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     Ok(DefaultIsmResponse {
@@ -36,60 +59,413 @@ pub fn get_default_ism(deps: Deps) -> Result<DefaultIsmResponse, ContractError>
     })
 }
 
-pub fn get_default_hook(deps: Deps) -> Result<DefaultHookResponse, ContractError> {
+pub fn get_default_hook<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<DefaultHookResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     Ok(DefaultHookResponse {
-        default_hook: config.get_default_hook().into(),
+        default_hook: config.get_default_hook()?.into(),
     })
 }
 
-pub fn get_required_hook(deps: Deps) -> Result<RequiredHookResponse, ContractError> {
+pub fn get_required_hook<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<RequiredHookResponse, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
     Ok(RequiredHookResponse {
-        required_hook: config.get_required_hook().into(),
+        required_hook: config.get_required_hook()?.into(),
+    })
+}
+
+pub fn get_max_body_size<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<MaxBodySizeResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(MaxBodySizeResponse {
+        max_body_size: config.max_body_size,
+    })
+}
+
+pub fn get_config<C: CustomQuery>(deps: Deps<'_, C>) -> Result<ConfigResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        hrp: config.hrp,
+        alt_hrps: config.alt_hrps,
+        local_domain: config.local_domain,
+        default_ism: config.default_ism,
+        default_hook: config.default_hook,
+        required_hook: config.required_hook,
+        max_body_size: config.max_body_size,
+        process_restricted: config.process_restricted,
+        recipients_restricted: config.recipients_restricted,
+        process_rewards: config.process_rewards,
+        event_body_mode: config.event_body_mode,
+        dispatched_ids_retention: config.dispatched_ids_retention,
     })
 }
 
-pub fn get_delivered(deps: Deps, id: HexBinary) -> Result<MessageDeliveredResponse, ContractError> {
+pub fn get_dispatched_ids_retention<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<DispatchedIdsRetentionResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(DispatchedIdsRetentionResponse {
+        retention: config.dispatched_ids_retention,
+    })
+}
+
+pub fn get_ready<C: CustomQuery>(deps: Deps<'_, C>) -> Result<ReadyResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ReadyResponse {
+        has_default_ism: config.default_ism.is_some(),
+        has_default_hook: config.default_hook.is_some(),
+        has_required_hook: config.required_hook.is_some(),
+        owner: hpl_ownable::get_owner(deps.storage)?,
+    })
+}
+
+pub fn get_delivered<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    id: HexBinary,
+) -> Result<MessageDeliveredResponse, ContractError> {
     let delivered = DELIVERIES.has(deps.storage, id.to_vec());
 
     Ok(MessageDeliveredResponse { delivered })
 }
 
-pub fn get_nonce(deps: Deps) -> Result<NonceResponse, ContractError> {
+pub fn get_deliveries<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    start_after: Option<HexBinary>,
+    limit: Option<u32>,
+) -> Result<ExportDeliveriesResponse, ContractError> {
+    let limit = get_and_check_limit(limit, MAX_LIMIT, DEFAULT_LIMIT)? as usize;
+
+    // keys only, so legacy records are exported as well
+    let ids = DELIVERIES
+        .keys(
+            deps.storage,
+            start_after.map(|v| Bound::exclusive(v.to_vec())),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|v| v.map(HexBinary::from))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ExportDeliveriesResponse { ids })
+}
+
+pub fn get_nonce<C: CustomQuery>(deps: Deps<'_, C>) -> Result<NonceResponse, ContractError> {
     let nonce = NONCE.load(deps.storage)?;
 
     Ok(NonceResponse { nonce })
 }
 
-pub fn get_recipient_ism(
-    deps: Deps,
+pub fn get_recipient_ism<C: CustomQuery>(
+    deps: Deps<'_, C>,
     recipient: String,
 ) -> Result<RecipientIsmResponse, ContractError> {
-    let default_ism = CONFIG.load(deps.storage)?.get_default_ism();
+    let config = CONFIG.load(deps.storage)?;
 
-    let recipient = deps.api.addr_validate(&recipient)?;
+    let recipient = config.addr_validate(deps.api, &recipient)?;
 
-    let ism = ism::recipient(&deps.querier, recipient)?.unwrap_or(default_ism);
+    let ism = match recipient_ism(deps, recipient.clone())? {
+        Some(ism) => ism,
+        None => fallback_ism(deps, &config, &recipient)?,
+    };
 
     Ok(RecipientIsmResponse { ism: ism.into() })
 }
 
-pub fn get_latest_dispatch_id(deps: Deps) -> Result<LatestDispatchedIdResponse, ContractError> {
-    let latest_dispatched_id = LATEST_DISPATCHED_ID.load(deps.storage)?.into();
+/// The recipient's own ism, the way `Process` resolves it
+fn recipient_ism<C: CustomQuery>(deps: Deps<'_, C>, recipient: Addr) -> StdResult<Option<Addr>> {
+    if RECIPIENT_ISM_OPTOUT.has(deps.storage, recipient.clone()) {
+        return Ok(None);
+    }
+
+    let cached = match RECIPIENT_ISM_CACHING.has(deps.storage, recipient.clone()) {
+        true => RECIPIENT_ISM_CACHE.may_load(deps.storage, recipient.clone())?,
+        false => None,
+    };
+
+    match cached {
+        Some(ism) => Ok(Some(ism)),
+        None => ism::recipient(&deps.querier, recipient),
+    }
+}
+
+/// Ism of a recipient without its own, the one of its code id or else the default ism.
+/// The code id is only queried while some code id has an ism
+pub(crate) fn fallback_ism<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    config: &Config,
+    recipient: &Addr,
+) -> StdResult<Addr> {
+    if !CODE_ID_ISM.is_empty(deps.storage) {
+        let code_id = deps.querier.query_wasm_contract_info(recipient)?.code_id;
+
+        if let Some(ism) = CODE_ID_ISM.may_load(deps.storage, code_id)? {
+            return Ok(ism);
+        }
+    }
+
+    Ok(config.get_default_ism())
+}
+
+pub fn get_code_id_ism<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    code_id: u64,
+) -> Result<CodeIdIsmResponse, ContractError> {
+    Ok(CodeIdIsmResponse {
+        ism: CODE_ID_ISM
+            .may_load(deps.storage, code_id)?
+            .map(String::from),
+    })
+}
+
+pub fn get_context_isms<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<ContextIsmsResponse, ContractError> {
+    let isms = CONTEXT_ISMS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|v| v.map(String::from))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ContextIsmsResponse { isms })
+}
+
+pub fn get_approved_dispatchers<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<ApprovedDispatchersResponse, ContractError> {
+    let dispatchers = APPROVED_DISPATCHERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|v| v.map(String::from))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ApprovedDispatchersResponse { dispatchers })
+}
+
+pub fn get_process_authorization<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<ProcessAuthorizationResponse, ContractError> {
+    let mode = if CONFIG.load(deps.storage)?.process_restricted {
+        let relayers = PROCESS_RELAYERS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .map(|v| v.map(String::from))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        ProcessAuthorizationMode::Restricted { relayers }
+    } else {
+        ProcessAuthorizationMode::Open
+    };
+
+    Ok(ProcessAuthorizationResponse { mode })
+}
+
+pub fn get_recipient_allowlist<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<RecipientAllowlistResponse, ContractError> {
+    let recipients = if CONFIG.load(deps.storage)?.recipients_restricted {
+        let recipients = ALLOWED_RECIPIENTS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .map(|v| v.map(String::from))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Some(recipients)
+    } else {
+        None
+    };
+
+    Ok(RecipientAllowlistResponse { recipients })
+}
+
+pub fn get_process_reward<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    recipient: String,
+) -> Result<ProcessRewardResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let recipient = config.addr_validate(deps.api, &recipient)?;
+    let process_reward = PROCESS_REWARDS.may_load(deps.storage, recipient)?;
+
+    Ok(ProcessRewardResponse {
+        enabled: config.process_rewards,
+        balance: process_reward
+            .as_ref()
+            .map(|v| v.balance)
+            .unwrap_or_default(),
+        reward: process_reward.map(|v| v.reward),
+    })
+}
+
+pub fn get_event_body_mode<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<EventBodyModeResponse, ContractError> {
+    Ok(EventBodyModeResponse {
+        mode: CONFIG.load(deps.storage)?.event_body_mode,
+    })
+}
+
+pub fn get_domain_name<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    domain: u32,
+) -> Result<DomainNameResponse, ContractError> {
+    Ok(DomainNameResponse {
+        name: DOMAIN_NAMES.may_load(deps.storage, domain)?,
+    })
+}
+
+pub fn get_domain_names<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<DomainNamesResponse, ContractError> {
+    let domains = DOMAIN_NAMES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (domain, name) = item?;
+
+            Ok(DomainName { domain, name })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DomainNamesResponse { domains })
+}
+
+pub fn verify<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    metadata: HexBinary,
+    message: HexBinary,
+) -> Result<VerifyResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let decoded_msg = Message::try_from(message.clone())?;
+    let recipient = decoded_msg.recipient_addr(&config.hrp)?;
+
+    let ism = match recipient_ism(deps, recipient.clone())? {
+        Some(ism) => ism,
+        None => fallback_ism(deps, &config, &recipient)?,
+    };
+
+    Ok(deps
+        .querier
+        .query_wasm_smart(ism, &IsmQueryMsg::Verify { metadata, message }.wrap())?)
+}
+
+pub fn get_latest_dispatch_id<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<LatestDispatchedIdResponse, ContractError> {
+    let latest_dispatched_id = LATEST_DISPATCHED_ID.load(deps.storage)?;
+    // mailboxes dispatching before the nonce was tracked only have it as `NONCE - 1`
+    let latest_dispatched_nonce = match LATEST_DISPATCHED_NONCE.may_load(deps.storage)? {
+        Some(nonce) => nonce,
+        None => NONCE.load(deps.storage)?.saturating_sub(1),
+    };
 
     Ok(LatestDispatchedIdResponse {
         message_id: latest_dispatched_id,
+        nonce: latest_dispatched_nonce,
+    })
+}
+
+/// Fails unless the sender and recipient are 32 bytes, like in messages `Process` accepts
+pub fn get_message_id(message: Message) -> Result<MessageIdResponse, ContractError> {
+    for addr in [&message.sender, &message.recipient] {
+        ensure!(
+            addr.len() == 32,
+            HplError::InvalidAddressLength { len: addr.len() }
+        );
+    }
+
+    Ok(MessageIdResponse {
+        message_id: message.id(),
+    })
+}
+
+pub fn get_dispatched_id_at_nonce<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    nonce: u32,
+) -> Result<DispatchedIdAtNonceResponse, ContractError> {
+    let message_id = DISPATCHED_IDS.may_load(deps.storage, nonce)?;
+
+    Ok(DispatchedIdAtNonceResponse {
+        message_id: message_id.map(HexBinary::from),
     })
 }
 
-pub fn quote_dispatch(
-    deps: Deps,
+pub fn get_dispatched_ids<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> Result<DispatchedIdsResponse, ContractError> {
+    let limit = get_and_check_limit(limit, MAX_LIMIT, DEFAULT_LIMIT)? as usize;
+
+    let ids = DISPATCHED_IDS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (nonce, message_id) = item?;
+
+            Ok(DispatchedId {
+                nonce,
+                message_id: message_id.into(),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DispatchedIdsResponse { ids })
+}
+
+pub fn get_snapshot<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    id: u64,
+) -> Result<SnapshotResponse, ContractError> {
+    Ok(SnapshotResponse {
+        id,
+        snapshot: SNAPSHOTS.load(deps.storage, id)?,
+    })
+}
+
+pub fn get_snapshots<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<SnapshotsResponse, ContractError> {
+    let limit = get_and_check_limit(limit, MAX_LIMIT, DEFAULT_LIMIT)? as usize;
+
+    let snapshots = SNAPSHOTS
+        .range(
+            deps.storage,
+            start_after.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (id, snapshot) = item?;
+
+            Ok(SnapshotResponse { id, snapshot })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(SnapshotsResponse { snapshots })
+}
+
+pub fn quote_dispatch<C: CustomQuery>(
+    deps: Deps<'_, C>,
     sender: String,
     dispatch_msg: DispatchMsg,
 ) -> Result<QuoteDispatchResponse, ContractError> {
+    // quotes don't check approval, the override only changes the message sender
+    let sender = dispatch_msg.sender_override.clone().unwrap_or(sender);
     let sender = deps.api.addr_validate(&sender)?;
 
     let config = CONFIG.load(deps.storage)?;
@@ -99,8 +475,8 @@ pub fn quote_dispatch(
         .clone()
         .to_msg(MAILBOX_VERSION, nonce, config.local_domain, sender)?;
 
-    let default_hook = config.get_default_hook();
-    let required_hook = config.get_required_hook();
+    let default_hook = config.get_default_hook()?;
+    let required_hook = config.get_required_hook()?;
 
     let base_fee = hook::quote_dispatch(
         &deps.querier,
@@ -135,24 +511,39 @@ pub fn quote_dispatch(
 mod test {
 
     use cosmwasm_std::{
-        from_json,
+        coin, from_json,
         testing::{mock_dependencies, mock_env},
-        Addr,
+        to_json_binary, Addr, Coin, ContractResult, SystemResult, WasmQuery,
+    };
+    use hpl_interface::{
+        core::mailbox::{
+            EventBodyMode, MailboxHookQueryMsg, MailboxQueryMsg, MultiQueryResponse, QueryMsg,
+        },
+        hook::{ExpectedHookQueryMsg, HookQueryMsg},
+        ism::{
+            ExpectedIsmQueryMsg, ExpectedIsmSpecifierQueryMsg, InterchainSecurityModuleResponse,
+        },
+        types::bech32_encode,
     };
-    use hpl_interface::core::mailbox::MailboxQueryMsg;
     use ibcx_test_utils::{gen_addr, gen_bz, hex};
     use rstest::rstest;
     use serde::de::DeserializeOwned;
 
     use crate::{
         contract,
-        state::{Config, Delivery},
+        state::{Config, ProcessReward},
+        MAX_MULTI_QUERY_SIZE,
     };
 
     use super::*;
 
     fn query<T: DeserializeOwned>(deps: Deps, req: MailboxQueryMsg) -> T {
-        from_json(contract::query(deps, mock_env(), req.wrap()).unwrap()).unwrap()
+        from_json(
+            contract::query(deps, mock_env(), req.wrap())
+                .map_err(|e| e.to_string())
+                .unwrap(),
+        )
+        .unwrap()
     }
 
     fn query_hrp(deps: Deps) -> HrpResponse {
@@ -175,6 +566,14 @@ mod test {
         query(deps, MailboxQueryMsg::RequiredHook {})
     }
 
+    fn query_max_body_size(deps: Deps) -> MaxBodySizeResponse {
+        query(deps, MailboxQueryMsg::MaxBodySize {})
+    }
+
+    fn query_config(deps: Deps) -> ConfigResponse {
+        query(deps, MailboxQueryMsg::Config {})
+    }
+
     fn query_delivered(deps: Deps, id: HexBinary) -> MessageDeliveredResponse {
         query(deps, MailboxQueryMsg::MessageDelivered { id })
     }
@@ -209,6 +608,7 @@ mod test {
                     default_hook: default_hook.clone(),
                     default_ism: default_ism.clone(),
                     required_hook: required_hook.clone(),
+                    max_body_size: Some(1024),
                     ..Config::new("hrp", 123)
                 },
             )
@@ -226,16 +626,61 @@ mod test {
 
         let required_hook_res = query_required_hook(deps.as_ref()).required_hook;
 
+        let max_body_size_res = query_max_body_size(deps.as_ref()).max_body_size;
+
+        let config_res = query_config(deps.as_ref());
+
         let nonce_res = query_nonce(deps.as_ref()).nonce;
 
         assert_eq!(hrp_res, "hrp");
         assert_eq!(local_domain_res, 123);
+        assert_eq!(config_res.default_ism, default_ism);
+        assert_eq!(config_res.max_body_size, Some(1024));
         assert_eq!(default_hook_res, default_hook.unwrap());
         assert_eq!(default_ism_res, default_ism.unwrap());
         assert_eq!(required_hook_res, required_hook.unwrap());
+        assert_eq!(max_body_size_res, Some(1024));
         assert_eq!(nonce_res, 7);
     }
 
+    #[rstest]
+    #[case(None, None, None)]
+    #[case(Some(gen_addr("osmo")), None, None)]
+    #[case(None, Some(gen_addr("osmo")), Some(gen_addr("osmo")))]
+    fn test_query_ready(
+        #[case] default_ism: Option<Addr>,
+        #[case] default_hook: Option<Addr>,
+        #[case] required_hook: Option<Addr>,
+    ) {
+        let mut deps = mock_dependencies();
+        let owner = gen_addr("osmo");
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config {
+                    default_ism: default_ism.clone(),
+                    default_hook: default_hook.clone(),
+                    required_hook: required_hook.clone(),
+                    ..Config::new("osmo", 123)
+                },
+            )
+            .unwrap();
+        hpl_ownable::initialize(deps.as_mut().storage, &owner).unwrap();
+
+        let res: ReadyResponse = query(deps.as_ref(), MailboxQueryMsg::Ready {});
+
+        assert_eq!(
+            res,
+            ReadyResponse {
+                has_default_ism: default_ism.is_some(),
+                has_default_hook: default_hook.is_some(),
+                has_required_hook: required_hook.is_some(),
+                owner,
+            }
+        );
+    }
+
     #[rstest]
     #[case(None, false)]
     #[case(Some(hex("beef")), true)]
@@ -245,14 +690,7 @@ mod test {
 
         if let Some(id) = message_id {
             DELIVERIES
-                .save(
-                    deps.as_mut().storage,
-                    id.to_vec(),
-                    &Delivery {
-                        sender: Addr::unchecked("sender"),
-                        block_number: 123,
-                    },
-                )
+                .save(deps.as_mut().storage, id.to_vec(), &())
                 .unwrap();
         }
 
@@ -266,14 +704,523 @@ mod test {
     fn test_query_latest_dispatched_id() {
         let mut deps = mock_dependencies();
 
-        let rand_id = gen_bz(32).to_vec();
+        let rand_id = gen_bz(32);
+
+        LATEST_DISPATCHED_ID
+            .save(deps.as_mut().storage, &rand_id)
+            .unwrap();
+        LATEST_DISPATCHED_NONCE
+            .save(deps.as_mut().storage, &3u32)
+            .unwrap();
+
+        let res: LatestDispatchedIdResponse =
+            query(deps.as_ref(), MailboxQueryMsg::LatestDispatchId {});
+        assert_eq!(res.message_id, rand_id);
+        assert_eq!(res.nonce, 3);
+    }
+
+    #[rstest]
+    fn test_query_latest_dispatched_id_untracked_nonce() {
+        let mut deps = mock_dependencies();
+
+        let rand_id = gen_bz(32);
 
         LATEST_DISPATCHED_ID
             .save(deps.as_mut().storage, &rand_id)
             .unwrap();
+        NONCE.save(deps.as_mut().storage, &4u32).unwrap();
 
         let res: LatestDispatchedIdResponse =
             query(deps.as_ref(), MailboxQueryMsg::LatestDispatchId {});
         assert_eq!(res.message_id, rand_id);
+        assert_eq!(res.nonce, 3);
+    }
+
+    #[rstest]
+    #[case(32, 32)]
+    #[should_panic(expected = "invalid address length: 20")]
+    #[case(20, 32)]
+    #[should_panic(expected = "invalid address length: 20")]
+    #[case(32, 20)]
+    fn test_query_message_id(#[case] sender_len: usize, #[case] recipient_len: usize) {
+        let deps = mock_dependencies();
+
+        let msg = Message {
+            version: crate::MAILBOX_VERSION,
+            nonce: 3,
+            origin_domain: 26657,
+            sender: gen_bz(sender_len),
+            dest_domain: 1,
+            recipient: gen_bz(recipient_len),
+            body: gen_bz(123),
+        };
+
+        let res = contract::query(
+            deps.as_ref(),
+            mock_env(),
+            MailboxQueryMsg::MessageId {
+                version: msg.version,
+                nonce: msg.nonce,
+                origin_domain: msg.origin_domain,
+                sender: msg.sender.clone(),
+                dest_domain: msg.dest_domain,
+                recipient: msg.recipient.clone(),
+                body: msg.body.clone(),
+            }
+            .wrap(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let res: MessageIdResponse = from_json(res).unwrap();
+        assert_eq!(res.message_id, msg.id());
+    }
+
+    #[rstest]
+    fn test_query_process_authorization() {
+        let mut deps = mock_dependencies();
+
+        CONFIG
+            .save(deps.as_mut().storage, &Config::new("osmo", 123))
+            .unwrap();
+
+        let res: ProcessAuthorizationResponse =
+            query(deps.as_ref(), MailboxQueryMsg::ProcessAuthorization {});
+        assert_eq!(res.mode, ProcessAuthorizationMode::Open);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config {
+                    process_restricted: true,
+                    ..Config::new("osmo", 123)
+                },
+            )
+            .unwrap();
+        for relayer in ["relayer_b", "relayer_a"] {
+            PROCESS_RELAYERS
+                .save(deps.as_mut().storage, Addr::unchecked(relayer), &())
+                .unwrap();
+        }
+
+        let res: ProcessAuthorizationResponse =
+            query(deps.as_ref(), MailboxQueryMsg::ProcessAuthorization {});
+        assert_eq!(
+            res.mode,
+            ProcessAuthorizationMode::Restricted {
+                relayers: vec!["relayer_a".to_string(), "relayer_b".to_string()]
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_query_recipient_allowlist() {
+        let mut deps = mock_dependencies();
+
+        CONFIG
+            .save(deps.as_mut().storage, &Config::new("osmo", 123))
+            .unwrap();
+
+        let res: RecipientAllowlistResponse =
+            query(deps.as_ref(), MailboxQueryMsg::RecipientAllowlist {});
+        assert_eq!(res.recipients, None);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config {
+                    recipients_restricted: true,
+                    ..Config::new("osmo", 123)
+                },
+            )
+            .unwrap();
+        for recipient in ["recipient_b", "recipient_a"] {
+            ALLOWED_RECIPIENTS
+                .save(deps.as_mut().storage, Addr::unchecked(recipient), &())
+                .unwrap();
+        }
+
+        let res: RecipientAllowlistResponse =
+            query(deps.as_ref(), MailboxQueryMsg::RecipientAllowlist {});
+        assert_eq!(
+            res.recipients,
+            Some(vec!["recipient_a".to_string(), "recipient_b".to_string()])
+        );
+    }
+
+    #[rstest]
+    fn test_query_process_reward() {
+        let mut deps = mock_dependencies();
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config {
+                    process_rewards: true,
+                    ..Config::new("osmo", 123)
+                },
+            )
+            .unwrap();
+
+        let query_reward = |deps: Deps| -> ProcessRewardResponse {
+            query(
+                deps,
+                MailboxQueryMsg::ProcessReward {
+                    recipient: "recipient".to_string(),
+                },
+            )
+        };
+
+        let res = query_reward(deps.as_ref());
+        assert!(res.enabled);
+        assert_eq!(res.reward, None);
+        assert!(res.balance.is_zero());
+
+        PROCESS_REWARDS
+            .save(
+                deps.as_mut().storage,
+                Addr::unchecked("recipient"),
+                &ProcessReward {
+                    reward: coin(10, "uosmo"),
+                    balance: 25u128.into(),
+                },
+            )
+            .unwrap();
+
+        let res = query_reward(deps.as_ref());
+        assert_eq!(res.reward, Some(coin(10, "uosmo")));
+        assert_eq!(res.balance.u128(), 25);
+    }
+
+    #[rstest]
+    fn test_query_event_body_mode() {
+        let mut deps = mock_dependencies();
+
+        // configs saved before the mode existed default to `Full`
+        CONFIG
+            .save(deps.as_mut().storage, &Config::new("osmo", 123))
+            .unwrap();
+
+        let res: EventBodyModeResponse = query(deps.as_ref(), MailboxQueryMsg::EventBodyMode {});
+        assert_eq!(res.mode, EventBodyMode::Full);
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config {
+                    event_body_mode: EventBodyMode::HashOnly,
+                    ..Config::new("osmo", 123)
+                },
+            )
+            .unwrap();
+
+        let res: EventBodyModeResponse = query(deps.as_ref(), MailboxQueryMsg::EventBodyMode {});
+        assert_eq!(res.mode, EventBodyMode::HashOnly);
+    }
+
+    #[rstest]
+    fn test_query_code_id_ism() {
+        let mut deps = mock_dependencies();
+
+        CODE_ID_ISM
+            .save(deps.as_mut().storage, 1, &Addr::unchecked("code_id_ism"))
+            .unwrap();
+
+        let res: CodeIdIsmResponse =
+            query(deps.as_ref(), MailboxQueryMsg::CodeIdIsm { code_id: 1 });
+        assert_eq!(res.ism, Some("code_id_ism".to_string()));
+
+        let res: CodeIdIsmResponse =
+            query(deps.as_ref(), MailboxQueryMsg::CodeIdIsm { code_id: 2 });
+        assert_eq!(res.ism, None);
+    }
+
+    #[rstest]
+    fn test_query_domain_names() {
+        let mut deps = mock_dependencies();
+
+        for (domain, name) in [(2, "neutron"), (1, "osmosis")] {
+            DOMAIN_NAMES
+                .save(deps.as_mut().storage, domain, &name.to_string())
+                .unwrap();
+        }
+
+        let res: DomainNameResponse =
+            query(deps.as_ref(), MailboxQueryMsg::DomainName { domain: 1 });
+        assert_eq!(res.name, Some("osmosis".to_string()));
+
+        // unknown domains have no name
+        let res: DomainNameResponse =
+            query(deps.as_ref(), MailboxQueryMsg::DomainName { domain: 3 });
+        assert_eq!(res.name, None);
+
+        let res: DomainNamesResponse = query(deps.as_ref(), MailboxQueryMsg::DomainNames {});
+        assert_eq!(
+            res.domains,
+            vec![
+                DomainName {
+                    domain: 1,
+                    name: "osmosis".to_string()
+                },
+                DomainName {
+                    domain: 2,
+                    name: "neutron".to_string()
+                },
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(None, "default_ism", true)]
+    #[case(None, "custom_ism", false)]
+    #[case(Some("custom_ism"), "custom_ism", true)]
+    #[case(Some("custom_ism"), "default_ism", false)]
+    fn test_query_verify(
+        #[case] recipient_ism: Option<&'static str>,
+        #[case] metadata: &str,
+        #[case] verified: bool,
+    ) {
+        let mut deps = mock_dependencies();
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", 123).with_ism(Addr::unchecked("default_ism")),
+            )
+            .unwrap();
+
+        let message = Message {
+            version: MAILBOX_VERSION,
+            nonce: 0,
+            origin_domain: 1,
+            sender: gen_bz(32),
+            dest_domain: 123,
+            recipient: gen_bz(32),
+            body: gen_bz(12),
+        };
+        let recipient = bech32_encode("osmo", &message.recipient).unwrap();
+
+        // each mocked ism only accepts its own address as metadata
+        deps.querier.update_wasm(move |req| {
+            let (contract_addr, msg) = match req {
+                WasmQuery::Smart { contract_addr, msg } => (contract_addr, msg),
+                _ => unreachable!("wrong query type"),
+            };
+
+            let res = if contract_addr == recipient.as_str() {
+                let ExpectedIsmSpecifierQueryMsg::IsmSpecifier(_) = from_json(msg).unwrap();
+
+                to_json_binary(&InterchainSecurityModuleResponse {
+                    ism: recipient_ism.map(Addr::unchecked),
+                })
+            } else {
+                let ExpectedIsmQueryMsg::Ism(IsmQueryMsg::Verify { metadata, .. }) =
+                    from_json(msg).unwrap()
+                else {
+                    unreachable!("wrong query type")
+                };
+
+                to_json_binary(&VerifyResponse {
+                    verified: metadata.as_slice() == contract_addr.as_bytes(),
+                })
+            };
+
+            SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+        });
+
+        let res: VerifyResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::Verify {
+                metadata: metadata.as_bytes().into(),
+                message: message.into(),
+            },
+        );
+        assert_eq!(res.verified, verified);
+    }
+
+    #[rstest]
+    #[case(
+        None,
+        vec![coin(100, "uosmo")],
+        vec![coin(50, "uatom")],
+        vec![coin(50, "uatom"), coin(100, "uosmo")]
+    )]
+    #[case(
+        None,
+        vec![coin(100, "uosmo")],
+        vec![coin(50, "uosmo")],
+        vec![coin(150, "uosmo")]
+    )]
+    #[case(None, vec![], vec![coin(50, "uosmo")], vec![coin(50, "uosmo")])]
+    // the override hook replaces the default hook, the required hook always runs
+    #[case(
+        Some("custom_hook"),
+        vec![coin(100, "uosmo")],
+        vec![coin(50, "uosmo")],
+        vec![coin(60, "uosmo")]
+    )]
+    fn test_query_quote_dispatch(
+        #[case] hook: Option<&str>,
+        #[case] default_fees: Vec<Coin>,
+        #[case] required_fees: Vec<Coin>,
+        #[case] expected: Vec<Coin>,
+    ) {
+        let mut deps = mock_dependencies();
+
+        CONFIG
+            .save(
+                deps.as_mut().storage,
+                &Config::new("osmo", 123).with_hook(
+                    Addr::unchecked("default_hook"),
+                    Addr::unchecked("required_hook"),
+                ),
+            )
+            .unwrap();
+        NONCE.save(deps.as_mut().storage, &0u32).unwrap();
+
+        // each mocked hook quotes its own fees
+        deps.querier.update_wasm(move |req| {
+            let WasmQuery::Smart { contract_addr, msg } = req else {
+                unreachable!("wrong query type")
+            };
+            let ExpectedHookQueryMsg::Hook(HookQueryMsg::QuoteDispatch(_)) =
+                from_json(msg).unwrap()
+            else {
+                unreachable!("wrong query type")
+            };
+
+            let fees = match contract_addr.as_str() {
+                "default_hook" => default_fees.clone(),
+                "required_hook" => required_fees.clone(),
+                "custom_hook" => vec![coin(10, "uosmo")],
+                _ => unreachable!("unknown hook"),
+            };
+
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&QuoteDispatchResponse { fees }).unwrap(),
+            ))
+        });
+
+        let mut msg = DispatchMsg::new(1, gen_bz(32), gen_bz(12));
+        if let Some(hook) = hook {
+            msg = msg.with_hook(hook);
+        }
+
+        let res: QuoteDispatchResponse = from_json(
+            contract::query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Hook(MailboxHookQueryMsg::QuoteDispatch {
+                    sender: gen_addr("osmo").to_string(),
+                    msg,
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.fees, expected);
+    }
+
+    #[rstest]
+    #[case(None, Some("required_hook"), "[E118] default_hook not set")]
+    #[case(Some("default_hook"), None, "[E118] required_hook not set")]
+    fn test_query_quote_dispatch_hook_not_set(
+        #[case] default_hook: Option<&str>,
+        #[case] required_hook: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let mut deps = mock_dependencies();
+
+        let config = Config {
+            default_hook: default_hook.map(Addr::unchecked),
+            required_hook: required_hook.map(Addr::unchecked),
+            ..Config::new("osmo", 123)
+        };
+        CONFIG.save(deps.as_mut().storage, &config).unwrap();
+        NONCE.save(deps.as_mut().storage, &0u32).unwrap();
+
+        let err = contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Hook(MailboxHookQueryMsg::QuoteDispatch {
+                sender: gen_addr("osmo").to_string(),
+                msg: DispatchMsg::new(1, gen_bz(32), gen_bz(12)),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[rstest]
+    fn test_query_multi() {
+        let mut deps = mock_dependencies();
+
+        CONFIG
+            .save(deps.as_mut().storage, &Config::new("osmo", 123))
+            .unwrap();
+
+        let delivered_id = gen_bz(32);
+        DELIVERIES
+            .save(deps.as_mut().storage, delivered_id.to_vec(), &())
+            .unwrap();
+
+        // nonce is never initialized, so the query fails
+        let res: MultiQueryResponse = query(
+            deps.as_ref(),
+            MailboxQueryMsg::MultiQuery {
+                queries: vec![
+                    MailboxQueryMsg::Hrp {}.into(),
+                    MailboxQueryMsg::Nonce {}.into(),
+                    MailboxQueryMsg::MessageDelivered { id: delivered_id }.into(),
+                    MailboxQueryMsg::MultiQuery { queries: vec![] }.into(),
+                    MailboxQueryMsg::MessageDelivered { id: gen_bz(32) }.into(),
+                ],
+            },
+        );
+        assert_eq!(res.results.len(), 5);
+
+        let hrp: HrpResponse = from_json(res.results[0].as_ref().unwrap()).unwrap();
+        assert_eq!(hrp.hrp, "osmo");
+
+        assert!(res.results[1].as_ref().unwrap_err().contains("not found"));
+
+        let delivered: MessageDeliveredResponse =
+            from_json(res.results[2].as_ref().unwrap()).unwrap();
+        assert!(delivered.delivered);
+
+        assert_eq!(
+            res.results[3],
+            Err(ContractError::NestedMultiQuery {}.to_string())
+        );
+
+        let delivered: MessageDeliveredResponse =
+            from_json(res.results[4].as_ref().unwrap()).unwrap();
+        assert!(!delivered.delivered);
+    }
+
+    #[rstest]
+    #[case(MAX_MULTI_QUERY_SIZE)]
+    #[should_panic(expected = "too many queries: 31 > 30")]
+    #[case(MAX_MULTI_QUERY_SIZE + 1)]
+    fn test_query_multi_size(#[case] size: usize) {
+        let mut deps = mock_dependencies();
+
+        CONFIG
+            .save(deps.as_mut().storage, &Config::new("osmo", 123))
+            .unwrap();
+
+        let res = contract::query(
+            deps.as_ref(),
+            mock_env(),
+            MailboxQueryMsg::MultiQuery {
+                queries: vec![MailboxQueryMsg::Hrp {}.into(); size],
+            }
+            .wrap(),
+        )
+        .map_err(|v| v.to_string())
+        .unwrap();
+
+        let res: MultiQueryResponse = from_json(res).unwrap();
+        assert_eq!(res.results.len(), size);
     }
 }
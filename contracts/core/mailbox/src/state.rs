@@ -1,15 +1,43 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Api, Binary, Coin, HexBinary, StdResult, Uint128};
 use cw_storage_plus::{Item, Map};
+use hpl_interface::{
+    core::mailbox::EventBodyMode,
+    types::{bech32_decode_with_hrp, bech32_encode_with_len},
+};
+use hpl_reentrancy::ReentrancyGuard;
+
+use crate::error::ContractError;
 
 #[cw_serde]
 #[derive(Default)]
 pub struct Config {
+    /// Prefix of the addresses the mailbox encodes
     pub hrp: String,
+    /// Prefixes accepted besides `hrp` when validating addresses, see `SetAltHrps`
+    #[serde(default)]
+    pub alt_hrps: Vec<String>,
     pub local_domain: u32,
     pub default_ism: Option<Addr>,
     pub default_hook: Option<Addr>,
     pub required_hook: Option<Addr>,
+    /// `None` disables the dispatch body size check
+    pub max_body_size: Option<u64>,
+    /// Only `PROCESS_RELAYERS` can call `Process`, see `SetProcessAuthorization`
+    #[serde(default)]
+    pub process_restricted: bool,
+    /// Only `ALLOWED_RECIPIENTS` receive messages from `Process`, see `SetRecipientAllowlist`
+    #[serde(default)]
+    pub recipients_restricted: bool,
+    /// `Process` pays the relayer the reward set by the recipient, see `SetProcessRewardsEnabled`
+    #[serde(default)]
+    pub process_rewards: bool,
+    /// What the dispatch event carries of the message body, see `SetEventBodyMode`
+    #[serde(default)]
+    pub event_body_mode: EventBodyMode,
+    /// Number of latest `DISPATCHED_IDS` kept by `dispatch`, see `SetDispatchedIdsRetention`
+    #[serde(default)]
+    pub dispatched_ids_retention: Option<u32>,
 }
 
 #[allow(dead_code)]
@@ -33,19 +61,41 @@ impl Config {
         self
     }
 
+    /// Validates `addr`, converting addresses under an alternate hrp to the primary one
+    pub fn addr_validate(&self, api: &dyn Api, addr: &str) -> StdResult<Addr> {
+        match bech32_decode_with_hrp(addr) {
+            Ok((hrp, raw_addr)) if self.alt_hrps.contains(&hrp) => {
+                bech32_encode_with_len(&self.hrp, &raw_addr, raw_addr.len())
+            }
+            _ => api.addr_validate(addr),
+        }
+    }
+
     pub fn get_default_ism(&self) -> Addr {
         self.default_ism.clone().expect("default_ism not set")
     }
 
-    pub fn get_default_hook(&self) -> Addr {
-        self.default_hook.clone().expect("default_hook not set")
+    pub fn get_default_hook(&self) -> Result<Addr, ContractError> {
+        self.default_hook.clone().ok_or(ContractError::HookNotSet {
+            hook: "default_hook".to_string(),
+        })
     }
 
-    pub fn get_required_hook(&self) -> Addr {
-        self.required_hook.clone().expect("required_hook not set")
+    pub fn get_required_hook(&self) -> Result<Addr, ContractError> {
+        self.required_hook.clone().ok_or(ContractError::HookNotSet {
+            hook: "required_hook".to_string(),
+        })
     }
 }
 
+/// Reward a recipient pays the relayer per delivered message, out of `balance`
+#[cw_serde]
+pub struct ProcessReward {
+    pub reward: Coin,
+    pub balance: Uint128,
+}
+
+/// Legacy delivery record, replaced by unit values in `DELIVERIES`
 #[cw_serde]
 pub struct Delivery {
     pub sender: Addr,
@@ -59,7 +109,85 @@ pub const NONCE_KEY: &str = "nonce";
 pub const NONCE: Item<u32> = Item::new(NONCE_KEY);
 
 pub const LATEST_DISPATCHED_ID_KEY: &str = "latest_dispatched_id";
-pub const LATEST_DISPATCHED_ID: Item<Vec<u8>> = Item::new(LATEST_DISPATCHED_ID_KEY);
+pub const LATEST_DISPATCHED_ID: Item<HexBinary> = Item::new(LATEST_DISPATCHED_ID_KEY);
+
+/// Reads `LATEST_DISPATCHED_ID` as written before it was stored as hex
+pub const LEGACY_LATEST_DISPATCHED_ID: Item<Vec<u8>> = Item::new(LATEST_DISPATCHED_ID_KEY);
+
+pub const LATEST_DISPATCHED_NONCE_KEY: &str = "latest_dispatched_nonce";
+pub const LATEST_DISPATCHED_NONCE: Item<u32> = Item::new(LATEST_DISPATCHED_NONCE_KEY);
+
+pub const DISPATCHED_IDS_PREFIX: &str = "dispatched_ids";
+pub const DISPATCHED_IDS: Map<u32, Vec<u8>> = Map::new(DISPATCHED_IDS_PREFIX);
+
+/// Every `DISPATCHED_IDS` entry below this nonce was removed by `PruneDispatchedIds`
+pub const DISPATCHED_IDS_PRUNE_CURSOR_KEY: &str = "dispatched_ids_prune_cursor";
+pub const DISPATCHED_IDS_PRUNE_CURSOR: Item<u32> = Item::new(DISPATCHED_IDS_PRUNE_CURSOR_KEY);
 
 pub const DELIVERIES_PREFIX: &str = "deliveries";
-pub const DELIVERIES: Map<Vec<u8>, Delivery> = Map::new(DELIVERIES_PREFIX);
+pub const DELIVERIES: Map<Vec<u8>, ()> = Map::new(DELIVERIES_PREFIX);
+
+/// Reads both legacy `Delivery` records and migrated unit values (as `None`)
+pub const LEGACY_DELIVERIES: Map<Vec<u8>, Option<Delivery>> = Map::new(DELIVERIES_PREFIX);
+
+pub const DELIVERIES_MIGRATION_CURSOR_KEY: &str = "deliveries_migration_cursor";
+pub const DELIVERIES_MIGRATION_CURSOR: Item<Vec<u8>> = Item::new(DELIVERIES_MIGRATION_CURSOR_KEY);
+
+/// Value escrowed by `Dispatch` per message id, sent to the recipient by `Process`
+pub const MESSAGE_VALUES_PREFIX: &str = "message_values";
+pub const MESSAGE_VALUES: Map<Vec<u8>, Coin> = Map::new(MESSAGE_VALUES_PREFIX);
+
+/// Isms verified with the relayer as context, see `SetContextIsm`
+pub const CONTEXT_ISMS_PREFIX: &str = "context_isms";
+pub const CONTEXT_ISMS: Map<Addr, ()> = Map::new(CONTEXT_ISMS_PREFIX);
+
+/// Dispatchers allowed to set `DispatchMsg::sender_override`
+pub const APPROVED_DISPATCHERS_PREFIX: &str = "approved_dispatchers";
+pub const APPROVED_DISPATCHERS: Map<Addr, ()> = Map::new(APPROVED_DISPATCHERS_PREFIX);
+
+/// Relayers allowed to call `Process` while it's restricted
+pub const PROCESS_RELAYERS_PREFIX: &str = "process_relayers";
+pub const PROCESS_RELAYERS: Map<Addr, ()> = Map::new(PROCESS_RELAYERS_PREFIX);
+
+/// Recipients `Process` delivers to while recipients are restricted
+pub const ALLOWED_RECIPIENTS_PREFIX: &str = "allowed_recipients";
+pub const ALLOWED_RECIPIENTS: Map<Addr, ()> = Map::new(ALLOWED_RECIPIENTS_PREFIX);
+
+/// Process rewards by recipient, see `SetProcessReward`
+pub const PROCESS_REWARDS_PREFIX: &str = "process_rewards";
+pub const PROCESS_REWARDS: Map<Addr, ProcessReward> = Map::new(PROCESS_REWARDS_PREFIX);
+
+/// Chain names of domains, only informational for clients
+pub const DOMAIN_NAMES_PREFIX: &str = "domain_names";
+pub const DOMAIN_NAMES: Map<u32, String> = Map::new(DOMAIN_NAMES_PREFIX);
+
+/// Isms specified by recipients with `RECIPIENT_ISM_CACHING`, read by `Process` before
+/// querying the recipient. Recipients without their own ism aren't cached,
+/// so they follow the default ism
+pub const RECIPIENT_ISM_CACHE_PREFIX: &str = "recipient_ism_cache";
+pub const RECIPIENT_ISM_CACHE: Map<Addr, Addr> = Map::new(RECIPIENT_ISM_CACHE_PREFIX);
+
+/// Recipients whose ism is cached, see `SetIsmCaching`. Others are queried on every `Process`
+pub const RECIPIENT_ISM_CACHING_PREFIX: &str = "recipient_ism_caching";
+pub const RECIPIENT_ISM_CACHING: Map<Addr, ()> = Map::new(RECIPIENT_ISM_CACHING_PREFIX);
+
+/// Recipients that follow the default ism without being queried, see `SetIsmLookupOptOut`
+pub const RECIPIENT_ISM_OPTOUT_PREFIX: &str = "recipient_ism_optout";
+pub const RECIPIENT_ISM_OPTOUT: Map<Addr, ()> = Map::new(RECIPIENT_ISM_OPTOUT_PREFIX);
+
+/// Isms of recipients by code id, between their own ism and the default one.
+/// See `SetCodeIdIsm`
+pub const CODE_ID_ISM_PREFIX: &str = "code_id_ism";
+pub const CODE_ID_ISM: Map<u64, Addr> = Map::new(CODE_ID_ISM_PREFIX);
+
+/// Id of the latest `SnapshotConfig`, ids start at 1
+pub const SNAPSHOT_ID_KEY: &str = "snapshot_id";
+pub const SNAPSHOT_ID: Item<u64> = Item::new(SNAPSHOT_ID_KEY);
+
+/// Serialized `ConfigSnapshot`s by id, pruned down to the latest `MAX_SNAPSHOTS`
+pub const SNAPSHOTS_PREFIX: &str = "snapshots";
+pub const SNAPSHOTS: Map<u64, Binary> = Map::new(SNAPSHOTS_PREFIX);
+
+/// Held while a recipient `Handle` is in flight, cleared on its reply
+pub const PROCESS_GUARD_KEY: &str = "process_guard";
+pub const PROCESS_GUARD: ReentrancyGuard = ReentrancyGuard::new(PROCESS_GUARD_KEY);
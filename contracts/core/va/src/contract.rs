@@ -1,8 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, ensure_eq, Deps, DepsMut, Empty, Env, Event, HexBinary, MessageInfo, Order,
-    QueryResponse, Response, StdResult,
+    ensure, ensure_eq, to_json_binary, Api, Deps, DepsMut, Empty, Env, Event, HexBinary,
+    MessageInfo, Order, QueryResponse, Response, StdResult, Storage,
 };
 
 use hpl_interface::{
@@ -10,11 +10,16 @@ use hpl_interface::{
         mailbox::{self, MailboxQueryMsg},
         va::{
             ExecuteMsg, GetAnnounceStorageLocationsResponse, GetAnnouncedValidatorsResponse,
-            InstantiateMsg, LocalDomainResponse, MailboxResponse, QueryMsg,
+            InstantiateMsg, InstantiateResponse, LocalDomainResponse, MailboxResponse, QueryMsg,
+            SignatureFormat,
         },
     },
+    digest::{
+        announcement_digest, announcement_typed_data_hash, eth_signed_message_hash,
+        unannouncement_digest,
+    },
     to_binary,
-    types::{bech32_decode, eth_addr, eth_hash, keccak256_hash},
+    types::{bech32_decode, eth_addr, keccak256_hash, pad32},
 };
 
 use crate::{
@@ -32,6 +37,7 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let owner = deps.api.addr_validate(&msg.owner)?;
     let mailbox = deps.api.addr_validate(&msg.mailbox)?;
     let mailbox_addr = bech32_decode(mailbox.as_str())?;
 
@@ -43,21 +49,30 @@ pub fn instantiate(
         )?
         .local_domain;
 
+    hpl_ownable::initialize(deps.storage, &owner)?;
+
     MAILBOX.save(deps.storage, &mailbox_addr)?;
     LOCAL_DOMAIN.save(deps.storage, &local_domain)?;
 
-    Ok(Response::new().add_event(
-        Event::new("init-validator-announce")
-            .add_attribute("creator", info.sender)
-            .add_attribute("mailbox", msg.mailbox)
-            .add_attribute("local-domain", local_domain.to_string()),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox,
+        })?)
+        .add_event(
+            Event::new("init-validator-announce")
+                .add_attribute("creator", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("mailbox", msg.mailbox)
+                .add_attribute("local-domain", local_domain.to_string()),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -66,12 +81,30 @@ pub fn execute(
             validator,
             storage_location,
             signature,
-        } => announce(deps, info, validator, storage_location, signature),
+            format,
+        } => announce(
+            deps,
+            info,
+            validator,
+            storage_location,
+            signature,
+            format.unwrap_or_default(),
+        ),
+        ExecuteMsg::Unannounce {
+            validator,
+            storage_location,
+            signature,
+        } => unannounce(deps, info, validator, storage_location, signature),
+        ExecuteMsg::ForceUnannounce {
+            validator,
+            storage_location,
+        } => force_unannounce(deps, info, validator, storage_location),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+#[hpl_ownable::route_ownable]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     match msg {
         QueryMsg::GetAnnounceStorageLocations { validators } => {
             to_binary(get_announce(deps, validators))
@@ -79,6 +112,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, Cont
         QueryMsg::GetAnnouncedValidators {} => to_binary(get_validators(deps)),
         QueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
         QueryMsg::LocalDomain {} => to_binary(get_local_domain(deps)),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
     }
 }
 
@@ -128,34 +162,13 @@ fn replay_hash(validator: &HexBinary, storage_location: &str) -> StdResult<HexBi
     ))
 }
 
-fn domain_hash(local_domain: u32, mailbox: HexBinary) -> StdResult<HexBinary> {
-    let mut bz = vec![];
-    bz.append(&mut local_domain.to_be_bytes().to_vec());
-    // left pad with zeroes
-    let mut addr = [0u8; 32];
-    addr[32 - mailbox.len()..].copy_from_slice(&mailbox);
-    bz.append(&mut addr.to_vec());
-    bz.append(&mut "HYPERLANE_ANNOUNCEMENT".as_bytes().to_vec());
-
-    let hash = keccak256_hash(&bz);
-
-    Ok(hash)
-}
-
-fn announcement_hash(mut domain_hash: Vec<u8>, storage_location: &str) -> HexBinary {
-    let mut bz = vec![];
-    bz.append(&mut domain_hash);
-    bz.append(&mut storage_location.as_bytes().to_vec());
-
-    keccak256_hash(&bz)
-}
-
 fn announce(
     deps: DepsMut,
     info: MessageInfo,
     validator: HexBinary,
     storage_location: String,
     signature: HexBinary,
+    format: SignatureFormat,
 ) -> Result<Response, ContractError> {
     ensure_eq!(
         validator.len(),
@@ -167,7 +180,7 @@ fn announce(
     let replay_id = replay_hash(&validator, &storage_location)?;
     ensure!(
         !REPLAY_PROTECITONS.has(deps.storage, replay_id.to_vec()),
-        ContractError::unauthorized("replay protection triggered")
+        ContractError::forbidden("replay protection triggered")
     );
     REPLAY_PROTECITONS.save(deps.storage, replay_id.to_vec(), &Empty {})?;
 
@@ -176,24 +189,19 @@ fn announce(
     let mailbox_addr = MAILBOX.load(deps.storage)?;
 
     // make digest
-    let message_hash = eth_hash(announcement_hash(
-        domain_hash(local_domain, mailbox_addr.into())?.to_vec(),
-        &storage_location,
-    ))?;
-
-    // recover pubkey from signature & verify
-    let pubkey = deps.api.secp256k1_recover_pubkey(
-        &message_hash,
-        &signature.as_slice()[..64],
-        // We subs 27 according to this - https://eips.ethereum.org/EIPS/eip-155
-        signature[64] - 27,
-    )?;
+    let mailbox_addr = pad32(&mailbox_addr)?;
+    let message_hash = match format {
+        SignatureFormat::EthSignedMessage => eth_signed_message_hash(announcement_digest(
+            local_domain,
+            mailbox_addr,
+            &storage_location,
+        )),
+        SignatureFormat::TypedData => {
+            announcement_typed_data_hash(local_domain, mailbox_addr, &storage_location)
+        }
+    };
 
-    ensure_eq!(
-        eth_addr(pubkey.into())?,
-        validator,
-        ContractError::VerifyFailed {}
-    );
+    verify_signature(deps.api, &message_hash, &signature, &validator)?;
 
     // save validator if not saved yet
     if !VALIDATORS.has(deps.storage, validator.to_vec()) {
@@ -215,16 +223,124 @@ fn announce(
     ))
 }
 
+fn unannounce(
+    deps: DepsMut,
+    info: MessageInfo,
+    validator: HexBinary,
+    storage_location: String,
+    signature: HexBinary,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        validator.len(),
+        20,
+        ContractError::invalid_addr("length should be 20")
+    );
+
+    let local_domain = LOCAL_DOMAIN.load(deps.storage)?;
+    let mailbox_addr = pad32(&MAILBOX.load(deps.storage)?)?;
+
+    let message_hash = eth_signed_message_hash(unannouncement_digest(
+        local_domain,
+        mailbox_addr,
+        &storage_location,
+    ));
+    verify_signature(deps.api, &message_hash, &signature, &validator)?;
+
+    remove_storage_location(deps.storage, &validator, &storage_location)?;
+
+    Ok(Response::new().add_event(
+        Event::new("validator-unannouncement")
+            .add_attribute("sender", info.sender)
+            .add_attribute("validator", validator.to_string())
+            .add_attribute("storage-location", storage_location),
+    ))
+}
+
+fn force_unannounce(
+    deps: DepsMut,
+    info: MessageInfo,
+    validator: HexBinary,
+    storage_location: String,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        hpl_ownable::get_owner(deps.storage)?,
+        info.sender,
+        ContractError::Unauthorized {}
+    );
+
+    remove_storage_location(deps.storage, &validator, &storage_location)?;
+
+    Ok(Response::new().add_event(
+        Event::new("validator-force-unannouncement")
+            .add_attribute("sender", info.sender)
+            .add_attribute("validator", validator.to_string())
+            .add_attribute("storage-location", storage_location),
+    ))
+}
+
+fn verify_signature(
+    api: &dyn Api,
+    message_hash: &[u8],
+    signature: &HexBinary,
+    validator: &HexBinary,
+) -> Result<(), ContractError> {
+    // recover pubkey from signature & verify
+    let pubkey = api.secp256k1_recover_pubkey(
+        message_hash,
+        &signature.as_slice()[..64],
+        // We subs 27 according to this - https://eips.ethereum.org/EIPS/eip-155
+        signature[64] - 27,
+    )?;
+
+    ensure_eq!(
+        &eth_addr(pubkey.into())?,
+        validator,
+        ContractError::VerifyFailed {}
+    );
+
+    Ok(())
+}
+
+/// Drops the validator from the index once its last location is removed
+fn remove_storage_location(
+    storage: &mut dyn Storage,
+    validator: &HexBinary,
+    storage_location: &str,
+) -> Result<(), ContractError> {
+    let mut storage_locations = STORAGE_LOCATIONS
+        .may_load(storage, validator.to_vec())?
+        .unwrap_or_default();
+
+    let Some(index) = storage_locations.iter().position(|v| v == storage_location) else {
+        return Err(ContractError::invalid_args(&format!(
+            "storage location {storage_location} is not announced"
+        )));
+    };
+    storage_locations.remove(index);
+
+    if storage_locations.is_empty() {
+        STORAGE_LOCATIONS.remove(storage, validator.to_vec());
+        VALIDATORS.remove(storage, validator.to_vec());
+    } else {
+        STORAGE_LOCATIONS.save(storage, validator.to_vec(), &storage_locations)?;
+    }
+
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::new())
 }
 
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
-        testing::{mock_dependencies, mock_env, mock_info},
-        to_json_binary, ContractResult, QuerierResult, SystemResult, WasmQuery,
+        from_json,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        to_json_binary, Addr, ContractResult, OwnedDeps, QuerierResult, SystemResult, WasmQuery,
     };
 
     use hpl_interface::build_test_querier;
@@ -240,12 +356,15 @@ mod test {
 
     build_test_querier!(crate::contract::query);
 
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
     struct Announcement {
         validator: HexBinary,
         mailbox: String,
         domain: u32,
         location: String,
         signature: HexBinary,
+        format: SignatureFormat,
     }
 
     impl Announcement {
@@ -262,6 +381,7 @@ mod test {
                 domain,
                 location: location.into(),
                 signature: hex(signature),
+                format: SignatureFormat::EthSignedMessage,
             }
         }
 
@@ -278,10 +398,10 @@ mod test {
         fn preset_20_byte_address() -> Self {
             let mut p = Announcement::preset();
             p.mailbox = "49cfd6ef774acab14814d699e3f7ee36fdfba932".into();
-            return p;
+            p
         }
 
-        fn rand() -> Self {
+        fn rand(format: SignatureFormat) -> Self {
             // prepare test data
             let mailbox = gen_bz(32);
             let local_domain = 26657;
@@ -296,11 +416,18 @@ mod test {
             let addr_bin = eth_addr(pubkey_bin.into()).unwrap();
 
             // make announcement data
-            let verify_digest = eth_hash(announcement_hash(
-                domain_hash(local_domain, mailbox.clone()).unwrap().to_vec(),
-                storage_location,
-            ))
-            .unwrap();
+            let verify_digest = match format {
+                SignatureFormat::EthSignedMessage => eth_signed_message_hash(announcement_digest(
+                    local_domain,
+                    mailbox.to_array().unwrap(),
+                    storage_location,
+                )),
+                SignatureFormat::TypedData => announcement_typed_data_hash(
+                    local_domain,
+                    mailbox.to_array().unwrap(),
+                    storage_location,
+                ),
+            };
             let signature = pack_signature(
                 signing_key
                     .sign_prehash_recoverable(&verify_digest)
@@ -313,16 +440,29 @@ mod test {
                 domain: local_domain,
                 location: storage_location.to_string(),
                 signature,
+                format,
             }
         }
 
         fn fail() -> Self {
-            let mut announcement = Self::rand();
+            let mut announcement = Self::rand(SignatureFormat::EthSignedMessage);
 
             announcement.domain = 26658;
 
             announcement
         }
+
+        /// signed in `signed`, announced as the other format
+        fn mismatch(signed: SignatureFormat) -> Self {
+            let mut announcement = Self::rand(signed.clone());
+
+            announcement.format = match signed {
+                SignatureFormat::EthSignedMessage => SignatureFormat::TypedData,
+                SignatureFormat::TypedData => SignatureFormat::EthSignedMessage,
+            };
+
+            announcement
+        }
     }
 
     fn pack_signature((rs, v): (Signature, RecoveryId)) -> HexBinary {
@@ -331,6 +471,89 @@ mod test {
         bz.into()
     }
 
+    const LOCAL_DOMAIN_ID: u32 = 26657;
+
+    struct Validator {
+        key: SigningKey,
+        addr: HexBinary,
+    }
+
+    impl Validator {
+        fn rand() -> Self {
+            let secret_key = SecretKey::random(&mut OsRng);
+            let pubkey_bin = secret_key
+                .public_key()
+                .to_encoded_point(false)
+                .as_bytes()
+                .to_vec();
+
+            Self {
+                key: SigningKey::from(secret_key),
+                addr: eth_addr(pubkey_bin.into()).unwrap(),
+            }
+        }
+
+        fn sign(&self, digest: [u8; 32]) -> HexBinary {
+            pack_signature(self.key.sign_prehash_recoverable(&digest).unwrap())
+        }
+
+        fn sign_announcement(&self, mailbox: [u8; 32], location: &str) -> HexBinary {
+            self.sign(eth_signed_message_hash(announcement_digest(
+                LOCAL_DOMAIN_ID,
+                mailbox,
+                location,
+            )))
+        }
+
+        fn sign_unannouncement(&self, mailbox: [u8; 32], location: &str) -> HexBinary {
+            self.sign(eth_signed_message_hash(unannouncement_digest(
+                LOCAL_DOMAIN_ID,
+                mailbox,
+                location,
+            )))
+        }
+    }
+
+    /// deps with `validator` announced at every location in `locations`
+    fn deps_announced(validator: &Validator, locations: &[&str]) -> (TestDeps, [u8; 32]) {
+        let mut deps = mock_dependencies();
+        let mailbox = gen_bz(32);
+
+        hpl_ownable::initialize(deps.as_mut().storage, &Addr::unchecked("owner")).unwrap();
+        LOCAL_DOMAIN
+            .save(deps.as_mut().storage, &LOCAL_DOMAIN_ID)
+            .unwrap();
+        MAILBOX
+            .save(deps.as_mut().storage, &mailbox.to_vec())
+            .unwrap();
+
+        let mailbox = mailbox.to_array().unwrap();
+        for location in locations {
+            announce(
+                deps.as_mut(),
+                mock_info("someone", &[]),
+                validator.addr.clone(),
+                location.to_string(),
+                validator.sign_announcement(mailbox, location),
+                SignatureFormat::EthSignedMessage,
+            )
+            .unwrap();
+        }
+
+        (deps, mailbox)
+    }
+
+    fn locations(deps: Deps, validator: &HexBinary) -> Vec<String> {
+        let GetAnnounceStorageLocationsResponse { storage_locations } = test_query(
+            deps,
+            QueryMsg::GetAnnounceStorageLocations {
+                validators: vec![validator.clone()],
+            },
+        );
+
+        storage_locations[0].1.clone()
+    }
+
     #[rstest]
     fn test_init(#[values("osmo", "neutron")] hrp: &str) {
         let sender = gen_addr(hrp);
@@ -347,16 +570,21 @@ mod test {
             ))
         });
 
-        instantiate(
+        let res = instantiate(
             deps.as_mut(),
             mock_env(),
             mock_info(sender.as_str(), &[]),
             InstantiateMsg {
                 hrp: hrp.to_string(),
+                owner: "owner".to_string(),
                 mailbox: mailbox.to_string(),
             },
         )
         .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(data.mailbox, mailbox);
+        assert_eq!(data.owner, "owner");
     }
 
     #[rstest]
@@ -395,13 +623,23 @@ mod test {
     }
 
     #[rstest]
-    #[case::rand(Announcement::rand(), false)]
+    #[case::rand(Announcement::rand(SignatureFormat::EthSignedMessage), false)]
+    #[case::rand_typed_data(Announcement::rand(SignatureFormat::TypedData), false)]
     #[case::actual_data(Announcement::preset(), false)]
     #[case::actual_data_20_bytes(Announcement::preset_20_byte_address(), false)]
     #[should_panic(expected = "unauthorized")]
-    #[case::replay(Announcement::rand(), true)]
+    #[case::replay(Announcement::rand(SignatureFormat::EthSignedMessage), true)]
+    #[should_panic(expected = "unauthorized")]
+    #[case::replay_typed_data(Announcement::rand(SignatureFormat::TypedData), true)]
     #[should_panic(expected = "verify failed")]
     #[case::verify(Announcement::fail(), false)]
+    #[should_panic(expected = "verify failed")]
+    #[case::verify_typed_data_as_raw(Announcement::mismatch(SignatureFormat::TypedData), false)]
+    #[should_panic(expected = "verify failed")]
+    #[case::verify_raw_as_typed_data(
+        Announcement::mismatch(SignatureFormat::EthSignedMessage),
+        false
+    )]
     fn test_announce(#[case] announcement: Announcement, #[case] enable_duplication: bool) {
         let validator = announcement.validator;
         let mailbox = HexBinary::from_hex(&announcement.mailbox).unwrap();
@@ -428,6 +666,7 @@ mod test {
             validator.clone(),
             announcement.location.clone(),
             announcement.signature,
+            announcement.format,
         )
         .map_err(|e| e.to_string())
         .unwrap();
@@ -442,4 +681,88 @@ mod test {
             vec![announcement.location]
         );
     }
+
+    #[rstest]
+    #[case::removal(false)]
+    #[should_panic(expected = "verify failed")]
+    #[case::replayed_announcement(true)]
+    fn test_unannounce(#[case] replay_announcement: bool) {
+        let validator = Validator::rand();
+        let (mut deps, mailbox) = deps_announced(&validator, &["file://foo", "file://bar"]);
+
+        let unannounce = |deps: &mut TestDeps, location: &str| {
+            let signature = if replay_announcement {
+                validator.sign_announcement(mailbox, location)
+            } else {
+                validator.sign_unannouncement(mailbox, location)
+            };
+
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("someone", &[]),
+                ExecuteMsg::Unannounce {
+                    validator: validator.addr.clone(),
+                    storage_location: location.to_string(),
+                    signature,
+                },
+            )
+            .map_err(|e| e.to_string())
+            .unwrap()
+        };
+
+        let res = unannounce(&mut deps, "file://foo");
+        assert_eq!(res.events[0].ty, "validator-unannouncement");
+        assert_eq!(
+            locations(deps.as_ref(), &validator.addr),
+            vec!["file://bar"]
+        );
+        assert!(VALIDATORS.has(deps.as_ref().storage, validator.addr.to_vec()));
+
+        // the last location takes the validator out of the index
+        unannounce(&mut deps, "file://bar");
+        assert!(locations(deps.as_ref(), &validator.addr).is_empty());
+        assert!(!VALIDATORS.has(deps.as_ref().storage, validator.addr.to_vec()));
+    }
+
+    #[rstest]
+    #[case("owner")]
+    #[should_panic(expected = "unauthorized")]
+    #[case("someone")]
+    fn test_force_unannounce(#[case] sender: &str) {
+        let validator = Validator::rand();
+        let (mut deps, _) = deps_announced(&validator, &["file://foo"]);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::ForceUnannounce {
+                validator: validator.addr.clone(),
+                storage_location: "file://foo".to_string(),
+            },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(res.events[0].ty, "validator-force-unannouncement");
+        assert!(locations(deps.as_ref(), &validator.addr).is_empty());
+        assert!(!VALIDATORS.has(deps.as_ref().storage, validator.addr.to_vec()));
+    }
+
+    #[rstest]
+    #[should_panic(expected = "storage location file://bar is not announced")]
+    fn test_force_unannounce_unknown() {
+        let validator = Validator::rand();
+        let (mut deps, _) = deps_announced(&validator, &["file://foo"]);
+
+        force_unannounce(
+            deps.as_mut(),
+            mock_info("owner", &[]),
+            validator.addr,
+            "file://bar".to_string(),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+    }
 }
@@ -1,30 +1,2 @@
-use cosmwasm_std::{RecoverPubkeyError, StdError};
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-
-    #[error("{0}")]
-    RecoverPubkeyError(#[from] RecoverPubkeyError),
-
-    #[error("unauthorized. reason: {0}")]
-    Unauthorized(String),
-
-    #[error("invalid address. reason: {0}")]
-    InvalidAddress(String),
-
-    #[error("verify failed")]
-    VerifyFailed {},
-}
-
-impl ContractError {
-    pub fn unauthorized(reason: &str) -> Self {
-        ContractError::Unauthorized(reason.into())
-    }
-
-    pub fn invalid_addr(reason: &str) -> Self {
-        ContractError::InvalidAddress(reason.into())
-    }
-}
+/// Validator announce has no errors of its own
+pub type ContractError = hpl_error::HplError;
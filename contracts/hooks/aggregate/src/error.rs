@@ -1,16 +1,45 @@
-use cosmwasm_std::StdError;
+use hpl_error::HplError;
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
+    #[error(transparent)]
+    Hpl(#[from] HplError),
 
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
+    #[error("[E201] quorum not reached: {failed} of {hooks} hooks failed, quorum is {quorum}")]
+    QuorumNotReached {
+        quorum: u32,
+        hooks: u32,
+        failed: u32,
+    },
+}
+
+hpl_error::impl_from_hpl!(ContractError);
+
+impl ContractError {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Hpl(err) => err.code(),
+            Self::QuorumNotReached { .. } => 201,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::rstest;
 
-    #[error("{0}")]
-    CoinsError(#[from] cosmwasm_std::CoinsError),
+    use super::*;
 
-    #[error("unauthorized")]
-    Unauthorized {},
+    #[rstest]
+    #[case(
+        ContractError::QuorumNotReached { quorum: 2, hooks: 3, failed: 2 },
+        201,
+        "[E201] quorum not reached: 2 of 3 hooks failed, quorum is 2"
+    )]
+    #[case(HplError::Unauthorized {}.into(), 10, "[E010] unauthorized")]
+    fn test_error_codes(#[case] err: ContractError, #[case] code: u16, #[case] golden: &str) {
+        assert_eq!(err.code(), code);
+        assert_eq!(err.to_string(), golden);
+        assert!(golden.starts_with(&hpl_error::code_prefix(code)));
+    }
 }
@@ -1,18 +1,26 @@
 mod error;
 
+#[cfg(test)]
+mod multitest;
+
+use cosmwasm_schema::cw_serde;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, Addr, Coins, CosmosMsg, Deps, DepsMut, Env, Event, HexBinary, MessageInfo,
-    QueryResponse, Response, StdResult,
+    ensure, ensure_eq, to_json_binary, Addr, BankMsg, Coins, CosmosMsg, Deps, DepsMut, Empty, Env,
+    Event, HexBinary, MessageInfo, QueryResponse, Reply, Response, StdResult, Storage, SubMsg,
 };
 use cw_storage_plus::Item;
 use error::ContractError;
+use hpl_error::HplError;
 use hpl_interface::{
     hook::{
-        aggregate::{AggregateHookQueryMsg, ExecuteMsg, HooksResponse, InstantiateMsg, QueryMsg},
-        post_dispatch, HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchMsg,
-        QuoteDispatchResponse,
+        aggregate::{
+            AggregateHookQueryMsg, ExecuteMsg, HooksResponse, InstantiateMsg, QueryMsg,
+            QuorumResponse,
+        },
+        post_dispatch, HookQueryMsg, HookType, HookTypeResponse, InstantiateResponse,
+        MailboxResponse, PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
     },
     to_binary,
     types::Message,
@@ -26,6 +34,24 @@ pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const HOOKS_KEY: &str = "hooks";
 pub const HOOKS: Item<Vec<Addr>> = Item::new(HOOKS_KEY);
 
+pub const QUORUM_KEY: &str = "quorum";
+pub const QUORUM: Item<u32> = Item::new(QUORUM_KEY);
+
+pub const PENDING_DISPATCH_KEY: &str = "pending_dispatch";
+pub const PENDING_DISPATCH: Item<PendingDispatch> = Item::new(PENDING_DISPATCH_KEY);
+
+pub const REPLY_ID_POST_DISPATCH: u64 = 0;
+
+/// Results of the hooks of a quorum `post_dispatch`, collected by their replies
+#[cw_serde]
+pub struct PendingDispatch {
+    pub message_id: HexBinary,
+    pub hooks: u32,
+    pub replied: u32,
+    pub failed: u32,
+    pub quorum: u32,
+}
+
 fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_hook_aggregate::{}", name))
 }
@@ -50,15 +76,21 @@ pub fn instantiate(
 
     HOOKS.save(deps.storage, &hooks)?;
 
-    Ok(Response::new().add_event(
-        new_event("initialize")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner)
-            .add_attribute("hooks", msg.hooks.join(",")),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox: None,
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("hooks", msg.hooks.join(",")),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -66,70 +98,221 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         ExecuteMsg::PostDispatch(PostDispatchMsg { message, metadata }) => {
             // aggregate it
             let hooks = HOOKS.load(deps.storage)?;
+            let quorum = QUORUM.may_load(deps.storage)?;
+            let message_id = Message::try_from(message.clone())?.id();
 
-            let msgs: Vec<CosmosMsg> = hooks
-                .into_iter()
-                .map(|v| {
-                    let quote = hpl_interface::hook::quote_dispatch(
-                        &deps.querier,
-                        &v,
-                        metadata.clone(),
-                        message.clone(),
-                    )?;
-                    let msg =
-                        post_dispatch(v, metadata.clone(), message.clone(), Some(quote.fees))?
-                            .into();
-
-                    Ok(msg)
-                })
-                .collect::<StdResult<_>>()?;
+            let mut msgs: Vec<CosmosMsg> = vec![];
+            let mut failed_events = vec![];
+            for hook in hooks.iter() {
+                let quote = hpl_interface::hook::quote_dispatch(
+                    &deps.querier,
+                    hook,
+                    metadata.clone(),
+                    message.clone(),
+                );
+
+                match quote {
+                    Ok(quote) => msgs.push(
+                        post_dispatch(hook, metadata.clone(), message.clone(), Some(quote.fees))?
+                            .into(),
+                    ),
+                    // under a quorum, a hook that can't quote is a failed hook
+                    Err(err) if quorum.is_some() => failed_events.push(
+                        new_event("hook_failed")
+                            .add_attribute("message_id", message_id.to_hex())
+                            .add_attribute("reason", err.to_string()),
+                    ),
+                    Err(err) => return Err(err.into()),
+                }
+            }
 
-            let decoded_msg: Message = message.into();
+            let event = new_event("post_dispatch").add_attribute("message_id", message_id.to_hex());
 
-            // do nothing
-            Ok(Response::new().add_messages(msgs).add_event(
-                new_event("post_dispatch").add_attribute("message_id", decoded_msg.id().to_hex()),
-            ))
+            let Some(quorum) = quorum else {
+                return Ok(Response::new().add_messages(msgs).add_event(event));
+            };
+
+            // failed hooks are reverted and counted in `reply`, fees sent to them stay here
+            // until the owner claims them
+            let pending = PendingDispatch {
+                message_id,
+                hooks: hooks.len() as u32,
+                replied: failed_events.len() as u32,
+                failed: failed_events.len() as u32,
+                quorum,
+            };
+            ensure_quorum_reachable(&pending)?;
+            PENDING_DISPATCH.save(deps.storage, &pending)?;
+
+            Ok(Response::new()
+                .add_submessages(
+                    msgs.into_iter()
+                        .map(|v| SubMsg::reply_always(v, REPLY_ID_POST_DISPATCH)),
+                )
+                .add_event(event.add_attribute("quorum", quorum.to_string()))
+                .add_events(failed_events))
         }
         ExecuteMsg::SetHooks { hooks } => {
             ensure_eq!(
                 get_owner(deps.storage)?,
                 info.sender,
-                ContractError::Unauthorized {}
+                HplError::Unauthorized {}
             );
 
-            let parsed_hooks = hooks
+            let parsed_hooks: Vec<Addr> = hooks
                 .iter()
                 .map(|v| deps.api.addr_validate(v))
                 .collect::<StdResult<_>>()?;
 
+            ensure_quorum(
+                QUORUM.may_load(deps.storage)?,
+                parsed_hooks.len(),
+                "hooks can't be fewer than the quorum",
+            )?;
+
             HOOKS.save(deps.storage, &parsed_hooks)?;
 
+            Ok(Response::new().add_event(hpl_events::hook_set(
+                new_event("set_hooks"),
+                &info.sender,
+                &hooks,
+            )))
+        }
+        ExecuteMsg::SetQuorum { quorum } => {
+            ensure_eq!(
+                get_owner(deps.storage)?,
+                info.sender,
+                HplError::Unauthorized {}
+            );
+
+            let hooks = HOOKS.load(deps.storage)?;
+            ensure_quorum(
+                quorum,
+                hooks.len(),
+                "quorum must be between 1 and the number of hooks",
+            )?;
+
+            match quorum {
+                Some(quorum) => QUORUM.save(deps.storage, &quorum)?,
+                None => QUORUM.remove(deps.storage),
+            }
+
             Ok(Response::new().add_event(
-                new_event("set_hooks")
+                new_event("set_quorum")
                     .add_attribute("sender", info.sender)
-                    .add_attribute("hooks", hooks.join(",")),
+                    .add_attribute("quorum", quorum.map_or("none".into(), |v| v.to_string())),
             ))
         }
+        ExecuteMsg::Claim { recipient } => {
+            let owner = get_owner(deps.storage)?;
+            ensure_eq!(owner, info.sender, HplError::Unauthorized {});
+
+            let recipient = match recipient {
+                Some(recipient) => deps.api.addr_validate(&recipient)?,
+                None => owner,
+            };
+            let balances = deps.querier.query_all_balances(&env.contract.address)?;
+
+            let event = new_event("claim")
+                .add_attribute("sender", info.sender)
+                .add_attribute("recipient", recipient.as_str());
+
+            if balances.is_empty() {
+                return Ok(Response::new().add_event(event.add_attribute("amount", "none")));
+            }
+
+            let amount = Coins::try_from(balances.clone())?.to_string();
+            let event = event.add_attribute("amount", amount);
+
+            Ok(Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: recipient.into(),
+                    amount: balances,
+                })
+                .add_event(event))
+        }
     }
 }
 
+fn ensure_quorum_reachable(pending: &PendingDispatch) -> Result<(), ContractError> {
+    ensure!(
+        pending.hooks - pending.failed >= pending.quorum,
+        ContractError::QuorumNotReached {
+            quorum: pending.quorum,
+            hooks: pending.hooks,
+            failed: pending.failed,
+        }
+    );
+
+    Ok(())
+}
+
+fn ensure_quorum(quorum: Option<u32>, hooks: usize, reason: &str) -> Result<(), ContractError> {
+    if let Some(quorum) = quorum {
+        ensure!(
+            quorum > 0 && quorum as usize <= hooks,
+            HplError::invalid_config(reason)
+        );
+    }
+
+    Ok(())
+}
+
+/// Counts the result of a hook, failing the dispatch once the quorum can't be reached
 #[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    ensure_eq!(msg.id, REPLY_ID_POST_DISPATCH, HplError::InvalidReplyId {});
+
+    let mut pending = PENDING_DISPATCH.load(deps.storage)?;
+    pending.replied += 1;
+
+    let mut resp = Response::new();
+    if let Err(reason) = msg.result.into_result() {
+        pending.failed += 1;
+        resp = resp.add_event(
+            new_event("hook_failed")
+                .add_attribute("message_id", pending.message_id.to_hex())
+                .add_attribute("reason", reason),
+        );
+    }
+
+    ensure_quorum_reachable(&pending)?;
+
+    if pending.replied < pending.hooks {
+        PENDING_DISPATCH.save(deps.storage, &pending)?;
+        return Ok(resp);
+    }
+
+    PENDING_DISPATCH.remove(deps.storage);
+
+    Ok(resp.add_event(
+        new_event("quorum_reached")
+            .add_attribute("message_id", pending.message_id.to_hex())
+            .add_attribute("succeeded", (pending.hooks - pending.failed).to_string())
+            .add_attribute("failed", pending.failed.to_string()),
+    ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Hook(msg) => match msg {
             HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::Aggregation,
+            })?),
             HookQueryMsg::QuoteDispatch(QuoteDispatchMsg { metadata, message }) => {
                 to_binary(quote_dispatch(deps, metadata, message))
             }
         },
         QueryMsg::AggregateHook(msg) => match msg {
             AggregateHookQueryMsg::Hooks {} => to_binary(get_hooks(deps)),
+            AggregateHookQueryMsg::Quorum {} => to_binary(get_quorum(deps.storage)),
         },
     }
 }
@@ -146,6 +329,7 @@ fn quote_dispatch(
     message: HexBinary,
 ) -> Result<QuoteDispatchResponse, ContractError> {
     let hooks = HOOKS.load(deps.storage)?;
+    let quorum = QUORUM.may_load(deps.storage)?;
 
     let total = hooks
         .into_iter()
@@ -155,7 +339,13 @@ fn quote_dispatch(
                 hook,
                 metadata.clone(),
                 message.clone(),
-            )?;
+            );
+
+            // hooks that can't quote aren't paid under a quorum, as `PostDispatch` skips them
+            let res = match res {
+                Err(_) if quorum.is_some() => return Ok(acc),
+                res => res?,
+            };
 
             for fee in res.fees {
                 acc.add(fee)?;
@@ -178,3 +368,16 @@ fn get_hooks(deps: Deps) -> Result<HooksResponse, ContractError> {
             .collect(),
     })
 }
+
+fn get_quorum(storage: &dyn Storage) -> Result<QuorumResponse, ContractError> {
+    Ok(QuorumResponse {
+        quorum: QUORUM.may_load(storage)?,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
@@ -0,0 +1,460 @@
+use cosmwasm_std::{
+    coin, coins, to_json_binary, Addr, Binary, Coin, Deps, DepsMut, Empty, Env, HexBinary,
+    MessageInfo, Response, StdError, StdResult,
+};
+use cw_multi_test::{
+    addons::{MockAddressGenerator, MockApiBech32},
+    App, AppBuilder, BankKeeper, Contract, ContractWrapper, Executor, WasmKeeper,
+};
+use hpl_interface::{
+    hook::{
+        aggregate::{AggregateHookQueryMsg, ExecuteMsg, InstantiateMsg, QueryMsg, QuorumResponse},
+        mock, PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
+    },
+    types::Message,
+};
+use ibcx_test_utils::gen_bz;
+use rstest::rstest;
+
+use crate::{execute, instantiate, query, reply, PENDING_DISPATCH};
+
+type TestApp = App<BankKeeper, MockApiBech32>;
+
+const HRP: &str = "osmo";
+const DENOM: &str = "uosmo";
+
+fn aggregate() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+fn mock_hook() -> Box<dyn Contract<Empty>> {
+    use hpl_hook_mock::{execute, instantiate, query};
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// Quotes nothing and fails every `post_dispatch`
+fn failing_hook() -> Box<dyn Contract<Empty>> {
+    fn execute(_: DepsMut, _: Env, _: MessageInfo, _: mock::ExecuteMsg) -> StdResult<Response> {
+        Err(StdError::generic_err("hook is down"))
+    }
+
+    fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn query(_: Deps, _: Env, _: mock::QueryMsg) -> StdResult<Binary> {
+        to_json_binary(&QuoteDispatchResponse { fees: vec![] })
+    }
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// Quotes a fee of 100 and fails every `post_dispatch`
+fn failing_paid_hook() -> Box<dyn Contract<Empty>> {
+    fn execute(_: DepsMut, _: Env, _: MessageInfo, _: mock::ExecuteMsg) -> StdResult<Response> {
+        Err(StdError::generic_err("hook is down"))
+    }
+
+    fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn query(_: Deps, _: Env, _: mock::QueryMsg) -> StdResult<Binary> {
+        to_json_binary(&QuoteDispatchResponse {
+            fees: coins(100, DENOM),
+        })
+    }
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// Fails every quote
+fn unquotable_hook() -> Box<dyn Contract<Empty>> {
+    fn execute(_: DepsMut, _: Env, _: MessageInfo, _: mock::ExecuteMsg) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn query(_: Deps, _: Env, _: mock::QueryMsg) -> StdResult<Binary> {
+        Err(StdError::generic_err("oracle is down"))
+    }
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+struct Suite {
+    app: TestApp,
+    owner: Addr,
+    aggregate: Addr,
+    hooks: Vec<Addr>,
+}
+
+impl Suite {
+    fn set_quorum(&mut self, quorum: Option<u32>) -> anyhow::Result<()> {
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.aggregate.clone(),
+            &ExecuteMsg::SetQuorum { quorum },
+            &[],
+        )?;
+
+        Ok(())
+    }
+
+    fn post_dispatch(&mut self, message: &Message) -> anyhow::Result<cw_multi_test::AppResponse> {
+        self.post_dispatch_with_funds(message, &[])
+    }
+
+    fn post_dispatch_with_funds(
+        &mut self,
+        message: &Message,
+        funds: &[Coin],
+    ) -> anyhow::Result<cw_multi_test::AppResponse> {
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.aggregate.clone(),
+            &ExecuteMsg::PostDispatch(PostDispatchMsg {
+                metadata: HexBinary::default(),
+                message: message.clone().into(),
+            }),
+            funds,
+        )
+    }
+
+    /// Appends an instance of `contract` to the hooks of the aggregate
+    fn add_hook(&mut self, contract: Box<dyn Contract<Empty>>) -> anyhow::Result<Addr> {
+        let code_id = self.app.store_code(contract);
+        let hook = self.app.instantiate_contract(
+            code_id,
+            self.owner.clone(),
+            &Empty {},
+            &[],
+            format!("hook_{}", self.hooks.len()),
+            None,
+        )?;
+        self.hooks.push(hook.clone());
+
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.aggregate.clone(),
+            &ExecuteMsg::SetHooks {
+                hooks: self.hooks.iter().map(|v| v.to_string()).collect(),
+            },
+            &[],
+        )?;
+
+        Ok(hook)
+    }
+
+    fn balance(&self, addr: &Addr) -> anyhow::Result<u128> {
+        Ok(self.app.wrap().query_balance(addr, DENOM)?.amount.u128())
+    }
+
+    fn last_post_dispatch(&self, hook: &Addr) -> anyhow::Result<Option<HexBinary>> {
+        let res: mock::LastPostDispatchResponse = self.app.wrap().query_wasm_smart(
+            hook,
+            &mock::QueryMsg::MockHook(mock::MockHookQueryMsg::LastPostDispatch {}),
+        )?;
+
+        Ok(res.message_id)
+    }
+}
+
+/// Aggregate of `working` mock hooks followed by `failing` hooks
+fn setup(working: usize, failing: usize) -> anyhow::Result<Suite> {
+    let mut app = AppBuilder::default()
+        .with_api(MockApiBech32::new(HRP))
+        .with_wasm(WasmKeeper::default().with_address_generator(MockAddressGenerator))
+        .build(|_, _, _| {});
+
+    let owner = app.api().addr_make("owner");
+    app.init_modules(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &owner, vec![coin(1_000, DENOM)])
+    })?;
+
+    let mock_code_id = app.store_code(mock_hook());
+    let failing_code_id = app.store_code(failing_hook());
+
+    let mut hooks = vec![];
+    for i in 0..working {
+        hooks.push(app.instantiate_contract(
+            mock_code_id,
+            owner.clone(),
+            &mock::InstantiateMsg {
+                owner: owner.to_string(),
+            },
+            &[],
+            format!("hook_{i}"),
+            None,
+        )?);
+    }
+    for i in 0..failing {
+        hooks.push(app.instantiate_contract(
+            failing_code_id,
+            owner.clone(),
+            &Empty {},
+            &[],
+            format!("failing_{i}"),
+            None,
+        )?);
+    }
+
+    let aggregate_code_id = app.store_code(aggregate());
+    let aggregate = app.instantiate_contract(
+        aggregate_code_id,
+        owner.clone(),
+        &InstantiateMsg {
+            owner: owner.to_string(),
+            hooks: hooks.iter().map(|v| v.to_string()).collect(),
+        },
+        &[],
+        "aggregate",
+        None,
+    )?;
+
+    Ok(Suite {
+        app,
+        owner,
+        aggregate,
+        hooks,
+    })
+}
+
+fn message() -> Message {
+    Message {
+        version: 3,
+        nonce: 0,
+        origin_domain: 26657,
+        sender: gen_bz(32),
+        dest_domain: 1,
+        recipient: gen_bz(32),
+        body: gen_bz(123),
+    }
+}
+
+#[test]
+fn test_quorum_one_failed() -> anyhow::Result<()> {
+    let mut suite = setup(2, 1)?;
+    suite.set_quorum(Some(2))?;
+
+    let msg = message();
+    let res = suite.post_dispatch(&msg)?;
+
+    for hook in &suite.hooks[..2] {
+        assert_eq!(suite.last_post_dispatch(hook)?, Some(msg.id()));
+    }
+
+    let reached = res
+        .events
+        .iter()
+        .find(|v| v.ty == "wasm-hpl_hook_aggregate::quorum_reached")
+        .unwrap();
+    let attr = |key: &str| {
+        reached
+            .attributes
+            .iter()
+            .find(|v| v.key == key)
+            .unwrap()
+            .value
+            .clone()
+    };
+    assert_eq!(attr("succeeded"), "2");
+    assert_eq!(attr("failed"), "1");
+
+    // nothing is left pending for the next dispatch
+    let pending = PENDING_DISPATCH.query(&suite.app.wrap(), suite.aggregate.clone());
+    assert!(pending.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_quorum_two_failed() -> anyhow::Result<()> {
+    let mut suite = setup(1, 2)?;
+    suite.set_quorum(Some(2))?;
+
+    let err = suite.post_dispatch(&message()).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "[E201] quorum not reached: 2 of 3 hooks failed, quorum is 2"
+    );
+
+    // the working hook is reverted along with the dispatch
+    assert_eq!(suite.last_post_dispatch(&suite.hooks[0])?, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_quorum_quote_failed() -> anyhow::Result<()> {
+    let mut suite = setup(2, 0)?;
+    suite.add_hook(unquotable_hook())?;
+
+    // every hook must quote without a quorum
+    let err = suite.post_dispatch(&message()).unwrap_err();
+    assert!(err.root_cause().to_string().contains("oracle is down"));
+
+    suite.set_quorum(Some(2))?;
+
+    // the quote skips the hook
+    let res: QuoteDispatchResponse = suite.app.wrap().query_wasm_smart(
+        &suite.aggregate,
+        &QuoteDispatchMsg {
+            metadata: HexBinary::default(),
+            message: message().into(),
+        }
+        .request(),
+    )?;
+    assert!(res.fees.is_empty());
+
+    let msg = message();
+    let res = suite.post_dispatch(&msg)?;
+
+    for hook in &suite.hooks[..2] {
+        assert_eq!(suite.last_post_dispatch(hook)?, Some(msg.id()));
+    }
+    assert!(res
+        .events
+        .iter()
+        .any(|v| v.ty == "wasm-hpl_hook_aggregate::hook_failed"));
+
+    // counted as failed before any hook runs
+    suite.set_quorum(Some(3))?;
+    let err = suite.post_dispatch(&message()).unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        "[E201] quorum not reached: 1 of 3 hooks failed, quorum is 3"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_quorum_claim_failed_fees() -> anyhow::Result<()> {
+    let mut suite = setup(1, 0)?;
+    suite.add_hook(failing_paid_hook())?;
+    suite.set_quorum(Some(1))?;
+
+    // the fee sent to the failed hook is reverted to the aggregate
+    suite.post_dispatch_with_funds(&message(), &coins(100, DENOM))?;
+    assert_eq!(suite.balance(&suite.aggregate)?, 100);
+    assert_eq!(suite.balance(&suite.owner)?, 900);
+
+    let claim = |suite: &mut Suite, sender: &Addr, recipient: Option<&Addr>| {
+        suite.app.execute_contract(
+            sender.clone(),
+            suite.aggregate.clone(),
+            &ExecuteMsg::Claim {
+                recipient: recipient.map(|v| v.to_string()),
+            },
+            &[],
+        )
+    };
+
+    let someone = suite.app.api().addr_make("someone");
+    let err = claim(&mut suite, &someone, None).unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "[E010] unauthorized");
+
+    let owner = suite.owner.clone();
+    claim(&mut suite, &owner, Some(&someone))?;
+    assert_eq!(suite.balance(&suite.aggregate)?, 0);
+    assert_eq!(suite.balance(&someone)?, 100);
+
+    // nothing left to claim
+    claim(&mut suite, &owner, None)?;
+    assert_eq!(suite.balance(&suite.owner)?, 900);
+
+    Ok(())
+}
+
+#[test]
+fn test_without_quorum() -> anyhow::Result<()> {
+    let mut suite = setup(2, 1)?;
+
+    // every hook must succeed
+    let err = suite.post_dispatch(&message()).unwrap_err();
+    assert!(err.root_cause().to_string().contains("hook is down"));
+
+    suite.set_quorum(Some(2))?;
+    suite.post_dispatch(&message())?;
+
+    suite.set_quorum(None)?;
+    suite.post_dispatch(&message()).unwrap_err();
+
+    Ok(())
+}
+
+#[rstest]
+#[case(Some(2), None)]
+#[case(None, None)]
+#[case(Some(0), Some("quorum must be between 1 and the number of hooks"))]
+#[case(Some(4), Some("quorum must be between 1 and the number of hooks"))]
+fn test_set_quorum(#[case] quorum: Option<u32>, #[case] err: Option<&str>) -> anyhow::Result<()> {
+    let mut suite = setup(3, 0)?;
+
+    let res = suite.set_quorum(quorum);
+    match err {
+        Some(err) => assert!(res.unwrap_err().root_cause().to_string().contains(err)),
+        None => res?,
+    }
+
+    let res: QuorumResponse = suite.app.wrap().query_wasm_smart(
+        &suite.aggregate,
+        &QueryMsg::AggregateHook(AggregateHookQueryMsg::Quorum {}),
+    )?;
+    assert_eq!(res.quorum, if err.is_some() { None } else { quorum });
+
+    Ok(())
+}
+
+#[test]
+fn test_set_hooks_below_quorum() -> anyhow::Result<()> {
+    let mut suite = setup(3, 0)?;
+    suite.set_quorum(Some(2))?;
+
+    let set_hooks = |suite: &mut Suite, hooks: &[Addr]| {
+        suite.app.execute_contract(
+            suite.owner.clone(),
+            suite.aggregate.clone(),
+            &ExecuteMsg::SetHooks {
+                hooks: hooks.iter().map(|v| v.to_string()).collect(),
+            },
+            &[],
+        )
+    };
+
+    let hooks = suite.hooks.clone();
+    let err = set_hooks(&mut suite, &hooks[..1]).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("hooks can't be fewer than the quorum"));
+
+    set_hooks(&mut suite, &hooks[..2])?;
+
+    Ok(())
+}
+
+#[test]
+fn test_set_quorum_unauthorized() -> anyhow::Result<()> {
+    let mut suite = setup(3, 0)?;
+
+    let err = suite
+        .app
+        .execute_contract(
+            suite.app.api().addr_make("someone"),
+            suite.aggregate.clone(),
+            &ExecuteMsg::SetQuorum { quorum: Some(1) },
+            &[],
+        )
+        .unwrap_err();
+    assert_eq!(err.root_cause().to_string(), "[E010] unauthorized");
+
+    Ok(())
+}
@@ -1,32 +1,21 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, ensure_eq, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, Event, MessageInfo,
-    QueryResponse, Response, StdError,
+    ensure, ensure_eq, to_json_binary, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, Event,
+    MessageInfo, QueryResponse, Response, StdError,
 };
 use cw_storage_plus::Item;
 use hpl_interface::{
     hook::{
         fee::{ExecuteMsg, FeeHookMsg, FeeHookQueryMsg, FeeResponse, InstantiateMsg, QueryMsg},
-        HookQueryMsg, MailboxResponse, QuoteDispatchResponse,
+        HookQueryMsg, HookType, HookTypeResponse, InstantiateResponse, MailboxResponse,
+        QuoteDispatchResponse,
     },
     to_binary,
 };
 
-#[derive(thiserror::Error, Debug, PartialEq)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
-
-    #[error("unauthorized")]
-    Unauthorized {},
-
-    #[error("hook paused")]
-    Paused {},
-}
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
 
 // version info for migration info
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -53,13 +42,18 @@ pub fn instantiate(
     hpl_ownable::initialize(deps.storage, &owner)?;
     COIN_FEE.save(deps.storage, &msg.fee)?;
 
-    Ok(Response::new().add_event(
-        new_event("initialize")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner)
-            .add_attribute("fee_denom", msg.fee.denom)
-            .add_attribute("fee_amount", msg.fee.amount),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox: None,
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("fee_denom", msg.fee.denom)
+                .add_attribute("fee_amount", msg.fee.amount),
+        ))
 }
 
 fn get_fee(deps: Deps) -> Result<FeeResponse, ContractError> {
@@ -69,6 +63,7 @@ fn get_fee(deps: Deps) -> Result<FeeResponse, ContractError> {
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -76,7 +71,6 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         ExecuteMsg::FeeHook(msg) => match msg {
             FeeHookMsg::SetFee { fee } => {
                 let owner = hpl_ownable::get_owner(deps.storage)?;
@@ -128,11 +122,15 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Hook(msg) => match msg {
             HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::ProtocolFee,
+            })?),
             HookQueryMsg::QuoteDispatch(_) => to_binary(quote_dispatch(deps)),
         },
         QueryMsg::FeeHook(FeeHookQueryMsg::Fee {}) => to_binary(get_fee(deps)),
@@ -150,6 +148,13 @@ fn quote_dispatch(deps: Deps) -> Result<QuoteDispatchResponse, ContractError> {
     Ok(QuoteDispatchResponse { fees: vec![fee] })
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_schema::serde::{de::DeserializeOwned, Serialize};
@@ -201,6 +206,31 @@ mod test {
         assert_eq!("owner", get_owner(deps.as_ref().storage).unwrap().as_str());
     }
 
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                fee: coin(100, "uusd"),
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: addr("owner"),
+                mailbox: None
+            }
+        );
+    }
+
     #[rstest]
     #[case(&[coin(100, "uusd")])]
     #[should_panic(expected = "Generic error: insufficient funds")]
@@ -258,7 +288,9 @@ mod test {
             deps.as_mut(),
             mock_env(),
             mock_info(sender.as_str(), &[]),
-            ExecuteMsg::FeeHook(FeeHookMsg::Claim { recipient: recipient.clone() }),
+            ExecuteMsg::FeeHook(FeeHookMsg::Claim {
+                recipient: recipient.clone(),
+            }),
         )
         .map_err(|e| e.to_string())
         .unwrap();
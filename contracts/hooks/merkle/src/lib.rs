@@ -1,40 +1,24 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, Addr, Deps, DepsMut, Empty, Env, Event, MessageInfo, QueryResponse, Response,
-    StdError,
+    ensure, ensure_eq, to_json_binary, Addr, Deps, DepsMut, Empty, Env, Event, HexBinary,
+    MessageInfo, Order, QueryResponse, Response, StdResult, Storage,
 };
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 use hpl_interface::{
-    core::mailbox::{LatestDispatchedIdResponse, MailboxQueryMsg},
+    core::mailbox::{LatestDispatchedIdResponse, MailboxQueryMsg, NonceResponse},
     hook::{
         merkle::{self, ExecuteMsg, InstantiateMsg, MerkleHookQueryMsg, QueryMsg},
-        HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchResponse,
+        AuthorizedCallersResponse, HookQueryMsg, HookType, HookTypeResponse, InstantiateResponse,
+        MailboxResponse, PostDispatchMsg, QuoteDispatchResponse,
     },
     to_binary,
-    types::{MerkleTree, Message},
+    types::{keccak256_hash, MerkleTree, Message},
 };
+use hpl_ownable::get_owner;
 
-#[derive(thiserror::Error, Debug, PartialEq)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
-
-    #[error("unauthorized. reason: {0}")]
-    Unauthorized(String),
-
-    #[error("hook paused")]
-    Paused {},
-}
-
-impl ContractError {
-    pub fn unauthorized(reason: &str) -> Self {
-        ContractError::Unauthorized(reason.into())
-    }
-}
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
 
 // version info for migration info
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -43,9 +27,34 @@ pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const MAILBOX_KEY: &str = "mailbox";
 pub const MAILBOX: Item<Addr> = Item::new(MAILBOX_KEY);
 
+/// Hooks wrapping this one that may post dispatch besides the mailbox
+pub const AUTHORIZED_CALLERS_PREFIX: &str = "authorized_callers";
+pub const AUTHORIZED_CALLERS: Map<Addr, ()> = Map::new(AUTHORIZED_CALLERS_PREFIX);
+
 pub const MESSAGE_TREE_KEY: &str = "message_tree";
 pub const MESSAGE_TREE: Item<MerkleTree> = Item::new(MESSAGE_TREE_KEY);
 
+pub const EPOCH_KEY: &str = "epoch";
+pub const EPOCH: Item<u32> = Item::new(EPOCH_KEY);
+
+/// Mailbox nonce of the first leaf of the current epoch.
+/// A message's leaf index is its nonce minus this
+pub const EPOCH_BASE_KEY: &str = "epoch_base";
+pub const EPOCH_BASE: Item<u32> = Item::new(EPOCH_BASE_KEY);
+
+/// (root, count) of the tree at each reset, keyed by the epoch it closed
+pub const ARCHIVED_CHECKPOINTS_PREFIX: &str = "archived_checkpoints";
+pub const ARCHIVED_CHECKPOINTS: Map<u32, (HexBinary, u32)> = Map::new(ARCHIVED_CHECKPOINTS_PREFIX);
+
+pub const STORE_LEAVES_KEY: &str = "store_leaves";
+pub const STORE_LEAVES: Item<bool> = Item::new(STORE_LEAVES_KEY);
+
+/// Node hashes keyed by (level, index), level 0 being the leaves.
+/// Only written with `STORE_LEAVES`. Nodes not covering a leaf below the current count are
+/// left over from a previous epoch
+pub const TREE_NODES_PREFIX: &str = "tree_nodes";
+pub const TREE_NODES: Map<(u8, u32), HexBinary> = Map::new(TREE_NODES_PREFIX);
+
 fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_hook_merkle::{}", name))
 }
@@ -59,49 +68,110 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let owner = deps.api.addr_validate(&msg.owner)?;
     let mailbox = deps.api.addr_validate(&msg.mailbox)?;
+    let tree = MerkleTree::new(msg.tree_depth.unwrap_or(merkle::TREE_DEPTH as u8))?;
+    let store_leaves = msg.store_leaves.unwrap_or_default();
+
+    hpl_ownable::initialize(deps.storage, &owner)?;
 
     MAILBOX.save(deps.storage, &mailbox)?;
-    MESSAGE_TREE.save(deps.storage, &MerkleTree::default())?;
+    MESSAGE_TREE.save(deps.storage, &tree)?;
+    STORE_LEAVES.save(deps.storage, &store_leaves)?;
 
-    Ok(Response::new().add_event(
-        new_event("initialize")
-            .add_attribute("sender", info.sender)
-            .add_attribute("mailbox", mailbox),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox: Some(mailbox.clone()),
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("mailbox", mailbox)
+                .add_attribute("tree_depth", tree.depth().to_string())
+                .add_attribute("store_leaves", store_leaves.to_string()),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
+    env: Env,
+    info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
+        ExecuteMsg::SetMailbox { mailbox } => {
+            ensure_eq!(
+                info.sender,
+                get_owner(deps.storage)?,
+                ContractError::forbidden("only owner can set mailbox")
+            );
+
+            let mailbox = deps.api.addr_validate(&mailbox)?;
+
+            MAILBOX.save(deps.storage, &mailbox)?;
+
+            Ok(Response::new().add_event(
+                new_event("set_mailbox")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("mailbox", mailbox),
+            ))
+        }
+        ExecuteMsg::SetAuthorizedCaller { caller, authorized } => {
+            ensure_eq!(
+                info.sender,
+                get_owner(deps.storage)?,
+                ContractError::Unauthorized {}
+            );
+
+            let caller = deps.api.addr_validate(&caller)?;
+
+            if authorized {
+                AUTHORIZED_CALLERS.save(deps.storage, caller.clone(), &())?;
+            } else {
+                AUTHORIZED_CALLERS.remove(deps.storage, caller.clone());
+            }
+
+            Ok(Response::new().add_event(
+                new_event("set_authorized_caller")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("caller", caller)
+                    .add_attribute("authorized", authorized.to_string()),
+            ))
+        }
+        ExecuteMsg::ResetTree {} => reset_tree(deps, info),
         ExecuteMsg::PostDispatch(PostDispatchMsg { message, .. }) => {
             let mailbox = MAILBOX.load(deps.storage)?;
 
-            let latest_dispatch_id = deps
+            ensure!(
+                info.sender == mailbox || AUTHORIZED_CALLERS.has(deps.storage, info.sender.clone()),
+                ContractError::forbidden("sender is not mailbox")
+            );
+
+            let latest_dispatch = deps
                 .querier
                 .query_wasm_smart::<LatestDispatchedIdResponse>(
                     &mailbox,
                     &MailboxQueryMsg::LatestDispatchId {}.wrap(),
-                )?
-                .message_id;
+                )?;
 
-            let decoded_msg: Message = message.into();
+            let decoded_msg = Message::try_from(message)?;
 
             ensure_eq!(
-                latest_dispatch_id,
+                latest_dispatch.message_id,
                 decoded_msg.id(),
-                ContractError::unauthorized("message is not dispatching")
+                ContractError::forbidden("message is not dispatching")
+            );
+            ensure_eq!(
+                latest_dispatch.nonce,
+                decoded_msg.nonce,
+                ContractError::forbidden("message nonce is not latest")
             );
 
-            let mut tree = MESSAGE_TREE.load(deps.storage)?;
-            let index = tree.count;
-            tree.insert(decoded_msg.id())?;
-            MESSAGE_TREE.save(deps.storage, &tree)?;
+            let index = insert_leaf(deps.storage, decoded_msg.id())?;
 
             // do nothing
             Ok(Response::new()
@@ -117,13 +187,89 @@ pub fn execute(
     }
 }
 
+/// Inserts `leaf` into the tree and returns its index
+fn insert_leaf(storage: &mut dyn Storage, leaf: HexBinary) -> Result<u32, ContractError> {
+    let mut tree = MESSAGE_TREE.load(storage)?;
+    let index = tree.count as u32;
+    tree.insert(leaf.clone())?;
+    MESSAGE_TREE.save(storage, &tree)?;
+
+    if !STORE_LEAVES.may_load(storage)?.unwrap_or_default() {
+        return Ok(index);
+    }
+
+    // rewrite the path from the leaf up, leaves to the right are still empty
+    let mut node = leaf;
+    for (level, zero) in MerkleTree::zero_hashes(tree.depth())?.iter().enumerate() {
+        let idx = index >> level;
+        TREE_NODES.save(storage, (level as u8, idx), &node)?;
+
+        node = if idx & 1 == 1 {
+            let left = TREE_NODES.load(storage, (level as u8, idx - 1))?;
+            keccak256_hash(&[left.to_vec(), node.to_vec()].concat())
+        } else {
+            keccak256_hash(&[node.to_vec(), zero.to_vec()].concat())
+        };
+    }
+
+    Ok(index)
+}
+
+fn reset_tree(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        ContractError::Unauthorized {}
+    );
+
+    let tree = MESSAGE_TREE.load(deps.storage)?;
+    let count = tree.count as u32;
+    let base = EPOCH_BASE.may_load(deps.storage)?.unwrap_or_default();
+
+    // leaves dispatched but not yet in the tree would be lost with it
+    let mailbox = MAILBOX.load(deps.storage)?;
+    let NonceResponse { nonce } = deps
+        .querier
+        .query_wasm_smart(&mailbox, &MailboxQueryMsg::Nonce {}.wrap())?;
+    ensure_eq!(
+        base as u64 + count as u64,
+        nonce as u64,
+        ContractError::forbidden(&format!(
+            "epoch base {base} plus tree count {count} does not match mailbox nonce {nonce}"
+        ))
+    );
+
+    let root = tree.root()?;
+    let epoch = EPOCH.may_load(deps.storage)?.unwrap_or_default();
+
+    ARCHIVED_CHECKPOINTS.save(deps.storage, epoch, &(root.clone(), count))?;
+    EPOCH.save(deps.storage, &(epoch + 1))?;
+    EPOCH_BASE.save(deps.storage, &nonce)?;
+    MESSAGE_TREE.save(deps.storage, &MerkleTree::new(tree.depth() as u8)?)?;
+
+    Ok(Response::new().add_event(
+        new_event("reset_tree")
+            .add_attribute("sender", info.sender)
+            .add_attribute("archived_epoch", epoch.to_string())
+            .add_attribute("archived_root", root.to_hex())
+            .add_attribute("archived_count", count.to_string())
+            .add_attribute("epoch", (epoch + 1).to_string())
+            .add_attribute("epoch_base", nonce.to_string()),
+    ))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+#[hpl_ownable::route_ownable]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     use MerkleHookQueryMsg::*;
 
     match msg {
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Hook(msg) => match msg {
             HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::MerkleTree,
+            })?),
             HookQueryMsg::QuoteDispatch(_) => to_binary(quote_dispatch()),
         },
         QueryMsg::MerkleHook(msg) => match msg {
@@ -132,6 +278,10 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<QueryResponse, Cont
             Branch {} => to_binary(get_tree_branch(deps)),
             Tree {} => to_binary(get_tree(deps)),
             CheckPoint {} => to_binary(get_tree_checkpoint(deps)),
+            Epoch {} => to_binary(get_epoch(deps)),
+            AuthorizedCallers {} => to_binary(get_authorized_callers(deps)),
+            ArchivedCheckpoint { epoch } => to_binary(get_archived_checkpoint(deps, epoch)),
+            Proof { nonce } => to_binary(get_tree_proof(deps, nonce)),
         },
     }
 }
@@ -190,8 +340,77 @@ fn get_tree_checkpoint(deps: Deps) -> Result<merkle::CheckPointResponse, Contrac
     })
 }
 
+fn get_epoch(deps: Deps) -> Result<merkle::EpochResponse, ContractError> {
+    Ok(merkle::EpochResponse {
+        epoch: EPOCH.may_load(deps.storage)?.unwrap_or_default(),
+        base: EPOCH_BASE.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+fn get_authorized_callers(deps: Deps) -> Result<AuthorizedCallersResponse, ContractError> {
+    let callers = AUTHORIZED_CALLERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|v| v.map(String::from))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AuthorizedCallersResponse { callers })
+}
+
+fn get_archived_checkpoint(
+    deps: Deps,
+    epoch: u32,
+) -> Result<merkle::ArchivedCheckpointResponse, ContractError> {
+    let (root, count) = ARCHIVED_CHECKPOINTS.load(deps.storage, epoch)?;
+
+    Ok(merkle::ArchivedCheckpointResponse { root, count })
+}
+
+fn get_tree_proof(deps: Deps, nonce: u32) -> Result<merkle::ProofResponse, ContractError> {
+    ensure!(
+        STORE_LEAVES.may_load(deps.storage)?.unwrap_or_default(),
+        ContractError::invalid_config("leaves are not stored")
+    );
+
+    let tree = MESSAGE_TREE.load(deps.storage)?;
+    let count = tree.count as u32;
+    let base = EPOCH_BASE.may_load(deps.storage)?.unwrap_or_default();
+
+    let index = nonce
+        .checked_sub(base)
+        .filter(|index| *index < count)
+        .ok_or_else(|| {
+            ContractError::invalid_args(&format!(
+                "nonce {nonce} is not in the current epoch. base: {base}, count: {count}"
+            ))
+        })?;
+
+    let branch = MerkleTree::zero_hashes(tree.depth())?
+        .into_iter()
+        .enumerate()
+        .map(|(level, zero)| {
+            let sibling = (index >> level) ^ 1;
+            // subtrees past the last leaf are empty, whatever a previous epoch left behind
+            if (sibling as u64) << level < count as u64 {
+                TREE_NODES.load(deps.storage, (level as u8, sibling))
+            } else {
+                Ok(zero.into())
+            }
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(merkle::ProofResponse {
+        index,
+        leaf: TREE_NODES.load(deps.storage, (0, index))?,
+        branch,
+        root: tree.root()?,
+        count,
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::new())
 }
 
@@ -202,7 +421,7 @@ mod test {
     use cosmwasm_std::{
         from_json,
         testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-        HexBinary, OwnedDeps, WasmQuery,
+        HexBinary, OwnedDeps, QuerierResult, WasmQuery,
     };
 
     use hpl_interface::{
@@ -230,7 +449,10 @@ mod test {
             mock_env(),
             mock_info(sender.as_str(), &[]),
             InstantiateMsg {
+                owner: "owner".to_string(),
                 mailbox: mailbox.to_string(),
+                tree_depth: None,
+                store_leaves: None,
             },
         )
         .unwrap();
@@ -240,6 +462,7 @@ mod test {
 
     #[rstest]
     fn test_init(deps: TestDeps) {
+        assert_eq!("owner", get_owner(deps.as_ref().storage).unwrap().as_str());
         assert_eq!(
             "mailbox",
             MAILBOX.load(deps.as_ref().storage).unwrap().as_str()
@@ -250,38 +473,113 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                mailbox: "mailbox".to_string(),
+                tree_depth: None,
+                store_leaves: None,
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: Addr::unchecked("owner"),
+                mailbox: Some(Addr::unchecked("mailbox"))
+            }
+        );
+    }
+
+    #[rstest]
+    #[case(Some(4))]
+    #[case(Some(32))]
+    #[should_panic(expected = "invalid tree depth: 0. must be 1..=32")]
+    #[case(Some(0))]
+    #[should_panic(expected = "invalid tree depth: 33. must be 1..=32")]
+    #[case(Some(33))]
+    fn test_init_tree_depth(#[case] tree_depth: Option<u8>) {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                mailbox: "mailbox".to_string(),
+                tree_depth,
+                store_leaves: None,
+            },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let res: merkle::BranchResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::MerkleHook(MerkleHookQueryMsg::Branch {}),
+        );
+        assert_eq!(res.branch.len(), tree_depth.unwrap() as usize);
+
+        let res: merkle::RootResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::MerkleHook(MerkleHookQueryMsg::Root {}),
+        );
+        assert_eq!(
+            res.root,
+            MerkleTree::new(tree_depth.unwrap())
+                .unwrap()
+                .root()
+                .unwrap()
+        );
+    }
+
     const TEST_MESSAGE: &str = "dc7b240deb74cca40636435ade8514b7ac35176e085f810e92dbc8bdb54a3d554ef32b9f724df19861d7e9b89a8ed11a4ecb35512f58b18b6607689cb9ba36dcf0f4af3cc1c7128c6cf0b47ea1f1aa07a4fe64502edd9a2b2e2dddf770776040efa24f19";
     const TEST_MESSAGE_FAIL: &str = "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef";
 
+    fn mock_latest_dispatch(query: &WasmQuery) -> QuerierResult {
+        use cosmwasm_std::{to_json_binary, ContractResult, SystemResult};
+
+        let (_contract_addr, msg) = match query {
+            WasmQuery::Smart { contract_addr, msg } => (contract_addr, from_json(msg).unwrap()),
+            _ => unreachable!("noo"),
+        };
+
+        match msg {
+            mailbox::QueryMsg::Mailbox(MailboxQueryMsg::LatestDispatchId {}) => {
+                let res = LatestDispatchedIdResponse {
+                    message_id: hex(
+                        "a6d8af738f99da8a0a8a3611e6c777bc9ebf42b1f685a5ff6b1ff1f2b7b70f45",
+                    ),
+                    nonce: 0x7b240deb,
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+            }
+            _ => unreachable!("unwrap noo"),
+        }
+    }
+
     #[rstest]
     #[case("mailbox", None)]
-    #[should_panic(expected = "unauthorized")]
+    #[should_panic(expected = "unauthorized. reason: message is not dispatching")]
     #[case("mailbox", Some(hex(TEST_MESSAGE_FAIL)))]
+    #[should_panic(expected = "unauthorized. reason: sender is not mailbox")]
+    #[case("stranger", None)]
     fn test_post_dispatch(
         mut deps: TestDeps,
         #[case] sender: &str,
         #[case] message: Option<HexBinary>,
     ) {
-        deps.querier.update_wasm(|query| {
-            use cosmwasm_std::{to_json_binary, ContractResult, SystemResult};
-
-            let (_contract_addr, msg) = match query {
-                WasmQuery::Smart { contract_addr, msg } => (contract_addr, from_json(msg).unwrap()),
-                _ => unreachable!("noo"),
-            };
-
-            match msg {
-                mailbox::QueryMsg::Mailbox(MailboxQueryMsg::LatestDispatchId {}) => {
-                    let res = LatestDispatchedIdResponse {
-                        message_id: hex(
-                            "a6d8af738f99da8a0a8a3611e6c777bc9ebf42b1f685a5ff6b1ff1f2b7b70f45",
-                        ),
-                    };
-                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
-                }
-                _ => unreachable!("unwrap noo"),
-            }
-        });
+        deps.querier.update_wasm(mock_latest_dispatch);
 
         let res = execute(
             deps.as_mut(),
@@ -312,6 +610,105 @@ mod test {
         assert_eq!(tree.count, 1);
     }
 
+    #[rstest]
+    #[case("owner")]
+    #[should_panic(expected = "unauthorized. reason: only owner can set mailbox")]
+    #[case("stranger")]
+    fn test_set_mailbox(mut deps: TestDeps, #[case] sender: &str) {
+        deps.querier.update_wasm(mock_latest_dispatch);
+
+        test_execute(
+            deps.as_mut(),
+            &Addr::unchecked(sender),
+            ExecuteMsg::SetMailbox {
+                mailbox: "new_mailbox".to_string(),
+            },
+            vec![],
+        );
+
+        let res: MailboxResponse =
+            test_query(deps.as_ref(), QueryMsg::Hook(HookQueryMsg::Mailbox {}));
+        assert_eq!("new_mailbox", res.mailbox.as_str());
+
+        let post_dispatch = |deps: DepsMut, sender: &str| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::PostDispatch(PostDispatchMsg {
+                    metadata: HexBinary::default(),
+                    message: hex(TEST_MESSAGE),
+                }),
+            )
+        };
+
+        // the previous mailbox is no longer honored
+        let err = post_dispatch(deps.as_mut(), "mailbox").unwrap_err();
+        assert_eq!(err, ContractError::forbidden("sender is not mailbox"));
+
+        post_dispatch(deps.as_mut(), "new_mailbox").unwrap();
+        assert_eq!(MESSAGE_TREE.load(deps.as_ref().storage).unwrap().count, 1);
+    }
+
+    #[rstest]
+    #[case("owner")]
+    #[should_panic(expected = "unauthorized")]
+    #[case("stranger")]
+    fn test_set_authorized_caller(mut deps: TestDeps, #[case] sender: &str) {
+        deps.querier.update_wasm(mock_latest_dispatch);
+
+        let post_dispatch = |deps: DepsMut, message: &str| {
+            execute(
+                deps,
+                mock_env(),
+                mock_info("aggregate", &[]),
+                ExecuteMsg::PostDispatch(PostDispatchMsg {
+                    metadata: HexBinary::default(),
+                    message: hex(message),
+                }),
+            )
+        };
+
+        let err = post_dispatch(deps.as_mut(), TEST_MESSAGE).unwrap_err();
+        assert_eq!(err, ContractError::forbidden("sender is not mailbox"));
+
+        test_execute(
+            deps.as_mut(),
+            &Addr::unchecked(sender),
+            ExecuteMsg::SetAuthorizedCaller {
+                caller: "aggregate".to_string(),
+                authorized: true,
+            },
+            vec![],
+        );
+
+        let res: AuthorizedCallersResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::MerkleHook(MerkleHookQueryMsg::AuthorizedCallers {}),
+        );
+        assert_eq!(res.callers, vec!["aggregate".to_string()]);
+
+        // authorized callers still only insert the message being dispatched
+        let err = post_dispatch(deps.as_mut(), TEST_MESSAGE_FAIL).unwrap_err();
+        assert_eq!(err, ContractError::forbidden("message is not dispatching"));
+
+        post_dispatch(deps.as_mut(), TEST_MESSAGE).unwrap();
+        assert_eq!(MESSAGE_TREE.load(deps.as_ref().storage).unwrap().count, 1);
+
+        test_execute(
+            deps.as_mut(),
+            &Addr::unchecked(sender),
+            ExecuteMsg::SetAuthorizedCaller {
+                caller: "aggregate".to_string(),
+                authorized: false,
+            },
+            vec![],
+        );
+
+        let err = post_dispatch(deps.as_mut(), TEST_MESSAGE).unwrap_err();
+        assert_eq!(err, ContractError::forbidden("sender is not mailbox"));
+    }
+
     #[rstest]
     fn test_queries(deps: TestDeps) {
         let res: MailboxResponse =
@@ -354,4 +751,286 @@ mod test {
         );
         assert_eq!(res.root, MerkleTree::default().root().unwrap());
     }
+
+    fn mock_nonce(nonce: u32) -> impl Fn(&WasmQuery) -> QuerierResult {
+        use cosmwasm_std::{to_json_binary, ContractResult, SystemResult};
+
+        move |query| match query {
+            WasmQuery::Smart { msg, .. } => match from_json(msg).unwrap() {
+                mailbox::QueryMsg::Mailbox(MailboxQueryMsg::Nonce {}) => SystemResult::Ok(
+                    ContractResult::Ok(to_json_binary(&NonceResponse { nonce }).unwrap()),
+                ),
+                _ => mock_latest_dispatch(query),
+            },
+            _ => unreachable!("noo"),
+        }
+    }
+
+    fn reset_tree(deps: DepsMut, sender: &str) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::ResetTree {},
+        )
+    }
+
+    #[rstest]
+    fn test_reset_tree(mut deps: TestDeps) {
+        deps.querier.update_wasm(mock_nonce(1));
+
+        test_execute(
+            deps.as_mut(),
+            &Addr::unchecked("mailbox"),
+            ExecuteMsg::PostDispatch(PostDispatchMsg {
+                metadata: HexBinary::default(),
+                message: hex(TEST_MESSAGE),
+            }),
+            vec![],
+        );
+        let root = MESSAGE_TREE
+            .load(deps.as_ref().storage)
+            .unwrap()
+            .root()
+            .unwrap();
+
+        let err = reset_tree(deps.as_mut(), "stranger").unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let res = reset_tree(deps.as_mut(), "owner").unwrap();
+        let attrs = res.events[0]
+            .attributes
+            .iter()
+            .map(|v| (v.key.as_str(), v.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            attrs,
+            vec![
+                ("sender", "owner"),
+                ("archived_epoch", "0"),
+                ("archived_root", root.to_hex().as_str()),
+                ("archived_count", "1"),
+                ("epoch", "1"),
+                ("epoch_base", "1"),
+            ]
+        );
+
+        let res: merkle::EpochResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::MerkleHook(MerkleHookQueryMsg::Epoch {}),
+        );
+        assert_eq!(res, merkle::EpochResponse { epoch: 1, base: 1 });
+
+        let res: merkle::ArchivedCheckpointResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::MerkleHook(MerkleHookQueryMsg::ArchivedCheckpoint { epoch: 0 }),
+        );
+        assert_eq!(res.root, root);
+        assert_eq!(res.count, 1);
+
+        assert_eq!(
+            MESSAGE_TREE.load(deps.as_ref().storage).unwrap(),
+            MerkleTree::default()
+        );
+    }
+
+    #[rstest]
+    fn test_reset_tree_twice(mut deps: TestDeps) {
+        for i in 0..3 {
+            insert_leaf(deps.as_mut().storage, leaf(i)).unwrap();
+        }
+        deps.querier.update_wasm(mock_nonce(3));
+        reset_tree(deps.as_mut(), "owner").unwrap();
+
+        // the nonce keeps counting from where the first epoch left off
+        for i in 0..2 {
+            insert_leaf(deps.as_mut().storage, leaf(i + 3)).unwrap();
+        }
+
+        deps.querier.update_wasm(mock_nonce(4));
+        let err = reset_tree(deps.as_mut(), "owner").unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::forbidden(
+                "epoch base 3 plus tree count 2 does not match mailbox nonce 4"
+            )
+        );
+
+        deps.querier.update_wasm(mock_nonce(5));
+        let res = reset_tree(deps.as_mut(), "owner").unwrap();
+        assert_eq!(
+            res.events[0]
+                .attributes
+                .iter()
+                .find(|v| v.key == "epoch_base")
+                .unwrap()
+                .value,
+            "5"
+        );
+
+        let res: merkle::EpochResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::MerkleHook(MerkleHookQueryMsg::Epoch {}),
+        );
+        assert_eq!(res, merkle::EpochResponse { epoch: 2, base: 5 });
+
+        let res: merkle::ArchivedCheckpointResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::MerkleHook(MerkleHookQueryMsg::ArchivedCheckpoint { epoch: 1 }),
+        );
+        assert_eq!(res.count, 2);
+    }
+
+    #[rstest]
+    #[case(0, true)]
+    #[case(1, false)]
+    fn test_reset_tree_pending(mut deps: TestDeps, #[case] nonce: u32, #[case] permitted: bool) {
+        // a message dispatched without being inserted into the tree
+        deps.querier.update_wasm(mock_nonce(nonce));
+
+        let res = reset_tree(deps.as_mut(), "owner");
+        match permitted {
+            true => {
+                res.unwrap();
+            }
+            false => {
+                assert_eq!(
+                    res.unwrap_err(),
+                    ContractError::forbidden(
+                        "epoch base 0 plus tree count 0 does not match mailbox nonce 1"
+                    )
+                );
+
+                let res: merkle::EpochResponse = test_query(
+                    deps.as_ref(),
+                    QueryMsg::MerkleHook(MerkleHookQueryMsg::Epoch {}),
+                );
+                assert_eq!(res.epoch, 0);
+            }
+        }
+    }
+
+    fn deps_store_leaves(depth: u8, store_leaves: Option<bool>) -> TestDeps {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                mailbox: "mailbox".to_string(),
+                tree_depth: Some(depth),
+                store_leaves,
+            },
+        )
+        .unwrap();
+
+        deps
+    }
+
+    fn leaf(i: u32) -> HexBinary {
+        keccak256_hash(format!("leaf_{i}").as_bytes())
+    }
+
+    fn assert_proofs(deps: Deps, base: u32, size: u32, leaf: impl Fn(u32) -> HexBinary) {
+        let root: merkle::RootResponse =
+            test_query(deps, QueryMsg::MerkleHook(MerkleHookQueryMsg::Root {}));
+
+        for i in 0..size {
+            let res: merkle::ProofResponse = test_query(
+                deps,
+                QueryMsg::MerkleHook(MerkleHookQueryMsg::Proof { nonce: base + i }),
+            );
+            assert_eq!(res.index, i);
+            assert_eq!(res.leaf, leaf(i));
+            assert_eq!(res.root, root.root);
+            assert_eq!(res.count, size);
+
+            // the branch reconstructs the root
+            assert_eq!(
+                MerkleTree::branch_root(res.leaf, res.branch, i as u128),
+                root.root
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(4, 1)]
+    #[case(4, 6)]
+    #[case(4, 15)]
+    #[case(32, 1)]
+    #[case(32, 9)]
+    fn test_proof(#[case] depth: u8, #[case] size: u32) {
+        let mut deps = deps_store_leaves(depth, Some(true));
+
+        for i in 0..size {
+            assert_eq!(insert_leaf(deps.as_mut().storage, leaf(i)).unwrap(), i);
+        }
+
+        assert_proofs(deps.as_ref(), 0, size, leaf);
+    }
+
+    #[test]
+    fn test_proof_after_reset() {
+        let mut deps = deps_store_leaves(4, Some(true));
+
+        for i in 0..5 {
+            insert_leaf(deps.as_mut().storage, leaf(i)).unwrap();
+        }
+
+        deps.querier.update_wasm(mock_nonce(5));
+        reset_tree(deps.as_mut(), "owner").unwrap();
+
+        // nodes of the previous epoch are left in storage but never served
+        let leaf = |i: u32| leaf(i + 100);
+        for i in 0..3 {
+            insert_leaf(deps.as_mut().storage, leaf(i)).unwrap();
+        }
+
+        assert_proofs(deps.as_ref(), 5, 3, leaf);
+
+        // messages of the archived epoch have no proof against the current tree
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::MerkleHook(MerkleHookQueryMsg::Proof { nonce: 4 }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::invalid_args("nonce 4 is not in the current epoch. base: 5, count: 3")
+        );
+    }
+
+    #[rstest]
+    #[case(None, 0, ContractError::invalid_config("leaves are not stored"))]
+    #[case(Some(false), 0, ContractError::invalid_config("leaves are not stored"))]
+    #[case(
+        Some(true),
+        2,
+        ContractError::invalid_args("nonce 2 is not in the current epoch. base: 0, count: 2")
+    )]
+    fn test_proof_unavailable(
+        #[case] store_leaves: Option<bool>,
+        #[case] index: u32,
+        #[case] expected: ContractError,
+    ) {
+        let mut deps = deps_store_leaves(4, store_leaves);
+
+        for i in 0..2 {
+            insert_leaf(deps.as_mut().storage, leaf(i)).unwrap();
+        }
+
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::MerkleHook(MerkleHookQueryMsg::Proof { nonce: index }),
+        )
+        .unwrap_err();
+        assert_eq!(err, expected);
+
+        // the tree itself is kept either way
+        assert_eq!(MESSAGE_TREE.load(deps.as_ref().storage).unwrap().count, 2);
+    }
 }
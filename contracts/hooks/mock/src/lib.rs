@@ -0,0 +1,334 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    ensure, ensure_eq, to_json_binary, Coin, Deps, DepsMut, Empty, Env, Event, HexBinary,
+    MessageInfo, QueryResponse, Response, StdError,
+};
+use cw_storage_plus::{Item, Map};
+use hpl_interface::{
+    hook::{
+        mock::{
+            ExecuteMsg, InstantiateMsg, LastPostDispatchResponse, MockHookQueryMsg, QueryMsg,
+            QuoteResponse,
+        },
+        HookQueryMsg, HookType, HookTypeResponse, InstantiateResponse, MailboxResponse,
+        PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
+    },
+    to_binary,
+    types::Message,
+};
+use hpl_ownable::get_owner;
+
+#[cfg(test)]
+mod multitest;
+
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
+
+// version info for migration info
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const QUOTES_PREFIX: &str = "quotes";
+pub const QUOTES: Map<u32, Vec<Coin>> = Map::new(QUOTES_PREFIX);
+
+pub const LAST_POST_DISPATCH_KEY: &str = "last_post_dispatch";
+pub const LAST_POST_DISPATCH: Item<HexBinary> = Item::new(LAST_POST_DISPATCH_KEY);
+
+fn new_event(name: &str) -> Event {
+    Event::new(format!("hpl_hook_mock::{}", name))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let owner = deps.api.addr_validate(&msg.owner)?;
+
+    hpl_ownable::initialize(deps.storage, &owner)?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox: None,
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::SetQuote { domain, coins } => {
+            ensure_eq!(
+                get_owner(deps.storage)?,
+                info.sender,
+                ContractError::Unauthorized {}
+            );
+
+            let quote = if coins.is_empty() {
+                QUOTES.remove(deps.storage, domain);
+                "none".to_string()
+            } else {
+                QUOTES.save(deps.storage, domain, &coins)?;
+                coins
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+
+            Ok(Response::new().add_event(
+                new_event("set_quote")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("domain", domain.to_string())
+                    .add_attribute("coins", quote),
+            ))
+        }
+        ExecuteMsg::PostDispatch(PostDispatchMsg { message, .. }) => {
+            let message = Message::try_from(message)?;
+            let quote = QuoteDispatchResponse {
+                fees: QUOTES
+                    .may_load(deps.storage, message.dest_domain)?
+                    .unwrap_or_default(),
+            };
+
+            ensure!(
+                quote.covered_by(&info.funds),
+                StdError::generic_err("insufficient funds")
+            );
+
+            let id = message.id();
+            LAST_POST_DISPATCH.save(deps.storage, &id)?;
+
+            Ok(Response::new().add_event(
+                new_event("post_dispatch")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("message_id", id.to_hex()),
+            ))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+    match msg {
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
+        QueryMsg::Hook(msg) => match msg {
+            HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::Unused,
+            })?),
+            HookQueryMsg::QuoteDispatch(msg) => to_binary(quote_dispatch(deps, msg)),
+        },
+        QueryMsg::MockHook(msg) => match msg {
+            MockHookQueryMsg::Quote { domain } => to_binary(get_quote(deps, domain)),
+            MockHookQueryMsg::LastPostDispatch {} => to_binary(get_last_post_dispatch(deps)),
+        },
+    }
+}
+
+fn get_mailbox(_deps: Deps) -> Result<MailboxResponse, ContractError> {
+    Ok(MailboxResponse {
+        mailbox: "unrestricted".to_string(),
+    })
+}
+
+fn quote_dispatch(
+    deps: Deps,
+    req: QuoteDispatchMsg,
+) -> Result<QuoteDispatchResponse, ContractError> {
+    let message = Message::try_from(req.message)?;
+
+    Ok(QuoteDispatchResponse {
+        fees: get_quote(deps, message.dest_domain)?.coins,
+    })
+}
+
+fn get_quote(deps: Deps, domain: u32) -> Result<QuoteResponse, ContractError> {
+    Ok(QuoteResponse {
+        coins: QUOTES.may_load(deps.storage, domain)?.unwrap_or_default(),
+    })
+}
+
+fn get_last_post_dispatch(deps: Deps) -> Result<LastPostDispatchResponse, ContractError> {
+    Ok(LastPostDispatchResponse {
+        message_id: LAST_POST_DISPATCH.may_load(deps.storage)?,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_schema::serde::{de::DeserializeOwned, Serialize};
+    use cosmwasm_std::{
+        coin, coins, from_json,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        to_json_binary, OwnedDeps,
+    };
+    use ibcx_test_utils::gen_bz;
+    use rstest::{fixture, rstest};
+
+    use super::*;
+
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    const DEST_DOMAIN: u32 = 26657;
+
+    fn query<S: Serialize, T: DeserializeOwned>(deps: Deps, msg: S) -> T {
+        let req: QueryMsg = from_json(to_json_binary(&msg).unwrap()).unwrap();
+        let res = crate::query(deps, mock_env(), req).unwrap();
+        from_json(res).unwrap()
+    }
+
+    fn message(dest_domain: u32) -> Message {
+        Message {
+            version: 3,
+            nonce: 1,
+            origin_domain: 1,
+            sender: gen_bz(32),
+            dest_domain,
+            recipient: gen_bz(32),
+            body: gen_bz(100),
+        }
+    }
+
+    #[fixture]
+    fn deps() -> TestDeps {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            ExecuteMsg::SetQuote {
+                domain: DEST_DOMAIN,
+                coins: coins(100, "uosmo"),
+            },
+        )
+        .unwrap();
+
+        deps
+    }
+
+    #[rstest]
+    #[case("owner")]
+    #[should_panic(expected = "unauthorized")]
+    #[case("someone")]
+    fn test_set_quote(mut deps: TestDeps, #[case] sender: &str) {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::SetQuote {
+                domain: 1,
+                coins: coins(10, "uatom"),
+            },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let res: QuoteResponse = query(
+            deps.as_ref(),
+            QueryMsg::MockHook(MockHookQueryMsg::Quote { domain: 1 }),
+        );
+        assert_eq!(res.coins, coins(10, "uatom"));
+
+        // an empty quote makes the domain free again
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::SetQuote {
+                domain: 1,
+                coins: vec![],
+            },
+        )
+        .unwrap();
+        assert!(!QUOTES.has(deps.as_ref().storage, 1));
+    }
+
+    #[rstest]
+    #[case(DEST_DOMAIN, coins(100, "uosmo"))]
+    #[case(DEST_DOMAIN, vec![coin(150, "uosmo"), coin(1, "uatom")])]
+    #[should_panic(expected = "insufficient funds")]
+    #[case(DEST_DOMAIN, coins(99, "uosmo"))]
+    #[should_panic(expected = "insufficient funds")]
+    #[case(DEST_DOMAIN, coins(100, "uatom"))]
+    #[case(1, vec![])]
+    fn test_post_dispatch(mut deps: TestDeps, #[case] dest_domain: u32, #[case] funds: Vec<Coin>) {
+        let message = message(dest_domain);
+
+        let res: LastPostDispatchResponse = query(
+            deps.as_ref(),
+            QueryMsg::MockHook(MockHookQueryMsg::LastPostDispatch {}),
+        );
+        assert_eq!(res.message_id, None);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mailbox", &funds),
+            ExecuteMsg::PostDispatch(PostDispatchMsg {
+                metadata: HexBinary::default(),
+                message: message.clone().into(),
+            }),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        let res: LastPostDispatchResponse = query(
+            deps.as_ref(),
+            QueryMsg::MockHook(MockHookQueryMsg::LastPostDispatch {}),
+        );
+        assert_eq!(res.message_id, Some(message.id()));
+    }
+
+    #[rstest]
+    fn test_query(deps: TestDeps) {
+        let res: MailboxResponse = query(deps.as_ref(), QueryMsg::Hook(HookQueryMsg::Mailbox {}));
+        assert_eq!("unrestricted", res.mailbox.as_str());
+
+        for (dest_domain, fees) in [(DEST_DOMAIN, coins(100, "uosmo")), (1, vec![])] {
+            let res: QuoteDispatchResponse = query(
+                deps.as_ref(),
+                QueryMsg::Hook(HookQueryMsg::QuoteDispatch(QuoteDispatchMsg {
+                    metadata: HexBinary::default(),
+                    message: message(dest_domain).into(),
+                })),
+            );
+            assert_eq!(res.fees, fees);
+        }
+    }
+}
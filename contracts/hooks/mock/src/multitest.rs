@@ -0,0 +1,203 @@
+use cosmwasm_std::{coin, coins, from_json, Addr, Coin, Empty, HexBinary};
+use cw_multi_test::{
+    addons::{MockAddressGenerator, MockApiBech32},
+    App, AppBuilder, BankKeeper, Contract, ContractWrapper, Executor, WasmKeeper,
+};
+use hpl_interface::{
+    core::mailbox::{self, DispatchMsg, DispatchResponse, MailboxHookQueryMsg},
+    hook::{
+        aggregate,
+        mock::{ExecuteMsg, InstantiateMsg, LastPostDispatchResponse, MockHookQueryMsg, QueryMsg},
+        QuoteDispatchResponse,
+    },
+};
+use ibcx_test_utils::gen_bz;
+
+use crate::{execute, instantiate, query};
+
+type TestApp = App<BankKeeper, MockApiBech32>;
+
+const HRP: &str = "osmo";
+const DENOM: &str = "uosmo";
+const LOCAL_DOMAIN: u32 = 26657;
+const DEST_DOMAIN: u32 = 1;
+
+fn mock_hook() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn aggregate() -> Box<dyn Contract<Empty>> {
+    use hpl_hook_aggregate::{execute, instantiate, query};
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn mailbox() -> Box<dyn Contract<Empty>> {
+    use hpl_mailbox::contract::{execute, instantiate, query};
+
+    Box::new(ContractWrapper::new(
+        execute::<Empty>,
+        instantiate::<Empty>,
+        query::<Empty>,
+    ))
+}
+
+struct Suite {
+    app: TestApp,
+    owner: Addr,
+    mailbox: Addr,
+    hooks: Vec<Addr>,
+}
+
+impl Suite {
+    fn quote(&self) -> anyhow::Result<Vec<Coin>> {
+        let res: QuoteDispatchResponse = self.app.wrap().query_wasm_smart(
+            &self.mailbox,
+            &mailbox::QueryMsg::Hook(MailboxHookQueryMsg::QuoteDispatch {
+                sender: self.owner.to_string(),
+                msg: DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(32)),
+            }),
+        )?;
+
+        Ok(res.fees)
+    }
+
+    fn dispatch(&mut self, funds: &[Coin]) -> anyhow::Result<HexBinary> {
+        let res = self.app.execute_contract(
+            self.owner.clone(),
+            self.mailbox.clone(),
+            &mailbox::ExecuteMsg::Dispatch(DispatchMsg::new(DEST_DOMAIN, gen_bz(32), gen_bz(32))),
+            funds,
+        )?;
+
+        let data: DispatchResponse = from_json(res.data.unwrap())?;
+        Ok(data.message_id)
+    }
+
+    fn last_post_dispatch(&self, hook: &Addr) -> anyhow::Result<Option<HexBinary>> {
+        let res: LastPostDispatchResponse = self.app.wrap().query_wasm_smart(
+            hook,
+            &QueryMsg::MockHook(MockHookQueryMsg::LastPostDispatch {}),
+        )?;
+
+        Ok(res.message_id)
+    }
+}
+
+/// Mailbox with an aggregate of two mock hooks as its default hook and a free one as required
+fn setup() -> anyhow::Result<Suite> {
+    let mut app = AppBuilder::default()
+        .with_api(MockApiBech32::new(HRP))
+        .with_wasm(WasmKeeper::default().with_address_generator(MockAddressGenerator))
+        .build(|router, _, storage| {
+            let owner = MockApiBech32::new(HRP).addr_make("owner");
+            router
+                .bank
+                .init_balance(storage, &owner, coins(1_000, DENOM))
+                .unwrap();
+        });
+
+    let owner = app.api().addr_make("owner");
+
+    let hook_code_id = app.store_code(mock_hook());
+    let mut hooks = vec![];
+    for (label, quote) in [("hook_a", 100), ("hook_b", 50), ("required", 0)] {
+        let hook = app.instantiate_contract(
+            hook_code_id,
+            owner.clone(),
+            &InstantiateMsg {
+                owner: owner.to_string(),
+            },
+            &[],
+            label,
+            None,
+        )?;
+        app.execute_contract(
+            owner.clone(),
+            hook.clone(),
+            &ExecuteMsg::SetQuote {
+                domain: DEST_DOMAIN,
+                coins: if quote > 0 {
+                    coins(quote, DENOM)
+                } else {
+                    vec![]
+                },
+            },
+            &[],
+        )?;
+
+        hooks.push(hook);
+    }
+
+    let aggregate_code_id = app.store_code(aggregate());
+    let aggregate = app.instantiate_contract(
+        aggregate_code_id,
+        owner.clone(),
+        &aggregate::InstantiateMsg {
+            owner: owner.to_string(),
+            hooks: vec![hooks[0].to_string(), hooks[1].to_string()],
+        },
+        &[],
+        "aggregate",
+        None,
+    )?;
+
+    let mailbox_code_id = app.store_code(mailbox());
+    let mailbox = app.instantiate_contract(
+        mailbox_code_id,
+        owner.clone(),
+        &mailbox::InstantiateMsg {
+            hrp: HRP.into(),
+            owner: owner.to_string(),
+            domain: LOCAL_DOMAIN,
+            default_ism: None,
+            default_hook: Some(aggregate.to_string()),
+        },
+        &[],
+        "mailbox",
+        None,
+    )?;
+    app.execute_contract(
+        owner.clone(),
+        mailbox.clone(),
+        &mailbox::ExecuteMsg::SetRequiredHook {
+            hook: hooks[2].to_string(),
+            unchecked: false,
+        },
+        &[],
+    )?;
+
+    Ok(Suite {
+        app,
+        owner,
+        mailbox,
+        hooks,
+    })
+}
+
+#[test]
+fn test_quote_through_aggregate() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+
+    // no oracle involved, the quotes of both hooks add up
+    let quote = suite.quote()?;
+    assert_eq!(quote, coins(150, DENOM));
+
+    // the mailbox holds the dispatch to the summed quote
+    let err = suite.dispatch(&coins(149, DENOM)).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("insufficient dispatch funds"));
+
+    let id = suite.dispatch(&quote)?;
+    for hook in suite.hooks.clone() {
+        assert_eq!(suite.last_post_dispatch(&hook)?, Some(id.clone()));
+    }
+
+    // the hooks were paid what they quoted
+    let balance = suite.app.wrap().query_balance(&suite.hooks[0], DENOM)?;
+    assert_eq!(balance, coin(100, DENOM));
+
+    Ok(())
+}
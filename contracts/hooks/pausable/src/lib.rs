@@ -1,30 +1,19 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, Deps, DepsMut, Env, Event, MessageInfo, QueryResponse, Response, StdError,
+    ensure, to_json_binary, Deps, DepsMut, Empty, Env, Event, MessageInfo, QueryResponse, Response,
 };
 use hpl_interface::{
     hook::{
         pausable::{ExecuteMsg, InstantiateMsg, QueryMsg},
-        HookQueryMsg, MailboxResponse, QuoteDispatchResponse,
+        HookQueryMsg, HookType, HookTypeResponse, InstantiateResponse, MailboxResponse,
+        QuoteDispatchResponse,
     },
     to_binary,
 };
 
-#[derive(thiserror::Error, Debug, PartialEq)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
-
-    #[error("unauthorized")]
-    Unauthorized {},
-
-    #[error("hook paused")]
-    Paused {},
-}
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
 
 // version info for migration info
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -48,14 +37,20 @@ pub fn instantiate(
     hpl_ownable::initialize(deps.storage, &owner)?;
     hpl_pausable::initialize(deps.storage, &msg.paused)?;
 
-    Ok(Response::new().add_event(
-        new_event("initialize")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox: None,
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -63,7 +58,6 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         ExecuteMsg::Pausable(msg) => Ok(hpl_pausable::handle(deps, env, info, msg)?),
         ExecuteMsg::PostDispatch(_) => {
             ensure!(
@@ -78,12 +72,16 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     match msg {
         QueryMsg::Pausable(msg) => Ok(hpl_pausable::handle_query(deps, env, msg)?),
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Hook(msg) => match msg {
             HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::Pausable,
+            })?),
             HookQueryMsg::QuoteDispatch(_) => to_binary(quote_dispatch()),
         },
     }
@@ -99,6 +97,13 @@ fn quote_dispatch() -> Result<QuoteDispatchResponse, ContractError> {
     Ok(QuoteDispatchResponse { fees: vec![] })
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_schema::serde::{de::DeserializeOwned, Serialize};
@@ -151,9 +156,34 @@ mod test {
         assert_eq!("owner", get_owner(deps.as_ref().storage).unwrap().as_str());
     }
 
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                paused: false,
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: addr("owner"),
+                mailbox: None
+            }
+        );
+    }
+
     #[rstest]
     #[case("owner", false)]
-    #[should_panic(expected = "hook paused")]
+    #[should_panic(expected = "paused")]
     #[case("owner", true)]
     fn test_post_dispatch(mut deps: TestDeps, #[case] sender: &str, #[case] paused: bool) {
         if paused {
@@ -0,0 +1,817 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    ensure, ensure_eq, to_json_binary, Addr, Deps, DepsMut, Empty, Env, Event, HexBinary,
+    MessageInfo, Order, QueryResponse, Response, StdError, StdResult, Storage, Uint256,
+};
+use cw_storage_plus::{Item, Map};
+use hpl_error::HplError;
+use hpl_interface::{
+    core::mailbox::{LatestDispatchedIdResponse, MailboxQueryMsg},
+    hook::{
+        ratelimit::{
+            AmountRangeResponse, ExecuteMsg, InstantiateMsg, QueryMsg, RateLimitHookMsg,
+            RateLimitHookQueryMsg, RateLimitResponse,
+        },
+        AuthorizedCallersResponse, HookQueryMsg, HookType, HookTypeResponse, InstantiateResponse,
+        MailboxResponse, PostDispatchMsg, QuoteDispatchResponse,
+    },
+    to_binary,
+    types::{split_offsets, CanonicalSender, Message},
+};
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error(transparent)]
+    Hpl(#[from] HplError),
+
+    #[error(
+        "[E200] rate limited. domain: {domain}, available: {available}, requested: {requested}"
+    )]
+    RateLimited {
+        domain: u32,
+        available: Uint256,
+        requested: Uint256,
+    },
+}
+
+hpl_error::impl_from_hpl!(ContractError);
+
+impl ContractError {
+    pub fn code(&self) -> u16 {
+        match self {
+            ContractError::Hpl(err) => err.code(),
+            ContractError::RateLimited { .. } => 200,
+        }
+    }
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct AmountRange {
+    pub offset: u32,
+    pub length: u32,
+}
+
+#[cosmwasm_schema::cw_serde]
+pub struct Bucket {
+    pub capacity: Uint256,
+    pub refill_per_second: Uint256,
+    pub available: Uint256,
+    pub last_refill: u64,
+}
+
+impl Bucket {
+    pub fn refill(&mut self, now: u64) {
+        let elapsed = Uint256::from(now.saturating_sub(self.last_refill));
+
+        self.available = self
+            .available
+            .saturating_add(self.refill_per_second.saturating_mul(elapsed))
+            .min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+// version info for migration info
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const MAILBOX_KEY: &str = "mailbox";
+pub const MAILBOX: Item<Addr> = Item::new(MAILBOX_KEY);
+
+/// Hooks wrapping this one that may post dispatch besides the mailbox
+pub const AUTHORIZED_CALLERS_PREFIX: &str = "authorized_callers";
+pub const AUTHORIZED_CALLERS: Map<Addr, ()> = Map::new(AUTHORIZED_CALLERS_PREFIX);
+
+pub const AMOUNT_RANGE_KEY: &str = "amount_range";
+pub const AMOUNT_RANGE: Item<AmountRange> = Item::new(AMOUNT_RANGE_KEY);
+
+pub const LATEST_LIMITED_ID_KEY: &str = "latest_limited_id";
+pub const LATEST_LIMITED_ID: Item<HexBinary> = Item::new(LATEST_LIMITED_ID_KEY);
+
+/// Keyed by (message sender, destination domain), so routes of different tokens
+/// never share a bucket
+pub const BUCKETS_PREFIX: &str = "buckets";
+pub const BUCKETS: Map<(&[u8], u32), Bucket> = Map::new(BUCKETS_PREFIX);
+
+fn new_event(name: &str) -> Event {
+    Event::new(format!("hpl_hook_ratelimit::{}", name))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    ensure!(
+        (1..=32).contains(&msg.amount_length),
+        HplError::InvalidConfig {
+            reason: format!(
+                "amount length must be in 1..=32. got: {}",
+                msg.amount_length
+            )
+        }
+    );
+
+    let owner = deps.api.addr_validate(&msg.owner)?;
+    let mailbox = deps.api.addr_validate(&msg.mailbox)?;
+
+    hpl_ownable::initialize(deps.storage, &owner)?;
+    MAILBOX.save(deps.storage, &mailbox)?;
+    AMOUNT_RANGE.save(
+        deps.storage,
+        &AmountRange {
+            offset: msg.amount_offset,
+            length: msg.amount_length,
+        },
+    )?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox: Some(mailbox.clone()),
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("mailbox", mailbox)
+                .add_attribute("amount_offset", msg.amount_offset.to_string())
+                .add_attribute("amount_length", msg.amount_length.to_string()),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::RateLimitHook(RateLimitHookMsg::SetRateLimit {
+            sender,
+            domain,
+            capacity,
+            refill_per_second,
+        }) => {
+            let owner = hpl_ownable::get_owner(deps.storage)?;
+            ensure_eq!(owner, info.sender, HplError::Unauthorized {});
+
+            let route = CanonicalSender::from_addr(&sender)?;
+            let key = (route.as_bytes().as_slice(), domain);
+            let now = env.block.time.seconds();
+
+            // keep the consumed capacity of an existing bucket, start new ones full
+            let available = match BUCKETS.may_load(deps.storage, key)? {
+                Some(mut bucket) => {
+                    bucket.refill(now);
+                    bucket.available.min(capacity)
+                }
+                None => capacity,
+            };
+
+            BUCKETS.save(
+                deps.storage,
+                key,
+                &Bucket {
+                    capacity,
+                    refill_per_second,
+                    available,
+                    last_refill: now,
+                },
+            )?;
+
+            Ok(Response::new().add_event(
+                new_event("set_rate_limit")
+                    .add_attribute("route", sender)
+                    .add_attribute("domain", domain.to_string())
+                    .add_attribute("capacity", capacity)
+                    .add_attribute("refill_per_second", refill_per_second),
+            ))
+        }
+        ExecuteMsg::RateLimitHook(RateLimitHookMsg::SetAuthorizedCaller { caller, authorized }) => {
+            let owner = hpl_ownable::get_owner(deps.storage)?;
+            ensure_eq!(owner, info.sender, HplError::Unauthorized {});
+
+            let caller = deps.api.addr_validate(&caller)?;
+
+            if authorized {
+                AUTHORIZED_CALLERS.save(deps.storage, caller.clone(), &())?;
+            } else {
+                AUTHORIZED_CALLERS.remove(deps.storage, caller.clone());
+            }
+
+            Ok(Response::new().add_event(
+                new_event("set_authorized_caller")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("caller", caller)
+                    .add_attribute("authorized", authorized.to_string()),
+            ))
+        }
+        ExecuteMsg::PostDispatch(PostDispatchMsg { message, .. }) => {
+            let mailbox = MAILBOX.load(deps.storage)?;
+
+            ensure!(
+                info.sender == mailbox || AUTHORIZED_CALLERS.has(deps.storage, info.sender.clone()),
+                HplError::forbidden("sender is not mailbox")
+            );
+
+            let latest_dispatch = deps
+                .querier
+                .query_wasm_smart::<LatestDispatchedIdResponse>(
+                    &mailbox,
+                    &MailboxQueryMsg::LatestDispatchId {}.wrap(),
+                )?;
+
+            let decoded_msg = Message::try_from(message)?;
+            let message_id = decoded_msg.id();
+
+            ensure_eq!(
+                latest_dispatch.message_id,
+                message_id,
+                HplError::forbidden("message is not dispatching")
+            );
+            ensure!(
+                LATEST_LIMITED_ID.may_load(deps.storage)? != Some(message_id.clone()),
+                HplError::forbidden("message is already rate limited")
+            );
+
+            let domain = decoded_msg.dest_domain;
+            let key = (decoded_msg.sender.as_slice(), domain);
+
+            let event = new_event("post_dispatch")
+                .add_attribute("message_id", message_id.to_hex())
+                .add_attribute("domain", domain.to_string());
+
+            // only configured routes are limited, other senders' bodies are never decoded
+            let Some(mut bucket) = BUCKETS.may_load(deps.storage, key)? else {
+                return Ok(Response::new().add_event(event));
+            };
+
+            let requested = decode_amount(deps.storage, &decoded_msg.body)?;
+
+            bucket.refill(env.block.time.seconds());
+            ensure!(
+                requested <= bucket.available,
+                ContractError::RateLimited {
+                    domain,
+                    available: bucket.available,
+                    requested,
+                }
+            );
+            bucket.available -= requested;
+
+            BUCKETS.save(deps.storage, key, &bucket)?;
+            LATEST_LIMITED_ID.save(deps.storage, &message_id)?;
+
+            Ok(Response::new().add_event(
+                event
+                    .add_attribute("requested", requested)
+                    .add_attribute("available", bucket.available),
+            ))
+        }
+    }
+}
+
+fn decode_amount(storage: &dyn Storage, body: &HexBinary) -> Result<Uint256, ContractError> {
+    let range = AMOUNT_RANGE.load(storage)?;
+
+    let start = range.offset as usize;
+    let length = range.length as usize;
+    let end = start.checked_add(length).ok_or_else(|| {
+        StdError::generic_err(format!(
+            "invalid amount range. offset: {start}, length: {length}"
+        ))
+    })?;
+
+    let amount = &split_offsets(body, &[(start, end)])?[0];
+
+    let mut bz = [0u8; 32];
+    bz[32 - length..].copy_from_slice(amount);
+
+    Ok(Uint256::from_be_bytes(bz))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+    match msg {
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
+        QueryMsg::Hook(msg) => match msg {
+            HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::RateLimited,
+            })?),
+            HookQueryMsg::QuoteDispatch(_) => to_binary(quote_dispatch()),
+        },
+        QueryMsg::RateLimitHook(msg) => match msg {
+            RateLimitHookQueryMsg::RateLimit { sender, domain } => {
+                to_binary(get_rate_limit(deps, env, sender, domain))
+            }
+            RateLimitHookQueryMsg::AuthorizedCallers {} => to_binary(get_authorized_callers(deps)),
+            RateLimitHookQueryMsg::AmountRange {} => to_binary(get_amount_range(deps)),
+        },
+    }
+}
+
+fn get_mailbox(deps: Deps) -> Result<MailboxResponse, ContractError> {
+    Ok(MailboxResponse {
+        mailbox: MAILBOX.load(deps.storage)?.into(),
+    })
+}
+
+fn quote_dispatch() -> Result<QuoteDispatchResponse, ContractError> {
+    Ok(QuoteDispatchResponse { fees: vec![] })
+}
+
+fn get_rate_limit(
+    deps: Deps,
+    env: Env,
+    sender: String,
+    domain: u32,
+) -> Result<RateLimitResponse, ContractError> {
+    let route = CanonicalSender::from_addr(&sender)?;
+    let mut bucket = BUCKETS.load(deps.storage, (route.as_bytes().as_slice(), domain))?;
+    bucket.refill(env.block.time.seconds());
+
+    Ok(RateLimitResponse {
+        capacity: bucket.capacity,
+        refill_per_second: bucket.refill_per_second,
+        available: bucket.available,
+        last_refill: bucket.last_refill,
+    })
+}
+
+fn get_authorized_callers(deps: Deps) -> Result<AuthorizedCallersResponse, ContractError> {
+    let callers = AUTHORIZED_CALLERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|v| v.map(String::from))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AuthorizedCallersResponse { callers })
+}
+
+fn get_amount_range(deps: Deps) -> Result<AmountRangeResponse, ContractError> {
+    let range = AMOUNT_RANGE.load(deps.storage)?;
+
+    Ok(AmountRangeResponse {
+        offset: range.offset,
+        length: range.length,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{
+        from_json,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        to_json_binary, ContractResult, OwnedDeps, SystemResult, WasmQuery,
+    };
+    use hpl_interface::{
+        build_test_querier,
+        core::mailbox,
+        hook::{
+            ratelimit::{WARP_AMOUNT_LENGTH, WARP_AMOUNT_OFFSET},
+            QuoteDispatchMsg,
+        },
+        types::bech32_encode,
+        warp,
+    };
+    use ibcx_test_utils::{addr, gen_bz};
+    use rstest::{fixture, rstest};
+
+    use super::*;
+
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    build_test_querier!(crate::query);
+
+    const DOMAIN: u32 = 26657;
+
+    fn env_at(seconds: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = cosmwasm_std::Timestamp::from_seconds(seconds);
+        env
+    }
+
+    fn route() -> Addr {
+        bech32_encode("osmo", &[0xab; 32]).unwrap()
+    }
+
+    fn transfer_msg(domain: u32, nonce: u32, amount: u128) -> Message {
+        Message {
+            version: 3,
+            nonce,
+            origin_domain: 1,
+            sender: CanonicalSender::from_addr(route().as_str()).unwrap().into(),
+            dest_domain: domain,
+            recipient: gen_bz(32),
+            body: warp::Message {
+                recipient: gen_bz(32),
+                amount: amount.into(),
+                metadata: HexBinary::default(),
+            }
+            .into(),
+        }
+    }
+
+    fn mock_mailbox(deps: &mut TestDeps, latest: &Message) {
+        let res = LatestDispatchedIdResponse {
+            message_id: latest.id(),
+            nonce: latest.nonce,
+        };
+
+        deps.querier.update_wasm(move |query| {
+            let msg = match query {
+                WasmQuery::Smart { msg, .. } => from_json(msg).unwrap(),
+                _ => unreachable!("unsupported query"),
+            };
+
+            match msg {
+                mailbox::QueryMsg::Mailbox(MailboxQueryMsg::LatestDispatchId {}) => {
+                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+                }
+                _ => unreachable!("unsupported query"),
+            }
+        });
+    }
+
+    fn post_dispatch(
+        deps: &mut TestDeps,
+        env: Env,
+        msg: Message,
+    ) -> Result<Response, ContractError> {
+        post_dispatch_from(deps, env, "mailbox", msg)
+    }
+
+    fn post_dispatch_from(
+        deps: &mut TestDeps,
+        env: Env,
+        caller: &str,
+        msg: Message,
+    ) -> Result<Response, ContractError> {
+        mock_mailbox(deps, &msg);
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(caller, &[]),
+            ExecuteMsg::PostDispatch(PostDispatchMsg {
+                metadata: HexBinary::default(),
+                message: msg.into(),
+            }),
+        )
+    }
+
+    fn set_rate_limit(
+        deps: &mut TestDeps,
+        env: Env,
+        sender: &Addr,
+        capacity: u128,
+        refill_per_second: u128,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(sender.as_str(), &[]),
+            ExecuteMsg::RateLimitHook(RateLimitHookMsg::SetRateLimit {
+                sender: route().into(),
+                domain: DOMAIN,
+                capacity: capacity.into(),
+                refill_per_second: refill_per_second.into(),
+            }),
+        )
+    }
+
+    fn available(deps: &TestDeps, env: Env) -> Uint256 {
+        get_rate_limit(deps.as_ref(), env, route().into(), DOMAIN)
+            .unwrap()
+            .available
+    }
+
+    #[fixture]
+    fn deps(
+        #[default(WARP_AMOUNT_OFFSET)] amount_offset: u32,
+        #[default(WARP_AMOUNT_LENGTH)] amount_length: u32,
+    ) -> TestDeps {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                mailbox: "mailbox".to_string(),
+                amount_offset,
+                amount_length,
+            },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        deps
+    }
+
+    #[rstest]
+    #[case(32, 32)]
+    #[case(48, 16)]
+    #[should_panic(expected = "invalid config. reason: amount length must be in 1..=32. got: 0")]
+    #[case(32, 0)]
+    #[should_panic(expected = "invalid config. reason: amount length must be in 1..=32. got: 33")]
+    #[case(32, 33)]
+    fn test_init(#[case] amount_offset: u32, #[case] amount_length: u32) {
+        let deps = deps(amount_offset, amount_length);
+
+        let res: AmountRangeResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::RateLimitHook(RateLimitHookQueryMsg::AmountRange {}),
+        );
+        assert_eq!(res.offset, amount_offset);
+        assert_eq!(res.length, amount_length);
+
+        let res: MailboxResponse =
+            test_query(deps.as_ref(), QueryMsg::Hook(HookQueryMsg::Mailbox {}));
+        assert_eq!("mailbox", res.mailbox.as_str());
+
+        let res: QuoteDispatchResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::Hook(HookQueryMsg::QuoteDispatch(QuoteDispatchMsg::default())),
+        );
+        assert_eq!(res.fees, vec![]);
+    }
+
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                mailbox: "mailbox".to_string(),
+                amount_offset: WARP_AMOUNT_OFFSET,
+                amount_length: WARP_AMOUNT_LENGTH,
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: addr("owner"),
+                mailbox: Some(addr("mailbox"))
+            }
+        );
+    }
+
+    #[rstest]
+    #[case(addr("owner"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("deployer"))]
+    fn test_set_rate_limit(mut deps: TestDeps, #[case] sender: Addr) {
+        set_rate_limit(&mut deps, env_at(100), &sender, 1000, 10)
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        let res: RateLimitResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::RateLimitHook(RateLimitHookQueryMsg::RateLimit {
+                sender: route().into(),
+                domain: DOMAIN,
+            }),
+        );
+        assert_eq!(res.capacity, Uint256::from(1000u128));
+        assert_eq!(res.refill_per_second, Uint256::from(10u128));
+        assert_eq!(res.available, Uint256::from(1000u128));
+    }
+
+    #[rstest]
+    fn test_update_rate_limit(mut deps: TestDeps) {
+        let owner = addr("owner");
+
+        set_rate_limit(&mut deps, env_at(100), &owner, 1000, 10).unwrap();
+        post_dispatch(&mut deps, env_at(100), transfer_msg(DOMAIN, 0, 900)).unwrap();
+
+        // consumed capacity is kept across updates
+        set_rate_limit(&mut deps, env_at(110), &owner, 2000, 1).unwrap();
+        assert_eq!(available(&deps, env_at(110)), Uint256::from(200u128));
+
+        // and clamped by the new capacity
+        set_rate_limit(&mut deps, env_at(110), &owner, 50, 1).unwrap();
+        assert_eq!(available(&deps, env_at(110)), Uint256::from(50u128));
+    }
+
+    #[rstest]
+    fn test_refill(mut deps: TestDeps) {
+        set_rate_limit(&mut deps, env_at(100), &addr("owner"), 100, 2).unwrap();
+
+        post_dispatch(&mut deps, env_at(100), transfer_msg(DOMAIN, 0, 60)).unwrap();
+        assert_eq!(available(&deps, env_at(100)), Uint256::from(40u128));
+
+        let err = post_dispatch(&mut deps, env_at(100), transfer_msg(DOMAIN, 1, 50)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RateLimited {
+                domain: DOMAIN,
+                available: Uint256::from(40u128),
+                requested: Uint256::from(50u128),
+            }
+        );
+
+        // 5 seconds later, 10 more is available
+        assert_eq!(available(&deps, env_at(105)), Uint256::from(50u128));
+        post_dispatch(&mut deps, env_at(105), transfer_msg(DOMAIN, 1, 50)).unwrap();
+        assert_eq!(available(&deps, env_at(105)), Uint256::from(0u128));
+
+        // refill never exceeds capacity
+        assert_eq!(available(&deps, env_at(10_000)), Uint256::from(100u128));
+    }
+
+    #[rstest]
+    fn test_post_dispatch_unlimited_domain(mut deps: TestDeps) {
+        set_rate_limit(&mut deps, env_at(100), &addr("owner"), 100, 1).unwrap();
+
+        post_dispatch(
+            &mut deps,
+            env_at(100),
+            transfer_msg(DOMAIN + 1, 0, 1_000_000),
+        )
+        .unwrap();
+        assert_eq!(available(&deps, env_at(100)), Uint256::from(100u128));
+    }
+
+    #[rstest]
+    fn test_post_dispatch_unlimited_sender(mut deps: TestDeps) {
+        set_rate_limit(&mut deps, env_at(100), &addr("owner"), 100, 1).unwrap();
+
+        // another app dispatching to the limited domain, with a body too short to hold an amount
+        let mut msg = transfer_msg(DOMAIN, 0, 1_000_000);
+        msg.sender = gen_bz(32);
+        msg.body = gen_bz(8);
+
+        let res = post_dispatch(&mut deps, env_at(100), msg).unwrap();
+        assert!(!res.events[0]
+            .attributes
+            .iter()
+            .any(|v| v.key == "requested"));
+        assert_eq!(available(&deps, env_at(100)), Uint256::from(100u128));
+    }
+
+    #[rstest]
+    #[case("mailbox")]
+    #[case("aggregate")]
+    #[should_panic(expected = "sender is not mailbox")]
+    #[case("someone")]
+    fn test_post_dispatch_caller(mut deps: TestDeps, #[case] caller: &str) {
+        set_rate_limit(&mut deps, env_at(100), &addr("owner"), 100, 1).unwrap();
+
+        execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("owner", &[]),
+            ExecuteMsg::RateLimitHook(RateLimitHookMsg::SetAuthorizedCaller {
+                caller: "aggregate".to_string(),
+                authorized: true,
+            }),
+        )
+        .unwrap();
+
+        post_dispatch_from(&mut deps, env_at(100), caller, transfer_msg(DOMAIN, 0, 10))
+            .map_err(|e| e.to_string())
+            .unwrap();
+        assert_eq!(available(&deps, env_at(100)), Uint256::from(90u128));
+    }
+
+    #[rstest]
+    #[case("owner")]
+    #[should_panic(expected = "unauthorized")]
+    #[case("someone")]
+    fn test_set_authorized_caller(mut deps: TestDeps, #[case] sender: &str) {
+        let set = |deps: &mut TestDeps, authorized: bool| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender, &[]),
+                ExecuteMsg::RateLimitHook(RateLimitHookMsg::SetAuthorizedCaller {
+                    caller: "aggregate".to_string(),
+                    authorized,
+                }),
+            )
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+            let res: AuthorizedCallersResponse = test_query(
+                deps.as_ref(),
+                QueryMsg::RateLimitHook(RateLimitHookQueryMsg::AuthorizedCallers {}),
+            );
+            res.callers
+        };
+
+        assert_eq!(set(&mut deps, true), vec!["aggregate".to_string()]);
+        assert!(set(&mut deps, false).is_empty());
+    }
+
+    #[rstest]
+    #[case(WARP_AMOUNT_OFFSET, WARP_AMOUNT_LENGTH)]
+    #[case(WARP_AMOUNT_OFFSET + 16, 16)]
+    fn test_decode_amount(#[case] amount_offset: u32, #[case] amount_length: u32) {
+        let mut deps = deps(amount_offset, amount_length);
+        set_rate_limit(&mut deps, env_at(100), &addr("owner"), 100, 1).unwrap();
+
+        let res = post_dispatch(&mut deps, env_at(100), transfer_msg(DOMAIN, 0, 30)).unwrap();
+        assert_eq!(
+            res.events[0]
+                .attributes
+                .iter()
+                .find(|v| v.key == "requested")
+                .unwrap()
+                .value,
+            "30"
+        );
+    }
+
+    #[rstest]
+    #[should_panic(expected = "metadata truncated")]
+    #[case(WARP_AMOUNT_OFFSET)]
+    #[should_panic(expected = "metadata truncated")]
+    #[case(u32::MAX)]
+    fn test_post_dispatch_truncated_body(#[case] amount_offset: u32) {
+        let mut deps = deps(amount_offset, WARP_AMOUNT_LENGTH);
+        set_rate_limit(&mut deps, env_at(100), &addr("owner"), 100, 1).unwrap();
+
+        let mut msg = transfer_msg(DOMAIN, 0, 30);
+        msg.body = gen_bz(40);
+
+        post_dispatch(&mut deps, env_at(100), msg)
+            .map_err(|e| e.to_string())
+            .unwrap();
+    }
+
+    #[rstest]
+    fn test_post_dispatch_unauthorized(mut deps: TestDeps) {
+        set_rate_limit(&mut deps, env_at(100), &addr("owner"), 100, 1).unwrap();
+
+        let msg = transfer_msg(DOMAIN, 0, 10);
+        post_dispatch(&mut deps, env_at(100), msg.clone()).unwrap();
+
+        // replay of the same message
+        let err = post_dispatch(&mut deps, env_at(100), msg.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Hpl(HplError::forbidden("message is already rate limited"))
+        );
+
+        // message other than the latest dispatched one
+        mock_mailbox(&mut deps, &transfer_msg(DOMAIN, 1, 10));
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("mailbox", &[]),
+            ExecuteMsg::PostDispatch(PostDispatchMsg {
+                metadata: HexBinary::default(),
+                message: transfer_msg(DOMAIN, 2, 10).into(),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Hpl(HplError::forbidden("message is not dispatching"))
+        );
+
+        assert_eq!(available(&deps, env_at(100)), Uint256::from(90u128));
+    }
+
+    #[rstest]
+    #[case(
+        ContractError::RateLimited {
+            domain: DOMAIN,
+            available: Uint256::from(1u128),
+            requested: Uint256::from(2u128),
+        },
+        200,
+        "[E200] rate limited. domain: 26657, available: 1, requested: 2"
+    )]
+    #[case(HplError::Unauthorized {}.into(), 10, "[E010] unauthorized")]
+    fn test_error_codes(#[case] err: ContractError, #[case] code: u16, #[case] golden: &str) {
+        assert_eq!(err.code(), code);
+        assert_eq!(err.to_string(), golden);
+        assert!(golden.starts_with(&hpl_error::code_prefix(code)));
+    }
+}
@@ -1,8 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, wasm_execute, Addr, Deps, DepsMut, Env, Event, HexBinary, MessageInfo,
-    QueryResponse, Response, StdError, StdResult, Storage,
+    ensure_eq, to_json_binary, wasm_execute, Addr, Deps, DepsMut, Empty, Env, Event, HexBinary,
+    MessageInfo, QueryResponse, Response, StdError, StdResult, Storage,
 };
 
 use cw_storage_plus::Map;
@@ -13,7 +13,8 @@ use hpl_interface::{
             ClearCustomHookMsg, CustomHookResponse, CustomHooksResponse, CustomRoutingHookQueryMsg,
             ExecuteMsg, InstantiateMsg, QueryMsg, RegisterCustomHookMsg,
         },
-        HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
+        HookQueryMsg, HookType, HookTypeResponse, InstantiateResponse, MailboxResponse,
+        PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
     },
     range_option, to_binary,
     types::Message,
@@ -21,23 +22,8 @@ use hpl_interface::{
 };
 use hpl_ownable::get_owner;
 
-#[derive(thiserror::Error, Debug, PartialEq)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
-
-    #[error("unauthorized")]
-    Unauthorized {},
-
-    #[error("route not found for {0}")]
-    RouteNotFound(u32),
-
-    #[error("invalid arguments. reason: {reason:?}")]
-    InvalidArguments { reason: String },
-}
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
 
 // version info for migration info
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -63,14 +49,20 @@ pub fn instantiate(
 
     hpl_ownable::initialize(deps.storage, &owner)?;
 
-    Ok(Response::new().add_event(
-        new_event("initialize")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox: None,
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -78,7 +70,6 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         ExecuteMsg::Router(msg) => Ok(hpl_router::handle(deps, env, info, msg)?),
         ExecuteMsg::PostDispatch(msg) => post_dispatch(deps, info, msg),
 
@@ -91,12 +82,16 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Router(msg) => Ok(hpl_router::handle_query(deps, env, msg)?),
         QueryMsg::Hook(msg) => match msg {
             HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::Routing,
+            })?),
             HookQueryMsg::QuoteDispatch(msg) => to_binary(quote_dispatch(deps, msg)),
         },
         QueryMsg::CustomRoutingHook(msg) => match msg {
@@ -262,7 +257,7 @@ fn clear(
 }
 
 fn route(storage: &dyn Storage, message: &HexBinary) -> Result<(Message, Addr), ContractError> {
-    let decoded_msg: Message = message.clone().into();
+    let decoded_msg = Message::try_from(message.clone())?;
     let dest_domain = decoded_msg.dest_domain;
 
     let custom_hook =
@@ -272,9 +267,9 @@ fn route(storage: &dyn Storage, message: &HexBinary) -> Result<(Message, Addr),
     }
 
     let routed_hook_set = hpl_router::get_route::<Addr>(storage, dest_domain)?;
-    let routed_hook = routed_hook_set
-        .route
-        .ok_or(ContractError::RouteNotFound(dest_domain))?;
+    let routed_hook = routed_hook_set.route.ok_or(ContractError::RouteNotFound {
+        domain: dest_domain,
+    })?;
 
     Ok((decoded_msg, routed_hook))
 }
@@ -312,6 +307,13 @@ fn quote_dispatch(
     Ok(resp)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
@@ -445,6 +447,30 @@ mod test {
         assert_eq!(OWNER, get_owner(deps.as_ref().storage).unwrap());
     }
 
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(DEPLOYER, &[]),
+            InstantiateMsg {
+                owner: OWNER.to_string(),
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: addr(OWNER),
+                mailbox: None
+            }
+        );
+    }
+
     #[rstest]
     fn test_get_mailbox(deps: TestDeps) {
         let res: MailboxResponse =
@@ -569,9 +595,9 @@ mod test {
     #[case(OWNER, 26657, gen_bz(20), ROUTE1.1)]
     #[case(OWNER, 26657, hex(CUSTOM_USER), CUSTOM_ROUTE1.2)]
     #[case(OWNER, 111333, hex(CUSTOM_USER), CUSTOM_ROUTE2.2)]
-    #[should_panic(expected = "route not found for 111333")]
+    #[should_panic(expected = "route not found for domain 111333")]
     #[case(OWNER, 111333, gen_bz(20), CUSTOM_ROUTE2.2)]
-    #[should_panic(expected = "route not found for 12345")]
+    #[should_panic(expected = "route not found for domain 12345")]
     #[case(OWNER, 12345, gen_bz(20), ROUTE1.1)]
     fn test_post_dispatch(
         deps_custom_routes: (TestDeps, Routes, CustomRoutes),
@@ -582,7 +608,7 @@ mod test {
     ) {
         let (mut deps, _, _) = deps_custom_routes;
 
-        let mut rand_msg: Message = gen_bz(200).into();
+        let mut rand_msg: Message = gen_bz(200).try_into().unwrap();
         rand_msg.dest_domain = test_domain;
         rand_msg.recipient = recipient;
 
@@ -612,7 +638,7 @@ mod test {
 
     #[rstest]
     #[case(26657, Some(26657))]
-    #[should_panic(expected = "route not found for 12345")]
+    #[should_panic(expected = "route not found for domain 12345")]
     #[case(12345, None)]
     fn test_quote_dispatch(
         deps_custom_routes: (TestDeps, Routes, CustomRoutes),
@@ -623,7 +649,7 @@ mod test {
 
         deps.querier.update_wasm(mock_query_handler);
 
-        let mut rand_msg: Message = gen_bz(100).into();
+        let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
         rand_msg.dest_domain = test_domain;
 
         let res: QuoteDispatchResponse = test_query(
@@ -1,8 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, wasm_execute, Addr, Deps, DepsMut, Env, Event, HexBinary, MessageInfo,
-    QueryResponse, Response, StdError, Storage,
+    ensure_eq, to_json_binary, wasm_execute, Addr, Deps, DepsMut, Empty, Env, Event, HexBinary,
+    MessageInfo, QueryResponse, Response, Storage,
 };
 
 use cw_storage_plus::Item;
@@ -10,24 +10,16 @@ use hpl_interface::{
     hook::{
         self,
         routing_fallback::{ExecuteMsg, InstantiateMsg, QueryMsg},
-        HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
+        HookQueryMsg, HookType, HookTypeResponse, InstantiateResponse, MailboxResponse,
+        PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
     },
     to_binary,
     types::Message,
 };
 use hpl_ownable::get_owner;
 
-#[derive(thiserror::Error, Debug, PartialEq)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
-
-    #[error("unauthorized")]
-    Unauthorized {},
-}
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
 
 // version info for migration info
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -53,14 +45,20 @@ pub fn instantiate(
 
     hpl_ownable::initialize(deps.storage, &owner)?;
 
-    Ok(Response::new().add_event(
-        new_event("initialize")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox: None,
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -68,7 +66,6 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         ExecuteMsg::Router(msg) => Ok(hpl_router::handle(deps, env, info, msg)?),
         ExecuteMsg::PostDispatch(msg) => post_dispatch(deps, info, msg),
         ExecuteMsg::SetFallbackHook { hook } => {
@@ -82,22 +79,26 @@ pub fn execute(
 
             FALLBACK_HOOK.save(deps.storage, &fallback_hook)?;
 
-            Ok(Response::new().add_event(
-                new_event("set_fallback_hook")
-                    .add_attribute("sender", info.sender)
-                    .add_attribute("fallback-hook", hook),
-            ))
+            Ok(Response::new().add_event(hpl_events::hook_set(
+                new_event("set_fallback_hook"),
+                &info.sender,
+                &[hook],
+            )))
         }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Router(msg) => Ok(hpl_router::handle_query(deps, env, msg)?),
         QueryMsg::Hook(msg) => match msg {
             HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::FallbackRouting,
+            })?),
             HookQueryMsg::QuoteDispatch(msg) => to_binary(quote_dispatch(deps, msg)),
         },
     }
@@ -110,7 +111,7 @@ fn get_mailbox(_deps: Deps) -> Result<MailboxResponse, ContractError> {
 }
 
 fn route(storage: &dyn Storage, message: &HexBinary) -> Result<(Message, Addr), ContractError> {
-    let decoded_msg: Message = message.clone().into();
+    let decoded_msg = Message::try_from(message.clone())?;
     let dest_domain = decoded_msg.dest_domain;
 
     let fallback_hook = FALLBACK_HOOK.load(storage)?;
@@ -154,6 +155,13 @@ pub fn quote_dispatch(
     Ok(resp)
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
@@ -271,6 +279,30 @@ mod test {
         assert_eq!(OWNER, get_owner(deps.as_ref().storage).unwrap());
     }
 
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(DEPLOYER, &[]),
+            InstantiateMsg {
+                owner: OWNER.to_string(),
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: addr(OWNER),
+                mailbox: None
+            }
+        );
+    }
+
     #[rstest]
     fn test_get_mailbox(deps: TestDeps) {
         let res: MailboxResponse =
@@ -288,7 +320,7 @@ mod test {
     ) {
         let (mut deps, _) = deps_routes;
 
-        let mut rand_msg: Message = gen_bz(100).into();
+        let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
         rand_msg.dest_domain = route.0;
 
         let res = post_dispatch(
@@ -324,7 +356,7 @@ mod test {
 
         deps.querier.update_wasm(mock_query_handler);
 
-        let mut rand_msg: Message = gen_bz(100).into();
+        let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
         rand_msg.dest_domain = test_domain;
 
         let res: QuoteDispatchResponse = test_query(
@@ -1,34 +1,23 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    wasm_execute, Addr, Deps, DepsMut, Env, Event, HexBinary, MessageInfo, QueryResponse, Response,
-    StdError, Storage,
+    to_json_binary, wasm_execute, Addr, Deps, DepsMut, Empty, Env, Event, HexBinary, MessageInfo,
+    QueryResponse, Response, Storage,
 };
 
 use hpl_interface::{
     hook::{
         self,
         routing::{ExecuteMsg, InstantiateMsg, QueryMsg},
-        HookQueryMsg, MailboxResponse, PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
+        HookQueryMsg, HookType, HookTypeResponse, InstantiateResponse, MailboxResponse,
+        PostDispatchMsg, QuoteDispatchMsg, QuoteDispatchResponse,
     },
     to_binary,
     types::Message,
 };
 
-#[derive(thiserror::Error, Debug, PartialEq)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
-
-    #[error("unauthorized")]
-    Unauthorized {},
-
-    #[error("route not found for {0}")]
-    RouteNotFound(u32),
-}
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
 
 // version info for migration info
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -51,14 +40,20 @@ pub fn instantiate(
 
     hpl_ownable::initialize(deps.storage, &owner)?;
 
-    Ok(Response::new().add_event(
-        new_event("initialize")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+            mailbox: None,
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -66,19 +61,22 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         ExecuteMsg::Router(msg) => Ok(hpl_router::handle(deps, env, info, msg)?),
         ExecuteMsg::PostDispatch(msg) => post_dispatch(deps, info, msg),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Router(msg) => Ok(hpl_router::handle_query(deps, env, msg)?),
         QueryMsg::Hook(msg) => match msg {
             HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::Routing,
+            })?),
             HookQueryMsg::QuoteDispatch(msg) => to_binary(quote_dispatch(deps, msg)),
         },
     }
@@ -107,13 +105,13 @@ fn quote_dispatch(
 }
 
 fn route(storage: &dyn Storage, message: &HexBinary) -> Result<(Message, Addr), ContractError> {
-    let decoded_msg: Message = message.clone().into();
+    let decoded_msg = Message::try_from(message.clone())?;
     let dest_domain = decoded_msg.dest_domain;
 
     let routed_hook_set = hpl_router::get_route::<Addr>(storage, dest_domain)?;
-    let routed_hook = routed_hook_set
-        .route
-        .ok_or(ContractError::RouteNotFound(dest_domain))?;
+    let routed_hook = routed_hook_set.route.ok_or(ContractError::RouteNotFound {
+        domain: dest_domain,
+    })?;
 
     Ok((decoded_msg, routed_hook))
 }
@@ -135,6 +133,13 @@ fn post_dispatch(
     ))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
@@ -235,6 +240,30 @@ mod test {
         assert_eq!(OWNER, get_owner(deps.as_ref().storage).unwrap());
     }
 
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(DEPLOYER, &[]),
+            InstantiateMsg {
+                owner: OWNER.to_string(),
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: addr(OWNER),
+                mailbox: None
+            }
+        );
+    }
+
     #[rstest]
     fn test_get_mailbox(deps: TestDeps) {
         let res: MailboxResponse =
@@ -244,7 +273,7 @@ mod test {
 
     #[rstest]
     #[case(OWNER, 26657)]
-    #[should_panic(expected = "route not found for 12345")]
+    #[should_panic(expected = "route not found for domain 12345")]
     #[case(OWNER, 12345)]
     fn test_post_dispatch(
         deps_routes: (TestDeps, Routes),
@@ -253,7 +282,7 @@ mod test {
     ) {
         let (mut deps, routes) = deps_routes;
 
-        let mut rand_msg: Message = gen_bz(100).into();
+        let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
         rand_msg.dest_domain = test_domain;
 
         let res = post_dispatch(
@@ -285,7 +314,7 @@ mod test {
 
     #[rstest]
     #[case(26657, Some(26657))]
-    #[should_panic(expected = "route not found for 12345")]
+    #[should_panic(expected = "route not found for domain 12345")]
     #[case(12345, None)]
     fn test_quote_dispatch(
         deps_routes: (TestDeps, Routes),
@@ -296,7 +325,7 @@ mod test {
 
         deps.querier.update_wasm(mock_query_handler);
 
-        let mut rand_msg: Message = gen_bz(100).into();
+        let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
         rand_msg.dest_domain = test_domain;
 
         let res: QuoteDispatchResponse = test_query(
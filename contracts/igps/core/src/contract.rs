@@ -1,16 +1,19 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, Deps, DepsMut, Empty, Env, Event, MessageInfo, QueryResponse, Response,
+    ensure, to_json_binary, Deps, DepsMut, Empty, Env, Event, MessageInfo, QueryResponse, Response,
 };
 
-use hpl_interface::hook::HookQueryMsg;
-use hpl_interface::igp::core::{ExecuteMsg, IgpQueryMsg, InstantiateMsg, QueryMsg};
+use hpl_interface::hook::{HookQueryMsg, HookType, HookTypeResponse};
+use hpl_interface::igp::core::{
+    ExecuteMsg, IgpQueryMsg, InstantiateMsg, InstantiateResponse, QueryMsg,
+};
 use hpl_interface::igp::oracle::IgpGasOracleQueryMsg;
 use hpl_interface::to_binary;
 
 use crate::{
     ContractError, BENEFICIARY, CONTRACT_NAME, CONTRACT_VERSION, DEFAULT_GAS_USAGE, GAS_TOKEN, HRP,
+    MAILBOX,
 };
 
 fn new_event(name: &str) -> Event {
@@ -38,26 +41,36 @@ pub fn instantiate(
     })?;
 
     let owner = deps.api.addr_validate(&msg.owner)?;
+    let mailbox = deps.api.addr_validate(&msg.mailbox)?;
     let beneficiary = deps.api.addr_validate(&msg.beneficiary)?;
 
     hpl_ownable::initialize(deps.storage, &owner)?;
 
+    MAILBOX.save(deps.storage, &mailbox)?;
     BENEFICIARY.save(deps.storage, &beneficiary)?;
 
     GAS_TOKEN.save(deps.storage, &msg.gas_token)?;
     HRP.save(deps.storage, &msg.hrp)?;
     DEFAULT_GAS_USAGE.save(deps.storage, &msg.default_gas_usage)?;
 
-    Ok(Response::new().add_event(
-        new_event("initialize")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", msg.owner)
-            .add_attribute("beneficiary", msg.beneficiary)
-            .add_attribute("default_gas", msg.default_gas_usage.to_string()),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner,
+            mailbox,
+            beneficiary,
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", msg.owner)
+                .add_attribute("mailbox", msg.mailbox)
+                .add_attribute("beneficiary", msg.beneficiary)
+                .add_attribute("default_gas", msg.default_gas_usage.to_string()),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -67,7 +80,6 @@ pub fn execute(
     use crate::execute;
 
     match msg {
-        ExecuteMsg::Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         ExecuteMsg::Router(msg) => Ok(hpl_router::handle(deps, env, info, msg)?),
         ExecuteMsg::PostDispatch(msg) => Ok(execute::post_dispatch(deps, info, msg)?),
 
@@ -77,10 +89,26 @@ pub fn execute(
             execute::unset_gas_for_domain(deps, info, domains)
         }
 
+        ExecuteMsg::SetQuoteCaps { caps } => execute::set_quote_caps(deps, info, caps),
+        ExecuteMsg::UnsetQuoteCaps { domains } => execute::unset_quote_caps(deps, info, domains),
+
+        ExecuteMsg::SetGasOracle { oracle } => execute::set_gas_oracle(deps, info, oracle),
+
         ExecuteMsg::SetBeneficiary { beneficiary } => {
             execute::set_beneficiary(deps, info, beneficiary)
         }
+        ExecuteMsg::SetMailbox { mailbox } => execute::set_mailbox(deps, info, mailbox),
+        ExecuteMsg::SetAuthorizedCaller { caller, authorized } => {
+            execute::set_authorized_caller(deps, info, caller, authorized)
+        }
+        ExecuteMsg::SetRequirePayment { require_payment } => {
+            execute::set_require_payment(deps, info, require_payment)
+        }
         ExecuteMsg::Claim {} => execute::claim(deps, env, info),
+        ExecuteMsg::SettleBatch {
+            message_ids,
+            recipient,
+        } => execute::settle_batch(deps, info, message_ids, recipient),
 
         ExecuteMsg::PayForGas {
             message_id,
@@ -88,7 +116,7 @@ pub fn execute(
             gas_amount,
             refund_address,
         } => execute::pay_for_gas(
-            &deps,
+            deps,
             info,
             message_id,
             dest_domain,
@@ -99,16 +127,20 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     use crate::query::*;
 
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Router(msg) => Ok(hpl_router::handle_query(deps, env, msg)?),
 
         QueryMsg::Hook(msg) => match msg {
             HookQueryMsg::QuoteDispatch(msg) => to_binary(quote_dispatch(deps, msg)),
             HookQueryMsg::Mailbox {} => to_binary(get_mailbox(deps)),
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::InterchainGasPaymaster,
+            })?),
         },
         QueryMsg::Oracle(msg) => match msg {
             IgpGasOracleQueryMsg::GetExchangeRateAndGasPrice { dest_domain } => {
@@ -125,16 +157,24 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
             } => to_binary(list_gas_for_domains(deps, offset, limit, order)),
 
             IgpQueryMsg::Beneficiary {} => to_binary(get_beneficiary(deps)),
+            IgpQueryMsg::GasOracle {} => to_binary(get_gas_oracle_config(deps)),
+            IgpQueryMsg::QuoteCaps { domains } => to_binary(get_quote_caps(deps, domains)),
+            IgpQueryMsg::RequirePayment {} => to_binary(get_require_payment(deps)),
+            IgpQueryMsg::AuthorizedCallers {} => to_binary(get_authorized_callers(deps)),
 
             IgpQueryMsg::QuoteGasPayment {
                 dest_domain,
                 gas_amount,
             } => to_binary(quote_gas_payment(deps, dest_domain, gas_amount)),
+
+            IgpQueryMsg::GasPaidFor { message_id } => to_binary(get_gas_paid_for(deps, message_id)),
         },
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::new())
 }
@@ -1,36 +1,49 @@
-use cosmwasm_std::Uint256;
+use cosmwasm_std::{Uint128, Uint256};
+use hpl_error::HplError;
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] cosmwasm_std::StdError),
+    #[error(transparent)]
+    Hpl(#[from] HplError),
 
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
-
-    #[error("{0}")]
-    ParseIntError(#[from] std::num::ParseIntError),
-
-    #[error("unauthorized")]
-    Unauthorized {},
-
-    #[error("insufficient funds: needed {gas_needed:?}, but only received {received:?}")]
+    #[error("[E300] insufficient funds: needed {gas_needed:?}, but only received {received:?}")]
     InsufficientFunds {
         received: Uint256,
         gas_needed: Uint256,
     },
 
-    #[error("invalid config. reason: {reason:?}")]
-    InvalidConfig { reason: String },
-
-    #[error("gas oracle not found for {0}")]
+    #[error("[E301] gas oracle not found for {0}")]
     GasOracleNotFound(u32),
+
+    #[error("[E302] gas payment not found for message {message_id}")]
+    GasPaymentNotFound { message_id: String },
+
+    #[error("[E303] gas payment already settled for message {message_id}")]
+    GasPaymentSettled { message_id: String },
+
+    #[error("[E304] quote {quote} exceeds the cap {cap} for domain {domain}")]
+    QuoteExceedsCap {
+        domain: u32,
+        quote: Uint256,
+        cap: Uint128,
+    },
 }
 
+hpl_error::impl_from_hpl!(ContractError);
+
 impl ContractError {
-    pub fn invalid_config(reason: &str) -> Self {
-        Self::InvalidConfig {
-            reason: reason.to_string(),
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Hpl(err) => err.code(),
+            Self::InsufficientFunds { .. } => 300,
+            Self::GasOracleNotFound(_) => 301,
+            Self::GasPaymentNotFound { .. } => 302,
+            Self::GasPaymentSettled { .. } => 303,
+            Self::QuoteExceedsCap { .. } => 304,
         }
     }
+
+    pub fn invalid_config(reason: &str) -> Self {
+        HplError::invalid_config(reason).into()
+    }
 }
@@ -32,18 +32,90 @@ pub fn emit_unset_gas_for_domain(owner: Addr, domains: Vec<u32>) -> Event {
         )
 }
 
+pub fn emit_set_quote_caps(owner: Addr, caps: Vec<(u32, Uint128)>) -> Event {
+    Event::new("igp-core-set-quote-caps")
+        .add_attribute("owner", owner)
+        .add_attribute(
+            "caps",
+            caps.into_iter()
+                .map(|(domain, cap)| format!("{domain}:{cap}"))
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+}
+
+pub fn emit_unset_quote_caps(owner: Addr, domains: Vec<u32>) -> Event {
+    Event::new("igp-core-unset-quote-caps")
+        .add_attribute("owner", owner)
+        .add_attribute(
+            "domains",
+            domains
+                .into_iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+}
+
 pub fn emit_set_beneficiary(owner: Addr, beneficiary: String) -> Event {
     Event::new("igp-core-set-beneficiary")
         .add_attribute("owner", owner)
         .add_attribute("beneficiary", beneficiary)
 }
 
+pub fn emit_set_mailbox(owner: Addr, mailbox: String) -> Event {
+    Event::new("igp-core-set-mailbox")
+        .add_attribute("owner", owner)
+        .add_attribute("mailbox", mailbox)
+}
+
+pub fn emit_set_authorized_caller(owner: Addr, caller: String, authorized: bool) -> Event {
+    Event::new("igp-core-set-authorized-caller")
+        .add_attribute("owner", owner)
+        .add_attribute("caller", caller)
+        .add_attribute("authorized", authorized.to_string())
+}
+
+pub fn emit_set_require_payment(owner: Addr, require_payment: bool) -> Event {
+    Event::new("igp-core-set-require-payment")
+        .add_attribute("owner", owner)
+        .add_attribute("require-payment", require_payment.to_string())
+}
+
+pub fn emit_set_gas_oracle(owner: Addr, oracle: Option<Addr>) -> Event {
+    Event::new("igp-core-set-gas-oracle")
+        .add_attribute("owner", owner)
+        .add_attribute(
+            "oracle",
+            oracle.map_or("none".to_string(), |v| v.to_string()),
+        )
+}
+
 pub fn emit_claim(beneficiary: Addr, balance: Coin) -> Event {
     Event::new("igp-core-claim")
         .add_attribute("beneficiary", beneficiary)
         .add_attribute("collected", balance.to_string())
 }
 
+pub fn emit_settle_batch(beneficiary: Addr, recipient: Addr, settled: usize, total: Coin) -> Event {
+    Event::new("igp-core-settle-batch")
+        .add_attribute("beneficiary", beneficiary)
+        .add_attribute("recipient", recipient)
+        .add_attribute("settled", settled.to_string())
+        .add_attribute("collected", total.to_string())
+}
+
+pub fn emit_post_dispatch_unpaid(
+    message_id: HexBinary,
+    gas_required: Uint256,
+    refunded: Uint128,
+) -> Event {
+    Event::new("igp-core-post-dispatch-unpaid")
+        .add_attribute("message-id", message_id.to_hex())
+        .add_attribute("gas-required", gas_required)
+        .add_attribute("refunded", refunded)
+}
+
 pub fn emit_post_dispatch(metadata: HexBinary, message: HexBinary) -> Event {
     Event::new("igp-core-post-dispatch")
         .add_attribute(
@@ -56,22 +128,3 @@ pub fn emit_post_dispatch(metadata: HexBinary, message: HexBinary) -> Event {
         )
         .add_attribute("message", message.to_string())
 }
-
-pub fn emit_pay_for_gas(
-    sender: Addr,
-    dest_domain: u32,
-    message_id: HexBinary,
-    gas_amount: Uint256,
-    gas_refunded: Uint128,
-    gas_required: Uint256,
-    payment: Uint256,
-) -> Event {
-    Event::new("igp-core-pay-for-gas")
-        .add_attribute("sender", sender)
-        .add_attribute("dest_domain", dest_domain.to_string())
-        .add_attribute("message_id", message_id.to_hex())
-        .add_attribute("gas_amount", gas_amount)
-        .add_attribute("gas_refunded", gas_refunded)
-        .add_attribute("gas_required", gas_required)
-        .add_attribute("payment", payment)
-}
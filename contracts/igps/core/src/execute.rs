@@ -1,16 +1,21 @@
 use crate::event::{
-    emit_claim, emit_pay_for_gas, emit_post_dispatch, emit_set_beneficiary, emit_set_default_gas,
-    emit_set_gas_for_domain, emit_unset_gas_for_domain,
+    emit_claim, emit_post_dispatch, emit_post_dispatch_unpaid, emit_set_authorized_caller,
+    emit_set_beneficiary, emit_set_default_gas, emit_set_gas_for_domain, emit_set_gas_oracle,
+    emit_set_mailbox, emit_set_quote_caps, emit_set_require_payment, emit_settle_batch,
+    emit_unset_gas_for_domain, emit_unset_quote_caps,
 };
 use crate::query::quote_gas_price;
 use crate::{
-    get_default_gas, ContractError, BENEFICIARY, DEFAULT_GAS_USAGE, GAS_FOR_DOMAIN, GAS_TOKEN, HRP,
+    get_default_gas, get_gas_limit, is_payment_required, ContractError, AUTHORIZED_CALLERS,
+    BENEFICIARY, DEFAULT_GAS_USAGE, GAS_FOR_DOMAIN, GAS_ORACLE, GAS_PAID, GAS_TOKEN, HRP, MAILBOX,
+    QUOTE_CAPS, REQUIRE_PAYMENT, UNSETTLED,
 };
 
 use cosmwasm_std::{
-    coins, ensure, ensure_eq, BankMsg, DepsMut, Env, HexBinary, MessageInfo, Response, Uint128,
-    Uint256,
+    coin, coins, ensure, ensure_eq, BankMsg, DepsMut, Env, HexBinary, MessageInfo, Response,
+    StdError, Uint128, Uint256,
 };
+use hpl_error::HplError;
 use hpl_interface::{
     hook::PostDispatchMsg,
     types::{IGPMetadata, Message},
@@ -27,7 +32,7 @@ pub fn set_default_gas(
     ensure_eq!(
         info.sender,
         get_owner(deps.storage)?,
-        ContractError::Unauthorized {}
+        HplError::Unauthorized {}
     );
 
     DEFAULT_GAS_USAGE.save(deps.storage, &default_gas)?;
@@ -43,7 +48,7 @@ pub fn set_gas_for_domain(
     ensure_eq!(
         info.sender,
         get_owner(deps.storage)?,
-        ContractError::Unauthorized {}
+        HplError::Unauthorized {}
     );
 
     for (domain, custom_gas) in config.clone() {
@@ -61,7 +66,7 @@ pub fn unset_gas_for_domain(
     ensure_eq!(
         info.sender,
         get_owner(deps.storage)?,
-        ContractError::Unauthorized {}
+        HplError::Unauthorized {}
     );
 
     for domain in domains.clone() {
@@ -71,6 +76,42 @@ pub fn unset_gas_for_domain(
     Ok(Response::new().add_event(emit_unset_gas_for_domain(info.sender, domains)))
 }
 
+pub fn set_quote_caps(
+    deps: DepsMut,
+    info: MessageInfo,
+    caps: Vec<(u32, Uint128)>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    for (domain, cap) in caps.clone() {
+        QUOTE_CAPS.save(deps.storage, domain, &cap)?;
+    }
+
+    Ok(Response::new().add_event(emit_set_quote_caps(info.sender, caps)))
+}
+
+pub fn unset_quote_caps(
+    deps: DepsMut,
+    info: MessageInfo,
+    domains: Vec<u32>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    for domain in domains.clone() {
+        QUOTE_CAPS.remove(deps.storage, domain);
+    }
+
+    Ok(Response::new().add_event(emit_unset_quote_caps(info.sender, domains)))
+}
+
 pub fn set_beneficiary(
     deps: DepsMut,
     info: MessageInfo,
@@ -79,7 +120,7 @@ pub fn set_beneficiary(
     ensure_eq!(
         info.sender,
         get_owner(deps.storage)?,
-        ContractError::Unauthorized {}
+        HplError::Unauthorized {}
     );
 
     BENEFICIARY.save(deps.storage, &deps.api.addr_validate(&beneficiary)?)?;
@@ -87,24 +128,161 @@ pub fn set_beneficiary(
     Ok(Response::new().add_event(emit_set_beneficiary(info.sender, beneficiary)))
 }
 
+pub fn set_mailbox(
+    deps: DepsMut,
+    info: MessageInfo,
+    mailbox: String,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    MAILBOX.save(deps.storage, &deps.api.addr_validate(&mailbox)?)?;
+
+    Ok(Response::new().add_event(emit_set_mailbox(info.sender, mailbox)))
+}
+
+pub fn set_authorized_caller(
+    deps: DepsMut,
+    info: MessageInfo,
+    caller: String,
+    authorized: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    let caller_addr = deps.api.addr_validate(&caller)?;
+
+    if authorized {
+        AUTHORIZED_CALLERS.save(deps.storage, caller_addr, &())?;
+    } else {
+        AUTHORIZED_CALLERS.remove(deps.storage, caller_addr);
+    }
+
+    Ok(Response::new().add_event(emit_set_authorized_caller(info.sender, caller, authorized)))
+}
+
+pub fn set_require_payment(
+    deps: DepsMut,
+    info: MessageInfo,
+    require_payment: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    REQUIRE_PAYMENT.save(deps.storage, &require_payment)?;
+
+    Ok(Response::new().add_event(emit_set_require_payment(info.sender, require_payment)))
+}
+
+pub fn set_gas_oracle(
+    deps: DepsMut,
+    info: MessageInfo,
+    oracle: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    let oracle = oracle.map(|v| deps.api.addr_validate(&v)).transpose()?;
+    match &oracle {
+        Some(oracle) => GAS_ORACLE.save(deps.storage, oracle)?,
+        None => GAS_ORACLE.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_event(emit_set_gas_oracle(info.sender, oracle)))
+}
+
+/// Sends the balance not owed to unsettled records, which are only paid out by `SettleBatch`
 pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
     let beneficiary = BENEFICIARY.load(deps.storage)?;
-    ensure_eq!(info.sender, beneficiary, ContractError::Unauthorized {});
+    ensure_eq!(info.sender, beneficiary, HplError::Unauthorized {});
 
     let gas_token = GAS_TOKEN.load(deps.storage)?;
 
     let balance = deps
         .querier
-        .query_balance(env.contract.address, gas_token)?;
+        .query_balance(env.contract.address, &gas_token)?;
+    let unsettled = UNSETTLED.may_load(deps.storage)?.unwrap_or_default();
+    let claimable = Uint256::from(balance.amount).saturating_sub(unsettled);
+    let claimable = coin(Uint128::from_str(&claimable.to_string())?.u128(), gas_token);
 
-    let send_msg = BankMsg::Send {
-        to_address: beneficiary.to_string(),
-        amount: vec![balance.clone()],
-    };
+    let mut resp = Response::new();
+    if !claimable.amount.is_zero() {
+        resp = resp.add_message(BankMsg::Send {
+            to_address: beneficiary.to_string(),
+            amount: vec![claimable.clone()],
+        });
+    }
+
+    Ok(resp.add_event(emit_claim(beneficiary, claimable)))
+}
+
+pub fn settle_batch(
+    deps: DepsMut,
+    info: MessageInfo,
+    message_ids: Vec<HexBinary>,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let beneficiary = BENEFICIARY.load(deps.storage)?;
+    ensure_eq!(info.sender, beneficiary, HplError::Unauthorized {});
+    ensure!(
+        !message_ids.is_empty(),
+        HplError::invalid_args("message_ids must not be empty")
+    );
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let gas_token = GAS_TOKEN.load(deps.storage)?;
+
+    let mut total = Uint256::zero();
+    for message_id in &message_ids {
+        let mut record = GAS_PAID
+            .may_load(deps.storage, message_id.to_vec())?
+            .ok_or_else(|| ContractError::GasPaymentNotFound {
+                message_id: message_id.to_hex(),
+            })?;
+        ensure!(
+            !record.settled,
+            ContractError::GasPaymentSettled {
+                message_id: message_id.to_hex(),
+            }
+        );
+
+        total += record.payment;
+        record.settled = true;
+        GAS_PAID.save(deps.storage, message_id.to_vec(), &record)?;
+    }
+
+    let unsettled = UNSETTLED.may_load(deps.storage)?.unwrap_or_default();
+    let unsettled = unsettled.checked_sub(total).map_err(StdError::from)?;
+    UNSETTLED.save(deps.storage, &unsettled)?;
+
+    let total = coin(Uint128::from_str(&total.to_string())?.u128(), gas_token);
+
+    let mut resp = Response::new();
+    if !total.amount.is_zero() {
+        resp = resp.add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![total.clone()],
+        });
+    }
 
-    Ok(Response::new()
-        .add_message(send_msg)
-        .add_event(emit_claim(beneficiary, balance)))
+    Ok(resp.add_event(emit_settle_batch(
+        beneficiary,
+        recipient,
+        message_ids.len(),
+        total,
+    )))
 }
 
 pub fn post_dispatch(
@@ -112,9 +290,19 @@ pub fn post_dispatch(
     info: MessageInfo,
     req: PostDispatchMsg,
 ) -> Result<Response, ContractError> {
-    let message: Message = req.message.clone().into();
+    // igps instantiated before the mailbox binding stay unrestricted
+    if let Some(mailbox) = MAILBOX.may_load(deps.storage)? {
+        ensure!(
+            info.sender == mailbox || AUTHORIZED_CALLERS.has(deps.storage, info.sender.clone()),
+            HplError::Unauthorized {}
+        );
+    }
+
+    let message = Message::try_from(req.message.clone())?;
     let hrp = HRP.load(deps.storage)?;
 
+    // overpayment goes to the metadata refund address. without one, the message sender is
+    // refunded rather than `info.sender`, which is the mailbox calling the hook
     let (gas_limit, refund_address) = match req.metadata.to_vec().len() < 32 {
         true => (
             Uint256::from(get_default_gas(deps.storage, message.dest_domain)?),
@@ -123,14 +311,40 @@ pub fn post_dispatch(
         false => {
             let igp_metadata: IGPMetadata = req.metadata.clone().into();
             (
-                igp_metadata.gas_limit,
+                get_gas_limit(deps.storage, message.dest_domain, igp_metadata.gas_limit)?,
                 igp_metadata.get_refund_address(&hrp, message.sender_addr(&hrp)?),
             )
         }
     };
 
+    if !is_payment_required(deps.storage)? {
+        let gas_token = GAS_TOKEN.load(deps.storage)?;
+        let received = cw_utils::may_pay(&info, &gas_token)?;
+        let gas_needed =
+            quote_gas_price(deps.storage, &deps.querier, message.dest_domain, gas_limit)?;
+
+        // nothing is recorded for the message, so the relayer sees it as unpaid
+        if Uint256::from(received) < gas_needed {
+            let mut resp = Response::new();
+            if !received.is_zero() {
+                resp = resp.add_message(BankMsg::Send {
+                    to_address: refund_address.to_string(),
+                    amount: coins(received.u128(), &gas_token),
+                });
+            }
+
+            return Ok(resp
+                .add_event(emit_post_dispatch_unpaid(
+                    message.id(),
+                    gas_needed,
+                    received,
+                ))
+                .add_event(emit_post_dispatch(req.metadata, req.message)));
+        }
+    }
+
     Ok(pay_for_gas(
-        &deps,
+        deps,
         info,
         message.id(),
         message.dest_domain,
@@ -141,7 +355,7 @@ pub fn post_dispatch(
 }
 
 pub fn pay_for_gas(
-    deps: &DepsMut,
+    deps: DepsMut,
     info: MessageInfo,
     message_id: HexBinary,
     dest_domain: u32,
@@ -161,6 +375,19 @@ pub fn pay_for_gas(
 
     let payment_gap = Uint128::from_str(&(received - gas_needed).to_string())?;
 
+    let mut record = GAS_PAID
+        .may_load(deps.storage, message_id.to_vec())?
+        .unwrap_or_default();
+    record.gas_amount += gas_amount;
+    record.payment += gas_needed;
+    GAS_PAID.save(deps.storage, message_id.to_vec(), &record)?;
+
+    // payments to a settled record can't be settled again, so they're left to `Claim`
+    if !record.settled {
+        let unsettled = UNSETTLED.may_load(deps.storage)?.unwrap_or_default();
+        UNSETTLED.save(deps.storage, &(unsettled + gas_needed))?;
+    }
+
     let mut resp = Response::new();
 
     if !payment_gap.is_zero() {
@@ -171,10 +398,10 @@ pub fn pay_for_gas(
         resp = resp.add_message(refund_msg);
     }
 
-    Ok(resp.add_event(emit_pay_for_gas(
-        info.sender,
+    Ok(resp.add_event(hpl_events::gas_payment(
+        &info.sender,
         dest_domain,
-        message_id,
+        &message_id,
         gas_amount,
         payment_gap,
         gas_needed,
@@ -7,7 +7,8 @@ pub mod query;
 #[cfg(test)]
 pub mod tests;
 
-use cosmwasm_std::{Addr, StdResult, Storage};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, StdResult, Storage, Uint128, Uint256};
 use cw_storage_plus::{Item, Map};
 pub use error::ContractError;
 
@@ -30,12 +31,77 @@ pub const DEFAULT_GAS_USAGE: Item<u128> = Item::new(DEFAULT_GAS_USAGE_KEY);
 pub const GAS_FOR_DOMAIN_PREFIX: &str = "gas_for_domain";
 pub const GAS_FOR_DOMAIN: Map<u32, u128> = Map::new(GAS_FOR_DOMAIN_PREFIX);
 
+pub const QUOTE_CAPS_PREFIX: &str = "quote_caps";
+pub const QUOTE_CAPS: Map<u32, Uint128> = Map::new(QUOTE_CAPS_PREFIX);
+
+pub const MAILBOX_KEY: &str = "mailbox";
+pub const MAILBOX: Item<Addr> = Item::new(MAILBOX_KEY);
+
+/// Hooks wrapping the igp that may post dispatch besides the mailbox
+pub const AUTHORIZED_CALLERS_PREFIX: &str = "authorized_callers";
+pub const AUTHORIZED_CALLERS: Map<Addr, ()> = Map::new(AUTHORIZED_CALLERS_PREFIX);
+
 pub const BENEFICIARY_KEY: &str = "beneficiary";
 pub const BENEFICIARY: Item<Addr> = Item::new(BENEFICIARY_KEY);
 
+pub const GAS_ORACLE_KEY: &str = "gas_oracle";
+pub const GAS_ORACLE: Item<Addr> = Item::new(GAS_ORACLE_KEY);
+
+/// Unset means required, as igps enforced payment before the flag was added
+pub const REQUIRE_PAYMENT_KEY: &str = "require_payment";
+pub const REQUIRE_PAYMENT: Item<bool> = Item::new(REQUIRE_PAYMENT_KEY);
+
+pub const GAS_PAID_PREFIX: &str = "gas_paid";
+pub const GAS_PAID: Map<Vec<u8>, GasPaymentRecord> = Map::new(GAS_PAID_PREFIX);
+
+/// Sum of the payments of unsettled records, kept back from `Claim` for `SettleBatch`
+pub const UNSETTLED_KEY: &str = "unsettled";
+pub const UNSETTLED: Item<Uint256> = Item::new(UNSETTLED_KEY);
+
+/// Accumulated gas payments for a message.
+/// `payment` is the amount kept by the IGP, excluding refunds.
+/// `settled` is set once the payment is sent out through `SettleBatch`.
+/// Records written before settlement existed read as settled, as `Claim` pays them out.
+#[cw_serde]
+#[derive(Default)]
+pub struct GasPaymentRecord {
+    pub gas_amount: Uint256,
+    pub payment: Uint256,
+    #[serde(default = "legacy_settled")]
+    pub settled: bool,
+}
+
+fn legacy_settled() -> bool {
+    true
+}
+
+/// Resolves the oracle for `domain`, preferring the external gas oracle over the routed ones
+pub fn get_gas_oracle(storage: &dyn Storage, domain: u32) -> Result<Addr, ContractError> {
+    if let Some(oracle) = GAS_ORACLE.may_load(storage)? {
+        return Ok(oracle);
+    }
+
+    hpl_router::get_route::<Addr>(storage, domain)?
+        .route
+        .ok_or(ContractError::GasOracleNotFound(domain))
+}
+
+pub fn is_payment_required(storage: &dyn Storage) -> StdResult<bool> {
+    Ok(REQUIRE_PAYMENT.may_load(storage)?.unwrap_or(true))
+}
+
 pub fn get_default_gas(storage: &dyn Storage, domain: u32) -> StdResult<u128> {
     let custom_gas = GAS_FOR_DOMAIN.may_load(storage, domain)?;
     let default_gas = DEFAULT_GAS_USAGE.load(storage)?;
 
     Ok(custom_gas.unwrap_or(default_gas))
 }
+
+/// A zero gas limit in hook metadata means the default, so it can't be used to skip payment
+pub fn get_gas_limit(storage: &dyn Storage, domain: u32, gas_limit: Uint256) -> StdResult<Uint256> {
+    if gas_limit.is_zero() {
+        return Ok(Uint256::from(get_default_gas(storage, domain)?));
+    }
+
+    Ok(gas_limit)
+}
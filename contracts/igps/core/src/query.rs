@@ -1,18 +1,27 @@
 use crate::error::ContractError;
-use crate::{BENEFICIARY, DEFAULT_GAS_USAGE, GAS_FOR_DOMAIN, GAS_TOKEN, TOKEN_EXCHANGE_RATE_SCALE};
+use crate::{
+    get_gas_oracle, is_payment_required, AUTHORIZED_CALLERS, BENEFICIARY, DEFAULT_GAS_USAGE,
+    GAS_FOR_DOMAIN, GAS_ORACLE, GAS_PAID, GAS_TOKEN, MAILBOX, QUOTE_CAPS,
+    TOKEN_EXCHANGE_RATE_SCALE,
+};
 
-use cosmwasm_std::{coins, Addr, Deps, QuerierWrapper, StdResult, Storage, Uint256};
-use hpl_interface::hook::{MailboxResponse, QuoteDispatchMsg, QuoteDispatchResponse};
+use cosmwasm_std::{coins, ensure, Deps, HexBinary, QuerierWrapper, StdResult, Storage, Uint256};
+use hpl_interface::hook::{
+    AuthorizedCallersResponse, MailboxResponse, QuoteDispatchMsg, QuoteDispatchResponse,
+};
 use hpl_interface::igp::core::{
-    BeneficiaryResponse, DefaultGasResponse, GasForDomainResponse, QuoteGasPaymentResponse,
+    BeneficiaryResponse, DefaultGasResponse, GasForDomainResponse, GasOracleResponse,
+    GasPaidResponse, QuoteCapsResponse, QuoteGasPaymentResponse, RequirePaymentResponse,
 };
 use hpl_interface::igp::oracle::{self, GetExchangeRateAndGasPriceResponse, IgpGasOracleQueryMsg};
 use hpl_interface::types::{IGPMetadata, Message};
 use hpl_interface::Order;
 
-pub fn get_mailbox(_deps: Deps) -> Result<MailboxResponse, ContractError> {
+pub fn get_mailbox(deps: Deps) -> Result<MailboxResponse, ContractError> {
     Ok(MailboxResponse {
-        mailbox: "unrestricted".to_string(),
+        mailbox: MAILBOX
+            .may_load(deps.storage)?
+            .map_or("unrestricted".to_string(), |v| v.into()),
     })
 }
 
@@ -58,16 +67,60 @@ pub fn get_beneficiary(deps: Deps) -> Result<BeneficiaryResponse, ContractError>
     })
 }
 
+pub fn get_gas_oracle_config(deps: Deps) -> Result<GasOracleResponse, ContractError> {
+    let oracle = GAS_ORACLE.may_load(deps.storage)?;
+
+    Ok(GasOracleResponse {
+        oracle: oracle.map(|v| v.into()),
+    })
+}
+
+pub fn get_quote_caps(deps: Deps, domains: Vec<u32>) -> Result<QuoteCapsResponse, ContractError> {
+    Ok(QuoteCapsResponse {
+        caps: domains
+            .into_iter()
+            .map(|v| Ok((v, QUOTE_CAPS.may_load(deps.storage, v)?)))
+            .collect::<StdResult<_>>()?,
+    })
+}
+
+pub fn get_require_payment(deps: Deps) -> Result<RequirePaymentResponse, ContractError> {
+    Ok(RequirePaymentResponse {
+        require_payment: is_payment_required(deps.storage)?,
+    })
+}
+
+pub fn get_authorized_callers(deps: Deps) -> Result<AuthorizedCallersResponse, ContractError> {
+    let callers = AUTHORIZED_CALLERS
+        .keys(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|v| v.map(String::from))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AuthorizedCallersResponse { callers })
+}
+
+pub fn get_gas_paid_for(
+    deps: Deps,
+    message_id: HexBinary,
+) -> Result<GasPaidResponse, ContractError> {
+    let record = GAS_PAID
+        .may_load(deps.storage, message_id.to_vec())?
+        .unwrap_or_default();
+
+    Ok(GasPaidResponse {
+        gas_amount: record.gas_amount,
+        payment: record.payment,
+        settled: record.settled,
+    })
+}
+
 pub fn quote_gas_price(
     storage: &dyn Storage,
     querier: &QuerierWrapper,
     dest_domain: u32,
     fees: Uint256,
 ) -> Result<Uint256, ContractError> {
-    let gas_oracle_set = hpl_router::get_route::<Addr>(storage, dest_domain)?;
-    let gas_oracle = gas_oracle_set
-        .route
-        .ok_or(ContractError::GasOracleNotFound(dest_domain))?;
+    let gas_oracle = get_gas_oracle(storage, dest_domain)?;
 
     let gas_price_resp: GetExchangeRateAndGasPriceResponse = querier.query_wasm_smart(
         gas_oracle,
@@ -78,6 +131,18 @@ pub fn quote_gas_price(
     let gas_needed = (dest_gas_cost * Uint256::from(gas_price_resp.exchange_rate))
         / Uint256::from(TOKEN_EXCHANGE_RATE_SCALE);
 
+    // circuit breaker for a misconfigured oracle
+    if let Some(cap) = QUOTE_CAPS.may_load(storage, dest_domain)? {
+        ensure!(
+            gas_needed <= Uint256::from(cap),
+            ContractError::QuoteExceedsCap {
+                domain: dest_domain,
+                quote: gas_needed,
+                cap,
+            }
+        );
+    }
+
     Ok(gas_needed)
 }
 
@@ -95,7 +160,7 @@ pub fn quote_dispatch(
     deps: Deps,
     req: QuoteDispatchMsg,
 ) -> Result<QuoteDispatchResponse, ContractError> {
-    let igp_message: Message = req.message.into();
+    let igp_message = Message::try_from(req.message)?;
 
     let gas_limit = match req.metadata.len() < 32 {
         true => Uint256::from(crate::get_default_gas(
@@ -104,7 +169,11 @@ pub fn quote_dispatch(
         )?),
         false => {
             let igp_metadata: IGPMetadata = req.metadata.clone().into();
-            igp_metadata.gas_limit
+            crate::get_gas_limit(
+                deps.storage,
+                igp_message.dest_domain,
+                igp_metadata.gas_limit,
+            )?
         }
     };
 
@@ -125,10 +194,7 @@ pub fn get_exchange_rate_and_gas_price(
     deps: Deps,
     dest_domain: u32,
 ) -> Result<GetExchangeRateAndGasPriceResponse, ContractError> {
-    let gas_oracle_set = hpl_router::get_route::<Addr>(deps.storage, dest_domain)?;
-    let gas_oracle = gas_oracle_set
-        .route
-        .ok_or(ContractError::GasOracleNotFound(dest_domain))?;
+    let gas_oracle = get_gas_oracle(deps.storage, dest_domain)?;
 
     Ok(deps.querier.query_wasm_smart(
         gas_oracle,
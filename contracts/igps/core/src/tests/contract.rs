@@ -4,22 +4,26 @@ use cosmwasm_std::{
     to_json_binary, Addr, BankMsg, Coin, ContractResult, HexBinary, Order, QuerierResult,
     StdResult, SubMsg, SystemResult, Uint128, Uint256, WasmQuery,
 };
+use hpl_error::HplError;
 use hpl_interface::{
     igp::{
         core::{
             DefaultGasResponse, ExecuteMsg, GasForDomainResponse, GasOracleConfig, IgpQueryMsg,
-            QueryMsg,
+            InstantiateResponse, QueryMsg, QuoteCapsResponse,
         },
         oracle,
     },
-    types::{IGPMetadata, Message},
+    types::{bech32_encode, IGPMetadata, Message},
 };
 use hpl_ownable::get_owner;
-use hpl_router::get_routes;
+use hpl_router::{get_routes, RouterError};
 use ibcx_test_utils::{addr, gen_bz};
 use rstest::{fixture, rstest};
 
-use crate::{get_default_gas, BENEFICIARY, DEFAULT_GAS_USAGE, GAS_TOKEN, HRP};
+use crate::{
+    get_default_gas, ContractError, GasPaymentRecord, BENEFICIARY, DEFAULT_GAS_USAGE, GAS_TOKEN,
+    HRP, MAILBOX,
+};
 
 use super::IGP;
 
@@ -68,14 +72,22 @@ macro_rules! arg_fixture {
 arg_fixture!(deployer, Addr, addr("deployer"));
 arg_fixture!(hrp, &'static str, "test");
 arg_fixture!(owner, Addr, addr("owner"));
+arg_fixture!(mailbox, Addr, addr("mailbox"));
 arg_fixture!(gas_token, &'static str, "utest");
 arg_fixture!(beneficiary, Addr, addr("beneficiary"));
 
 #[fixture]
-fn igp(deployer: Addr, hrp: &str, owner: Addr, gas_token: &str, beneficiary: Addr) -> IGP {
+fn igp(
+    deployer: Addr,
+    hrp: &str,
+    owner: Addr,
+    mailbox: Addr,
+    gas_token: &str,
+    beneficiary: Addr,
+) -> IGP {
     let mut igp = IGP::new(mock_dependencies(), mock_env());
 
-    igp.init(&deployer, hrp, &owner, gas_token, &beneficiary)
+    igp.init(&deployer, hrp, &owner, &mailbox, gas_token, &beneficiary)
         .unwrap();
 
     igp
@@ -97,21 +109,56 @@ fn igp_routes(
 fn test_init(igp: IGP) {
     let storage = igp.deps_ref().storage;
     assert_eq!(get_owner(storage).unwrap(), "owner");
+    assert_eq!(MAILBOX.load(storage).unwrap(), "mailbox");
     assert_eq!(BENEFICIARY.load(storage).unwrap(), "beneficiary");
     assert_eq!(GAS_TOKEN.load(storage).unwrap(), "utest");
     assert_eq!(HRP.load(storage).unwrap(), "test");
 }
 
 #[rstest]
-#[case(addr("owner"))]
-#[should_panic(expected = "unauthorized")]
-#[case(addr("mailbox"))]
-fn test_set_gas_oracles(mut igp: IGP, #[case] sender: Addr) {
+fn test_init_data(
+    deployer: Addr,
+    hrp: &str,
+    owner: Addr,
+    mailbox: Addr,
+    gas_token: &str,
+    beneficiary: Addr,
+) {
+    let mut igp = IGP::new(mock_dependencies(), mock_env());
+
+    let res = igp
+        .init(&deployer, hrp, &owner, &mailbox, gas_token, &beneficiary)
+        .unwrap();
+
+    let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+    assert_eq!(
+        data,
+        InstantiateResponse {
+            owner,
+            mailbox,
+            beneficiary
+        }
+    );
+}
+
+#[rstest]
+#[case(addr("owner"), None)]
+#[case(addr("mailbox"), Some(RouterError::Unauthorized {}.into()))]
+fn test_set_gas_oracles(
+    mut igp: IGP,
+    #[case] sender: Addr,
+    #[case] expected_err: Option<ContractError>,
+) {
     let configs: Vec<GasOracleConfig> = (1..4)
         .map(|v: u32| (v, format!("oracle/{v}/{v}")).into())
         .collect();
 
-    igp.set_gas_oracles(&sender, configs.clone()).unwrap();
+    let res = igp.set_gas_oracles(&sender, configs.clone());
+    if let Some(expected_err) = expected_err {
+        assert_eq!(res.unwrap_err(), expected_err);
+        return;
+    }
+    res.unwrap();
 
     // check state mutation
     let storage = igp.deps_ref().storage;
@@ -141,6 +188,97 @@ fn test_set_beneficiary(mut igp: IGP, #[case] sender: Addr) {
     assert_eq!(next_beneficiary, actual_beneficiary);
 }
 
+#[rstest]
+#[case(addr("owner"))]
+#[should_panic(expected = "unauthorized")]
+#[case(addr("mailbox"))]
+fn test_set_mailbox(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+    #[case] sender: Addr,
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    igp.set_mailbox(&sender, &addr("next-mailbox"))
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+    assert_eq!(igp.get_mailbox().unwrap().mailbox, "next-mailbox");
+
+    let mut message: Message = gen_bz(100).try_into().unwrap();
+    message.dest_domain = 1;
+
+    let post_dispatch = |igp: &mut IGP, sender: &str| {
+        igp.post_dispatch(
+            &addr(sender),
+            HexBinary::default(),
+            message.clone().into(),
+            vec![coin(9 * DEC_15, "utest")],
+        )
+    };
+
+    // the previous mailbox is no longer honored
+    let err = post_dispatch(&mut igp, "mailbox").unwrap_err();
+    assert_eq!(err, ContractError::Hpl(HplError::Unauthorized {}));
+
+    post_dispatch(&mut igp, "next-mailbox").unwrap();
+}
+
+#[rstest]
+#[case(addr("owner"))]
+#[should_panic(expected = "unauthorized")]
+#[case(addr("stranger"))]
+fn test_set_authorized_caller(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+    #[case] sender: Addr,
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    let mut message: Message = gen_bz(100).try_into().unwrap();
+    message.dest_domain = 1;
+
+    let post_dispatch = |igp: &mut IGP| {
+        igp.post_dispatch(
+            &addr("aggregate"),
+            HexBinary::default(),
+            message.clone().into(),
+            vec![coin(9 * DEC_15, "utest")],
+        )
+    };
+
+    let err = post_dispatch(&mut igp).unwrap_err();
+    assert_eq!(err, ContractError::Hpl(HplError::Unauthorized {}));
+
+    igp.set_authorized_caller(&sender, &addr("aggregate"), true)
+        .map_err(|e| e.to_string())
+        .unwrap();
+    assert_eq!(
+        igp.get_authorized_callers().unwrap().callers,
+        vec![addr("aggregate").to_string()]
+    );
+
+    post_dispatch(&mut igp).unwrap();
+
+    igp.set_authorized_caller(&sender, &addr("aggregate"), false)
+        .unwrap();
+    assert!(igp.get_authorized_callers().unwrap().callers.is_empty());
+
+    let err = post_dispatch(&mut igp).unwrap_err();
+    assert_eq!(err, ContractError::Hpl(HplError::Unauthorized {}));
+}
+
+#[rstest]
+fn test_get_mailbox(mut igp: IGP) {
+    assert_eq!(igp.get_mailbox().unwrap().mailbox, "mailbox");
+
+    // igps instantiated before the mailbox binding
+    MAILBOX.remove(igp.deps_mut().storage);
+    assert_eq!(igp.get_mailbox().unwrap().mailbox, "unrestricted");
+}
+
 #[rstest]
 fn test_get_beneficiary(igp: IGP) {
     let storage = igp.deps_ref().storage;
@@ -169,6 +307,66 @@ fn test_get_quote_gas_payment(
     assert_eq!(resp.gas_needed, Uint256::from_u128(9 * 10u128.pow(15)))
 }
 
+#[rstest]
+#[case(addr("owner"))]
+#[should_panic(expected = "unauthorized")]
+#[case(addr("mailbox"))]
+fn test_set_gas_oracle(mut igp: IGP, #[case] sender: Addr) {
+    igp.set_gas_oracle(&sender, Some("oracle/4/150"))
+        .map_err(|e| e.to_string())
+        .unwrap();
+    assert_eq!(
+        igp.get_gas_oracle().unwrap().oracle,
+        Some("oracle/4/150".to_string())
+    );
+
+    igp.set_gas_oracle(&sender, None).unwrap();
+    assert_eq!(igp.get_gas_oracle().unwrap().oracle, None);
+}
+
+#[rstest]
+#[case(1)]
+#[case(2)]
+fn test_get_quote_gas_payment_with_oracle(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+    #[case] dest_domain: u32,
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    // external oracle overrides routed oracles for every domain
+    igp.set_gas_oracle(&addr("owner"), Some("oracle/4/150"))
+        .unwrap();
+
+    let resp = igp.get_quote_gas_payment(dest_domain, 300_000).unwrap();
+    assert_eq!(resp.gas_needed, Uint256::from_u128(18 * DEC_15));
+
+    let resp = igp.get_exchange_rate_and_gas_price(dest_domain).unwrap();
+    assert_eq!(resp.exchange_rate, Uint128::new(4 * DEC_9));
+
+    igp.pay_for_gas(
+        &addr("user-payer"),
+        &[coin(18 * DEC_15, "utest")],
+        &gen_bz(32),
+        dest_domain,
+        300_000,
+        &addr("user-refund"),
+    )
+    .unwrap();
+
+    // falls back to the routed oracles once unset
+    igp.set_gas_oracle(&addr("owner"), None).unwrap();
+
+    let resp = igp
+        .get_quote_gas_payment(dest_domain, 300_000)
+        .map_err(|e| e.to_string());
+    match dest_domain {
+        1 => assert_eq!(resp.unwrap().gas_needed, Uint256::from_u128(9 * DEC_15)),
+        _ => assert_eq!(resp.unwrap_err(), "[E301] gas oracle not found for 2"),
+    }
+}
+
 #[rstest]
 #[case(1)]
 #[should_panic(expected = "gas oracle not found for 2")]
@@ -242,10 +440,80 @@ fn test_pay_for_gas(
     }
 }
 
+#[rstest]
+#[case(vec![300_000])]
+#[case(vec![300_000, 100_000, 50_000])]
+fn test_gas_paid_for(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+    #[case] gas_amounts: Vec<u128>,
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    let message_id = gen_bz(32);
+
+    // unpaid message
+    let resp = igp.get_gas_paid_for(&message_id).unwrap();
+    assert_eq!(resp.gas_amount, Uint256::zero());
+    assert_eq!(resp.payment, Uint256::zero());
+
+    let mut expected_gas_amount = Uint256::zero();
+    let mut expected_payment = Uint256::zero();
+
+    for gas_amount in gas_amounts {
+        let gas_needed = igp.get_quote_gas_payment(1, gas_amount).unwrap().gas_needed;
+
+        // overpay to make sure refunds are not recorded
+        let res = igp
+            .pay_for_gas(
+                &addr("user-payer"),
+                &[coin(10 * DEC_15, "utest")],
+                &message_id,
+                1,
+                gas_amount,
+                &addr("user-refund"),
+            )
+            .unwrap();
+
+        let event = res
+            .events
+            .into_iter()
+            .find(|v| v.ty == "igp-core-pay-for-gas")
+            .unwrap();
+        let attr = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|v| v.key == key)
+                .unwrap()
+                .value
+                .clone()
+        };
+        assert_eq!(attr("message_id"), message_id.to_hex());
+        assert_eq!(attr("gas_amount"), gas_amount.to_string());
+        assert_eq!(attr("gas_required"), gas_needed.to_string());
+
+        expected_gas_amount += Uint256::from_u128(gas_amount);
+        expected_payment += gas_needed;
+
+        let resp = igp.get_gas_paid_for(&message_id).unwrap();
+        assert_eq!(resp.gas_amount, expected_gas_amount);
+        assert_eq!(resp.payment, expected_payment);
+    }
+
+    // other messages are not affected
+    let resp = igp.get_gas_paid_for(&gen_bz(32)).unwrap();
+    assert_eq!(resp.gas_amount, Uint256::zero());
+}
+
 #[rstest]
 #[case(addr("mailbox"), true, Some(300_000))]
 #[case(addr("mailbox"), true, None)]
 #[case(addr("mailbox"), false, None)]
+#[case(addr("mailbox"), true, Some(0))]
+#[should_panic(expected = "unauthorized")]
+#[case(addr("stranger"), true, None)]
 fn test_post_dispatch(
     #[values("osmo", "neutron")] hrp: &str,
     #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
@@ -276,7 +544,7 @@ fn test_post_dispatch(
         })
         .unwrap_or_default();
 
-    let mut rand_msg: Message = gen_bz(100).into();
+    let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
     rand_msg.sender = addr_sender;
     rand_msg.dest_domain = 1;
 
@@ -305,12 +573,212 @@ fn test_post_dispatch(
         .parse::<u128>()
         .unwrap();
 
+    // a zero gas limit pays for the default gas
     assert_eq!(
-        gas_limit.unwrap_or(DEFAULT_GAS_USAGE.load(igp.deps.as_mut().storage).unwrap()),
+        gas_limit
+            .filter(|v| *v != 0)
+            .unwrap_or(DEFAULT_GAS_USAGE.load(igp.deps.as_mut().storage).unwrap()),
         gas_amount_log
     );
 }
 
+#[rstest]
+#[case::refund_address(gen_bz(32), true)]
+#[case::unset(HexBinary::default(), false)]
+#[case::zero(vec![0; 32].into(), false)]
+fn test_post_dispatch_refund(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+    #[case] refund_address: HexBinary,
+    #[case] to_refund_address: bool,
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    let hrp = HRP.load(igp.deps.as_ref().storage).unwrap();
+
+    let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
+    rand_msg.sender = gen_bz(32);
+    rand_msg.dest_domain = 1;
+
+    let metadata = IGPMetadata {
+        gas_limit: Uint256::from_u128(300_000),
+        refund_address: refund_address.clone(),
+    };
+
+    // a contract dispatching on behalf of a user gets the overpayment to the user
+    let res = igp
+        .post_dispatch(
+            &addr("mailbox"),
+            metadata.into(),
+            rand_msg.clone().into(),
+            vec![coin(10 * DEC_15, "utest")],
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+    let to_address = match to_refund_address {
+        true => bech32_encode(&hrp, &refund_address).unwrap(),
+        false => rand_msg.sender_addr(&hrp).unwrap(),
+    };
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: to_address.to_string(),
+            amount: vec![coin(DEC_15, "utest")]
+        })]
+    );
+}
+
+#[rstest]
+#[case(addr("owner"))]
+#[should_panic(expected = "unauthorized")]
+#[case(addr("someone"))]
+fn test_set_require_payment(mut igp: IGP, #[case] sender: Addr) {
+    assert!(igp.get_require_payment().unwrap().require_payment);
+
+    let res = igp
+        .set_require_payment(&sender, false)
+        .map_err(|e| e.to_string())
+        .unwrap();
+    assert_eq!(res.events[0].attributes[1].value, "false");
+    assert!(!igp.get_require_payment().unwrap().require_payment);
+
+    igp.set_require_payment(&sender, true).unwrap();
+    assert!(igp.get_require_payment().unwrap().require_payment);
+}
+
+#[rstest]
+#[case::enforced(true, 9 * DEC_15, Some(9 * DEC_15))]
+#[should_panic(expected = "insufficient funds")]
+#[case::enforced_underpaid(true, 8 * DEC_15, None)]
+#[case::permissive(false, 10 * DEC_15, Some(9 * DEC_15))]
+#[case::permissive_underpaid(false, 8 * DEC_15, None)]
+#[case::permissive_unpaid(false, 0, None)]
+fn test_post_dispatch_require_payment(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+    #[case] require_payment: bool,
+    #[case] funds: u128,
+    #[case] recorded: Option<u128>,
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+    igp.set_require_payment(&addr("owner"), require_payment)
+        .unwrap();
+
+    let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
+    rand_msg.sender = gen_bz(32);
+    rand_msg.dest_domain = 1;
+
+    // quoted at 9 * DEC_15
+    let metadata = IGPMetadata {
+        gas_limit: Uint256::from_u128(300_000),
+        refund_address: HexBinary::default(),
+    };
+
+    let res = igp
+        .post_dispatch(
+            &addr("mailbox"),
+            metadata.into(),
+            rand_msg.clone().into(),
+            match funds {
+                0 => vec![],
+                v => vec![coin(v, "utest")],
+            },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+    let paid = igp.get_gas_paid_for(&rand_msg.id()).unwrap();
+    assert_eq!(paid.payment, Uint256::from(recorded.unwrap_or_default()));
+
+    let unpaid = res
+        .events
+        .iter()
+        .any(|v| v.ty == "igp-core-post-dispatch-unpaid");
+    assert_eq!(unpaid, recorded.is_none());
+
+    // whatever isn't kept for the message goes back to the sender
+    let refund = funds - recorded.unwrap_or_default();
+    let hrp = HRP.load(igp.deps.as_ref().storage).unwrap();
+    let expected = match refund {
+        0 => vec![],
+        v => vec![SubMsg::new(BankMsg::Send {
+            to_address: rand_msg.sender_addr(&hrp).unwrap().to_string(),
+            amount: vec![coin(v, "utest")],
+        })],
+    };
+    assert_eq!(res.messages, expected);
+}
+
+#[rstest]
+#[case(None, 250_000)]
+#[case(Some(300_000), 300_000)]
+#[case(Some(0), 250_000)]
+fn test_quote_dispatch(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+    #[case] gas_limit: Option<u128>,
+    #[case] expected_gas: u128,
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    let metadata = gas_limit
+        .map(|v| {
+            IGPMetadata {
+                gas_limit: Uint256::from_u128(v),
+                refund_address: HexBinary::default(),
+            }
+            .into()
+        })
+        .unwrap_or_default();
+
+    let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
+    rand_msg.dest_domain = 1;
+
+    let resp = igp.quote_dispatch(metadata, rand_msg.into()).unwrap();
+
+    // 150 gwei gas price at a 0.2 exchange rate
+    assert_eq!(
+        resp.fees,
+        vec![coin(expected_gas * 3 * 10u128.pow(10), "utest")]
+    );
+}
+
+#[rstest]
+fn test_quote_dispatch_default_gas(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    let mut rand_msg: Message = gen_bz(100).try_into().unwrap();
+    rand_msg.dest_domain = 1;
+    let rand_msg: HexBinary = rand_msg.into();
+
+    let resp = igp
+        .quote_dispatch(HexBinary::default(), rand_msg.clone())
+        .unwrap();
+    assert_eq!(resp.fees, vec![coin(250_000 * 3 * 10u128.pow(10), "utest")]);
+
+    igp.execute(
+        mock_info("owner", &[]),
+        ExecuteMsg::SetDefaultGas { gas: 100_000 },
+    )
+    .unwrap();
+
+    let DefaultGasResponse { gas } = igp
+        .query(QueryMsg::Igp(IgpQueryMsg::DefaultGas {}))
+        .unwrap();
+    assert_eq!(gas, 100_000);
+
+    let resp = igp.quote_dispatch(HexBinary::default(), rand_msg).unwrap();
+    assert_eq!(resp.fees, vec![coin(100_000 * 3 * 10u128.pow(10), "utest")]);
+}
+
 #[rstest]
 #[case(addr("beneficiary"), vec![coin(10, "utest")])]
 #[should_panic(expected = "unauthorized")]
@@ -331,6 +799,183 @@ fn test_claim(mut igp: IGP, #[case] sender: Addr, #[case] funds: Vec<Coin>) {
     )
 }
 
+#[rstest]
+#[case(addr("beneficiary"))]
+#[should_panic(expected = "unauthorized")]
+#[case(addr("owner"))]
+fn test_settle_batch(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+    #[case] sender: Addr,
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    let message_ids = vec![gen_bz(32), gen_bz(32)];
+    for (message_id, gas_amount) in message_ids.iter().zip([300_000, 100_000]) {
+        igp.pay_for_gas(
+            &addr("user-payer"),
+            &[coin(10 * DEC_15, "utest")],
+            message_id,
+            1,
+            gas_amount,
+            &addr("user-refund"),
+        )
+        .unwrap();
+    }
+
+    let res = igp
+        .settle_batch(&sender, message_ids.clone(), &addr("relayer"))
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+    // refunds are excluded, only the quoted payments are sent
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: addr("relayer").to_string(),
+            amount: vec![coin(12 * DEC_15, "utest")],
+        })]
+    );
+
+    for message_id in &message_ids {
+        assert!(igp.get_gas_paid_for(message_id).unwrap().settled);
+    }
+}
+
+#[rstest]
+fn test_claim_then_settle(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    let beneficiary = addr("beneficiary");
+    let message_ids = vec![gen_bz(32), gen_bz(32)];
+    for message_id in &message_ids {
+        igp.pay_for_gas(
+            &addr("user-payer"),
+            &[coin(9 * DEC_15, "utest")],
+            message_id,
+            1,
+            300_000,
+            &addr("user-refund"),
+        )
+        .unwrap();
+    }
+
+    // the kept payments plus funds sent to the igp without a record
+    let set_balance = |igp: &mut IGP, amount: u128| {
+        igp.deps
+            .querier
+            .update_balance(mock_env().contract.address, vec![coin(amount, "utest")])
+    };
+    set_balance(&mut igp, 18 * DEC_15 + 5);
+
+    // claim leaves the payments of unsettled records in place
+    let res = igp.claim(&beneficiary).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: beneficiary.to_string(),
+            amount: vec![coin(5, "utest")],
+        })]
+    );
+    set_balance(&mut igp, 18 * DEC_15);
+
+    // nothing left to claim
+    let res = igp.claim(&beneficiary).unwrap();
+    assert!(res.messages.is_empty());
+
+    // so settling them is still paid for
+    let res = igp
+        .settle_batch(&beneficiary, message_ids, &addr("relayer"))
+        .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: addr("relayer").to_string(),
+            amount: vec![coin(18 * DEC_15, "utest")],
+        })]
+    );
+    set_balance(&mut igp, 0);
+
+    let res = igp.claim(&beneficiary).unwrap();
+    assert!(res.messages.is_empty());
+}
+
+#[rstest]
+fn test_legacy_record_settled() {
+    // written before settlement existed, its payment was left to `Claim`
+    let record: GasPaymentRecord = from_json(r#"{"gas_amount":"300000","payment":"900"}"#).unwrap();
+    assert!(record.settled);
+}
+
+#[rstest]
+fn test_settle_batch_twice(
+    #[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>),
+) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    let beneficiary = addr("beneficiary");
+    let (settled, unsettled, duplicated) = (gen_bz(32), gen_bz(32), gen_bz(32));
+    for message_id in [&settled, &unsettled, &duplicated] {
+        igp.pay_for_gas(
+            &addr("user-payer"),
+            &[coin(9 * DEC_15, "utest")],
+            message_id,
+            1,
+            300_000,
+            &addr("user-refund"),
+        )
+        .unwrap();
+    }
+
+    igp.settle_batch(&beneficiary, vec![settled.clone()], &beneficiary)
+        .unwrap();
+
+    // a batch with a settled id fails as a whole
+    let err = igp
+        .settle_batch(&beneficiary, vec![unsettled, settled.clone()], &beneficiary)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::GasPaymentSettled {
+            message_id: settled.to_hex()
+        }
+    );
+
+    // so does a duplicated id
+    let err = igp
+        .settle_batch(
+            &beneficiary,
+            vec![duplicated.clone(), duplicated.clone()],
+            &beneficiary,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::GasPaymentSettled {
+            message_id: duplicated.to_hex()
+        }
+    );
+
+    // and an unpaid one
+    let unpaid = gen_bz(32);
+    let err = igp
+        .settle_batch(&beneficiary, vec![unpaid.clone()], &beneficiary)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::GasPaymentNotFound {
+            message_id: unpaid.to_hex()
+        }
+    );
+}
+
 #[rstest]
 #[case(addr("owner"))]
 #[should_panic(expected = "unauthorized")]
@@ -499,3 +1144,131 @@ fn test_gas_query(mut igp: IGP) {
         gas_for_domain
     );
 }
+
+#[rstest]
+#[case(addr("owner"))]
+#[should_panic(expected = "unauthorized")]
+#[case(addr("someone"))]
+fn test_set_quote_caps(mut igp: IGP, #[case] sender: Addr) {
+    igp.execute(
+        mock_info(sender.as_str(), &[]),
+        ExecuteMsg::SetQuoteCaps {
+            caps: vec![(1, Uint128::new(DEC_15)), (2, Uint128::new(2 * DEC_15))],
+        },
+    )
+    .map_err(|v| v.to_string())
+    .unwrap();
+
+    let QuoteCapsResponse { caps } = igp
+        .query(
+            IgpQueryMsg::QuoteCaps {
+                domains: vec![1, 2, 3],
+            }
+            .wrap(),
+        )
+        .unwrap();
+    assert_eq!(
+        caps,
+        vec![
+            (1, Some(Uint128::new(DEC_15))),
+            (2, Some(Uint128::new(2 * DEC_15))),
+            (3, None)
+        ]
+    );
+
+    igp.execute(
+        mock_info(sender.as_str(), &[]),
+        ExecuteMsg::UnsetQuoteCaps { domains: vec![1] },
+    )
+    .unwrap();
+
+    let QuoteCapsResponse { caps } = igp
+        .query(IgpQueryMsg::QuoteCaps { domains: vec![1] }.wrap())
+        .unwrap();
+    assert_eq!(caps, vec![(1, None)]);
+}
+
+#[rstest]
+fn test_quote_cap(#[with(vec![(1, "oracle/2/150".into())])] igp_routes: (IGP, Vec<(u32, String)>)) {
+    let (mut igp, _) = igp_routes;
+
+    igp.deps.querier.update_wasm(test_mock_querier);
+
+    let cap = Uint128::new(10 * DEC_15);
+    igp.execute(
+        mock_info("owner", &[]),
+        ExecuteMsg::SetQuoteCaps {
+            caps: vec![(1, cap)],
+        },
+    )
+    .unwrap();
+
+    let pay = |igp: &mut IGP| {
+        igp.pay_for_gas(
+            &addr("user-payer"),
+            &[coin(100_000 * DEC_15, "utest")],
+            &gen_bz(32),
+            1,
+            300_000,
+            &addr("user-refund"),
+        )
+    };
+
+    // below the cap
+    let resp = igp.get_quote_gas_payment(1, 300_000).unwrap();
+    assert_eq!(resp.gas_needed, Uint256::from_u128(9 * DEC_15));
+    pay(&mut igp).unwrap();
+
+    // the oracle quotes 10,000x the exchange rate
+    igp.set_gas_oracles(
+        &addr("owner"),
+        vec![(1, "oracle/20000/150".to_string()).into()],
+    )
+    .unwrap();
+
+    let err = ContractError::QuoteExceedsCap {
+        domain: 1,
+        quote: Uint256::from_u128(90_000 * DEC_15),
+        cap,
+    };
+    assert_eq!(igp.get_quote_gas_payment(1, 300_000).unwrap_err(), err);
+    assert_eq!(pay(&mut igp).unwrap_err(), err);
+
+    // fixing the rate lifts the breaker
+    igp.set_gas_oracles(&addr("owner"), vec![(1, "oracle/2/150".to_string()).into()])
+        .unwrap();
+
+    let resp = igp.get_quote_gas_payment(1, 300_000).unwrap();
+    assert_eq!(resp.gas_needed, Uint256::from_u128(9 * DEC_15));
+    pay(&mut igp).unwrap();
+}
+
+#[rstest]
+#[case(
+    ContractError::InsufficientFunds {
+        received: Uint256::from(1u128),
+        gas_needed: Uint256::from(2u128),
+    },
+    300,
+    "[E300] insufficient funds: needed Uint256(2), but only received Uint256(1)"
+)]
+#[case(
+    ContractError::GasOracleNotFound(1),
+    301,
+    "[E301] gas oracle not found for 1"
+)]
+#[case(
+    ContractError::QuoteExceedsCap {
+        domain: 1,
+        quote: Uint256::from(3u128),
+        cap: Uint128::new(2),
+    },
+    304,
+    "[E304] quote 3 exceeds the cap 2 for domain 1"
+)]
+#[case(HplError::Unauthorized {}.into(), 10, "[E010] unauthorized")]
+fn test_error_codes(#[case] err: ContractError, #[case] code: u16, #[case] golden: &str) {
+    assert_eq!(err.code(), code);
+    assert_eq!(err.to_string(), golden);
+    assert!(golden.starts_with(&hpl_error::code_prefix(code)));
+}
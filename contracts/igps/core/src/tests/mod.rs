@@ -4,11 +4,14 @@ use cosmwasm_std::{
     Addr, Coin, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo, OwnedDeps, Response,
 };
 use hpl_interface::{
-    hook::PostDispatchMsg,
+    hook::{
+        AuthorizedCallersResponse, HookQueryMsg, MailboxResponse, PostDispatchMsg,
+        QuoteDispatchMsg, QuoteDispatchResponse,
+    },
     igp::{
         core::{
-            ExecuteMsg, GasOracleConfig, IgpQueryMsg, InstantiateMsg, QueryMsg,
-            QuoteGasPaymentResponse,
+            ExecuteMsg, GasOracleConfig, GasOracleResponse, GasPaidResponse, IgpQueryMsg,
+            InstantiateMsg, QueryMsg, QuoteGasPaymentResponse, RequirePaymentResponse,
         },
         oracle::{GetExchangeRateAndGasPriceResponse, IgpGasOracleQueryMsg},
     },
@@ -42,6 +45,7 @@ impl IGP {
         sender: &Addr,
         hrp: &str,
         owner: &Addr,
+        mailbox: &Addr,
         gas_token: &str,
         beneficiary: &Addr,
     ) -> Result<Response, ContractError> {
@@ -52,6 +56,7 @@ impl IGP {
             InstantiateMsg {
                 hrp: hrp.to_string(),
                 owner: owner.to_string(),
+                mailbox: mailbox.to_string(),
                 gas_token: gas_token.to_string(),
                 beneficiary: beneficiary.to_string(),
                 default_gas_usage: 250_000,
@@ -69,11 +74,11 @@ impl IGP {
             .map_err(|e| e.into())
     }
 
-    pub fn deps_mut(&mut self) -> DepsMut {
+    pub fn deps_mut(&mut self) -> DepsMut<'_> {
         self.deps.as_mut()
     }
 
-    pub fn deps_ref(&self) -> Deps {
+    pub fn deps_ref(&self) -> Deps<'_> {
         self.deps.as_ref()
     }
 
@@ -96,6 +101,19 @@ impl IGP {
         )
     }
 
+    pub fn set_gas_oracle(
+        &mut self,
+        sender: &Addr,
+        oracle: Option<&str>,
+    ) -> Result<Response, ContractError> {
+        self.execute(
+            mock_info(sender.as_str(), &[]),
+            ExecuteMsg::SetGasOracle {
+                oracle: oracle.map(|v| v.to_string()),
+            },
+        )
+    }
+
     pub fn set_beneficiary(
         &mut self,
         sender: &Addr,
@@ -109,10 +127,64 @@ impl IGP {
         )
     }
 
+    pub fn set_mailbox(
+        &mut self,
+        sender: &Addr,
+        mailbox: &Addr,
+    ) -> Result<Response, ContractError> {
+        self.execute(
+            mock_info(sender.as_str(), &[]),
+            ExecuteMsg::SetMailbox {
+                mailbox: mailbox.to_string(),
+            },
+        )
+    }
+
+    pub fn set_authorized_caller(
+        &mut self,
+        sender: &Addr,
+        caller: &Addr,
+        authorized: bool,
+    ) -> Result<Response, ContractError> {
+        self.execute(
+            mock_info(sender.as_str(), &[]),
+            ExecuteMsg::SetAuthorizedCaller {
+                caller: caller.to_string(),
+                authorized,
+            },
+        )
+    }
+
+    pub fn set_require_payment(
+        &mut self,
+        sender: &Addr,
+        require_payment: bool,
+    ) -> Result<Response, ContractError> {
+        self.execute(
+            mock_info(sender.as_str(), &[]),
+            ExecuteMsg::SetRequirePayment { require_payment },
+        )
+    }
+
     pub fn claim(&mut self, sender: &Addr) -> Result<Response, ContractError> {
         self.execute(mock_info(sender.as_str(), &[]), ExecuteMsg::Claim {})
     }
 
+    pub fn settle_batch(
+        &mut self,
+        sender: &Addr,
+        message_ids: Vec<HexBinary>,
+        recipient: &Addr,
+    ) -> Result<Response, ContractError> {
+        self.execute(
+            mock_info(sender.as_str(), &[]),
+            ExecuteMsg::SettleBatch {
+                message_ids,
+                recipient: recipient.to_string(),
+            },
+        )
+    }
+
     pub fn pay_for_gas(
         &mut self,
         sender: &Addr,
@@ -160,6 +232,44 @@ impl IGP {
         )
     }
 
+    pub fn quote_dispatch(
+        &self,
+        metadata: HexBinary,
+        message: HexBinary,
+    ) -> Result<QuoteDispatchResponse, ContractError> {
+        self.query(QueryMsg::Hook(HookQueryMsg::QuoteDispatch(
+            QuoteDispatchMsg { metadata, message },
+        )))
+    }
+
+    pub fn get_mailbox(&self) -> Result<MailboxResponse, ContractError> {
+        self.query(QueryMsg::Hook(HookQueryMsg::Mailbox {}))
+    }
+
+    pub fn get_gas_oracle(&self) -> Result<GasOracleResponse, ContractError> {
+        self.query(IgpQueryMsg::GasOracle {}.wrap())
+    }
+
+    pub fn get_authorized_callers(&self) -> Result<AuthorizedCallersResponse, ContractError> {
+        self.query(IgpQueryMsg::AuthorizedCallers {}.wrap())
+    }
+
+    pub fn get_require_payment(&self) -> Result<RequirePaymentResponse, ContractError> {
+        self.query(IgpQueryMsg::RequirePayment {}.wrap())
+    }
+
+    pub fn get_gas_paid_for(
+        &self,
+        message_id: &HexBinary,
+    ) -> Result<GasPaidResponse, ContractError> {
+        self.query(
+            IgpQueryMsg::GasPaidFor {
+                message_id: message_id.clone(),
+            }
+            .wrap(),
+        )
+    }
+
     pub fn get_exchange_rate_and_gas_price(
         &self,
         dest_domain: u32,
@@ -1,12 +1,13 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, ensure_eq, to_json_binary, Deps, DepsMut, Env, Event, MessageInfo, QueryResponse,
-    Response,
+    ensure, ensure_eq, to_json_binary, Deps, DepsMut, Empty, Env, Event, MessageInfo,
+    QueryResponse, Response,
 };
 
 use hpl_interface::igp::oracle::{
-    ExecuteMsg, GetExchangeRateAndGasPriceResponse, IgpGasOracleQueryMsg, InstantiateMsg, QueryMsg,
+    ExecuteMsg, GetExchangeRateAndGasPriceResponse, IgpGasOracleQueryMsg, InstantiateMsg,
+    InstantiateResponse, QueryMsg,
 };
 use hpl_ownable::get_owner;
 
@@ -29,10 +30,15 @@ pub fn instantiate(
 
     hpl_ownable::initialize(deps.storage, &owner)?;
 
-    Ok(Response::new().add_event(Event::new("init-igp-gas-oracle").add_attribute("owner", owner)))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+        })?)
+        .add_event(Event::new("init-igp-gas-oracle").add_attribute("owner", owner)))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable(Ownership)]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -40,8 +46,6 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Ownership(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
-
         ExecuteMsg::SetRemoteGasDataConfigs { configs } => {
             ensure_eq!(
                 info.sender,
@@ -83,9 +87,10 @@ pub fn execute(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Oracle(msg) => match msg {
             IgpGasOracleQueryMsg::GetExchangeRateAndGasPrice { dest_domain } => {
                 let gas_data = REMOTE_GAS_DATA.load(deps.storage, dest_domain)?;
@@ -98,3 +103,10 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
         },
     }
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
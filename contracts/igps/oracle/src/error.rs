@@ -1,22 +1,2 @@
-use cosmwasm_std::StdError;
-use thiserror::Error;
-
-#[derive(Error, Debug)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-
-    #[error("unauthorized")]
-    Unauthorized {},
-
-    #[error("invalid config. reason: {reason:?}")]
-    InvalidConfig { reason: String },
-}
-
-impl ContractError {
-    pub fn invalid_config(reason: &str) -> Self {
-        Self::InvalidConfig {
-            reason: reason.to_string(),
-        }
-    }
-}
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
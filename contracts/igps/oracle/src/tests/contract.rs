@@ -1,11 +1,26 @@
 use cosmwasm_std::{
+    from_json,
     testing::{mock_dependencies, mock_env},
     Addr, Uint128,
 };
-use hpl_interface::igp::oracle::RemoteGasDataConfig;
+use hpl_interface::igp::oracle::{InstantiateResponse, RemoteGasDataConfig};
 
 use crate::{error::ContractError, tests::IGPGasOracle};
 
+#[test]
+fn test_init() -> anyhow::Result<()> {
+    let owner = Addr::unchecked("owner");
+    let deployer = Addr::unchecked("deployer");
+
+    let mut oracle = IGPGasOracle::new(mock_dependencies(), mock_env());
+    let res = oracle.init(&deployer, &owner)?;
+
+    let data: InstantiateResponse = from_json(res.data.unwrap())?;
+    assert_eq!(data.owner, owner);
+
+    Ok(())
+}
+
 #[test]
 fn test_gas_data() -> anyhow::Result<()> {
     let owner = Addr::unchecked("owner");
@@ -1,17 +1,26 @@
-use cosmwasm_std::{StdError, VerificationError};
+use hpl_error::HplError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
+    #[error(transparent)]
+    Hpl(#[from] HplError),
 
-    #[error("{0}")]
-    VerificationError(#[from] VerificationError),
+    #[error("[E400] invalid ism. address: {0}")]
+    InvalidIsm(String),
 
-    #[error("unauthorized")]
-    Unauthorized,
-
-    #[error("invalid threshold. reason: {0}")]
+    #[error("[E401] invalid threshold. reason: {0}")]
     InvalidThreshold(String),
 }
+
+hpl_error::impl_from_hpl!(ContractError);
+
+impl ContractError {
+    pub fn code(&self) -> u16 {
+        match self {
+            ContractError::Hpl(err) => err.code(),
+            ContractError::InvalidIsm(_) => 400,
+            ContractError::InvalidThreshold(_) => 401,
+        }
+    }
+}
@@ -9,10 +9,12 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 use cw_storage_plus::Item;
+use hpl_error::HplError;
 use hpl_interface::{
     ism::{
         aggregate::{AggregateIsmQueryMsg, ExecuteMsg, InstantiateMsg, IsmsResponse, QueryMsg},
-        IsmQueryMsg, IsmType, ModuleTypeResponse, VerifyInfoResponse, VerifyResponse,
+        InstantiateResponse, IsmMetadataQueryMsg, IsmQueryMsg, IsmType, MetadataLayout,
+        MetadataLayoutResponse, ModuleTypeResponse, VerifyInfoResponse, VerifyResponse,
     },
     to_binary,
     types::{bech32_decode, AggregateMetadata},
@@ -33,6 +35,23 @@ fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_ism_aggregate::{}", name))
 }
 
+/// Validates the addresses of `isms`, each of them has to answer with a known module type
+fn validate_isms(deps: Deps, isms: &[String]) -> Result<Vec<Addr>, ContractError> {
+    let isms = isms
+        .iter()
+        .map(|v| deps.api.addr_validate(v))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for ism in &isms {
+        ensure!(
+            hpl_interface::ism::module_type(&deps.querier, ism)? != IsmType::Unused,
+            ContractError::InvalidIsm(ism.to_string())
+        );
+    }
+
+    Ok(isms)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -43,27 +62,28 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let owner = deps.api.addr_validate(&msg.owner)?;
-    let isms = msg
-        .isms
-        .iter()
-        .map(|v| deps.api.addr_validate(v))
-        .collect::<StdResult<_>>()?;
+    let isms = validate_isms(deps.as_ref(), &msg.isms)?;
 
     hpl_ownable::initialize(deps.storage, &owner)?;
 
     ISMS.save(deps.storage, &isms)?;
     THRESHOLD.save(deps.storage, &msg.threshold)?;
 
-    Ok(Response::new().add_event(
-        new_event("instantiate")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner)
-            .add_attribute("isms", msg.isms.join(","))
-            .add_attribute("threshold", msg.threshold.to_string()),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+        })?)
+        .add_event(
+            new_event("instantiate")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("isms", msg.isms.join(","))
+                .add_attribute("threshold", msg.threshold.to_string()),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -71,12 +91,11 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         ExecuteMsg::SetIsms { isms, threshold } => {
             ensure_eq!(
                 get_owner(deps.storage)?,
                 info.sender,
-                ContractError::Unauthorized
+                HplError::Unauthorized {}
             );
             ensure!(
                 threshold > 0,
@@ -89,26 +108,27 @@ pub fn execute(
                 )
             );
 
-            let parsed_isms = isms
-                .iter()
-                .map(|v| deps.api.addr_validate(v))
-                .collect::<StdResult<_>>()?;
+            let parsed_isms = validate_isms(deps.as_ref(), &isms)?;
 
             ISMS.save(deps.storage, &parsed_isms)?;
             THRESHOLD.save(deps.storage, &threshold)?;
 
-            Ok(Response::new()
-                .add_event(new_event("set_isms").add_attribute("isms", isms.join(","))))
+            Ok(Response::new().add_event(hpl_events::ism_set(
+                new_event("set_isms"),
+                &info.sender,
+                &isms,
+            )))
         }
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     use IsmQueryMsg::*;
 
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
 
         QueryMsg::Ism(msg) => match msg {
             ModuleType {} => to_binary({
@@ -120,6 +140,8 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
             VerifyInfo { message } => to_binary(verify_info(deps, message)),
         },
 
+        QueryMsg::IsmMetadata(msg) => to_binary(metadata_layout(deps, msg)),
+
         QueryMsg::AggregateIsm(msg) => match msg {
             AggregateIsmQueryMsg::Isms {} => Ok(to_json_binary(&IsmsResponse {
                 isms: ISMS
@@ -139,10 +161,16 @@ fn verify(
     message: HexBinary,
 ) -> Result<VerifyResponse, ContractError> {
     let isms = ISMS.load(deps.storage)?;
+    let metadata = AggregateMetadata::from_hex(metadata, isms.len())?;
 
     let mut threshold = THRESHOLD.load(deps.storage)?;
 
-    for (ism, meta) in AggregateMetadata::from_hex(metadata, isms) {
+    for (ism, meta) in isms.into_iter().zip(metadata) {
+        // sub isms without metadata count as not verified
+        let Some(meta) = meta else {
+            continue;
+        };
+
         let verified = hpl_interface::ism::verify(&deps.querier, ism, meta, message.clone())?;
 
         if verified {
@@ -159,6 +187,25 @@ fn verify(
     })
 }
 
+/// Forwards the query to every sub ism, failing if any of them has no layout
+fn metadata_layout(
+    deps: Deps,
+    msg: IsmMetadataQueryMsg,
+) -> Result<MetadataLayoutResponse, ContractError> {
+    let layouts = ISMS
+        .load(deps.storage)?
+        .into_iter()
+        .map(|ism| hpl_interface::ism::metadata_layout(&deps.querier, ism, msg.clone()))
+        .collect::<StdResult<_>>()?;
+
+    Ok(MetadataLayoutResponse {
+        layout: MetadataLayout::Aggregate {
+            threshold: THRESHOLD.load(deps.storage)?,
+            layouts,
+        },
+    })
+}
+
 fn verify_info(deps: Deps, _message: HexBinary) -> Result<VerifyInfoResponse, ContractError> {
     Ok(VerifyInfoResponse {
         threshold: THRESHOLD.load(deps.storage)?,
@@ -171,6 +218,301 @@ fn verify_info(deps: Deps, _message: HexBinary) -> Result<VerifyInfoResponse, Co
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::default())
 }
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{
+        from_json,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        to_json_binary, ContractResult, OwnedDeps, StdError, SystemResult, WasmQuery,
+    };
+    use hpl_interface::ism::{ExpectedIsmMetadataQueryMsg, ExpectedIsmQueryMsg};
+    use ibcx_test_utils::hex;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test]
+    fn test_module_type() -> anyhow::Result<()> {
+        let deps = mock_dependencies();
+
+        let res: ModuleTypeResponse = from_json(query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Ism(IsmQueryMsg::ModuleType {}),
+        )?)?;
+        assert_eq!(res.typ, IsmType::Aggregation);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                isms: vec![],
+                threshold: 1,
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: Addr::unchecked("owner")
+            }
+        );
+    }
+
+    /// answers `ModuleType` of the "unused" contract with an unused ism type
+    fn mock_module_types(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+        deps.querier.update_wasm(|v| match v {
+            WasmQuery::Smart { contract_addr, .. } => SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&ModuleTypeResponse {
+                    typ: match contract_addr.as_str() {
+                        "unused" => IsmType::Unused,
+                        _ => IsmType::MessageIdMultisig,
+                    },
+                })
+                .unwrap(),
+            )),
+            _ => panic!("not implemented"),
+        });
+    }
+
+    #[rstest]
+    #[case(&["ism1", "ism2"])]
+    #[should_panic(expected = "invalid ism. address: unused")]
+    #[case(&["ism1", "unused"])]
+    fn test_init_isms(#[case] isms: &[&str]) {
+        let mut deps = mock_dependencies();
+        mock_module_types(&mut deps);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                isms: isms.iter().map(|v| v.to_string()).collect(),
+                threshold: 1,
+            },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            ISMS.load(deps.as_ref().storage).unwrap(),
+            isms.iter().map(|v| Addr::unchecked(*v)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_set_isms() -> anyhow::Result<()> {
+        let mut deps = mock_dependencies();
+        mock_module_types(&mut deps);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                isms: vec![],
+                threshold: 1,
+            },
+        )?;
+
+        let set_isms = |isms: &[&str]| ExecuteMsg::SetIsms {
+            isms: isms.iter().map(|v| v.to_string()).collect(),
+            threshold: 1,
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            set_isms(&["ism1", "unused"]),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidIsm("unused".to_string()));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            set_isms(&["ism1", "ism2"]),
+        )?;
+
+        let res: IsmsResponse = from_json(query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::AggregateIsm(AggregateIsmQueryMsg::Isms {}),
+        )?)?;
+        assert_eq!(res.isms, vec!["ism1".to_string(), "ism2".to_string()]);
+
+        Ok(())
+    }
+
+    fn deps_verify(threshold: u8) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        mock_module_types(&mut deps);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                isms: vec!["ism1".to_string(), "ism2".to_string()],
+                threshold,
+            },
+        )
+        .unwrap();
+
+        // each sub ism verifies its own metadata only
+        deps.querier.update_wasm(|v| match v {
+            WasmQuery::Smart { contract_addr, msg } => {
+                let ExpectedIsmQueryMsg::Ism(IsmQueryMsg::Verify { metadata, .. }) =
+                    from_json(msg).unwrap()
+                else {
+                    panic!("not implemented")
+                };
+
+                let verified = match contract_addr.as_str() {
+                    "ism1" => metadata == hex("aabbcc"),
+                    _ => metadata.as_slice() == [0xdd; 70],
+                };
+
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&VerifyResponse { verified }).unwrap(),
+                ))
+            }
+            _ => panic!("not implemented"),
+        });
+
+        deps
+    }
+
+    #[rstest]
+    #[case(2, Some(hex("aabbcc")), Some(vec![0xdd; 70].into()), true)]
+    #[case(2, Some(hex("aabbcc")), None, false)]
+    #[case(1, Some(hex("aabbcc")), None, true)]
+    #[case(1, None, Some(vec![0xdd; 70].into()), true)]
+    #[case(1, None, None, false)]
+    // metadata of one sub ism handed to the other
+    #[case(1, Some(vec![0xdd; 70].into()), Some(hex("aabbcc")), false)]
+    fn test_verify(
+        #[case] threshold: u8,
+        #[case] ism1_metadata: Option<HexBinary>,
+        #[case] ism2_metadata: Option<HexBinary>,
+        #[case] expected: bool,
+    ) {
+        let deps = deps_verify(threshold);
+
+        let metadata = AggregateMetadata::new(vec![ism1_metadata, ism2_metadata]);
+        let res = verify(deps.as_ref(), metadata.into(), HexBinary::default()).unwrap();
+
+        assert_eq!(res.verified, expected);
+    }
+
+    #[rstest]
+    #[case(IsmMetadataQueryMsg::MetadataLayout { origin_domain: 1 })]
+    #[case(IsmMetadataQueryMsg::MetadataLayoutFor { message: hex("aabbcc") })]
+    fn test_metadata_layout(#[case] msg: IsmMetadataQueryMsg) {
+        let mut deps = deps_verify(1);
+
+        let layout = MetadataLayout::MessageIdMultisig {
+            threshold: 1,
+            validators_len: 2,
+            expected_len: 133,
+        };
+
+        // sub isms answer the forwarded query, "ism2" doesn't implement it
+        let expected = msg.clone();
+        let sub_layout = layout.clone();
+        deps.querier.update_wasm(move |v| match v {
+            WasmQuery::Smart { contract_addr, msg } => match contract_addr.as_str() {
+                "ism1" => {
+                    let ExpectedIsmMetadataQueryMsg::IsmMetadata(msg) = from_json(msg).unwrap();
+                    assert_eq!(msg, expected);
+
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&MetadataLayoutResponse {
+                            layout: sub_layout.clone(),
+                        })
+                        .unwrap(),
+                    ))
+                }
+                _ => SystemResult::Ok(ContractResult::Err("Error parsing into type".into())),
+            },
+            _ => panic!("not implemented"),
+        });
+
+        let err = metadata_layout(deps.as_ref(), msg.clone()).unwrap_err();
+        assert!(err.to_string().contains("Error parsing into type"));
+
+        ISMS.save(deps.as_mut().storage, &vec![Addr::unchecked("ism1"); 2])
+            .unwrap();
+
+        let res: MetadataLayoutResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::IsmMetadata(msg)).unwrap())
+                .unwrap();
+        assert_eq!(
+            res.layout,
+            MetadataLayout::Aggregate {
+                threshold: 1,
+                layouts: vec![layout.clone(), layout],
+            }
+        );
+    }
+
+    #[test]
+    fn test_verify_malformed() {
+        let deps = deps_verify(1);
+
+        // range of the second sub ism runs past the metadata
+        let metadata = hex("00000010000000130000001300000100aabbcc");
+        let err = verify(deps.as_ref(), metadata, HexBinary::default()).unwrap_err();
+
+        assert_eq!(
+            err,
+            HplError::Std(StdError::generic_err(
+                "metadata truncated. range: 19..256, length: 19"
+            ))
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        for (err, code, golden) in [
+            (
+                ContractError::InvalidIsm("ism".to_string()),
+                400,
+                "[E400] invalid ism. address: ism",
+            ),
+            (
+                ContractError::InvalidThreshold("zero".to_string()),
+                401,
+                "[E401] invalid threshold. reason: zero",
+            ),
+            (HplError::Unauthorized {}.into(), 10, "[E010] unauthorized"),
+        ] {
+            assert_eq!(err.code(), code);
+            assert_eq!(err.to_string(), golden);
+        }
+    }
+}
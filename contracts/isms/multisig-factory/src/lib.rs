@@ -0,0 +1,181 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    ensure, ensure_eq, instantiate2_address, to_json_binary, wasm_execute, Addr, Deps, DepsMut,
+    Empty, Env, Event, MessageInfo, QueryResponse, Response, StdError, WasmMsg,
+};
+use cw_storage_plus::{Item, Map};
+use hpl_interface::{
+    ism::{
+        multisig,
+        multisig_factory::{
+            ExecuteMsg, InstantiateMsg, IsmResponse, MultisigIsmFactoryQueryMsg, QueryMsg,
+        },
+        InstantiateResponse,
+    },
+    ownable::OwnableMsg,
+    types::HexOrBinary,
+};
+
+#[cfg(test)]
+mod multitest;
+
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
+
+// version info for migration info
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const ISM_CODE_ID_KEY: &str = "ism_code_id";
+pub const ISM_CODE_ID: Item<u64> = Item::new(ISM_CODE_ID_KEY);
+
+pub const ISMS_PREFIX: &str = "isms";
+pub const ISMS: Map<u32, Addr> = Map::new(ISMS_PREFIX);
+
+fn new_event(name: &str) -> Event {
+    Event::new(format!("hpl_ism_multisig_factory::{}", name))
+}
+
+/// Salt of the ism created for `domain`
+pub fn ism_salt(domain: u32) -> Vec<u8> {
+    domain.to_be_bytes().to_vec()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let owner = deps.api.addr_validate(&msg.owner)?;
+
+    hpl_ownable::initialize(deps.storage, &owner)?;
+
+    ISM_CODE_ID.save(deps.storage, &msg.ism_code_id)?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("ism_code_id", msg.ism_code_id.to_string()),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateIsm {
+            domain,
+            validators,
+            threshold,
+        } => create_ism(deps, env, info, domain, validators, threshold),
+    }
+}
+
+fn create_ism(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    domain: u32,
+    validators: Vec<HexOrBinary>,
+    threshold: u8,
+) -> Result<Response, ContractError> {
+    let owner = hpl_ownable::get_owner(deps.storage)?;
+    ensure_eq!(owner, info.sender, ContractError::Unauthorized {});
+
+    ensure!(
+        !ISMS.has(deps.storage, domain),
+        ContractError::invalid_args(&format!("ism already exists for domain {domain}"))
+    );
+
+    let code_id = ISM_CODE_ID.load(deps.storage)?;
+    let salt = ism_salt(domain);
+
+    // the address is known upfront, so the ism is configured in the same response
+    let checksum = deps.querier.query_wasm_code_info(code_id)?.checksum;
+    let creator = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+    let ism = instantiate2_address(&checksum, &creator, &salt)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+    let ism = deps.api.addr_humanize(&ism)?;
+
+    ISMS.save(deps.storage, domain, &ism)?;
+
+    let init_msg = WasmMsg::Instantiate2 {
+        admin: Some(owner.to_string()),
+        code_id,
+        label: format!("hpl_ism_multisig {domain}"),
+        msg: to_json_binary(&multisig::InstantiateMsg {
+            owner: env.contract.address.to_string(),
+            module_type: None,
+        })?,
+        funds: vec![],
+        salt: salt.into(),
+    };
+
+    let set_validators_msg = wasm_execute(
+        &ism,
+        &multisig::ExecuteMsg::SetValidators {
+            domain,
+            threshold,
+            validators,
+        },
+        vec![],
+    )?;
+
+    let transfer_msg = wasm_execute(
+        &ism,
+        &multisig::ExecuteMsg::Ownable(OwnableMsg::InitOwnershipTransfer {
+            next_owner: owner.to_string(),
+            deadline: None,
+        }),
+        vec![],
+    )?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&IsmResponse {
+            ism: Some(ism.clone()),
+        })?)
+        .add_message(init_msg)
+        .add_message(set_validators_msg)
+        .add_message(transfer_msg)
+        .add_event(
+            new_event("create_ism")
+                .add_attribute("sender", info.sender)
+                .add_attribute("domain", domain.to_string())
+                .add_attribute("ism", ism),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+    match msg {
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
+        QueryMsg::MultisigIsmFactory(MultisigIsmFactoryQueryMsg::GetIsm { domain }) => {
+            Ok(to_json_binary(&IsmResponse {
+                ism: ISMS.may_load(deps.storage, domain)?,
+            })?)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
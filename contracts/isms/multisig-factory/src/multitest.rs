@@ -0,0 +1,188 @@
+use cosmwasm_std::{Addr, Empty};
+use cw_multi_test::{
+    addons::{MockAddressGenerator, MockApiBech32},
+    App, AppBuilder, BankKeeper, Contract, ContractWrapper, Executor, WasmKeeper,
+};
+use hpl_interface::{
+    ism::{
+        multisig::{self, EnrolledValidatorsResponse, MultisigIsmQueryMsg},
+        multisig_factory::{
+            ExecuteMsg, InstantiateMsg, IsmResponse, MultisigIsmFactoryQueryMsg, QueryMsg,
+        },
+    },
+    ownable::{OwnableMsg, OwnableQueryMsg, OwnerResponse, PendingOwnerResponse},
+};
+use ibcx_test_utils::hex;
+
+use crate::{execute, instantiate, query};
+
+type TestApp = App<BankKeeper, MockApiBech32>;
+
+const HRP: &str = "osmo";
+const VALIDATOR: &str = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+
+fn multisig() -> Box<dyn Contract<Empty>> {
+    use hpl_ism_multisig::contract::{execute, instantiate, query};
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn factory() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+struct Suite {
+    app: TestApp,
+    owner: Addr,
+    factory: Addr,
+}
+
+impl Suite {
+    fn create_ism(&mut self, sender: &Addr, domain: u32, threshold: u8) -> anyhow::Result<()> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.factory.clone(),
+            &ExecuteMsg::CreateIsm {
+                domain,
+                validators: vec![hex(VALIDATOR).into()],
+                threshold,
+            },
+            &[],
+        )?;
+
+        Ok(())
+    }
+
+    fn get_ism(&self, domain: u32) -> anyhow::Result<Option<Addr>> {
+        let res: IsmResponse = self.app.wrap().query_wasm_smart(
+            &self.factory,
+            &QueryMsg::MultisigIsmFactory(MultisigIsmFactoryQueryMsg::GetIsm { domain }),
+        )?;
+
+        Ok(res.ism)
+    }
+}
+
+fn setup() -> anyhow::Result<Suite> {
+    let mut app = AppBuilder::default()
+        .with_api(MockApiBech32::new(HRP))
+        .with_wasm(WasmKeeper::default().with_address_generator(MockAddressGenerator))
+        .build(|_, _, _| {});
+
+    let owner = app.api().addr_make("owner");
+
+    let ism_code_id = app.store_code(multisig());
+    let factory_code_id = app.store_code(factory());
+    let factory = app.instantiate_contract(
+        factory_code_id,
+        owner.clone(),
+        &InstantiateMsg {
+            owner: owner.to_string(),
+            ism_code_id,
+        },
+        &[],
+        "factory",
+        None,
+    )?;
+
+    Ok(Suite {
+        app,
+        owner,
+        factory,
+    })
+}
+
+#[test]
+fn test_create_ism() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+    let owner = suite.owner.clone();
+
+    assert_eq!(suite.get_ism(1)?, None);
+
+    suite.create_ism(&owner, 1, 1)?;
+
+    // configured right away, so the recorded address is the instantiated one
+    let ism = suite.get_ism(1)?.expect("ism is recorded");
+    let res: EnrolledValidatorsResponse = suite.app.wrap().query_wasm_smart(
+        &ism,
+        &multisig::QueryMsg::MultisigIsm(MultisigIsmQueryMsg::EnrolledValidators { domain: 1 }),
+    )?;
+    assert_eq!(res.validators, vec![hex(VALIDATOR)]);
+    assert_eq!(res.threshold, 1);
+
+    // the factory owner takes the ism over
+    let res: PendingOwnerResponse = suite.app.wrap().query_wasm_smart(
+        &ism,
+        &multisig::QueryMsg::Ownable(OwnableQueryMsg::GetPendingOwner {}),
+    )?;
+    assert_eq!(res.pending_owner, Some(owner.clone()));
+
+    suite.app.execute_contract(
+        owner.clone(),
+        ism.clone(),
+        &multisig::ExecuteMsg::Ownable(OwnableMsg::ClaimOwnership {}),
+        &[],
+    )?;
+
+    let res: OwnerResponse = suite.app.wrap().query_wasm_smart(
+        &ism,
+        &multisig::QueryMsg::Ownable(OwnableQueryMsg::GetOwner {}),
+    )?;
+    assert_eq!(res.owner, owner);
+
+    // every domain gets its own address
+    suite.create_ism(&owner, 2, 1)?;
+    assert_ne!(suite.get_ism(2)?, Some(ism));
+
+    Ok(())
+}
+
+#[test]
+fn test_create_ism_invalid() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+    let owner = suite.owner.clone();
+    let someone = suite.app.api().addr_make("someone");
+
+    let err = suite.create_ism(&someone, 1, 1).unwrap_err();
+    assert!(err.root_cause().to_string().contains("unauthorized"));
+
+    // rejected by the ism, nothing is recorded
+    let err = suite.create_ism(&owner, 1, 2).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("threshold not in range"));
+    assert_eq!(suite.get_ism(1)?, None);
+
+    suite.create_ism(&owner, 1, 1)?;
+
+    let err = suite.create_ism(&owner, 1, 1).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("ism already exists for domain 1"));
+
+    Ok(())
+}
+
+#[test]
+fn test_create_ism_data() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+    let owner = suite.owner.clone();
+
+    let res = suite.app.execute_contract(
+        owner,
+        suite.factory.clone(),
+        &ExecuteMsg::CreateIsm {
+            domain: 1,
+            validators: vec![hex(VALIDATOR).into()],
+            threshold: 1,
+        },
+        &[],
+    )?;
+
+    let data: IsmResponse = cosmwasm_std::from_json(res.data.unwrap())?;
+    assert_eq!(data.ism, suite.get_ism(1)?);
+
+    Ok(())
+}
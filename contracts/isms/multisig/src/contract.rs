@@ -1,23 +1,27 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, ensure_eq, Deps, DepsMut, Empty, Env, Event, MessageInfo, QueryResponse, Response,
+    ensure, ensure_eq, to_json_binary, Deps, DepsMut, Empty, Env, Event, HexBinary, MessageInfo,
+    QueryResponse, Response,
 };
 use cw2::set_contract_version;
+use hpl_error::HplError;
 use hpl_interface::{
+    core::va::{self, GetAnnounceStorageLocationsResponse},
     ism::{
-        multisig::{
-            EnrolledValidatorsResponse, ExecuteMsg, InstantiateMsg, MultisigIsmQueryMsg, QueryMsg,
-        },
-        IsmQueryMsg,
+        multisig::{ExecuteMsg, InstantiateMsg, MultisigIsmQueryMsg, QueryMsg, WeightedValidator},
+        InstantiateResponse, IsmMetadataQueryMsg, IsmQueryMsg, IsmType,
     },
     to_binary,
+    types::{HexOrBinary, Message},
 };
 use hpl_ownable::get_owner;
 
 use crate::{
     error::ContractError,
-    state::{THRESHOLD, VALIDATORS},
+    state::{
+        EPOCH_BASE, MODULE_TYPE, THRESHOLD, THRESHOLD_WEIGHT, VALIDATORS, WEIGHTED_VALIDATORS,
+    },
     CONTRACT_NAME, CONTRACT_VERSION,
 };
 
@@ -32,13 +36,28 @@ pub fn instantiate(
 
     let owner = deps.api.addr_validate(&msg.owner)?;
 
+    let module_type = msg.module_type.unwrap_or(IsmType::MessageIdMultisig);
+    ensure!(
+        matches!(
+            module_type,
+            IsmType::MessageIdMultisig | IsmType::LegacyMultisig
+        ),
+        ContractError::invalid_args(&format!("unsupported module type: {module_type:?}"))
+    );
+
     hpl_ownable::initialize(deps.storage, &owner)?;
 
-    Ok(Response::new().add_attribute("method", "instantiate"))
+    MODULE_TYPE.save(deps.storage, &module_type)?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse { owner })?)
+        .add_attribute("method", "instantiate")
+        .add_attribute("module_type", format!("{module_type:?}")))
 }
 
 /// Handling contract execution
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -48,7 +67,6 @@ pub fn execute(
     use ExecuteMsg::*;
 
     match msg {
-        Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         SetValidators {
             domain,
             threshold,
@@ -57,8 +75,10 @@ pub fn execute(
             ensure_eq!(
                 info.sender,
                 get_owner(deps.storage)?,
-                ContractError::Unauthorized {}
+                HplError::Unauthorized {}
             );
+
+            let validators: Vec<HexBinary> = validators.into_iter().map(Into::into).collect();
             ensure!(
                 validators.iter().all(|v| v.len() == 20),
                 ContractError::invalid_addr("length should be 20")
@@ -73,6 +93,8 @@ pub fn execute(
 
             VALIDATORS.save(deps.storage, domain, &validators)?;
             THRESHOLD.save(deps.storage, domain, &threshold)?;
+            WEIGHTED_VALIDATORS.remove(deps.storage, domain);
+            THRESHOLD_WEIGHT.remove(deps.storage, domain);
 
             Ok(Response::new().add_event(
                 Event::new("ism_multisig_set_validators")
@@ -86,11 +108,13 @@ pub fn execute(
             ensure_eq!(
                 info.sender,
                 get_owner(deps.storage)?,
-                ContractError::Unauthorized {}
+                HplError::Unauthorized {}
             );
 
             VALIDATORS.remove(deps.storage, domain);
             THRESHOLD.remove(deps.storage, domain);
+            WEIGHTED_VALIDATORS.remove(deps.storage, domain);
+            THRESHOLD_WEIGHT.remove(deps.storage, domain);
 
             Ok(Response::new().add_event(
                 Event::new("ism_multisig_unset_domain")
@@ -98,19 +122,176 @@ pub fn execute(
                     .add_attribute("domain", domain.to_string()),
             ))
         }
+        ImportValidators {
+            domain,
+            validator_announce,
+            validators,
+        } => import_validators(deps, info, domain, validator_announce, validators),
+        SetWeightedValidators {
+            domain,
+            threshold_weight,
+            validators,
+        } => set_weighted_validators(deps, info, domain, threshold_weight, validators),
+        SetEpochBase { domain, base } => {
+            ensure_eq!(
+                info.sender,
+                get_owner(deps.storage)?,
+                HplError::Unauthorized {}
+            );
+
+            EPOCH_BASE.save(deps.storage, domain, &base)?;
+
+            Ok(Response::new().add_event(
+                Event::new("ism_multisig_set_epoch_base")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("domain", domain.to_string())
+                    .add_attribute("base", base.to_string()),
+            ))
+        }
+    }
+}
+
+fn set_weighted_validators(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+    threshold_weight: u64,
+    validators: Vec<WeightedValidator>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    let typ = crate::query::get_module_type(deps.as_ref())?.typ;
+    ensure!(
+        typ == IsmType::MessageIdMultisig,
+        ContractError::invalid_args(&format!("weighted validators not supported for {typ:?}"))
+    );
+
+    let validators: Vec<(HexBinary, u32)> = validators
+        .into_iter()
+        .map(|v| (v.validator.into(), v.weight))
+        .collect();
+    ensure!(
+        validators.iter().all(|(v, _)| v.len() == 20),
+        ContractError::invalid_addr("length should be 20")
+    );
+    ensure!(
+        validators.iter().all(|(_, w)| *w > 0),
+        ContractError::invalid_args("weight should be positive")
+    );
+    for (i, (validator, _)) in validators.iter().enumerate() {
+        ensure!(
+            validators[..i].iter().all(|(v, _)| v != validator),
+            ContractError::ValidatorDuplicate
+        );
+    }
+
+    let total_weight: u64 = validators.iter().map(|(_, w)| *w as u64).sum();
+    ensure!(
+        total_weight >= threshold_weight && threshold_weight > 0,
+        ContractError::invalid_args(&format!(
+            "threshold weight not in range. 0 <  <= {total_weight}"
+        ))
+    );
+
+    WEIGHTED_VALIDATORS.save(deps.storage, domain, &validators)?;
+    THRESHOLD_WEIGHT.save(deps.storage, domain, &threshold_weight)?;
+    VALIDATORS.remove(deps.storage, domain);
+    THRESHOLD.remove(deps.storage, domain);
+
+    Ok(Response::new().add_event(
+        Event::new("ism_multisig_set_weighted_validators")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("validators", validators.len().to_string())
+            .add_attribute("threshold_weight", threshold_weight.to_string()),
+    ))
+}
+
+fn import_validators(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+    validator_announce: String,
+    validators: Vec<HexOrBinary>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    let validators: Vec<HexBinary> = validators.into_iter().map(Into::into).collect();
+    ensure!(
+        validators.iter().all(|v| v.len() == 20),
+        ContractError::invalid_addr("length should be 20")
+    );
+
+    let threshold = THRESHOLD
+        .may_load(deps.storage, domain)?
+        .ok_or_else(|| ContractError::invalid_args(&format!("domain {domain} not configured")))?;
+
+    let validator_announce = deps.api.addr_validate(&validator_announce)?;
+    let GetAnnounceStorageLocationsResponse { storage_locations } = deps.querier.query_wasm_smart(
+        &validator_announce,
+        &va::QueryMsg::GetAnnounceStorageLocations {
+            validators: validators.clone(),
+        },
+    )?;
+
+    // locations come back in the order they were asked for
+    ensure!(
+        storage_locations.len() == validators.len(),
+        ContractError::invalid_args(&format!(
+            "validator announce returned {} storage locations for {} validators",
+            storage_locations.len(),
+            validators.len()
+        ))
+    );
+    for (validator, (_, locations)) in validators.iter().zip(storage_locations) {
+        ensure!(
+            !locations.is_empty(),
+            ContractError::ValidatorNotAnnounced {
+                validator: validator.to_hex()
+            }
+        );
+    }
+
+    let mut enrolled = VALIDATORS.load(deps.storage, domain)?;
+    let before = enrolled.len();
+    for validator in validators {
+        if !enrolled.contains(&validator) {
+            enrolled.push(validator);
+        }
     }
+
+    VALIDATORS.save(deps.storage, domain, &enrolled)?;
+
+    Ok(Response::new().add_event(
+        Event::new("ism_multisig_import_validators")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("validator_announce", validator_announce)
+            .add_attribute("imported", (enrolled.len() - before).to_string())
+            .add_attribute("validators", enrolled.len().to_string())
+            .add_attribute("threshold", threshold.to_string()),
+    ))
 }
 
 /// Handling contract query
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     use crate::query;
     use IsmQueryMsg::*;
 
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Ism(msg) => match msg {
-            ModuleType {} => to_binary(query::get_module_type()),
+            ModuleType {} => to_binary(query::get_module_type(deps)),
             Verify {
                 metadata: raw_metadata,
                 message: raw_message,
@@ -119,42 +300,88 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
                 message: raw_message,
             } => to_binary(query::get_verify_info(deps, raw_message)),
         },
-        QueryMsg::MultisigIsm(msg) => match msg {
-            MultisigIsmQueryMsg::EnrolledValidators { domain } => to_binary({
-                let validators = VALIDATORS.load(deps.storage, domain)?;
-                let threshold = THRESHOLD.load(deps.storage, domain)?;
-
-                Ok::<_, ContractError>(EnrolledValidatorsResponse {
-                    validators,
-                    threshold,
-                })
+        QueryMsg::IsmMetadata(msg) => match msg {
+            IsmMetadataQueryMsg::MetadataLayout { origin_domain } => {
+                to_binary(query::get_metadata_layout(deps, origin_domain))
+            }
+            IsmMetadataQueryMsg::MetadataLayoutFor {
+                message: raw_message,
+            } => to_binary({
+                let message = Message::try_from(raw_message)?;
+                query::get_metadata_layout(deps, message.origin_domain)
             }),
         },
+        QueryMsg::MultisigIsm(msg) => match msg {
+            MultisigIsmQueryMsg::EnrolledValidators { domain } => {
+                to_binary(query::get_enrolled_validators(deps, domain))
+            }
+            MultisigIsmQueryMsg::WeightedValidators { domain } => {
+                to_binary(query::get_weighted_validators(deps, domain))
+            }
+            MultisigIsmQueryMsg::EpochBase { domain } => {
+                to_binary(query::get_epoch_base(deps, domain))
+            }
+        },
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
     Ok(Response::new())
 }
 
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
-        testing::mock_dependencies, HexBinary,
+        from_json,
+        testing::{mock_dependencies, mock_env, mock_info},
+        Deps, HexBinary,
     };
     use hpl_interface::{
         build_test_executor, build_test_querier,
-        ism::multisig::ExecuteMsg,
+        ism::{
+            multisig::{
+                EnrolledValidatorsResponse, EpochBaseResponse, ExecuteMsg, InstantiateMsg,
+                MultisigIsmQueryMsg, QueryMsg, WeightedValidator, WeightedValidatorsResponse,
+            },
+            InstantiateResponse, IsmType,
+        },
     };
     use ibcx_test_utils::{addr, hex};
     use rstest::rstest;
 
-    use crate::state::VALIDATORS;
+    use crate::state::{MODULE_TYPE, THRESHOLD, THRESHOLD_WEIGHT, VALIDATORS, WEIGHTED_VALIDATORS};
 
     build_test_executor!(crate::contract::execute);
     build_test_querier!(crate::contract::query);
 
+    #[rstest]
+    #[case(None, IsmType::MessageIdMultisig)]
+    #[case(Some(IsmType::LegacyMultisig), IsmType::LegacyMultisig)]
+    #[should_panic(expected = "unsupported module type: Routing")]
+    #[case(Some(IsmType::Routing), IsmType::Routing)]
+    fn test_init(#[case] module_type: Option<IsmType>, #[case] expected: IsmType) {
+        let mut deps = mock_dependencies();
+
+        let res = super::instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                module_type,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(MODULE_TYPE.load(deps.as_ref().storage).unwrap(), expected);
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(data.owner, "owner");
+    }
+
     #[rstest]
     #[case("owner", vec![hex(&"deadbeef".repeat(5))])]
     #[should_panic(expected = "unauthorized")]
@@ -170,7 +397,7 @@ mod test {
             ExecuteMsg::SetValidators {
                 domain: 1,
                 threshold: 1,
-                validators: validators.clone(),
+                validators: validators.iter().cloned().map(Into::into).collect(),
             },
             vec![],
         );
@@ -181,6 +408,36 @@ mod test {
         );
     }
 
+    #[rstest]
+    #[case("owner")]
+    #[should_panic(expected = "unauthorized")]
+    #[case("someone")]
+    fn test_set_epoch_base(#[case] sender: &str) {
+        let mut deps = mock_dependencies();
+
+        hpl_ownable::initialize(deps.as_mut().storage, &addr("owner")).unwrap();
+
+        let epoch_base = |deps: Deps| {
+            let res: EpochBaseResponse = test_query(
+                deps,
+                QueryMsg::MultisigIsm(MultisigIsmQueryMsg::EpochBase { domain: 1 }),
+            );
+            res.base
+        };
+        assert_eq!(epoch_base(deps.as_ref()), 0);
+
+        test_execute(
+            deps.as_mut(),
+            &addr(sender),
+            ExecuteMsg::SetEpochBase {
+                domain: 1,
+                base: 42,
+            },
+            vec![],
+        );
+        assert_eq!(epoch_base(deps.as_ref()), 42);
+    }
+
     #[rstest]
     #[case("owner")]
     #[should_panic(expected = "unauthorized")]
@@ -196,7 +453,7 @@ mod test {
             ExecuteMsg::SetValidators {
                 domain: 1,
                 threshold: 1,
-                validators: vec![hex(&"deadbeef".repeat(5))],
+                validators: vec![hex(&"deadbeef".repeat(5)).into()],
             },
             vec![],
         );
@@ -204,10 +461,125 @@ mod test {
         test_execute(
             deps.as_mut(),
             &addr(sender),
-            ExecuteMsg::UnsetDomain { domain: 1 } ,
+            ExecuteMsg::UnsetDomain { domain: 1 },
             vec![],
         );
 
         assert!(!VALIDATORS.has(deps.as_ref().storage, 1));
     }
+
+    fn weighted(validators: &[(&str, u32)]) -> Vec<WeightedValidator> {
+        validators
+            .iter()
+            .map(|(v, weight)| WeightedValidator {
+                validator: hex(v).into(),
+                weight: *weight,
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case("owner")]
+    #[should_panic(expected = "unauthorized")]
+    #[case("someone")]
+    fn test_set_weighted_validators(#[case] sender: &str) {
+        let mut deps = mock_dependencies();
+
+        hpl_ownable::initialize(deps.as_mut().storage, &addr("owner")).unwrap();
+
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::SetValidators {
+                domain: 1,
+                threshold: 1,
+                validators: vec![hex(&"deadbeef".repeat(5)).into()],
+            },
+            vec![],
+        );
+
+        let validators = weighted(&[(&"ab".repeat(20), 3), (&"cd".repeat(20), 1)]);
+        test_execute(
+            deps.as_mut(),
+            &addr(sender),
+            ExecuteMsg::SetWeightedValidators {
+                domain: 1,
+                threshold_weight: 3,
+                validators,
+            },
+            vec![],
+        );
+
+        // the weighted set replaces the plain one
+        assert!(!VALIDATORS.has(deps.as_ref().storage, 1));
+        assert!(!THRESHOLD.has(deps.as_ref().storage, 1));
+        assert_eq!(
+            WEIGHTED_VALIDATORS.load(deps.as_ref().storage, 1).unwrap(),
+            vec![(hex(&"ab".repeat(20)), 3), (hex(&"cd".repeat(20)), 1)]
+        );
+        assert_eq!(THRESHOLD_WEIGHT.load(deps.as_ref().storage, 1).unwrap(), 3);
+
+        let res: WeightedValidatorsResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::MultisigIsm(MultisigIsmQueryMsg::WeightedValidators { domain: 1 }),
+        );
+        assert_eq!(res.threshold_weight, 3);
+
+        let res: EnrolledValidatorsResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::MultisigIsm(MultisigIsmQueryMsg::EnrolledValidators { domain: 1 }),
+        );
+        assert_eq!(res.threshold, 1);
+
+        // and the other way around
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::SetValidators {
+                domain: 1,
+                threshold: 1,
+                validators: vec![hex(&"deadbeef".repeat(5)).into()],
+            },
+            vec![],
+        );
+        assert!(!WEIGHTED_VALIDATORS.has(deps.as_ref().storage, 1));
+        assert!(!THRESHOLD_WEIGHT.has(deps.as_ref().storage, 1));
+    }
+
+    #[rstest]
+    #[should_panic(expected = "weight should be positive")]
+    #[case(weighted(&[(&"ab".repeat(20), 0)]), 1, None)]
+    #[should_panic(expected = "threshold weight not in range")]
+    #[case(weighted(&[(&"ab".repeat(20), 2), (&"cd".repeat(20), 1)]), 4, None)]
+    #[should_panic(expected = "threshold weight not in range")]
+    #[case(weighted(&[(&"ab".repeat(20), 2)]), 0, None)]
+    #[should_panic(expected = "duplicate validator")]
+    #[case(weighted(&[(&"ab".repeat(20), 2), (&"ab".repeat(20), 1)]), 1, None)]
+    #[should_panic(expected = "length should be 20")]
+    #[case(weighted(&[(&"ab".repeat(19), 1)]), 1, None)]
+    #[should_panic(expected = "weighted validators not supported for LegacyMultisig")]
+    #[case(weighted(&[(&"ab".repeat(20), 1)]), 1, Some(IsmType::LegacyMultisig))]
+    fn test_set_weighted_validators_invalid(
+        #[case] validators: Vec<WeightedValidator>,
+        #[case] threshold_weight: u64,
+        #[case] module_type: Option<IsmType>,
+    ) {
+        let mut deps = mock_dependencies();
+
+        hpl_ownable::initialize(deps.as_mut().storage, &addr("owner")).unwrap();
+        if let Some(typ) = module_type {
+            MODULE_TYPE.save(deps.as_mut().storage, &typ).unwrap();
+        }
+
+        test_execute(
+            deps.as_mut(),
+            &addr("owner"),
+            ExecuteMsg::SetWeightedValidators {
+                domain: 1,
+                threshold_weight,
+                validators,
+            },
+            vec![],
+        );
+    }
 }
@@ -1,47 +1,55 @@
-use cosmwasm_std::{RecoverPubkeyError, StdError, VerificationError};
+use hpl_error::HplError;
+use hpl_interface::ism::IsmType;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
+    #[error(transparent)]
+    Hpl(#[from] HplError),
 
-    #[error("{0}")]
-    VerificationError(#[from] VerificationError),
-
-    #[error("{0}")]
-    RecoverPubkeyError(#[from] RecoverPubkeyError),
-
-    #[error("unauthorized")]
-    Unauthorized,
+    #[error("[E410] invalid pubkey")]
+    InvalidPubKey,
 
-    #[error("wrong length")]
-    WrongLength,
+    #[error("[E411] duplicate validator")]
+    ValidatorDuplicate,
 
-    #[error("invalid pubkey")]
-    InvalidPubKey,
+    #[error("[E412] validator not exists")]
+    ValidatorNotExist,
 
-    #[error("invalid address. reason: {0}")]
-    InvalidAddress(String),
+    #[error("[E413] validator set in metadata does not match enrolled set")]
+    ValidatorSetMismatch,
 
-    #[error("invalid arguments. reason: {reason:?}")]
-    InvalidArguments { reason: String },
+    #[error("[E414] metadata layout is not supported for {typ:?}")]
+    UnsupportedMetadataLayout { typ: IsmType },
 
-    #[error("duplicate validator")]
-    ValidatorDuplicate,
+    #[error("[E415] validator not announced: {validator}")]
+    ValidatorNotAnnounced { validator: String },
 
-    #[error("validator not exists")]
-    ValidatorNotExist,
+    #[error("[E416] malleable signature: s is above the secp256k1 half order")]
+    HighS,
 }
 
+hpl_error::impl_from_hpl!(ContractError);
+
 impl ContractError {
+    pub fn code(&self) -> u16 {
+        match self {
+            ContractError::Hpl(err) => err.code(),
+            ContractError::InvalidPubKey => 410,
+            ContractError::ValidatorDuplicate => 411,
+            ContractError::ValidatorNotExist => 412,
+            ContractError::ValidatorSetMismatch => 413,
+            ContractError::UnsupportedMetadataLayout { .. } => 414,
+            ContractError::ValidatorNotAnnounced { .. } => 415,
+            ContractError::HighS => 416,
+        }
+    }
+
     pub fn invalid_addr(reason: &str) -> Self {
-        ContractError::InvalidAddress(reason.into())
+        HplError::invalid_addr(reason).into()
     }
 
     pub fn invalid_args(reason: &str) -> Self {
-        ContractError::InvalidArguments {
-            reason: reason.into(),
-        }
+        HplError::invalid_args(reason).into()
     }
 }
@@ -0,0 +1,309 @@
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo, Response,
+    StdResult,
+};
+use cw_multi_test::{
+    addons::{MockAddressGenerator, MockApiBech32},
+    App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper, Executor, WasmKeeper,
+};
+use hpl_interface::{
+    core::{mailbox, va},
+    digest::{announcement_digest, eth_signed_message_hash},
+    ism::multisig::{
+        EnrolledValidatorsResponse, ExecuteMsg, InstantiateMsg, MultisigIsmQueryMsg, QueryMsg,
+    },
+    types::{bech32_decode, eth_addr, pad32},
+};
+use ibcx_test_utils::hex;
+use k256::{
+    ecdsa::SigningKey,
+    elliptic_curve::{rand_core::OsRng, sec1::ToEncodedPoint},
+    SecretKey,
+};
+
+use crate::contract::{execute, instantiate, query};
+
+type TestApp = App<BankKeeper, MockApiBech32>;
+
+const HRP: &str = "osmo";
+const LOCAL_DOMAIN: u32 = 26657;
+const DOMAIN: u32 = 1;
+
+fn multisig() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn mailbox() -> Box<dyn Contract<Empty>> {
+    use hpl_mailbox::contract::{execute, instantiate, query};
+
+    Box::new(ContractWrapper::new(
+        execute::<Empty>,
+        instantiate::<Empty>,
+        query::<Empty>,
+    ))
+}
+
+fn va() -> Box<dyn Contract<Empty>> {
+    use hpl_validator_announce::contract::{execute, instantiate, query};
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// Validator announce answering every query with no storage locations at all
+fn va_without_locations() -> Box<dyn Contract<Empty>> {
+    fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn query(_: Deps, _: Env, _: va::QueryMsg) -> StdResult<Binary> {
+        to_json_binary(&va::GetAnnounceStorageLocationsResponse {
+            storage_locations: vec![],
+        })
+    }
+
+    Box::new(ContractWrapper::new(instantiate, instantiate, query))
+}
+
+struct Validator {
+    key: SigningKey,
+    addr: HexBinary,
+}
+
+impl Validator {
+    fn rand() -> Self {
+        let secret_key = SecretKey::random(&mut OsRng);
+        let pubkey_bin = secret_key
+            .public_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec();
+
+        Self {
+            key: SigningKey::from(secret_key),
+            addr: eth_addr(pubkey_bin.into()).unwrap(),
+        }
+    }
+
+    fn sign_announcement(&self, mailbox: [u8; 32], location: &str) -> HexBinary {
+        let digest = eth_signed_message_hash(announcement_digest(LOCAL_DOMAIN, mailbox, location));
+        let (rs, v) = self.key.sign_prehash_recoverable(&digest).unwrap();
+
+        let mut bz = rs.to_bytes().to_vec();
+        bz.push(v.to_byte() + 27u8);
+        bz.into()
+    }
+}
+
+struct Suite {
+    app: TestApp,
+    owner: Addr,
+    mailbox: Addr,
+    va: Addr,
+    ism: Addr,
+}
+
+impl Suite {
+    fn announce(&mut self, validator: &Validator, location: &str) -> anyhow::Result<()> {
+        let mailbox = pad32(&bech32_decode(self.mailbox.as_str())?)?;
+
+        self.app.execute_contract(
+            self.owner.clone(),
+            self.va.clone(),
+            &va::ExecuteMsg::Announce {
+                validator: validator.addr.clone(),
+                signature: validator.sign_announcement(mailbox, location),
+                storage_location: location.to_string(),
+                format: None,
+            },
+            &[],
+        )?;
+
+        Ok(())
+    }
+
+    fn import(&mut self, sender: &Addr, validators: Vec<HexBinary>) -> anyhow::Result<AppResponse> {
+        self.app.execute_contract(
+            sender.clone(),
+            self.ism.clone(),
+            &ExecuteMsg::ImportValidators {
+                domain: DOMAIN,
+                validator_announce: self.va.to_string(),
+                validators: validators.into_iter().map(Into::into).collect(),
+            },
+            &[],
+        )
+    }
+
+    fn enrolled(&self) -> anyhow::Result<EnrolledValidatorsResponse> {
+        Ok(self.app.wrap().query_wasm_smart(
+            &self.ism,
+            &QueryMsg::MultisigIsm(MultisigIsmQueryMsg::EnrolledValidators { domain: DOMAIN }),
+        )?)
+    }
+}
+
+fn setup() -> anyhow::Result<Suite> {
+    let mut app = AppBuilder::default()
+        .with_api(MockApiBech32::new(HRP))
+        .with_wasm(WasmKeeper::default().with_address_generator(MockAddressGenerator))
+        .build(|_, _, _| {});
+
+    let owner = app.api().addr_make("owner");
+
+    let mailbox_code_id = app.store_code(mailbox());
+    let mailbox = app.instantiate_contract(
+        mailbox_code_id,
+        owner.clone(),
+        &mailbox::InstantiateMsg {
+            hrp: HRP.to_string(),
+            owner: owner.to_string(),
+            domain: LOCAL_DOMAIN,
+            default_ism: None,
+            default_hook: None,
+        },
+        &[],
+        "mailbox",
+        None,
+    )?;
+
+    let va_code_id = app.store_code(va());
+    let va = app.instantiate_contract(
+        va_code_id,
+        owner.clone(),
+        &va::InstantiateMsg {
+            hrp: HRP.to_string(),
+            owner: owner.to_string(),
+            mailbox: mailbox.to_string(),
+        },
+        &[],
+        "va",
+        None,
+    )?;
+
+    let ism_code_id = app.store_code(multisig());
+    let ism = app.instantiate_contract(
+        ism_code_id,
+        owner.clone(),
+        &InstantiateMsg {
+            owner: owner.to_string(),
+            module_type: None,
+        },
+        &[],
+        "ism",
+        None,
+    )?;
+
+    app.execute_contract(
+        owner.clone(),
+        ism.clone(),
+        &ExecuteMsg::SetValidators {
+            domain: DOMAIN,
+            threshold: 1,
+            validators: vec![hex(&"deadbeef".repeat(5)).into()],
+        },
+        &[],
+    )?;
+
+    Ok(Suite {
+        app,
+        owner,
+        mailbox,
+        va,
+        ism,
+    })
+}
+
+#[test]
+fn test_import_validators() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+    let owner = suite.owner.clone();
+
+    let announced = Validator::rand();
+    let unannounced = Validator::rand();
+    suite.announce(&announced, "file:///tmp/signatures")?;
+
+    // one unannounced validator rejects the whole import
+    let err = suite
+        .import(
+            &owner,
+            vec![announced.addr.clone(), unannounced.addr.clone()],
+        )
+        .unwrap_err();
+    assert_eq!(
+        err.root_cause().to_string(),
+        format!(
+            "[E415] validator not announced: {}",
+            unannounced.addr.to_hex()
+        )
+    );
+    assert_eq!(suite.enrolled()?.validators.len(), 1);
+
+    let someone = suite.app.api().addr_make("someone");
+    let err = suite
+        .import(&someone, vec![announced.addr.clone()])
+        .unwrap_err();
+    assert!(err.root_cause().to_string().contains("unauthorized"));
+
+    suite.import(&owner, vec![announced.addr.clone()])?;
+
+    let res = suite.enrolled()?;
+    assert_eq!(
+        res.validators,
+        vec![hex(&"deadbeef".repeat(5)), announced.addr.clone()]
+    );
+    assert_eq!(res.threshold, 1);
+
+    // importing again leaves the set as is
+    suite.import(&owner, vec![announced.addr.clone()])?;
+    assert_eq!(suite.enrolled()?.validators.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_import_validators_unconfigured_domain() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+    let owner = suite.owner.clone();
+
+    suite.app.execute_contract(
+        owner.clone(),
+        suite.ism.clone(),
+        &ExecuteMsg::UnsetDomain { domain: DOMAIN },
+        &[],
+    )?;
+
+    let validator = Validator::rand();
+    suite.announce(&validator, "file:///tmp/signatures")?;
+
+    let err = suite.import(&owner, vec![validator.addr]).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("domain 1 not configured"));
+
+    Ok(())
+}
+
+#[test]
+fn test_import_validators_missing_locations() -> anyhow::Result<()> {
+    let mut suite = setup()?;
+    let owner = suite.owner.clone();
+
+    let va_code_id = suite.app.store_code(va_without_locations());
+    suite.va =
+        suite
+            .app
+            .instantiate_contract(va_code_id, owner.clone(), &Empty {}, &[], "va", None)?;
+
+    // a response shorter than the request can't vouch for every validator
+    let err = suite
+        .import(&owner, vec![Validator::rand().addr])
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("validator announce returned 0 storage locations for 1 validators"));
+    assert_eq!(suite.enrolled()?.validators.len(), 1);
+
+    Ok(())
+}
@@ -1,73 +1,257 @@
-use cosmwasm_std::{Deps, HexBinary};
+use cosmwasm_std::{ensure, Api, Deps, HexBinary, StdResult, Storage};
 use hpl_interface::{
-    ism::{IsmType, ModuleTypeResponse, VerifyInfoResponse, VerifyResponse},
-    types::{eth_addr, eth_hash, Message, MessageIdMultisigIsmMetadata},
+    digest::{checkpoint_digest, domain_hash, eth_signed_message_hash, legacy_checkpoint_digest},
+    ism::{
+        multisig::{EnrolledValidatorsResponse, EpochBaseResponse, WeightedValidatorsResponse},
+        IsmType, MetadataLayout, MetadataLayoutResponse, ModuleTypeResponse, VerifyInfoResponse,
+        VerifyResponse,
+    },
+    types::{
+        eth_addr, pad32, LegacyMultisigIsmMetadata, MerkleTree, Message,
+        MessageIdMultisigIsmMetadata,
+    },
 };
 
 use crate::{
-    domain_hash, multisig_hash,
-    state::{THRESHOLD, VALIDATORS},
+    state::{
+        EPOCH_BASE, MODULE_TYPE, THRESHOLD, THRESHOLD_WEIGHT, VALIDATORS, WEIGHTED_VALIDATORS,
+    },
     ContractError,
 };
 
-pub fn get_module_type() -> Result<ModuleTypeResponse, ContractError> {
-    Ok(ModuleTypeResponse {
-        typ: IsmType::MessageIdMultisig,
+/// Half the secp256k1 group order, the highest `s` of a non-malleable signature
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+pub fn get_module_type(deps: Deps) -> Result<ModuleTypeResponse, ContractError> {
+    // instances deployed before the module type was configurable are message id multisigs
+    let typ = MODULE_TYPE
+        .may_load(deps.storage)?
+        .unwrap_or(IsmType::MessageIdMultisig);
+
+    Ok(ModuleTypeResponse { typ })
+}
+
+/// Validators of the domain with their weights, and the weight their signatures must reach.
+/// Validators set without weights weigh 1 each against the threshold
+fn load_weighted_set(
+    storage: &dyn Storage,
+    domain: u32,
+) -> Result<(Vec<(HexBinary, u32)>, u64), ContractError> {
+    if let Some(validators) = WEIGHTED_VALIDATORS.may_load(storage, domain)? {
+        return Ok((validators, THRESHOLD_WEIGHT.load(storage, domain)?));
+    }
+
+    let validators = VALIDATORS
+        .load(storage, domain)?
+        .into_iter()
+        .map(|v| (v, 1))
+        .collect();
+
+    Ok((validators, THRESHOLD.load(storage, domain)? as u64))
+}
+
+/// Validators of the domain, and the fewest signatures that can reach its threshold
+fn load_validator_set(
+    storage: &dyn Storage,
+    domain: u32,
+) -> Result<(Vec<HexBinary>, u8), ContractError> {
+    let (validators, threshold_weight) = load_weighted_set(storage, domain)?;
+
+    let mut weights: Vec<u64> = validators.iter().map(|(_, w)| *w as u64).collect();
+    weights.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut threshold = 0u8;
+    let mut sum = 0u64;
+    for weight in weights {
+        if sum >= threshold_weight {
+            break;
+        }
+        sum += weight;
+        threshold = threshold.saturating_add(1);
+    }
+
+    Ok((validators.into_iter().map(|(v, _)| v).collect(), threshold))
+}
+
+pub fn get_enrolled_validators(
+    deps: Deps,
+    domain: u32,
+) -> Result<EnrolledValidatorsResponse, ContractError> {
+    let (validators, threshold) = load_validator_set(deps.storage, domain)?;
+
+    Ok(EnrolledValidatorsResponse {
+        validators,
+        threshold,
+    })
+}
+
+pub fn get_weighted_validators(
+    deps: Deps,
+    domain: u32,
+) -> Result<WeightedValidatorsResponse, ContractError> {
+    let (validators, threshold_weight) = load_weighted_set(deps.storage, domain)?;
+
+    Ok(WeightedValidatorsResponse {
+        validators,
+        threshold_weight,
+    })
+}
+
+pub fn get_epoch_base(deps: Deps, domain: u32) -> Result<EpochBaseResponse, ContractError> {
+    Ok(EpochBaseResponse {
+        base: EPOCH_BASE
+            .may_load(deps.storage, domain)?
+            .unwrap_or_default(),
     })
 }
 
+/// Address of the signer of a 65 byte `r || s || v` signature.
+/// `(r, n - s)` with the flipped `v` recovers the same signer, so high-s ones are rejected
+fn recover_signer(
+    api: &dyn Api,
+    hashed_message: &[u8],
+    signature: &[u8],
+) -> Result<HexBinary, ContractError> {
+    ensure!(
+        signature[32..64] <= SECP256K1_HALF_ORDER[..],
+        ContractError::HighS
+    );
+
+    let pubkey =
+        api.secp256k1_recover_pubkey(hashed_message, &signature[..64], signature[64] - 27)?;
+
+    Ok(eth_addr(pubkey.into())?)
+}
+
 pub fn verify_message(
     deps: Deps,
     raw_metadata: HexBinary,
     raw_message: HexBinary,
+) -> Result<VerifyResponse, ContractError> {
+    match get_module_type(deps)?.typ {
+        IsmType::LegacyMultisig => verify_legacy_message(deps, raw_metadata, raw_message),
+        _ => verify_message_id(deps, raw_metadata, raw_message),
+    }
+}
+
+fn verify_message_id(
+    deps: Deps,
+    raw_metadata: HexBinary,
+    raw_message: HexBinary,
 ) -> Result<VerifyResponse, ContractError> {
     let metadata: MessageIdMultisigIsmMetadata = raw_metadata.into();
-    let message: Message = raw_message.into();
+    let message = Message::try_from(raw_message)?;
 
     let merkle_index = metadata.merkle_index();
 
-    let multisig_hash = multisig_hash(
-        domain_hash(message.origin_domain, metadata.origin_merkle_tree)?.to_vec(),
-        metadata.merkle_root.to_vec(),
+    let digest = checkpoint_digest(
+        domain_hash(
+            message.origin_domain,
+            metadata.origin_merkle_tree.to_array()?,
+        ),
+        metadata.merkle_root.to_array()?,
         merkle_index,
-        message.id().to_vec(),
-    )?;
+        message.id().to_array()?,
+    );
 
-    let hashed_message = eth_hash(multisig_hash)?;
+    let hashed_message = eth_signed_message_hash(digest);
 
     // pizza :)
-    let validators = VALIDATORS.load(deps.storage, message.origin_domain)?;
-    let mut threshold = THRESHOLD.load(deps.storage, message.origin_domain)?;
+    let (validators, mut remaining) = load_weighted_set(deps.storage, message.origin_domain)?;
 
-    for signature in metadata.signatures {
-        let signature = signature.as_slice();
-        let pubkey = deps.api.secp256k1_recover_pubkey(
-            &hashed_message,
-            &signature[..64],
-            signature[64] - 27,
-        )?;
-
-        if validators.contains(&eth_addr(pubkey.into())?) {
-            threshold -= 1;
-            if threshold == 0 {
-                break;
+    // each validator counts once, however many of its signatures are included
+    let mut signed = vec![false; validators.len()];
+
+    for (i, signature) in metadata.signatures.iter().enumerate() {
+        let signer = recover_signer(deps.api, &hashed_message, signature)?;
+
+        // an index hint is checked against that validator only, instead of the whole set
+        let position = match &metadata.validator_indices {
+            Some(indices) => Some(indices[i] as usize)
+                .filter(|&v| validators.get(v).map(|(v, _)| v) == Some(&signer)),
+            None => validators.iter().position(|(v, _)| v == &signer),
+        };
+
+        if let Some(position) = position {
+            if !signed[position] {
+                signed[position] = true;
+                remaining = remaining.saturating_sub(validators[position].1 as u64);
+                if remaining == 0 {
+                    break;
+                }
             }
         }
     }
 
     Ok(VerifyResponse {
-        verified: threshold == 0,
+        verified: remaining == 0,
     })
 }
 
+fn verify_legacy_message(
+    deps: Deps,
+    raw_metadata: HexBinary,
+    raw_message: HexBinary,
+) -> Result<VerifyResponse, ContractError> {
+    let metadata = LegacyMultisigIsmMetadata::try_from_hex(raw_metadata)?;
+    let message = Message::try_from(raw_message)?;
+
+    let validators = VALIDATORS.load(deps.storage, message.origin_domain)?;
+    let threshold = THRESHOLD.load(deps.storage, message.origin_domain)?;
+
+    // stands in for the validator set commitment of the legacy ism
+    let padded = validators
+        .iter()
+        .map(|v| Ok(pad32(v)?.to_vec().into()))
+        .collect::<StdResult<Vec<HexBinary>>>()?;
+    ensure!(
+        metadata.threshold == threshold && metadata.validators == padded,
+        ContractError::ValidatorSetMismatch
+    );
+
+    // messages dispatched before the last reset of the origin tree are not in it
+    let base = get_epoch_base(deps, message.origin_domain)?.base;
+    let Some(index) = message.nonce.checked_sub(base) else {
+        return Ok(VerifyResponse { verified: false });
+    };
+
+    let root = MerkleTree::branch_root(message.id(), metadata.proof, index as u128);
+    if root != metadata.merkle_root {
+        return Ok(VerifyResponse { verified: false });
+    }
+
+    let digest = legacy_checkpoint_digest(
+        domain_hash(message.origin_domain, metadata.origin_mailbox.to_array()?),
+        metadata.merkle_root.to_array()?,
+        metadata.merkle_index,
+    );
+
+    let hashed_message = eth_signed_message_hash(digest);
+
+    // signatures are expected in the same order as the validator set
+    let mut candidates = validators.iter();
+
+    for signature in metadata.signatures {
+        let signer = recover_signer(deps.api, &hashed_message, &signature)?;
+
+        if !candidates.any(|v| v == &signer) {
+            return Ok(VerifyResponse { verified: false });
+        }
+    }
+
+    Ok(VerifyResponse { verified: true })
+}
+
 pub fn get_verify_info(
     deps: Deps,
     raw_message: HexBinary,
 ) -> Result<VerifyInfoResponse, ContractError> {
-    let message: Message = raw_message.into();
+    let message = Message::try_from(raw_message)?;
 
-    let threshold = THRESHOLD.load(deps.storage, message.origin_domain)?;
-    let validators = VALIDATORS.load(deps.storage, message.origin_domain)?;
+    let (validators, threshold) = load_validator_set(deps.storage, message.origin_domain)?;
 
     Ok(VerifyInfoResponse {
         threshold,
@@ -75,32 +259,235 @@ pub fn get_verify_info(
     })
 }
 
+/// Only message id metadata has a fixed layout, legacy metadata carries a merkle proof
+pub fn get_metadata_layout(
+    deps: Deps,
+    origin_domain: u32,
+) -> Result<MetadataLayoutResponse, ContractError> {
+    let typ = get_module_type(deps)?.typ;
+    ensure!(
+        typ == IsmType::MessageIdMultisig,
+        ContractError::UnsupportedMetadataLayout { typ }
+    );
+
+    let (validators, threshold) = load_validator_set(deps.storage, origin_domain)?;
+
+    Ok(MetadataLayoutResponse {
+        layout: MetadataLayout::MessageIdMultisig {
+            threshold,
+            validators_len: validators.len() as u32,
+            expected_len: MessageIdMultisigIsmMetadata::encoded_len(threshold as usize) as u32,
+        },
+    })
+}
+
 #[cfg(test)]
 mod test {
-    use crate::state::{THRESHOLD, VALIDATORS};
-    use cosmwasm_std::{testing::mock_dependencies, HexBinary};
+    use crate::state::{
+        EPOCH_BASE, MODULE_TYPE, THRESHOLD, THRESHOLD_WEIGHT, VALIDATORS, WEIGHTED_VALIDATORS,
+    };
+    use cosmwasm_std::{
+        from_json,
+        testing::{mock_dependencies, mock_env},
+        HexBinary, Storage,
+    };
     use hpl_interface::{
-        ism::{IsmType, ModuleTypeResponse, VerifyResponse},
-        types::{eth_addr, Message},
+        digest::{
+            checkpoint_digest, domain_hash, eth_signed_message_hash, legacy_checkpoint_digest,
+        },
+        ism::{
+            multisig::QueryMsg, IsmMetadataQueryMsg, IsmType, MetadataLayout,
+            MetadataLayoutResponse, ModuleTypeResponse, VerifyResponse,
+        },
+        types::{
+            eth_addr, pad32, LegacyMultisigIsmMetadata, MerkleTree, Message,
+            MessageIdMultisigIsmMetadata,
+        },
     };
     use ibcx_test_utils::hex;
-    use k256::{ecdsa::SigningKey, elliptic_curve::rand_core::OsRng};
+    use k256::{
+        ecdsa::{Signature, SigningKey},
+        elliptic_curve::rand_core::OsRng,
+    };
     use rstest::rstest;
 
-    use super::{get_module_type, get_verify_info, verify_message};
+    use super::{get_metadata_layout, get_module_type, get_verify_info, verify_message};
+    use crate::contract::query;
+
+    #[rstest]
+    #[case(None, IsmType::MessageIdMultisig)]
+    #[case(Some(IsmType::MessageIdMultisig), IsmType::MessageIdMultisig)]
+    #[case(Some(IsmType::LegacyMultisig), IsmType::LegacyMultisig)]
+    fn test_get_module_type(#[case] stored: Option<IsmType>, #[case] expected: IsmType) {
+        let mut deps = mock_dependencies();
+
+        if let Some(typ) = stored {
+            MODULE_TYPE.save(deps.as_mut().storage, &typ).unwrap();
+        }
+
+        let result = get_module_type(deps.as_ref()).unwrap();
+
+        assert_eq!(result, ModuleTypeResponse { typ: expected });
+    }
+
+    const LEGACY_MESSAGE: &str = "0000000000000068220000000000000000000000000d1255b09d94659bb0888e0aa9fca60245ce402a0000682155208cd518cffaac1b5d8df216a9bd050c9a03f0d4f3ba88e5268ac4cd12ee2d68656c6c6f";
+    const LEGACY_MAILBOX: &str = "000000000000000000000000c005dc82818d67af737725bd4bf75435d065d239";
+
+    fn legacy_signer(seed: u8) -> (SigningKey, HexBinary) {
+        let signing_key = SigningKey::from_slice(&[seed; 32]).unwrap();
+        let addr = eth_addr(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes()
+                .into(),
+        )
+        .unwrap();
+
+        (signing_key, addr)
+    }
+
+    /// legacy metadata for `LEGACY_MESSAGE` as the only leaf of the origin tree,
+    /// signed by the given validators of a 2/3 set
+    fn legacy_fixture(storage: &mut dyn Storage, signers: &[usize]) -> LegacyMultisigIsmMetadata {
+        legacy_fixture_for(storage, signers, &hex(LEGACY_MESSAGE).try_into().unwrap())
+    }
+
+    /// `message` is the first leaf of a fresh tree
+    fn legacy_fixture_for(
+        storage: &mut dyn Storage,
+        signers: &[usize],
+        message: &Message,
+    ) -> LegacyMultisigIsmMetadata {
+        let keys: Vec<_> = (1..=3).map(legacy_signer).collect();
+        let validators: Vec<_> = keys.iter().map(|(_, addr)| addr.clone()).collect();
+
+        VALIDATORS
+            .save(storage, message.origin_domain, &validators)
+            .unwrap();
+        THRESHOLD
+            .save(storage, message.origin_domain, &2u8)
+            .unwrap();
+        MODULE_TYPE.save(storage, &IsmType::LegacyMultisig).unwrap();
+
+        let mut tree = MerkleTree::default();
+        tree.insert(message.id()).unwrap();
+
+        let merkle_root = tree.root().unwrap();
+        let merkle_index = 0;
+        let origin_mailbox = hex(LEGACY_MAILBOX);
+
+        let digest = legacy_checkpoint_digest(
+            domain_hash(message.origin_domain, origin_mailbox.to_array().unwrap()),
+            merkle_root.to_array().unwrap(),
+            merkle_index,
+        );
+        let hashed = eth_signed_message_hash(digest);
+
+        let signatures = signers
+            .iter()
+            .map(|&i| {
+                let (rs, v) = keys[i].0.sign_prehash_recoverable(&hashed).unwrap();
+
+                let mut bz = rs.to_bytes().to_vec();
+                bz.push(v.to_byte() + 27u8);
+                bz.into()
+            })
+            .collect();
+
+        LegacyMultisigIsmMetadata {
+            merkle_root,
+            merkle_index,
+            origin_mailbox,
+            proof: MerkleTree::zero_hashes(32)
+                .unwrap()
+                .into_iter()
+                .map(HexBinary::from)
+                .collect(),
+            threshold: 2,
+            signatures,
+            validators: validators
+                .iter()
+                .map(|v| pad32(v).unwrap().to_vec().into())
+                .collect(),
+        }
+    }
+
+    #[rstest]
+    #[case(&[0, 1], true)]
+    #[case(&[0, 2], true)]
+    #[case(&[1, 2], true)]
+    #[case(&[1, 0], false)]
+    #[case(&[1, 1], false)]
+    fn test_verify_legacy(#[case] signers: &[usize], #[case] verified: bool) {
+        let mut deps = mock_dependencies();
+
+        let metadata = legacy_fixture(deps.as_mut().storage, signers);
+
+        let res = verify_message(deps.as_ref(), metadata.into(), hex(LEGACY_MESSAGE)).unwrap();
+        assert_eq!(res, VerifyResponse { verified });
+    }
+
+    #[rstest]
+    #[case(None, false)]
+    #[case(Some(3), true)]
+    #[case(Some(4), false)]
+    fn test_verify_legacy_epoch_base(#[case] base: Option<u32>, #[case] verified: bool) {
+        let mut deps = mock_dependencies();
+
+        // first message in the origin tree after a reset at nonce 3
+        let mut message: Message = hex(LEGACY_MESSAGE).try_into().unwrap();
+        message.nonce = 3;
+
+        let metadata = legacy_fixture_for(deps.as_mut().storage, &[0, 1], &message);
+        if let Some(base) = base {
+            EPOCH_BASE
+                .save(deps.as_mut().storage, message.origin_domain, &base)
+                .unwrap();
+        }
+
+        let res = verify_message(deps.as_ref(), metadata.into(), message.into()).unwrap();
+        assert_eq!(res, VerifyResponse { verified });
+    }
 
     #[test]
-    fn test_get_module_type() {
-        let result = get_module_type().unwrap();
+    fn test_verify_legacy_invalid_proof() {
+        let mut deps = mock_dependencies();
+
+        let mut metadata = legacy_fixture(deps.as_mut().storage, &[0, 1]);
+        metadata.proof[3] = hex(&"ff".repeat(32));
+
+        let res = verify_message(deps.as_ref(), metadata.into(), hex(LEGACY_MESSAGE)).unwrap();
+        assert_eq!(res, VerifyResponse { verified: false });
+    }
+
+    #[test]
+    fn test_verify_legacy_validator_set_mismatch() {
+        let mut deps = mock_dependencies();
+
+        let mut metadata = legacy_fixture(deps.as_mut().storage, &[0, 1]);
+        metadata.validators.pop();
 
+        let err = verify_message(deps.as_ref(), metadata.into(), hex(LEGACY_MESSAGE)).unwrap_err();
+        assert_eq!(err.code(), 413);
         assert_eq!(
-            result,
-            ModuleTypeResponse {
-                typ: IsmType::MessageIdMultisig
-            }
+            err.to_string(),
+            "[E413] validator set in metadata does not match enrolled set"
         );
     }
 
+    #[test]
+    fn test_verify_legacy_rejects_message_id_metadata() {
+        let mut deps = mock_dependencies();
+
+        legacy_fixture(deps.as_mut().storage, &[0, 1]);
+
+        // message id metadata is too short to be parsed as the legacy layout
+        let raw_metadata = hex("986a1625d44e4b3969b08a5876171b2b4fcdf61b3e5c70a86ad17b304f17740a9f45d99ea6bec61392a47684f4e5d1416ddbcb5fdef0f132c27d7034e9bbff1c00000000ba9911d78ec6d561413e3589f920388cbd7554fbddd8ce50739337250853ec3577a51fa40e727c05b50f15db13f5aad5857c89d432644be48d70325ea83fdb6c1c");
+
+        assert!(verify_message(deps.as_ref(), raw_metadata, hex(LEGACY_MESSAGE)).is_err());
+    }
+
     #[rstest]
     #[case(
         hex("0000000000000068220000000000000000000000000d1255b09d94659bb0888e0aa9fca60245ce402a0000682155208cd518cffaac1b5d8df216a9bd050c9a03f0d4f3ba88e5268ac4cd12ee2d68656c6c6f"),
@@ -126,7 +513,7 @@ mod test {
     ) {
         let mut deps = mock_dependencies();
 
-        let message: Message = raw_message.clone().into();
+        let message: Message = raw_message.clone().try_into().unwrap();
 
         VALIDATORS
             .save(deps.as_mut().storage, message.origin_domain, &validators)
@@ -139,6 +526,210 @@ mod test {
         assert_eq!(res, VerifyResponse { verified: true });
     }
 
+    /// message id metadata for `LEGACY_MESSAGE` signed by the given validators of a 2/3 set,
+    /// with index hints if `indexed`
+    fn message_id_fixture(
+        storage: &mut dyn Storage,
+        signers: &[usize],
+        indexed: bool,
+    ) -> MessageIdMultisigIsmMetadata {
+        let message: Message = hex(LEGACY_MESSAGE).try_into().unwrap();
+        let keys: Vec<_> = (1..=3).map(legacy_signer).collect();
+        let validators: Vec<_> = keys.iter().map(|(_, addr)| addr.clone()).collect();
+
+        VALIDATORS
+            .save(storage, message.origin_domain, &validators)
+            .unwrap();
+        THRESHOLD
+            .save(storage, message.origin_domain, &2u8)
+            .unwrap();
+
+        let origin_merkle_tree = hex(LEGACY_MAILBOX);
+        let merkle_root = hex(&"ab".repeat(32));
+
+        let digest = checkpoint_digest(
+            domain_hash(
+                message.origin_domain,
+                origin_merkle_tree.to_array().unwrap(),
+            ),
+            merkle_root.to_array().unwrap(),
+            7,
+            message.id().to_array().unwrap(),
+        );
+        let hashed = eth_signed_message_hash(digest);
+
+        let signatures = signers
+            .iter()
+            .map(|&i| {
+                let (rs, v) = keys[i].0.sign_prehash_recoverable(&hashed).unwrap();
+
+                let mut bz = rs.to_bytes().to_vec();
+                bz.push(v.to_byte() + 27u8);
+                bz.into()
+            })
+            .collect();
+
+        MessageIdMultisigIsmMetadata {
+            origin_merkle_tree,
+            merkle_root,
+            merkle_index: 7u32.to_be_bytes().to_vec().into(),
+            signatures,
+            validator_indices: indexed.then(|| signers.iter().map(|&i| i as u8).collect()),
+        }
+    }
+
+    #[rstest]
+    #[case(&[0, 1], true)]
+    #[case(&[2, 0], true)]
+    #[case(&[1, 2, 0], true)]
+    #[case(&[1], false)]
+    #[case(&[1, 1], false)]
+    fn test_verify_indexed(#[case] signers: &[usize], #[case] verified: bool) {
+        let mut deps = mock_dependencies();
+
+        for indexed in [false, true] {
+            let metadata = message_id_fixture(deps.as_mut().storage, signers, indexed);
+
+            let res = verify_message(deps.as_ref(), metadata.into(), hex(LEGACY_MESSAGE)).unwrap();
+            assert_eq!(res, VerifyResponse { verified }, "indexed: {indexed}");
+        }
+    }
+
+    /// `message_id_fixture` with its validators weighted by `weights`
+    fn weighted_fixture(
+        storage: &mut dyn Storage,
+        weights: &[u32],
+        threshold_weight: u64,
+        signers: &[usize],
+    ) -> MessageIdMultisigIsmMetadata {
+        let metadata = message_id_fixture(storage, signers, false);
+
+        let validators = VALIDATORS.load(storage, 26658).unwrap();
+        VALIDATORS.remove(storage, 26658);
+        THRESHOLD.remove(storage, 26658);
+
+        WEIGHTED_VALIDATORS
+            .save(
+                storage,
+                26658,
+                &validators.into_iter().zip(weights.to_vec()).collect(),
+            )
+            .unwrap();
+        THRESHOLD_WEIGHT
+            .save(storage, 26658, &threshold_weight)
+            .unwrap();
+
+        metadata
+    }
+
+    #[rstest]
+    // one heavy validator outweighs the others together
+    #[case(&[5, 1, 1], 5, &[0], true)]
+    #[case(&[5, 1, 1], 5, &[1, 2], false)]
+    #[case(&[5, 1, 1], 5, &[1, 2, 0], true)]
+    #[case(&[3, 1, 1], 4, &[0, 2], true)]
+    #[case(&[3, 1, 1], 4, &[0], false)]
+    // a repeated signature counts once
+    #[case(&[3, 1, 1], 4, &[0, 0], false)]
+    fn test_verify_weighted(
+        #[case] weights: &[u32],
+        #[case] threshold_weight: u64,
+        #[case] signers: &[usize],
+        #[case] verified: bool,
+    ) {
+        let mut deps = mock_dependencies();
+
+        let metadata = weighted_fixture(deps.as_mut().storage, weights, threshold_weight, signers);
+
+        let res = verify_message(deps.as_ref(), metadata.into(), hex(LEGACY_MESSAGE)).unwrap();
+        assert_eq!(res, VerifyResponse { verified });
+    }
+
+    #[rstest]
+    #[case(&[5, 1, 1], 5, 1)]
+    #[case(&[3, 1, 1], 4, 2)]
+    #[case(&[1, 1, 1], 3, 3)]
+    fn test_get_verify_info_weighted(
+        #[case] weights: &[u32],
+        #[case] threshold_weight: u64,
+        #[case] threshold: u8,
+    ) {
+        let mut deps = mock_dependencies();
+
+        weighted_fixture(deps.as_mut().storage, weights, threshold_weight, &[]);
+
+        // relayers gather the fewest signatures that can verify
+        let info = get_verify_info(deps.as_ref(), hex(LEGACY_MESSAGE)).unwrap();
+        assert_eq!(info.threshold, threshold);
+        assert_eq!(info.validators.len(), 3);
+    }
+
+    /// `signature` with `s` replaced by `n - s`, which recovers the same signer
+    fn malleate(signature: &HexBinary) -> HexBinary {
+        let sig = Signature::from_slice(&signature[..64]).unwrap();
+        let high = Signature::from_scalars(sig.r(), -*sig.s().as_ref()).unwrap();
+
+        let mut bz = high.to_bytes().to_vec();
+        // the recovery id flips along with s
+        bz.push(if signature[64] == 27 { 28 } else { 27 });
+        bz.into()
+    }
+
+    #[rstest]
+    #[case::low_s(false, Some(true))]
+    #[case::high_s(true, None)]
+    fn test_verify_high_s(
+        #[values(IsmType::MessageIdMultisig, IsmType::LegacyMultisig)] typ: IsmType,
+        #[case] high_s: bool,
+        #[case] verified: Option<bool>,
+    ) {
+        let mut deps = mock_dependencies();
+        let storage = deps.as_mut().storage;
+
+        let raw_metadata: HexBinary = match typ {
+            IsmType::LegacyMultisig => {
+                let mut metadata = legacy_fixture(storage, &[0, 1]);
+                if high_s {
+                    metadata.signatures[1] = malleate(&metadata.signatures[1]);
+                }
+                metadata.into()
+            }
+            _ => {
+                let mut metadata = message_id_fixture(storage, &[0, 1], false);
+                if high_s {
+                    metadata.signatures[1] = malleate(&metadata.signatures[1]);
+                }
+                metadata.into()
+            }
+        };
+
+        let res = verify_message(deps.as_ref(), raw_metadata, hex(LEGACY_MESSAGE));
+        match verified {
+            Some(verified) => assert_eq!(res.unwrap(), VerifyResponse { verified }),
+            None => {
+                let err = res.unwrap_err();
+                assert_eq!(err.code(), 416);
+                assert_eq!(
+                    err.to_string(),
+                    "[E416] malleable signature: s is above the secp256k1 half order"
+                );
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::wrong_validator(vec![1, 0])]
+    #[case::out_of_range(vec![0, 9])]
+    fn test_verify_indexed_bad_hint(#[case] indices: Vec<u8>) {
+        let mut deps = mock_dependencies();
+
+        let mut metadata = message_id_fixture(deps.as_mut().storage, &[0, 2], true);
+        metadata.validator_indices = Some(indices);
+
+        let res = verify_message(deps.as_ref(), metadata.into(), hex(LEGACY_MESSAGE)).unwrap();
+        assert_eq!(res, VerifyResponse { verified: false });
+    }
+
     #[test]
     fn test_get_verify_info() {
         let raw_message = hex("0000000000000068220000000000000000000000000d1255b09d94659bb0888e0aa9fca60245ce402a0000682155208cd518cffaac1b5d8df216a9bd050c9a03f0d4f3ba88e5268ac4cd12ee2d68656c6c6f");
@@ -160,4 +751,75 @@ mod test {
         assert_eq!(info.validators, vec![addr]);
         assert_eq!(info.threshold, 1);
     }
+
+    #[test]
+    fn test_metadata_layout() {
+        let raw_message = hex(LEGACY_MESSAGE);
+        let raw_metadata = hex("986a1625d44e4b3969b08a5876171b2b4fcdf61b3e5c70a86ad17b304f17740a9f45d99ea6bec61392a47684f4e5d1416ddbcb5fdef0f132c27d7034e9bbff1c00000000ba9911d78ec6d561413e3589f920388cbd7554fbddd8ce50739337250853ec3577a51fa40e727c05b50f15db13f5aad5857c89d432644be48d70325ea83fdb6c1c");
+
+        let mut deps = mock_dependencies();
+
+        let message: Message = raw_message.clone().try_into().unwrap();
+        let validators = vec![
+            hex("122e0663ccc190266427e7fc0ed6589b5d7d36db"),
+            hex("01d7525e91dfc3f594fd366aad70f956b398de9e"),
+        ];
+
+        VALIDATORS
+            .save(deps.as_mut().storage, message.origin_domain, &validators)
+            .unwrap();
+        THRESHOLD
+            .save(deps.as_mut().storage, message.origin_domain, &1u8)
+            .unwrap();
+
+        let res: MetadataLayoutResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::IsmMetadata(IsmMetadataQueryMsg::MetadataLayoutFor {
+                    message: raw_message.clone(),
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            get_metadata_layout(deps.as_ref(), message.origin_domain).unwrap()
+        );
+
+        let MetadataLayout::MessageIdMultisig {
+            threshold,
+            validators_len,
+            expected_len,
+        } = res.layout
+        else {
+            panic!("unexpected layout");
+        };
+        assert_eq!((threshold, validators_len, expected_len), (1, 2, 133));
+
+        // metadata of the expected length verifies, one byte short loses the signature
+        assert_eq!(raw_metadata.len(), expected_len as usize);
+
+        let res = verify_message(deps.as_ref(), raw_metadata.clone(), raw_message.clone());
+        assert_eq!(res.unwrap(), VerifyResponse { verified: true });
+
+        let truncated = raw_metadata[..expected_len as usize - 1].to_vec().into();
+        let res = verify_message(deps.as_ref(), truncated, raw_message);
+        assert_eq!(res.unwrap(), VerifyResponse { verified: false });
+    }
+
+    #[test]
+    fn test_metadata_layout_legacy() {
+        let mut deps = mock_dependencies();
+
+        legacy_fixture(deps.as_mut().storage, &[0, 1]);
+
+        let err = get_metadata_layout(deps.as_ref(), 26658).unwrap_err();
+        assert_eq!(err.code(), 414);
+        assert_eq!(
+            err.to_string(),
+            "[E414] metadata layout is not supported for LegacyMultisig"
+        );
+    }
 }
@@ -1,8 +1,24 @@
 use cosmwasm_std::HexBinary;
-use cw_storage_plus::Map;
+use cw_storage_plus::{Item, Map};
+use hpl_interface::ism::IsmType;
 
 pub const VALIDATORS_PREFIX: &str = "validators";
 pub const VALIDATORS: Map<u32, Vec<HexBinary>> = Map::new(VALIDATORS_PREFIX);
 
 pub const THRESHOLD_PREFIX: &str = "threshold";
 pub const THRESHOLD: Map<u32, u8> = Map::new(THRESHOLD_PREFIX);
+
+/// Set in place of `VALIDATORS` and `THRESHOLD` for weighted domains
+pub const WEIGHTED_VALIDATORS_PREFIX: &str = "weighted_validators";
+pub const WEIGHTED_VALIDATORS: Map<u32, Vec<(HexBinary, u32)>> =
+    Map::new(WEIGHTED_VALIDATORS_PREFIX);
+
+pub const THRESHOLD_WEIGHT_PREFIX: &str = "threshold_weight";
+pub const THRESHOLD_WEIGHT: Map<u32, u64> = Map::new(THRESHOLD_WEIGHT_PREFIX);
+
+/// Unset for domains whose merkle hook was never reset
+pub const EPOCH_BASE_PREFIX: &str = "epoch_base";
+pub const EPOCH_BASE: Map<u32, u32> = Map::new(EPOCH_BASE_PREFIX);
+
+pub const MODULE_TYPE_KEY: &str = "module_type";
+pub const MODULE_TYPE: Item<IsmType> = Item::new(MODULE_TYPE_KEY);
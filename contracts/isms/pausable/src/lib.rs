@@ -1,33 +1,32 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, to_json_binary, Deps, DepsMut, Env, Event, MessageInfo, QueryResponse, Response,
-    StdError,
+    ensure, to_json_binary, Addr, Deps, DepsMut, Empty, Env, Event, HexBinary, MessageInfo,
+    QueryResponse, Response,
 };
+use cw_storage_plus::Item;
 use hpl_interface::ism::{
-    pausable::{ExecuteMsg, InstantiateMsg, QueryMsg},
-    IsmQueryMsg, IsmType, ModuleTypeResponse, VerifyResponse,
+    self,
+    pausable::{
+        ExecuteMsg, InstantiateMsg, PausableIsmQueryMsg, PausedBehavior, PausedResponse, QueryMsg,
+    },
+    InstantiateResponse, IsmQueryMsg, IsmType, ModuleTypeResponse, VerifyInfoResponse,
+    VerifyResponse,
 };
 
-#[derive(thiserror::Error, Debug, PartialEq)]
-pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
-
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
-
-    #[error("unauthorized")]
-    Unauthorized {},
-
-    #[error("hook paused")]
-    Paused {},
-}
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
 
 // version info for migration info
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+pub const ISM_KEY: &str = "ism";
+pub const ISM: Item<Addr> = Item::new(ISM_KEY);
+
+pub const PAUSED_BEHAVIOR_KEY: &str = "paused_behavior";
+pub const PAUSED_BEHAVIOR: Item<PausedBehavior> = Item::new(PAUSED_BEHAVIOR_KEY);
+
 fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_hook_pausable::{}", name))
 }
@@ -46,14 +45,26 @@ pub fn instantiate(
     hpl_ownable::initialize(deps.storage, &owner)?;
     hpl_pausable::initialize(deps.storage, &msg.paused)?;
 
-    Ok(Response::new().add_event(
-        new_event("initialize")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner),
-    ))
+    let mut event = new_event("initialize")
+        .add_attribute("sender", info.sender)
+        .add_attribute("owner", owner.clone());
+
+    if let Some(ism) = msg.ism {
+        let ism = deps.api.addr_validate(&ism)?;
+        ISM.save(deps.storage, &ism)?;
+        event = event.add_attribute("ism", ism);
+    }
+
+    let behavior = msg.paused_behavior.unwrap_or_default();
+    PAUSED_BEHAVIOR.save(deps.storage, &behavior)?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse { owner })?)
+        .add_event(event))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -61,41 +72,94 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         ExecuteMsg::Pausable(msg) => Ok(hpl_pausable::handle(deps, env, info, msg)?),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     use IsmQueryMsg::*;
 
     match msg {
         QueryMsg::Pausable(msg) => Ok(hpl_pausable::handle_query(deps, env, msg)?),
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
+        QueryMsg::PausableIsm(PausableIsmQueryMsg::Paused {}) => {
+            Ok(to_json_binary(&get_paused(deps)?)?)
+        }
         QueryMsg::Ism(msg) => match msg {
-            ModuleType {} => Ok(to_json_binary(&ModuleTypeResponse { typ: IsmType::Null })?),
-            Verify {
-                metadata: _,
-                message: _,
-            } => {
-                ensure!(
-                    !hpl_pausable::get_pause_info(deps.storage)?,
-                    ContractError::Paused {}
-                );
-                Ok(to_json_binary(&VerifyResponse { verified: true })?)
+            ModuleType {} => {
+                let typ = match ISM.may_load(deps.storage)? {
+                    Some(ism) => ism::module_type(&deps.querier, ism)?,
+                    None => IsmType::Null,
+                };
+                Ok(to_json_binary(&ModuleTypeResponse { typ })?)
             }
-            _ => unimplemented!(),
+            Verify { metadata, message } => Ok(to_json_binary(&verify(deps, metadata, message)?)?),
+            VerifyInfo { message } => match ISM.may_load(deps.storage)? {
+                Some(ism) => Ok(to_json_binary(
+                    &deps.querier.query_wasm_smart::<VerifyInfoResponse>(
+                        ism,
+                        &VerifyInfo { message }.wrap(),
+                    )?,
+                )?),
+                // a null ism has no validators
+                None => Ok(to_json_binary(&VerifyInfoResponse {
+                    threshold: 0,
+                    validators: vec![],
+                })?),
+            },
         },
     }
 }
 
+fn get_paused_behavior(deps: Deps) -> Result<PausedBehavior, ContractError> {
+    // instances from before the behavior was configurable always error
+    Ok(PAUSED_BEHAVIOR.may_load(deps.storage)?.unwrap_or_default())
+}
+
+fn get_paused(deps: Deps) -> Result<PausedResponse, ContractError> {
+    Ok(PausedResponse {
+        paused: hpl_pausable::get_pause_info(deps.storage)?,
+        behavior: get_paused_behavior(deps)?,
+        ism: ISM.may_load(deps.storage)?.map(String::from),
+    })
+}
+
+fn verify(
+    deps: Deps,
+    metadata: HexBinary,
+    message: HexBinary,
+) -> Result<VerifyResponse, ContractError> {
+    if hpl_pausable::get_pause_info(deps.storage)? {
+        ensure!(
+            get_paused_behavior(deps)? == PausedBehavior::Reject,
+            ContractError::Paused {}
+        );
+        return Ok(VerifyResponse { verified: false });
+    }
+
+    let verified = match ISM.may_load(deps.storage)? {
+        Some(ism) => ism::verify(&deps.querier, ism, metadata, message)?,
+        None => true,
+    };
+
+    Ok(VerifyResponse { verified })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
         from_json,
         testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-        to_json_binary, Addr, OwnedDeps,
+        to_json_binary, Addr, ContractResult, OwnedDeps, QuerierResult, SystemResult, WasmQuery,
     };
     use hpl_ownable::get_owner;
     use hpl_pausable::get_pause_info;
@@ -116,6 +180,8 @@ mod test {
         #[default(addr("deployer"))] sender: Addr,
         #[default(addr("owner"))] owner: Addr,
         #[default(false)] paused: bool,
+        #[default(None)] ism: Option<Addr>,
+        #[default(None)] paused_behavior: Option<PausedBehavior>,
     ) -> TestDeps {
         let mut deps = mock_dependencies();
 
@@ -126,6 +192,8 @@ mod test {
             InstantiateMsg {
                 owner: owner.to_string(),
                 paused,
+                ism: ism.map(String::from),
+                paused_behavior,
             },
         )
         .unwrap();
@@ -139,9 +207,61 @@ mod test {
         assert_eq!("owner", get_owner(deps.as_ref().storage).unwrap().as_str());
     }
 
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                paused: false,
+                ism: None,
+                paused_behavior: None,
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: addr("owner")
+            }
+        );
+    }
+
+    #[rstest]
+    fn test_module_type(deps: TestDeps) {
+        let res: ModuleTypeResponse =
+            from_json(query(deps.as_ref(), QueryMsg::Ism(IsmQueryMsg::ModuleType {})).unwrap())
+                .unwrap();
+
+        assert_eq!(res.typ, IsmType::Null);
+    }
+
+    #[rstest]
+    fn test_verify_info(deps: TestDeps) {
+        let res: VerifyInfoResponse = from_json(
+            query(
+                deps.as_ref(),
+                QueryMsg::Ism(IsmQueryMsg::VerifyInfo {
+                    message: HexBinary::default(),
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(res.threshold, 0);
+        assert!(res.validators.is_empty());
+    }
+
     #[rstest]
     #[case(false)]
-    #[should_panic(expected = "hook paused")]
+    #[should_panic(expected = "paused")]
     #[case(true)]
     fn test_query(mut deps: TestDeps, #[case] paused: bool) {
         if paused {
@@ -161,4 +281,98 @@ mod test {
         .map_err(|e| e.to_string())
         .unwrap();
     }
+
+    /// Inner ism verifying only non-empty metadata
+    fn mock_inner_ism(query: &WasmQuery) -> QuerierResult {
+        let WasmQuery::Smart { contract_addr, msg } = query else {
+            unreachable!("only smart query")
+        };
+        assert_eq!(contract_addr, "inner");
+
+        let res = match from_json(msg).unwrap() {
+            ism::ExpectedIsmQueryMsg::Ism(IsmQueryMsg::ModuleType {}) => {
+                to_json_binary(&ModuleTypeResponse {
+                    typ: IsmType::MessageIdMultisig,
+                })
+            }
+            ism::ExpectedIsmQueryMsg::Ism(IsmQueryMsg::Verify { metadata, .. }) => {
+                to_json_binary(&VerifyResponse {
+                    verified: !metadata.is_empty(),
+                })
+            }
+            _ => unreachable!("unexpected query"),
+        };
+
+        SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+    }
+
+    fn verified(deps: Deps, metadata: HexBinary) -> Result<bool, ContractError> {
+        let res: VerifyResponse = from_json(query(
+            deps,
+            QueryMsg::Ism(IsmQueryMsg::Verify {
+                metadata,
+                message: HexBinary::default(),
+            }),
+        )?)?;
+
+        Ok(res.verified)
+    }
+
+    #[rstest]
+    fn test_delegate(
+        #[with(addr("deployer"), addr("owner"), false, Some(addr("inner")))] mut deps: TestDeps,
+    ) {
+        deps.querier.update_wasm(mock_inner_ism);
+
+        let res: ModuleTypeResponse =
+            from_json(query(deps.as_ref(), QueryMsg::Ism(IsmQueryMsg::ModuleType {})).unwrap())
+                .unwrap();
+        assert_eq!(res.typ, IsmType::MessageIdMultisig);
+
+        assert!(verified(deps.as_ref(), hex("deadbeef")).unwrap());
+        assert!(!verified(deps.as_ref(), HexBinary::default()).unwrap());
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(PausedBehavior::Error))]
+    #[case(Some(PausedBehavior::Reject))]
+    fn test_paused_behavior(#[case] paused_behavior: Option<PausedBehavior>) {
+        let mut deps = deps(
+            addr("deployer"),
+            addr("owner"),
+            true,
+            Some(addr("inner")),
+            paused_behavior.clone(),
+        );
+        deps.querier.update_wasm(mock_inner_ism);
+
+        let res: PausedResponse = from_json(
+            query(
+                deps.as_ref(),
+                QueryMsg::PausableIsm(PausableIsmQueryMsg::Paused {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            PausedResponse {
+                paused: true,
+                behavior: paused_behavior.clone().unwrap_or_default(),
+                ism: Some("inner".to_string()),
+            }
+        );
+
+        // the inner ism would verify this
+        let res = verified(deps.as_ref(), hex("deadbeef"));
+        match paused_behavior {
+            Some(PausedBehavior::Reject) => assert!(!res.unwrap()),
+            _ => assert_eq!(res.unwrap_err(), ContractError::Paused {}),
+        }
+
+        // releasing delegates again
+        hpl_pausable::release(deps.as_mut().storage, &addr("owner")).unwrap();
+        assert!(verified(deps.as_ref(), hex("deadbeef")).unwrap());
+    }
 }
@@ -1,13 +1,16 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, to_json_binary, Deps, DepsMut, Env, MessageInfo, QueryResponse, Response,
+    ensure, ensure_eq, to_json_binary, Addr, Deps, DepsMut, Empty, Env, HexBinary, MessageInfo,
+    QueryResponse, Response, Storage,
 };
 use cw2::set_contract_version;
+use hpl_error::HplError;
 use hpl_interface::{
     ism::{
         routing::{ExecuteMsg, InstantiateMsg, QueryMsg, RouteResponse, RoutingIsmQueryMsg},
-        IsmQueryMsg, ModuleTypeResponse, VerifyResponse,
+        InstantiateResponse, IsmMetadataQueryMsg, IsmQueryMsg, IsmType, MetadataLayout,
+        MetadataLayoutResponse, ModuleTypeResponse, VerifyResponse,
     },
     types::Message,
 };
@@ -37,11 +40,13 @@ pub fn instantiate(
     }
 
     Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse { owner })?)
         .add_attribute("method", "instantiate")
         .add_attribute("owner", msg.owner))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -51,70 +56,123 @@ pub fn execute(
     use ExecuteMsg::*;
 
     match msg {
-        Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
-        Set { ism } => {
+        Set { ism, unchecked } => {
             ensure_eq!(
                 get_owner(deps.storage)?,
                 info.sender,
-                ContractError::Unauthorized {}
+                HplError::Unauthorized {}
             );
 
-            MODULES.save(
-                deps.storage,
+            let ism_addr = deps.api.addr_validate(&ism.address)?;
+
+            if !unchecked {
+                let typ =
+                    hpl_interface::ism::module_type(&deps.querier, &ism_addr).map_err(|e| {
+                        ContractError::NotAnIsm {
+                            address: ism.address.clone(),
+                            error: e.to_string(),
+                        }
+                    })?;
+
+                ensure!(
+                    typ != IsmType::Unused,
+                    ContractError::InvalidIsm {
+                        ism: ism.address.clone()
+                    }
+                );
+            }
+
+            MODULES.save(deps.storage, ism.domain, &ism_addr)?;
+
+            Ok(Response::default().add_event(hpl_events::route_set(
+                new_event("set"),
+                &info.sender,
                 ism.domain,
-                &deps.api.addr_validate(&ism.address)?,
-            )?;
-
-            Ok(Response::default().add_event(
-                new_event("set")
-                    .add_attribute("sender", info.sender)
-                    .add_attribute("domain", ism.domain.to_string())
-                    .add_attribute("ism", ism.address),
-            ))
+                ism.address,
+            )))
         }
         Unset { domains } => {
             ensure_eq!(
                 get_owner(deps.storage)?,
                 info.sender,
-                ContractError::Unauthorized {}
+                HplError::Unauthorized {}
             );
 
             for domain in domains.clone() {
                 MODULES.remove(deps.storage, domain);
             }
 
-            Ok(Response::default().add_event(
-                new_event("unset")
-                    .add_attribute("sender", info.sender)
-                    .add_attribute(
-                        "domains",
-                        domains
-                            .into_iter()
-                            .map(|v| v.to_string())
-                            .collect::<Vec<_>>()
-                            .join(","),
-                    ),
-            ))
+            Ok(Response::default().add_event(hpl_events::route_unset(
+                new_event("unset"),
+                &info.sender,
+                &domains,
+            )))
         }
+        VerifyAndRecord { metadata, message } => verify_and_record(deps, metadata, message),
     }
 }
 
+fn route(storage: &dyn Storage, message: &Message) -> Result<Addr, ContractError> {
+    MODULES
+        .may_load(storage, message.origin_domain)?
+        .ok_or(ContractError::Hpl(HplError::RouteNotFound {
+            domain: message.origin_domain,
+        }))
+}
+
+fn verify_and_record(
+    deps: DepsMut,
+    metadata: HexBinary,
+    message: HexBinary,
+) -> Result<Response, ContractError> {
+    let decoded = Message::try_from(message.clone())?;
+    let message_id = decoded.id();
+
+    let ism = route(deps.storage, &decoded)?;
+    let verified = hpl_interface::ism::verify(&deps.querier, &ism, metadata, message.clone())?;
+
+    // follow nested routing isms down to the leaf that verified the message
+    let mut hops = vec![ism];
+    while let Some(hop) = hops.last() {
+        if hpl_interface::ism::module_type(&deps.querier, hop)? != IsmType::Routing {
+            break;
+        }
+
+        let resp: RouteResponse = deps.querier.query_wasm_smart(
+            hop,
+            &QueryMsg::RoutingIsm(RoutingIsmQueryMsg::Route {
+                message: message.clone(),
+            }),
+        )?;
+        hops.push(deps.api.addr_validate(&resp.ism)?);
+    }
+
+    let events = hops.into_iter().map(|hop| {
+        new_event("verified")
+            .add_attribute("message_id", message_id.to_hex())
+            .add_attribute("route_domain", decoded.origin_domain.to_string())
+            .add_attribute("ism", hop)
+            .add_attribute("result", verified.to_string())
+    });
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&VerifyResponse { verified })?)
+        .add_events(events))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     use IsmQueryMsg::*;
 
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Ism(msg) => match msg {
             ModuleType {} => Ok(to_json_binary(&ModuleTypeResponse {
-                typ: hpl_interface::ism::IsmType::Routing,
+                typ: IsmType::Routing,
             })?),
             Verify { metadata, message } => {
-                let decoded = Message::from(message.clone());
-
-                let ism = MODULES
-                    .may_load(deps.storage, decoded.origin_domain)?
-                    .ok_or(ContractError::RouteNotFound {})?;
+                let ism = route(deps.storage, &message.clone().try_into()?)?;
 
                 let verify_resp: VerifyResponse = deps
                     .querier
@@ -123,11 +181,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
                 Ok(to_json_binary(&verify_resp)?)
             }
             VerifyInfo { message } => {
-                let decoded = Message::from(message.clone());
-
-                let ism = MODULES
-                    .may_load(deps.storage, decoded.origin_domain)?
-                    .ok_or(ContractError::RouteNotFound {})?;
+                let ism = route(deps.storage, &message.clone().try_into()?)?;
 
                 let verify_resp: VerifyResponse = deps
                     .querier
@@ -136,17 +190,37 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, Contr
                 Ok(to_json_binary(&verify_resp)?)
             }
         },
+        QueryMsg::IsmMetadata(msg) => match msg {
+            IsmMetadataQueryMsg::MetadataLayout { .. } => {
+                Ok(to_json_binary(&MetadataLayoutResponse {
+                    layout: MetadataLayout::DependsOnMessage {},
+                })?)
+            }
+            IsmMetadataQueryMsg::MetadataLayoutFor { message } => {
+                let ism = route(deps.storage, &message.clone().try_into()?)?;
+
+                let layout = hpl_interface::ism::metadata_layout(
+                    &deps.querier,
+                    ism,
+                    IsmMetadataQueryMsg::MetadataLayoutFor { message },
+                )?;
+
+                Ok(to_json_binary(&MetadataLayoutResponse { layout })?)
+            }
+        },
         QueryMsg::RoutingIsm(msg) => match msg {
             RoutingIsmQueryMsg::Route { message } => {
-                let decoded = Message::from(message);
-
-                let ism = MODULES
-                    .may_load(deps.storage, decoded.origin_domain)?
-                    .ok_or(ContractError::RouteNotFound {})?
-                    .to_string();
+                let ism = route(deps.storage, &message.try_into()?)?.to_string();
 
                 Ok(to_json_binary(&RouteResponse { ism })?)
             }
         },
     }
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
@@ -1,17 +1,26 @@
-use cosmwasm_std::{StdError, VerificationError};
+use hpl_error::HplError;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
+    #[error(transparent)]
+    Hpl(#[from] HplError),
 
-    #[error("{0}")]
-    VerificationError(#[from] VerificationError),
+    #[error("[E400] invalid ism. address: {ism}")]
+    InvalidIsm { ism: String },
 
-    #[error("Unauthorized")]
-    Unauthorized,
+    #[error("[E401] {address} is not an ism: {error}")]
+    NotAnIsm { address: String, error: String },
+}
+
+hpl_error::impl_from_hpl!(ContractError);
 
-    #[error("RouteNotFound")]
-    RouteNotFound {},
+impl ContractError {
+    pub fn code(&self) -> u16 {
+        match self {
+            ContractError::Hpl(err) => err.code(),
+            ContractError::InvalidIsm { .. } => 400,
+            ContractError::NotAnIsm { .. } => 401,
+        }
+    }
 }
@@ -1,6 +1,12 @@
-use cosmwasm_std::{to_json_binary, Addr, ContractResult, HexBinary, SystemResult, WasmQuery};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, ContractResult, HexBinary, SystemResult, WasmQuery,
+};
+use hpl_error::HplError;
 use hpl_interface::{
-    ism::{routing::IsmSet, IsmType, VerifyResponse},
+    ism::{
+        routing::IsmSet, ExpectedIsmMetadataQueryMsg, InstantiateResponse, IsmMetadataQueryMsg,
+        IsmType, MetadataLayout, MetadataLayoutResponse, ModuleTypeResponse, VerifyResponse,
+    },
     types::Message,
 };
 use hpl_ownable::get_owner;
@@ -39,7 +45,10 @@ fn test_init() -> anyhow::Result<()> {
 
     let mut ism = IsmRouting::default();
 
-    ism.init(&deployer, &owner, isms)?;
+    let res = ism.init(&deployer, &owner, isms)?;
+
+    let data: InstantiateResponse = from_json(res.data.unwrap())?;
+    assert_eq!(data.owner, owner);
 
     let storage = ism.deps.as_ref().storage;
     assert_eq!(owner, get_owner(storage)?);
@@ -56,6 +65,22 @@ fn test_set() -> anyhow::Result<()> {
 
     let mut ism = IsmRouting::default();
 
+    ism.deps.querier.update_wasm(|v| match v {
+        WasmQuery::Smart { contract_addr, .. } => SystemResult::Ok(ContractResult::Ok(
+            match contract_addr.as_str() {
+                "hook" => return SystemResult::Ok(ContractResult::Err("unknown variant".into())),
+                "ism1" => to_json_binary(&ModuleTypeResponse {
+                    typ: IsmType::MessageIdMultisig,
+                }),
+                _ => to_json_binary(&ModuleTypeResponse {
+                    typ: IsmType::Unused,
+                }),
+            }
+            .unwrap(),
+        )),
+        _ => panic!("not implemented"),
+    });
+
     ism.init(&deployer, &owner, vec![])?;
 
     let target = IsmSet {
@@ -65,15 +90,44 @@ fn test_set() -> anyhow::Result<()> {
 
     // fails if sender is not owner
     let err = ism.set(&deployer, &target).unwrap_err();
-    assert_eq!(err, ContractError::Unauthorized {});
+    assert_eq!(err, ContractError::Hpl(HplError::Unauthorized {}));
+
+    // fails if ism is not a valid module
+    let unused = IsmSet {
+        domain: 2,
+        address: "ism2".to_string(),
+    };
+    let err = ism.set(&owner, &unused).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidIsm {
+            ism: "ism2".to_string()
+        }
+    );
+
+    // fails if the target doesn't answer ism queries
+    let hook = IsmSet {
+        domain: 3,
+        address: "hook".to_string(),
+    };
+    let err = ism.set(&owner, &hook).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::NotAnIsm { ref address, .. } if address == "hook"
+    ));
 
     // ok
     ism.set(&owner, &target)?;
 
+    // unchecked skips the probe
+    ism.set_unchecked(&owner, &hook, true)?;
+
     // check state
 
     let storage = ism.deps.as_ref().storage;
     assert_eq!(Addr::unchecked("ism1"), MODULES.load(storage, 1)?);
+    assert!(!MODULES.has(storage, 2));
+    assert_eq!(Addr::unchecked("hook"), MODULES.load(storage, 3)?);
 
     Ok(())
 }
@@ -119,7 +173,9 @@ fn test_query() -> anyhow::Result<()> {
     assert_eq!(IsmType::Routing, ism.get_module_type()?.typ);
 
     // check verify query
-    let err_not_found = ContractError::RouteNotFound {};
+    let err_not_found = ContractError::Hpl(HplError::RouteNotFound {
+        domain: chain_src_domain,
+    });
     for (domain, expect) in [
         (chain_a_domain, Ok(true)),
         (chain_b_domain, Ok(false)),
@@ -138,7 +194,9 @@ fn test_query() -> anyhow::Result<()> {
     }
 
     // check route query
-    let err_not_found = ContractError::RouteNotFound {};
+    let err_not_found = ContractError::Hpl(HplError::RouteNotFound {
+        domain: chain_src_domain,
+    });
     for (domain, expect) in [
         (chain_a_domain, Ok("ism1")),
         (chain_b_domain, Ok("ism2")),
@@ -157,3 +215,96 @@ fn test_query() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_metadata_layout() -> anyhow::Result<()> {
+    let mut ism = IsmRouting::default();
+
+    let layout = MetadataLayout::MessageIdMultisig {
+        threshold: 2,
+        validators_len: 3,
+        expected_len: 198,
+    };
+
+    let message: HexBinary = Message {
+        origin_domain: 2,
+        ..make_default_message()
+    }
+    .into();
+
+    // the routed ism is asked for the layout of the same message
+    let (expected, sub_layout) = (message.clone(), layout.clone());
+    ism.deps.querier.update_wasm(move |v| match v {
+        WasmQuery::Smart { contract_addr, msg } => {
+            assert_eq!(contract_addr, "ism1");
+            assert_eq!(
+                from_json::<ExpectedIsmMetadataQueryMsg>(msg).unwrap(),
+                IsmMetadataQueryMsg::MetadataLayoutFor {
+                    message: expected.clone()
+                }
+                .wrap()
+            );
+
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&MetadataLayoutResponse {
+                    layout: sub_layout.clone(),
+                })
+                .unwrap(),
+            ))
+        }
+        _ => panic!("not implemented"),
+    });
+
+    ism.init(
+        &Addr::unchecked("deployer"),
+        &Addr::unchecked("owner"),
+        vec![IsmSet {
+            domain: 2,
+            address: "ism1".to_string(),
+        }],
+    )?;
+
+    let res =
+        ism.query_metadata_layout(IsmMetadataQueryMsg::MetadataLayout { origin_domain: 2 })?;
+    assert_eq!(res.layout, MetadataLayout::DependsOnMessage {});
+
+    let res = ism.query_metadata_layout(IsmMetadataQueryMsg::MetadataLayoutFor { message })?;
+    assert_eq!(res.layout, layout);
+
+    let res = ism.query_metadata_layout(IsmMetadataQueryMsg::MetadataLayoutFor {
+        message: Message {
+            origin_domain: 3,
+            ..make_default_message()
+        }
+        .into(),
+    });
+    assert_eq!(
+        res,
+        Err(ContractError::Hpl(HplError::RouteNotFound { domain: 3 }))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_error_codes() {
+    let err = ContractError::InvalidIsm {
+        ism: "ism".to_string(),
+    };
+    assert_eq!(err.code(), 400);
+    assert_eq!(err.to_string(), "[E400] invalid ism. address: ism");
+
+    let err = ContractError::NotAnIsm {
+        address: "hook".to_string(),
+        error: "unknown variant".to_string(),
+    };
+    assert_eq!(err.code(), 401);
+    assert_eq!(
+        err.to_string(),
+        "[E401] hook is not an ism: unknown variant"
+    );
+
+    let err = ContractError::from(HplError::RouteNotFound { domain: 1 });
+    assert_eq!(err.code(), 17);
+    assert_eq!(err.to_string(), "[E017] route not found for domain 1");
+}
@@ -5,7 +5,7 @@ use cosmwasm_std::{
 };
 use hpl_interface::ism::{
     routing::{ExecuteMsg, InstantiateMsg, IsmSet, QueryMsg, RouteResponse, RoutingIsmQueryMsg},
-    IsmQueryMsg, ModuleTypeResponse, VerifyResponse,
+    IsmMetadataQueryMsg, IsmQueryMsg, MetadataLayoutResponse, ModuleTypeResponse, VerifyResponse,
 };
 use serde::de::DeserializeOwned;
 
@@ -15,6 +15,7 @@ use crate::{
 };
 
 mod contract;
+mod multitest;
 
 pub struct IsmRouting {
     pub deps: OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
@@ -64,9 +65,21 @@ impl IsmRouting {
     }
 
     pub fn set(&mut self, sender: &Addr, ism: &IsmSet) -> Result<Response, ContractError> {
+        self.set_unchecked(sender, ism, false)
+    }
+
+    pub fn set_unchecked(
+        &mut self,
+        sender: &Addr,
+        ism: &IsmSet,
+        unchecked: bool,
+    ) -> Result<Response, ContractError> {
         self.execute(
             mock_info(sender.as_str(), &[]),
-            ExecuteMsg::Set { ism: ism.clone() },
+            ExecuteMsg::Set {
+                ism: ism.clone(),
+                unchecked,
+            },
         )
     }
 
@@ -85,4 +98,11 @@ impl IsmRouting {
     pub fn query_route(&self, message: HexBinary) -> Result<RouteResponse, ContractError> {
         self.query(QueryMsg::RoutingIsm(RoutingIsmQueryMsg::Route { message }))
     }
+
+    pub fn query_metadata_layout(
+        &self,
+        msg: IsmMetadataQueryMsg,
+    ) -> Result<MetadataLayoutResponse, ContractError> {
+        self.query(QueryMsg::IsmMetadata(msg))
+    }
 }
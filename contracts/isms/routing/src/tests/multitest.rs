@@ -0,0 +1,114 @@
+use cosmwasm_std::{from_json, Addr, Empty, HexBinary};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use hpl_interface::{
+    ism::{
+        routing::{ExecuteMsg, InstantiateMsg, IsmSet},
+        VerifyResponse,
+    },
+    types::Message,
+};
+
+use crate::contract::{execute, instantiate, query};
+
+fn routing_ism() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn mock_ism() -> Box<dyn Contract<Empty>> {
+    use hpl_test_mock_ism::contract::{execute, instantiate, query};
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn deploy_routing(app: &mut App, owner: &Addr, domain: u32, ism: &Addr) -> Addr {
+    let code_id = app.store_code(routing_ism());
+
+    app.instantiate_contract(
+        code_id,
+        owner.clone(),
+        &InstantiateMsg {
+            owner: owner.to_string(),
+            isms: vec![IsmSet {
+                domain,
+                address: ism.to_string(),
+            }],
+        },
+        &[],
+        "routing",
+        None,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_verify_and_record() -> anyhow::Result<()> {
+    let mut app = App::default();
+
+    let owner = Addr::unchecked("owner");
+    let relayer = Addr::unchecked("relayer");
+    let origin_domain = 1;
+
+    let leaf_code_id = app.store_code(mock_ism());
+    let leaf = app.instantiate_contract(
+        leaf_code_id,
+        owner.clone(),
+        &hpl_test_mock_ism::contract::InstantiateMsg {},
+        &[],
+        "leaf",
+        None,
+    )?;
+
+    // outer routing ism -> inner routing ism -> leaf ism
+    let inner = deploy_routing(&mut app, &owner, origin_domain, &leaf);
+    let outer = deploy_routing(&mut app, &owner, origin_domain, &inner);
+
+    let message = Message {
+        version: 3,
+        nonce: 0,
+        origin_domain,
+        sender: [1u8; 32].into(),
+        dest_domain: 2,
+        recipient: [2u8; 32].into(),
+        body: HexBinary::default(),
+    };
+    let message_id = message.id();
+
+    let res = app.execute_contract(
+        relayer,
+        outer.clone(),
+        &ExecuteMsg::VerifyAndRecord {
+            metadata: HexBinary::default(),
+            message: message.into(),
+        },
+        &[],
+    )?;
+
+    let resp: VerifyResponse = from_json(res.data.unwrap())?;
+    assert!(resp.verified);
+
+    let events = res
+        .events
+        .iter()
+        .filter(|v| v.ty == "wasm-hpl_ism_routing::verified")
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 2);
+
+    for (event, ism) in events.into_iter().zip([&inner, &leaf]) {
+        let attr = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|v| v.key == key)
+                .map(|v| v.value.clone())
+                .unwrap()
+        };
+
+        assert_eq!(attr("_contract_address"), outer.to_string());
+        assert_eq!(attr("message_id"), message_id.to_hex());
+        assert_eq!(attr("route_domain"), origin_domain.to_string());
+        assert_eq!(attr("ism"), ism.to_string());
+        assert_eq!(attr("result"), "true");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,290 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    ensure_eq, to_json_binary, Addr, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order,
+    QueryResponse, Response, StdResult,
+};
+use cw_storage_plus::Map;
+use hpl_interface::ism::{
+    trusted_relayer::{
+        ExecuteMsg, InstantiateMsg, QueryMsg, RelayersResponse, TrustedRelayerQueryMsg,
+    },
+    InstantiateResponse, IsmContextQueryMsg, IsmQueryMsg, IsmType, ModuleTypeResponse,
+    VerifyResponse,
+};
+
+/// No errors of its own
+pub type ContractError = hpl_error::HplError;
+
+// version info for migration info
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const RELAYERS_PREFIX: &str = "relayers";
+pub const RELAYERS: Map<Addr, ()> = Map::new(RELAYERS_PREFIX);
+
+fn new_event(name: &str) -> Event {
+    Event::new(format!("hpl_ism_trusted_relayer::{}", name))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let owner = deps.api.addr_validate(&msg.owner)?;
+
+    hpl_ownable::initialize(deps.storage, &owner)?;
+
+    for relayer in msg.relayers.iter() {
+        RELAYERS.save(deps.storage, deps.api.addr_validate(relayer)?, &())?;
+    }
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&InstantiateResponse {
+            owner: owner.clone(),
+        })?)
+        .add_event(
+            new_event("initialize")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("relayers", msg.relayers.join(",")),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::SetRelayer { relayer, trusted } => {
+            ensure_eq!(
+                hpl_ownable::get_owner(deps.storage)?,
+                info.sender,
+                ContractError::Unauthorized {}
+            );
+
+            let relayer = deps.api.addr_validate(&relayer)?;
+
+            if trusted {
+                RELAYERS.save(deps.storage, relayer.clone(), &())?;
+            } else {
+                RELAYERS.remove(deps.storage, relayer.clone());
+            }
+
+            Ok(Response::new().add_event(
+                new_event("set_relayer")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("relayer", relayer)
+                    .add_attribute("trusted", trusted.to_string()),
+            ))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+    match msg {
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
+        QueryMsg::Ism(msg) => match msg {
+            IsmQueryMsg::ModuleType {} => {
+                Ok(to_json_binary(&ModuleTypeResponse { typ: IsmType::Null })?)
+            }
+            // the relayer is unknown without context, so nothing can be trusted
+            IsmQueryMsg::Verify { .. } => Ok(to_json_binary(&VerifyResponse { verified: false })?),
+            IsmQueryMsg::VerifyInfo { .. } => {
+                Err(ContractError::invalid_args("verify info is not supported"))
+            }
+        },
+        QueryMsg::IsmContext(IsmContextQueryMsg::VerifyWithContext { relayer, .. }) => {
+            let verified = match deps.api.addr_validate(&relayer) {
+                Ok(relayer) => RELAYERS.has(deps.storage, relayer),
+                Err(_) => false,
+            };
+
+            Ok(to_json_binary(&VerifyResponse { verified })?)
+        }
+        QueryMsg::TrustedRelayer(TrustedRelayerQueryMsg::Relayers {}) => {
+            let relayers = RELAYERS
+                .keys(deps.storage, None, None, Order::Ascending)
+                .map(|v| v.map(String::from))
+                .collect::<StdResult<Vec<_>>>()?;
+
+            Ok(to_json_binary(&RelayersResponse { relayers })?)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{
+        from_json,
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        HexBinary, OwnedDeps,
+    };
+    use hpl_ownable::get_owner;
+    use ibcx_test_utils::addr;
+    use rstest::{fixture, rstest};
+
+    use super::*;
+
+    type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    fn query<T: cosmwasm_schema::serde::de::DeserializeOwned>(deps: Deps, msg: QueryMsg) -> T {
+        from_json(crate::query(deps, mock_env(), msg).unwrap()).unwrap()
+    }
+
+    fn verify_with_context(deps: Deps, relayer: &str) -> bool {
+        let res: VerifyResponse = query(
+            deps,
+            QueryMsg::IsmContext(IsmContextQueryMsg::VerifyWithContext {
+                metadata: HexBinary::default(),
+                message: HexBinary::default(),
+                relayer: relayer.to_string(),
+            }),
+        );
+
+        res.verified
+    }
+
+    #[fixture]
+    fn deps(
+        #[default(addr("deployer"))] sender: Addr,
+        #[default(addr("owner"))] owner: Addr,
+        #[default(vec![addr("relayer")])] relayers: Vec<Addr>,
+    ) -> TestDeps {
+        let mut deps = mock_dependencies();
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender.as_str(), &[]),
+            InstantiateMsg {
+                owner: owner.to_string(),
+                relayers: relayers.iter().map(|v| v.to_string()).collect(),
+            },
+        )
+        .unwrap();
+
+        deps
+    }
+
+    #[rstest]
+    fn test_init(deps: TestDeps) {
+        assert_eq!("owner", get_owner(deps.as_ref().storage).unwrap().as_str());
+
+        let res: RelayersResponse = query(
+            deps.as_ref(),
+            QueryMsg::TrustedRelayer(TrustedRelayerQueryMsg::Relayers {}),
+        );
+        assert_eq!(res.relayers, vec!["relayer".to_string()]);
+
+        let res: ModuleTypeResponse =
+            query(deps.as_ref(), QueryMsg::Ism(IsmQueryMsg::ModuleType {}));
+        assert_eq!(res.typ, IsmType::Null);
+    }
+
+    #[test]
+    fn test_init_data() {
+        let mut deps = mock_dependencies();
+
+        let res = instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("deployer", &[]),
+            InstantiateMsg {
+                owner: "owner".to_string(),
+                relayers: vec![],
+            },
+        )
+        .unwrap();
+
+        let data: InstantiateResponse = from_json(res.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            InstantiateResponse {
+                owner: addr("owner")
+            }
+        );
+    }
+
+    #[rstest]
+    #[case("relayer", true)]
+    #[case("not_relayer", false)]
+    #[case("Relayer", false)]
+    fn test_verify_with_context(deps: TestDeps, #[case] relayer: &str, #[case] verified: bool) {
+        assert_eq!(verify_with_context(deps.as_ref(), relayer), verified);
+    }
+
+    #[rstest]
+    fn test_verify_without_context(deps: TestDeps) {
+        let res: VerifyResponse = query(
+            deps.as_ref(),
+            QueryMsg::Ism(IsmQueryMsg::Verify {
+                metadata: HexBinary::default(),
+                message: HexBinary::default(),
+            }),
+        );
+
+        assert!(!res.verified);
+    }
+
+    #[rstest]
+    fn test_verify_info(deps: TestDeps) {
+        let err = crate::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Ism(IsmQueryMsg::VerifyInfo {
+                message: HexBinary::default(),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::invalid_args("verify info is not supported")
+        );
+    }
+
+    #[rstest]
+    #[case(addr("owner"))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("someone"))]
+    fn test_set_relayer(mut deps: TestDeps, #[case] sender: Addr) {
+        let mut set_relayer = |relayer: &str, trusted: bool| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(sender.as_str(), &[]),
+                ExecuteMsg::SetRelayer {
+                    relayer: relayer.to_string(),
+                    trusted,
+                },
+            )
+            .map_err(|e| e.to_string())
+            .unwrap();
+        };
+
+        set_relayer("new_relayer", true);
+        set_relayer("relayer", false);
+
+        assert!(verify_with_context(deps.as_ref(), "new_relayer"));
+        assert!(!verify_with_context(deps.as_ref(), "relayer"));
+    }
+}
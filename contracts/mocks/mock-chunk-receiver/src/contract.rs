@@ -0,0 +1,343 @@
+use cosmwasm_schema::cw_serde;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    ensure, ensure_eq, to_json_binary, wasm_execute, Addr, Binary, Deps, DepsMut, Empty, Env,
+    Event, HexBinary, MessageInfo, QueryResponse, Response, StdError, StdResult,
+};
+use cw2::set_contract_version;
+use cw_storage_plus::{Item, Map};
+use hpl_interface::{
+    core::{chunk::ChunkHeader, ExpectedHandleMsg, HandleMsg},
+    ism,
+};
+
+use crate::{CONTRACT_NAME, CONTRACT_VERSION};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub mailbox: String,
+    /// Recipient handling the reassembled bodies
+    pub target: String,
+}
+
+/// Body being reassembled, keyed by origin, sender and correlation id so senders can't
+/// write into each other's bodies
+#[cw_serde]
+pub struct Pending {
+    pub origin: u32,
+    pub sender: HexBinary,
+    pub total: u16,
+    pub received: u16,
+}
+
+pub const MAILBOX_KEY: &str = "mailbox";
+pub const MAILBOX: Item<Addr> = Item::new(MAILBOX_KEY);
+
+pub const TARGET_KEY: &str = "target";
+pub const TARGET: Item<Addr> = Item::new(TARGET_KEY);
+
+/// (origin, sender, correlation id)
+pub type BodyKey<'a> = (u32, &'a [u8], &'a [u8]);
+
+pub const PENDING_PREFIX: &str = "pending";
+pub const PENDING: Map<BodyKey, Pending> = Map::new(PENDING_PREFIX);
+
+pub const CHUNKS_PREFIX: &str = "chunks";
+pub const CHUNKS: Map<(BodyKey, u16), Binary> = Map::new(CHUNKS_PREFIX);
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    MAILBOX.save(deps.storage, &deps.api.addr_validate(&msg.mailbox)?)?;
+    TARGET.save(deps.storage, &deps.api.addr_validate(&msg.target)?)?;
+
+    Ok(Response::new().add_attribute("method", "instantiate"))
+}
+
+/// Buffers each chunk, then hands the reassembled body to the target once all arrived
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExpectedHandleMsg,
+) -> StdResult<Response> {
+    let ExpectedHandleMsg::Handle(msg) = msg;
+
+    ensure_eq!(
+        info.sender,
+        MAILBOX.load(deps.storage)?,
+        StdError::generic_err("unauthorized")
+    );
+
+    let (header, data) = ChunkHeader::decode(&msg.body)?;
+    let id = (
+        msg.origin,
+        msg.sender.as_slice(),
+        header.correlation_id.as_slice(),
+    );
+
+    let mut pending = PENDING.may_load(deps.storage, id)?.unwrap_or(Pending {
+        origin: msg.origin,
+        sender: msg.sender.clone(),
+        total: header.total,
+        received: 0,
+    });
+    ensure!(
+        pending.total == header.total,
+        StdError::generic_err("chunk doesn't match the pending body")
+    );
+    ensure!(
+        !CHUNKS.has(deps.storage, (id, header.index)),
+        StdError::generic_err(format!("duplicated chunk {}", header.index))
+    );
+
+    CHUNKS.save(deps.storage, (id, header.index), &data.into())?;
+    pending.received += 1;
+
+    let event = Event::new("chunk_received")
+        .add_attribute("correlation_id", header.correlation_id.to_hex())
+        .add_attribute("index", header.index.to_string())
+        .add_attribute("total", header.total.to_string());
+
+    if pending.received < pending.total {
+        PENDING.save(deps.storage, id, &pending)?;
+
+        return Ok(Response::new().add_event(event));
+    }
+
+    let mut body = vec![];
+    for index in 0..pending.total {
+        body.extend_from_slice(&CHUNKS.load(deps.storage, (id, index))?);
+        CHUNKS.remove(deps.storage, (id, index));
+    }
+    PENDING.remove(deps.storage, id);
+
+    let handle_msg = HandleMsg {
+        origin: pending.origin,
+        sender: pending.sender,
+        body: body.into(),
+    };
+
+    Ok(Response::new()
+        .add_message(wasm_execute(
+            TARGET.load(deps.storage)?,
+            &handle_msg.wrap(),
+            vec![],
+        )?)
+        .add_event(event))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(
+    _deps: Deps,
+    _env: Env,
+    msg: ism::ExpectedIsmSpecifierQueryMsg,
+) -> StdResult<QueryResponse> {
+    match msg {
+        ism::ExpectedIsmSpecifierQueryMsg::IsmSpecifier(
+            ism::IsmSpecifierQueryMsg::InterchainSecurityModule(),
+        ) => Ok(to_json_binary(&ism::InterchainSecurityModuleResponse {
+            ism: None,
+        })?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::default())
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{Addr, Empty, HexBinary};
+    use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+    use hpl_interface::core::{chunk::split_chunks, HandleMsg};
+    use ibcx_test_utils::gen_bz;
+    use rstest::rstest;
+
+    use super::{execute, instantiate, query, InstantiateMsg, CHUNKS_PREFIX, PENDING_PREFIX};
+
+    const ORIGIN: u32 = 1;
+    const BODY: &str = "a body spanning three chunks";
+
+    struct Suite {
+        app: App,
+        mailbox: Addr,
+        receiver: Addr,
+    }
+
+    impl Suite {
+        fn handle(&mut self, sender: &HexBinary, body: &HexBinary) -> anyhow::Result<AppResponse> {
+            self.app.execute_contract(
+                self.mailbox.clone(),
+                self.receiver.clone(),
+                &HandleMsg {
+                    origin: ORIGIN,
+                    sender: sender.clone(),
+                    body: body.clone(),
+                }
+                .wrap(),
+                &[],
+            )
+        }
+    }
+
+    fn receiver() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    fn target() -> Box<dyn Contract<Empty>> {
+        use hpl_test_mock_msg_receiver::contract::{execute, instantiate, query};
+
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    fn setup() -> anyhow::Result<Suite> {
+        let mut app = App::default();
+        let mailbox = Addr::unchecked("mailbox");
+
+        let target_code_id = app.store_code(target());
+        let target = app.instantiate_contract(
+            target_code_id,
+            mailbox.clone(),
+            &hpl_test_mock_msg_receiver::contract::InstantiateMsg { hrp: "osmo".into() },
+            &[],
+            "target",
+            None,
+        )?;
+
+        let receiver_code_id = app.store_code(receiver());
+        let receiver = app.instantiate_contract(
+            receiver_code_id,
+            mailbox.clone(),
+            &InstantiateMsg {
+                mailbox: mailbox.to_string(),
+                target: target.to_string(),
+            },
+            &[],
+            "receiver",
+            None,
+        )?;
+
+        Ok(Suite {
+            app,
+            mailbox,
+            receiver,
+        })
+    }
+
+    fn received_body(res: &AppResponse) -> Option<String> {
+        res.events
+            .iter()
+            .find(|v| v.ty == "wasm-mailbox_msg_received")
+            .and_then(|v| v.attributes.iter().find(|v| v.key == "body"))
+            .map(|v| v.value.clone())
+    }
+
+    #[rstest]
+    #[case(vec![0, 1, 2])]
+    #[case(vec![2, 0, 1])]
+    #[case(vec![1, 2, 0])]
+    fn test_reassembly(#[case] order: Vec<usize>) -> anyhow::Result<()> {
+        let mut suite = setup()?;
+        let sender = gen_bz(32);
+
+        let chunks = split_chunks(&gen_bz(32), BODY.as_bytes(), 10)?;
+        assert_eq!(chunks.len(), 3);
+
+        let (last, rest) = order.split_last().unwrap();
+        for i in rest {
+            let res = suite.handle(&sender, &chunks[*i])?;
+            assert_eq!(received_body(&res), None);
+        }
+
+        // handed to the target in order, whatever order the chunks arrived in
+        let res = suite.handle(&sender, &chunks[*last])?;
+        assert_eq!(received_body(&res), Some(BODY.to_string()));
+
+        // nothing is left behind
+        let keys = suite
+            .app
+            .dump_wasm_raw(&suite.receiver)
+            .into_iter()
+            .map(|(k, _)| String::from_utf8_lossy(&k).to_string())
+            .collect::<Vec<_>>();
+        assert!(keys
+            .iter()
+            .all(|k| !k.contains(PENDING_PREFIX) && !k.contains(CHUNKS_PREFIX)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reassembly_same_id() -> anyhow::Result<()> {
+        let mut suite = setup()?;
+        let (sender_a, sender_b) = (gen_bz(32), gen_bz(32));
+        let id = gen_bz(32);
+        let other_body = "another body of three chunks";
+
+        let chunks_a = split_chunks(&id, BODY.as_bytes(), 10)?;
+        let chunks_b = split_chunks(&id, other_body.as_bytes(), 10)?;
+
+        for i in 0..2 {
+            suite.handle(&sender_a, &chunks_a[i])?;
+            suite.handle(&sender_b, &chunks_b[i])?;
+        }
+
+        // each sender gets its own body back
+        let res = suite.handle(&sender_b, &chunks_b[2])?;
+        assert_eq!(received_body(&res), Some(other_body.to_string()));
+
+        let res = suite.handle(&sender_a, &chunks_a[2])?;
+        assert_eq!(received_body(&res), Some(BODY.to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reassembly_invalid() -> anyhow::Result<()> {
+        let mut suite = setup()?;
+        let sender = gen_bz(32);
+        let id = gen_bz(32);
+
+        let chunks = split_chunks(&id, BODY.as_bytes(), 10)?;
+        suite.handle(&sender, &chunks[0])?;
+
+        let err = suite.handle(&sender, &chunks[0]).unwrap_err();
+        assert!(err.root_cause().to_string().contains("duplicated chunk 0"));
+
+        // chunks of the same body can't change its size
+        let resized = split_chunks(&id, BODY.as_bytes(), 20)?;
+        let err = suite.handle(&sender, &resized[1]).unwrap_err();
+        assert!(err
+            .root_cause()
+            .to_string()
+            .contains("chunk doesn't match the pending body"));
+
+        let err = suite
+            .app
+            .execute_contract(
+                Addr::unchecked("someone"),
+                suite.receiver.clone(),
+                &HandleMsg {
+                    origin: ORIGIN,
+                    sender,
+                    body: chunks[1].clone(),
+                }
+                .wrap(),
+                &[],
+            )
+            .unwrap_err();
+        assert!(err.root_cause().to_string().contains("unauthorized"));
+
+        Ok(())
+    }
+}
@@ -8,7 +8,8 @@ use cosmwasm_std::{
 use cw2::set_contract_version;
 use cw_storage_plus::Item;
 use hpl_interface::hook::{
-    ExpectedHookQueryMsg, HookQueryMsg, PostDispatchMsg, QuoteDispatchResponse,
+    ExpectedHookQueryMsg, HookQueryMsg, HookType, HookTypeResponse, PostDispatchMsg,
+    QuoteDispatchResponse,
 };
 
 use crate::{CONTRACT_NAME, CONTRACT_VERSION};
@@ -106,6 +107,9 @@ pub fn query(deps: Deps, _env: Env, msg: ExpectedHookQueryMsg) -> StdResult<Quer
 
                 Ok(to_json_binary(&QuoteDispatchResponse { fees })?)
             }
+            HookQueryMsg::HookType {} => Ok(to_json_binary(&HookTypeResponse {
+                typ: HookType::Unused,
+            })?),
             HookQueryMsg::Mailbox {} => {
                 unimplemented!("mailbox query not implemented on mock hook")
             }
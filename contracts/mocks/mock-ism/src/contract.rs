@@ -6,7 +6,8 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 use hpl_interface::ism::{
-    ExpectedIsmQueryMsg, IsmQueryMsg, IsmType, VerifyInfoResponse, VerifyResponse,
+    ExpectedIsmQueryMsg, IsmQueryMsg, IsmType, ModuleTypeResponse, VerifyInfoResponse,
+    VerifyResponse,
 };
 
 use crate::{CONTRACT_NAME, CONTRACT_VERSION};
@@ -55,7 +56,7 @@ pub fn query(_deps: Deps, _env: Env, msg: ExpectedIsmQueryMsg) -> StdResult<Quer
 
     match msg {
         ExpectedIsmQueryMsg::Ism(msg) => match msg {
-            ModuleType {} => Ok(to_json_binary(&IsmType::Null)?),
+            ModuleType {} => Ok(to_json_binary(&ModuleTypeResponse { typ: IsmType::Null })?),
             Verify { .. } => Ok(to_json_binary(&VerifyResponse { verified: true })?),
             VerifyInfo { .. } => Ok(to_json_binary(&VerifyInfoResponse {
                 threshold: 1u8,
@@ -1,28 +1,40 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, to_json_binary, wasm_execute, CosmosMsg, Deps, DepsMut, Env, HexBinary, MessageInfo,
-    QueryResponse, Reply, Response, StdError, SubMsg, Uint128, Uint256, WasmMsg,
+    ensure, ensure_eq, instantiate2_address, to_json_binary, wasm_execute, CosmosMsg, Deps,
+    DepsMut, Env, HexBinary, MessageInfo, QueryResponse, Reply, Response, StdError, Storage,
+    SubMsg, Uint128, Uint256, WasmMsg,
 };
 
-use cw20::Cw20ExecuteMsg;
+use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse, TokenInfoResponse};
 use hpl_connection::{get_hook, get_ism};
+use hpl_error::HplError;
 use hpl_interface::{
     core::mailbox,
     ism::{InterchainSecurityModuleResponse, IsmSpecifierQueryMsg},
+    router::{DomainRouteSet, RouteResponse, RoutesResponse},
     to_binary,
-    types::bech32_encode,
+    types::{bech32_encode, bech32_to_h256, IGPMetadata, RemoteAddress},
     warp::{
         self,
-        cw20::{ExecuteMsg, InstantiateMsg, QueryMsg},
-        TokenMode, TokenModeMsg, TokenModeResponse, TokenTypeResponse,
+        cw20::{
+            ExecuteMsg, ExpectedTokenAddressResponse, InstantiateMsg, MigrateMsg,
+            MintCapacityResponse, QueryMsg, TokenCw20QueryMsg,
+        },
+        payload::{Payload, PayloadVersion},
+        DecodedRoute, GasPaymentRequiredResponse, HandleCallMsg, MailboxInfoResponse,
+        PauseStateResponse, PayloadVersionResponse, QuoteTransferRemoteResponse, RecipientFormat,
+        RecipientFormatResponse, RemoteRouterConfig, TokenMode, TokenModeMsg, TokenModeResponse,
+        TokenTypeResponse,
     },
 };
-use hpl_router::get_route;
+use hpl_router::{get_domains, get_route, migrate_routes};
 
 use crate::{
-    conv, error::ContractError, new_event, CONTRACT_NAME, CONTRACT_VERSION, HRP, MAILBOX, MODE,
-    REPLY_ID_CREATE_DENOM, TOKEN,
+    conv, error::ContractError, new_event, CONTRACT_NAME, CONTRACT_VERSION, DECIMALS,
+    DEFAULT_GAS_LIMITS, DOMAIN_HRPS, HRP, INBOUND_PAUSED, MAILBOX, MODE, PAYLOAD_VERSION,
+    PENDING_DISPATCH, RECIPIENT_FORMATS, REPLY_ID_BURN_FROM, REPLY_ID_CREATE_DENOM,
+    REQUIRE_GAS_PAYMENT, TOKEN, TOKEN_CODE_ID,
 };
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -43,45 +55,76 @@ pub fn instantiate(
     MAILBOX.save(deps.storage, &mailbox)?;
 
     hpl_ownable::initialize(deps.storage, &owner)?;
+    hpl_pausable::initialize(deps.storage, &false)?;
 
-    let (msgs, denom) = match msg.token {
+    let (msgs, denom, decimals) = match msg.token {
         TokenModeMsg::Bridged(token) => {
             let mut token_init_msg = token.init_msg;
+            // the cap is kept, but the warp route is always the minter
             token_init_msg.mint = Some(cw20::MinterResponse {
                 minter: env.contract.address.to_string(),
-                cap: None,
+                cap: token_init_msg.mint.and_then(|v| v.cap),
             });
 
-            let msgs = vec![SubMsg::reply_on_success(
-                WasmMsg::Instantiate {
-                    admin: Some(env.contract.address.to_string()),
+            TOKEN_CODE_ID.save(deps.storage, &token.code_id)?;
+
+            let admin = Some(env.contract.address.to_string());
+            let msg = to_json_binary(&token_init_msg)?;
+            let label = "token warp cw20".to_string();
+
+            let init_msg = match token.salt {
+                Some(salt) => WasmMsg::Instantiate2 {
+                    admin,
+                    code_id: token.code_id,
+                    label,
+                    msg,
+                    funds: vec![],
+                    salt: salt.into(),
+                },
+                None => WasmMsg::Instantiate {
+                    admin,
                     code_id: token.code_id,
-                    msg: to_json_binary(&token_init_msg)?,
+                    msg,
                     funds: vec![],
-                    label: "token warp cw20".to_string(),
+                    label,
                 },
-                REPLY_ID_CREATE_DENOM,
-            )];
+            };
 
-            (msgs, token_init_msg.name)
+            let msgs = vec![SubMsg::reply_on_success(init_msg, REPLY_ID_CREATE_DENOM)];
+
+            (msgs, token_init_msg.name, token_init_msg.decimals)
         }
         TokenModeMsg::Collateral(token) => {
             let token_addr = deps.api.addr_validate(&token.address)?;
+            let token_info: TokenInfoResponse = deps
+                .querier
+                .query_wasm_smart(&token_addr, &Cw20QueryMsg::TokenInfo {})?;
+
             TOKEN.save(deps.storage, &token_addr)?;
-            (vec![], token_addr.into())
+            (vec![], token_addr.into(), token_info.decimals)
         }
     };
 
-    Ok(Response::new().add_submessages(msgs).add_event(
-        new_event("instantiate")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner)
-            .add_attribute("mode", format!("{mode}"))
-            .add_attribute("denom", denom),
-    ))
+    DECIMALS.save(deps.storage, &decimals)?;
+
+    Ok(Response::new()
+        .set_data(to_json_binary(&warp::InstantiateResponse {
+            mailbox,
+            owner: owner.clone(),
+        })?)
+        .add_submessages(msgs)
+        .add_event(
+            new_event("instantiate")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("mode", format!("{mode}"))
+                .add_attribute("denom", denom)
+                .add_attribute("decimals", decimals.to_string()),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn execute(
     deps: DepsMut,
     env: Env,
@@ -91,15 +134,35 @@ pub fn execute(
     use ExecuteMsg::*;
 
     match msg {
-        Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         Router(msg) => Ok(hpl_router::handle(deps, env, info, msg)?),
         Connection(msg) => Ok(hpl_connection::handle(deps, env, info, msg)?),
-        Handle(msg) => mailbox_handle(deps, info, msg),
+        Pausable(msg) => Ok(hpl_pausable::handle(deps, env, info, msg)?),
+        SetInboundPaused { paused } => set_inbound_paused(deps, info, paused),
+        SetRecipientFormat { domain, format } => set_recipient_format(deps, info, domain, format),
+        SetGasPaymentRequired { domain, required } => {
+            set_gas_payment_required(deps, info, domain, required)
+        }
+        SetPayloadVersion { version } => set_payload_version(deps, info, version),
+        SetDomainHrp { domain, hrp } => set_domain_hrp(deps, info, domain, hrp),
+        SetDefaultGas { domain, gas } => set_default_gas(deps, info, domain, gas),
+        Handle(msg) => mailbox_handle(deps, env, info, msg),
+        EnrollRemoteRouter {
+            domain,
+            address,
+            decimals,
+        } => enroll_remote_router(deps, info, domain, address, decimals),
         TransferRemote {
             dest_domain,
             recipient,
             amount,
-        } => transfer_remote(deps, env, info, dest_domain, recipient, amount),
+            min_amount,
+        } => transfer_remote(deps, env, info, dest_domain, recipient, amount, min_amount),
+        TransferRemoteBurn {
+            dest_domain,
+            recipient,
+            amount,
+            min_amount,
+        } => transfer_remote_burn(deps, env, info, dest_domain, recipient, amount, min_amount),
     }
 }
 
@@ -124,52 +187,484 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
             Ok(resp)
         }
 
-        _ => Err(ContractError::InvalidReplyId),
+        REPLY_ID_BURN_FROM => {
+            let dispatch_msg = PENDING_DISPATCH.load(deps.storage)?;
+            PENDING_DISPATCH.remove(deps.storage);
+
+            Ok(Response::new().add_message(dispatch_msg))
+        }
+
+        _ => Err(HplError::InvalidReplyId {}.into()),
     }
 }
 
 fn mailbox_handle(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     msg: hpl_interface::core::HandleMsg,
 ) -> Result<Response, ContractError> {
     // validate mailbox
-    ensure_eq!(
-        info.sender,
-        MAILBOX.load(deps.storage)?,
-        ContractError::Unauthorized
+    let mailbox = MAILBOX.load(deps.storage)?;
+    ensure_eq!(info.sender, mailbox, HplError::Unauthorized {});
+    // paused deliveries stay on the mailbox, so they can be retried once released
+    ensure!(
+        !INBOUND_PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+        ContractError::InboundPaused
     );
     // validate origin chain router
+    let origin_router = get_remote_router(deps.storage, msg.origin)?;
     ensure_eq!(
         msg.sender,
-        get_route::<HexBinary>(deps.storage, msg.origin)?
-            .route
-            .expect("route not found"),
-        ContractError::Unauthorized
+        *origin_router.address,
+        HplError::Unauthorized {}
     );
 
-    let token_msg: warp::Message = msg.body.into();
+    let payload = Payload::try_decode(&msg.body)?;
+    let token_msg = payload.message();
     let recipient = bech32_encode(&HRP.load(deps.storage)?, &token_msg.recipient)?;
+    // amount is already scaled to local decimals by the origin router
+    let amount = token_msg.amount;
 
     let token = TOKEN.load(deps.storage)?;
     let mode = MODE.load(deps.storage)?;
 
-    let msg = match mode {
+    // transfers below the min amount go back to the refund recipient on the origin domain,
+    // without one reverting keeps the message undelivered so it can be retried
+    if let Some(min_amount) = token_msg.min_amount().filter(|v| amount < *v) {
+        let refund_recipient = token_msg
+            .refund_recipient()
+            .ok_or(ContractError::BelowMinAmount { amount, min_amount })?;
+
+        return refund(
+            deps.as_ref(),
+            &env,
+            msg.origin,
+            refund_recipient,
+            amount,
+            min_amount,
+        );
+    }
+
+    // the route mints and holds the collateral, so it never calls the token, the mailbox
+    // or itself on behalf of a remote sender
+    let call = payload.call();
+    if call.is_some() {
+        ensure!(
+            ![&token, &mailbox, &env.contract.address].contains(&&recipient),
+            ContractError::InvalidCallRecipient {
+                recipient: recipient.to_string()
+            }
+        );
+    }
+
+    let mut event = new_event("handle")
+        .add_attribute("recipient", &recipient)
+        .add_attribute("token", &token)
+        .add_attribute("amount", amount);
+
+    let transfer_msg = match mode {
         // make token mint msg if token mode is bridged
-        TokenMode::Bridged => conv::to_mint_msg(&token, &recipient, token_msg.amount)?,
+        TokenMode::Bridged => {
+            // fail here rather than in the mint, so the error tells the cap is reached
+            let requested = conv::to_uint128(amount)?;
+            let remaining = match get_mint_capacity(deps.as_ref())?.remaining {
+                Some(remaining) => {
+                    ensure!(
+                        requested <= remaining,
+                        ContractError::MintCapExceeded {
+                            requested,
+                            remaining
+                        }
+                    );
+                    (remaining - requested).to_string()
+                }
+                None => "none".to_string(),
+            };
+            event = event.add_attribute("remaining_mint_capacity", remaining);
+
+            conv::to_mint_msg(&token, &recipient, amount)?
+        }
         // make token transfer msg if token mode is collateral
         // we can consider to use MsgSend for further utility
-        TokenMode::Collateral => conv::to_send_msg(&token, &recipient, token_msg.amount)?,
+        TokenMode::Collateral => conv::to_send_msg(&token, &recipient, amount)?,
     };
 
-    Ok(Response::new().add_message(msg).add_event(
-        new_event("handle")
-            .add_attribute("recipient", recipient)
-            .add_attribute("token", token)
-            .add_attribute("amount", token_msg.amount),
+    let mut msgs = vec![transfer_msg];
+
+    // forward the call after the transfer, failing the whole delivery if it reverts
+    if let Some(call) = call {
+        let call = HandleCallMsg {
+            origin: msg.origin,
+            sender: msg.sender,
+            amount,
+            call,
+        };
+        msgs.push(wasm_execute(&recipient, &call.wrap(), vec![])?);
+    }
+
+    Ok(Response::new().add_messages(msgs).add_event(event))
+}
+
+/// Sends a transfer below its min amount back to the origin domain. Nothing is minted or
+/// released, so the origin route mints or releases it again. Hook fees of the dispatch
+/// can't be paid out of cw20 tokens, so the delivery fails if the route's hooks charge any
+fn refund(
+    deps: Deps,
+    env: &Env,
+    origin: u32,
+    refund_recipient: HexBinary,
+    amount: Uint256,
+    min_amount: Uint256,
+) -> Result<Response, ContractError> {
+    let dispatch_msg = new_dispatch_msg(
+        deps,
+        origin,
+        refund_recipient.clone(),
+        conv::to_uint128(amount)?,
+        None,
+        None,
+    )?;
+    let mailbox = MAILBOX.load(deps.storage)?;
+
+    let fees = mailbox::quote_dispatch(
+        &deps.querier,
+        &mailbox,
+        &env.contract.address,
+        dispatch_msg.clone(),
+    )?
+    .fees;
+    ensure!(
+        fees.is_empty(),
+        ContractError::RefundFeeNotCovered {
+            fees,
+            refund: amount
+        }
+    );
+
+    Ok(Response::new()
+        .add_message(wasm_execute(
+            mailbox,
+            &mailbox::ExecuteMsg::Dispatch(dispatch_msg),
+            vec![],
+        )?)
+        .add_event(
+            new_event("refund")
+                .add_attribute("origin", origin.to_string())
+                .add_attribute("refund_recipient", refund_recipient.to_hex())
+                .add_attribute("amount", amount)
+                .add_attribute("min_amount", min_amount),
+        ))
+}
+
+fn enroll_remote_router(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+    address: RemoteAddress,
+    decimals: u8,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    let route = RemoteRouterConfig {
+        address: address.to_h256()?.into(),
+        decimals,
+    };
+    let event = hpl_router::set_route(
+        deps.storage,
+        &info.sender,
+        DomainRouteSet {
+            domain,
+            route: Some(route),
+        },
+    )?;
+
+    Ok(Response::new().add_event(event))
+}
+
+fn set_recipient_format(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+    format: RecipientFormat,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    RECIPIENT_FORMATS.save(deps.storage, domain, &format)?;
+
+    Ok(Response::new().add_event(
+        new_event("set-recipient-format")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("format", format.to_string()),
+    ))
+}
+
+fn set_gas_payment_required(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+    required: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    REQUIRE_GAS_PAYMENT.save(deps.storage, domain, &required)?;
+
+    Ok(Response::new().add_event(
+        new_event("set-gas-payment-required")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("required", required.to_string()),
+    ))
+}
+
+fn set_inbound_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    INBOUND_PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new().add_event(
+        new_event("set-inbound-paused")
+            .add_attribute("sender", info.sender)
+            .add_attribute("paused", paused.to_string()),
+    ))
+}
+
+fn set_payload_version(
+    deps: DepsMut,
+    info: MessageInfo,
+    version: PayloadVersion,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    PAYLOAD_VERSION.save(deps.storage, &version)?;
+
+    Ok(Response::new().add_event(
+        new_event("set-payload-version")
+            .add_attribute("sender", info.sender)
+            .add_attribute("version", version.to_string()),
+    ))
+}
+
+fn set_domain_hrp(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+    hrp: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    match &hrp {
+        Some(hrp) => {
+            // rejects prefixes that can't encode an address
+            bech32_encode(hrp, &[1u8; 20])?;
+            DOMAIN_HRPS.save(deps.storage, domain, hrp)?
+        }
+        None => DOMAIN_HRPS.remove(deps.storage, domain),
+    }
+
+    Ok(Response::new().add_event(
+        new_event("set-domain-hrp")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("hrp", hrp.unwrap_or("none".into())),
+    ))
+}
+
+fn set_default_gas(
+    deps: DepsMut,
+    info: MessageInfo,
+    domain: u32,
+    gas: Option<u64>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    match gas {
+        Some(gas) => DEFAULT_GAS_LIMITS.save(deps.storage, domain, &gas)?,
+        None => DEFAULT_GAS_LIMITS.remove(deps.storage, domain),
+    }
+
+    Ok(Response::new().add_event(
+        new_event("set-default-gas")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("gas", gas.map(|v| v.to_string()).unwrap_or("none".into())),
     ))
 }
 
+fn get_remote_router(
+    storage: &dyn Storage,
+    domain: u32,
+) -> Result<RemoteRouterConfig, ContractError> {
+    get_route::<RemoteRouterConfig>(storage, domain)?
+        .route
+        .ok_or(ContractError::Hpl(HplError::RouteNotFound { domain }))
+}
+
+/// Dispatch of a transfer to `dest_domain`, as sent to the mailbox
+fn new_dispatch_msg(
+    deps: Deps,
+    dest_domain: u32,
+    recipient: HexBinary,
+    transfer_amount: Uint128,
+    min_amount: Option<Uint128>,
+    refund_recipient: Option<[u8; 32]>,
+) -> Result<mailbox::DispatchMsg, ContractError> {
+    let dest_router = get_remote_router(deps.storage, dest_domain)?;
+    let decimals = DECIMALS.load(deps.storage)?;
+    let remote_amount =
+        dest_router.to_remote_amount(decimals, Uint256::from_uint128(transfer_amount))?;
+    let remote_min_amount = min_amount
+        .map(|v| dest_router.to_remote_amount(decimals, Uint256::from_uint128(v)))
+        .transpose()?;
+
+    let mut dispatch_payload = warp::Message {
+        recipient,
+        amount: remote_amount,
+        metadata: HexBinary::default(),
+    };
+    if let Some(remote_min_amount) = remote_min_amount {
+        dispatch_payload = dispatch_payload.with_min_amount(remote_min_amount);
+    }
+    if let Some(refund_recipient) = refund_recipient {
+        dispatch_payload = dispatch_payload.with_refund_recipient(refund_recipient);
+    }
+
+    let version = PAYLOAD_VERSION.may_load(deps.storage)?.unwrap_or_default();
+    let mut dispatch_msg = mailbox::DispatchMsg::new(
+        dest_domain,
+        dest_router.address.into_inner(),
+        Payload::new(version, dispatch_payload).encode()?,
+    );
+    if let Some(hook) = get_hook(deps.storage)? {
+        dispatch_msg = dispatch_msg.with_hook(hook);
+    }
+    // an empty refund address leaves the refund to the igp default
+    if let Some(gas) = DEFAULT_GAS_LIMITS.may_load(deps.storage, dest_domain)? {
+        dispatch_msg = dispatch_msg.with_metadata(IGPMetadata {
+            gas_limit: Uint256::from(gas),
+            refund_address: HexBinary::default(),
+        });
+    }
+
+    Ok(dispatch_msg)
+}
+
+fn build_dispatch_msg(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    dest_domain: u32,
+    recipient: HexBinary,
+    transfer_amount: Uint128,
+    min_amount: Option<Uint128>,
+) -> Result<CosmosMsg, ContractError> {
+    ensure!(
+        !hpl_pausable::get_pause_info(deps.storage)?,
+        ContractError::OutboundPaused
+    );
+
+    let recipient_format = RECIPIENT_FORMATS
+        .may_load(deps.storage, dest_domain)?
+        .unwrap_or_default();
+    ensure!(
+        recipient_format.is_valid(&recipient),
+        ContractError::InvalidRecipientFormat {
+            domain: dest_domain,
+            expected: recipient_format,
+        }
+    );
+    if let Some(min_amount) = min_amount {
+        ensure!(
+            min_amount <= transfer_amount,
+            ContractError::InvalidMinAmount {
+                amount: transfer_amount,
+                min_amount,
+            }
+        );
+    }
+
+    let mailbox = MAILBOX.load(deps.storage)?;
+
+    // all funds sent are forwarded to the mailbox as gas payment
+    let gas_payment_required = REQUIRE_GAS_PAYMENT
+        .may_load(deps.storage, dest_domain)?
+        .unwrap_or_default();
+    ensure!(
+        !gas_payment_required || info.funds.iter().any(|v| !v.amount.is_zero()),
+        ContractError::GasPaymentRequired {
+            domain: dest_domain
+        }
+    );
+
+    // the sender gets the transfer back if it arrives below the min amount
+    let refund_recipient = min_amount
+        .map(|_| bech32_to_h256(info.sender.as_str()))
+        .transpose()?;
+    let dispatch_msg = new_dispatch_msg(
+        deps,
+        dest_domain,
+        recipient,
+        transfer_amount,
+        min_amount,
+        refund_recipient,
+    )?;
+
+    // fail here rather than in the hook, anything above the quote still goes to the hook
+    let quote = mailbox::quote_dispatch(
+        &deps.querier,
+        &mailbox,
+        &env.contract.address,
+        dispatch_msg.clone(),
+    )?;
+    ensure!(
+        quote.covered_by(&info.funds),
+        ContractError::InsufficientHookPayment {
+            required: quote.fees,
+            provided: info.funds.clone(),
+        }
+    );
+
+    Ok(wasm_execute(
+        mailbox,
+        &mailbox::ExecuteMsg::Dispatch(dispatch_msg),
+        info.funds.clone(),
+    )?
+    .into())
+}
+
 fn transfer_remote(
     deps: DepsMut,
     env: Env,
@@ -177,14 +672,20 @@ fn transfer_remote(
     dest_domain: u32,
     recipient: HexBinary,
     transfer_amount: Uint128,
+    min_amount: Option<Uint128>,
 ) -> Result<Response, ContractError> {
+    let dispatch_msg = build_dispatch_msg(
+        deps.as_ref(),
+        &env,
+        &info,
+        dest_domain,
+        recipient.clone(),
+        transfer_amount,
+        min_amount,
+    )?;
+
     let token = TOKEN.load(deps.storage)?;
     let mode = MODE.load(deps.storage)?;
-    let mailbox = MAILBOX.load(deps.storage)?;
-
-    let dest_router = get_route::<HexBinary>(deps.storage, dest_domain)?
-        .route
-        .expect("route not found");
 
     let mut msgs: Vec<CosmosMsg> = vec![];
 
@@ -208,20 +709,7 @@ fn transfer_remote(
     }
 
     // push mailbox dispatch msg
-    msgs.push(mailbox::dispatch(
-        mailbox,
-        dest_domain,
-        dest_router,
-        warp::Message {
-            recipient: recipient.clone(),
-            amount: Uint256::from_uint128(transfer_amount),
-            metadata: HexBinary::default(),
-        }
-        .into(),
-        get_hook(deps.storage)?.map(|v| v.into()),
-        None,
-        info.funds,
-    )?);
+    msgs.push(dispatch_msg);
 
     Ok(Response::new().add_messages(msgs).add_event(
         new_event("transfer-remote")
@@ -233,26 +721,150 @@ fn transfer_remote(
     ))
 }
 
+fn transfer_remote_burn(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    dest_domain: u32,
+    recipient: HexBinary,
+    transfer_amount: Uint128,
+    min_amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        MODE.load(deps.storage)?,
+        TokenMode::Bridged,
+        ContractError::BurnFromNotBridged
+    );
+
+    let dispatch_msg = build_dispatch_msg(
+        deps.as_ref(),
+        &env,
+        &info,
+        dest_domain,
+        recipient.clone(),
+        transfer_amount,
+        min_amount,
+    )?;
+
+    let token = TOKEN.load(deps.storage)?;
+
+    // dispatch is sent from the reply, only once the tokens are burnt
+    PENDING_DISPATCH.save(deps.storage, &dispatch_msg)?;
+
+    let burn_msg = SubMsg::reply_on_success(
+        wasm_execute(
+            &token,
+            &Cw20ExecuteMsg::BurnFrom {
+                owner: info.sender.to_string(),
+                amount: transfer_amount,
+            },
+            vec![],
+        )?,
+        REPLY_ID_BURN_FROM,
+    );
+
+    Ok(Response::new().add_submessage(burn_msg).add_event(
+        new_event("transfer-remote-burn")
+            .add_attribute("sender", info.sender)
+            .add_attribute("dest_domain", dest_domain.to_string())
+            .add_attribute("recipient", recipient.to_hex())
+            .add_attribute("token", token)
+            .add_attribute("amount", transfer_amount),
+    ))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
+#[hpl_ownable::route_ownable]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
     use warp::TokenWarpDefaultQueryMsg::*;
 
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Router(msg) => Ok(hpl_router::handle_query(deps, env, msg)?),
         QueryMsg::Connection(msg) => Ok(hpl_connection::handle_query(deps, env, msg)?),
         QueryMsg::TokenDefault(msg) => match msg {
             TokenType {} => to_binary(get_token_type(deps)),
             TokenMode {} => to_binary(get_token_mode(deps)),
+            RecipientFormat { domain } => to_binary(get_recipient_format(deps, domain)),
+            GasPaymentRequired { domain } => to_binary(get_gas_payment_required(deps, domain)),
+            PauseState {} => to_binary(get_pause_state(deps)),
+            PayloadVersion {} => to_binary(get_payload_version(deps)),
+            MailboxInfo {} => to_binary(get_mailbox_info(deps)),
+            RouterDecoded { domain } => to_binary(get_router_decoded(deps, domain)),
+            RoutersDecoded {} => to_binary(get_routers_decoded(deps)),
+            Ism {} => Ok(to_json_binary(&InterchainSecurityModuleResponse {
+                ism: get_ism(deps.storage)?,
+            })?),
+            QuoteTransferRemote {
+                dest_domain,
+                amount,
+                recipient,
+            } => to_binary(quote_transfer_remote(
+                deps,
+                env,
+                dest_domain,
+                amount,
+                recipient,
+            )),
         },
         QueryMsg::IsmSpecifier(IsmSpecifierQueryMsg::InterchainSecurityModule()) => {
             Ok(to_json_binary(&InterchainSecurityModuleResponse {
                 ism: get_ism(deps.storage)?,
             })?)
         }
+        QueryMsg::TokenCw20(msg) => match msg {
+            TokenCw20QueryMsg::ExpectedTokenAddress { salt } => {
+                to_binary(get_expected_token_address(deps, env, salt))
+            }
+            TokenCw20QueryMsg::MintCapacity {} => to_binary(get_mint_capacity(deps)),
+        },
     }
 }
 
+fn get_expected_token_address(
+    deps: Deps,
+    env: Env,
+    salt: HexBinary,
+) -> Result<ExpectedTokenAddressResponse, ContractError> {
+    let code_id = TOKEN_CODE_ID
+        .may_load(deps.storage)?
+        .ok_or(ContractError::ExpectedTokenAddressNotBridged)?;
+
+    let checksum = deps.querier.query_wasm_code_info(code_id)?.checksum;
+    let creator = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+    let address = instantiate2_address(&checksum, &creator, &salt)
+        .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+    Ok(ExpectedTokenAddressResponse {
+        address: deps.api.addr_humanize(&address)?.into(),
+    })
+}
+
+fn get_mint_capacity(deps: Deps) -> Result<MintCapacityResponse, ContractError> {
+    ensure_eq!(
+        MODE.load(deps.storage)?,
+        TokenMode::Bridged,
+        ContractError::MintCapacityNotBridged
+    );
+
+    let token = TOKEN.load(deps.storage)?;
+    let minter: Option<MinterResponse> = deps
+        .querier
+        .query_wasm_smart(&token, &Cw20QueryMsg::Minter {})?;
+    let token_info: TokenInfoResponse = deps
+        .querier
+        .query_wasm_smart(&token, &Cw20QueryMsg::TokenInfo {})?;
+
+    let cap = minter.and_then(|v| v.cap);
+    let minted = token_info.total_supply;
+
+    Ok(MintCapacityResponse {
+        cap,
+        minted,
+        remaining: cap.map(|v| v.saturating_sub(minted)),
+    })
+}
+
 fn get_token_type(deps: Deps) -> Result<TokenTypeResponse, ContractError> {
     let contract = TOKEN.load(deps.storage)?.into_string();
 
@@ -267,39 +879,227 @@ fn get_token_mode(deps: Deps) -> Result<TokenModeResponse, ContractError> {
     Ok(TokenModeResponse { mode })
 }
 
-#[cfg(test)]
-mod test {
-    use cosmwasm_std::{
-        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-        Empty, OwnedDeps, Uint128,
-    };
-    use hpl_interface::{
-        build_test_executor, build_test_querier,
-        core::HandleMsg,
-        router::DomainRouteSet,
-        warp::cw20::{Cw20ModeBridged, Cw20ModeCollateral},
-    };
-    use hpl_router::set_routes;
-    use ibcx_test_utils::{addr, gen_bz};
-    use rstest::{fixture, rstest};
+fn get_recipient_format(deps: Deps, domain: u32) -> Result<RecipientFormatResponse, ContractError> {
+    let format = RECIPIENT_FORMATS
+        .may_load(deps.storage, domain)?
+        .unwrap_or_default();
 
-    use super::*;
+    Ok(RecipientFormatResponse { format })
+}
 
-    build_test_querier!(super::query);
-    build_test_executor!(super::execute);
+fn get_gas_payment_required(
+    deps: Deps,
+    domain: u32,
+) -> Result<GasPaymentRequiredResponse, ContractError> {
+    let required = REQUIRE_GAS_PAYMENT
+        .may_load(deps.storage, domain)?
+        .unwrap_or_default();
 
-    const DEPLOYER: &str = "sender";
-    const OWNER: &str = "owner";
-    const MAILBOX: &str = "mailbox";
-    const TOKEN: &str = "token";
+    Ok(GasPaymentRequiredResponse { required })
+}
 
-    const CW20_BRIDGED_CODE_ID: u64 = 1;
-    const CW20_BRIDGED_NAME: &str = "cw20-created";
+fn get_pause_state(deps: Deps) -> Result<PauseStateResponse, ContractError> {
+    Ok(PauseStateResponse {
+        outbound: hpl_pausable::get_pause_info(deps.storage)?,
+        inbound: INBOUND_PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+fn get_payload_version(deps: Deps) -> Result<PayloadVersionResponse, ContractError> {
+    let version = PAYLOAD_VERSION.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(PayloadVersionResponse { version })
+}
+
+fn decode_route(
+    storage: &dyn Storage,
+    domain: u32,
+) -> Result<DomainRouteSet<DecodedRoute>, ContractError> {
+    let route = get_route::<RemoteRouterConfig>(storage, domain)?
+        .route
+        .map(|v| {
+            let hrp = DOMAIN_HRPS.may_load(storage, domain)?;
+            DecodedRoute::new(&v.address, hrp.as_deref())
+        })
+        .transpose()?;
+
+    Ok(DomainRouteSet { domain, route })
+}
+
+fn get_router_decoded(
+    deps: Deps,
+    domain: u32,
+) -> Result<RouteResponse<DecodedRoute>, ContractError> {
+    Ok(RouteResponse {
+        route: decode_route(deps.storage, domain)?,
+    })
+}
+
+fn get_routers_decoded(deps: Deps) -> Result<RoutesResponse<DecodedRoute>, ContractError> {
+    let routes = get_domains::<RemoteRouterConfig>(deps.storage)?
+        .into_iter()
+        .map(|domain| decode_route(deps.storage, domain))
+        .collect::<Result<_, _>>()?;
+
+    Ok(RoutesResponse { routes })
+}
+
+fn get_mailbox_info(deps: Deps) -> Result<MailboxInfoResponse, ContractError> {
+    let mailbox = MAILBOX.load(deps.storage)?;
+    let local_domain = deps
+        .querier
+        .query_wasm_smart::<mailbox::LocalDomainResponse>(
+            &mailbox,
+            &mailbox::QueryMsg::Mailbox(mailbox::MailboxQueryMsg::LocalDomain {}),
+        )?
+        .local_domain;
+
+    Ok(MailboxInfoResponse {
+        mailbox,
+        local_domain,
+    })
+}
+
+fn quote_transfer_remote(
+    deps: Deps,
+    env: Env,
+    dest_domain: u32,
+    amount: Uint128,
+    recipient: HexBinary,
+) -> Result<QuoteTransferRemoteResponse, ContractError> {
+    let dispatch_msg = new_dispatch_msg(deps, dest_domain, recipient, amount, None, None)?;
+
+    let quote = mailbox::quote_dispatch(
+        &deps.querier,
+        MAILBOX.load(deps.storage)?,
+        env.contract.address,
+        dispatch_msg.clone(),
+    )?;
+
+    Ok(QuoteTransferRemoteResponse::new(&dispatch_msg, quote))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // routes deployed before decimal scaling track the decimals of their token
+    if DECIMALS.may_load(deps.storage)?.is_none() {
+        let token_info: TokenInfoResponse = deps
+            .querier
+            .query_wasm_smart(TOKEN.load(deps.storage)?, &Cw20QueryMsg::TokenInfo {})?;
+        DECIMALS.save(deps.storage, &token_info.decimals)?;
+    }
+
+    // routes enrolled before then were stored as bare addresses
+    migrate_routes(deps.storage, |domain, address: HexBinary| {
+        RemoteRouterConfig::from_legacy(&msg.remote_decimals, domain, address)
+    })?;
+
+    // routes deployed before pausing start released
+    if hpl_pausable::get_pause_info(deps.storage).is_err() {
+        hpl_pausable::initialize(deps.storage, &false)?;
+    }
+
+    Ok(Response::new())
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{
+        coin, from_json,
+        testing::{
+            mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+            MOCK_CONTRACT_ADDR,
+        },
+        Addr, Binary, Coin, ContractResult, Empty, OwnedDeps, SystemResult, Uint128, WasmQuery,
+    };
+    use hpl_interface::{
+        build_test_executor, build_test_querier,
+        connection::ConnectionMsg,
+        core::HandleMsg,
+        hook::QuoteDispatchResponse,
+        pausable::PausableMsg,
+        router::DomainRouteSet,
+        testing::padded,
+        warp::{
+            cw20::{Cw20ModeBridged, Cw20ModeCollateral},
+            payload::PayloadError,
+            RemoteRouterDecimals,
+        },
+    };
+    use hpl_router::{set_route, set_routes};
+    use ibcx_test_utils::{addr, gen_addr, gen_bz};
+    use rstest::{fixture, rstest};
+
+    use super::*;
+
+    build_test_querier!(super::query);
+    build_test_executor!(super::execute);
+
+    const DEPLOYER: &str = "sender";
+    const OWNER: &str = "owner";
+    const MAILBOX: &str = "mailbox";
+    const TOKEN: &str = "token";
+    const LOCAL_DOMAIN: u32 = 26657;
+
+    const CW20_BRIDGED_CODE_ID: u64 = 1;
+    const CW20_BRIDGED_NAME: &str = "cw20-created";
     const CW20_COLLATERAL_ADDRESS: &str = "cw20-exisiting";
+    const CW20_COLLATERAL_DECIMALS: u8 = 6;
 
     type Cw20TokenMode = TokenModeMsg<Cw20ModeBridged, Cw20ModeCollateral>;
     type TestDeps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
 
+    /// answers the collateral token info, the bridged token as uncapped,
+    /// the mailbox local domain and mailbox quotes with `fees`
+    fn mock_query(deps: &mut TestDeps, fees: Vec<Coin>) {
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == TOKEN => {
+                let res = match from_json(msg).unwrap() {
+                    Cw20QueryMsg::Minter {} => to_json_binary(&Some(MinterResponse {
+                        minter: MOCK_CONTRACT_ADDR.to_string(),
+                        cap: None,
+                    })),
+                    Cw20QueryMsg::TokenInfo {} => to_json_binary(&TokenInfoResponse {
+                        name: TOKEN.to_string(),
+                        symbol: TOKEN.to_string(),
+                        decimals: 6,
+                        total_supply: Uint128::zero(),
+                    }),
+                    _ => unreachable!("unexpected token query"),
+                };
+
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == CW20_COLLATERAL_ADDRESS => {
+                let res = TokenInfoResponse {
+                    name: CW20_COLLATERAL_ADDRESS.to_string(),
+                    symbol: CW20_COLLATERAL_ADDRESS.to_string(),
+                    decimals: CW20_COLLATERAL_DECIMALS,
+                    total_supply: Uint128::zero(),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == MAILBOX => {
+                let res = match from_json(msg).unwrap() {
+                    mailbox::QueryMsg::Hook(mailbox::MailboxHookQueryMsg::QuoteDispatch {
+                        ..
+                    }) => to_json_binary(&QuoteDispatchResponse { fees: fees.clone() }),
+                    mailbox::QueryMsg::Mailbox(mailbox::MailboxQueryMsg::LocalDomain {}) => {
+                        to_json_binary(&mailbox::LocalDomainResponse {
+                            local_domain: LOCAL_DOMAIN,
+                        })
+                    }
+                    _ => unreachable!("unexpected mailbox query"),
+                };
+
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            _ => unreachable!("unexpected query"),
+        });
+    }
+
     #[fixture]
     fn token_mode_bridged() -> Cw20TokenMode {
         TokenModeMsg::Bridged(Cw20ModeBridged {
@@ -313,6 +1113,7 @@ mod test {
                 marketing: None,
             }
             .into(),
+            salt: None,
         })
     }
 
@@ -332,6 +1133,8 @@ mod test {
     ) -> (TestDeps, Response) {
         let mut deps = mock_dependencies();
 
+        mock_query(&mut deps, vec![]);
+
         let res = instantiate(
             deps.as_mut(),
             mock_env(),
@@ -352,6 +1155,8 @@ mod test {
         }
 
         if !routes.is_empty() {
+            let decimals = super::DECIMALS.load(deps.as_ref().storage).unwrap();
+
             set_routes(
                 deps.as_mut().storage,
                 &addr(OWNER),
@@ -359,7 +1164,10 @@ mod test {
                     .into_iter()
                     .map(|v| DomainRouteSet {
                         domain: v.0,
-                        route: Some(v.1),
+                        route: Some(RemoteRouterConfig {
+                            address: v.1.into(),
+                            decimals,
+                        }),
                     })
                     .collect(),
             )
@@ -406,8 +1214,19 @@ mod test {
         assert_eq!(super::MODE.load(storage).unwrap(), mode);
         assert_eq!(super::MAILBOX.load(storage).unwrap(), MAILBOX);
 
+        let data: warp::InstantiateResponse = from_json(res.data.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            data,
+            warp::InstantiateResponse {
+                mailbox: addr(MAILBOX),
+                owner: addr(OWNER),
+            }
+        );
+
         match token_mode {
             TokenModeMsg::Bridged(mut v) => {
+                assert_eq!(super::DECIMALS.load(storage).unwrap(), v.init_msg.decimals);
+
                 v.init_msg.mint = Some(cw20::MinterResponse {
                     minter: mock_env().contract.address.into(),
                     cap: None,
@@ -415,7 +1234,7 @@ mod test {
 
                 assert!(!super::TOKEN.exists(storage));
 
-                let reply = res.messages.get(0).unwrap();
+                let reply = res.messages.first().unwrap();
                 assert_eq!(reply.id, REPLY_ID_CREATE_DENOM);
                 assert_eq!(
                     reply.msg,
@@ -429,6 +1248,10 @@ mod test {
                 )
             }
             TokenModeMsg::Collateral(v) => {
+                assert_eq!(
+                    super::DECIMALS.load(storage).unwrap(),
+                    CW20_COLLATERAL_DECIMALS
+                );
                 assert_eq!(super::TOKEN.load(storage).unwrap(), v.address);
                 assert!(res.messages.is_empty())
             }
@@ -440,10 +1263,11 @@ mod test {
     #[case(MAILBOX, 1, gen_bz(32), token_mode_collateral())]
     #[should_panic(expected = "unauthorized")]
     #[case(TOKEN, 1, gen_bz(32), token_mode_collateral())]
-    #[should_panic(expected = "route not found")]
+    #[should_panic(expected = "route not found for domain 2")]
     #[case(MAILBOX, 2, gen_bz(32), token_mode_collateral())]
     fn test_mailbox_handle(
         #[values("osmo", "neutron")] hrp: &str,
+        #[values(PayloadVersion::V0, PayloadVersion::V1)] version: PayloadVersion,
         #[case] sender: &str,
         #[case] domain: u32,
         #[case] route: HexBinary,
@@ -462,10 +1286,11 @@ mod test {
             metadata: HexBinary::default(),
         };
 
+        // every version is accepted, whatever the route sends itself
         let handle_msg = HandleMsg {
             origin: domain,
             sender: route,
-            body: warp_msg.clone().into(),
+            body: Payload::new(version, warp_msg.clone()).encode().unwrap(),
         };
 
         let res = test_execute(
@@ -474,7 +1299,7 @@ mod test {
             ExecuteMsg::Handle(handle_msg),
             vec![],
         );
-        let msg = &res.messages.get(0).unwrap().msg;
+        let msg = &res.messages.first().unwrap().msg;
 
         match token_mode {
             TokenModeMsg::Bridged(_) => {
@@ -511,7 +1336,7 @@ mod test {
     #[rstest]
     #[case(1, gen_bz(32), token_mode_bridged())]
     #[case(1, gen_bz(32), token_mode_collateral())]
-    #[should_panic(expected = "route not found")]
+    #[should_panic(expected = "route not found for domain 2")]
     #[case(2, gen_bz(32), token_mode_collateral())]
     fn test_transfer_remote(
         #[values("osmo", "neutron")] hrp: &str,
@@ -536,6 +1361,7 @@ mod test {
                 dest_domain: domain,
                 recipient: recipient.clone(),
                 amount: Uint128::new(100),
+                min_amount: None,
             },
             vec![],
         );
@@ -581,4 +1407,1279 @@ mod test {
             }
         }
     }
+
+    #[rstest]
+    #[case(1, 18, 100, 100_000_000_000_000)]
+    #[case(2, 2, 100_000_000, 10_000)]
+    #[should_panic(expected = "loses precision on remote")]
+    #[case(2, 2, 100_000_001, 0)]
+    fn test_transfer_remote_decimals(
+        #[case] domain: u32,
+        #[case] remote_decimals: u8,
+        #[case] amount: u128,
+        #[case] expected: u128,
+    ) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        let route = gen_bz(32);
+        let recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain,
+                route: Some(RemoteRouterConfig {
+                    address: route.clone().into(),
+                    decimals: remote_decimals,
+                }),
+            },
+        )
+        .unwrap();
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: domain,
+                recipient: recipient.clone(),
+                amount: Uint128::new(amount),
+                min_amount: None,
+            },
+            vec![],
+        );
+
+        let warp_msg = warp::Message {
+            recipient,
+            amount: Uint256::from_u128(expected),
+            metadata: HexBinary::default(),
+        };
+
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            mailbox::dispatch(MAILBOX, domain, route, warp_msg.into(), None, None, vec![]).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(90)]
+    #[should_panic(expected = "invalid min amount")]
+    #[case(101)]
+    fn test_transfer_remote_min_amount(#[case] min_amount: u128) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        let route = gen_bz(32);
+        let recipient = gen_bz(32);
+        let sender = gen_addr("osmo");
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: Some(RemoteRouterConfig {
+                    address: route.clone().into(),
+                    decimals: 18,
+                }),
+            },
+        )
+        .unwrap();
+
+        let res = test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: recipient.clone(),
+                amount: Uint128::new(100),
+                min_amount: Some(Uint128::new(min_amount)),
+            },
+            vec![],
+        );
+
+        // min amount is scaled to remote decimals along with the amount,
+        // and the sender is refunded below it
+        let warp_msg = warp::Message {
+            recipient,
+            amount: warp::scale_amount(100u128.into(), CW20_COLLATERAL_DECIMALS, 18).unwrap(),
+            metadata: HexBinary::default(),
+        }
+        .with_min_amount(
+            warp::scale_amount(min_amount.into(), CW20_COLLATERAL_DECIMALS, 18).unwrap(),
+        )
+        .with_refund_recipient(bech32_to_h256(sender.as_str()).unwrap());
+
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            mailbox::dispatch(MAILBOX, 1, route, warp_msg.into(), None, None, vec![]).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(1_000_000)]
+    #[should_panic(expected = "received amount is below min amount")]
+    #[case(1_000_001)]
+    fn test_mailbox_handle_min_amount(#[case] min_amount: u128) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        let route = gen_bz(32);
+        let recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: Some(RemoteRouterConfig {
+                    address: route.clone().into(),
+                    decimals: CW20_COLLATERAL_DECIMALS,
+                }),
+            },
+        )
+        .unwrap();
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: route,
+                body: warp::Message {
+                    recipient: recipient.clone(),
+                    amount: Uint256::from_u128(1_000_000),
+                    metadata: HexBinary::default(),
+                }
+                .with_min_amount(Uint256::from_u128(min_amount))
+                .into(),
+            }),
+            vec![],
+        );
+
+        assert_eq!(
+            to_json_binary(&res.messages.first().unwrap().msg).unwrap(),
+            to_json_binary(&CosmosMsg::<Empty>::Wasm(
+                conv::to_send_msg(
+                    TOKEN,
+                    bech32_encode("osmo", recipient.as_slice()).unwrap(),
+                    Uint256::from_u128(1_000_000)
+                )
+                .unwrap()
+            ))
+            .unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(vec![])]
+    #[should_panic(expected = "[E510] refund can't cover its hook fees")]
+    #[case(vec![coin(10, "uosmo")])]
+    fn test_mailbox_handle_min_amount_refund(#[case] fees: Vec<Coin>) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+        mock_query(&mut deps, fees);
+
+        let route = gen_bz(32);
+        let refund_recipient = [7u8; 32];
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: Some(RemoteRouterConfig {
+                    address: route.clone().into(),
+                    decimals: 18,
+                }),
+            },
+        )
+        .unwrap();
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: route.clone(),
+                body: warp::Message {
+                    recipient: gen_bz(32),
+                    amount: Uint256::from_u128(1_000_000),
+                    metadata: HexBinary::default(),
+                }
+                .with_min_amount(Uint256::from_u128(1_000_001))
+                .with_refund_recipient(refund_recipient)
+                .into(),
+            }),
+            vec![],
+        );
+
+        // nothing is released, the whole amount goes back in the origin decimals
+        let refund_msg = warp::Message {
+            recipient: refund_recipient.to_vec().into(),
+            amount: warp::scale_amount(1_000_000u128.into(), CW20_COLLATERAL_DECIMALS, 18).unwrap(),
+            metadata: HexBinary::default(),
+        };
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            mailbox::dispatch(MAILBOX, 1, route, refund_msg.into(), None, None, vec![]).unwrap()
+        );
+        assert_eq!(res.events[0].ty, "hpl_warp_cw20::refund");
+    }
+
+    #[rstest]
+    #[case(gen_bz(20))]
+    #[case(gen_bz(31))]
+    #[case(gen_bz(100))]
+    fn test_mailbox_handle_foreign_metadata(#[case] metadata: HexBinary) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        let route = gen_bz(32);
+        let recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: Some(RemoteRouterConfig {
+                    address: route.clone().into(),
+                    decimals: CW20_COLLATERAL_DECIMALS,
+                }),
+            },
+        )
+        .unwrap();
+
+        // metadata of other routers is delivered without a min amount or a call
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: route,
+                body: warp::Message {
+                    recipient: recipient.clone(),
+                    amount: Uint256::from_u128(1_000_000),
+                    metadata,
+                }
+                .into(),
+            }),
+            vec![],
+        );
+
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            to_json_binary(&res.messages[0].msg).unwrap(),
+            to_json_binary(&CosmosMsg::<Empty>::Wasm(
+                conv::to_send_msg(
+                    TOKEN,
+                    bech32_encode("osmo", recipient.as_slice()).unwrap(),
+                    Uint256::from_u128(1_000_000)
+                )
+                .unwrap()
+            ))
+            .unwrap()
+        );
+    }
+
+    /// body of the message dispatched by a transfer
+    fn dispatched_body(res: &Response) -> HexBinary {
+        let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = &res.messages.last().unwrap().msg
+        else {
+            unreachable!("expected a dispatch")
+        };
+
+        let mailbox::ExecuteMsg::Dispatch(dispatch) = from_json(msg).unwrap() else {
+            unreachable!("expected a dispatch")
+        };
+
+        dispatch.msg_body
+    }
+
+    #[rstest]
+    #[case(18)]
+    #[case(6)]
+    #[case(2)]
+    fn test_mailbox_handle_decimals(#[case] remote_decimals: u8) {
+        let (mut local, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+        let (mut remote, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        let remote_domain = 1;
+        let local_route = gen_bz(32);
+        let remote_route = gen_bz(32);
+        let recipient = gen_bz(32);
+
+        super::DECIMALS
+            .save(remote.as_mut().storage, &remote_decimals)
+            .unwrap();
+
+        for (deps, domain, address, decimals) in [
+            (&mut local, remote_domain, &remote_route, remote_decimals),
+            (
+                &mut remote,
+                LOCAL_DOMAIN,
+                &local_route,
+                CW20_COLLATERAL_DECIMALS,
+            ),
+        ] {
+            set_route(
+                deps.as_mut().storage,
+                &addr(OWNER),
+                DomainRouteSet {
+                    domain,
+                    route: Some(RemoteRouterConfig {
+                        address: address.clone().into(),
+                        decimals,
+                    }),
+                },
+            )
+            .unwrap();
+        }
+
+        // one whole token leaves the remote chain
+        let res = test_execute(
+            remote.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: LOCAL_DOMAIN,
+                recipient: recipient.clone(),
+                amount: Uint128::new(10u128.pow(remote_decimals.into())),
+                min_amount: None,
+            },
+            vec![],
+        );
+
+        let res = test_execute(
+            local.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: remote_domain,
+                sender: remote_route,
+                body: dispatched_body(&res),
+            }),
+            vec![],
+        );
+
+        // and one whole token arrives in local decimals
+        assert_eq!(
+            to_json_binary(&res.messages.first().unwrap().msg).unwrap(),
+            to_json_binary(&CosmosMsg::<Empty>::Wasm(
+                conv::to_send_msg(
+                    TOKEN,
+                    bech32_encode("osmo", recipient.as_slice()).unwrap(),
+                    Uint256::from_u128(10u128.pow(CW20_COLLATERAL_DECIMALS.into()))
+                )
+                .unwrap()
+            ))
+            .unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(vec![RemoteRouterDecimals { domain: 1, decimals: 18 }])]
+    #[should_panic(expected = "decimals of the remote router on domain 1 are required")]
+    #[case(vec![])]
+    #[should_panic(expected = "decimals of the remote router on domain 1 are required")]
+    #[case(vec![RemoteRouterDecimals { domain: 2, decimals: 18 }])]
+    fn test_migrate_legacy_routes(#[case] remote_decimals: Vec<RemoteRouterDecimals>) {
+        let (mut deps, _) = deps(
+            vec![],
+            "osmo",
+            Some(CW20_COLLATERAL_ADDRESS),
+            token_mode_collateral(),
+        );
+
+        let route = gen_bz(32);
+
+        // routes and decimals as stored before decimal scaling
+        super::DECIMALS.remove(deps.as_mut().storage);
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: Some(route.clone()),
+            },
+        )
+        .unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg { remote_decimals })
+            .map_err(|e| e.to_string())
+            .unwrap();
+
+        assert_eq!(
+            super::DECIMALS.load(deps.as_ref().storage).unwrap(),
+            CW20_COLLATERAL_DECIMALS
+        );
+        assert_eq!(
+            get_route::<RemoteRouterConfig>(deps.as_ref().storage, 1)
+                .unwrap()
+                .route,
+            Some(RemoteRouterConfig {
+                address: route.into(),
+                decimals: 18,
+            })
+        );
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(Binary::from(b"{\"deposit\":{}}".to_vec())))]
+    fn test_mailbox_handle_call(#[case] call: Option<Binary>) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        let route = gen_bz(32);
+        let recipient = gen_bz(32);
+        let recipient_addr = bech32_encode("osmo", recipient.as_slice()).unwrap();
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: Some(RemoteRouterConfig {
+                    address: route.clone().into(),
+                    decimals: CW20_COLLATERAL_DECIMALS,
+                }),
+            },
+        )
+        .unwrap();
+
+        let token_msg = warp::Message {
+            recipient,
+            amount: Uint256::from_u128(1_000_000),
+            metadata: HexBinary::default(),
+        };
+        let mut payload = Payload::new(PayloadVersion::V1, token_msg);
+        if let Some(call) = call.clone() {
+            payload = payload.with_call(call).unwrap();
+        }
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: route.clone(),
+                body: payload.encode().unwrap(),
+            }),
+            vec![],
+        );
+        let msgs: Vec<_> = res.messages.into_iter().map(|v| v.msg).collect();
+
+        let mut expected: Vec<CosmosMsg> =
+            vec![
+                conv::to_send_msg(TOKEN, &recipient_addr, Uint256::from_u128(1_000_000))
+                    .unwrap()
+                    .into(),
+            ];
+        if let Some(call) = call {
+            // delivered through `HandleCall`, never as a raw execute
+            let call = HandleCallMsg {
+                origin: 1,
+                sender: route,
+                amount: Uint256::from_u128(1_000_000),
+                call,
+            };
+            expected.push(
+                wasm_execute(&recipient_addr, &call.wrap(), vec![])
+                    .unwrap()
+                    .into(),
+            );
+        }
+
+        assert_eq!(msgs, expected);
+    }
+
+    #[rstest]
+    #[case::token("token")]
+    #[case::mailbox("mailbox")]
+    #[case::route("route")]
+    fn test_mailbox_handle_call_recipient(#[case] target: &str) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        let route = gen_bz(32);
+        let recipient = gen_bz(32);
+        let recipient_addr = bech32_encode("osmo", recipient.as_slice()).unwrap();
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: Some(RemoteRouterConfig {
+                    address: route.clone().into(),
+                    decimals: CW20_COLLATERAL_DECIMALS,
+                }),
+            },
+        )
+        .unwrap();
+
+        // the recipient is one of the contracts the route has authority over
+        let mut env = mock_env();
+        match target {
+            "token" => super::TOKEN
+                .save(deps.as_mut().storage, &recipient_addr)
+                .unwrap(),
+            "mailbox" => super::MAILBOX
+                .save(deps.as_mut().storage, &recipient_addr)
+                .unwrap(),
+            _ => env.contract.address = recipient_addr.clone(),
+        }
+        let mailbox = super::MAILBOX.load(deps.as_ref().storage).unwrap();
+
+        let token_msg = warp::Message {
+            recipient,
+            amount: Uint256::from_u128(1_000_000),
+            metadata: HexBinary::default(),
+        };
+        let payload = Payload::new(PayloadVersion::V1, token_msg);
+        let handle = |payload: Payload| {
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: route.clone(),
+                body: payload.encode().unwrap(),
+            })
+        };
+
+        let call = Binary::from(b"{\"mint\":{}}".to_vec());
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(mailbox.as_str(), &[]),
+            handle(payload.clone().with_call(call).unwrap()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidCallRecipient {
+                recipient: recipient_addr.to_string()
+            }
+        );
+
+        // plain transfers aren't affected
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(mailbox.as_str(), &[]),
+            handle(payload),
+        )
+        .unwrap();
+    }
+
+    #[rstest]
+    #[should_panic(expected = "burn from is only supported in bridged mode")]
+    fn test_transfer_remote_burn_collateral() {
+        let (mut deps, _) = deps(
+            vec![(1, gen_bz(32))],
+            "osmo",
+            Some(TOKEN),
+            token_mode_collateral(),
+        );
+
+        test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemoteBurn {
+                dest_domain: 1,
+                recipient: gen_bz(32),
+                amount: Uint128::new(100),
+                min_amount: None,
+            },
+            vec![],
+        );
+    }
+
+    #[rstest]
+    #[case(
+        addr(OWNER),
+        RemoteAddress::Hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".into())
+    )]
+    #[case(
+        addr(OWNER),
+        RemoteAddress::Base64(Binary::from_base64("Wq62BT8+lMm5oJ8zZpQ15+8b6u0=").unwrap())
+    )]
+    #[case(
+        addr(OWNER),
+        RemoteAddress::Bech32("osmo1t2htvpfl862vnwdqnuekd9p4ulh3h6hdlvdhh0".into())
+    )]
+    #[should_panic(expected = "unauthorized")]
+    #[case(
+        addr(DEPLOYER),
+        RemoteAddress::Hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".into())
+    )]
+    fn test_enroll_remote_router(#[case] sender: Addr, #[case] address: RemoteAddress) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::EnrollRemoteRouter {
+                domain: 1,
+                address,
+                decimals: 18,
+            },
+            vec![],
+        );
+
+        // every format is stored as the same padded bytes
+        let route = get_route::<RemoteRouterConfig>(deps.as_ref().storage, 1)
+            .unwrap()
+            .route
+            .unwrap();
+        assert_eq!(
+            route.address.to_hex(),
+            "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        );
+        assert_eq!(route.decimals, 18);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(DEPLOYER))]
+    fn test_set_recipient_format(#[case] sender: Addr) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetRecipientFormat {
+                domain: 1,
+                format: RecipientFormat::Cosmos32,
+            },
+            vec![],
+        );
+
+        let res: warp::RecipientFormatResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RecipientFormat { domain: 1 }),
+        );
+        assert_eq!(res.format, RecipientFormat::Cosmos32);
+
+        // other domains are not validated by default
+        let res: warp::RecipientFormatResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RecipientFormat { domain: 2 }),
+        );
+        assert_eq!(res.format, RecipientFormat::None);
+    }
+
+    #[rstest]
+    #[case(None, gen_bz(20))]
+    #[case(Some(RecipientFormat::Evm20), padded(12, &[1u8; 20]))]
+    #[should_panic(expected = "invalid recipient format for domain 1. expected: evm20")]
+    #[case(Some(RecipientFormat::Evm20), gen_bz(32))]
+    #[case(Some(RecipientFormat::Cosmos32), gen_bz(32))]
+    #[should_panic(expected = "invalid recipient format for domain 1. expected: cosmos32")]
+    #[case(Some(RecipientFormat::Cosmos32), padded(32, &[]))]
+    #[case(Some(RecipientFormat::Raw32), padded(32, &[]))]
+    #[should_panic(expected = "invalid recipient format for domain 1. expected: raw32")]
+    #[case(Some(RecipientFormat::Raw32), gen_bz(33))]
+    fn test_transfer_remote_recipient_format(
+        #[case] format: Option<RecipientFormat>,
+        #[case] recipient: HexBinary,
+    ) {
+        let (mut deps, _) = deps(
+            vec![(1, gen_bz(32))],
+            "osmo",
+            Some(TOKEN),
+            token_mode_collateral(),
+        );
+
+        if let Some(format) = format {
+            test_execute(
+                deps.as_mut(),
+                &addr(OWNER),
+                ExecuteMsg::SetRecipientFormat { domain: 1, format },
+                vec![],
+            );
+        }
+
+        test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient,
+                amount: Uint128::new(100),
+                min_amount: None,
+            },
+            vec![],
+        );
+    }
+
+    #[rstest]
+    #[case(1, vec![coin(10, "ugas")])]
+    #[should_panic(expected = "gas payment required for domain 1")]
+    #[case(1, vec![])]
+    #[should_panic(expected = "gas payment required for domain 1")]
+    #[case(1, vec![coin(0, "ugas")])]
+    #[case(2, vec![])]
+    fn test_transfer_remote_gas_payment(#[case] dest_domain: u32, #[case] funds: Vec<Coin>) {
+        let (mut deps, _) = deps(
+            vec![(1, gen_bz(32)), (2, gen_bz(32))],
+            "osmo",
+            Some(TOKEN),
+            token_mode_collateral(),
+        );
+
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetGasPaymentRequired {
+                domain: 1,
+                required: true,
+            },
+            vec![],
+        );
+
+        for (domain, expected) in [(1, true), (2, false)] {
+            let res: warp::GasPaymentRequiredResponse = test_query(
+                deps.as_ref(),
+                QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::GasPaymentRequired {
+                    domain,
+                }),
+            );
+            assert_eq!(res.required, expected);
+        }
+
+        test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain,
+                recipient: gen_bz(32),
+                amount: Uint128::new(100),
+                min_amount: None,
+            },
+            funds,
+        );
+    }
+
+    #[rstest]
+    #[case(vec![coin(10, "ugas")])]
+    #[case(vec![coin(15, "ugas"), coin(3, "uatom")])]
+    #[should_panic(expected = "insufficient hook payment")]
+    #[case(vec![])]
+    #[should_panic(expected = "insufficient hook payment")]
+    #[case(vec![coin(9, "ugas")])]
+    fn test_transfer_remote_hook_payment(#[case] funds: Vec<Coin>) {
+        let route = gen_bz(32);
+        let (mut deps, _) = deps(
+            vec![(1, route.clone())],
+            "osmo",
+            Some(TOKEN),
+            token_mode_collateral(),
+        );
+
+        mock_query(&mut deps, vec![coin(10, "ugas")]);
+
+        let recipient = gen_bz(32);
+        let res = test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: recipient.clone(),
+                amount: Uint128::new(100),
+                min_amount: None,
+            },
+            funds.clone(),
+        );
+
+        let warp_msg = warp::Message {
+            recipient,
+            amount: Uint256::from_u128(100),
+            metadata: HexBinary::default(),
+        };
+
+        // every attached coin, surplus included, is forwarded with the dispatch
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            mailbox::dispatch(MAILBOX, 1, route, warp_msg.into(), None, None, funds).unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("someone"))]
+    fn test_transfer_remote_default_gas(#[case] sender: Addr) {
+        let routes = [gen_bz(32), gen_bz(32)];
+        let (mut deps, _) = deps(
+            vec![(1, routes[0].clone()), (2, routes[1].clone())],
+            "osmo",
+            Some(TOKEN),
+            token_mode_collateral(),
+        );
+
+        mock_query(&mut deps, vec![]);
+
+        test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetDefaultGas {
+                domain: 1,
+                gas: Some(300_000),
+            },
+            vec![],
+        );
+
+        // only transfers to domain 1 carry the gas limit
+        let metadata: HexBinary = IGPMetadata {
+            gas_limit: Uint256::from_u128(300_000),
+            refund_address: HexBinary::default(),
+        }
+        .into();
+        let cases = [
+            (1, routes[0].clone(), Some(metadata)),
+            (2, routes[1].clone(), None),
+        ];
+
+        for (domain, route, metadata) in cases {
+            let recipient = gen_bz(32);
+            let res = test_execute(
+                deps.as_mut(),
+                &addr("sender"),
+                ExecuteMsg::TransferRemote {
+                    dest_domain: domain,
+                    recipient: recipient.clone(),
+                    amount: Uint128::new(100),
+                    min_amount: None,
+                },
+                vec![],
+            );
+
+            let warp_msg = warp::Message {
+                recipient,
+                amount: Uint256::from_u128(100),
+                metadata: HexBinary::default(),
+            };
+
+            assert_eq!(
+                res.messages.last().unwrap().msg,
+                mailbox::dispatch(
+                    MAILBOX,
+                    domain,
+                    route,
+                    warp_msg.into(),
+                    None,
+                    metadata,
+                    vec![]
+                )
+                .unwrap()
+            );
+        }
+    }
+
+    #[rstest]
+    fn test_quote_transfer_remote() {
+        let route = gen_bz(32);
+        let (mut deps, _) = deps(
+            vec![(1, route.clone())],
+            "osmo",
+            Some(TOKEN),
+            token_mode_collateral(),
+        );
+
+        mock_query(&mut deps, vec![coin(10, "ugas")]);
+
+        let res: warp::QuoteTransferRemoteResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::QuoteTransferRemote {
+                dest_domain: 1,
+                amount: Uint128::new(100),
+                recipient: gen_bz(32),
+            }),
+        );
+
+        assert_eq!(res.gas_amount, None);
+        assert_eq!(res.fees, vec![coin(10, "ugas")]);
+    }
+
+    #[test]
+    fn test_mailbox_info() {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        mock_query(&mut deps, vec![]);
+
+        let res: warp::MailboxInfoResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::MailboxInfo {}),
+        );
+        assert_eq!(res.mailbox, addr(MAILBOX));
+        assert_eq!(res.local_domain, LOCAL_DOMAIN);
+    }
+
+    #[test]
+    fn test_ism() {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        let res: InterchainSecurityModuleResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::Ism {}),
+        );
+        assert_eq!(res.ism, None);
+
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::Connection(ConnectionMsg::SetIsm {
+                ism: "ism".to_string(),
+            }),
+            vec![],
+        );
+
+        let res: InterchainSecurityModuleResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::Ism {}),
+        );
+        assert_eq!(res.ism, Some(addr("ism")));
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("someone"))]
+    fn test_router_decoded(#[case] sender: Addr) {
+        let evm_route = HexBinary::from_hex("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+        let cosmos_route = gen_bz(32);
+        let (mut deps, _) = deps(
+            vec![(1, evm_route.clone()), (2, cosmos_route.clone())],
+            "osmo",
+            Some(TOKEN),
+            token_mode_collateral(),
+        );
+
+        let res = test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetDomainHrp {
+                domain: 2,
+                hrp: Some("neutron".to_string()),
+            },
+            vec![],
+        );
+        assert_eq!(
+            res.events,
+            vec![new_event("set-domain-hrp")
+                .add_attribute("sender", sender.as_str())
+                .add_attribute("domain", "2")
+                .add_attribute("hrp", "neutron")]
+        );
+
+        let res: RouteResponse<DecodedRoute> = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RouterDecoded { domain: 1 }),
+        );
+        let route = res.route.route.unwrap();
+        assert_eq!(
+            route.evm.as_deref(),
+            Some("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+        );
+        assert_eq!(route.bech32, None);
+
+        let res: RoutesResponse<DecodedRoute> = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RoutersDecoded {}),
+        );
+        assert_eq!(res.routes.len(), 2);
+        assert_eq!(res.routes[1].domain, 2);
+        assert_eq!(
+            res.routes[1].route,
+            Some(DecodedRoute::new(&cosmos_route, Some("neutron")).unwrap())
+        );
+
+        // without an hrp the address is only given as bytes
+        test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetDomainHrp {
+                domain: 2,
+                hrp: None,
+            },
+            vec![],
+        );
+
+        let res: RouteResponse<DecodedRoute> = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RouterDecoded { domain: 2 }),
+        );
+        assert_eq!(
+            res.route.route,
+            Some(DecodedRoute {
+                address: cosmos_route,
+                bech32: None,
+                evm: None,
+            })
+        );
+
+        let res: RouteResponse<DecodedRoute> = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RouterDecoded { domain: 3 }),
+        );
+        assert_eq!(res.route.route, None);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "invalid")]
+    fn test_set_domain_hrp_invalid() {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetDomainHrp {
+                domain: 2,
+                hrp: Some(String::default()),
+            },
+            vec![],
+        );
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("someone"))]
+    fn test_set_payload_version(#[case] sender: Addr) {
+        let route = gen_bz(32);
+        let (mut deps, _) = deps(
+            vec![(1, route.clone())],
+            "osmo",
+            Some(TOKEN),
+            token_mode_collateral(),
+        );
+
+        let res: warp::PayloadVersionResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::PayloadVersion {}),
+        );
+        assert_eq!(res.version, PayloadVersion::V0);
+
+        let res = test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetPayloadVersion {
+                version: PayloadVersion::V1,
+            },
+            vec![],
+        );
+        assert_eq!(
+            res.events,
+            vec![new_event("set-payload-version")
+                .add_attribute("sender", sender.as_str())
+                .add_attribute("version", "v1")]
+        );
+
+        let res: warp::PayloadVersionResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::PayloadVersion {}),
+        );
+        assert_eq!(res.version, PayloadVersion::V1);
+
+        let recipient = gen_bz(32);
+        let res = test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: recipient.clone(),
+                amount: Uint128::new(100),
+                min_amount: None,
+            },
+            vec![],
+        );
+
+        let warp_msg = warp::Message {
+            recipient,
+            amount: Uint256::from_u128(100),
+            metadata: HexBinary::default(),
+        };
+        let body = Payload::new(PayloadVersion::V1, warp_msg).encode().unwrap();
+        let dispatch_msg = mailbox::dispatch(MAILBOX, 1, route, body, None, None, vec![]).unwrap();
+
+        assert_eq!(res.messages.last().unwrap().msg, dispatch_msg);
+    }
+
+    #[test]
+    fn test_mailbox_handle_invalid_payload() {
+        let route = gen_bz(32);
+        let (mut deps, _) = deps(
+            vec![(1, route.clone())],
+            "osmo",
+            Some(TOKEN),
+            token_mode_collateral(),
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MAILBOX, &[]),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: route,
+                body: gen_bz(63),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, PayloadError::TooShort { len: 63 }.into());
+    }
+
+    #[rstest]
+    fn test_mint_capacity_collateral() {
+        let (deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        let err = get_mint_capacity(deps.as_ref()).unwrap_err();
+        assert_eq!(err, ContractError::MintCapacityNotBridged);
+    }
+
+    #[rstest]
+    #[case(
+        ContractError::GasPaymentRequired { domain: 1 },
+        501,
+        "[E501] gas payment required for domain 1"
+    )]
+    #[case(
+        PayloadError::TooShort { len: 63 }.into(),
+        508,
+        "[E508] invalid payload. payload too short. length: 63"
+    )]
+    #[case(
+        ContractError::InvalidCallRecipient {
+            recipient: "osmo1mailbox".to_string()
+        },
+        509,
+        "[E509] calls can't be sent to osmo1mailbox"
+    )]
+    #[case(
+        ContractError::RefundFeeNotCovered {
+            fees: vec![coin(10, "uosmo")],
+            refund: Uint256::from_u128(100),
+        },
+        510,
+        "[E510] refund can't cover its hook fees. fees: [Coin { 10 \"uosmo\" }], refund: 100"
+    )]
+    #[case(
+        ContractError::BurnFromNotBridged,
+        522,
+        "[E522] burn from is only supported in bridged mode"
+    )]
+    #[case(
+        ContractError::MintCapExceeded {
+            requested: Uint128::new(401),
+            remaining: Uint128::new(400)
+        },
+        524,
+        "[E524] mint cap exceeded. requested: 401, remaining: 400"
+    )]
+    #[case(
+        HplError::RouteNotFound { domain: 1 }.into(),
+        17,
+        "[E017] route not found for domain 1"
+    )]
+    fn test_error_codes(#[case] err: ContractError, #[case] code: u16, #[case] golden: &str) {
+        assert_eq!(err.code(), code);
+        assert_eq!(err.to_string(), golden);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("someone"))]
+    fn test_set_inbound_paused(#[case] sender: Addr) {
+        let (mut deps, _) = deps(vec![], "osmo", Some(TOKEN), token_mode_collateral());
+
+        for paused in [true, false] {
+            let res = test_execute(
+                deps.as_mut(),
+                &sender,
+                ExecuteMsg::SetInboundPaused { paused },
+                vec![],
+            );
+            assert_eq!(
+                res.events,
+                vec![new_event("set-inbound-paused")
+                    .add_attribute("sender", sender.as_str())
+                    .add_attribute("paused", paused.to_string())]
+            );
+
+            let res: PauseStateResponse = test_query(
+                deps.as_ref(),
+                QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::PauseState {}),
+            );
+            assert!(!res.outbound);
+            assert_eq!(res.inbound, paused);
+        }
+    }
+
+    #[rstest]
+    fn test_pause(#[values(false, true)] outbound: bool, #[values(false, true)] inbound: bool) {
+        let router = gen_bz(32);
+        let (mut deps, _) = deps(
+            vec![(1, router.clone())],
+            "osmo",
+            Some(TOKEN),
+            token_mode_bridged(),
+        );
+
+        if outbound {
+            let res = test_execute(
+                deps.as_mut(),
+                &addr(OWNER),
+                ExecuteMsg::Pausable(PausableMsg::Pause {}),
+                vec![],
+            );
+            assert_eq!(res.events[0].ty, "hpl_pausable::pause");
+        }
+        if inbound {
+            test_execute(
+                deps.as_mut(),
+                &addr(OWNER),
+                ExecuteMsg::SetInboundPaused { paused: true },
+                vec![],
+            );
+        }
+
+        let res: PauseStateResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::PauseState {}),
+        );
+        assert_eq!(res, PauseStateResponse { outbound, inbound });
+
+        let transfer_msgs = [
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: gen_bz(32),
+                amount: Uint128::new(100),
+                min_amount: None,
+            },
+            ExecuteMsg::TransferRemoteBurn {
+                dest_domain: 1,
+                recipient: gen_bz(32),
+                amount: Uint128::new(100),
+                min_amount: None,
+            },
+        ];
+        for msg in transfer_msgs {
+            let res = execute(deps.as_mut(), mock_env(), mock_info("sender", &[]), msg);
+            assert_eq!(res.err(), outbound.then_some(ContractError::OutboundPaused));
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MAILBOX, &[]),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: router,
+                body: warp::Message {
+                    recipient: gen_bz(32),
+                    amount: Uint256::from_u128(100),
+                    metadata: HexBinary::default(),
+                }
+                .into(),
+            }),
+        );
+        assert_eq!(res.err(), inbound.then_some(ContractError::InboundPaused));
+    }
 }
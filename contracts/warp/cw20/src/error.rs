@@ -1,29 +1,102 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, Uint128, Uint256};
+use hpl_error::HplError;
+use hpl_interface::warp::{payload::PayloadError, RecipientFormat};
 use thiserror::Error;
 
 #[derive(Error, PartialEq, Debug)]
 pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
+    #[error(transparent)]
+    Hpl(#[from] HplError),
 
-    #[error("{0}")]
-    ParseReplyError(#[from] cw_utils::ParseReplyError),
+    #[error("[E500] invalid recipient format for domain {domain}. expected: {expected}")]
+    InvalidRecipientFormat {
+        domain: u32,
+        expected: RecipientFormat,
+    },
 
-    #[error("unauthorized")]
-    Unauthorized,
+    #[error("[E501] gas payment required for domain {domain}")]
+    GasPaymentRequired { domain: u32 },
 
-    #[error("wrong length")]
-    WrongLength {},
+    #[error("[E502] insufficient hook payment: required {required:?}, provided {provided:?}")]
+    InsufficientHookPayment {
+        required: Vec<Coin>,
+        provided: Vec<Coin>,
+    },
 
-    #[error("invalid token option")]
-    InvalidTokenOption,
+    #[error("[E503] invalid min amount. amount: {amount}, min_amount: {min_amount}")]
+    InvalidMinAmount {
+        amount: Uint128,
+        min_amount: Uint128,
+    },
+
+    #[error(
+        "[E504] received amount is below min amount. amount: {amount}, min_amount: {min_amount}"
+    )]
+    BelowMinAmount {
+        amount: Uint256,
+        min_amount: Uint256,
+    },
+
+    #[error("[E506] outbound transfers are paused")]
+    OutboundPaused,
+
+    #[error("[E507] inbound transfers are paused")]
+    InboundPaused,
+
+    #[error("[E508] invalid payload. {0}")]
+    InvalidPayload(#[from] PayloadError),
+
+    #[error("[E509] calls can't be sent to {recipient}")]
+    InvalidCallRecipient { recipient: String },
+
+    /// Hook fees of a refund can't be paid out of the refunded cw20 tokens
+    #[error("[E510] refund can't cover its hook fees. fees: {fees:?}, refund: {refund}")]
+    RefundFeeNotCovered { fees: Vec<Coin>, refund: Uint256 },
 
-    #[error("invalid reply id")]
-    InvalidReplyId,
+    #[error("[E520] invalid token option")]
+    InvalidTokenOption,
 
-    #[error("invalid receive msg")]
+    #[error("[E521] invalid receive msg")]
     InvalidReceiveMsg,
 
-    #[error("no router for domain {domain:?}")]
-    NoRouter { domain: u32 },
+    #[error("[E522] burn from is only supported in bridged mode")]
+    BurnFromNotBridged,
+
+    #[error("[E523] expected token address is only supported in bridged mode")]
+    ExpectedTokenAddressNotBridged,
+
+    #[error("[E524] mint cap exceeded. requested: {requested}, remaining: {remaining}")]
+    MintCapExceeded {
+        requested: Uint128,
+        remaining: Uint128,
+    },
+
+    #[error("[E525] mint capacity is only supported in bridged mode")]
+    MintCapacityNotBridged,
+}
+
+hpl_error::impl_from_hpl!(ContractError);
+
+impl ContractError {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Hpl(err) => err.code(),
+            Self::InvalidRecipientFormat { .. } => 500,
+            Self::GasPaymentRequired { .. } => 501,
+            Self::InsufficientHookPayment { .. } => 502,
+            Self::InvalidMinAmount { .. } => 503,
+            Self::BelowMinAmount { .. } => 504,
+            Self::OutboundPaused => 506,
+            Self::InboundPaused => 507,
+            Self::InvalidPayload(_) => 508,
+            Self::InvalidCallRecipient { .. } => 509,
+            Self::RefundFeeNotCovered { .. } => 510,
+            Self::InvalidTokenOption => 520,
+            Self::InvalidReceiveMsg => 521,
+            Self::BurnFromNotBridged => 522,
+            Self::ExpectedTokenAddressNotBridged => 523,
+            Self::MintCapExceeded { .. } => 524,
+            Self::MintCapacityNotBridged => 525,
+        }
+    }
 }
@@ -1,13 +1,17 @@
-use cosmwasm_std::{Addr, Event};
-use cw_storage_plus::Item;
-use hpl_interface::warp::TokenMode;
+use cosmwasm_std::{Addr, CosmosMsg, Event};
+use cw_storage_plus::{Item, Map};
+use hpl_interface::warp::{payload::PayloadVersion, RecipientFormat, TokenMode};
 
 pub mod contract;
 mod conv;
 pub mod error;
 
+#[cfg(test)]
+mod multitest;
+
 // reply message
 pub const REPLY_ID_CREATE_DENOM: u64 = 0;
+pub const REPLY_ID_BURN_FROM: u64 = 1;
 
 // version info for migration info
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -17,6 +21,14 @@ pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const TOKEN_KEY: &str = "token";
 const TOKEN: Item<Addr> = Item::new(TOKEN_KEY);
 
+// storage definition for code id of the token created in bridged mode
+const TOKEN_CODE_ID_KEY: &str = "token_code_id";
+const TOKEN_CODE_ID: Item<u64> = Item::new(TOKEN_CODE_ID_KEY);
+
+// storage definition for token decimals
+const DECIMALS_KEY: &str = "decimals";
+const DECIMALS: Item<u8> = Item::new(DECIMALS_KEY);
+
 // storage definition for token mode
 const MODE_KEY: &str = "mode";
 const MODE: Item<TokenMode> = Item::new(MODE_KEY);
@@ -25,10 +37,38 @@ const MODE: Item<TokenMode> = Item::new(MODE_KEY);
 const HRP_KEY: &str = "hrp";
 const HRP: Item<String> = Item::new(HRP_KEY);
 
+// storage definition for recipient format of destination domains
+const RECIPIENT_FORMATS_PREFIX: &str = "recipient_formats";
+const RECIPIENT_FORMATS: Map<u32, RecipientFormat> = Map::new(RECIPIENT_FORMATS_PREFIX);
+
+// storage definition for gas payment requirement of destination domains
+const REQUIRE_GAS_PAYMENT_PREFIX: &str = "require_gas_payment";
+const REQUIRE_GAS_PAYMENT: Map<u32, bool> = Map::new(REQUIRE_GAS_PAYMENT_PREFIX);
+
+// storage definition for bech32 prefixes of cosmos domains, only used to decode routers
+const DOMAIN_HRPS_PREFIX: &str = "domain_hrps";
+const DOMAIN_HRPS: Map<u32, String> = Map::new(DOMAIN_HRPS_PREFIX);
+
+// storage definition for igp gas limit of destination domains
+const DEFAULT_GAS_LIMITS_PREFIX: &str = "default_gas_limits";
+const DEFAULT_GAS_LIMITS: Map<u32, u64> = Map::new(DEFAULT_GAS_LIMITS_PREFIX);
+
+// storage definition for pausing inbound transfers, outbound transfers use hpl-pausable
+const INBOUND_PAUSED_KEY: &str = "inbound_paused";
+const INBOUND_PAUSED: Item<bool> = Item::new(INBOUND_PAUSED_KEY);
+
+// storage definition for payload version of outbound transfers
+const PAYLOAD_VERSION_KEY: &str = "payload_version";
+const PAYLOAD_VERSION: Item<PayloadVersion> = Item::new(PAYLOAD_VERSION_KEY);
+
 // storage definition for mailbox
 const MAILBOX_KEY: &str = "mailbox";
 const MAILBOX: Item<Addr> = Item::new(MAILBOX_KEY);
 
+// storage definition for mailbox dispatch waiting on the burn from reply
+const PENDING_DISPATCH_KEY: &str = "pending_dispatch";
+const PENDING_DISPATCH: Item<CosmosMsg> = Item::new(PENDING_DISPATCH_KEY);
+
 fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_warp_cw20::{name}"))
 }
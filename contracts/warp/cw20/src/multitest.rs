@@ -0,0 +1,430 @@
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, Event, HexBinary, MessageInfo,
+    Response, StdResult, Uint128, Uint256,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse, TokenInfoResponse};
+use cw_multi_test::{
+    addons::{MockAddressGenerator, MockApiBech32},
+    App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper, Executor, WasmKeeper,
+};
+use hpl_interface::{
+    core::{mailbox, HandleMsg},
+    hook::QuoteDispatchResponse,
+    router::{DomainRouteSet, RouterMsg},
+    types::bech32_decode,
+    warp::{
+        self,
+        cw20::{
+            Cw20ModeBridged, ExecuteMsg, ExpectedTokenAddressResponse, InstantiateMsg,
+            MintCapacityResponse, QueryMsg, TokenCw20QueryMsg,
+        },
+        payload::{Payload, PayloadVersion},
+        RemoteRouterConfig, TokenModeMsg, TokenType, TokenTypeResponse, TokenWarpDefaultQueryMsg,
+    },
+};
+use ibcx_test_utils::gen_bz;
+
+use crate::contract::{execute, instantiate, query, reply};
+
+type TestApp = App<BankKeeper, MockApiBech32>;
+
+const HRP: &str = "osmo";
+const DEST_DOMAIN: u32 = 1;
+
+fn mock_mailbox_execute(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: mailbox::ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        mailbox::ExecuteMsg::Dispatch(msg) => Ok(Response::new().add_event(
+            Event::new("mock_dispatch").add_attribute("dest_domain", msg.dest_domain.to_string()),
+        )),
+        _ => unreachable!("unexpected mailbox msg"),
+    }
+}
+
+fn mock_mailbox_instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: Empty,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn mock_mailbox_query(_deps: Deps, _env: Env, msg: mailbox::QueryMsg) -> StdResult<Binary> {
+    match msg {
+        mailbox::QueryMsg::Hook(mailbox::MailboxHookQueryMsg::QuoteDispatch { .. }) => {
+            to_json_binary(&QuoteDispatchResponse { fees: vec![] })
+        }
+        _ => unreachable!("unexpected mailbox query"),
+    }
+}
+
+fn mock_mailbox() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        mock_mailbox_execute,
+        mock_mailbox_instantiate,
+        mock_mailbox_query,
+    ))
+}
+
+fn cw20() -> Box<dyn Contract<Empty>> {
+    use cw20_base::contract::{execute, instantiate, query};
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+fn warp_cw20() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(execute, instantiate, query).with_reply(reply))
+}
+
+struct Suite {
+    app: TestApp,
+    user: Addr,
+    mailbox: Addr,
+    route: HexBinary,
+    warp: Addr,
+    token: Addr,
+}
+
+/// deploys a bridged warp route and mints `balance` synthetic tokens to the user
+fn setup(balance: u128, salt: Option<HexBinary>) -> anyhow::Result<Suite> {
+    setup_capped(balance, salt, None)
+}
+
+/// like `setup`, with the synthetic token capped at `cap`
+fn setup_capped(
+    balance: u128,
+    salt: Option<HexBinary>,
+    cap: Option<u128>,
+) -> anyhow::Result<Suite> {
+    let mut app = AppBuilder::default()
+        .with_api(MockApiBech32::new(HRP))
+        .with_wasm(WasmKeeper::default().with_address_generator(MockAddressGenerator))
+        .build(|_, _, _| {});
+
+    let owner = app.api().addr_make("owner");
+    let user = app.api().addr_make("user");
+
+    let mailbox_code_id = app.store_code(mock_mailbox());
+    let mailbox = app.instantiate_contract(
+        mailbox_code_id,
+        owner.clone(),
+        &Empty {},
+        &[],
+        "mailbox",
+        None,
+    )?;
+
+    let cw20_code_id = app.store_code(cw20());
+    let warp_code_id = app.store_code(warp_cw20());
+    let warp = app.instantiate_contract(
+        warp_code_id,
+        owner.clone(),
+        &InstantiateMsg {
+            token: TokenModeMsg::Bridged(Cw20ModeBridged {
+                code_id: cw20_code_id,
+                init_msg: cw20_base::msg::InstantiateMsg {
+                    name: "synthetic".into(),
+                    symbol: "SYN".into(),
+                    decimals: 6,
+                    initial_balances: vec![],
+                    mint: cap.map(|v| MinterResponse {
+                        minter: owner.to_string(),
+                        cap: Some(Uint128::new(v)),
+                    }),
+                    marketing: None,
+                }
+                .into(),
+                salt,
+            }),
+            hrp: HRP.into(),
+            owner: owner.to_string(),
+            mailbox: mailbox.to_string(),
+        },
+        &[],
+        "warp",
+        None,
+    )?;
+
+    let route = gen_bz(32);
+    app.execute_contract(
+        owner,
+        warp.clone(),
+        &ExecuteMsg::Router(RouterMsg::SetRoute {
+            set: DomainRouteSet {
+                domain: DEST_DOMAIN,
+                route: Some(RemoteRouterConfig {
+                    address: route.clone().into(),
+                    decimals: 6,
+                }),
+            },
+        }),
+        &[],
+    )?;
+
+    let res: TokenTypeResponse = app.wrap().query_wasm_smart(
+        &warp,
+        &QueryMsg::TokenDefault(TokenWarpDefaultQueryMsg::TokenType {}),
+    )?;
+    let token = match res.typ {
+        TokenType::CW20 { contract } => Addr::unchecked(contract),
+        _ => unreachable!("warp token is cw20"),
+    };
+
+    // warp route is the minter of the synthetic token
+    app.execute_contract(
+        warp.clone(),
+        token.clone(),
+        &Cw20ExecuteMsg::Mint {
+            recipient: user.to_string(),
+            amount: Uint128::new(balance),
+        },
+        &[],
+    )?;
+
+    Ok(Suite {
+        app,
+        user,
+        mailbox,
+        route,
+        warp,
+        token,
+    })
+}
+
+fn handle(suite: &mut Suite, amount: u128) -> anyhow::Result<AppResponse> {
+    let body = warp::Message {
+        recipient: bech32_decode(suite.user.as_str())?.into(),
+        amount: Uint256::from_u128(amount),
+        metadata: HexBinary::default(),
+    };
+
+    suite.app.execute_contract(
+        suite.mailbox.clone(),
+        suite.warp.clone(),
+        &ExecuteMsg::Handle(HandleMsg {
+            origin: DEST_DOMAIN,
+            sender: suite.route.clone(),
+            body: Payload::new(PayloadVersion::V0, body).encode()?,
+        }),
+        &[],
+    )
+}
+
+fn mint_capacity(suite: &Suite) -> MintCapacityResponse {
+    suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.warp,
+            &QueryMsg::TokenCw20(TokenCw20QueryMsg::MintCapacity {}),
+        )
+        .unwrap()
+}
+
+fn transfer_remote_burn(suite: &mut Suite, amount: u128) -> anyhow::Result<AppResponse> {
+    suite.app.execute_contract(
+        suite.user.clone(),
+        suite.warp.clone(),
+        &ExecuteMsg::TransferRemoteBurn {
+            dest_domain: DEST_DOMAIN,
+            recipient: gen_bz(32),
+            amount: Uint128::new(amount),
+            min_amount: None,
+        },
+        &[],
+    )
+}
+
+fn balance(suite: &Suite, addr: &Addr) -> u128 {
+    let res: BalanceResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &suite.token,
+            &Cw20QueryMsg::Balance {
+                address: addr.to_string(),
+            },
+        )
+        .unwrap();
+
+    res.balance.u128()
+}
+
+fn total_supply(suite: &Suite) -> u128 {
+    let res: TokenInfoResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(&suite.token, &Cw20QueryMsg::TokenInfo {})
+        .unwrap();
+
+    res.total_supply.u128()
+}
+
+#[test]
+fn test_transfer_remote_burn() -> anyhow::Result<()> {
+    let mut suite = setup(1_000, None)?;
+
+    suite.app.execute_contract(
+        suite.user.clone(),
+        suite.token.clone(),
+        &Cw20ExecuteMsg::IncreaseAllowance {
+            spender: suite.warp.to_string(),
+            amount: Uint128::new(400),
+            expires: None,
+        },
+        &[],
+    )?;
+
+    let res = transfer_remote_burn(&mut suite, 400)?;
+
+    // burnt straight from the user, warp route never holds the tokens
+    assert_eq!(balance(&suite, &suite.user), 600);
+    assert_eq!(balance(&suite, &suite.warp), 0);
+    assert_eq!(total_supply(&suite), 600);
+
+    let dispatch = res
+        .events
+        .iter()
+        .find(|v| v.ty == "wasm-mock_dispatch")
+        .expect("dispatch not sent");
+    assert!(dispatch
+        .attributes
+        .iter()
+        .any(|v| v.key == "dest_domain" && v.value == DEST_DOMAIN.to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn test_transfer_remote_burn_no_allowance() -> anyhow::Result<()> {
+    let mut suite = setup(1_000, None)?;
+
+    let err = transfer_remote_burn(&mut suite, 400).unwrap_err();
+    assert!(format!("{err:?}").contains("No allowance for this account"));
+
+    // nothing burnt or dispatched
+    assert_eq!(balance(&suite, &suite.user), 1_000);
+    assert_eq!(total_supply(&suite), 1_000);
+    assert_eq!(
+        suite
+            .app
+            .wrap()
+            .query_wasm_raw(&suite.warp, crate::PENDING_DISPATCH_KEY.as_bytes())?,
+        None
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_expected_token_address() -> anyhow::Result<()> {
+    let salt = HexBinary::from(b"warp-route".to_vec());
+    let suite = setup(0, Some(salt.clone()))?;
+
+    let res: ExpectedTokenAddressResponse = suite.app.wrap().query_wasm_smart(
+        &suite.warp,
+        &QueryMsg::TokenCw20(TokenCw20QueryMsg::ExpectedTokenAddress { salt }),
+    )?;
+    assert_eq!(res.address, suite.token.as_str());
+
+    // a different salt gives a different address
+    let res: ExpectedTokenAddressResponse = suite.app.wrap().query_wasm_smart(
+        &suite.warp,
+        &QueryMsg::TokenCw20(TokenCw20QueryMsg::ExpectedTokenAddress {
+            salt: HexBinary::from(b"other".to_vec()),
+        }),
+    )?;
+    assert_ne!(res.address, suite.token.as_str());
+
+    Ok(())
+}
+
+#[test]
+fn test_expected_token_address_without_salt() -> anyhow::Result<()> {
+    let salt = HexBinary::from(b"warp-route".to_vec());
+    let suite = setup(0, None)?;
+
+    // plain instantiate doesn't land on the instantiate2 address
+    let res: ExpectedTokenAddressResponse = suite.app.wrap().query_wasm_smart(
+        &suite.warp,
+        &QueryMsg::TokenCw20(TokenCw20QueryMsg::ExpectedTokenAddress { salt }),
+    )?;
+    assert_ne!(res.address, suite.token.as_str());
+
+    Ok(())
+}
+
+fn remaining_mint_capacity(res: &AppResponse) -> String {
+    res.events
+        .iter()
+        .find(|v| v.ty == "wasm-hpl_warp_cw20::handle")
+        .and_then(|v| {
+            v.attributes
+                .iter()
+                .find(|v| v.key == "remaining_mint_capacity")
+        })
+        .map(|v| v.value.clone())
+        .expect("remaining mint capacity not emitted")
+}
+
+#[test]
+fn test_handle_mint_cap_reached() -> anyhow::Result<()> {
+    let mut suite = setup_capped(600, None, Some(1_000))?;
+
+    assert_eq!(
+        mint_capacity(&suite),
+        MintCapacityResponse {
+            cap: Some(Uint128::new(1_000)),
+            minted: Uint128::new(600),
+            remaining: Some(Uint128::new(400)),
+        }
+    );
+
+    // minting up to the cap exactly is fine
+    let res = handle(&mut suite, 400)?;
+    assert_eq!(remaining_mint_capacity(&res), "0");
+    assert_eq!(balance(&suite, &suite.user), 1_000);
+    assert_eq!(mint_capacity(&suite).remaining, Some(Uint128::zero()));
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_mint_cap_exceeded() -> anyhow::Result<()> {
+    let mut suite = setup_capped(600, None, Some(1_000))?;
+
+    let err = handle(&mut suite, 401).unwrap_err();
+    let golden = "[E524] mint cap exceeded. requested: 401, remaining: 400";
+    assert!(format!("{err:?}").contains(golden));
+
+    // nothing minted
+    assert_eq!(balance(&suite, &suite.user), 600);
+    assert_eq!(total_supply(&suite), 600);
+
+    Ok(())
+}
+
+#[test]
+fn test_handle_uncapped() -> anyhow::Result<()> {
+    let mut suite = setup(600, None)?;
+
+    assert_eq!(
+        mint_capacity(&suite),
+        MintCapacityResponse {
+            cap: None,
+            minted: Uint128::new(600),
+            remaining: None,
+        }
+    );
+
+    let res = handle(&mut suite, 1_000)?;
+    assert_eq!(remaining_mint_capacity(&res), "none");
+    assert_eq!(balance(&suite, &suite.user), 1_600);
+
+    Ok(())
+}
@@ -1,34 +1,47 @@
-#[cfg(not(feature = "library"))]
-use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure, ensure_eq, to_json_binary, CosmosMsg, Deps, DepsMut, Empty, Env, HexBinary,
-    MessageInfo, QueryResponse, Reply, Response, StdError, SubMsg, Uint128, Uint256,
+    ensure, ensure_eq, to_json_binary, wasm_execute, Coin, CosmosMsg, CustomQuery, Deps, DepsMut,
+    Env, HexBinary, MessageInfo, Order, QueryResponse, Reply, Response, StdError, StdResult,
+    Storage, SubMsg, Uint128, Uint256,
 };
 use hpl_connection::{get_hook, get_ism};
+use hpl_error::HplError;
 use hpl_interface::{
     core::mailbox,
     ism::{InterchainSecurityModuleResponse, IsmSpecifierQueryMsg},
+    router::{DomainRouteSet, RouteResponse, RoutesResponse},
     to_binary,
-    types::bech32_encode,
+    types::{bech32_encode, bech32_to_h256, IGPMetadata, RemoteAddress},
     warp::{
         self,
-        native::{ExecuteMsg, InstantiateMsg, QueryMsg},
+        native::{
+            DenomsQueryMsg, ExecuteMsg, IbcForwardConfigResponse, IbcForwardQueryMsg,
+            IbcLifecycleComplete, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg,
+            SupportedDenomsResponse,
+        },
+    },
+    warp::{
+        payload::{Payload, PayloadVersion},
+        DecodedRoute, GasPaymentRequiredResponse, HandleCallMsg, MailboxInfoResponse,
+        PauseStateResponse, PayloadVersionResponse, QuoteTransferRemoteResponse, RecipientFormat,
+        RecipientFormatResponse, RemoteRouterConfig, TokenMode, TokenModeMsg, TokenModeResponse,
+        TokenTypeResponse,
     },
-    warp::{TokenMode, TokenModeMsg, TokenModeResponse, TokenTypeResponse},
 };
-use hpl_router::get_route;
+use hpl_router::{get_domains, get_route, migrate_routes};
 
 use crate::{
     conv,
     error::ContractError,
     new_event,
-    proto::{MsgCreateDenom, MsgCreateDenomResponse},
-    CONTRACT_NAME, CONTRACT_VERSION, HRP, MAILBOX, MODE, REPLY_ID_CREATE_DENOM, TOKEN,
+    proto::{MsgCreateDenom, MsgCreateDenomResponse, MsgTransferResponse},
+    IbcForwardRecord, CONTRACT_NAME, CONTRACT_VERSION, DECIMALS, DEFAULT_GAS_LIMITS, DOMAIN_HRPS,
+    ESCROW, HRP, IBC_FORWARDS, IBC_FORWARD_CHANNELS, IBC_FORWARD_ENABLED, INBOUND_PAUSED, MAILBOX,
+    MODE, PAYLOAD_VERSION, PENDING_IBC_FORWARD, RECIPIENT_FORMATS, REPLY_ID_CREATE_DENOM,
+    REPLY_ID_IBC_FORWARD, REQUIRE_GAS_PAYMENT, SUPPORTED_DENOMS, TOKEN,
 };
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
+pub fn instantiate<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
@@ -37,12 +50,15 @@ pub fn instantiate(
 
     let mode: TokenMode = msg.token.clone().into();
     let owner = deps.api.addr_validate(&msg.owner)?;
+    let mailbox = deps.api.addr_validate(&msg.mailbox)?;
 
     HRP.save(deps.storage, &msg.hrp)?;
     MODE.save(deps.storage, &mode)?;
-    MAILBOX.save(deps.storage, &deps.api.addr_validate(&msg.mailbox)?)?;
+    DECIMALS.save(deps.storage, &msg.decimals)?;
+    MAILBOX.save(deps.storage, &mailbox)?;
 
     hpl_ownable::initialize(deps.storage, &owner)?;
+    hpl_pausable::initialize(deps.storage, &false)?;
 
     let (msgs, denom) = match msg.token {
         // create native denom if token is bridged
@@ -76,18 +92,25 @@ pub fn instantiate(
         }
     };
 
-    Ok(Response::new().add_submessages(msgs).add_event(
-        new_event("instantiate")
-            .add_attribute("sender", info.sender)
-            .add_attribute("owner", owner)
-            .add_attribute("mode", format!("{}", mode))
-            .add_attribute("denom", denom),
-    ))
+    Ok(Response::new()
+        .set_data(to_json_binary(&warp::InstantiateResponse {
+            mailbox,
+            owner: owner.clone(),
+        })?)
+        .add_submessages(msgs)
+        .add_event(
+            new_event("instantiate")
+                .add_attribute("sender", info.sender)
+                .add_attribute("owner", owner)
+                .add_attribute("mode", format!("{}", mode))
+                .add_attribute("denom", denom)
+                .add_attribute("decimals", msg.decimals.to_string()),
+        ))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
+#[hpl_ownable::route_ownable]
+pub fn execute<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
@@ -95,20 +118,52 @@ pub fn execute(
     use ExecuteMsg::*;
 
     match msg {
-        Ownable(msg) => Ok(hpl_ownable::handle(deps, env, info, msg)?),
         Router(msg) => Ok(hpl_router::handle(deps, env, info, msg)?),
         Connection(msg) => Ok(hpl_connection::handle(deps, env, info, msg)?),
+        Pausable(msg) => Ok(hpl_pausable::handle(deps, env, info, msg)?),
+        SetInboundPaused { paused } => set_inbound_paused(deps, info, paused),
+        SetRecipientFormat { domain, format } => set_recipient_format(deps, info, domain, format),
+        SetGasPaymentRequired { domain, required } => {
+            set_gas_payment_required(deps, info, domain, required)
+        }
+        SetPayloadVersion { version } => set_payload_version(deps, info, version),
+        SetDomainHrp { domain, hrp } => set_domain_hrp(deps, info, domain, hrp),
+        SetDefaultGas { domain, gas } => set_default_gas(deps, info, domain, gas),
+        SetIbcForwardEnabled { enabled } => set_ibc_forward_enabled(deps, info, enabled),
+        SetIbcForwardChannel { channel, allowed } => {
+            set_ibc_forward_channel(deps, info, channel, allowed)
+        }
+        SetSupportedDenom { denom, supported } => set_supported_denom(deps, info, denom, supported),
         Handle(msg) => mailbox_handle(deps, env, info, msg),
+        EnrollRemoteRouter {
+            domain,
+            address,
+            decimals,
+        } => enroll_remote_router(deps, info, domain, address, decimals),
         TransferRemote {
             dest_domain,
             recipient,
             amount,
-        } => transfer_remote(deps, env, info, dest_domain, recipient, amount),
+            min_amount,
+            denom,
+        } => transfer_remote(
+            deps,
+            env,
+            info,
+            dest_domain,
+            recipient,
+            amount,
+            min_amount,
+            denom,
+        ),
     }
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+pub fn reply<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    _env: Env,
+    msg: Reply,
+) -> Result<Response, ContractError> {
     let reply_data = msg
         .result
         .into_result()
@@ -127,71 +182,654 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 
             Ok(resp)
         }
+        REPLY_ID_IBC_FORWARD => {
+            let reply: MsgTransferResponse = reply_data.try_into()?;
+
+            let forward = PENDING_IBC_FORWARD.load(deps.storage)?;
+            PENDING_IBC_FORWARD.remove(deps.storage);
+            IBC_FORWARDS.save(deps.storage, (&forward.channel, reply.sequence), &forward)?;
+
+            let resp = Response::new().add_event(
+                new_event("reply-ibc-forward")
+                    .add_attribute("ibc_channel", forward.channel)
+                    .add_attribute("sequence", reply.sequence.to_string()),
+            );
+
+            Ok(resp)
+        }
+
+        _ => Err(HplError::InvalidReplyId {}.into()),
+    }
+}
+
+/// Outcome of a forwarded transfer, reported by the ibc-hooks module.
+/// Failed and timed out transfers refund the warp contract, the tokens go on to the recipient
+pub fn sudo<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    _env: Env,
+    msg: SudoMsg,
+) -> Result<Response, ContractError> {
+    let SudoMsg::IbcLifecycleComplete(complete) = msg;
+    let (channel, sequence, success) = match complete {
+        IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            success,
+            ..
+        } => (channel, sequence, success),
+        IbcLifecycleComplete::IbcTimeout { channel, sequence } => (channel, sequence, false),
+    };
 
-        _ => Err(ContractError::InvalidReplyId),
+    let forward = IBC_FORWARDS.load(deps.storage, (&channel, sequence))?;
+    IBC_FORWARDS.remove(deps.storage, (&channel, sequence));
+
+    let event = new_event("ibc-forward-complete")
+        .add_attribute("ibc_channel", channel)
+        .add_attribute("sequence", sequence.to_string())
+        .add_attribute("success", success.to_string());
+
+    if success {
+        return Ok(Response::new().add_event(event));
     }
+
+    Ok(Response::new()
+        .add_message(conv::to_send_msg(
+            &forward.recipient,
+            vec![forward.amount.clone()],
+        ))
+        .add_event(
+            event
+                .add_attribute("refund_recipient", forward.recipient)
+                .add_attribute("refund", forward.amount.to_string()),
+        ))
 }
 
-fn mailbox_handle(
-    deps: DepsMut,
+fn mailbox_handle<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     env: Env,
     info: MessageInfo,
     msg: hpl_interface::core::HandleMsg,
 ) -> Result<Response, ContractError> {
     // validate mailbox
-    ensure_eq!(
-        info.sender,
-        MAILBOX.load(deps.storage)?,
-        ContractError::Unauthorized
+    let mailbox = MAILBOX.load(deps.storage)?;
+    ensure_eq!(info.sender, mailbox, HplError::Unauthorized {});
+    // paused deliveries stay on the mailbox, so they can be retried once released
+    ensure!(
+        !INBOUND_PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+        ContractError::InboundPaused
     );
     // validate message origin - this should be registered route
+    let origin_router = get_remote_router(deps.storage, msg.origin)?;
     ensure_eq!(
         msg.sender,
-        get_route::<HexBinary>(deps.storage, msg.origin)?
-            .route
-            .expect("route not found"),
-        ContractError::Unauthorized
+        *origin_router.address,
+        HplError::Unauthorized {}
     );
 
-    let token_msg: warp::Message = msg.body.into();
+    let payload = Payload::try_decode(&msg.body)?;
+    let token_msg = payload.message();
     let recipient = bech32_encode(&HRP.load(deps.storage)?, &token_msg.recipient)?;
+    // amount is already scaled to local decimals by the origin router
+    let amount = token_msg.amount;
+
+    // forwards that can't be decoded, are disabled, take a channel that isn't allowed
+    // or a timeout that overflows fall back to the local recipient
+    let forward = match payload.ibc_forward() {
+        Some(Ok(forward))
+            if IBC_FORWARD_ENABLED
+                .may_load(deps.storage)?
+                .unwrap_or_default()
+                && IBC_FORWARD_CHANNELS.has(deps.storage, &forward.ibc_channel) =>
+        {
+            conv::to_ibc_timeout(env.block.time, forward.timeout_seconds)
+                .map(|timeout| (forward, timeout))
+        }
+        _ => None,
+    };
 
-    let token = TOKEN.load(deps.storage)?;
+    let token = match payload.denom() {
+        Some(denom) => {
+            let denom = denom?;
+            ensure!(
+                is_supported_denom(deps.storage, &denom)?,
+                ContractError::UnsupportedDenom { denom }
+            );
+            denom
+        }
+        None => TOKEN.load(deps.storage)?,
+    };
     let mode = MODE.load(deps.storage)?;
 
-    let mut msgs: Vec<CosmosMsg> = vec![];
+    // transfers below the min amount go back to the refund recipient on the origin domain,
+    // without one reverting keeps the message undelivered so it can be retried
+    if let Some(min_amount) = token_msg.min_amount().filter(|v| amount < *v) {
+        let refund_recipient = token_msg
+            .refund_recipient()
+            .ok_or(ContractError::BelowMinAmount { amount, min_amount })?;
+
+        return refund(
+            deps,
+            &env,
+            msg.origin,
+            refund_recipient,
+            conv::to_coin_u256(amount, &token)?,
+            min_amount,
+        );
+    }
+
+    // the route mints and holds the collateral, so it never calls the mailbox or itself
+    // on behalf of a remote sender
+    let call = payload.call();
+    if call.is_some() {
+        ensure!(
+            recipient != mailbox && recipient != env.contract.address,
+            ContractError::InvalidCallRecipient {
+                recipient: recipient.to_string()
+            }
+        );
+    }
+
+    let mut msgs: Vec<SubMsg> = vec![];
 
     if mode == TokenMode::Bridged {
         // push token mint msg if token is bridged
-        msgs.push(conv::to_mint_msg(&env.contract.address, &token, token_msg.amount).into());
+        msgs.push(SubMsg::new(conv::to_mint_msg(
+            &env.contract.address,
+            &token,
+            amount,
+        )));
+    } else {
+        // release collateral escrowed for this denom
+        let amount = conv::to_uint128(amount)?;
+        let escrow = ESCROW.may_load(deps.storage, &token)?.unwrap_or_default();
+        let escrow = escrow
+            .checked_sub(amount)
+            .map_err(|_| ContractError::InsufficientEscrow {
+                denom: token.clone(),
+            })?;
+        ESCROW.save(deps.storage, &token, &escrow)?;
     }
 
-    // push token send msg
-    msgs.push(
-        conv::to_send_msg(
-            &recipient,
-            vec![conv::to_coin_u256(token_msg.amount, &token)?],
-        )
-        .into(),
-    );
+    let coin = conv::to_coin_u256(amount, &token)?;
+    let mut event = new_event("handle")
+        .add_attribute("recipient", &recipient)
+        .add_attribute("token", &token)
+        .add_attribute("amount", amount);
+
+    if let Some((forward, timeout)) = forward {
+        // tokens are held by the warp contract until the ibc transfer. the forward is
+        // recorded under the sequence of the transfer, so a failed transfer can be refunded
+        PENDING_IBC_FORWARD.save(
+            deps.storage,
+            &IbcForwardRecord {
+                channel: forward.ibc_channel.clone(),
+                recipient: recipient.clone(),
+                amount: coin.clone(),
+            },
+        )?;
+        msgs.push(SubMsg::reply_on_success(
+            conv::to_ibc_transfer_msg(&env.contract.address, &forward, coin, timeout),
+            REPLY_ID_IBC_FORWARD,
+        ));
+
+        event = event
+            .add_attribute("ibc_channel", forward.ibc_channel)
+            .add_attribute("final_recipient", forward.final_recipient);
+    } else {
+        // push token send msg
+        msgs.push(SubMsg::new(conv::to_send_msg(&recipient, vec![coin])));
+    }
+
+    // forward the call after the transfer, failing the whole delivery if it reverts
+    if let Some(call) = call {
+        let call = HandleCallMsg {
+            origin: msg.origin,
+            sender: msg.sender,
+            amount,
+            call,
+        };
+        msgs.push(SubMsg::new(wasm_execute(&recipient, &call.wrap(), vec![])?));
+    }
+
+    Ok(Response::new().add_submessages(msgs).add_event(event))
+}
+
+/// Sends a transfer below its min amount back to the origin domain. Nothing is minted or
+/// released, so the origin route mints or releases it again. Hook fees of the dispatch are
+/// paid out of the refunded tokens, fees in another denom fail the delivery
+fn refund<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    env: &Env,
+    origin: u32,
+    refund_recipient: HexBinary,
+    amount: Coin,
+    min_amount: Uint256,
+) -> Result<Response, ContractError> {
+    let mailbox = MAILBOX.load(deps.storage)?;
+
+    let dispatch_msg = new_dispatch_msg(
+        deps.as_ref(),
+        origin,
+        refund_recipient.clone(),
+        amount.amount,
+        None,
+        None,
+        &amount.denom,
+    )?;
+    let fees =
+        mailbox::quote_dispatch(&deps.querier, &mailbox, &env.contract.address, dispatch_msg)?.fees;
+
+    let fee = match fees.as_slice() {
+        [] => Uint128::zero(),
+        [fee] if fee.denom == amount.denom && fee.amount < amount.amount => fee.amount,
+        _ => {
+            return Err(ContractError::RefundFeeNotCovered {
+                fees,
+                refund: amount,
+            })
+        }
+    };
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+
+    if !fee.is_zero() {
+        if MODE.load(deps.storage)? == TokenMode::Bridged {
+            msgs.push(conv::to_mint_msg(&env.contract.address, &amount.denom, fee).into());
+        } else {
+            let escrow = ESCROW
+                .may_load(deps.storage, &amount.denom)?
+                .unwrap_or_default()
+                .checked_sub(fee)
+                .map_err(|_| ContractError::InsufficientEscrow {
+                    denom: amount.denom.clone(),
+                })?;
+            ESCROW.save(deps.storage, &amount.denom, &escrow)?;
+        }
+    }
+
+    let refunded = amount.amount - fee;
+    let dispatch_msg = new_dispatch_msg(
+        deps.as_ref(),
+        origin,
+        refund_recipient.clone(),
+        refunded,
+        None,
+        None,
+        &amount.denom,
+    )?;
+    msgs.push(wasm_execute(mailbox, &mailbox::ExecuteMsg::Dispatch(dispatch_msg), fees)?.into());
 
     Ok(Response::new().add_messages(msgs).add_event(
-        new_event("handle")
-            .add_attribute("recipient", recipient)
-            .add_attribute("token", token)
-            .add_attribute("amount", token_msg.amount),
+        new_event("refund")
+            .add_attribute("origin", origin.to_string())
+            .add_attribute("refund_recipient", refund_recipient.to_hex())
+            .add_attribute("token", amount.denom)
+            .add_attribute("amount", refunded)
+            .add_attribute("fee", fee)
+            .add_attribute("min_amount", min_amount),
+    ))
+}
+
+fn enroll_remote_router<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    domain: u32,
+    address: RemoteAddress,
+    decimals: u8,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    let route = RemoteRouterConfig {
+        address: address.to_h256()?.into(),
+        decimals,
+    };
+    let event = hpl_router::set_route(
+        deps.storage,
+        &info.sender,
+        DomainRouteSet {
+            domain,
+            route: Some(route),
+        },
+    )?;
+
+    Ok(Response::new().add_event(event))
+}
+
+fn set_recipient_format<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    domain: u32,
+    format: RecipientFormat,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    RECIPIENT_FORMATS.save(deps.storage, domain, &format)?;
+
+    Ok(Response::new().add_event(
+        new_event("set-recipient-format")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("format", format.to_string()),
+    ))
+}
+
+fn set_gas_payment_required<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    domain: u32,
+    required: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    REQUIRE_GAS_PAYMENT.save(deps.storage, domain, &required)?;
+
+    Ok(Response::new().add_event(
+        new_event("set-gas-payment-required")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("required", required.to_string()),
+    ))
+}
+
+fn set_inbound_paused<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    INBOUND_PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new().add_event(
+        new_event("set-inbound-paused")
+            .add_attribute("sender", info.sender)
+            .add_attribute("paused", paused.to_string()),
+    ))
+}
+
+fn set_payload_version<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    version: PayloadVersion,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    PAYLOAD_VERSION.save(deps.storage, &version)?;
+
+    Ok(Response::new().add_event(
+        new_event("set-payload-version")
+            .add_attribute("sender", info.sender)
+            .add_attribute("version", version.to_string()),
+    ))
+}
+
+fn set_domain_hrp<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    domain: u32,
+    hrp: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    match &hrp {
+        Some(hrp) => {
+            // rejects prefixes that can't encode an address
+            bech32_encode(hrp, &[1u8; 20])?;
+            DOMAIN_HRPS.save(deps.storage, domain, hrp)?
+        }
+        None => DOMAIN_HRPS.remove(deps.storage, domain),
+    }
+
+    Ok(Response::new().add_event(
+        new_event("set-domain-hrp")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("hrp", hrp.unwrap_or("none".into())),
+    ))
+}
+
+fn set_default_gas<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    domain: u32,
+    gas: Option<u64>,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    match gas {
+        Some(gas) => DEFAULT_GAS_LIMITS.save(deps.storage, domain, &gas)?,
+        None => DEFAULT_GAS_LIMITS.remove(deps.storage, domain),
+    }
+
+    Ok(Response::new().add_event(
+        new_event("set-default-gas")
+            .add_attribute("sender", info.sender)
+            .add_attribute("domain", domain.to_string())
+            .add_attribute("gas", gas.map(|v| v.to_string()).unwrap_or("none".into())),
+    ))
+}
+
+fn set_ibc_forward_enabled<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    IBC_FORWARD_ENABLED.save(deps.storage, &enabled)?;
+
+    Ok(Response::new().add_event(
+        new_event("set-ibc-forward-enabled")
+            .add_attribute("sender", info.sender)
+            .add_attribute("enabled", enabled.to_string()),
+    ))
+}
+
+fn set_ibc_forward_channel<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    channel: String,
+    allowed: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+
+    if allowed {
+        IBC_FORWARD_CHANNELS.save(deps.storage, &channel, &())?;
+    } else {
+        IBC_FORWARD_CHANNELS.remove(deps.storage, &channel);
+    }
+
+    Ok(Response::new().add_event(
+        new_event("set-ibc-forward-channel")
+            .add_attribute("sender", info.sender)
+            .add_attribute("channel", channel)
+            .add_attribute("allowed", allowed.to_string()),
+    ))
+}
+
+fn set_supported_denom<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    info: MessageInfo,
+    denom: String,
+    supported: bool,
+) -> Result<Response, ContractError> {
+    ensure_eq!(
+        info.sender,
+        hpl_ownable::get_owner(deps.storage)?,
+        HplError::Unauthorized {}
+    );
+    // bridged routes only mint their own denom
+    ensure!(
+        MODE.load(deps.storage)? == TokenMode::Collateral,
+        ContractError::UnsupportedDenom { denom }
+    );
+
+    if supported {
+        SUPPORTED_DENOMS.save(deps.storage, &denom, &())?;
+    } else {
+        SUPPORTED_DENOMS.remove(deps.storage, &denom);
+    }
+
+    Ok(Response::new().add_event(
+        new_event("set-supported-denom")
+            .add_attribute("sender", info.sender)
+            .add_attribute("denom", denom)
+            .add_attribute("supported", supported.to_string()),
     ))
 }
 
-fn transfer_remote(
-    deps: DepsMut,
+/// The primary denom is always supported
+fn is_supported_denom(storage: &dyn Storage, denom: &str) -> Result<bool, ContractError> {
+    Ok(TOKEN.load(storage)? == denom || SUPPORTED_DENOMS.has(storage, denom))
+}
+
+fn get_remote_router(
+    storage: &dyn Storage,
+    domain: u32,
+) -> Result<RemoteRouterConfig, ContractError> {
+    get_route::<RemoteRouterConfig>(storage, domain)?
+        .route
+        .ok_or(ContractError::Hpl(HplError::RouteNotFound { domain }))
+}
+
+/// Dispatch of a transfer to `dest_domain`, as sent to the mailbox
+fn new_dispatch_msg<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    dest_domain: u32,
+    recipient: HexBinary,
+    transfer_amount: Uint128,
+    min_amount: Option<Uint128>,
+    refund_recipient: Option<[u8; 32]>,
+    token: &str,
+) -> Result<mailbox::DispatchMsg, ContractError> {
+    let dest_router = get_remote_router(deps.storage, dest_domain)?;
+    let decimals = DECIMALS.load(deps.storage)?;
+    let remote_amount =
+        dest_router.to_remote_amount(decimals, Uint256::from_uint128(transfer_amount))?;
+    let remote_min_amount = min_amount
+        .map(|v| dest_router.to_remote_amount(decimals, Uint256::from_uint128(v)))
+        .transpose()?;
+
+    let mut dispatch_payload = warp::Message {
+        recipient,
+        amount: remote_amount,
+        metadata: HexBinary::default(),
+    };
+    if let Some(remote_min_amount) = remote_min_amount {
+        dispatch_payload = dispatch_payload.with_min_amount(remote_min_amount);
+    }
+    if let Some(refund_recipient) = refund_recipient {
+        dispatch_payload = dispatch_payload.with_refund_recipient(refund_recipient);
+    }
+
+    let version = PAYLOAD_VERSION.may_load(deps.storage)?.unwrap_or_default();
+    let mut payload = Payload::new(version, dispatch_payload);
+    // every denom shares the route's decimals, only the denom tells them apart.
+    // v0 routes can't carry it, so they only send the primary denom
+    if token != TOKEN.load(deps.storage)? {
+        payload = payload.with_denom(token)?;
+    }
+
+    let mut dispatch_msg = mailbox::DispatchMsg::new(
+        dest_domain,
+        dest_router.address.into_inner(),
+        payload.encode()?,
+    );
+    if let Some(hook) = get_hook(deps.storage)? {
+        dispatch_msg = dispatch_msg.with_hook(hook);
+    }
+    // an empty refund address leaves the refund to the igp default
+    if let Some(gas) = DEFAULT_GAS_LIMITS.may_load(deps.storage, dest_domain)? {
+        dispatch_msg = dispatch_msg.with_metadata(IGPMetadata {
+            gas_limit: Uint256::from(gas),
+            refund_address: HexBinary::default(),
+        });
+    }
+
+    Ok(dispatch_msg)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn transfer_remote<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
     env: Env,
     info: MessageInfo,
     dest_domain: u32,
     recipient: HexBinary,
     transfer_amount: Uint128,
+    min_amount: Option<Uint128>,
+    denom: Option<String>,
 ) -> Result<Response, ContractError> {
-    let token = TOKEN.load(deps.storage)?;
+    ensure!(
+        !hpl_pausable::get_pause_info(deps.storage)?,
+        ContractError::OutboundPaused
+    );
+
+    let recipient_format = RECIPIENT_FORMATS
+        .may_load(deps.storage, dest_domain)?
+        .unwrap_or_default();
+    ensure!(
+        recipient_format.is_valid(&recipient),
+        ContractError::InvalidRecipientFormat {
+            domain: dest_domain,
+            expected: recipient_format,
+        }
+    );
+    if let Some(min_amount) = min_amount {
+        ensure!(
+            min_amount <= transfer_amount,
+            ContractError::InvalidMinAmount {
+                amount: transfer_amount,
+                min_amount,
+            }
+        );
+    }
+
+    let token = match denom {
+        Some(denom) => denom,
+        None => TOKEN.load(deps.storage)?,
+    };
+    ensure!(
+        is_supported_denom(deps.storage, &token)?,
+        ContractError::UnsupportedDenom { denom: token }
+    );
+
     let mode = MODE.load(deps.storage)?;
     let mailbox = MAILBOX.load(deps.storage)?;
 
@@ -209,33 +847,59 @@ fn transfer_remote(
 
     funds[token_index].amount -= transfer_amount;
 
-    let dest_router = get_route::<HexBinary>(deps.storage, dest_domain)?
-        .route
-        .expect("route not found");
+    // funds left after the transfer amount are forwarded to the mailbox as gas payment
+    let gas_payment_required = REQUIRE_GAS_PAYMENT
+        .may_load(deps.storage, dest_domain)?
+        .unwrap_or_default();
+    ensure!(
+        !gas_payment_required || funds.iter().any(|v| !v.amount.is_zero()),
+        ContractError::GasPaymentRequired {
+            domain: dest_domain
+        }
+    );
+
+    // the sender gets the transfer back if it arrives below the min amount
+    let refund_recipient = min_amount
+        .map(|_| bech32_to_h256(info.sender.as_str()))
+        .transpose()?;
+    let dispatch_msg = new_dispatch_msg(
+        deps.as_ref(),
+        dest_domain,
+        recipient.clone(),
+        transfer_amount,
+        min_amount,
+        refund_recipient,
+        &token,
+    )?;
+
+    // fail here rather than in the hook, anything above the quote still goes to the hook
+    let quote = mailbox::quote_dispatch(
+        &deps.querier,
+        &mailbox,
+        &env.contract.address,
+        dispatch_msg.clone(),
+    )?;
+    ensure!(
+        quote.covered_by(&funds),
+        ContractError::InsufficientHookPayment {
+            required: quote.fees,
+            provided: funds,
+        }
+    );
 
     let mut msgs: Vec<CosmosMsg> = vec![];
 
     if mode == TokenMode::Bridged {
         // push token burn msg if token is bridged
         msgs.push(conv::to_burn_msg(&env.contract.address, &token, transfer_amount).into());
+    } else {
+        ESCROW.update(deps.storage, &token, |v| -> StdResult<_> {
+            Ok(v.unwrap_or_default().checked_add(transfer_amount)?)
+        })?;
     }
 
-    let dispatch_payload = warp::Message {
-        recipient: recipient.clone(),
-        amount: Uint256::from_uint128(transfer_amount),
-        metadata: HexBinary::default(),
-    };
-
     // push mailbox dispatch msg
-    msgs.push(mailbox::dispatch(
-        mailbox,
-        dest_domain,
-        dest_router,
-        dispatch_payload.into(),
-        get_hook(deps.storage)?.map(|v| v.into()),
-        None,
-        funds,
-    )?);
+    msgs.push(wasm_execute(mailbox, &mailbox::ExecuteMsg::Dispatch(dispatch_msg), funds)?.into());
 
     Ok(Response::new().add_messages(msgs).add_event(
         new_event("transfer-remote")
@@ -246,63 +910,340 @@ fn transfer_remote(
     ))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+#[hpl_ownable::route_ownable]
+pub fn query<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    env: Env,
+    msg: QueryMsg,
+) -> Result<QueryResponse, ContractError> {
     use warp::TokenWarpDefaultQueryMsg::*;
 
     match msg {
-        QueryMsg::Ownable(msg) => Ok(hpl_ownable::handle_query(deps, env, msg)?),
+        QueryMsg::Version(msg) => Ok(hpl_version::handle_query(deps, env, msg)?),
         QueryMsg::Router(msg) => Ok(hpl_router::handle_query(deps, env, msg)?),
         QueryMsg::Connection(msg) => Ok(hpl_connection::handle_query(deps, env, msg)?),
         QueryMsg::TokenDefault(msg) => match msg {
             TokenType {} => to_binary(get_token_type(deps)),
             TokenMode {} => to_binary(get_token_mode(deps)),
+            RecipientFormat { domain } => to_binary(get_recipient_format(deps, domain)),
+            GasPaymentRequired { domain } => to_binary(get_gas_payment_required(deps, domain)),
+            PauseState {} => to_binary(get_pause_state(deps)),
+            PayloadVersion {} => to_binary(get_payload_version(deps)),
+            MailboxInfo {} => to_binary(get_mailbox_info(deps)),
+            RouterDecoded { domain } => to_binary(get_router_decoded(deps, domain)),
+            RoutersDecoded {} => to_binary(get_routers_decoded(deps)),
+            Ism {} => Ok(to_json_binary(&InterchainSecurityModuleResponse {
+                ism: get_ism(deps.storage)?,
+            })?),
+            QuoteTransferRemote {
+                dest_domain,
+                amount,
+                recipient,
+            } => to_binary(quote_transfer_remote(
+                deps,
+                env,
+                dest_domain,
+                amount,
+                recipient,
+            )),
         },
         QueryMsg::IsmSpecifier(IsmSpecifierQueryMsg::InterchainSecurityModule()) => {
             Ok(to_json_binary(&InterchainSecurityModuleResponse {
                 ism: get_ism(deps.storage)?,
             })?)
         }
+        QueryMsg::IbcForward(msg) => match msg {
+            IbcForwardQueryMsg::IbcForwardConfig {} => to_binary(get_ibc_forward_config(deps)),
+        },
+        QueryMsg::Denoms(msg) => match msg {
+            DenomsQueryMsg::SupportedDenoms {} => to_binary(get_supported_denoms(deps)),
+        },
     }
 }
 
-fn get_token_type(deps: Deps) -> Result<TokenTypeResponse, ContractError> {
-    let denom = TOKEN.load(deps.storage)?;
+fn get_ibc_forward_config<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<IbcForwardConfigResponse, ContractError> {
+    let enabled = IBC_FORWARD_ENABLED
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let channels = IBC_FORWARD_CHANNELS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(IbcForwardConfigResponse { enabled, channels })
+}
 
-    Ok(TokenTypeResponse {
+fn get_supported_denoms<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<SupportedDenomsResponse, ContractError> {
+    let primary = TOKEN.load(deps.storage)?;
+
+    let mut denoms = vec![primary.clone()];
+    for denom in SUPPORTED_DENOMS.keys(deps.storage, None, None, Order::Ascending) {
+        let denom = denom?;
+        if denom != primary {
+            denoms.push(denom);
+        }
+    }
+
+    let escrow = if MODE.load(deps.storage)? == TokenMode::Collateral {
+        denoms
+            .iter()
+            .map(|denom| {
+                let amount = ESCROW.may_load(deps.storage, denom)?.unwrap_or_default();
+                Ok(Coin::new(amount.u128(), denom))
+            })
+            .collect::<StdResult<Vec<_>>>()?
+    } else {
+        vec![]
+    };
+
+    Ok(SupportedDenomsResponse { denoms, escrow })
+}
+
+fn get_token_type<C: CustomQuery>(deps: Deps<'_, C>) -> Result<TokenTypeResponse, ContractError> {
+    let denom = TOKEN.load(deps.storage)?;
+
+    Ok(TokenTypeResponse {
         typ: warp::TokenType::Native(warp::TokenTypeNative::Fungible { denom }),
     })
 }
 
-fn get_token_mode(deps: Deps) -> Result<TokenModeResponse, ContractError> {
+fn get_token_mode<C: CustomQuery>(deps: Deps<'_, C>) -> Result<TokenModeResponse, ContractError> {
     let mode = MODE.load(deps.storage)?;
 
     Ok(TokenModeResponse { mode })
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+fn get_recipient_format<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    domain: u32,
+) -> Result<RecipientFormatResponse, ContractError> {
+    let format = RECIPIENT_FORMATS
+        .may_load(deps.storage, domain)?
+        .unwrap_or_default();
+
+    Ok(RecipientFormatResponse { format })
+}
+
+fn get_gas_payment_required<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    domain: u32,
+) -> Result<GasPaymentRequiredResponse, ContractError> {
+    let required = REQUIRE_GAS_PAYMENT
+        .may_load(deps.storage, domain)?
+        .unwrap_or_default();
+
+    Ok(GasPaymentRequiredResponse { required })
+}
+
+fn get_pause_state<C: CustomQuery>(deps: Deps<'_, C>) -> Result<PauseStateResponse, ContractError> {
+    Ok(PauseStateResponse {
+        outbound: hpl_pausable::get_pause_info(deps.storage)?,
+        inbound: INBOUND_PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+    })
+}
+
+fn get_payload_version<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<PayloadVersionResponse, ContractError> {
+    let version = PAYLOAD_VERSION.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(PayloadVersionResponse { version })
+}
+
+fn decode_route(
+    storage: &dyn Storage,
+    domain: u32,
+) -> Result<DomainRouteSet<DecodedRoute>, ContractError> {
+    let route = get_route::<RemoteRouterConfig>(storage, domain)?
+        .route
+        .map(|v| {
+            let hrp = DOMAIN_HRPS.may_load(storage, domain)?;
+            DecodedRoute::new(&v.address, hrp.as_deref())
+        })
+        .transpose()?;
+
+    Ok(DomainRouteSet { domain, route })
+}
+
+fn get_router_decoded<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    domain: u32,
+) -> Result<RouteResponse<DecodedRoute>, ContractError> {
+    Ok(RouteResponse {
+        route: decode_route(deps.storage, domain)?,
+    })
+}
+
+fn get_routers_decoded<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<RoutesResponse<DecodedRoute>, ContractError> {
+    let routes = get_domains::<RemoteRouterConfig>(deps.storage)?
+        .into_iter()
+        .map(|domain| decode_route(deps.storage, domain))
+        .collect::<Result<_, _>>()?;
+
+    Ok(RoutesResponse { routes })
+}
+
+fn get_mailbox_info<C: CustomQuery>(
+    deps: Deps<'_, C>,
+) -> Result<MailboxInfoResponse, ContractError> {
+    let mailbox = MAILBOX.load(deps.storage)?;
+    let local_domain = deps
+        .querier
+        .query_wasm_smart::<mailbox::LocalDomainResponse>(
+            &mailbox,
+            &mailbox::QueryMsg::Mailbox(mailbox::MailboxQueryMsg::LocalDomain {}),
+        )?
+        .local_domain;
+
+    Ok(MailboxInfoResponse {
+        mailbox,
+        local_domain,
+    })
+}
+
+fn quote_transfer_remote<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    env: Env,
+    dest_domain: u32,
+    amount: Uint128,
+    recipient: HexBinary,
+) -> Result<QuoteTransferRemoteResponse, ContractError> {
+    let token = TOKEN.load(deps.storage)?;
+    let dispatch_msg = new_dispatch_msg(deps, dest_domain, recipient, amount, None, None, &token)?;
+
+    let quote = mailbox::quote_dispatch(
+        &deps.querier,
+        MAILBOX.load(deps.storage)?,
+        env.contract.address,
+        dispatch_msg.clone(),
+    )?;
+
+    Ok(QuoteTransferRemoteResponse::new(&dispatch_msg, quote))
+}
+
+pub fn migrate<C: CustomQuery>(
+    deps: DepsMut<'_, C>,
+    env: Env,
+    msg: MigrateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // routes deployed before decimal scaling don't know the decimals of their denom
+    if DECIMALS.may_load(deps.storage)?.is_none() {
+        let decimals = msg
+            .decimals
+            .ok_or_else(|| StdError::generic_err("decimals are required to migrate this route"))?;
+        DECIMALS.save(deps.storage, &decimals)?;
+    }
+
+    // routes enrolled before then were stored as bare addresses
+    migrate_routes(deps.storage, |domain, address: HexBinary| {
+        RemoteRouterConfig::from_legacy(&msg.remote_decimals, domain, address)
+    })?;
+
+    // routes deployed before escrow tracking hold their primary collateral untracked
+    if MODE.load(deps.storage)? == TokenMode::Collateral {
+        let token = TOKEN.load(deps.storage)?;
+
+        if !ESCROW.has(deps.storage, &token) {
+            let balance = deps.querier.query_balance(env.contract.address, &token)?;
+            ESCROW.save(deps.storage, &token, &balance.amount)?;
+        }
+    }
+
+    // routes deployed before pausing start released
+    if hpl_pausable::get_pause_info(deps.storage).is_err() {
+        hpl_pausable::initialize(deps.storage, &false)?;
+    }
+
     Ok(Response::new())
 }
 
+/// Entry points of the shipped wasm, which doesn't use custom queries
+#[cfg(not(feature = "library"))]
+pub mod entry {
+    use cosmwasm_std::{
+        entry_point, Deps, DepsMut, Env, MessageInfo, QueryResponse, Reply, Response,
+    };
+    use hpl_interface::warp::native::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
+
+    use crate::error::ContractError;
+
+    #[entry_point]
+    pub fn instantiate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        super::instantiate(deps, env, info, msg)
+    }
+
+    #[entry_point]
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        super::execute(deps, env, info, msg)
+    }
+
+    #[entry_point]
+    pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+        super::sudo(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        super::reply(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, ContractError> {
+        super::query(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+        super::migrate(deps, env, msg)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{
-        coin,
+        coin, from_json,
         testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
-        Coin, OwnedDeps, Uint128,
+        Addr, Binary, Coin, ContractResult, OwnedDeps, SubMsgResponse, SubMsgResult, SystemResult,
+        Uint128, WasmMsg, WasmQuery,
     };
     use hpl_interface::{
         build_test_executor, build_test_querier,
+        connection::ConnectionMsg,
         core::HandleMsg,
+        hook::QuoteDispatchResponse,
+        pausable::PausableMsg,
         router::DomainRouteSet,
-        warp::native::{Metadata, NativeModeBriged, NativeModeCollateral},
+        testing::padded,
+        warp::{
+            native::{Metadata, NativeModeBriged, NativeModeCollateral},
+            payload::PayloadError,
+            IbcForward, RemoteRouterDecimals, IBC_FORWARD_EXTENSION_V1,
+        },
     };
     use hpl_router::set_route;
-    use ibcx_test_utils::{addr, gen_bz};
+    use ibcx_test_utils::{addr, gen_addr, gen_bz};
+    use prost::Message;
     use rstest::{fixture, rstest};
 
     use super::*;
+    use crate::proto::{self, TOKEN_FACTORY_PACKAGE};
 
     build_test_executor!(super::execute);
     build_test_querier!(super::query);
@@ -314,6 +1255,37 @@ mod test {
     const OWNER: &str = "owner";
     const MAILBOX: &str = "mailbox";
     const DENOM: &str = "utest";
+    const DECIMALS: u8 = 6;
+    const ESCROWED: u128 = 1_000_000_000;
+    const LOCAL_DOMAIN: u32 = 26657;
+
+    fn remote_router(address: HexBinary, decimals: u8) -> Option<RemoteRouterConfig> {
+        Some(RemoteRouterConfig {
+            address: address.into(),
+            decimals,
+        })
+    }
+
+    fn mock_quote(deps: &mut TestDeps, fees: Vec<Coin>) {
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == MAILBOX => {
+                let res = match from_json(msg).unwrap() {
+                    mailbox::QueryMsg::Hook(mailbox::MailboxHookQueryMsg::QuoteDispatch {
+                        ..
+                    }) => to_json_binary(&QuoteDispatchResponse { fees: fees.clone() }),
+                    mailbox::QueryMsg::Mailbox(mailbox::MailboxQueryMsg::LocalDomain {}) => {
+                        to_json_binary(&mailbox::LocalDomainResponse {
+                            local_domain: LOCAL_DOMAIN,
+                        })
+                    }
+                    _ => unreachable!("unexpected mailbox query"),
+                };
+
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            }
+            _ => unreachable!("unexpected query"),
+        });
+    }
 
     #[fixture]
     fn metadata(#[default(true)] empty: bool) -> Option<Metadata> {
@@ -358,7 +1330,8 @@ mod test {
             mock_env(),
             mock_info(DEPLOYER, &[]),
             super::InstantiateMsg {
-                token: token_mode,
+                token: token_mode.clone(),
+                decimals: DECIMALS,
                 hrp: hrp.into(),
                 owner: OWNER.into(),
                 mailbox: MAILBOX.into(),
@@ -366,6 +1339,15 @@ mod test {
         )
         .unwrap();
 
+        // collateral released by handled messages was escrowed by earlier transfers
+        if let TokenModeMsg::Collateral(_) = token_mode {
+            super::ESCROW
+                .save(deps.as_mut().storage, DENOM, &Uint128::new(ESCROWED))
+                .unwrap();
+        }
+
+        mock_quote(&mut deps, vec![]);
+
         deps
     }
 
@@ -413,6 +1395,7 @@ mod test {
             mock_info(DEPLOYER, &[]),
             super::InstantiateMsg {
                 token: token_mode.clone(),
+                decimals: DECIMALS,
                 hrp: hrp.into(),
                 owner: OWNER.into(),
                 mailbox: MAILBOX.into(),
@@ -427,9 +1410,26 @@ mod test {
             token_mode.clone().into()
         );
         assert_eq!(super::MAILBOX.load(storage).unwrap(), MAILBOX);
+        assert_eq!(super::DECIMALS.load(storage).unwrap(), DECIMALS);
+
+        let data: warp::InstantiateResponse = from_json(res.data.as_ref().unwrap()).unwrap();
+        assert_eq!(
+            data,
+            warp::InstantiateResponse {
+                mailbox: addr(MAILBOX),
+                owner: addr(OWNER),
+            }
+        );
 
         match token_mode {
             TokenModeMsg::Bridged(v) => {
+                // created through the chain's TokenFactory module
+                assert!(matches!(
+                    &res.messages[0].msg,
+                    CosmosMsg::Stargate { type_url, .. }
+                        if *type_url == format!("/{TOKEN_FACTORY_PACKAGE}.MsgCreateDenom")
+                ));
+
                 if v.metadata.is_some() {
                     assert_eq!(res.messages.len(), 2);
                 } else {
@@ -447,25 +1447,27 @@ mod test {
     #[case(MAILBOX, 1, gen_bz(32))]
     #[should_panic(expected = "unauthorized")]
     #[case(OWNER, 1, gen_bz(32))]
-    #[should_panic(expected = "route not found")]
+    #[should_panic(expected = "route not found for domain 2")]
     #[case(MAILBOX, 2, gen_bz(32))]
     fn test_mailbox_handle(
         mut deps: TestDeps,
+        #[values(PayloadVersion::V0, PayloadVersion::V1)] version: PayloadVersion,
         #[case] sender: &str,
         #[case] origin_domain: u32,
         #[case] origin_sender: HexBinary,
     ) {
         let recipient = gen_bz(32);
+        let warp_msg = warp::Message {
+            recipient: recipient.clone(),
+            amount: Uint256::from_u128(100),
+            metadata: HexBinary::default(),
+        };
 
+        // every version is accepted, whatever the route sends itself
         let handle_msg = HandleMsg {
             origin: origin_domain,
             sender: origin_sender.clone(),
-            body: warp::Message {
-                recipient: recipient.clone(),
-                amount: Uint256::from_u128(100),
-                metadata: HexBinary::default(),
-            }
-            .into(),
+            body: Payload::new(version, warp_msg).encode().unwrap(),
         };
 
         set_route(
@@ -473,7 +1475,7 @@ mod test {
             &addr(OWNER),
             DomainRouteSet {
                 domain: 1,
-                route: Some(origin_sender),
+                route: remote_router(origin_sender, DECIMALS),
             },
         )
         .unwrap();
@@ -510,7 +1512,7 @@ mod test {
     #[rstest]
     #[case(1, gen_bz(32), gen_bz(32), vec![coin(100, DENOM)])]
     #[case(1, gen_bz(32), gen_bz(32), vec![coin(100, DENOM), coin(100, "uatom")])]
-    #[should_panic(expected = "route not found")]
+    #[should_panic(expected = "route not found for domain 2")]
     #[case(2, gen_bz(32), gen_bz(32), vec![coin(100, DENOM)])]
     #[should_panic(expected = "no funds sent")]
     #[case(1, gen_bz(32), gen_bz(32), vec![])]
@@ -528,7 +1530,7 @@ mod test {
             &addr(OWNER),
             DomainRouteSet {
                 domain: 1,
-                route: Some(dest_router.clone()),
+                route: remote_router(dest_router.clone(), DECIMALS),
             },
         )
         .unwrap();
@@ -540,6 +1542,8 @@ mod test {
                 dest_domain,
                 recipient: dest_recipient.clone(),
                 amount: Uint128::new(50),
+                min_amount: None,
+                denom: None,
             },
             funds.clone(),
         );
@@ -580,4 +1584,1952 @@ mod test {
             assert!(msgs.is_empty());
         }
     }
+
+    #[rstest]
+    #[case(DECIMALS, 100, 100)]
+    #[case(18, 100, 100_000_000_000_000)]
+    #[case(2, 100_000_000, 10_000)]
+    #[should_panic(expected = "loses precision on remote")]
+    #[case(2, 100_000_001, 0)]
+    fn test_transfer_remote_decimals(
+        mut deps: TestDeps,
+        #[case] remote_decimals: u8,
+        #[case] amount: u128,
+        #[case] expected: u128,
+    ) {
+        let dest_router = gen_bz(32);
+        let dest_recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(dest_router.clone(), remote_decimals),
+            },
+        )
+        .unwrap();
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: dest_recipient.clone(),
+                amount: Uint128::new(amount),
+                min_amount: None,
+                denom: None,
+            },
+            vec![coin(amount, DENOM)],
+        );
+
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            mailbox::dispatch(
+                MAILBOX,
+                1,
+                dest_router,
+                warp::Message {
+                    recipient: dest_recipient,
+                    amount: Uint256::from_u128(expected),
+                    metadata: HexBinary::default(),
+                }
+                .into(),
+                None,
+                None,
+                vec![coin(0, DENOM)],
+            )
+            .unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(40)]
+    #[case(50)]
+    #[should_panic(expected = "invalid min amount")]
+    #[case(51)]
+    fn test_transfer_remote_min_amount(mut deps: TestDeps, #[case] min_amount: u128) {
+        let dest_router = gen_bz(32);
+        let dest_recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(dest_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        let sender = gen_addr("osmo");
+        let res = test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: dest_recipient.clone(),
+                amount: Uint128::new(50),
+                min_amount: Some(Uint128::new(min_amount)),
+                denom: None,
+            },
+            vec![coin(50, DENOM)],
+        );
+
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            mailbox::dispatch(
+                MAILBOX,
+                1,
+                dest_router,
+                warp::Message {
+                    recipient: dest_recipient,
+                    amount: Uint256::from_u128(50),
+                    metadata: HexBinary::default(),
+                }
+                .with_min_amount(Uint256::from_u128(min_amount))
+                .with_refund_recipient(bech32_to_h256(sender.as_str()).unwrap())
+                .into(),
+                None,
+                None,
+                vec![coin(0, DENOM)],
+            )
+            .unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(99))]
+    #[case(Some(100))]
+    #[should_panic(expected = "received amount is below min amount")]
+    #[case(Some(101))]
+    fn test_mailbox_handle_min_amount(mut deps: TestDeps, #[case] min_amount: Option<u128>) {
+        let origin_router = gen_bz(32);
+        let recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(origin_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        let mut token_msg = warp::Message {
+            recipient: recipient.clone(),
+            amount: Uint256::from_u128(100),
+            metadata: HexBinary::default(),
+        };
+        if let Some(min_amount) = min_amount {
+            token_msg = token_msg.with_min_amount(Uint256::from_u128(min_amount));
+        }
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: origin_router,
+                body: token_msg.into(),
+            }),
+            vec![],
+        );
+
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            conv::to_send_msg(
+                &bech32_encode("osmo", recipient.as_slice()).unwrap(),
+                vec![coin(100, DENOM)]
+            )
+            .into()
+        );
+    }
+
+    #[rstest]
+    fn test_mailbox_handle_min_amount_refund(mut deps: TestDeps) {
+        let origin_router = gen_bz(32);
+        let refund_recipient = [7u8; 32];
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(origin_router.clone(), 18),
+            },
+        )
+        .unwrap();
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: origin_router.clone(),
+                body: warp::Message {
+                    recipient: gen_bz(32),
+                    amount: Uint256::from_u128(100),
+                    metadata: HexBinary::default(),
+                }
+                .with_min_amount(Uint256::from_u128(101))
+                .with_refund_recipient(refund_recipient)
+                .into(),
+            }),
+            vec![],
+        );
+
+        // nothing is released, the whole amount goes back in the origin decimals
+        let refund_msg = warp::Message {
+            recipient: refund_recipient.to_vec().into(),
+            amount: warp::scale_amount(100u128.into(), DECIMALS, 18).unwrap(),
+            metadata: HexBinary::default(),
+        };
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            mailbox::dispatch(
+                MAILBOX,
+                1,
+                origin_router,
+                refund_msg.into(),
+                None,
+                None,
+                vec![]
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            super::ESCROW.load(deps.as_ref().storage, DENOM).unwrap(),
+            Uint128::new(ESCROWED)
+        );
+    }
+
+    #[rstest]
+    #[case(token_mode_collateral(), vec![coin(10, DENOM)])]
+    #[case(token_mode_bridged(metadata(true)), vec![coin(10, DENOM)])]
+    #[should_panic(expected = "[E510] refund can't cover its hook fees")]
+    #[case(token_mode_collateral(), vec![coin(10, "uosmo")])]
+    #[should_panic(expected = "[E510] refund can't cover its hook fees")]
+    #[case(token_mode_collateral(), vec![coin(100, DENOM)])]
+    #[should_panic(expected = "[E510] refund can't cover its hook fees")]
+    #[case(token_mode_collateral(), vec![coin(10, DENOM), coin(10, "uosmo")])]
+    fn test_mailbox_handle_min_amount_refund_fee(
+        #[case] token_mode: NativeTokenMode,
+        #[case] fees: Vec<Coin>,
+    ) {
+        let bridged = TokenMode::from(token_mode.clone()) == TokenMode::Bridged;
+        let mut deps = deps(token_mode, "osmo");
+        if bridged {
+            super::TOKEN
+                .save(deps.as_mut().storage, &DENOM.into())
+                .unwrap();
+        }
+        mock_quote(&mut deps, fees.clone());
+
+        let origin_router = gen_bz(32);
+        let refund_recipient = [7u8; 32];
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(origin_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: origin_router.clone(),
+                body: warp::Message {
+                    recipient: gen_bz(32),
+                    amount: Uint256::from_u128(100),
+                    metadata: HexBinary::default(),
+                }
+                .with_min_amount(Uint256::from_u128(101))
+                .with_refund_recipient(refund_recipient)
+                .into(),
+            }),
+            vec![],
+        );
+
+        // the fee is paid out of the refunded tokens
+        let refund_msg = warp::Message {
+            recipient: refund_recipient.to_vec().into(),
+            amount: Uint256::from_u128(90),
+            metadata: HexBinary::default(),
+        };
+        let dispatch_msg = mailbox::dispatch(
+            MAILBOX,
+            1,
+            origin_router,
+            refund_msg.into(),
+            None,
+            None,
+            fees,
+        )
+        .unwrap();
+
+        let msgs: Vec<_> = res.messages.into_iter().map(|v| v.msg).collect();
+        if bridged {
+            let mint_msg = conv::to_mint_msg(&mock_env().contract.address, DENOM, 10u128);
+            assert_eq!(msgs, vec![mint_msg.into(), dispatch_msg]);
+        } else {
+            assert_eq!(msgs, vec![dispatch_msg]);
+            assert_eq!(
+                super::ESCROW.load(deps.as_ref().storage, DENOM).unwrap(),
+                Uint128::new(ESCROWED - 10)
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(gen_bz(20))]
+    #[case(gen_bz(31))]
+    #[case([vec![IBC_FORWARD_EXTENSION_V1], gen_bz(99).to_vec()].concat().into())]
+    fn test_mailbox_handle_foreign_metadata(mut deps: TestDeps, #[case] metadata: HexBinary) {
+        let origin_router = gen_bz(32);
+        let recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(origin_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        // metadata of other routers is delivered without a min amount, forward or call
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: origin_router,
+                body: warp::Message {
+                    recipient: recipient.clone(),
+                    amount: Uint256::from_u128(100),
+                    metadata,
+                }
+                .into(),
+            }),
+            vec![],
+        );
+
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            conv::to_send_msg(
+                &bech32_encode("osmo", recipient.as_slice()).unwrap(),
+                vec![coin(100, DENOM)]
+            )
+            .into()
+        );
+    }
+
+    #[rstest]
+    #[case(None)]
+    #[case(Some(Binary::from(b"{\"deposit\":{}}".to_vec())))]
+    fn test_mailbox_handle_call(mut deps: TestDeps, #[case] call: Option<Binary>) {
+        let origin_router = gen_bz(32);
+        let recipient = gen_bz(32);
+        let recipient_addr = bech32_encode("osmo", recipient.as_slice()).unwrap();
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(origin_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        let token_msg = warp::Message {
+            recipient,
+            amount: Uint256::from_u128(100),
+            metadata: HexBinary::default(),
+        };
+        let mut payload = Payload::new(PayloadVersion::V1, token_msg);
+        if let Some(call) = call.clone() {
+            payload = payload.with_call(call).unwrap();
+        }
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: origin_router.clone(),
+                body: payload.encode().unwrap(),
+            }),
+            vec![],
+        );
+        let msgs: Vec<_> = res.messages.into_iter().map(|v| v.msg).collect();
+
+        let mut expected: Vec<CosmosMsg> =
+            vec![conv::to_send_msg(&recipient_addr, vec![coin(100, DENOM)]).into()];
+        if let Some(call) = call {
+            // delivered through `HandleCall`, never as a raw execute
+            let call = HandleCallMsg {
+                origin: 1,
+                sender: origin_router,
+                amount: Uint256::from_u128(100),
+                call,
+            };
+            expected.push(
+                wasm_execute(&recipient_addr, &call.wrap(), vec![])
+                    .unwrap()
+                    .into(),
+            );
+        }
+
+        assert_eq!(msgs, expected);
+    }
+
+    #[rstest]
+    #[case::mailbox("mailbox")]
+    #[case::route("route")]
+    fn test_mailbox_handle_call_recipient(mut deps: TestDeps, #[case] target: &str) {
+        let origin_router = gen_bz(32);
+        let recipient = gen_bz(32);
+        let recipient_addr = bech32_encode("osmo", recipient.as_slice()).unwrap();
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(origin_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        // the recipient is one of the contracts the route has authority over
+        let mut env = mock_env();
+        match target {
+            "mailbox" => super::MAILBOX
+                .save(deps.as_mut().storage, &recipient_addr)
+                .unwrap(),
+            _ => env.contract.address = recipient_addr.clone(),
+        }
+        let mailbox = super::MAILBOX.load(deps.as_ref().storage).unwrap();
+
+        let token_msg = warp::Message {
+            recipient,
+            amount: Uint256::from_u128(100),
+            metadata: HexBinary::default(),
+        };
+        let payload = Payload::new(PayloadVersion::V1, token_msg);
+        let handle = |payload: Payload| {
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: origin_router.clone(),
+                body: payload.encode().unwrap(),
+            })
+        };
+
+        let call = Binary::from(b"{\"dispatch\":{}}".to_vec());
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(mailbox.as_str(), &[]),
+            handle(payload.clone().with_call(call).unwrap()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidCallRecipient {
+                recipient: recipient_addr.to_string()
+            }
+        );
+
+        // plain transfers aren't affected
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(mailbox.as_str(), &[]),
+            handle(payload),
+        )
+        .unwrap();
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(DEPLOYER))]
+    fn test_set_ibc_forward(mut deps: TestDeps, #[case] sender: Addr) {
+        let res: IbcForwardConfigResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::IbcForward(IbcForwardQueryMsg::IbcForwardConfig {}),
+        );
+        assert!(!res.enabled);
+        assert!(res.channels.is_empty());
+
+        test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetIbcForwardEnabled { enabled: true },
+            vec![],
+        );
+        for (channel, allowed) in [
+            ("channel-0", true),
+            ("channel-1", true),
+            ("channel-0", false),
+        ] {
+            test_execute(
+                deps.as_mut(),
+                &sender,
+                ExecuteMsg::SetIbcForwardChannel {
+                    channel: channel.into(),
+                    allowed,
+                },
+                vec![],
+            );
+        }
+
+        let res: IbcForwardConfigResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::IbcForward(IbcForwardQueryMsg::IbcForwardConfig {}),
+        );
+        assert!(res.enabled);
+        assert_eq!(res.channels, vec!["channel-1".to_string()]);
+    }
+
+    #[rstest]
+    #[case(true, Some("channel-0"), 600, true)]
+    #[case(false, Some("channel-0"), 600, false)]
+    #[case(true, None, 600, false)]
+    #[case(true, Some("channel-1"), 600, false)]
+    #[case(true, Some("channel-0"), u64::MAX, false)]
+    fn test_mailbox_handle_ibc_forward(
+        mut deps: TestDeps,
+        #[case] enabled: bool,
+        #[case] channel: Option<&str>,
+        #[case] timeout_seconds: u64,
+        #[case] forwarded: bool,
+    ) {
+        let origin_router = gen_bz(32);
+        let recipient = gen_bz(32);
+        let recipient_addr = bech32_encode("osmo", recipient.as_slice()).unwrap();
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(origin_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetIbcForwardEnabled { enabled },
+            vec![],
+        );
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetIbcForwardChannel {
+                channel: "channel-0".into(),
+                allowed: true,
+            },
+            vec![],
+        );
+
+        let token_msg = warp::Message {
+            recipient,
+            amount: Uint256::from_u128(100),
+            metadata: HexBinary::default(),
+        };
+        let forward = channel.map(|v| IbcForward {
+            ibc_channel: v.into(),
+            final_recipient: "osmo1finalrecipient".into(),
+            timeout_seconds,
+        });
+        let payload = Payload::new(PayloadVersion::V1, token_msg);
+        let payload = match &forward {
+            Some(forward) => payload.with_ibc_forward(forward).unwrap(),
+            // undecodable forward extension
+            None => payload
+                .with_extension(IBC_FORWARD_EXTENSION_V1, vec![0xff])
+                .unwrap(),
+        };
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: origin_router,
+                body: payload.encode().unwrap(),
+            }),
+            vec![],
+        );
+        let msgs: Vec<_> = res.messages.into_iter().map(|v| v.msg).collect();
+
+        let expected: CosmosMsg = if forwarded {
+            conv::to_ibc_transfer_msg(
+                &mock_env().contract.address,
+                forward.as_ref().unwrap(),
+                coin(100, DENOM),
+                mock_env().block.time.plus_seconds(timeout_seconds),
+            )
+            .into()
+        } else {
+            conv::to_send_msg(&recipient_addr, vec![coin(100, DENOM)]).into()
+        };
+        assert_eq!(msgs, vec![expected]);
+
+        // forwards wait on the sequence of their transfer
+        assert_eq!(
+            super::PENDING_IBC_FORWARD
+                .may_load(deps.as_ref().storage)
+                .unwrap(),
+            forwarded.then(|| IbcForwardRecord {
+                channel: "channel-0".into(),
+                recipient: recipient_addr,
+                amount: coin(100, DENOM),
+            })
+        );
+    }
+
+    #[rstest]
+    #[case(IbcLifecycleComplete::IbcAck {
+        channel: "channel-0".into(),
+        sequence: 7,
+        ack: "".into(),
+        success: true,
+    }, false)]
+    #[case(IbcLifecycleComplete::IbcAck {
+        channel: "channel-0".into(),
+        sequence: 7,
+        ack: "".into(),
+        success: false,
+    }, true)]
+    #[case(IbcLifecycleComplete::IbcTimeout {
+        channel: "channel-0".into(),
+        sequence: 7,
+    }, true)]
+    #[should_panic(expected = "not found")]
+    #[case(IbcLifecycleComplete::IbcTimeout {
+        channel: "channel-1".into(),
+        sequence: 7,
+    }, false)]
+    fn test_ibc_forward_lifecycle(
+        mut deps: TestDeps,
+        #[case] complete: IbcLifecycleComplete,
+        #[case] refunded: bool,
+    ) {
+        let recipient = gen_addr("osmo");
+        let forward = IbcForwardRecord {
+            channel: "channel-0".into(),
+            recipient: recipient.clone(),
+            amount: coin(100, DENOM),
+        };
+
+        super::PENDING_IBC_FORWARD
+            .save(deps.as_mut().storage, &forward)
+            .unwrap();
+
+        super::reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: REPLY_ID_IBC_FORWARD,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(
+                        proto::MsgTransferResponse { sequence: 7 }
+                            .encode_to_vec()
+                            .into(),
+                    ),
+                }),
+            },
+        )
+        .unwrap();
+
+        assert!(!super::PENDING_IBC_FORWARD.exists(deps.as_ref().storage));
+        assert_eq!(
+            IBC_FORWARDS
+                .load(deps.as_ref().storage, ("channel-0", 7))
+                .unwrap(),
+            forward
+        );
+
+        let res = super::sudo(
+            deps.as_mut(),
+            mock_env(),
+            SudoMsg::IbcLifecycleComplete(complete),
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        // failed forwards are sent on to the local recipient
+        let msgs: Vec<_> = res.messages.into_iter().map(|v| v.msg).collect();
+        let expected: Vec<CosmosMsg> = if refunded {
+            vec![conv::to_send_msg(&recipient, vec![coin(100, DENOM)]).into()]
+        } else {
+            vec![]
+        };
+        assert_eq!(msgs, expected);
+        assert!(!IBC_FORWARDS.has(deps.as_ref().storage, ("channel-0", 7)));
+    }
+
+    /// body of the message dispatched by a transfer
+    fn dispatched_body(res: &Response) -> HexBinary {
+        let CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) = &res.messages.last().unwrap().msg
+        else {
+            unreachable!("expected a dispatch")
+        };
+
+        let mailbox::ExecuteMsg::Dispatch(dispatch) = from_json(msg).unwrap() else {
+            unreachable!("expected a dispatch")
+        };
+
+        dispatch.msg_body
+    }
+
+    #[rstest]
+    #[case(18)]
+    #[case(6)]
+    #[case(2)]
+    fn test_decimals_per_route(
+        mut deps: TestDeps,
+        #[from(deps)] mut remote: TestDeps,
+        #[case] remote_decimals: u8,
+    ) {
+        let remote_domain = 1;
+        let local_route = gen_bz(32);
+        let remote_route = gen_bz(32);
+        let recipient = gen_bz(32);
+
+        super::DECIMALS
+            .save(remote.as_mut().storage, &remote_decimals)
+            .unwrap();
+
+        for (deps, domain, address, decimals) in [
+            (&mut deps, remote_domain, &remote_route, remote_decimals),
+            (&mut remote, LOCAL_DOMAIN, &local_route, DECIMALS),
+        ] {
+            set_route(
+                deps.as_mut().storage,
+                &addr(OWNER),
+                DomainRouteSet {
+                    domain,
+                    route: remote_router(address.clone(), decimals),
+                },
+            )
+            .unwrap();
+        }
+
+        // one whole token leaves the remote chain
+        let whole = 10u128.pow(remote_decimals.into());
+        let res = test_execute(
+            remote.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: LOCAL_DOMAIN,
+                recipient: recipient.clone(),
+                amount: Uint128::new(whole),
+                min_amount: None,
+                denom: None,
+            },
+            vec![coin(whole, DENOM)],
+        );
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: remote_domain,
+                sender: remote_route,
+                body: dispatched_body(&res),
+            }),
+            vec![],
+        );
+
+        // and one whole token arrives in local decimals
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            conv::to_send_msg(
+                &bech32_encode("osmo", recipient.as_slice()).unwrap(),
+                vec![coin(10u128.pow(DECIMALS.into()), DENOM)]
+            )
+            .into()
+        );
+    }
+
+    #[rstest]
+    #[case(Some(DECIMALS), vec![RemoteRouterDecimals { domain: 1, decimals: 18 }])]
+    #[should_panic(expected = "decimals are required")]
+    #[case(None, vec![RemoteRouterDecimals { domain: 1, decimals: 18 }])]
+    #[should_panic(expected = "decimals of the remote router on domain 1 are required")]
+    #[case(Some(DECIMALS), vec![])]
+    fn test_migrate_legacy_routes(
+        mut deps: TestDeps,
+        #[case] decimals: Option<u8>,
+        #[case] remote_decimals: Vec<RemoteRouterDecimals>,
+    ) {
+        let route = gen_bz(32);
+
+        // routes and decimals as stored before decimal scaling
+        super::DECIMALS.remove(deps.as_mut().storage);
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: Some(route.clone()),
+            },
+        )
+        .unwrap();
+
+        super::migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                decimals,
+                remote_decimals,
+            },
+        )
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+        assert_eq!(
+            super::DECIMALS.load(deps.as_ref().storage).unwrap(),
+            DECIMALS
+        );
+        assert_eq!(
+            get_route::<RemoteRouterConfig>(deps.as_ref().storage, 1)
+                .unwrap()
+                .route,
+            remote_router(route, 18)
+        );
+    }
+
+    #[rstest]
+    #[case(
+        addr(OWNER),
+        RemoteAddress::Hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".into())
+    )]
+    #[case(
+        addr(OWNER),
+        RemoteAddress::Base64(Binary::from_base64("Wq62BT8+lMm5oJ8zZpQ15+8b6u0=").unwrap())
+    )]
+    #[case(
+        addr(OWNER),
+        RemoteAddress::Bech32("osmo1t2htvpfl862vnwdqnuekd9p4ulh3h6hdlvdhh0".into())
+    )]
+    #[should_panic(expected = "unauthorized")]
+    #[case(
+        addr(DEPLOYER),
+        RemoteAddress::Hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".into())
+    )]
+    fn test_enroll_remote_router(
+        mut deps: TestDeps,
+        #[case] sender: Addr,
+        #[case] address: RemoteAddress,
+    ) {
+        test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::EnrollRemoteRouter {
+                domain: 1,
+                address,
+                decimals: 18,
+            },
+            vec![],
+        );
+
+        // every format is stored as the same padded bytes
+        let route = get_route::<RemoteRouterConfig>(deps.as_ref().storage, 1)
+            .unwrap()
+            .route
+            .unwrap();
+        assert_eq!(
+            route.address.to_hex(),
+            "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        );
+        assert_eq!(route.decimals, 18);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(DEPLOYER))]
+    fn test_set_recipient_format(mut deps: TestDeps, #[case] sender: Addr) {
+        let res: warp::RecipientFormatResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RecipientFormat { domain: 1 }),
+        );
+        assert_eq!(res.format, RecipientFormat::None);
+
+        test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetRecipientFormat {
+                domain: 1,
+                format: RecipientFormat::Evm20,
+            },
+            vec![],
+        );
+
+        let res: warp::RecipientFormatResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RecipientFormat { domain: 1 }),
+        );
+        assert_eq!(res.format, RecipientFormat::Evm20);
+    }
+
+    #[rstest]
+    #[case(None, gen_bz(20))]
+    #[case(Some(RecipientFormat::None), gen_bz(20))]
+    #[case(Some(RecipientFormat::Evm20), padded(12, &[1u8; 20]))]
+    #[should_panic(expected = "invalid recipient format for domain 1. expected: evm20")]
+    #[case(Some(RecipientFormat::Evm20), padded(11, &[1u8; 21]))]
+    #[should_panic(expected = "invalid recipient format for domain 1. expected: evm20")]
+    #[case(Some(RecipientFormat::Evm20), padded(32, &[]))]
+    #[case(Some(RecipientFormat::Cosmos32), gen_bz(32))]
+    #[should_panic(expected = "invalid recipient format for domain 1. expected: cosmos32")]
+    #[case(Some(RecipientFormat::Cosmos32), padded(32, &[]))]
+    #[case(Some(RecipientFormat::Raw32), padded(32, &[]))]
+    #[should_panic(expected = "invalid recipient format for domain 1. expected: raw32")]
+    #[case(Some(RecipientFormat::Raw32), gen_bz(20))]
+    fn test_transfer_remote_recipient_format(
+        mut deps: TestDeps,
+        #[case] format: Option<RecipientFormat>,
+        #[case] recipient: HexBinary,
+    ) {
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(gen_bz(32), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        if let Some(format) = format {
+            test_execute(
+                deps.as_mut(),
+                &addr(OWNER),
+                ExecuteMsg::SetRecipientFormat { domain: 1, format },
+                vec![],
+            );
+        }
+
+        test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient,
+                amount: Uint128::new(50),
+                min_amount: None,
+                denom: None,
+            },
+            vec![coin(50, DENOM)],
+        );
+    }
+
+    #[rstest]
+    #[case(1, vec![coin(50, DENOM), coin(10, "ugas")])]
+    #[should_panic(expected = "gas payment required for domain 1")]
+    #[case(1, vec![coin(50, DENOM)])]
+    #[should_panic(expected = "gas payment required for domain 1")]
+    #[case(1, vec![coin(50, DENOM), coin(0, "ugas")])]
+    #[case(2, vec![coin(50, DENOM)])]
+    fn test_transfer_remote_gas_payment(
+        mut deps: TestDeps,
+        #[case] dest_domain: u32,
+        #[case] funds: Vec<Coin>,
+    ) {
+        for domain in [1, 2] {
+            set_route(
+                deps.as_mut().storage,
+                &addr(OWNER),
+                DomainRouteSet {
+                    domain,
+                    route: remote_router(gen_bz(32), DECIMALS),
+                },
+            )
+            .unwrap();
+        }
+
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetGasPaymentRequired {
+                domain: 1,
+                required: true,
+            },
+            vec![],
+        );
+
+        for (domain, expected) in [(1, true), (2, false)] {
+            let res: warp::GasPaymentRequiredResponse = test_query(
+                deps.as_ref(),
+                QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::GasPaymentRequired {
+                    domain,
+                }),
+            );
+            assert_eq!(res.required, expected);
+        }
+
+        test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain,
+                recipient: gen_bz(32),
+                amount: Uint128::new(50),
+                min_amount: None,
+                denom: None,
+            },
+            funds,
+        );
+    }
+
+    #[rstest]
+    // exact collateral and fee
+    #[case(
+        coin(10, "ugas"),
+        vec![coin(50, DENOM), coin(10, "ugas")],
+        vec![coin(0, DENOM), coin(10, "ugas")]
+    )]
+    // fee paid in the collateral denom
+    #[case(coin(10, DENOM), vec![coin(60, DENOM)], vec![coin(10, DENOM)])]
+    // surplus goes to the hook along with the fee
+    #[case(
+        coin(10, "ugas"),
+        vec![coin(55, DENOM), coin(15, "ugas"), coin(3, "uatom")],
+        vec![coin(5, DENOM), coin(15, "ugas"), coin(3, "uatom")]
+    )]
+    #[should_panic(expected = "insufficient hook payment")]
+    #[case(coin(10, "ugas"), vec![coin(50, DENOM)], vec![])]
+    #[should_panic(expected = "insufficient hook payment")]
+    #[case(coin(10, "ugas"), vec![coin(50, DENOM), coin(9, "ugas")], vec![])]
+    #[should_panic(expected = "insufficient hook payment")]
+    #[case(coin(10, DENOM), vec![coin(59, DENOM)], vec![])]
+    fn test_transfer_remote_hook_payment(
+        mut deps: TestDeps,
+        #[case] fee: Coin,
+        #[case] funds: Vec<Coin>,
+        #[case] forwarded: Vec<Coin>,
+    ) {
+        let dest_router = gen_bz(32);
+        let dest_recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(dest_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        mock_quote(&mut deps, vec![fee]);
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: dest_recipient.clone(),
+                amount: Uint128::new(50),
+                min_amount: None,
+                denom: None,
+            },
+            funds,
+        );
+
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            mailbox::dispatch(
+                MAILBOX,
+                1,
+                dest_router,
+                warp::Message {
+                    recipient: dest_recipient,
+                    amount: Uint256::from_u128(50),
+                    metadata: HexBinary::default(),
+                }
+                .into(),
+                None,
+                None,
+                forwarded,
+            )
+            .unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("someone"))]
+    fn test_transfer_remote_default_gas(mut deps: TestDeps, #[case] sender: Addr) {
+        let dest_routers = [gen_bz(32), gen_bz(32)];
+        for (domain, dest_router) in [1, 2].into_iter().zip(dest_routers.clone()) {
+            set_route(
+                deps.as_mut().storage,
+                &addr(OWNER),
+                DomainRouteSet {
+                    domain,
+                    route: remote_router(dest_router, DECIMALS),
+                },
+            )
+            .unwrap();
+        }
+
+        mock_quote(&mut deps, vec![]);
+
+        test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetDefaultGas {
+                domain: 1,
+                gas: Some(300_000),
+            },
+            vec![],
+        );
+
+        // only transfers to domain 1 carry the gas limit
+        let metadata: HexBinary = IGPMetadata {
+            gas_limit: Uint256::from_u128(300_000),
+            refund_address: HexBinary::default(),
+        }
+        .into();
+        let cases = [
+            (1, dest_routers[0].clone(), Some(metadata)),
+            (2, dest_routers[1].clone(), None),
+        ];
+
+        for (domain, dest_router, metadata) in cases {
+            let recipient = gen_bz(32);
+
+            let res: QuoteTransferRemoteResponse = test_query(
+                deps.as_ref(),
+                QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::QuoteTransferRemote {
+                    dest_domain: domain,
+                    amount: Uint128::new(50),
+                    recipient: recipient.clone(),
+                }),
+            );
+            assert_eq!(
+                res.gas_amount,
+                metadata.as_ref().map(|_| Uint256::from_u128(300_000))
+            );
+
+            let res = test_execute(
+                deps.as_mut(),
+                &addr("sender"),
+                ExecuteMsg::TransferRemote {
+                    dest_domain: domain,
+                    recipient: recipient.clone(),
+                    amount: Uint128::new(50),
+                    min_amount: None,
+                    denom: None,
+                },
+                vec![coin(50, DENOM)],
+            );
+
+            assert_eq!(
+                res.messages.last().unwrap().msg,
+                mailbox::dispatch(
+                    MAILBOX,
+                    domain,
+                    dest_router,
+                    warp::Message {
+                        recipient,
+                        amount: Uint256::from_u128(50),
+                        metadata: HexBinary::default(),
+                    }
+                    .into(),
+                    None,
+                    metadata,
+                    vec![coin(0, DENOM)],
+                )
+                .unwrap()
+            );
+        }
+    }
+
+    #[rstest]
+    fn test_transfer_remote_hook_payment_no_state_change(mut deps: TestDeps) {
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(gen_bz(32), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        mock_quote(&mut deps, vec![coin(10, "ugas")]);
+
+        let err = super::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[coin(50, DENOM)]),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: gen_bz(32),
+                amount: Uint128::new(50),
+                min_amount: None,
+                denom: None,
+            },
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::InsufficientHookPayment {
+                required: vec![coin(10, "ugas")],
+                provided: vec![coin(0, DENOM)],
+            }
+        );
+        assert_eq!(
+            ESCROW.load(deps.as_ref().storage, DENOM).unwrap(),
+            Uint128::new(ESCROWED)
+        );
+    }
+
+    #[rstest]
+    fn test_quote_transfer_remote(mut deps: TestDeps) {
+        let dest_router = gen_bz(32);
+        let dest_recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(dest_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        let fees = vec![coin(10, "ugas"), coin(5, DENOM)];
+        let expected = mailbox::DispatchMsg::new(
+            1,
+            dest_router,
+            warp::Message {
+                recipient: dest_recipient.clone(),
+                amount: Uint256::from_u128(50),
+                metadata: HexBinary::default(),
+            },
+        );
+
+        let quote_fees = fees.clone();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == MAILBOX => {
+                let mailbox::QueryMsg::Hook(mailbox::MailboxHookQueryMsg::QuoteDispatch {
+                    sender,
+                    msg,
+                }) = from_json(msg).unwrap()
+                else {
+                    unreachable!("unexpected mailbox query")
+                };
+
+                // quoted as the dispatch `TransferRemote` would send
+                assert_eq!(sender, mock_env().contract.address.as_str());
+                assert_eq!(msg, expected);
+
+                let res = QuoteDispatchResponse {
+                    fees: quote_fees.clone(),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+            }
+            _ => unreachable!("unexpected query"),
+        });
+
+        let res: warp::QuoteTransferRemoteResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::QuoteTransferRemote {
+                dest_domain: 1,
+                amount: Uint128::new(50),
+                recipient: dest_recipient,
+            }),
+        );
+
+        assert_eq!(res.gas_amount, None);
+        assert_eq!(res.fees, fees);
+    }
+
+    fn supported_denoms(deps: Deps) -> SupportedDenomsResponse {
+        test_query(deps, QueryMsg::Denoms(DenomsQueryMsg::SupportedDenoms {}))
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr(DEPLOYER))]
+    fn test_set_supported_denom(mut deps: TestDeps, #[case] sender: Addr) {
+        let res = supported_denoms(deps.as_ref());
+        assert_eq!(res.denoms, vec![DENOM.to_string()]);
+        assert_eq!(res.escrow, vec![coin(ESCROWED, DENOM)]);
+
+        for (denom, supported) in [("uatom", true), ("uosmo", true), ("uosmo", false)] {
+            test_execute(
+                deps.as_mut(),
+                &sender,
+                ExecuteMsg::SetSupportedDenom {
+                    denom: denom.into(),
+                    supported,
+                },
+                vec![],
+            );
+        }
+
+        let res = supported_denoms(deps.as_ref());
+        assert_eq!(res.denoms, vec![DENOM.to_string(), "uatom".to_string()]);
+        assert_eq!(res.escrow, vec![coin(ESCROWED, DENOM), coin(0, "uatom")]);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "unsupported denom uatom")]
+    fn test_set_supported_denom_bridged(
+        #[with(token_mode_bridged(metadata(true)))] mut deps: TestDeps,
+    ) {
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetSupportedDenom {
+                denom: "uatom".into(),
+                supported: true,
+            },
+            vec![],
+        );
+    }
+
+    #[rstest]
+    #[case(None, DENOM, PayloadVersion::V0)]
+    #[case(Some(DENOM), DENOM, PayloadVersion::V0)]
+    #[case(Some("uatom"), "uatom", PayloadVersion::V1)]
+    #[should_panic(expected = "v0 payloads can't carry extensions")]
+    #[case(Some("uatom"), "uatom", PayloadVersion::V0)]
+    #[should_panic(expected = "unsupported denom uosmo")]
+    #[case(Some("uosmo"), "uosmo", PayloadVersion::V1)]
+    fn test_transfer_remote_denoms(
+        mut deps: TestDeps,
+        #[case] denom: Option<&str>,
+        #[case] expected_denom: &str,
+        #[case] version: PayloadVersion,
+    ) {
+        let dest_router = gen_bz(32);
+        let dest_recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(dest_router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetSupportedDenom {
+                denom: "uatom".into(),
+                supported: true,
+            },
+            vec![],
+        );
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetPayloadVersion { version },
+            vec![],
+        );
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: dest_recipient.clone(),
+                amount: Uint128::new(50),
+                min_amount: None,
+                denom: denom.map(|v| v.into()),
+            },
+            vec![coin(50, expected_denom), coin(10, "ugas")],
+        );
+
+        let mut payload = Payload::new(
+            version,
+            warp::Message {
+                recipient: dest_recipient,
+                amount: Uint256::from_u128(50),
+                metadata: HexBinary::default(),
+            },
+        );
+        if expected_denom != DENOM {
+            payload = payload.with_denom(expected_denom).unwrap();
+        }
+
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            mailbox::dispatch(
+                MAILBOX,
+                1,
+                dest_router,
+                payload.encode().unwrap(),
+                None,
+                None,
+                vec![coin(0, expected_denom), coin(10, "ugas")],
+            )
+            .unwrap()
+        );
+
+        // only the sent denom is escrowed
+        let escrow = supported_denoms(deps.as_ref()).escrow;
+        let expected_escrow = if expected_denom == DENOM {
+            vec![coin(ESCROWED + 50, DENOM), coin(0, "uatom")]
+        } else {
+            vec![coin(ESCROWED, DENOM), coin(50, "uatom")]
+        };
+        assert_eq!(escrow, expected_escrow);
+    }
+
+    #[rstest]
+    #[case("uatom", 50)]
+    #[should_panic(expected = "insufficient escrow for denom uatom")]
+    #[case("uatom", 51)]
+    #[should_panic(expected = "unsupported denom uosmo")]
+    #[case("uosmo", 50)]
+    fn test_mailbox_handle_denoms(mut deps: TestDeps, #[case] denom: &str, #[case] amount: u128) {
+        let router = gen_bz(32);
+        let recipient = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetSupportedDenom {
+                denom: "uatom".into(),
+                supported: true,
+            },
+            vec![],
+        );
+
+        // escrow 50uatom through an outbound transfer
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetPayloadVersion {
+                version: PayloadVersion::V1,
+            },
+            vec![],
+        );
+        test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: gen_bz(32),
+                amount: Uint128::new(50),
+                min_amount: None,
+                denom: Some("uatom".into()),
+            },
+            vec![coin(50, "uatom")],
+        );
+
+        let res = test_execute(
+            deps.as_mut(),
+            &addr(MAILBOX),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: router,
+                body: Payload::new(
+                    PayloadVersion::V1,
+                    warp::Message {
+                        recipient: recipient.clone(),
+                        amount: Uint256::from_u128(amount),
+                        metadata: HexBinary::default(),
+                    },
+                )
+                .with_denom(denom)
+                .unwrap()
+                .encode()
+                .unwrap(),
+            }),
+            vec![],
+        );
+
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            conv::to_send_msg(
+                &bech32_encode("osmo", recipient.as_slice()).unwrap(),
+                vec![coin(amount, denom)]
+            )
+            .into()
+        );
+
+        // the primary denom's escrow is left untouched
+        let escrow = supported_denoms(deps.as_ref()).escrow;
+        assert_eq!(
+            escrow,
+            vec![coin(ESCROWED, DENOM), coin(50 - amount, "uatom")]
+        );
+    }
+
+    #[rstest]
+    fn test_mailbox_info(mut deps: TestDeps) {
+        mock_quote(&mut deps, vec![]);
+
+        let res: warp::MailboxInfoResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::MailboxInfo {}),
+        );
+        assert_eq!(res.mailbox, addr(MAILBOX));
+        assert_eq!(res.local_domain, LOCAL_DOMAIN);
+    }
+
+    #[rstest]
+    fn test_ism(mut deps: TestDeps) {
+        let res: InterchainSecurityModuleResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::Ism {}),
+        );
+        assert_eq!(res.ism, None);
+
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::Connection(ConnectionMsg::SetIsm {
+                ism: "ism".to_string(),
+            }),
+            vec![],
+        );
+
+        let res: InterchainSecurityModuleResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::Ism {}),
+        );
+        assert_eq!(res.ism, Some(addr("ism")));
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("someone"))]
+    fn test_router_decoded(mut deps: TestDeps, #[case] sender: Addr) {
+        let evm_route = HexBinary::from_hex("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+        let cosmos_route = gen_bz(32);
+        for (domain, route) in [(1, evm_route), (2, cosmos_route.clone())] {
+            set_route(
+                deps.as_mut().storage,
+                &addr(OWNER),
+                DomainRouteSet {
+                    domain,
+                    route: remote_router(route, DECIMALS),
+                },
+            )
+            .unwrap();
+        }
+
+        let res = test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetDomainHrp {
+                domain: 2,
+                hrp: Some("neutron".to_string()),
+            },
+            vec![],
+        );
+        assert_eq!(
+            res.events,
+            vec![new_event("set-domain-hrp")
+                .add_attribute("sender", sender.as_str())
+                .add_attribute("domain", "2")
+                .add_attribute("hrp", "neutron")]
+        );
+
+        let res: RouteResponse<DecodedRoute> = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RouterDecoded { domain: 1 }),
+        );
+        let route = res.route.route.unwrap();
+        assert_eq!(
+            route.evm.as_deref(),
+            Some("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+        );
+        assert_eq!(route.bech32, None);
+
+        let res: RoutesResponse<DecodedRoute> = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RoutersDecoded {}),
+        );
+        assert_eq!(res.routes.len(), 2);
+        assert_eq!(res.routes[1].domain, 2);
+        assert_eq!(
+            res.routes[1].route,
+            Some(DecodedRoute::new(&cosmos_route, Some("neutron")).unwrap())
+        );
+
+        // without an hrp the address is only given as bytes
+        test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetDomainHrp {
+                domain: 2,
+                hrp: None,
+            },
+            vec![],
+        );
+
+        let res: RouteResponse<DecodedRoute> = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RouterDecoded { domain: 2 }),
+        );
+        assert_eq!(
+            res.route.route,
+            Some(DecodedRoute {
+                address: cosmos_route,
+                bech32: None,
+                evm: None,
+            })
+        );
+
+        let res: RouteResponse<DecodedRoute> = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::RouterDecoded { domain: 3 }),
+        );
+        assert_eq!(res.route.route, None);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "invalid")]
+    fn test_set_domain_hrp_invalid(mut deps: TestDeps) {
+        test_execute(
+            deps.as_mut(),
+            &addr(OWNER),
+            ExecuteMsg::SetDomainHrp {
+                domain: 2,
+                hrp: Some(String::default()),
+            },
+            vec![],
+        );
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("someone"))]
+    fn test_set_payload_version(mut deps: TestDeps, #[case] sender: Addr) {
+        let route = gen_bz(32);
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(route.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        let res: warp::PayloadVersionResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::PayloadVersion {}),
+        );
+        assert_eq!(res.version, PayloadVersion::V0);
+
+        let res = test_execute(
+            deps.as_mut(),
+            &sender,
+            ExecuteMsg::SetPayloadVersion {
+                version: PayloadVersion::V1,
+            },
+            vec![],
+        );
+        assert_eq!(
+            res.events,
+            vec![new_event("set-payload-version")
+                .add_attribute("sender", sender.as_str())
+                .add_attribute("version", "v1")]
+        );
+
+        let res: warp::PayloadVersionResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::PayloadVersion {}),
+        );
+        assert_eq!(res.version, PayloadVersion::V1);
+
+        let recipient = gen_bz(32);
+        let res = test_execute(
+            deps.as_mut(),
+            &addr("sender"),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: recipient.clone(),
+                amount: Uint128::new(50),
+                min_amount: None,
+                denom: None,
+            },
+            vec![coin(100, DENOM)],
+        );
+
+        let warp_msg = warp::Message {
+            recipient,
+            amount: Uint256::from_u128(50),
+            metadata: HexBinary::default(),
+        };
+        let body = Payload::new(PayloadVersion::V1, warp_msg).encode().unwrap();
+        let dispatch_msg =
+            mailbox::dispatch(MAILBOX, 1, route, body, None, None, vec![coin(50, DENOM)]).unwrap();
+
+        assert_eq!(res.messages.last().unwrap().msg, dispatch_msg);
+    }
+
+    #[rstest]
+    fn test_mailbox_handle_invalid_payload(mut deps: TestDeps) {
+        let route = gen_bz(32);
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(route.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MAILBOX, &[]),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: route,
+                body: gen_bz(63),
+            }),
+        )
+        .unwrap_err();
+
+        assert_eq!(err, PayloadError::TooShort { len: 63 }.into());
+    }
+
+    #[rstest]
+    #[case(
+        ContractError::GasPaymentRequired { domain: 1 },
+        501,
+        "[E501] gas payment required for domain 1"
+    )]
+    #[case(
+        PayloadError::TooShort { len: 63 }.into(),
+        508,
+        "[E508] invalid payload. payload too short. length: 63"
+    )]
+    #[case(
+        ContractError::InvalidCallRecipient {
+            recipient: "osmo1mailbox".to_string()
+        },
+        509,
+        "[E509] calls can't be sent to osmo1mailbox"
+    )]
+    #[case(
+        ContractError::RefundFeeNotCovered {
+            fees: vec![coin(10, "uosmo")],
+            refund: coin(100, DENOM),
+        },
+        510,
+        "[E510] refund can't cover its hook fees. fees: [Coin { 10 \"uosmo\" }], refund: 100utest"
+    )]
+    #[case(
+        ContractError::UnsupportedDenom {
+            denom: "uatom".to_string()
+        },
+        540,
+        "[E540] unsupported denom uatom"
+    )]
+    #[case(
+        HplError::RouteNotFound { domain: 1 }.into(),
+        17,
+        "[E017] route not found for domain 1"
+    )]
+    fn test_error_codes(#[case] err: ContractError, #[case] code: u16, #[case] golden: &str) {
+        assert_eq!(err.code(), code);
+        assert_eq!(err.to_string(), golden);
+    }
+
+    #[rstest]
+    #[case(addr(OWNER))]
+    #[should_panic(expected = "unauthorized")]
+    #[case(addr("someone"))]
+    fn test_set_inbound_paused(mut deps: TestDeps, #[case] sender: Addr) {
+        for paused in [true, false] {
+            let res = test_execute(
+                deps.as_mut(),
+                &sender,
+                ExecuteMsg::SetInboundPaused { paused },
+                vec![],
+            );
+            assert_eq!(
+                res.events,
+                vec![new_event("set-inbound-paused")
+                    .add_attribute("sender", sender.as_str())
+                    .add_attribute("paused", paused.to_string())]
+            );
+
+            let res: PauseStateResponse = test_query(
+                deps.as_ref(),
+                QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::PauseState {}),
+            );
+            assert!(!res.outbound);
+            assert_eq!(res.inbound, paused);
+        }
+    }
+
+    #[rstest]
+    fn test_pause(
+        mut deps: TestDeps,
+        #[values(false, true)] outbound: bool,
+        #[values(false, true)] inbound: bool,
+    ) {
+        let router = gen_bz(32);
+
+        set_route(
+            deps.as_mut().storage,
+            &addr(OWNER),
+            DomainRouteSet {
+                domain: 1,
+                route: remote_router(router.clone(), DECIMALS),
+            },
+        )
+        .unwrap();
+
+        if outbound {
+            let res = test_execute(
+                deps.as_mut(),
+                &addr(OWNER),
+                ExecuteMsg::Pausable(PausableMsg::Pause {}),
+                vec![],
+            );
+            assert_eq!(res.events[0].ty, "hpl_pausable::pause");
+        }
+        if inbound {
+            test_execute(
+                deps.as_mut(),
+                &addr(OWNER),
+                ExecuteMsg::SetInboundPaused { paused: true },
+                vec![],
+            );
+        }
+
+        let res: PauseStateResponse = test_query(
+            deps.as_ref(),
+            QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::PauseState {}),
+        );
+        assert_eq!(res, PauseStateResponse { outbound, inbound });
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("sender", &[coin(100, DENOM)]),
+            ExecuteMsg::TransferRemote {
+                dest_domain: 1,
+                recipient: gen_bz(32),
+                amount: Uint128::new(100),
+                min_amount: None,
+                denom: None,
+            },
+        );
+        assert_eq!(res.err(), outbound.then_some(ContractError::OutboundPaused));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MAILBOX, &[]),
+            ExecuteMsg::Handle(HandleMsg {
+                origin: 1,
+                sender: router,
+                body: warp::Message {
+                    recipient: gen_bz(32),
+                    amount: Uint256::from_u128(100),
+                    metadata: HexBinary::default(),
+                }
+                .into(),
+            }),
+        );
+        assert_eq!(res.err(), inbound.then_some(ContractError::InboundPaused));
+    }
+
+    #[cosmwasm_schema::cw_serde]
+    enum ChainQuery {}
+
+    impl CustomQuery for ChainQuery {}
+
+    #[rstest]
+    fn test_custom_query(
+        #[values(token_mode_bridged(None), token_mode_collateral())] token_mode: NativeTokenMode,
+    ) {
+        let mut deps: OwnedDeps<MockStorage, MockApi, MockQuerier<ChainQuery>, ChainQuery> =
+            OwnedDeps {
+                storage: MockStorage::default(),
+                api: MockApi::default(),
+                querier: MockQuerier::new(&[]),
+                custom_query_type: std::marker::PhantomData,
+            };
+
+        super::instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(DEPLOYER, &[]),
+            super::InstantiateMsg {
+                token: token_mode.clone(),
+                decimals: DECIMALS,
+                hrp: "osmo".into(),
+                owner: OWNER.into(),
+                mailbox: MAILBOX.into(),
+            },
+        )
+        .unwrap();
+
+        super::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::SetInboundPaused { paused: true },
+        )
+        .unwrap();
+
+        let res: TokenModeResponse = from_json(
+            super::query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TokenDefault(warp::TokenWarpDefaultQueryMsg::TokenMode {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.mode, token_mode.into());
+    }
 }
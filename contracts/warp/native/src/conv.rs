@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
-use cosmwasm_std::{coin, Addr, BankMsg, Coin, Uint128, Uint256};
-use hpl_interface::warp::native;
+use cosmwasm_std::{coin, Addr, BankMsg, Coin, Timestamp, Uint128, Uint256};
+use hpl_interface::warp::{native, IbcForward};
 
 use crate::{error::ContractError, proto};
 
@@ -32,6 +32,36 @@ pub fn to_send_msg(recipient: &Addr, amount: Vec<Coin>) -> BankMsg {
     }
 }
 
+/// Timeout of a forward, `None` if it doesn't fit in a timestamp
+pub fn to_ibc_timeout(now: Timestamp, timeout_seconds: u64) -> Option<Timestamp> {
+    timeout_seconds
+        .checked_mul(1_000_000_000)
+        .and_then(|v| v.checked_add(now.nanos()))
+        .map(Timestamp::from_nanos)
+}
+
+/// The memo asks the ibc-hooks module to report the outcome of the transfer to the sender
+pub fn to_ibc_transfer_msg(
+    sender: &Addr,
+    forward: &IbcForward,
+    amount: Coin,
+    timeout: Timestamp,
+) -> proto::MsgTransfer {
+    proto::MsgTransfer {
+        source_port: "transfer".to_string(),
+        source_channel: forward.ibc_channel.clone(),
+        token: Some(proto::Coin {
+            denom: amount.denom,
+            amount: amount.amount.to_string(),
+        }),
+        sender: sender.to_string(),
+        receiver: forward.final_recipient.clone(),
+        timeout_height: None,
+        timeout_timestamp: timeout.nanos(),
+        memo: format!(r#"{{"ibc_callback":"{sender}"}}"#),
+    }
+}
+
 pub fn to_coin(amount: impl Into<u128>, denom: impl Into<String>) -> Coin {
     coin(amount.into(), denom)
 }
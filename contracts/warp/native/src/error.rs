@@ -1,29 +1,87 @@
-use cosmwasm_std::{RecoverPubkeyError, StdError};
+use cosmwasm_std::{Coin, Uint128, Uint256};
+use hpl_error::HplError;
+use hpl_interface::warp::{payload::PayloadError, RecipientFormat};
 use thiserror::Error;
 
 #[derive(Error, PartialEq, Debug)]
 pub enum ContractError {
-    #[error("{0}")]
-    Std(#[from] StdError),
+    #[error(transparent)]
+    Hpl(#[from] HplError),
 
-    #[error("{0}")]
-    PaymentError(#[from] cw_utils::PaymentError),
+    #[error("[E500] invalid recipient format for domain {domain}. expected: {expected}")]
+    InvalidRecipientFormat {
+        domain: u32,
+        expected: RecipientFormat,
+    },
 
-    #[error("{0}")]
-    RecoverPubkeyError(#[from] RecoverPubkeyError),
+    #[error("[E501] gas payment required for domain {domain}")]
+    GasPaymentRequired { domain: u32 },
 
-    #[error("unauthorized")]
-    Unauthorized,
+    #[error("[E502] insufficient hook payment: required {required:?}, provided {provided:?}")]
+    InsufficientHookPayment {
+        required: Vec<Coin>,
+        provided: Vec<Coin>,
+    },
 
-    #[error("wrong length")]
-    WrongLength,
+    #[error("[E503] invalid min amount. amount: {amount}, min_amount: {min_amount}")]
+    InvalidMinAmount {
+        amount: Uint128,
+        min_amount: Uint128,
+    },
 
-    #[error("invalid reply id")]
-    InvalidReplyId,
+    #[error(
+        "[E504] received amount is below min amount. amount: {amount}, min_amount: {min_amount}"
+    )]
+    BelowMinAmount {
+        amount: Uint256,
+        min_amount: Uint256,
+    },
 
-    #[error("insufficient funds")]
+    #[error("[E505] insufficient funds")]
     InsufficientFunds,
 
-    #[error("no route for domain {domain:?}")]
-    NoRouter { domain: u32 },
+    #[error("[E506] outbound transfers are paused")]
+    OutboundPaused,
+
+    #[error("[E507] inbound transfers are paused")]
+    InboundPaused,
+
+    #[error("[E508] invalid payload. {0}")]
+    InvalidPayload(#[from] PayloadError),
+
+    #[error("[E509] calls can't be sent to {recipient}")]
+    InvalidCallRecipient { recipient: String },
+
+    /// Hook fees of a refund are paid out of the refunded tokens
+    #[error("[E510] refund can't cover its hook fees. fees: {fees:?}, refund: {refund}")]
+    RefundFeeNotCovered { fees: Vec<Coin>, refund: Coin },
+
+    #[error("[E540] unsupported denom {denom}")]
+    UnsupportedDenom { denom: String },
+
+    #[error("[E541] insufficient escrow for denom {denom}")]
+    InsufficientEscrow { denom: String },
+}
+
+hpl_error::impl_from_hpl!(ContractError);
+
+impl ContractError {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Hpl(err) => err.code(),
+            Self::InvalidRecipientFormat { .. } => 500,
+            Self::GasPaymentRequired { .. } => 501,
+            Self::InsufficientHookPayment { .. } => 502,
+            Self::InvalidMinAmount { .. } => 503,
+            Self::BelowMinAmount { .. } => 504,
+            Self::InsufficientFunds => 505,
+            Self::OutboundPaused => 506,
+            Self::InboundPaused => 507,
+            Self::InvalidPayload(_) => 508,
+            Self::InvalidCallRecipient { .. } => 509,
+            Self::RefundFeeNotCovered { .. } => 510,
+            Self::UnsupportedDenom { .. } => 540,
+            Self::InsufficientEscrow { .. } => 541,
+        }
+    }
 }
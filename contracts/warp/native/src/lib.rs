@@ -1,6 +1,7 @@
-use cosmwasm_std::{Addr, Event};
-use cw_storage_plus::Item;
-use hpl_interface::warp::TokenMode;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin, Event, Uint128};
+use cw_storage_plus::{Item, Map};
+use hpl_interface::warp::{payload::PayloadVersion, RecipientFormat, TokenMode};
 
 pub mod contract;
 mod conv;
@@ -9,6 +10,7 @@ mod proto;
 
 // reply message
 pub const REPLY_ID_CREATE_DENOM: u64 = 0;
+pub const REPLY_ID_IBC_FORWARD: u64 = 1;
 
 // version info for migration info
 pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -18,6 +20,10 @@ pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const TOKEN_KEY: &str = "token";
 const TOKEN: Item<String> = Item::new(TOKEN_KEY);
 
+// storage definition for token decimals
+const DECIMALS_KEY: &str = "decimals";
+const DECIMALS: Item<u8> = Item::new(DECIMALS_KEY);
+
 // storage definition for token mode
 const MODE_KEY: &str = "mode";
 const MODE: Item<TokenMode> = Item::new(MODE_KEY);
@@ -26,10 +32,66 @@ const MODE: Item<TokenMode> = Item::new(MODE_KEY);
 const HRP_KEY: &str = "hrp";
 const HRP: Item<String> = Item::new(HRP_KEY);
 
+// storage definition for recipient format of destination domains
+const RECIPIENT_FORMATS_PREFIX: &str = "recipient_formats";
+const RECIPIENT_FORMATS: Map<u32, RecipientFormat> = Map::new(RECIPIENT_FORMATS_PREFIX);
+
+// storage definition for gas payment requirement of destination domains
+const REQUIRE_GAS_PAYMENT_PREFIX: &str = "require_gas_payment";
+const REQUIRE_GAS_PAYMENT: Map<u32, bool> = Map::new(REQUIRE_GAS_PAYMENT_PREFIX);
+
+// storage definition for bech32 prefixes of cosmos domains, only used to decode routers
+const DOMAIN_HRPS_PREFIX: &str = "domain_hrps";
+const DOMAIN_HRPS: Map<u32, String> = Map::new(DOMAIN_HRPS_PREFIX);
+
+// storage definition for igp gas limit of destination domains
+const DEFAULT_GAS_LIMITS_PREFIX: &str = "default_gas_limits";
+const DEFAULT_GAS_LIMITS: Map<u32, u64> = Map::new(DEFAULT_GAS_LIMITS_PREFIX);
+
+// storage definition for ibc forwarding of received transfers
+const IBC_FORWARD_ENABLED_KEY: &str = "ibc_forward_enabled";
+const IBC_FORWARD_ENABLED: Item<bool> = Item::new(IBC_FORWARD_ENABLED_KEY);
+
+// storage definition for ibc channels allowed as forward routes
+const IBC_FORWARD_CHANNELS_PREFIX: &str = "ibc_forward_channels";
+const IBC_FORWARD_CHANNELS: Map<&str, ()> = Map::new(IBC_FORWARD_CHANNELS_PREFIX);
+
+// storage definition for the ibc forward waiting on the sequence of its transfer
+const PENDING_IBC_FORWARD_KEY: &str = "pending_ibc_forward";
+const PENDING_IBC_FORWARD: Item<IbcForwardRecord> = Item::new(PENDING_IBC_FORWARD_KEY);
+
+// storage definition for ibc forwards in flight by channel and sequence
+const IBC_FORWARDS_PREFIX: &str = "ibc_forwards";
+const IBC_FORWARDS: Map<(&str, u64), IbcForwardRecord> = Map::new(IBC_FORWARDS_PREFIX);
+
+// storage definition for collateral denoms bridged by the route, including the primary denom
+const SUPPORTED_DENOMS_PREFIX: &str = "supported_denoms";
+const SUPPORTED_DENOMS: Map<&str, ()> = Map::new(SUPPORTED_DENOMS_PREFIX);
+
+// storage definition for collateral escrowed per denom
+const ESCROW_PREFIX: &str = "escrow";
+const ESCROW: Map<&str, Uint128> = Map::new(ESCROW_PREFIX);
+
+// storage definition for pausing inbound transfers, outbound transfers use hpl-pausable
+const INBOUND_PAUSED_KEY: &str = "inbound_paused";
+const INBOUND_PAUSED: Item<bool> = Item::new(INBOUND_PAUSED_KEY);
+
+// storage definition for payload version of outbound transfers
+const PAYLOAD_VERSION_KEY: &str = "payload_version";
+const PAYLOAD_VERSION: Item<PayloadVersion> = Item::new(PAYLOAD_VERSION_KEY);
+
 // storage definition for mailbox
 const MAILBOX_KEY: &str = "mailbox";
 const MAILBOX: Item<Addr> = Item::new(MAILBOX_KEY);
 
+/// Forwarded transfer, sent to the local recipient if the ibc transfer fails
+#[cw_serde]
+struct IbcForwardRecord {
+    channel: String,
+    recipient: Addr,
+    amount: Coin,
+}
+
 fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_warp_native::{name}"))
 }
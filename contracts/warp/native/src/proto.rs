@@ -1,6 +1,16 @@
-use cosmwasm_std::{Binary, CosmosMsg};
+use cosmwasm_std::{Binary, CosmosMsg, StdError, StdResult};
 use prost::Message;
 
+/// Package of the chain's TokenFactory module, the messages are wire compatible across chains
+#[cfg(not(feature = "injective"))]
+pub const TOKEN_FACTORY_PACKAGE: &str = "osmosis.tokenfactory.v1beta1";
+#[cfg(feature = "injective")]
+pub const TOKEN_FACTORY_PACKAGE: &str = "injective.tokenfactory.v1beta1";
+
+fn token_factory_type_url(msg: &str) -> String {
+    format!("/{TOKEN_FACTORY_PACKAGE}.{msg}")
+}
+
 #[derive(serde::Serialize, serde::Deserialize, ::prost::Message)]
 pub struct MsgCreateDenom {
     #[prost(string, tag = "1")]
@@ -13,7 +23,7 @@ pub struct MsgCreateDenom {
 impl From<MsgCreateDenom> for CosmosMsg {
     fn from(v: MsgCreateDenom) -> Self {
         CosmosMsg::Stargate {
-            type_url: "/osmosis.tokenfactory.v1beta1.MsgCreateDenom".to_string(),
+            type_url: token_factory_type_url("MsgCreateDenom"),
             value: Binary(v.encode_to_vec()),
         }
     }
@@ -25,22 +35,26 @@ pub struct MsgCreateDenomResponse {
     pub new_token_denom: ::prost::alloc::string::String,
 }
 
-impl TryFrom<cosmwasm_std::Binary> for MsgCreateDenomResponse {
-    type Error = cosmwasm_std::StdError;
+impl TryFrom<Binary> for MsgCreateDenomResponse {
+    type Error = StdError;
 
-    fn try_from(binary: cosmwasm_std::Binary) -> Result<Self, Self::Error> {
-        Self::decode(&binary[..]).map_err(|e| cosmwasm_std::StdError::ParseErr {
-            target_type: stringify!(#ident).to_string(),
-            msg: format!(
-                "Unable to decode binary: \n  - base64: {}\n  - bytes array: {:?}\n\n{:?}",
-                binary,
-                binary.to_vec(),
-                e
-            ),
-        })
+    fn try_from(binary: Binary) -> Result<Self, Self::Error> {
+        decode_response(binary)
     }
 }
 
+fn decode_response<T: Message + Default>(binary: Binary) -> StdResult<T> {
+    T::decode(&binary[..]).map_err(|e| StdError::ParseErr {
+        target_type: std::any::type_name::<T>().to_string(),
+        msg: format!(
+            "Unable to decode binary: \n  - base64: {}\n  - bytes array: {:?}\n\n{:?}",
+            binary,
+            binary.to_vec(),
+            e
+        ),
+    })
+}
+
 #[derive(serde::Serialize, serde::Deserialize, ::prost::Message)]
 pub struct Coin {
     #[prost(string, tag = "1")]
@@ -60,7 +74,7 @@ pub struct MsgMint {
 impl From<MsgMint> for CosmosMsg {
     fn from(v: MsgMint) -> Self {
         CosmosMsg::Stargate {
-            type_url: "/osmosis.tokenfactory.v1beta1.MsgMint".to_string(),
+            type_url: token_factory_type_url("MsgMint"),
             value: Binary(v.encode_to_vec()),
         }
     }
@@ -77,7 +91,7 @@ pub struct MsgBurn {
 impl From<MsgBurn> for CosmosMsg {
     fn from(v: MsgBurn) -> Self {
         CosmosMsg::Stargate {
-            type_url: "/osmosis.tokenfactory.v1beta1.MsgBurn".to_string(),
+            type_url: token_factory_type_url("MsgBurn"),
             value: Binary(v.encode_to_vec()),
         }
     }
@@ -142,12 +156,66 @@ pub struct MsgSetDenomMetadata {
 impl From<MsgSetDenomMetadata> for CosmosMsg {
     fn from(v: MsgSetDenomMetadata) -> Self {
         CosmosMsg::Stargate {
-            type_url: "/osmosis.tokenfactory.v1beta1.MsgSetDenomMetadata".to_string(),
+            type_url: token_factory_type_url("MsgSetDenomMetadata"),
             value: Binary(v.encode_to_vec()),
         }
     }
 }
 
+#[derive(serde::Serialize, serde::Deserialize, ::prost::Message)]
+pub struct Height {
+    #[prost(uint64, tag = "1")]
+    pub revision_number: u64,
+    #[prost(uint64, tag = "2")]
+    pub revision_height: u64,
+}
+
+/// ICS-20 transfer, sent instead of `IbcMsg::Transfer` as it carries a memo
+#[derive(serde::Serialize, serde::Deserialize, ::prost::Message)]
+pub struct MsgTransfer {
+    #[prost(string, tag = "1")]
+    pub source_port: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub source_channel: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub token: ::core::option::Option<Coin>,
+    #[prost(string, tag = "4")]
+    pub sender: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub receiver: ::prost::alloc::string::String,
+    /// unset, transfers time out by timestamp
+    #[prost(message, optional, tag = "6")]
+    pub timeout_height: ::core::option::Option<Height>,
+    /// unix timestamp in nanoseconds
+    #[prost(uint64, tag = "7")]
+    pub timeout_timestamp: u64,
+    #[prost(string, tag = "8")]
+    pub memo: ::prost::alloc::string::String,
+}
+
+impl From<MsgTransfer> for CosmosMsg {
+    fn from(v: MsgTransfer) -> Self {
+        CosmosMsg::Stargate {
+            type_url: "/ibc.applications.transfer.v1.MsgTransfer".to_string(),
+            value: Binary(v.encode_to_vec()),
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, ::prost::Message)]
+pub struct MsgTransferResponse {
+    #[prost(uint64, tag = "1")]
+    pub sequence: u64,
+}
+
+impl TryFrom<Binary> for MsgTransferResponse {
+    type Error = StdError;
+
+    fn try_from(binary: Binary) -> Result<Self, Self::Error> {
+        decode_response(binary)
+    }
+}
+
 mod as_str {
     use serde::{de, Deserialize, Deserializer, Serializer};
     use std::{fmt::Display, str::FromStr};
@@ -1,7 +1,6 @@
 use std::collections::BTreeMap;
 
 use cosmwasm_schema::{cw_serde, serde::Serialize};
-use cosmwasm_std::HexBinary;
 use hpl_interface::{
     core::mailbox,
     router::{DomainRouteSet, RouterMsg},
@@ -55,6 +54,8 @@ pub fn deploy_core<'a, R: Runner<'a>>(
             hrp: hrp.to_string(),
             owner: deployer.address(),
             domain: origin_domain,
+            default_ism: None,
+            default_hook: None,
         },
     )
     .data
@@ -70,6 +71,7 @@ pub fn deploy_core<'a, R: Runner<'a>>(
         &mailbox,
         &mailbox::ExecuteMsg::SetDefaultIsm {
             ism: default_ism.clone(),
+            unchecked: false,
         },
         &[],
         deployer,
@@ -79,6 +81,7 @@ pub fn deploy_core<'a, R: Runner<'a>>(
         &mailbox,
         &mailbox::ExecuteMsg::SetDefaultHook {
             hook: default_hook.clone(),
+            unchecked: false,
         },
         &[],
         deployer,
@@ -88,6 +91,7 @@ pub fn deploy_core<'a, R: Runner<'a>>(
         &mailbox,
         &mailbox::ExecuteMsg::SetRequiredHook {
             hook: required_hook.clone(),
+            unchecked: false,
         },
         &[],
         deployer,
@@ -142,6 +146,7 @@ pub fn deploy_warp_route_bridged<'a, R: Runner<'a>>(
                     denom,
                     metadata: None,
                 }),
+                decimals: 6,
                 hrp: hrp.to_string(),
                 owner: owner.address(),
                 mailbox: mailbox.to_string(),
@@ -163,6 +168,7 @@ pub fn deploy_warp_route_bridged<'a, R: Runner<'a>>(
                         mint: None,
                         marketing: None,
                     }),
+                    salt: None,
                 }),
                 hrp: hrp.to_string(),
                 owner: owner.address(),
@@ -208,6 +214,7 @@ pub fn deploy_warp_route_collateral<'a, R: Runner<'a>>(
             &format!("warp-native-{denom}"),
             &warp::native::InstantiateMsg {
                 token: warp::TokenModeMsg::Collateral(warp::native::NativeModeCollateral { denom }),
+                decimals: 6,
                 hrp: hrp.to_string(),
                 owner: owner.address(),
                 mailbox: mailbox.to_string(),
@@ -221,11 +228,11 @@ pub fn link_warp_route<'a, R: Runner<'a>>(
     wasm: &Wasm<'a, R>,
     owner: &SigningAccount,
     origin: String,
-    remotes: BTreeMap<u32, HexBinary>,
+    remotes: BTreeMap<u32, warp::RemoteRouterConfig>,
 ) -> eyre::Result<()> {
     #[cw_serde]
     pub enum ExpectedExecuteMsg {
-        Router(RouterMsg<HexBinary>),
+        Router(RouterMsg<warp::RemoteRouterConfig>),
     }
 
     wasm.execute(
@@ -4,6 +4,7 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Empty, Uint256};
 use hpl_interface::{
     hook::{self, routing_custom::RegisterCustomHookMsg},
+    igp::core as igp_core,
     router::{DomainRouteSet, RouterMsg},
 };
 use ibcx_test_utils::addr;
@@ -99,7 +100,10 @@ impl Hook {
             .instantiate(
                 codes.hook_merkle,
                 &hook::merkle::InstantiateMsg {
+                    owner: owner.address(),
                     mailbox,
+                    tree_depth: None,
+                    store_leaves: None,
                 },
                 Some(deployer.address().as_str()),
                 Some("cw-hpl-hook-merkle"),
@@ -193,9 +197,18 @@ impl Hook {
     ) -> eyre::Result<String> {
         use hpl_interface::hook::aggregate::*;
 
-        let hook_addrs = hooks
+        let deployed = hooks
             .into_iter()
-            .map(|hook| hook.deploy(wasm, codes, mailbox.clone(), owner, deployer))
+            .map(|hook| {
+                let kind = (
+                    matches!(hook, Hook::Merkle {}),
+                    matches!(hook, Hook::Igp(_)),
+                );
+                Ok((
+                    hook.deploy(wasm, codes, mailbox.clone(), owner, deployer)?,
+                    kind,
+                ))
+            })
             .collect::<eyre::Result<Vec<_>>>()?;
 
         let hook = instantiate(
@@ -205,11 +218,40 @@ impl Hook {
             "cw-hpl-hook-aggregate",
             &InstantiateMsg {
                 owner: owner.address(),
-                hooks: hook_addrs,
+                hooks: deployed.iter().map(|(v, _)| v.clone()).collect(),
             },
-        );
+        )
+        .data
+        .address;
+
+        // the merkle hook and the igp only take post dispatch from the mailbox
+        // and the callers authorized by their owner
+        for (hook_addr, (is_merkle, is_igp)) in deployed {
+            if is_merkle {
+                wasm.execute(
+                    &hook_addr,
+                    &hook::merkle::ExecuteMsg::SetAuthorizedCaller {
+                        caller: hook.clone(),
+                        authorized: true,
+                    },
+                    &[],
+                    owner,
+                )?;
+            }
+            if is_igp {
+                wasm.execute(
+                    &hook_addr,
+                    &igp_core::ExecuteMsg::SetAuthorizedCaller {
+                        caller: hook.clone(),
+                        authorized: true,
+                    },
+                    &[],
+                    owner,
+                )?;
+            }
+        }
 
-        Ok(hook.data.address)
+        Ok(hook)
     }
 
     pub fn deploy<'a, R: Runner<'a>>(
@@ -222,7 +264,7 @@ impl Hook {
     ) -> eyre::Result<String> {
         match self {
             Hook::Mock { gas } => Self::deploy_mock(wasm, codes, gas, deployer),
-            Hook::Igp(igp) => Ok(igp.deploy(wasm, codes, owner, deployer)?.core),
+            Hook::Igp(igp) => Ok(igp.deploy(wasm, codes, mailbox, owner, deployer)?.core),
             Hook::Merkle {} => Self::deploy_merkle(wasm, codes, mailbox, owner, deployer),
             Hook::Pausable {} => Self::deploy_pausable(wasm, codes, owner, deployer),
             Hook::Routing { routes } => Self::deploy_routing(
@@ -25,6 +25,7 @@ impl Igp {
         self,
         wasm: &Wasm<'a, R>,
         codes: &Codes,
+        mailbox: String,
         owner: &SigningAccount,
         deployer: &SigningAccount,
     ) -> eyre::Result<IgpDeployment> {
@@ -34,6 +35,7 @@ impl Igp {
                 &igp::core::InstantiateMsg {
                     hrp: self.hrp,
                     owner: owner.address(),
+                    mailbox,
                     gas_token: self.gas_token,
                     beneficiary: self.beneficiary,
                     default_gas_usage: 25_000,
@@ -58,6 +58,7 @@ impl Ism {
                 codes.ism_multisig,
                 &hpl_interface::ism::multisig::InstantiateMsg {
                     owner: owner.address(),
+                    module_type: None,
                 },
                 None,
                 None,
@@ -79,6 +80,7 @@ impl Ism {
                         pub_to_addr(HexBinary::from(
                             v.pub_key.to_encoded_point(true).as_bytes().to_vec(),
                         ))
+                        .map(Into::into)
                     })
                     .collect::<StdResult<Vec<_>>>()?,
             },
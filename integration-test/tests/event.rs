@@ -6,6 +6,7 @@ pub struct CwDispatchEvent {
     pub sender: HexBinary,
     pub destination: u32,
     pub recipient: HexBinary,
+    pub origin: u32,
     pub message: HexBinary,
 }
 
@@ -21,7 +22,8 @@ pub fn parse_dispatch_from_res(events: &[Event]) -> CwDispatchEvent {
         sender: HexBinary::from_hex(&actual[0].value).unwrap(),
         destination: actual[1].value.parse::<u32>().unwrap(),
         recipient: HexBinary::from_hex(&actual[2].value).unwrap(),
-        message: HexBinary::from_hex(&actual[3].value).unwrap(),
+        origin: actual[3].value.parse::<u32>().unwrap(),
+        message: HexBinary::from_hex(&actual[4].value).unwrap(),
     }
 }
 
@@ -8,7 +8,6 @@ use cosmwasm_std::{attr, coin, Attribute, Binary, Uint128};
 use ethers::{
     prelude::parse_log, providers::Middleware, signers::Signer, types::TransactionReceipt,
 };
-use ibcx_test_utils::addr;
 use osmosis_test_tube::{
     osmosis_std::types::cosmwasm::wasm::v1::MsgExecuteContractResponse, Account, Module,
     OsmosisTestApp, Wasm,
@@ -62,6 +61,8 @@ where
             msg_body: msg_body.into(),
             hook: None,
             metadata: None,
+            sender_override: None,
+            msg_value: None,
         }),
         &[coin(56_000_000, "uosmo")],
         &from.acc_tester,
@@ -112,10 +113,7 @@ where
         true,
     )?;
 
-    let aggregate_ism_metadata = AggregateMetadata::new(vec![(
-        addr(&to.core.default_ism),
-        multisig_ism_metadata.into(),
-    )]);
+    let aggregate_ism_metadata = AggregateMetadata::new(vec![Some(multisig_ism_metadata.into())]);
 
     // process
     let process_res = Wasm::new(to.app).execute(
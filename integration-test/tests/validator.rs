@@ -5,10 +5,9 @@ use cosmwasm_std::{
 use ethers::types::{Address, H160};
 use ethers::utils::hex::FromHex;
 use hpl_interface::{
+    digest::{checkpoint_digest, domain_hash, eth_signed_message_hash},
     ism::multisig::ValidatorSet,
-    types::{
-        bech32_encode, eth_addr, eth_hash, pub_to_addr, Message, MessageIdMultisigIsmMetadata,
-    },
+    types::{bech32_encode, eth_addr, pub_to_addr, Message, MessageIdMultisigIsmMetadata},
 };
 use ibcx_test_utils::{addr, gen_bz};
 use k256::{
@@ -144,19 +143,19 @@ impl TestValidators {
         let mut addr = [0u8; 32];
         addr[32 - origin_merkle_tree.0.len()..].copy_from_slice(&origin_merkle_tree.0);
 
-        let multisig_hash = hpl_ism_multisig::multisig_hash(
-            hpl_ism_multisig::domain_hash(self.domain, addr.to_vec().into())?.to_vec(),
-            merkle_root.to_vec(),
+        let digest = checkpoint_digest(
+            domain_hash(self.domain, addr),
+            merkle_root,
             merkle_index,
-            message_id.to_vec(),
-        )?;
+            message_id,
+        );
 
-        let hashed_message = eth_hash(multisig_hash)?;
+        let hashed_message = eth_signed_message_hash(digest);
 
         let signatures = if is_passed {
-            self.sign(self.threshold, hashed_message.as_slice().try_into()?)
+            self.sign(self.threshold, hashed_message)
         } else {
-            self.sign(self.threshold - 1, hashed_message.as_slice().try_into()?)
+            self.sign(self.threshold - 1, hashed_message)
         };
 
         Ok(MessageIdMultisigIsmMetadata {
@@ -164,6 +163,7 @@ impl TestValidators {
             merkle_root: merkle_root.to_vec().into(),
             merkle_index: merkle_index.to_be_bytes().to_vec().into(),
             signatures,
+            validator_indices: None,
         })
     }
 }
@@ -187,13 +187,13 @@ fn test_validator() {
             validators: validators
                 .to_set()
                 .iter()
-                .map(|v| v.validator.clone())
+                .map(|v| v.validator.clone().into())
                 .collect(),
         },
     )
     .unwrap();
 
-    let mut message: Message = gen_bz(100).into();
+    let mut message: Message = gen_bz(100).try_into().unwrap();
     message.origin_domain = validators.domain;
 
     let message_id = message.id();
@@ -0,0 +1,333 @@
+use cosmwasm_schema::serde::Serialize;
+use cosmwasm_std::{coin, Addr, Empty};
+use cw_multi_test::{
+    addons::{MockAddressGenerator, MockApiBech32},
+    App, AppBuilder, BankKeeper, Contract, ContractWrapper, Executor, WasmKeeper,
+};
+
+use hpl_interface::{
+    core, hook, igp, ism,
+    version::contract_version,
+    warp::{self, TokenModeMsg},
+};
+
+type TestApp = App<BankKeeper, MockApiBech32>;
+
+const HRP: &str = "osmo";
+const DOMAIN: u32 = 26657;
+
+macro_rules! wrap {
+    ($($module:ident)::+) => {
+        Box::new(
+            ContractWrapper::new(
+                $($module)::+::execute,
+                $($module)::+::instantiate,
+                $($module)::+::query,
+            )
+            .with_migrate($($module)::+::migrate),
+        )
+    };
+}
+
+/// instantiates and migrates the contract, checking the reported version after each step
+fn deploy<T: Serialize>(
+    app: &mut TestApp,
+    name: &str,
+    contract: Box<dyn Contract<Empty>>,
+    msg: &T,
+) -> Addr {
+    let owner = owner(app);
+    let code_id = app.store_code(contract);
+
+    let addr = app
+        .instantiate_contract(
+            code_id,
+            owner.clone(),
+            msg,
+            &[],
+            name,
+            Some(owner.to_string()),
+        )
+        .unwrap();
+    assert_version(app, &addr, name);
+
+    app.migrate_contract(owner, addr.clone(), &Empty {}, code_id)
+        .unwrap();
+    assert_version(app, &addr, name);
+
+    addr
+}
+
+fn owner(app: &TestApp) -> Addr {
+    app.api().addr_make("owner")
+}
+
+fn assert_version(app: &TestApp, addr: &Addr, name: &str) {
+    let res = contract_version(&app.wrap(), addr).unwrap();
+
+    assert_eq!(res.contract, name);
+    assert_eq!(res.version, env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_contract_version() {
+    let owner = MockApiBech32::new(HRP).addr_make("owner");
+    let mut app = AppBuilder::default()
+        .with_api(MockApiBech32::new(HRP))
+        .with_wasm(WasmKeeper::default().with_address_generator(MockAddressGenerator))
+        .build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &owner, vec![coin(1_000_000, "uosmo")])
+                .unwrap();
+        });
+
+    // core
+    let mailbox = deploy(
+        &mut app,
+        "hpl-mailbox",
+        wrap!(hpl_mailbox::contract),
+        &core::mailbox::InstantiateMsg {
+            hrp: HRP.into(),
+            owner: owner.to_string(),
+            domain: DOMAIN,
+            default_ism: None,
+            default_hook: None,
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-validator-announce",
+        wrap!(hpl_validator_announce::contract),
+        &core::va::InstantiateMsg {
+            hrp: HRP.into(),
+            owner: owner.to_string(),
+            mailbox: mailbox.to_string(),
+        },
+    );
+
+    // hooks
+    deploy(
+        &mut app,
+        "hpl-hook-fee",
+        wrap!(hpl_hook_fee),
+        &hook::fee::InstantiateMsg {
+            owner: owner.to_string(),
+            fee: coin(1, "uosmo"),
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-hook-merkle",
+        wrap!(hpl_hook_merkle),
+        &hook::merkle::InstantiateMsg {
+            owner: owner.to_string(),
+            mailbox: mailbox.to_string(),
+            tree_depth: None,
+            store_leaves: None,
+        },
+    );
+
+    let hook_pausable = deploy(
+        &mut app,
+        "hpl-hook-pausable",
+        wrap!(hpl_hook_pausable),
+        &hook::pausable::InstantiateMsg {
+            owner: owner.to_string(),
+            paused: false,
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-hook-aggregate",
+        wrap!(hpl_hook_aggregate),
+        &hook::aggregate::InstantiateMsg {
+            owner: owner.to_string(),
+            hooks: vec![hook_pausable.to_string()],
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-hook-ratelimit",
+        wrap!(hpl_hook_ratelimit),
+        &hook::ratelimit::InstantiateMsg {
+            owner: owner.to_string(),
+            mailbox: mailbox.to_string(),
+            amount_offset: 32,
+            amount_length: 32,
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-hook-routing",
+        wrap!(hpl_hook_routing),
+        &hook::routing::InstantiateMsg {
+            owner: owner.to_string(),
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-hook-routing-custom",
+        wrap!(hpl_hook_routing_custom),
+        &hook::routing_custom::InstantiateMsg {
+            owner: owner.to_string(),
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-hook-routing-fallback",
+        wrap!(hpl_hook_routing_fallback),
+        &hook::routing_fallback::InstantiateMsg {
+            owner: owner.to_string(),
+        },
+    );
+
+    // igps
+    deploy(
+        &mut app,
+        "hpl-igp",
+        wrap!(hpl_igp::contract),
+        &igp::core::InstantiateMsg {
+            hrp: HRP.into(),
+            owner: owner.to_string(),
+            mailbox: mailbox.to_string(),
+            gas_token: "uosmo".into(),
+            beneficiary: owner.to_string(),
+            default_gas_usage: 250_000,
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-igp-oracle",
+        wrap!(hpl_igp_oracle::contract),
+        &igp::oracle::InstantiateMsg {
+            owner: owner.to_string(),
+        },
+    );
+
+    // isms
+    deploy(
+        &mut app,
+        "hpl-ism-multisig",
+        wrap!(hpl_ism_multisig::contract),
+        &ism::multisig::InstantiateMsg {
+            owner: owner.to_string(),
+            module_type: None,
+        },
+    );
+
+    let ism_multisig_code_id = app.store_code(wrap!(hpl_ism_multisig::contract));
+    deploy(
+        &mut app,
+        "hpl-ism-multisig-factory",
+        wrap!(hpl_ism_multisig_factory),
+        &ism::multisig_factory::InstantiateMsg {
+            owner: owner.to_string(),
+            ism_code_id: ism_multisig_code_id,
+        },
+    );
+
+    let ism_pausable = deploy(
+        &mut app,
+        "hpl-ism-pausable",
+        wrap!(hpl_ism_pausable),
+        &ism::pausable::InstantiateMsg {
+            owner: owner.to_string(),
+            paused: false,
+            ism: None,
+            paused_behavior: None,
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-ism-aggregate",
+        wrap!(hpl_ism_aggregate),
+        &ism::aggregate::InstantiateMsg {
+            owner: owner.to_string(),
+            isms: vec![ism_pausable.to_string()],
+            threshold: 1,
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-ism-routing",
+        wrap!(hpl_ism_routing::contract),
+        &ism::routing::InstantiateMsg {
+            owner: owner.to_string(),
+            isms: vec![],
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-ism-trusted-relayer",
+        wrap!(hpl_ism_trusted_relayer),
+        &ism::trusted_relayer::InstantiateMsg {
+            owner: owner.to_string(),
+            relayers: vec![owner.to_string()],
+        },
+    );
+
+    // warp
+    let cw20_code_id = app.store_code(Box::new(ContractWrapper::new(
+        cw20_base::contract::execute,
+        cw20_base::contract::instantiate,
+        cw20_base::contract::query,
+    )));
+    let cw20 = app
+        .instantiate_contract(
+            cw20_code_id,
+            owner.clone(),
+            &cw20_base::msg::InstantiateMsg {
+                name: "token".into(),
+                symbol: "TOKEN".into(),
+                decimals: 6,
+                initial_balances: vec![],
+                mint: None,
+                marketing: None,
+            },
+            &[],
+            "cw20",
+            None,
+        )
+        .unwrap();
+
+    deploy(
+        &mut app,
+        "hpl-warp-cw20",
+        wrap!(hpl_warp_cw20::contract),
+        &warp::cw20::InstantiateMsg {
+            token: TokenModeMsg::Collateral(warp::cw20::Cw20ModeCollateral {
+                address: cw20.to_string(),
+            }),
+            hrp: HRP.into(),
+            owner: owner.to_string(),
+            mailbox: mailbox.to_string(),
+        },
+    );
+
+    deploy(
+        &mut app,
+        "hpl-warp-native",
+        wrap!(hpl_warp_native::contract),
+        &warp::native::InstantiateMsg {
+            token: TokenModeMsg::Collateral(warp::native::NativeModeCollateral {
+                denom: "uosmo".into(),
+            }),
+            decimals: 6,
+            hrp: HRP.into(),
+            owner: owner.to_string(),
+            mailbox: mailbox.to_string(),
+        },
+    );
+}
@@ -53,18 +53,22 @@ pub fn handle<C: CustomQuery>(
 
             ISM.save(deps.storage, &ism_addr)?;
 
-            Ok(event_to_resp(
-                new_event("set_ism").add_attribute("ism", ism),
-            ))
+            Ok(event_to_resp(hpl_events::ism_set(
+                new_event("set_ism"),
+                &info.sender,
+                &[ism],
+            )))
         }
         SetHook { hook } => {
             let hook_addr = deps.api.addr_validate(&hook)?;
 
             HOOK.save(deps.storage, &hook_addr)?;
 
-            Ok(event_to_resp(
-                new_event("set_hook").add_attribute("hook", hook),
-            ))
+            Ok(event_to_resp(hpl_events::hook_set(
+                new_event("set_hook"),
+                &info.sender,
+                &[hook],
+            )))
         }
     }
 }
@@ -0,0 +1,260 @@
+//! Errors shared across hyperlane contracts.
+//!
+//! Every error renders with a stable `[EXXX]` prefix, also exposed through
+//! `code()`, so clients can tell failures apart without matching messages.
+//! Codes are pinned by the golden tests and never reused. Ranges:
+//! - `E001`-`E099`: shared errors, defined here
+//! - `E1XX`: mailbox, `E15X`: validator announce
+//! - `E2XX`: hooks
+//! - `E3XX`: igp
+//! - `E4XX`: isms
+//! - `E5XX`: warp routes
+//!
+//! Contracts with errors of their own wrap `HplError` in a `ContractError`
+//! and implement the conversions with `impl_from_hpl!`, the others use it as
+//! their `ContractError` directly.
+
+use cosmwasm_std::{CoinsError, RecoverPubkeyError, StdError, VerificationError};
+use cw_utils::{ParseReplyError, PaymentError};
+use hpl_ownable::OwnableError;
+use hpl_router::RouterError;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum HplError {
+    #[error("[E001] {0}")]
+    Std(#[from] StdError),
+
+    #[error("[E002] {0}")]
+    Ownable(#[from] OwnableError),
+
+    #[error("[E003] {0}")]
+    Router(#[from] RouterError),
+
+    #[error("[E004] {0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("[E005] {0}")]
+    Coins(#[from] CoinsError),
+
+    #[error("[E006] {0}")]
+    Verification(#[from] VerificationError),
+
+    #[error("[E007] {0}")]
+    RecoverPubkey(#[from] RecoverPubkeyError),
+
+    #[error("[E008] {0}")]
+    ParseReply(#[from] ParseReplyError),
+
+    #[error("[E009] {0}")]
+    ParseInt(#[from] std::num::ParseIntError),
+
+    #[error("[E010] unauthorized")]
+    Unauthorized {},
+
+    #[error("[E011] unauthorized. reason: {reason}")]
+    Forbidden { reason: String },
+
+    #[error("[E012] invalid config. reason: {reason}")]
+    InvalidConfig { reason: String },
+
+    #[error("[E013] invalid arguments. reason: {reason}")]
+    InvalidArguments { reason: String },
+
+    #[error("[E014] invalid address. reason: {reason}")]
+    InvalidAddress { reason: String },
+
+    #[error("[E015] invalid address length: {len}")]
+    InvalidAddressLength { len: usize },
+
+    #[error("[E016] verify failed")]
+    VerifyFailed {},
+
+    #[error("[E017] route not found for domain {domain}")]
+    RouteNotFound { domain: u32 },
+
+    #[error("[E018] hook not set")]
+    HookNotSet {},
+
+    #[error("[E019] invalid reply id")]
+    InvalidReplyId {},
+
+    #[error("[E020] paused")]
+    Paused {},
+
+    #[error("[E021] wrong length")]
+    WrongLength {},
+}
+
+impl HplError {
+    pub fn code(&self) -> u16 {
+        match self {
+            Self::Std(_) => 1,
+            Self::Ownable(_) => 2,
+            Self::Router(_) => 3,
+            Self::Payment(_) => 4,
+            Self::Coins(_) => 5,
+            Self::Verification(_) => 6,
+            Self::RecoverPubkey(_) => 7,
+            Self::ParseReply(_) => 8,
+            Self::ParseInt(_) => 9,
+            Self::Unauthorized {} => 10,
+            Self::Forbidden { .. } => 11,
+            Self::InvalidConfig { .. } => 12,
+            Self::InvalidArguments { .. } => 13,
+            Self::InvalidAddress { .. } => 14,
+            Self::InvalidAddressLength { .. } => 15,
+            Self::VerifyFailed {} => 16,
+            Self::RouteNotFound { .. } => 17,
+            Self::HookNotSet {} => 18,
+            Self::InvalidReplyId {} => 19,
+            Self::Paused {} => 20,
+            Self::WrongLength {} => 21,
+        }
+    }
+
+    pub fn forbidden(reason: &str) -> Self {
+        Self::Forbidden {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn invalid_config(reason: &str) -> Self {
+        Self::InvalidConfig {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn invalid_args(reason: &str) -> Self {
+        Self::InvalidArguments {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn invalid_addr(reason: &str) -> Self {
+        Self::InvalidAddress {
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// `[EXXX]` prefix of an error code
+pub fn code_prefix(code: u16) -> String {
+    format!("[E{code:03}]")
+}
+
+/// Converts every error wrapped by `HplError` into `$error` through its `Hpl` variant
+#[macro_export]
+macro_rules! impl_from_hpl {
+    ($error:ty) => {
+        $crate::impl_from_hpl!(
+            $error,
+            $crate::__private::StdError,
+            $crate::__private::OwnableError,
+            $crate::__private::RouterError,
+            $crate::__private::PaymentError,
+            $crate::__private::CoinsError,
+            $crate::__private::VerificationError,
+            $crate::__private::RecoverPubkeyError,
+            $crate::__private::ParseReplyError,
+            $crate::__private::ParseIntError
+        );
+    };
+    ($error:ty, $($from:ty),+) => {
+        $(
+            impl From<$from> for $error {
+                fn from(err: $from) -> Self {
+                    Self::Hpl($crate::HplError::from(err))
+                }
+            }
+        )+
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use cosmwasm_std::{CoinsError, RecoverPubkeyError, StdError, VerificationError};
+    pub use cw_utils::{ParseReplyError, PaymentError};
+    pub use hpl_ownable::OwnableError;
+    pub use hpl_router::RouterError;
+    pub use std::num::ParseIntError;
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{RecoverPubkeyError, StdError, VerificationError};
+    use cw_utils::{ParseReplyError, PaymentError};
+    use hpl_ownable::OwnableError;
+    use hpl_router::RouterError;
+    use rstest::rstest;
+
+    use super::*;
+
+    // codes are part of the client interface, never change or reuse one
+    #[rstest]
+    #[case(StdError::generic_err("std").into(), 1, "[E001] Generic error: std")]
+    #[case(OwnableError::NoOwner {}.into(), 2, "[E002] no owner")]
+    #[case(RouterError::EncodingFailed {}.into(), 3, "[E003] encoding failed")]
+    #[case(PaymentError::NoFunds {}.into(), 4, "[E004] No funds sent")]
+    #[case(
+        CoinsError::DuplicateDenom.into(),
+        5,
+        "[E005] Duplicate denom"
+    )]
+    #[case(
+        VerificationError::InvalidHashFormat.into(),
+        6,
+        "[E006] Invalid hash format"
+    )]
+    #[case(
+        RecoverPubkeyError::InvalidHashFormat.into(),
+        7,
+        "[E007] Invalid hash format"
+    )]
+    #[case(
+        ParseReplyError::ParseFailure("reply".to_string()).into(),
+        8,
+        "[E008] Invalid reply from sub-message: reply"
+    )]
+    #[case(
+        "x".parse::<u32>().unwrap_err().into(),
+        9,
+        "[E009] invalid digit found in string"
+    )]
+    #[case(HplError::Unauthorized {}, 10, "[E010] unauthorized")]
+    #[case(HplError::forbidden("why"), 11, "[E011] unauthorized. reason: why")]
+    #[case(
+        HplError::invalid_config("why"),
+        12,
+        "[E012] invalid config. reason: why"
+    )]
+    #[case(
+        HplError::invalid_args("why"),
+        13,
+        "[E013] invalid arguments. reason: why"
+    )]
+    #[case(
+        HplError::invalid_addr("why"),
+        14,
+        "[E014] invalid address. reason: why"
+    )]
+    #[case(
+        HplError::InvalidAddressLength { len: 3 },
+        15,
+        "[E015] invalid address length: 3"
+    )]
+    #[case(HplError::VerifyFailed {}, 16, "[E016] verify failed")]
+    #[case(
+        HplError::RouteNotFound { domain: 5 },
+        17,
+        "[E017] route not found for domain 5"
+    )]
+    #[case(HplError::HookNotSet {}, 18, "[E018] hook not set")]
+    #[case(HplError::InvalidReplyId {}, 19, "[E019] invalid reply id")]
+    #[case(HplError::Paused {}, 20, "[E020] paused")]
+    #[case(HplError::WrongLength {}, 21, "[E021] wrong length")]
+    fn test_codes(#[case] err: HplError, #[case] code: u16, #[case] display: &str) {
+        assert_eq!(err.code(), code);
+        assert_eq!(err.to_string(), display);
+        assert!(display.starts_with(&code_prefix(code)));
+    }
+}
@@ -0,0 +1,297 @@
+//! Events shared across hyperlane contracts.
+//!
+//! Attribute keys are part of the indexer interface and are pinned by the
+//! golden test below, so changing one is a breaking change. Values follow one
+//! encoding: binary values are lowercase hex without `0x`, addresses are
+//! bech32, numbers are decimal and lists are comma separated.
+//!
+//! Protocol events (dispatch, process, gas payment) keep a fixed event type.
+//! Configuration events (ism, hook and route changes) are built on the
+//! contract's own namespaced event, so only their attributes are shared.
+//!
+//! Dispatch events carry the routing context (`sender`, `destination`,
+//! `recipient`, `origin`) as attributes of their own, so relayers don't have
+//! to decode the message to know where to deliver it.
+//!
+//! The mailbox can emit `dispatch_body_hash` in place of `dispatch`. It keeps
+//! the `mailbox_dispatch` type but replaces `message` with the header fields
+//! and `body_hash`, the keccak256 of the body.
+//!
+//! Mapping changes from 0.0.6-rc6:
+//! - `mailbox_dispatch`: adds `origin`
+//! - `mailbox_default_ism_set`: `owner` -> `sender`, `new_default_ism` -> `ism`
+//! - `mailbox_default_hook_set`: `owner` -> `sender`, `new_default_hook` -> `hook`
+//! - `mailbox_required_hook_set`: `owner` -> `sender`, `new_required_hook` -> `hook`
+//! - `hpl_connection::set_ism` / `set_hook`: adds `sender`
+//! - `hpl_router::set_route`: `set` (json) -> `domain`, `route` (json)
+//! - `hpl_router::set_routes`: replaced by one `hpl_router::set_route` per route
+//! - `hpl_ism_aggregate::set_isms`: `isms` -> `ism`, adds `sender`
+//! - `hpl_hook_aggregate::set_hooks`: `hooks` -> `hook`
+//! - `hpl_hook_routing::set_fallback_hook`: `fallback-hook` -> `hook`
+//! - `hpl_ism_routing::set` / `unset`: unchanged
+
+use cosmwasm_std::{Addr, Event, HexBinary, Uint128, Uint256};
+use hpl_interface::types::{keccak256_hash, Message};
+
+pub const DISPATCH: &str = "mailbox_dispatch";
+pub const DISPATCH_ID: &str = "mailbox_dispatch_id";
+pub const PROCESS: &str = "mailbox_process";
+pub const PROCESS_ID: &str = "mailbox_process_id";
+pub const GAS_PAYMENT: &str = "igp-core-pay-for-gas";
+
+/// Every event built by this crate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    Dispatch,
+    DispatchBodyHash,
+    DispatchId,
+    Process,
+    ProcessId,
+    IsmSet,
+    HookSet,
+    RouteSet,
+    RouteUnset,
+    GasPayment,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 10] = [
+        EventKind::Dispatch,
+        EventKind::DispatchBodyHash,
+        EventKind::DispatchId,
+        EventKind::Process,
+        EventKind::ProcessId,
+        EventKind::IsmSet,
+        EventKind::HookSet,
+        EventKind::RouteSet,
+        EventKind::RouteUnset,
+        EventKind::GasPayment,
+    ];
+
+    /// Attribute keys of the event, in emission order
+    pub fn keys(&self) -> &'static [&'static str] {
+        match self {
+            EventKind::Dispatch => &["sender", "destination", "recipient", "origin", "message"],
+            EventKind::DispatchBodyHash => &[
+                "sender",
+                "destination",
+                "recipient",
+                "version",
+                "nonce",
+                "origin",
+                "body_hash",
+            ],
+            EventKind::DispatchId => &["message_id"],
+            EventKind::Process => &["origin", "sender", "recipient"],
+            EventKind::ProcessId => &["message_id", "relayer"],
+            EventKind::IsmSet => &["sender", "ism"],
+            EventKind::HookSet => &["sender", "hook"],
+            EventKind::RouteSet => &["sender", "domain", "route"],
+            EventKind::RouteUnset => &["sender", "domains"],
+            EventKind::GasPayment => &[
+                "sender",
+                "dest_domain",
+                "message_id",
+                "gas_amount",
+                "gas_refunded",
+                "gas_required",
+                "payment",
+            ],
+        }
+    }
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn dispatch(msg: Message) -> Event {
+    Event::new(DISPATCH)
+        .add_attribute("sender", msg.sender.to_hex())
+        .add_attribute("destination", msg.dest_domain.to_string())
+        .add_attribute("recipient", msg.recipient.to_hex())
+        .add_attribute("origin", msg.origin_domain.to_string())
+        .add_attribute("message", HexBinary::from(msg).to_hex())
+}
+
+pub fn dispatch_body_hash(msg: Message) -> Event {
+    Event::new(DISPATCH)
+        .add_attribute("sender", msg.sender.to_hex())
+        .add_attribute("destination", msg.dest_domain.to_string())
+        .add_attribute("recipient", msg.recipient.to_hex())
+        .add_attribute("version", msg.version.to_string())
+        .add_attribute("nonce", msg.nonce.to_string())
+        .add_attribute("origin", msg.origin_domain.to_string())
+        .add_attribute("body_hash", keccak256_hash(&msg.body).to_hex())
+}
+
+pub fn dispatch_id(id: &HexBinary) -> Event {
+    Event::new(DISPATCH_ID).add_attribute("message_id", id.to_hex())
+}
+
+pub fn process(origin: u32, sender: &HexBinary, recipient: &HexBinary) -> Event {
+    Event::new(PROCESS)
+        .add_attribute("origin", origin.to_string())
+        .add_attribute("sender", sender.to_hex())
+        .add_attribute("recipient", recipient.to_hex())
+}
+
+pub fn process_id(id: &HexBinary, relayer: &Addr) -> Event {
+    Event::new(PROCESS_ID)
+        .add_attribute("message_id", id.to_hex())
+        .add_attribute("relayer", relayer)
+}
+
+/// `isms` holds one address, or several for an aggregation
+pub fn ism_set(event: Event, sender: &Addr, isms: &[impl ToString]) -> Event {
+    event
+        .add_attribute("sender", sender)
+        .add_attribute("ism", join(isms))
+}
+
+/// `hooks` holds one address, or several for an aggregation
+pub fn hook_set(event: Event, sender: &Addr, hooks: &[impl ToString]) -> Event {
+    event
+        .add_attribute("sender", sender)
+        .add_attribute("hook", join(hooks))
+}
+
+/// `route` is the contract's own encoding of the route, e.g. an address or json
+pub fn route_set(event: Event, sender: &Addr, domain: u32, route: impl Into<String>) -> Event {
+    event
+        .add_attribute("sender", sender)
+        .add_attribute("domain", domain.to_string())
+        .add_attribute("route", route)
+}
+
+pub fn route_unset(event: Event, sender: &Addr, domains: &[u32]) -> Event {
+    event
+        .add_attribute("sender", sender)
+        .add_attribute("domains", join(domains))
+}
+
+pub fn gas_payment(
+    sender: &Addr,
+    dest_domain: u32,
+    message_id: &HexBinary,
+    gas_amount: Uint256,
+    gas_refunded: Uint128,
+    gas_required: Uint256,
+    payment: Uint256,
+) -> Event {
+    Event::new(GAS_PAYMENT)
+        .add_attribute("sender", sender)
+        .add_attribute("dest_domain", dest_domain.to_string())
+        .add_attribute("message_id", message_id.to_hex())
+        .add_attribute("gas_amount", gas_amount)
+        .add_attribute("gas_refunded", gas_refunded)
+        .add_attribute("gas_required", gas_required)
+        .add_attribute("payment", payment)
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{Addr, Event, HexBinary, Uint128, Uint256};
+    use hpl_interface::types::Message;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn sample_message() -> Message {
+        let id = HexBinary::from(vec![0xAB; 32]);
+
+        Message {
+            version: 3,
+            nonce: 1,
+            origin_domain: 1,
+            sender: id.clone(),
+            dest_domain: 2,
+            recipient: id,
+            body: HexBinary::from(vec![0xCD]),
+        }
+    }
+
+    fn sample(kind: EventKind) -> Event {
+        let sender = Addr::unchecked("sender");
+        let id = HexBinary::from(vec![0xAB; 32]);
+
+        // exhaustive, so a new event can't be added without pinning its keys
+        match kind {
+            EventKind::Dispatch => dispatch(sample_message()),
+            EventKind::DispatchBodyHash => dispatch_body_hash(sample_message()),
+            EventKind::DispatchId => dispatch_id(&id),
+            EventKind::Process => process(1, &id, &id),
+            EventKind::ProcessId => process_id(&id, &sender),
+            EventKind::IsmSet => ism_set(Event::new("test"), &sender, &["ism"]),
+            EventKind::HookSet => hook_set(Event::new("test"), &sender, &["hook"]),
+            EventKind::RouteSet => route_set(Event::new("test"), &sender, 1, "route"),
+            EventKind::RouteUnset => route_unset(Event::new("test"), &sender, &[1, 2]),
+            EventKind::GasPayment => gas_payment(
+                &sender,
+                1,
+                &id,
+                Uint256::from(100u128),
+                Uint128::new(10),
+                Uint256::from(90u128),
+                Uint256::from(90u128),
+            ),
+        }
+    }
+
+    #[rstest]
+    #[case(EventKind::Dispatch, "sender,destination,recipient,origin,message")]
+    #[case(
+        EventKind::DispatchBodyHash,
+        "sender,destination,recipient,version,nonce,origin,body_hash"
+    )]
+    #[case(EventKind::DispatchId, "message_id")]
+    #[case(EventKind::Process, "origin,sender,recipient")]
+    #[case(EventKind::ProcessId, "message_id,relayer")]
+    #[case(EventKind::IsmSet, "sender,ism")]
+    #[case(EventKind::HookSet, "sender,hook")]
+    #[case(EventKind::RouteSet, "sender,domain,route")]
+    #[case(EventKind::RouteUnset, "sender,domains")]
+    #[case(
+        EventKind::GasPayment,
+        "sender,dest_domain,message_id,gas_amount,gas_refunded,gas_required,payment"
+    )]
+    fn test_attribute_keys(#[case] kind: EventKind, #[case] golden: &str) {
+        let keys = sample(kind)
+            .attributes
+            .into_iter()
+            .map(|v| v.key)
+            .collect::<Vec<_>>();
+
+        assert_eq!(keys.join(","), golden);
+        assert_eq!(kind.keys().join(","), golden);
+    }
+
+    #[test]
+    fn test_all_kinds_pinned() {
+        // every kind appears once in `ALL`
+        for (i, kind) in EventKind::ALL.iter().enumerate() {
+            assert!(!EventKind::ALL[i + 1..].contains(kind));
+            assert!(!kind.keys().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_encoding() {
+        let id = HexBinary::from(vec![0xAB; 32]);
+
+        let event = process_id(&id, &Addr::unchecked("relayer"));
+        assert_eq!(event.ty, PROCESS_ID);
+        assert_eq!(event.attributes[0].value, "ab".repeat(32));
+
+        let event = route_unset(Event::new("test"), &Addr::unchecked("sender"), &[1, 2]);
+        assert_eq!(event.attributes[1].value, "1,2");
+
+        let event = dispatch_body_hash(sample_message());
+        assert_eq!(event.ty, DISPATCH);
+        assert_eq!(event.attributes[6].value, keccak256_hash(&[0xCD]).to_hex());
+    }
+}
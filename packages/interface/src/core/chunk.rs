@@ -0,0 +1,183 @@
+//! Bodies above the mailbox body limit can be split over several messages and reassembled
+//! by the recipient. Each chunk body is `correlation_id (32) || total (u16) || index (u16) || data`
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{HexBinary, StdError, StdResult};
+
+/// Length of the header preceding the data of a chunk
+pub const CHUNK_HEADER_LENGTH: usize = 36;
+
+#[cw_serde]
+pub struct ChunkHeader {
+    /// Chosen by the sender, the same for every chunk of a body
+    pub correlation_id: HexBinary,
+    /// Number of chunks of the body
+    pub total: u16,
+    /// Position of the chunk in the body, below `total`
+    pub index: u16,
+}
+
+impl ChunkHeader {
+    fn validate(&self) -> StdResult<()> {
+        if self.correlation_id.len() != 32 {
+            return Err(StdError::generic_err(format!(
+                "invalid correlation id length. expected: 32. got: {}",
+                self.correlation_id.len()
+            )));
+        }
+        if self.index >= self.total {
+            return Err(StdError::generic_err(format!(
+                "chunk index {} out of {}",
+                self.index, self.total
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Prepends the header to `data`
+    pub fn encode(&self, data: &[u8]) -> StdResult<HexBinary> {
+        self.validate()?;
+
+        let mut bz = Vec::with_capacity(CHUNK_HEADER_LENGTH + data.len());
+        bz.extend_from_slice(&self.correlation_id);
+        bz.extend_from_slice(&self.total.to_be_bytes());
+        bz.extend_from_slice(&self.index.to_be_bytes());
+        bz.extend_from_slice(data);
+
+        Ok(bz.into())
+    }
+
+    /// Splits a chunk body into its header and data
+    pub fn decode(body: &[u8]) -> StdResult<(Self, &[u8])> {
+        if body.len() < CHUNK_HEADER_LENGTH {
+            return Err(StdError::generic_err(format!(
+                "invalid chunk length. expected at least {CHUNK_HEADER_LENGTH}, got: {}",
+                body.len()
+            )));
+        }
+
+        let header = Self {
+            correlation_id: body[0..32].to_vec().into(),
+            total: u16::from_be_bytes(body[32..34].try_into().unwrap()),
+            index: u16::from_be_bytes(body[34..36].try_into().unwrap()),
+        };
+        header.validate()?;
+
+        Ok((header, &body[CHUNK_HEADER_LENGTH..]))
+    }
+}
+
+/// Splits `body` into chunk bodies carrying up to `chunk_size` bytes of it each.
+/// An empty body is sent as a single empty chunk
+pub fn split_chunks(
+    correlation_id: &HexBinary,
+    body: &[u8],
+    chunk_size: usize,
+) -> StdResult<Vec<HexBinary>> {
+    if chunk_size == 0 {
+        return Err(StdError::generic_err("chunk size must not be zero"));
+    }
+
+    let total = body.len().div_ceil(chunk_size).max(1);
+    let total = u16::try_from(total)
+        .map_err(|_| StdError::generic_err(format!("too many chunks. got: {total}")))?;
+
+    (0..total)
+        .map(|index| {
+            let start = (index as usize * chunk_size).min(body.len());
+            let end = (start + chunk_size).min(body.len());
+
+            ChunkHeader {
+                correlation_id: correlation_id.clone(),
+                total,
+                index,
+            }
+            .encode(&body[start..end])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{HexBinary, StdError};
+    use ibcx_test_utils::gen_bz;
+    use rstest::rstest;
+
+    use super::{split_chunks, ChunkHeader, CHUNK_HEADER_LENGTH};
+
+    #[rstest]
+    #[case(0, 10, 1)]
+    #[case(10, 10, 1)]
+    #[case(29, 10, 3)]
+    #[case(30, 10, 3)]
+    fn test_split_chunks(#[case] len: usize, #[case] chunk_size: usize, #[case] total: u16) {
+        let correlation_id = gen_bz(32);
+        let body = gen_bz(len);
+
+        let chunks = split_chunks(&correlation_id, &body, chunk_size).unwrap();
+        assert_eq!(chunks.len(), total as usize);
+
+        let mut joined = vec![];
+        for (i, chunk) in chunks.iter().enumerate() {
+            let (header, data) = ChunkHeader::decode(chunk).unwrap();
+            assert_eq!(
+                header,
+                ChunkHeader {
+                    correlation_id: correlation_id.clone(),
+                    total,
+                    index: i as u16,
+                }
+            );
+            assert!(data.len() <= chunk_size);
+
+            joined.extend_from_slice(data);
+        }
+        assert_eq!(joined, body.to_vec());
+    }
+
+    #[test]
+    fn test_encode_layout() {
+        let header = ChunkHeader {
+            correlation_id: vec![0xab; 32].into(),
+            total: 3,
+            index: 1,
+        };
+
+        let encoded = header.encode(b"data").unwrap();
+        // correlation id || total || index || "data"
+        assert_eq!(
+            encoded.to_hex(),
+            format!("{}0003000164617461", "ab".repeat(32))
+        );
+    }
+
+    #[rstest]
+    #[case(gen_bz(CHUNK_HEADER_LENGTH - 1), "invalid chunk length. expected at least 36, got: 35")]
+    #[case(
+        [vec![0; 32], vec![0, 2, 0, 2]].concat().into(),
+        "chunk index 2 out of 2"
+    )]
+    #[case(
+        [vec![0; 32], vec![0, 0, 0, 0]].concat().into(),
+        "chunk index 0 out of 0"
+    )]
+    fn test_decode_invalid(#[case] body: HexBinary, #[case] err: &str) {
+        assert_eq!(
+            ChunkHeader::decode(&body).unwrap_err(),
+            StdError::generic_err(err)
+        );
+    }
+
+    #[test]
+    fn test_split_chunks_invalid() {
+        assert_eq!(
+            split_chunks(&gen_bz(32), &gen_bz(10), 0).unwrap_err(),
+            StdError::generic_err("chunk size must not be zero")
+        );
+        assert_eq!(
+            split_chunks(&gen_bz(20), &gen_bz(10), 10).unwrap_err(),
+            StdError::generic_err("invalid correlation id length. expected: 32. got: 20")
+        );
+    }
+}
@@ -1,11 +1,18 @@
+use std::fmt;
+
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{wasm_execute, Addr, Api, Coin, CosmosMsg, HexBinary, StdResult};
+use cosmwasm_std::{
+    ensure, wasm_execute, Addr, Api, Binary, Coin, CosmosMsg, CustomQuery, HexBinary,
+    QuerierWrapper, StdError, StdResult, Timestamp, Uint128, Uint256,
+};
 
 #[allow(unused_imports)]
 use crate::{
     hook::QuoteDispatchResponse,
+    ism::VerifyResponse,
     ownable::{OwnableMsg, OwnableQueryMsg},
     types,
+    version::VersionQueryMsg,
 };
 
 #[cw_serde]
@@ -13,15 +20,27 @@ pub struct InstantiateMsg {
     pub hrp: String,
     pub owner: String,
     pub domain: u32,
+    pub default_ism: Option<String>,
+    pub default_hook: Option<String>,
 }
 
+/// Fields also accept the camelCase names the typescript sdk writes
 #[cw_serde]
 pub struct DispatchMsg {
+    #[serde(alias = "destDomain")]
     pub dest_domain: u32,
+    #[serde(alias = "recipientAddr")]
     pub recipient_addr: HexBinary,
+    #[serde(alias = "msgBody")]
     pub msg_body: HexBinary,
     pub hook: Option<String>,
     pub metadata: Option<HexBinary>,
+    /// Origin sender of the message, only honored for approved dispatchers
+    #[serde(alias = "senderOverride")]
+    pub sender_override: Option<String>,
+    /// Native value escrowed by the mailbox and sent to the recipient on delivery
+    #[serde(alias = "msgValue")]
+    pub msg_value: Option<Coin>,
 }
 
 impl DispatchMsg {
@@ -36,6 +55,8 @@ impl DispatchMsg {
             msg_body: msg_body.into(),
             hook: None,
             metadata: None,
+            sender_override: None,
+            msg_value: None,
         }
     }
 
@@ -49,6 +70,57 @@ impl DispatchMsg {
         self
     }
 
+    pub fn with_sender_override(mut self, sender: impl Into<String>) -> Self {
+        self.sender_override = Some(sender.into());
+        self
+    }
+
+    /// Gas limit paid to the igp for the destination, zero falls back to the igp default
+    pub fn with_gas_limit(self, gas_limit: u64) -> Self {
+        self.update_standard_metadata(|v| v.gas_limit = Uint256::from(gas_limit))
+    }
+
+    /// Escrows `msg_value` for the recipient and declares its amount in the standard metadata.
+    /// The funds sent with `Dispatch` must cover it on top of the hook fees.
+    /// Only allowed for the local domain, whose mailbox releases the escrow
+    pub fn with_msg_value(mut self, msg_value: Coin) -> Self {
+        let amount = msg_value.amount;
+        self.msg_value = Some(msg_value);
+        self.update_standard_metadata(|v| v.msg_value = amount.into())
+    }
+
+    /// Address refunded the hook overpayment, must be a 20 byte bech32 address
+    pub fn with_refund_address(self, refund_address: &str) -> StdResult<Self> {
+        let raw_addr = types::bech32_decode(refund_address)?;
+        let raw_addr = types::unpad(&raw_addr, 20).to_vec();
+        ensure!(
+            raw_addr.len() == 20,
+            StdError::generic_err(format!(
+                "refund address must be 20 bytes. got: {}",
+                raw_addr.len()
+            ))
+        );
+
+        Ok(self.update_standard_metadata(|v| v.refund_address = raw_addr.into()))
+    }
+
+    /// Sets one field of the standard hook metadata, keeping the others.
+    /// Metadata in another layout is replaced
+    fn update_standard_metadata(
+        mut self,
+        update: impl FnOnce(&mut types::StandardHookMetadata),
+    ) -> Self {
+        let mut metadata = self
+            .metadata
+            .as_ref()
+            .and_then(types::StandardHookMetadata::try_from_hex)
+            .unwrap_or_default();
+        update(&mut metadata);
+
+        self.metadata = Some(metadata.into());
+        self
+    }
+
     pub fn to_msg(
         self,
         version: u8,
@@ -60,7 +132,7 @@ impl DispatchMsg {
             version,
             nonce,
             origin_domain,
-            sender: types::bech32_to_h256(&sender.into())?.to_vec().into(),
+            sender: types::CanonicalSender::from_addr(&sender.into())?.into(),
             dest_domain: self.dest_domain,
             recipient: self.recipient_addr,
             body: self.msg_body,
@@ -83,16 +155,108 @@ pub enum ExecuteMsg {
     Ownable(OwnableMsg),
 
     // Mailbox
+    /// The ism and hook setters check their target answers `ModuleType` or `HookType`,
+    /// `unchecked` skips it for modules that don't
     SetDefaultIsm {
         ism: String,
+        #[serde(default)]
+        unchecked: bool,
     },
 
     SetDefaultHook {
         hook: String,
+        #[serde(default)]
+        unchecked: bool,
     },
 
     SetRequiredHook {
         hook: String,
+        #[serde(default)]
+        unchecked: bool,
+    },
+
+    /// Limits the size of dispatched message bodies, `None` removes the limit
+    SetMaxBodySize {
+        max_body_size: Option<u64>,
+    },
+
+    /// Registers an ism that `Process` verifies with `VerifyWithContext`,
+    /// passing the relayer along with the message
+    SetContextIsm {
+        ism: String,
+        enabled: bool,
+    },
+
+    /// Allows `dispatcher` to dispatch with `sender_override`
+    SetApprovedDispatcher {
+        dispatcher: String,
+        approved: bool,
+    },
+
+    /// Drops the cached ism of `recipient`, so the next `Process` queries it again.
+    /// Callable by the owner or the recipient itself
+    InvalidateIsmCache {
+        recipient: String,
+    },
+
+    /// Assigns the ism that recipients instantiated from `code_id` follow when they don't
+    /// specify one, ahead of the default ism. `None` removes it
+    SetCodeIdIsm {
+        code_id: u64,
+        ism: Option<String>,
+    },
+
+    /// Labels `domain` with a chain name for clients, `None` removes the label
+    SetDomainName {
+        domain: u32,
+        name: Option<String>,
+    },
+
+    /// Opens `Process` to anyone or restricts it to the listed relayers.
+    /// `Restricted` replaces the previous list, `Open` clears it
+    SetProcessAuthorization {
+        mode: ProcessAuthorizationMode,
+    },
+
+    /// Restricts `Process` to messages for the listed recipients, `None` lifts the restriction.
+    /// Replaces the previous list
+    SetRecipientAllowlist {
+        recipients: Option<Vec<String>>,
+    },
+
+    /// Accepts addresses under these prefixes besides `hrp`, e.g. while the chain is renaming it.
+    /// Addresses are still encoded with `hrp`. Replaces the previous list
+    SetAltHrps {
+        hrps: Vec<String>,
+    },
+
+    /// Lets the sender, as a recipient, skip its `InterchainSecurityModule` query in `Process`
+    /// and follow the default ism
+    SetIsmLookupOptOut {
+        opt_out: bool,
+    },
+
+    /// Lets the sender, as a recipient, have its ism cached on the first `Process` instead of
+    /// queried every time. Off by default, disabling drops the cached ism
+    SetIsmCaching {
+        enabled: bool,
+    },
+
+    /// Chooses whether the dispatch event carries the full message or only its body hash
+    SetEventBodyMode {
+        mode: EventBodyMode,
+    },
+
+    /// Turns on paying the process rewards set by recipients to relayers
+    SetProcessRewardsEnabled {
+        enabled: bool,
+    },
+
+    /// Sets the reward the sender, as a recipient, pays the relayer of each delivered message.
+    /// Funds sent along top up its reward balance. `None` removes the reward and refunds
+    /// the balance, as does changing the denom
+    SetProcessReward {
+        reward: Option<Coin>,
     },
 
     Dispatch(DispatchMsg),
@@ -101,6 +265,54 @@ pub enum ExecuteMsg {
         metadata: HexBinary,
         message: HexBinary,
     },
+
+    /// Processes up to 20 messages in order. Any failing item aborts the whole batch
+    ProcessBatch {
+        items: Vec<ProcessItem>,
+    },
+
+    /// Removes up to `limit` stored dispatched ids with a nonce lower than `below_nonce`,
+    /// resuming after the ids removed by previous calls
+    PruneDispatchedIds {
+        below_nonce: u32,
+        limit: u32,
+    },
+
+    /// Keeps only the ids of the last `retention` dispatched messages, `None` keeps all of them
+    SetDispatchedIdsRetention {
+        retention: Option<u32>,
+    },
+
+    /// Rewrites up to `limit` legacy delivery records in the compact format
+    MigrateDeliveries {
+        limit: u32,
+    },
+
+    /// Marks message ids delivered by a previous mailbox on this domain as delivered,
+    /// so they can't be replayed here. At most 100 ids per call
+    ImportDeliveries {
+        ids: Vec<HexBinary>,
+    },
+
+    /// Records the current `ConfigSnapshot` under the next snapshot id.
+    /// Only the latest 50 snapshots are kept
+    SnapshotConfig {},
+}
+
+/// Privileged config changes, e.g. by chain governance through the admin module
+#[cw_serde]
+pub enum SudoMsg {
+    SetDefaultIsm {
+        ism: String,
+        #[serde(default)]
+        unchecked: bool,
+    },
+
+    SetDefaultHook {
+        hook: String,
+        #[serde(default)]
+        unchecked: bool,
+    },
 }
 
 pub fn dispatch(
@@ -120,6 +332,8 @@ pub fn dispatch(
             msg_body,
             hook,
             metadata,
+            sender_override: None,
+            msg_value: None,
         }),
         funds,
     )?
@@ -132,6 +346,28 @@ pub fn process(mailbox: impl Into<String>, metadata: HexBinary, message: HexBina
         .into()
 }
 
+/// Quotes the required and the selected hook fees of dispatching `msg` from `sender`
+pub fn quote_dispatch<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    mailbox: impl Into<String>,
+    sender: impl Into<String>,
+    msg: DispatchMsg,
+) -> StdResult<QuoteDispatchResponse> {
+    querier.query_wasm_smart(
+        mailbox,
+        &QueryMsg::Hook(MailboxHookQueryMsg::QuoteDispatch {
+            sender: sender.into(),
+            msg,
+        }),
+    )
+}
+
+#[cw_serde]
+pub struct ProcessItem {
+    pub metadata: HexBinary,
+    pub message: HexBinary,
+}
+
 #[cw_serde]
 pub struct DispatchResponse {
     pub message_id: HexBinary,
@@ -148,6 +384,8 @@ pub enum QueryMsg {
 
     // mailbox
     Mailbox(MailboxQueryMsg),
+
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
@@ -169,6 +407,13 @@ pub enum MailboxQueryMsg {
     #[returns(MessageDeliveredResponse)]
     MessageDelivered { id: HexBinary },
 
+    /// Pages through delivered message ids, to be imported by a new mailbox
+    #[returns(ExportDeliveriesResponse)]
+    ExportDeliveries {
+        start_after: Option<HexBinary>,
+        limit: Option<u32>,
+    },
+
     #[returns(DefaultIsmResponse)]
     DefaultIsm {},
 
@@ -178,14 +423,96 @@ pub enum MailboxQueryMsg {
     #[returns(RequiredHookResponse)]
     RequiredHook {},
 
+    #[returns(MaxBodySizeResponse)]
+    MaxBodySize {},
+
+    #[returns(DispatchedIdsRetentionResponse)]
+    DispatchedIdsRetention {},
+
+    /// Mailbox config in a single query
+    #[returns(ConfigResponse)]
+    Config {},
+
+    /// Reports which parts of the config are set, in a single query
+    #[returns(ReadyResponse)]
+    Ready {},
+
     #[returns(NonceResponse)]
     Nonce {},
 
     #[returns(RecipientIsmResponse)]
     RecipientIsm { recipient_addr: String },
 
+    #[returns(ContextIsmsResponse)]
+    ContextIsms {},
+
+    #[returns(CodeIdIsmResponse)]
+    CodeIdIsm { code_id: u64 },
+
+    #[returns(ApprovedDispatchersResponse)]
+    ApprovedDispatchers {},
+
+    #[returns(ProcessAuthorizationResponse)]
+    ProcessAuthorization {},
+
+    #[returns(RecipientAllowlistResponse)]
+    RecipientAllowlist {},
+
+    #[returns(EventBodyModeResponse)]
+    EventBodyMode {},
+
+    #[returns(ProcessRewardResponse)]
+    ProcessReward { recipient: String },
+
+    #[returns(DomainNameResponse)]
+    DomainName { domain: u32 },
+
+    #[returns(DomainNamesResponse)]
+    DomainNames {},
+
+    /// Verifies the metadata with the ism `Process` would resolve for the message
+    #[returns(VerifyResponse)]
+    Verify {
+        metadata: HexBinary,
+        message: HexBinary,
+    },
+
     #[returns(LatestDispatchedIdResponse)]
     LatestDispatchId {},
+
+    /// Id of the message with these fields, saves clients encoding it themselves
+    #[returns(MessageIdResponse)]
+    MessageId {
+        version: u8,
+        nonce: u32,
+        origin_domain: u32,
+        sender: HexBinary,
+        dest_domain: u32,
+        recipient: HexBinary,
+        body: HexBinary,
+    },
+
+    #[returns(DispatchedIdAtNonceResponse)]
+    DispatchedIdAtNonce { nonce: u32 },
+
+    #[returns(DispatchedIdsResponse)]
+    DispatchedIds {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+
+    #[returns(SnapshotResponse)]
+    Snapshot { id: u64 },
+
+    #[returns(SnapshotsResponse)]
+    Snapshots {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Runs each sub-query in order, failed ones are returned as their error string
+    #[returns(MultiQueryResponse)]
+    MultiQuery { queries: Vec<MailboxQuery> },
 }
 impl MailboxQueryMsg {
     pub fn wrap(self) -> QueryMsg {
@@ -193,9 +520,30 @@ impl MailboxQueryMsg {
     }
 }
 
+#[cw_serde]
+pub enum MailboxQuery {
+    Hook(MailboxHookQueryMsg),
+    Mailbox(MailboxQueryMsg),
+}
+
+impl From<MailboxHookQueryMsg> for MailboxQuery {
+    fn from(v: MailboxHookQueryMsg) -> Self {
+        Self::Hook(v)
+    }
+}
+
+impl From<MailboxQueryMsg> for MailboxQuery {
+    fn from(v: MailboxQueryMsg) -> Self {
+        Self::Mailbox(v)
+    }
+}
+
 #[cw_serde]
 pub struct HrpResponse {
     pub hrp: String,
+    /// Prefixes accepted besides `hrp`
+    #[serde(default)]
+    pub alt_hrps: Vec<String>,
 }
 
 #[cw_serde]
@@ -208,6 +556,11 @@ pub struct MessageDeliveredResponse {
     pub delivered: bool,
 }
 
+#[cw_serde]
+pub struct ExportDeliveriesResponse {
+    pub ids: Vec<HexBinary>,
+}
+
 #[cw_serde]
 pub struct DefaultIsmResponse {
     pub default_ism: String,
@@ -223,11 +576,129 @@ pub struct RequiredHookResponse {
     pub required_hook: String,
 }
 
+#[cw_serde]
+pub struct MaxBodySizeResponse {
+    pub max_body_size: Option<u64>,
+}
+
+#[cw_serde]
+pub struct DispatchedIdsRetentionResponse {
+    pub retention: Option<u32>,
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub hrp: String,
+    pub alt_hrps: Vec<String>,
+    pub local_domain: u32,
+    pub default_ism: Option<Addr>,
+    pub default_hook: Option<Addr>,
+    pub required_hook: Option<Addr>,
+    /// `None` if dispatch doesn't limit the body size
+    pub max_body_size: Option<u64>,
+    pub process_restricted: bool,
+    pub recipients_restricted: bool,
+    pub process_rewards: bool,
+    pub event_body_mode: EventBodyMode,
+    pub dispatched_ids_retention: Option<u32>,
+}
+
+#[cw_serde]
+pub struct ReadyResponse {
+    pub has_default_ism: bool,
+    pub has_default_hook: bool,
+    pub has_required_hook: bool,
+    pub owner: Addr,
+}
+
 #[cw_serde]
 pub struct RecipientIsmResponse {
     pub ism: String,
 }
 
+#[cw_serde]
+pub struct ContextIsmsResponse {
+    pub isms: Vec<String>,
+}
+
+#[cw_serde]
+pub struct CodeIdIsmResponse {
+    pub ism: Option<String>,
+}
+
+#[cw_serde]
+pub struct ApprovedDispatchersResponse {
+    pub dispatchers: Vec<String>,
+}
+
+#[cw_serde]
+pub enum ProcessAuthorizationMode {
+    /// Anyone can relay messages
+    Open,
+    /// Only the listed relayers can relay messages
+    Restricted { relayers: Vec<String> },
+}
+
+#[cw_serde]
+pub struct ProcessAuthorizationResponse {
+    pub mode: ProcessAuthorizationMode,
+}
+
+#[cw_serde]
+pub struct RecipientAllowlistResponse {
+    /// `None` if any recipient is allowed
+    pub recipients: Option<Vec<String>>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum EventBodyMode {
+    /// The dispatch event carries the encoded message, body included
+    #[default]
+    Full,
+    /// The dispatch event carries the header fields and `keccak256(body)`
+    HashOnly,
+}
+
+impl fmt::Display for EventBodyMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventBodyMode::Full => write!(f, "full"),
+            EventBodyMode::HashOnly => write!(f, "hash_only"),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct EventBodyModeResponse {
+    pub mode: EventBodyMode,
+}
+
+#[cw_serde]
+pub struct ProcessRewardResponse {
+    /// Whether rewards are paid at all, see `SetProcessRewardsEnabled`
+    pub enabled: bool,
+    pub reward: Option<Coin>,
+    /// Left to pay rewards from, in the reward denom
+    pub balance: Uint128,
+}
+
+#[cw_serde]
+pub struct DomainNameResponse {
+    pub name: Option<String>,
+}
+
+#[cw_serde]
+pub struct DomainName {
+    pub domain: u32,
+    pub name: String,
+}
+
+#[cw_serde]
+pub struct DomainNamesResponse {
+    pub domains: Vec<DomainName>,
+}
+
 #[cw_serde]
 pub struct NonceResponse {
     pub nonce: u32,
@@ -236,4 +707,180 @@ pub struct NonceResponse {
 #[cw_serde]
 pub struct LatestDispatchedIdResponse {
     pub message_id: HexBinary,
+    pub nonce: u32,
+}
+
+#[cw_serde]
+pub struct MessageIdResponse {
+    pub message_id: HexBinary,
+}
+
+#[cw_serde]
+pub struct DispatchedIdAtNonceResponse {
+    pub message_id: Option<HexBinary>,
+}
+
+#[cw_serde]
+pub struct DispatchedId {
+    pub nonce: u32,
+    pub message_id: HexBinary,
+}
+
+#[cw_serde]
+pub struct DispatchedIdsResponse {
+    pub ids: Vec<DispatchedId>,
+}
+
+/// Mailbox config as of a `SnapshotConfig`
+#[cw_serde]
+pub struct ConfigSnapshot {
+    pub height: u64,
+    pub time: Timestamp,
+    pub owner: Addr,
+    pub hrp: String,
+    pub alt_hrps: Vec<String>,
+    pub local_domain: u32,
+    pub default_ism: Option<Addr>,
+    pub default_hook: Option<Addr>,
+    pub required_hook: Option<Addr>,
+    pub max_body_size: Option<u64>,
+    pub process_restricted: bool,
+    #[serde(default)]
+    pub recipients_restricted: bool,
+    #[serde(default)]
+    pub process_rewards: bool,
+    pub event_body_mode: EventBodyMode,
+    pub nonce: u32,
+}
+
+#[cw_serde]
+pub struct SnapshotResponse {
+    pub id: u64,
+    /// `ConfigSnapshot` as stored, so snapshots of an older layout stay readable
+    pub snapshot: Binary,
+}
+
+#[cw_serde]
+pub struct SnapshotsResponse {
+    pub snapshots: Vec<SnapshotResponse>,
+}
+
+#[cw_serde]
+pub struct MultiQueryResponse {
+    pub results: Vec<Result<Binary, String>>,
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{coin, from_json, to_json_string};
+    use ibcx_test_utils::gen_bz;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::types::{bech32_encode, IGPMetadata, StandardHookMetadata};
+
+    #[test]
+    fn test_dispatch_msg_metadata() {
+        let refund_address = bech32_encode("osmo", gen_bz(20).as_slice()).unwrap();
+
+        // fields are kept whatever the order they're set in
+        let msg = DispatchMsg::new(1, gen_bz(32), gen_bz(32))
+            .with_metadata(gen_bz(12))
+            .with_refund_address(refund_address.as_str())
+            .unwrap()
+            .with_gas_limit(300_000)
+            .with_msg_value(coin(10, "utest"))
+            .with_hook("hook");
+        assert_eq!(msg.hook, Some("hook".to_string()));
+        assert_eq!(msg.msg_value, Some(coin(10, "utest")));
+
+        let metadata = msg.metadata.unwrap();
+        assert_eq!(
+            StandardHookMetadata::try_from_hex(&metadata).unwrap(),
+            StandardHookMetadata {
+                msg_value: Uint256::from_u128(10),
+                gas_limit: Uint256::from_u128(300_000),
+                refund_address: types::bech32_decode(refund_address.as_str())
+                    .unwrap()
+                    .into(),
+                custom: HexBinary::default(),
+            }
+        );
+
+        let igp_metadata = IGPMetadata::from(metadata);
+        assert_eq!(igp_metadata.gas_limit, Uint256::from_u128(300_000));
+        assert_eq!(
+            igp_metadata.get_refund_address("osmo", Addr::unchecked("sender")),
+            refund_address
+        );
+    }
+
+    // as written by the typescript sdk
+    const SDK_DISPATCH_MSG: &str = r#"{
+        "dispatch": {
+            "destDomain": 11155111,
+            "recipientAddr": "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "msgBody": "68656c6c6f",
+            "hook": null,
+            "metadata": null,
+            "senderOverride": null,
+            "msgValue": { "denom": "uosmo", "amount": "10" }
+        }
+    }"#;
+
+    #[test]
+    fn test_dispatch_msg_camel_case() {
+        let msg: ExecuteMsg = from_json(SDK_DISPATCH_MSG).unwrap();
+
+        let expected = DispatchMsg {
+            msg_value: Some(coin(10, "uosmo")),
+            ..DispatchMsg::new(
+                11155111,
+                HexBinary::from_hex(
+                    "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+                )
+                .unwrap(),
+                HexBinary::from(b"hello"),
+            )
+        };
+        assert_eq!(msg, ExecuteMsg::Dispatch(expected));
+
+        // written back in snake_case
+        let written = to_json_string(&msg).unwrap();
+        assert!(written.contains("\"dest_domain\":11155111"));
+        assert!(!written.contains("destDomain"));
+        assert_eq!(from_json::<ExecuteMsg>(&written).unwrap(), msg);
+    }
+
+    #[test]
+    fn test_dispatch_msg_refund_address_invalid() {
+        let contract = bech32_encode("osmo", gen_bz(32).as_slice()).unwrap();
+
+        let err = DispatchMsg::new(1, gen_bz(32), gen_bz(32))
+            .with_refund_address(contract.as_str())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            StdError::generic_err("refund address must be 20 bytes. got: 32")
+        );
+    }
+
+    #[rstest]
+    // left-padded like `TypeCasts.addressToBytes32(0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed)`
+    #[case(
+        "osmo1t2htvpfl862vnwdqnuekd9p4ulh3h6hdlvdhh0",
+        "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+    )]
+    #[case(
+        "osmo17w4q6efzym3p4c6umyp4cjf2ustjtmwfqdhd7rt2fpcpk9fmjzsqmymnqz",
+        "f3aa0d652226e21ae35cd9035c492ae41725edc9036edf0d6a48701b153b90a0"
+    )]
+    fn test_dispatch_msg_sender(#[case] sender: &str, #[case] golden: &str) {
+        let msg = DispatchMsg::new(1, gen_bz(32), gen_bz(32))
+            .to_msg(3, 0, 26657, sender)
+            .unwrap();
+
+        assert_eq!(msg.sender.to_hex(), golden);
+        assert_eq!(msg.sender_addr("osmo").unwrap().as_str(), sender);
+    }
 }
@@ -1,6 +1,7 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::HexBinary;
 
+pub mod chunk;
 pub mod mailbox;
 pub mod va;
 
@@ -1,19 +1,60 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::HexBinary;
+use cosmwasm_std::{Addr, HexBinary};
+
+#[allow(unused_imports)]
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg, OwnerResponse},
+    version::{ContractVersionResponse, VersionQueryMsg},
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub hrp: String,
+    pub owner: String,
     pub mailbox: String,
 }
 
+#[cw_serde]
+pub struct InstantiateResponse {
+    pub owner: Addr,
+    pub mailbox: Addr,
+}
+
+/// What an announcement signature signs over
+#[cw_serde]
+#[derive(Default)]
+pub enum SignatureFormat {
+    /// eip-191 signed announcement digest
+    #[default]
+    EthSignedMessage,
+    /// eip-712 typed `Announcement(uint32 localDomain,bytes32 mailbox,string storageLocation)`
+    TypedData,
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Announce {
         validator: HexBinary,
         signature: HexBinary,
         storage_location: String,
+        /// defaults to `EthSignedMessage`
+        format: Option<SignatureFormat>,
     },
+
+    /// Removes an announced storage location, signed over the unannouncement digest
+    Unannounce {
+        validator: HexBinary,
+        storage_location: String,
+        signature: HexBinary,
+    },
+
+    /// Owner only, removes a storage location without the validator's signature
+    ForceUnannounce {
+        validator: HexBinary,
+        storage_location: String,
+    },
+
+    Ownable(OwnableMsg),
 }
 
 #[cw_serde]
@@ -30,6 +71,12 @@ pub enum QueryMsg {
 
     #[returns(LocalDomainResponse)]
     LocalDomain {},
+
+    #[returns(ContractVersionResponse)]
+    Version(VersionQueryMsg),
+
+    #[returns(OwnerResponse)]
+    Ownable(OwnableQueryMsg),
 }
 
 #[cw_serde]
@@ -0,0 +1,384 @@
+use sha3::{Digest, Keccak256};
+
+const DOMAIN_HASH_SUFFIX: &[u8] = b"HYPERLANE";
+const ANNOUNCEMENT_DOMAIN_HASH_SUFFIX: &[u8] = b"HYPERLANE_ANNOUNCEMENT";
+const UNANNOUNCEMENT_DOMAIN_HASH_SUFFIX: &[u8] = b"HYPERLANE_UNANNOUNCEMENT";
+const ETH_SIGNED_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n32";
+const TYPED_DATA_PREFIX: &[u8] = b"\x19\x01";
+
+const EIP712_DOMAIN_TYPE: &[u8] = b"EIP712Domain(string name,string version)";
+const EIP712_DOMAIN_NAME: &[u8] = b"Hyperlane ValidatorAnnounce";
+const EIP712_DOMAIN_VERSION: &[u8] = b"1";
+const ANNOUNCEMENT_TYPE: &[u8] =
+    b"Announcement(uint32 localDomain,bytes32 mailbox,string storageLocation)";
+
+fn keccak256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+
+    hasher.finalize().into()
+}
+
+/// keccak256(origin_domain || address || "HYPERLANE")
+///
+/// `address` is the 32 byte address of the contract checkpoints are built from
+/// (the origin merkle tree hook).
+pub fn domain_hash(origin_domain: u32, address: [u8; 32]) -> [u8; 32] {
+    keccak256(&[&origin_domain.to_be_bytes(), &address, DOMAIN_HASH_SUFFIX])
+}
+
+/// keccak256(domain_hash || root || index || message_id)
+pub fn checkpoint_digest(
+    domain_hash: [u8; 32],
+    root: [u8; 32],
+    index: u32,
+    message_id: [u8; 32],
+) -> [u8; 32] {
+    keccak256(&[&domain_hash, &root, &index.to_be_bytes(), &message_id])
+}
+
+/// keccak256(domain_hash || root || index)
+///
+/// pre-v3 checkpoints don't commit to the message id.
+pub fn legacy_checkpoint_digest(domain_hash: [u8; 32], root: [u8; 32], index: u32) -> [u8; 32] {
+    keccak256(&[&domain_hash, &root, &index.to_be_bytes()])
+}
+
+/// keccak256(local_domain || mailbox || "HYPERLANE_ANNOUNCEMENT")
+pub fn announcement_domain_hash(local_domain: u32, mailbox: [u8; 32]) -> [u8; 32] {
+    keccak256(&[
+        &local_domain.to_be_bytes(),
+        &mailbox,
+        ANNOUNCEMENT_DOMAIN_HASH_SUFFIX,
+    ])
+}
+
+/// keccak256(announcement_domain_hash || storage_location)
+pub fn announcement_digest(
+    local_domain: u32,
+    mailbox: [u8; 32],
+    storage_location: &str,
+) -> [u8; 32] {
+    keccak256(&[
+        &announcement_domain_hash(local_domain, mailbox),
+        storage_location.as_bytes(),
+    ])
+}
+
+/// keccak256(keccak256(local_domain || mailbox || "HYPERLANE_UNANNOUNCEMENT") || storage_location)
+///
+/// the distinct suffix keeps announcement signatures from being replayed as removals.
+pub fn unannouncement_digest(
+    local_domain: u32,
+    mailbox: [u8; 32],
+    storage_location: &str,
+) -> [u8; 32] {
+    keccak256(&[
+        &keccak256(&[
+            &local_domain.to_be_bytes(),
+            &mailbox,
+            UNANNOUNCEMENT_DOMAIN_HASH_SUFFIX,
+        ]),
+        storage_location.as_bytes(),
+    ])
+}
+
+/// keccak256(type_hash(EIP712Domain) || keccak256(name) || keccak256(version))
+pub fn announcement_domain_separator() -> [u8; 32] {
+    keccak256(&[
+        &keccak256(&[EIP712_DOMAIN_TYPE]),
+        &keccak256(&[EIP712_DOMAIN_NAME]),
+        &keccak256(&[EIP712_DOMAIN_VERSION]),
+    ])
+}
+
+/// keccak256("\x19\x01" || domain_separator || hash_struct(Announcement))
+///
+/// the eip-712 counterpart of `eth_signed_message_hash(announcement_digest(..))`,
+/// for keys that only sign typed data.
+pub fn announcement_typed_data_hash(
+    local_domain: u32,
+    mailbox: [u8; 32],
+    storage_location: &str,
+) -> [u8; 32] {
+    // atomic values are left padded to 32 bytes, dynamic ones are hashed
+    let mut local_domain_word = [0u8; 32];
+    local_domain_word[28..].copy_from_slice(&local_domain.to_be_bytes());
+
+    let struct_hash = keccak256(&[
+        &keccak256(&[ANNOUNCEMENT_TYPE]),
+        &local_domain_word,
+        &mailbox,
+        &keccak256(&[storage_location.as_bytes()]),
+    ]);
+
+    keccak256(&[
+        TYPED_DATA_PREFIX,
+        &announcement_domain_separator(),
+        &struct_hash,
+    ])
+}
+
+/// keccak256("\x19Ethereum Signed Message:\n32" || digest)
+///
+/// the hash validators actually sign over a checkpoint or announcement digest.
+pub fn eth_signed_message_hash(digest: [u8; 32]) -> [u8; 32] {
+    keccak256(&[ETH_SIGNED_MESSAGE_PREFIX, &digest])
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{testing::MockApi, Api, HexBinary};
+    use ibcx_test_utils::hex;
+    use rstest::rstest;
+
+    use crate::types::{eth_addr, eth_hash, pad32, Message, MessageIdMultisigIsmMetadata};
+
+    use super::*;
+
+    fn recover(digest: [u8; 32], signature: &[u8]) -> HexBinary {
+        let hashed = eth_signed_message_hash(digest);
+        let pubkey = MockApi::default()
+            .secp256k1_recover_pubkey(&hashed, &signature[..64], signature[64] - 27)
+            .unwrap();
+
+        eth_addr(pubkey.into()).unwrap()
+    }
+
+    // messages and signatures relayed between hyperlane testnets
+    #[rstest]
+    #[case(
+        hex("0000000000000068220000000000000000000000000d1255b09d94659bb0888e0aa9fca60245ce402a0000682155208cd518cffaac1b5d8df216a9bd050c9a03f0d4f3ba88e5268ac4cd12ee2d68656c6c6f"),
+        hex("986a1625d44e4b3969b08a5876171b2b4fcdf61b3e5c70a86ad17b304f17740a9f45d99ea6bec61392a47684f4e5d1416ddbcb5fdef0f132c27d7034e9bbff1c00000000ba9911d78ec6d561413e3589f920388cbd7554fbddd8ce50739337250853ec3577a51fa40e727c05b50f15db13f5aad5857c89d432644be48d70325ea83fdb6c1c"),
+        hex("01d7525e91dfc3f594fd366aad70f956b398de9e"),
+    )]
+    #[case(
+        hex("03000000240001388100000000000000000000000004980c17e2ce26578c82f81207e706e4505fae3b0000a8690000000000000000000000000b1c1b54f45e02552331d3106e71f5e0b573d5d448656c6c6f21"),
+        hex("0000000000000000000000009af85731edd41e2e50f81ef8a0a69d2fb836edf9a84430f822e0e9b5942faace72bd5b97f0b59a58a9b8281231d9e5c393b5859c00000024539feceace17782697e29e74151006dc7b47227cf48aba02926336cb5f7fa38b3d05e8293045f7b5811eda3ae8aa070116bb5fbf57c79e143a69e909df90cefa1b6e6ead7180e0415c36642ee4bc5454bc4f5ca250ca77a1a83562035544e0e898734d6541a20404e05fd53eb1c75b0bd21851c3bd8122cfa3550d7b6fb94d7cee1b"),
+        hex("17517c98358c5937c5d9ee47ce1f5b4c2b7fc9f5"),
+    )]
+    fn test_checkpoint_digest(
+        #[case] raw_message: HexBinary,
+        #[case] raw_metadata: HexBinary,
+        #[case] validator: HexBinary,
+    ) {
+        let message: Message = raw_message.try_into().unwrap();
+        let metadata: MessageIdMultisigIsmMetadata = raw_metadata.into();
+
+        let digest = checkpoint_digest(
+            domain_hash(
+                message.origin_domain,
+                metadata.origin_merkle_tree.to_array().unwrap(),
+            ),
+            metadata.merkle_root.to_array().unwrap(),
+            metadata.merkle_index(),
+            message.id().to_array().unwrap(),
+        );
+
+        assert_eq!(recover(digest, &metadata.signatures[0]), validator);
+    }
+
+    #[rstest]
+    #[case("00000000000000000000000049cfd6ef774acab14814d699e3f7ee36fdfba932")]
+    #[case("49cfd6ef774acab14814d699e3f7ee36fdfba932")]
+    fn test_announcement_digest(#[case] mailbox: &str) {
+        // announcement of a hyperlane goerli validator
+        let digest = announcement_digest(
+            5,
+            pad32(&hex(mailbox)).unwrap(),
+            "s3://hyperlane-testnet4-goerli-validator-0/us-east-1",
+        );
+        let signature = hex("dc47d48744fdb42b983f0244ed397feac08ee556eb48416582b5b638ada7b5322c8822e56a9020de7fe663ad43070f04b341514faf430ebf880bb1932434027d1c");
+
+        assert_eq!(
+            recover(digest, &signature),
+            hex("05a9b5efe9f61f9142453d8e9f61565f333c6768")
+        );
+    }
+
+    #[test]
+    fn test_domain_hash_layout() {
+        let address = [1u8; 32];
+
+        assert_ne!(domain_hash(1, address), domain_hash(2, address));
+        assert_ne!(
+            domain_hash(1, address),
+            announcement_domain_hash(1, address)
+        );
+        assert_eq!(
+            HexBinary::from(domain_hash(1, address)),
+            crate::types::keccak256_hash(
+                &[
+                    1u32.to_be_bytes().to_vec(),
+                    address.to_vec(),
+                    b"HYPERLANE".to_vec()
+                ]
+                .concat()
+            )
+        );
+    }
+
+    #[test]
+    fn test_unannouncement_digest_layout() {
+        let mailbox = [2u8; 32];
+        let location = "file://foo/bar";
+
+        assert_eq!(
+            HexBinary::from(unannouncement_digest(5, mailbox, location)),
+            crate::types::keccak256_hash(
+                &[
+                    crate::types::keccak256_hash(
+                        &[
+                            5u32.to_be_bytes().to_vec(),
+                            mailbox.to_vec(),
+                            b"HYPERLANE_UNANNOUNCEMENT".to_vec()
+                        ]
+                        .concat()
+                    )
+                    .to_vec(),
+                    location.as_bytes().to_vec()
+                ]
+                .concat()
+            )
+        );
+
+        // an announcement signature never verifies as a removal
+        assert_ne!(
+            unannouncement_digest(5, mailbox, location),
+            announcement_digest(5, mailbox, location)
+        );
+    }
+
+    #[test]
+    fn test_announcement_typed_data_layout() {
+        let mailbox = [2u8; 32];
+        let location = "file://foo/bar";
+
+        let domain_separator = crate::types::keccak256_hash(
+            &[
+                crate::types::keccak256_hash(b"EIP712Domain(string name,string version)").to_vec(),
+                crate::types::keccak256_hash(b"Hyperlane ValidatorAnnounce").to_vec(),
+                crate::types::keccak256_hash(b"1").to_vec(),
+            ]
+            .concat(),
+        );
+        assert_eq!(
+            HexBinary::from(announcement_domain_separator()),
+            domain_separator
+        );
+
+        let struct_hash = crate::types::keccak256_hash(
+            &[
+                crate::types::keccak256_hash(
+                    b"Announcement(uint32 localDomain,bytes32 mailbox,string storageLocation)",
+                )
+                .to_vec(),
+                [vec![0u8; 28], 5u32.to_be_bytes().to_vec()].concat(),
+                mailbox.to_vec(),
+                crate::types::keccak256_hash(location.as_bytes()).to_vec(),
+            ]
+            .concat(),
+        );
+        assert_eq!(
+            HexBinary::from(announcement_typed_data_hash(5, mailbox, location)),
+            crate::types::keccak256_hash(
+                &[
+                    b"\x19\x01".to_vec(),
+                    domain_separator.to_vec(),
+                    struct_hash.to_vec()
+                ]
+                .concat()
+            )
+        );
+
+        // never collides with the eip-191 hash of the same announcement
+        assert_ne!(
+            announcement_typed_data_hash(5, mailbox, location),
+            eth_signed_message_hash(announcement_digest(5, mailbox, location))
+        );
+    }
+
+    // pinned from checkpoints signed by hyperlane validators (see `test_checkpoint_digest`)
+    // so any change to the byte layout is caught before signatures stop recovering
+    #[rstest]
+    #[case(
+        26658,
+        "986a1625d44e4b3969b08a5876171b2b4fcdf61b3e5c70a86ad17b304f17740a",
+        "9f45d99ea6bec61392a47684f4e5d1416ddbcb5fdef0f132c27d7034e9bbff1c",
+        0,
+        "ccf6d982183f46a250d6f0ebc1262782235e8f5a2f5cf0e9f582576442f595a9",
+        [
+            "04ba552721af0ed5e12c999d0cadbccea990d721af86ddaef0904b9cc055de42",
+            "753c86bef70c2211f18da81d5b7fa87d0ba610044650ca02539d38bd60696372",
+            "3ce34a47d79f44282e96c048e6c1bf8bdd81250f5326ab41f0648b4376c45b01",
+        ]
+    )]
+    #[case(
+        80001,
+        "0000000000000000000000009af85731edd41e2e50f81ef8a0a69d2fb836edf9",
+        "a84430f822e0e9b5942faace72bd5b97f0b59a58a9b8281231d9e5c393b5859c",
+        36,
+        "488bdbb5bdbdcd0d6ff4fece2f5e5076538d786d9fa67ffb2ddb2e7f8450d990",
+        [
+            "10d83fe949873ef8c044aae12bfee145dc129ef1f00ff0f1fdbcf025a11015da",
+            "950b7eaef15d84c6d37d9d489a5e45c5ef6e79fd039ae89319fb76531ea4cc8e",
+            "b2d0ef044f43f33c56323968be4bc64af998a9da6a707c0b86d1fb57608b4f68",
+        ]
+    )]
+    fn test_checkpoint_vectors(
+        #[case] origin_domain: u32,
+        #[case] merkle_tree_hook: &str,
+        #[case] root: &str,
+        #[case] index: u32,
+        #[case] message_id: &str,
+        // domain hash, checkpoint digest, eth signed message hash
+        #[case] expected: [&str; 3],
+    ) {
+        let [expected_domain_hash, expected_digest, expected_signed] = expected;
+
+        let domain_hash = domain_hash(origin_domain, hex(merkle_tree_hook).to_array().unwrap());
+        assert_eq!(HexBinary::from(domain_hash), hex(expected_domain_hash));
+
+        let digest = checkpoint_digest(
+            domain_hash,
+            hex(root).to_array().unwrap(),
+            index,
+            hex(message_id).to_array().unwrap(),
+        );
+        assert_eq!(HexBinary::from(digest), hex(expected_digest));
+
+        let signed = eth_signed_message_hash(digest);
+        assert_eq!(HexBinary::from(signed), hex(expected_signed));
+        assert_eq!(
+            HexBinary::from(signed),
+            eth_hash(digest.to_vec().into()).unwrap()
+        );
+    }
+
+    // pinned from the goerli announcement in `test_announcement_digest`
+    #[test]
+    fn test_announcement_vectors() {
+        let mailbox = pad32(&hex("49cfd6ef774acab14814d699e3f7ee36fdfba932")).unwrap();
+
+        assert_eq!(
+            HexBinary::from(announcement_domain_hash(5, mailbox)),
+            hex("ef9bc9d5da4022b60097f3d8cc401e36e9d2b75e410b6ae371effe358d4ec3a8")
+        );
+
+        let digest = announcement_digest(
+            5,
+            mailbox,
+            "s3://hyperlane-testnet4-goerli-validator-0/us-east-1",
+        );
+        assert_eq!(
+            HexBinary::from(digest),
+            hex("0776268c9dcfb6efaf448cd77153ae62339a3c7843880d628fda5d7436feb002")
+        );
+        assert_eq!(
+            HexBinary::from(eth_signed_message_hash(digest)),
+            hex("6b8d543c92fdbfc60cb7db16d5781edc06bd0a27d586620e75e4d1cb0ea549a4")
+        );
+    }
+}
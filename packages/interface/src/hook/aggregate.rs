@@ -1,6 +1,9 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use crate::ownable::{OwnableMsg, OwnableQueryMsg};
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    version::VersionQueryMsg,
+};
 
 use super::{HookQueryMsg, PostDispatchMsg};
 
@@ -17,6 +20,12 @@ pub enum ExecuteMsg {
     Ownable(OwnableMsg),
     PostDispatch(PostDispatchMsg),
     SetHooks { hooks: Vec<String> },
+    /// Lets `PostDispatch` succeed once `quorum` of the hooks do, the others may fail.
+    /// `None` requires every hook to succeed.
+    SetQuorum { quorum: Option<u32> },
+    /// Sends the balance, like fees left by hooks that failed under a quorum,
+    /// to `recipient` or else the owner
+    Claim { recipient: Option<String> },
 }
 
 #[cw_serde]
@@ -26,6 +35,7 @@ pub enum QueryMsg {
     Ownable(OwnableQueryMsg),
     Hook(HookQueryMsg),
     AggregateHook(AggregateHookQueryMsg),
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
@@ -33,6 +43,9 @@ pub enum QueryMsg {
 pub enum AggregateHookQueryMsg {
     #[returns(HooksResponse)]
     Hooks {},
+
+    #[returns(QuorumResponse)]
+    Quorum {},
 }
 
 #[cw_serde]
@@ -40,6 +53,11 @@ pub struct HooksResponse {
     pub hooks: Vec<String>,
 }
 
+#[cw_serde]
+pub struct QuorumResponse {
+    pub quorum: Option<u32>,
+}
+
 #[cfg(test)]
 mod test {
     use cosmwasm_std::HexBinary;
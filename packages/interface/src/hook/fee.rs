@@ -1,7 +1,10 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Coin};
 
-use crate::ownable::{OwnableMsg, OwnableQueryMsg};
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    version::VersionQueryMsg,
+};
 
 use super::{HookQueryMsg, PostDispatchMsg};
 
@@ -37,6 +40,7 @@ pub enum QueryMsg {
     Ownable(OwnableQueryMsg),
     Hook(HookQueryMsg),
     FeeHook(FeeHookQueryMsg),
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
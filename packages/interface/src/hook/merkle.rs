@@ -1,26 +1,56 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::HexBinary;
 
-use super::{HookQueryMsg, PostDispatchMsg};
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    version::VersionQueryMsg,
+};
+
+use super::{AuthorizedCallersResponse, HookQueryMsg, PostDispatchMsg};
 
 pub const TREE_DEPTH: usize = 32;
 
 #[cw_serde]
 pub struct InstantiateMsg {
+    pub owner: String,
     pub mailbox: String,
+    /// defaults to `TREE_DEPTH`
+    pub tree_depth: Option<u8>,
+    /// Stores every leaf with its path of tree nodes so `Proof` can be served.
+    /// Costs `tree_depth` extra writes per dispatch, defaults to false
+    pub store_leaves: Option<bool>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
+    Ownable(OwnableMsg),
     PostDispatch(PostDispatchMsg),
+
+    /// Only the mailbox may post dispatched messages into the tree
+    SetMailbox {
+        mailbox: String,
+    },
+
+    /// Lets a hook composing this one, like an aggregate or routing hook, post dispatch too.
+    /// The message must still be the latest dispatched by the mailbox
+    SetAuthorizedCaller {
+        caller: String,
+        authorized: bool,
+    },
+
+    /// Archives the current checkpoint and starts an empty tree in a new epoch.
+    /// Only allowed while every message dispatched by the mailbox is in the tree.
+    ResetTree {},
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 #[query_responses(nested)]
 pub enum QueryMsg {
+    Ownable(OwnableQueryMsg),
     Hook(HookQueryMsg),
     MerkleHook(MerkleHookQueryMsg),
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
@@ -40,6 +70,20 @@ pub enum MerkleHookQueryMsg {
 
     #[returns(CheckPointResponse)]
     CheckPoint {},
+
+    #[returns(EpochResponse)]
+    Epoch {},
+
+    #[returns(AuthorizedCallersResponse)]
+    AuthorizedCallers {},
+
+    #[returns(ArchivedCheckpointResponse)]
+    ArchivedCheckpoint { epoch: u32 },
+
+    /// Proof of the message dispatched with `nonce`, which must be in the current epoch.
+    /// Only available if the hook was instantiated with `store_leaves`
+    #[returns(ProofResponse)]
+    Proof { nonce: u32 },
 }
 
 #[cw_serde]
@@ -54,12 +98,12 @@ pub struct RootResponse {
 
 #[cw_serde]
 pub struct BranchResponse {
-    pub branch: [HexBinary; TREE_DEPTH],
+    pub branch: Vec<HexBinary>,
 }
 
 #[cw_serde]
 pub struct TreeResponse {
-    pub branch: [HexBinary; TREE_DEPTH],
+    pub branch: Vec<HexBinary>,
     pub count: u32,
 }
 
@@ -69,6 +113,30 @@ pub struct CheckPointResponse {
     pub count: u32,
 }
 
+#[cw_serde]
+pub struct EpochResponse {
+    pub epoch: u32,
+    /// mailbox nonce of the first leaf of `epoch`. leaf indices are nonces minus this
+    pub base: u32,
+}
+
+/// Inclusion proof of the leaf at `index` against the current `root`
+#[cw_serde]
+pub struct ProofResponse {
+    pub index: u32,
+    pub leaf: HexBinary,
+    pub branch: Vec<HexBinary>,
+    pub root: HexBinary,
+    pub count: u32,
+}
+
+/// Root and leaf count of the tree when `epoch` was reset
+#[cw_serde]
+pub struct ArchivedCheckpointResponse {
+    pub root: HexBinary,
+    pub count: u32,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,87 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Coin, HexBinary};
+
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    version::VersionQueryMsg,
+};
+
+use super::{HookQueryMsg, PostDispatchMsg};
+
+/// Hook for local testing, quoting a fixed fee per destination without an oracle
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Ownable(OwnableMsg),
+    PostDispatch(PostDispatchMsg),
+    /// Quote for messages to `domain`, empty for free
+    SetQuote {
+        domain: u32,
+        coins: Vec<Coin>,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[query_responses(nested)]
+pub enum QueryMsg {
+    Ownable(OwnableQueryMsg),
+    Hook(HookQueryMsg),
+    MockHook(MockHookQueryMsg),
+    Version(VersionQueryMsg),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum MockHookQueryMsg {
+    #[returns(QuoteResponse)]
+    Quote { domain: u32 },
+
+    #[returns(LastPostDispatchResponse)]
+    LastPostDispatch {},
+}
+
+#[cw_serde]
+pub struct QuoteResponse {
+    pub coins: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct LastPostDispatchResponse {
+    pub message_id: Option<HexBinary>,
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::HexBinary;
+
+    use super::*;
+    use crate::{
+        hook::{ExpectedHookQueryMsg, PostDispatchMsg, QuoteDispatchMsg},
+        msg_checker,
+    };
+
+    #[test]
+    fn test_hook_interface() {
+        let _checked: ExecuteMsg = msg_checker(
+            PostDispatchMsg {
+                metadata: HexBinary::default(),
+                message: HexBinary::default(),
+            }
+            .wrap(),
+        );
+
+        let _checked: QueryMsg = msg_checker(ExpectedHookQueryMsg::Hook(HookQueryMsg::Mailbox {}));
+        let _checked: QueryMsg = msg_checker(
+            QuoteDispatchMsg {
+                metadata: HexBinary::default(),
+                message: HexBinary::default(),
+            }
+            .request(),
+        );
+    }
+}
@@ -1,16 +1,42 @@
 pub mod aggregate;
+pub mod fee;
 pub mod merkle;
+pub mod mock;
 pub mod pausable;
+pub mod ratelimit;
 pub mod routing;
 pub mod routing_custom;
 pub mod routing_fallback;
-pub mod fee;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{
-    wasm_execute, Coin, CustomQuery, HexBinary, QuerierWrapper, StdResult, WasmMsg,
+    wasm_execute, Addr, Coin, Coins, CustomQuery, HexBinary, QuerierWrapper, StdResult, Uint128,
+    WasmMsg,
 };
 
+/// Set as the `instantiate` response data of every hook
+#[cw_serde]
+pub struct InstantiateResponse {
+    pub owner: Addr,
+    /// Only for hooks bound to a mailbox
+    pub mailbox: Option<Addr>,
+}
+
+/// Numbered as the `Types` of the solidity `IPostDispatchHook`
+#[cw_serde]
+#[repr(u32)]
+pub enum HookType {
+    Unused = 0,
+    Routing = 1,
+    Aggregation = 2,
+    MerkleTree = 3,
+    InterchainGasPaymaster = 4,
+    FallbackRouting = 5,
+    Pausable = 7,
+    ProtocolFee = 8,
+    RateLimited = 10,
+}
+
 #[cw_serde]
 pub struct PostDispatchMsg {
     pub metadata: HexBinary,
@@ -54,6 +80,10 @@ pub enum HookQueryMsg {
 
     #[returns(MailboxResponse)]
     Mailbox {},
+
+    /// Cheap enough to probe whether a contract is a hook at all
+    #[returns(HookTypeResponse)]
+    HookType {},
 }
 
 #[cw_serde]
@@ -68,11 +98,54 @@ pub struct MailboxResponse {
     pub mailbox: String,
 }
 
+/// Hooks besides the mailbox allowed to post dispatch, e.g. an aggregate hook wrapping this one
+#[cw_serde]
+pub struct AuthorizedCallersResponse {
+    pub callers: Vec<String>,
+}
+
+#[cw_serde]
+pub struct HookTypeResponse {
+    pub typ: HookType,
+}
+
 #[cw_serde]
 pub struct QuoteDispatchResponse {
     pub fees: Vec<Coin>,
 }
 
+impl QuoteDispatchResponse {
+    /// Whether `funds` hold at least the quoted amount of every fee denom
+    pub fn covered_by(&self, funds: &[Coin]) -> bool {
+        self.fees.iter().all(|fee| {
+            let provided: Uint128 = funds
+                .iter()
+                .filter(|v| v.denom == fee.denom)
+                .map(|v| v.amount)
+                .sum();
+
+            provided >= fee.amount
+        })
+    }
+}
+
+/// Splits `funds` into the `quote` to forward to a hook and the remainder to refund.
+/// Returns `None` if `funds` don't cover the quote.
+pub fn split_funds(quote: &[Coin], funds: &[Coin]) -> Option<(Vec<Coin>, Vec<Coin>)> {
+    let mut forward = Coins::default();
+    let mut refund = Coins::default();
+
+    for coin in funds {
+        refund.add(coin.clone()).ok()?;
+    }
+    for coin in quote {
+        refund.sub(coin.clone()).ok()?;
+        forward.add(coin.clone()).ok()?;
+    }
+
+    Some((forward.into_vec(), refund.into_vec()))
+}
+
 pub fn post_dispatch(
     hook: impl Into<String>,
     metadata: impl Into<HexBinary>,
@@ -90,6 +163,18 @@ pub fn post_dispatch(
     )
 }
 
+pub fn hook_type<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    hook: impl Into<String>,
+) -> StdResult<HookType> {
+    let res = querier.query_wasm_smart::<HookTypeResponse>(
+        hook,
+        &ExpectedHookQueryMsg::Hook(HookQueryMsg::HookType {}),
+    )?;
+
+    Ok(res.typ)
+}
+
 pub fn quote_dispatch<C: CustomQuery>(
     querier: &QuerierWrapper<C>,
     hook: impl Into<String>,
@@ -105,3 +190,56 @@ pub fn quote_dispatch<C: CustomQuery>(
         .request(),
     )
 }
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::coin;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(vec![], vec![], true)]
+    #[case(vec![], vec![coin(1, "uosmo")], true)]
+    #[case(vec![coin(10, "uosmo")], vec![coin(10, "uosmo")], true)]
+    #[case(vec![coin(10, "uosmo")], vec![coin(5, "uosmo"), coin(5, "uosmo")], true)]
+    #[case(vec![coin(10, "uosmo")], vec![coin(9, "uosmo"), coin(10, "uatom")], false)]
+    #[case(vec![coin(10, "uosmo"), coin(1, "uatom")], vec![coin(10, "uosmo")], false)]
+    fn test_covered_by(#[case] fees: Vec<Coin>, #[case] funds: Vec<Coin>, #[case] covered: bool) {
+        assert_eq!(QuoteDispatchResponse { fees }.covered_by(&funds), covered);
+    }
+
+    #[rstest]
+    #[case(vec![], vec![], Some((vec![], vec![])))]
+    #[case(vec![], vec![coin(1, "uosmo")], Some((vec![], vec![coin(1, "uosmo")])))]
+    #[case(vec![coin(0, "uosmo")], vec![], Some((vec![], vec![])))]
+    #[case(
+        vec![coin(10, "uosmo")],
+        vec![coin(10, "uosmo")],
+        Some((vec![coin(10, "uosmo")], vec![]))
+    )]
+    #[case(
+        vec![coin(4, "uosmo"), coin(6, "uosmo")],
+        vec![coin(5, "uosmo"), coin(7, "uosmo")],
+        Some((vec![coin(10, "uosmo")], vec![coin(2, "uosmo")]))
+    )]
+    #[case(
+        vec![coin(10, "uosmo")],
+        vec![coin(15, "uosmo"), coin(3, "uatom")],
+        Some((vec![coin(10, "uosmo")], vec![coin(3, "uatom"), coin(5, "uosmo")]))
+    )]
+    #[case(
+        vec![coin(10, "uosmo"), coin(3, "uatom")],
+        vec![coin(10, "uosmo"), coin(3, "uatom")],
+        Some((vec![coin(3, "uatom"), coin(10, "uosmo")], vec![]))
+    )]
+    #[case(vec![coin(10, "uosmo")], vec![coin(9, "uosmo")], None)]
+    #[case(vec![coin(10, "uosmo"), coin(1, "uatom")], vec![coin(10, "uosmo")], None)]
+    fn test_split_funds(
+        #[case] quote: Vec<Coin>,
+        #[case] funds: Vec<Coin>,
+        #[case] expected: Option<(Vec<Coin>, Vec<Coin>)>,
+    ) {
+        assert_eq!(split_funds(&quote, &funds), expected);
+    }
+}
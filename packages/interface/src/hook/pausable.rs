@@ -3,6 +3,7 @@ use cosmwasm_schema::{cw_serde, QueryResponses};
 use crate::{
     ownable::{OwnableMsg, OwnableQueryMsg},
     pausable::{PausableMsg, PausableQueryMsg},
+    version::VersionQueryMsg,
 };
 
 use super::{HookQueryMsg, PostDispatchMsg};
@@ -27,6 +28,7 @@ pub enum QueryMsg {
     Pausable(PausableQueryMsg),
     Ownable(OwnableQueryMsg),
     Hook(HookQueryMsg),
+    Version(VersionQueryMsg),
 }
 
 #[cfg(test)]
@@ -0,0 +1,115 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint256;
+
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    version::VersionQueryMsg,
+};
+
+use super::{AuthorizedCallersResponse, HookQueryMsg, PostDispatchMsg};
+
+/// Location of the transfer amount in the standard warp payload (recipient(32) | amount(32))
+pub const WARP_AMOUNT_OFFSET: u32 = 32;
+pub const WARP_AMOUNT_LENGTH: u32 = 32;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub mailbox: String,
+    /// byte offset of the amount in the message body
+    pub amount_offset: u32,
+    /// byte length of the big-endian encoded amount. must be in 1..=32
+    pub amount_length: u32,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Ownable(OwnableMsg),
+    PostDispatch(PostDispatchMsg),
+    RateLimitHook(RateLimitHookMsg),
+}
+
+#[cw_serde]
+pub enum RateLimitHookMsg {
+    /// Limits transfers of the warp route `sender` to `domain`.
+    /// Messages from senders without a limit to their destination are not decoded
+    SetRateLimit {
+        sender: String,
+        domain: u32,
+        capacity: Uint256,
+        refill_per_second: Uint256,
+    },
+
+    /// Lets a hook composing this one, like an aggregate or routing hook, post dispatch too.
+    /// The message must still be the latest dispatched by the mailbox
+    SetAuthorizedCaller { caller: String, authorized: bool },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[query_responses(nested)]
+pub enum QueryMsg {
+    Ownable(OwnableQueryMsg),
+    Hook(HookQueryMsg),
+    RateLimitHook(RateLimitHookQueryMsg),
+    Version(VersionQueryMsg),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum RateLimitHookQueryMsg {
+    #[returns(RateLimitResponse)]
+    RateLimit { sender: String, domain: u32 },
+
+    #[returns(AuthorizedCallersResponse)]
+    AuthorizedCallers {},
+
+    #[returns(AmountRangeResponse)]
+    AmountRange {},
+}
+
+#[cw_serde]
+pub struct RateLimitResponse {
+    pub capacity: Uint256,
+    pub refill_per_second: Uint256,
+    /// capacity available at the current block time
+    pub available: Uint256,
+    pub last_refill: u64,
+}
+
+#[cw_serde]
+pub struct AmountRangeResponse {
+    pub offset: u32,
+    pub length: u32,
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::HexBinary;
+
+    use super::*;
+    use crate::{
+        hook::{ExpectedHookQueryMsg, PostDispatchMsg, QuoteDispatchMsg},
+        msg_checker,
+    };
+
+    #[test]
+    fn test_hook_interface() {
+        let _checked: ExecuteMsg = msg_checker(
+            PostDispatchMsg {
+                metadata: HexBinary::default(),
+                message: HexBinary::default(),
+            }
+            .wrap(),
+        );
+
+        let _checked: QueryMsg = msg_checker(ExpectedHookQueryMsg::Hook(HookQueryMsg::Mailbox {}));
+        let _checked: QueryMsg = msg_checker(
+            QuoteDispatchMsg {
+                metadata: HexBinary::default(),
+                message: HexBinary::default(),
+            }
+            .request(),
+        );
+    }
+}
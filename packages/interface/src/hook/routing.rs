@@ -4,6 +4,7 @@ use cosmwasm_std::Addr;
 use crate::{
     ownable::{OwnableMsg, OwnableQueryMsg},
     router::{RouterMsg, RouterQuery},
+    version::VersionQueryMsg,
 };
 
 use super::{HookQueryMsg, PostDispatchMsg};
@@ -27,6 +28,7 @@ pub enum QueryMsg {
     Ownable(OwnableQueryMsg),
     Router(RouterQuery<Addr>),
     Hook(HookQueryMsg),
+    Version(VersionQueryMsg),
 }
 
 #[cfg(test)]
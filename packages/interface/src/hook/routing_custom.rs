@@ -4,6 +4,7 @@ use cosmwasm_std::Addr;
 use crate::{
     ownable::{OwnableMsg, OwnableQueryMsg},
     router::{RouterMsg, RouterQuery},
+    version::VersionQueryMsg,
     Order,
 };
 
@@ -48,6 +49,7 @@ pub enum QueryMsg {
     Router(RouterQuery<Addr>),
     Hook(HookQueryMsg),
     CustomRoutingHook(CustomRoutingHookQueryMsg),
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
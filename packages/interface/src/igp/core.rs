@@ -1,10 +1,11 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, HexBinary, Uint256};
+use cosmwasm_std::{Addr, HexBinary, Uint128, Uint256};
 
 use crate::{
-    hook::{HookQueryMsg, PostDispatchMsg},
+    hook::{AuthorizedCallersResponse, HookQueryMsg, PostDispatchMsg},
     ownable::{OwnableMsg, OwnableQueryMsg},
     router::{RouterMsg, RouterQuery},
+    version::VersionQueryMsg,
     Order,
 };
 
@@ -14,11 +15,19 @@ use super::oracle::IgpGasOracleQueryMsg;
 pub struct InstantiateMsg {
     pub hrp: String,
     pub owner: String,
+    pub mailbox: String,
     pub gas_token: String,
     pub beneficiary: String,
     pub default_gas_usage: u128,
 }
 
+#[cw_serde]
+pub struct InstantiateResponse {
+    pub owner: Addr,
+    pub mailbox: Addr,
+    pub beneficiary: Addr,
+}
+
 #[cw_serde]
 pub struct GasOracleConfig {
     pub remote_domain: u32,
@@ -61,16 +70,60 @@ pub enum ExecuteMsg {
         domains: Vec<u32>,
     },
 
+    /// Caps the payment quoted for each domain, in the gas token.
+    /// Quotes above the cap fail until the oracle is fixed, uncapped domains are unlimited.
+    SetQuoteCaps {
+        caps: Vec<(u32, Uint128)>,
+    },
+    UnsetQuoteCaps {
+        domains: Vec<u32>,
+    },
+
+    /// Queries `oracle` for every domain instead of the routed gas oracles, `None` unsets it
+    SetGasOracle {
+        oracle: Option<String>,
+    },
+
     SetBeneficiary {
         beneficiary: String,
     },
+
+    /// Only the mailbox may pay for gas through `PostDispatch`
+    SetMailbox {
+        mailbox: String,
+    },
+
+    /// Lets a hook composing this one, like an aggregate or routing hook, post dispatch too
+    SetAuthorizedCaller {
+        caller: String,
+        authorized: bool,
+    },
+
+    /// With `require_payment`, `PostDispatch` fails unless it's sent the quote for the message.
+    /// Without it, underpaid messages go through unpaid and their funds are refunded.
+    SetRequirePayment {
+        require_payment: bool,
+    },
+
+    /// Fields also accept the camelCase names the typescript sdk writes
     PayForGas {
+        #[serde(alias = "messageId")]
         message_id: HexBinary,
+        #[serde(alias = "destDomain")]
         dest_domain: u32,
+        #[serde(alias = "gasAmount")]
         gas_amount: Uint256,
+        #[serde(alias = "refundAddress")]
         refund_address: String,
     },
+    /// Sends the balance not owed to unsettled records to the beneficiary
     Claim {},
+
+    /// Sends the payments recorded for `message_ids` to `recipient`, each id can be settled once
+    SettleBatch {
+        message_ids: Vec<HexBinary>,
+        recipient: String,
+    },
 }
 
 #[cw_serde]
@@ -85,6 +138,8 @@ pub enum QueryMsg {
 
     // base
     Igp(IgpQueryMsg),
+
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
@@ -106,11 +161,26 @@ pub enum IgpQueryMsg {
     #[returns(BeneficiaryResponse)]
     Beneficiary {},
 
+    #[returns(GasOracleResponse)]
+    GasOracle {},
+
+    #[returns(QuoteCapsResponse)]
+    QuoteCaps { domains: Vec<u32> },
+
+    #[returns(RequirePaymentResponse)]
+    RequirePayment {},
+
+    #[returns(AuthorizedCallersResponse)]
+    AuthorizedCallers {},
+
     #[returns(QuoteGasPaymentResponse)]
     QuoteGasPayment {
         dest_domain: u32,
         gas_amount: Uint256,
     },
+
+    #[returns(GasPaidResponse)]
+    GasPaidFor { message_id: HexBinary },
 }
 
 impl IgpQueryMsg {
@@ -134,14 +204,37 @@ pub struct BeneficiaryResponse {
     pub beneficiary: String,
 }
 
+#[cw_serde]
+pub struct GasOracleResponse {
+    pub oracle: Option<String>,
+}
+
+/// `None` for domains without a cap
+#[cw_serde]
+pub struct QuoteCapsResponse {
+    pub caps: Vec<(u32, Option<Uint128>)>,
+}
+
+#[cw_serde]
+pub struct RequirePaymentResponse {
+    pub require_payment: bool,
+}
+
 #[cw_serde]
 pub struct QuoteGasPaymentResponse {
     pub gas_needed: Uint256,
 }
 
+#[cw_serde]
+pub struct GasPaidResponse {
+    pub gas_amount: Uint256,
+    pub payment: Uint256,
+    pub settled: bool,
+}
+
 #[cfg(test)]
 mod test {
-    use cosmwasm_std::HexBinary;
+    use cosmwasm_std::{from_json, to_json_string, HexBinary};
 
     use super::*;
     use crate::{
@@ -168,4 +261,34 @@ mod test {
             .request(),
         );
     }
+    // as written by the typescript sdk
+    const SDK_PAY_FOR_GAS_MSG: &str = r#"{
+        "pay_for_gas": {
+            "messageId": "0101010101010101010101010101010101010101010101010101010101010101",
+            "destDomain": 11155111,
+            "gasAmount": "300000",
+            "refundAddress": "osmo1t2htvpfl862vnwdqnuekd9p4ulh3h6hdlvdhh0"
+        }
+    }"#;
+
+    #[test]
+    fn test_pay_for_gas_camel_case() {
+        let msg: ExecuteMsg = from_json(SDK_PAY_FOR_GAS_MSG).unwrap();
+
+        assert_eq!(
+            msg,
+            ExecuteMsg::PayForGas {
+                message_id: HexBinary::from(vec![1; 32]),
+                dest_domain: 11155111,
+                gas_amount: Uint256::from(300_000u128),
+                refund_address: "osmo1t2htvpfl862vnwdqnuekd9p4ulh3h6hdlvdhh0".to_string(),
+            }
+        );
+
+        // written back in snake_case
+        let written = to_json_string(&msg).unwrap();
+        assert!(written.contains("\"gas_amount\":\"300000\""));
+        assert!(!written.contains("gasAmount"));
+        assert_eq!(from_json::<ExecuteMsg>(&written).unwrap(), msg);
+    }
 }
@@ -1,13 +1,21 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Addr, Uint128};
 
-use crate::ownable::{OwnableMsg, OwnableQueryMsg};
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    version::VersionQueryMsg,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
 }
 
+#[cw_serde]
+pub struct InstantiateResponse {
+    pub owner: Addr,
+}
+
 #[cw_serde]
 pub struct RemoteGasDataConfig {
     pub remote_domain: u32,
@@ -34,6 +42,8 @@ pub enum QueryMsg {
 
     // base
     Oracle(IgpGasOracleQueryMsg),
+
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
@@ -1,8 +1,11 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use crate::ownable::{OwnableMsg, OwnableQueryMsg};
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    version::VersionQueryMsg,
+};
 
-use super::IsmQueryMsg;
+use super::{IsmMetadataQueryMsg, IsmQueryMsg};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -26,7 +29,11 @@ pub enum QueryMsg {
 
     Ism(IsmQueryMsg),
 
+    IsmMetadata(IsmMetadataQueryMsg),
+
     AggregateIsm(AggregateIsmQueryMsg),
+
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
@@ -65,5 +72,13 @@ mod test {
             }
             .wrap(),
         );
+        let _checked: QueryMsg =
+            msg_checker(IsmMetadataQueryMsg::MetadataLayout { origin_domain: 1 }.wrap());
+        let _checked: QueryMsg = msg_checker(
+            IsmMetadataQueryMsg::MetadataLayoutFor {
+                message: HexBinary::default(),
+            }
+            .wrap(),
+        );
     }
 }
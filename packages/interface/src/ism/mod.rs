@@ -1,10 +1,21 @@
 pub mod aggregate;
 pub mod multisig;
-pub mod routing;
+pub mod multisig_factory;
 pub mod pausable;
+pub mod routing;
+pub mod trusted_relayer;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, CustomQuery, HexBinary, QuerierWrapper, StdResult};
+use cosmwasm_std::{
+    from_json, to_json_binary, to_json_vec, Addr, ContractResult, CustomQuery, HexBinary,
+    QuerierWrapper, QueryRequest, StdError, StdResult, SystemResult, WasmQuery,
+};
+
+/// Set as the `instantiate` response data of every ism
+#[cw_serde]
+pub struct InstantiateResponse {
+    pub owner: Addr,
+}
 
 #[cw_serde]
 #[repr(u32)]
@@ -48,6 +59,59 @@ pub enum ExpectedIsmQueryMsg {
     Ism(IsmQueryMsg),
 }
 
+/// Verification for isms that also depend on who relays the message.
+/// The mailbox only sends it to isms registered with `SetContextIsm`.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum IsmContextQueryMsg {
+    #[returns(VerifyResponse)]
+    VerifyWithContext {
+        metadata: HexBinary,
+        message: HexBinary,
+        relayer: String,
+    },
+}
+
+impl IsmContextQueryMsg {
+    pub fn wrap(self) -> ExpectedIsmContextQueryMsg {
+        ExpectedIsmContextQueryMsg::IsmContext(self)
+    }
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[query_responses(nested)]
+pub enum ExpectedIsmContextQueryMsg {
+    IsmContext(IsmContextQueryMsg),
+}
+
+/// Metadata layout hints, so relayers can build and check metadata before submitting it.
+/// Isms that don't implement it reject the query as an unknown message.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum IsmMetadataQueryMsg {
+    /// Layout for messages from `origin_domain`, as isms are configured per origin
+    #[returns(MetadataLayoutResponse)]
+    MetadataLayout { origin_domain: u32 },
+
+    /// Layout for `message`, following routes to the ism that verifies it
+    #[returns(MetadataLayoutResponse)]
+    MetadataLayoutFor { message: HexBinary },
+}
+
+impl IsmMetadataQueryMsg {
+    pub fn wrap(self) -> ExpectedIsmMetadataQueryMsg {
+        ExpectedIsmMetadataQueryMsg::IsmMetadata(self)
+    }
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[query_responses(nested)]
+pub enum ExpectedIsmMetadataQueryMsg {
+    IsmMetadata(IsmMetadataQueryMsg),
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 #[query_responses(nested)]
@@ -85,21 +149,83 @@ pub struct VerifyInfoResponse {
     pub validators: Vec<HexBinary>,
 }
 
+#[cw_serde]
+pub enum MetadataLayout {
+    /// `origin_merkle_tree (32) || merkle_root (32) || merkle_index (4) || signatures (65 each)`.
+    /// `expected_len` is the length with exactly `threshold` signatures.
+    MessageIdMultisig {
+        threshold: u8,
+        validators_len: u32,
+        expected_len: u32,
+    },
+    /// Layouts of the sub isms in order, metadata for `threshold` of them is required
+    Aggregate {
+        threshold: u8,
+        layouts: Vec<MetadataLayout>,
+    },
+    /// The ism is picked per message, see `MetadataLayoutFor`
+    DependsOnMessage {},
+}
+
+#[cw_serde]
+pub struct MetadataLayoutResponse {
+    pub layout: MetadataLayout,
+}
+
 #[cw_serde]
 pub struct InterchainSecurityModuleResponse {
     pub ism: Option<Addr>,
 }
 
+/// Answer of a recipient to `InterchainSecurityModule`
+#[derive(Debug, PartialEq)]
+pub enum RecipientIsmLookup {
+    /// The recipient's own ism, `None` to follow the default ism
+    Specified(Option<Addr>),
+    /// The recipient rejected the query as an unknown message
+    Unsupported,
+}
+
+/// Queries the ism of `recipient`, telling recipients that don't implement the query apart.
+/// Any other failure, e.g. `recipient` not being a contract, is returned as an error.
+///
+/// Nodes that redact errors of nested queries report unsupported recipients as failures.
+pub fn lookup_recipient<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    recipient: impl Into<String>,
+) -> StdResult<RecipientIsmLookup> {
+    let request: QueryRequest<C> = WasmQuery::Smart {
+        contract_addr: recipient.into(),
+        msg: to_json_binary(&IsmSpecifierQueryMsg::InterchainSecurityModule().wrap())?,
+    }
+    .into();
+
+    match querier.raw_query(&to_json_vec(&request)?) {
+        SystemResult::Err(err) => Err(StdError::generic_err(format!(
+            "Querier system error: {err}"
+        ))),
+        // message parsing failed in the recipient's query entry point
+        SystemResult::Ok(ContractResult::Err(err)) if err.contains("Error parsing into type") => {
+            Ok(RecipientIsmLookup::Unsupported)
+        }
+        SystemResult::Ok(ContractResult::Err(err)) => Err(StdError::generic_err(format!(
+            "Querier contract error: {err}"
+        ))),
+        SystemResult::Ok(ContractResult::Ok(res)) => Ok(RecipientIsmLookup::Specified(
+            from_json::<InterchainSecurityModuleResponse>(res)?.ism,
+        )),
+    }
+}
+
+/// The recipient's own ism, `None` if it has none or doesn't implement the query
 pub fn recipient<C: CustomQuery>(
     querier: &QuerierWrapper<C>,
     recipient: impl Into<String>,
 ) -> StdResult<Option<Addr>> {
-    let res = querier.query_wasm_smart::<InterchainSecurityModuleResponse>(
-        recipient,
-        &IsmSpecifierQueryMsg::InterchainSecurityModule().wrap(),
-    )?;
-
-    Ok(res.ism)
+    match lookup_recipient(querier, recipient)? {
+        RecipientIsmLookup::Specified(ism) => Ok(ism),
+        RecipientIsmLookup::Unsupported => Ok(None),
+    }
 }
 
 pub fn verify<C: CustomQuery>(
@@ -115,3 +241,43 @@ pub fn verify<C: CustomQuery>(
 
     Ok(verify_resp.verified)
 }
+
+pub fn verify_with_context<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    ism: impl Into<String>,
+    metadata: HexBinary,
+    message: HexBinary,
+    relayer: impl Into<String>,
+) -> StdResult<bool> {
+    let verify_resp = querier.query_wasm_smart::<VerifyResponse>(
+        ism,
+        &IsmContextQueryMsg::VerifyWithContext {
+            metadata,
+            message,
+            relayer: relayer.into(),
+        }
+        .wrap(),
+    )?;
+
+    Ok(verify_resp.verified)
+}
+
+pub fn metadata_layout<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    ism: impl Into<String>,
+    msg: IsmMetadataQueryMsg,
+) -> StdResult<MetadataLayout> {
+    let res = querier.query_wasm_smart::<MetadataLayoutResponse>(ism, &msg.wrap())?;
+
+    Ok(res.layout)
+}
+
+pub fn module_type<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    ism: impl Into<String>,
+) -> StdResult<IsmType> {
+    let res =
+        querier.query_wasm_smart::<ModuleTypeResponse>(ism, &IsmQueryMsg::ModuleType {}.wrap())?;
+
+    Ok(res.typ)
+}
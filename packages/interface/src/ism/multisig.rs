@@ -1,15 +1,21 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::HexBinary;
 
-use crate::ownable::{OwnableMsg, OwnableQueryMsg};
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    types::HexOrBinary,
+    version::VersionQueryMsg,
+};
 
-use super::IsmQueryMsg;
+use super::{IsmMetadataQueryMsg, IsmQueryMsg, IsmType};
 #[allow(unused_imports)]
 use super::{ModuleTypeResponse, VerifyInfoResponse, VerifyResponse};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
+    /// `MessageIdMultisig` (default) or `LegacyMultisig` for pre-v3 origins
+    pub module_type: Option<IsmType>,
 }
 
 #[cw_serde]
@@ -18,6 +24,13 @@ pub struct ValidatorSet {
     pub validator: HexBinary,
 }
 
+#[cw_serde]
+pub struct WeightedValidator {
+    /// 20 byte address, as hex (0x-prefixed evm addresses included) or base64
+    pub validator: HexOrBinary,
+    pub weight: u32,
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
@@ -25,11 +38,36 @@ pub enum ExecuteMsg {
     SetValidators {
         domain: u32,
         threshold: u8,
-        validators: Vec<HexBinary>, // should be 20 lenghted
+        /// 20 byte addresses, as hex (0x-prefixed evm addresses included) or base64
+        validators: Vec<HexOrBinary>,
     },
     UnsetDomain {
         domain: u32,
     },
+
+    /// Replaces the domain's validators with weighted ones. A message verifies once the
+    /// weights of its signers add up to `threshold_weight`. Message id multisig only
+    SetWeightedValidators {
+        domain: u32,
+        threshold_weight: u64,
+        validators: Vec<WeightedValidator>,
+    },
+
+    /// Adds validators to a configured domain, keeping its threshold.
+    /// Each one must have announced a storage location on `validator_announce`
+    ImportValidators {
+        domain: u32,
+        validator_announce: String,
+        /// 20 byte addresses, as hex (0x-prefixed evm addresses included) or base64
+        validators: Vec<HexOrBinary>,
+    },
+
+    /// Mailbox nonce at the last tree reset of the domain's merkle hook, the `base` of its
+    /// epoch query. Legacy multisig proves a message at leaf index `nonce - base`
+    SetEpochBase {
+        domain: u32,
+        base: u32,
+    },
 }
 
 #[cw_serde]
@@ -38,14 +76,24 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Ownable(OwnableQueryMsg),
     Ism(IsmQueryMsg),
+    IsmMetadata(IsmMetadataQueryMsg),
     MultisigIsm(MultisigIsmQueryMsg),
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum MultisigIsmQueryMsg {
+    /// For weighted domains, `threshold` is the fewest signatures that can verify
     #[returns(EnrolledValidatorsResponse)]
     EnrolledValidators { domain: u32 },
+
+    /// Validators weigh 1 each on domains set without weights
+    #[returns(WeightedValidatorsResponse)]
+    WeightedValidators { domain: u32 },
+
+    #[returns(EpochBaseResponse)]
+    EpochBase { domain: u32 },
 }
 
 #[cw_serde]
@@ -54,6 +102,17 @@ pub struct EnrolledValidatorsResponse {
     pub threshold: u8,
 }
 
+#[cw_serde]
+pub struct WeightedValidatorsResponse {
+    pub validators: Vec<(HexBinary, u32)>,
+    pub threshold_weight: u64,
+}
+
+#[cw_serde]
+pub struct EpochBaseResponse {
+    pub base: u32,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -75,5 +134,33 @@ mod test {
             }
             .wrap(),
         );
+        let _checked: QueryMsg =
+            msg_checker(IsmMetadataQueryMsg::MetadataLayout { origin_domain: 1 }.wrap());
+        let _checked: QueryMsg = msg_checker(
+            IsmMetadataQueryMsg::MetadataLayoutFor {
+                message: HexBinary::default(),
+            }
+            .wrap(),
+        );
+    }
+
+    #[test]
+    fn test_set_validators_serde() {
+        let msg: ExecuteMsg = cosmwasm_std::from_json(
+            r#"{"set_validators":{"domain":1,"threshold":2,"validators":[
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+                "Wq62BT8+lMm5oJ8zZpQ15+8b6u0="
+            ]}}"#,
+        )
+        .unwrap();
+
+        let ExecuteMsg::SetValidators { validators, .. } = msg else {
+            panic!("unexpected message");
+        };
+        assert_eq!(validators[0], validators[1]);
+        assert_eq!(
+            validators[0].to_hex(),
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        );
     }
 }
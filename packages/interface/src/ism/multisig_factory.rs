@@ -0,0 +1,73 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Addr;
+
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    types::HexOrBinary,
+    version::VersionQueryMsg,
+};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    /// Code id of `hpl-ism-multisig`
+    pub ism_code_id: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Ownable(OwnableMsg),
+
+    /// Instantiates a multisig ism for the domain at a deterministic address.
+    /// Its ownership is handed over to the factory owner, who has to claim it.
+    CreateIsm {
+        domain: u32,
+        /// 20 byte addresses, as hex (0x-prefixed evm addresses included) or base64
+        validators: Vec<HexOrBinary>,
+        threshold: u8,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[query_responses(nested)]
+pub enum QueryMsg {
+    Ownable(OwnableQueryMsg),
+    MultisigIsmFactory(MultisigIsmFactoryQueryMsg),
+    Version(VersionQueryMsg),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum MultisigIsmFactoryQueryMsg {
+    #[returns(IsmResponse)]
+    GetIsm { domain: u32 },
+}
+
+#[cw_serde]
+pub struct IsmResponse {
+    pub ism: Option<Addr>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_create_ism_serde() {
+        let msg: ExecuteMsg = cosmwasm_std::from_json(
+            r#"{"create_ism":{"domain":1,"threshold":1,"validators":[
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+            ]}}"#,
+        )
+        .unwrap();
+
+        let ExecuteMsg::CreateIsm { validators, .. } = msg else {
+            panic!("unexpected message");
+        };
+        assert_eq!(
+            validators[0].to_hex(),
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        );
+    }
+}
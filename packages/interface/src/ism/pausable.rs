@@ -1,19 +1,38 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 
-use crate::{ownable::{OwnableMsg, OwnableQueryMsg}, pausable::{PausableMsg, PausableQueryMsg}};
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    pausable::{PausableMsg, PausableQueryMsg},
+    version::VersionQueryMsg,
+};
 
 use super::IsmQueryMsg;
 
+/// How `Verify` answers while paused
+#[cw_serde]
+#[derive(Default)]
+pub enum PausedBehavior {
+    /// The query fails with a paused error
+    #[default]
+    Error,
+    /// The query answers `verified: false`
+    Reject,
+}
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
-    pub paused: bool
+    pub paused: bool,
+    /// Ism to delegate to while unpaused, verifies everything if unset
+    pub ism: Option<String>,
+    /// defaults to `PausedBehavior::Error`
+    pub paused_behavior: Option<PausedBehavior>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
-    Pausable(PausableMsg)
+    Pausable(PausableMsg),
 }
 
 #[cw_serde]
@@ -22,5 +41,21 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Ownable(OwnableQueryMsg),
     Ism(IsmQueryMsg),
-    Pausable(PausableQueryMsg)
+    Pausable(PausableQueryMsg),
+    PausableIsm(PausableIsmQueryMsg),
+    Version(VersionQueryMsg),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum PausableIsmQueryMsg {
+    #[returns(PausedResponse)]
+    Paused {},
+}
+
+#[cw_serde]
+pub struct PausedResponse {
+    pub paused: bool,
+    pub behavior: PausedBehavior,
+    pub ism: Option<String>,
 }
@@ -1,9 +1,12 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::HexBinary;
 
-use crate::ownable::{OwnableMsg, OwnableQueryMsg};
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    version::VersionQueryMsg,
+};
 
-use super::IsmQueryMsg;
+use super::{IsmMetadataQueryMsg, IsmQueryMsg};
 #[allow(unused_imports)]
 use super::{ModuleTypeResponse, VerifyResponse};
 
@@ -23,8 +26,20 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
 
-    Set { ism: IsmSet },
-    Unset { domains: Vec<u32> },
+    /// `ism.address` must answer `ModuleType`, unless `unchecked`
+    Set {
+        ism: IsmSet,
+        #[serde(default)]
+        unchecked: bool,
+    },
+    Unset {
+        domains: Vec<u32>,
+    },
+
+    VerifyAndRecord {
+        metadata: HexBinary,
+        message: HexBinary,
+    },
 }
 
 #[cw_serde]
@@ -33,7 +48,9 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Ownable(OwnableQueryMsg),
     Ism(IsmQueryMsg),
+    IsmMetadata(IsmMetadataQueryMsg),
     RoutingIsm(RoutingIsmQueryMsg),
+    Version(VersionQueryMsg),
 }
 
 #[cw_serde]
@@ -71,5 +88,13 @@ mod test {
             }
             .wrap(),
         );
+        let _checked: QueryMsg =
+            msg_checker(IsmMetadataQueryMsg::MetadataLayout { origin_domain: 1 }.wrap());
+        let _checked: QueryMsg = msg_checker(
+            IsmMetadataQueryMsg::MetadataLayoutFor {
+                message: HexBinary::default(),
+            }
+            .wrap(),
+        );
     }
 }
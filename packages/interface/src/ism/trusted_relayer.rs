@@ -0,0 +1,69 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+use crate::{
+    ownable::{OwnableMsg, OwnableQueryMsg},
+    version::VersionQueryMsg,
+};
+
+use super::{IsmContextQueryMsg, IsmQueryMsg};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub relayers: Vec<String>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    Ownable(OwnableMsg),
+
+    SetRelayer { relayer: String, trusted: bool },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[query_responses(nested)]
+pub enum QueryMsg {
+    Ownable(OwnableQueryMsg),
+    Ism(IsmQueryMsg),
+    IsmContext(IsmContextQueryMsg),
+    TrustedRelayer(TrustedRelayerQueryMsg),
+    Version(VersionQueryMsg),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum TrustedRelayerQueryMsg {
+    #[returns(RelayersResponse)]
+    Relayers {},
+}
+
+#[cw_serde]
+pub struct RelayersResponse {
+    pub relayers: Vec<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::HexBinary;
+
+    use super::*;
+    use crate::{
+        ism::{ExpectedIsmContextQueryMsg, ExpectedIsmQueryMsg},
+        msg_checker,
+    };
+
+    #[test]
+    fn test_ism_interface() {
+        let _checked: QueryMsg = msg_checker(IsmQueryMsg::ModuleType {}.wrap());
+        let _checked: ExpectedIsmQueryMsg = msg_checker(QueryMsg::Ism(IsmQueryMsg::ModuleType {}));
+
+        let msg = IsmContextQueryMsg::VerifyWithContext {
+            metadata: HexBinary::default(),
+            message: HexBinary::default(),
+            relayer: "relayer".to_string(),
+        };
+        let _checked: QueryMsg = msg_checker(msg.clone().wrap());
+        let _checked: ExpectedIsmContextQueryMsg = msg_checker(QueryMsg::IsmContext(msg));
+    }
+}
@@ -6,6 +6,7 @@ use cw_storage_plus::Bound;
 
 pub mod connection;
 pub mod core;
+pub mod digest;
 pub mod hook;
 pub mod igp;
 pub mod ism;
@@ -13,23 +14,19 @@ mod macros;
 pub mod ownable;
 pub mod pausable;
 pub mod router;
+pub mod testing;
 pub mod types;
+pub mod version;
 pub mod warp;
 
-pub use macros::*;
-
 #[cw_serde]
+#[derive(Default)]
 pub enum Order {
+    #[default]
     Asc,
     Desc,
 }
 
-impl Default for Order {
-    fn default() -> Self {
-        Self::Asc
-    }
-}
-
 impl From<Order> for cosmwasm_std::Order {
     fn from(v: Order) -> Self {
         match v {
@@ -1,9 +1,15 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::Addr;
+use cw_utils::Expiration;
 
 #[cw_serde]
 pub enum OwnableMsg {
-    InitOwnershipTransfer { next_owner: String },
+    /// An expired transfer can't be claimed, and is overwritten by the next one
+    InitOwnershipTransfer {
+        next_owner: String,
+        #[serde(default)]
+        deadline: Option<Expiration>,
+    },
     RevokeOwnershipTransfer {},
     ClaimOwnership {},
 }
@@ -26,4 +32,95 @@ pub struct OwnerResponse {
 #[cw_serde]
 pub struct PendingOwnerResponse {
     pub pending_owner: Option<Addr>,
+    #[serde(default)]
+    pub deadline: Option<Expiration>,
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::to_json_string;
+    use serde::Serialize;
+
+    use super::*;
+
+    // json of every ownable message, wrapped by the contract's own messages
+    fn wire<E: Serialize, Q: Serialize>(
+        wrap_execute: fn(OwnableMsg) -> E,
+        wrap_query: fn(OwnableQueryMsg) -> Q,
+    ) -> Vec<String> {
+        let execute = [
+            OwnableMsg::InitOwnershipTransfer {
+                next_owner: "owner".to_string(),
+                deadline: None,
+            },
+            OwnableMsg::RevokeOwnershipTransfer {},
+            OwnableMsg::ClaimOwnership {},
+        ];
+        let query = [
+            OwnableQueryMsg::GetOwner {},
+            OwnableQueryMsg::GetPendingOwner {},
+        ];
+
+        let mut res = vec![];
+        for msg in execute {
+            res.push(to_json_string(&wrap_execute(msg)).unwrap());
+        }
+        for msg in query {
+            res.push(to_json_string(&wrap_query(msg)).unwrap());
+        }
+        res
+    }
+
+    // pins the json shape of the ownable messages of every contract
+    #[test]
+    fn test_wire_format() {
+        let init = r#"{"next_owner":"owner","deadline":null}"#;
+        let golden = |execute: &str, query: &str| {
+            vec![
+                format!(r#"{{"{execute}":{{"init_ownership_transfer":{init}}}}}"#),
+                format!(r#"{{"{execute}":{{"revoke_ownership_transfer":{{}}}}}}"#),
+                format!(r#"{{"{execute}":{{"claim_ownership":{{}}}}}}"#),
+                format!(r#"{{"{query}":{{"get_owner":{{}}}}}}"#),
+                format!(r#"{{"{query}":{{"get_pending_owner":{{}}}}}}"#),
+            ]
+        };
+
+        macro_rules! check {
+            ($($module:ident)::+) => {
+                check!($($module)::+, Ownable, "ownable", "ownable")
+            };
+            ($($module:ident)::+, $variant:ident, $execute:literal, $query:literal) => {
+                assert_eq!(
+                    wire(
+                        crate::$($module)::+::ExecuteMsg::$variant,
+                        crate::$($module)::+::QueryMsg::Ownable,
+                    ),
+                    golden($execute, $query),
+                    stringify!($($module)::+)
+                )
+            };
+        }
+
+        check!(core::mailbox);
+        check!(core::va);
+        check!(hook::aggregate);
+        check!(hook::fee);
+        check!(hook::merkle);
+        check!(hook::mock);
+        check!(hook::pausable);
+        check!(hook::ratelimit);
+        check!(hook::routing);
+        check!(hook::routing_custom);
+        check!(hook::routing_fallback);
+        check!(igp::core);
+        check!(igp::oracle, Ownership, "ownership", "ownable");
+        check!(ism::aggregate);
+        check!(ism::multisig);
+        check!(ism::multisig_factory);
+        check!(ism::pausable);
+        check!(ism::routing);
+        check!(ism::trusted_relayer);
+        check!(warp::cw20);
+        check!(warp::native);
+    }
 }
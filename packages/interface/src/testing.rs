@@ -0,0 +1,8 @@
+//! Helpers shared by the tests of the contracts
+
+use cosmwasm_std::HexBinary;
+
+/// `bz` behind `zeros` zero bytes, e.g. an address padded to 32 bytes
+pub fn padded(zeros: usize, bz: &[u8]) -> HexBinary {
+    [vec![0u8; zeros], bz.to_vec()].concat().into()
+}
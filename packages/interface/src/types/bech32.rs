@@ -1,42 +1,76 @@
 use bech32::{FromBase32, ToBase32};
-use cosmwasm_std::{Addr, StdError, StdResult};
+use cosmwasm_std::{Addr, HexBinary, StdError, StdResult};
 
-pub fn bech32_to_h256(target: &str) -> StdResult<[u8; 32]> {
-    let raw_addr = bech32_decode(target)?;
+pub const MAX_ADDR_LENGTH: usize = 32;
+pub const ACC_ADDR_LENGTH: usize = 20;
+
+fn ensure_addr_length(len: usize) -> StdResult<()> {
+    if len == 0 || len > MAX_ADDR_LENGTH {
+        return Err(StdError::generic_err(format!(
+            "invalid raw address length. expected: 1..={MAX_ADDR_LENGTH}. got: {len}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Left-pads a raw address to 32 bytes, as it is encoded in hyperlane messages
+pub fn pad32(raw_addr: &[u8]) -> StdResult<[u8; 32]> {
+    ensure_addr_length(raw_addr.len())?;
 
     let mut addr = [0u8; 32];
-    addr[32 - raw_addr.len()..].copy_from_slice(&raw_addr);
+    addr[32 - raw_addr.len()..].copy_from_slice(raw_addr);
 
     Ok(addr)
 }
 
+/// Strips the zero padding from a raw address, but never below `canonical_len` bytes.
+/// Only zero bytes are stripped, so addresses with meaningful leading zeros stay intact.
+pub fn unpad(raw_addr: &[u8], canonical_len: usize) -> &[u8] {
+    if raw_addr.len() <= canonical_len {
+        return raw_addr;
+    }
+
+    let pad_len = raw_addr.len() - canonical_len;
+    if raw_addr[..pad_len].iter().all(|v| *v == 0) {
+        &raw_addr[pad_len..]
+    } else {
+        raw_addr
+    }
+}
+
+pub fn bech32_to_h256(target: &str) -> StdResult<[u8; 32]> {
+    pad32(&bech32_decode(target)?)
+}
+
 pub fn bech32_decode(target: &str) -> StdResult<Vec<u8>> {
-    let (_, raw_addr_u5, _) = bech32::decode(target)
+    Ok(bech32_decode_with_hrp(target)?.1)
+}
+
+/// Decodes a bech32 address into its hrp and raw address
+pub fn bech32_decode_with_hrp(target: &str) -> StdResult<(String, Vec<u8>)> {
+    let (hrp, raw_addr_u5, _) = bech32::decode(target)
         .map_err(|e| StdError::generic_err(format!("invalid bech32 bytes. err: {e}")))?;
 
     let raw_addr = Vec::<u8>::from_base32(&raw_addr_u5)
         .map_err(|e| StdError::generic_err(format!("failed to parse [u5] to [u8]. err: {e}")))?;
 
-    Ok(raw_addr)
+    ensure_addr_length(raw_addr.len())?;
+
+    Ok((hrp, raw_addr))
 }
 
+/// Encodes a raw address, treating a 32 byte address padded from 20 bytes as an account address
 pub fn bech32_encode(hrp: &str, raw_addr: &[u8]) -> StdResult<Addr> {
-    if raw_addr.len() != 32 && raw_addr.len() != 20 {
-        return Err(StdError::generic_err(format!(
-            "invalid raw address length. expected: 32 or 20. got: {}",
-            raw_addr.len()
-        )));
-    }
+    bech32_encode_with_len(hrp, raw_addr, ACC_ADDR_LENGTH)
+}
 
-    if raw_addr.len() == 32 {
-        let mut bz = 0u128.to_be_bytes();
-        bz[4..].copy_from_slice(&raw_addr[0..12]);
-        let check = u128::from_be_bytes(bz);
+/// Encodes a raw address after stripping its padding down to `canonical_len` bytes.
+/// `canonical_len` should be taken from the original bech32 data of the address.
+pub fn bech32_encode_with_len(hrp: &str, raw_addr: &[u8], canonical_len: usize) -> StdResult<Addr> {
+    ensure_addr_length(raw_addr.len())?;
 
-        if check == 0 {
-            return bech32_encode(hrp, &raw_addr[12..]);
-        }
-    }
+    let raw_addr = unpad(raw_addr, canonical_len);
 
     let enc_addr = bech32::encode(hrp, raw_addr.to_base32(), bech32::Variant::Bech32)
         .map_err(|e| StdError::generic_err(format!("invalid bech32 address. err: {e}")))?;
@@ -44,14 +78,72 @@ pub fn bech32_encode(hrp: &str, raw_addr: &[u8]) -> StdResult<Addr> {
     Ok(Addr::unchecked(enc_addr))
 }
 
+/// Sender of a hyperlane message, the raw address left-padded with zeros to 32 bytes.
+/// Same as `TypeCasts.addressToBytes32` of the solidity implementation for 20 byte addresses,
+/// so remote routers compare the sender against the bytes they expect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CanonicalSender([u8; 32]);
+
+impl CanonicalSender {
+    /// Decodes `addr`, which must be under `hrp`
+    pub fn from_bech32(hrp: &str, addr: &str) -> StdResult<Self> {
+        let (addr_hrp, raw_addr) = bech32_decode_with_hrp(addr)?;
+        if addr_hrp != hrp {
+            return Err(StdError::generic_err(format!(
+                "unexpected hrp. expected: {hrp}, got: {addr_hrp}"
+            )));
+        }
+
+        Ok(Self(pad32(&raw_addr)?))
+    }
+
+    /// Decodes `addr` under any hrp
+    pub fn from_addr(addr: &str) -> StdResult<Self> {
+        Ok(Self(bech32_to_h256(addr)?))
+    }
+
+    /// Reads the sender field of a message, which must be 32 bytes
+    pub fn from_slice(raw_addr: &[u8]) -> StdResult<Self> {
+        raw_addr.try_into().map(Self).map_err(|_| {
+            StdError::generic_err(format!(
+                "invalid sender length. expected: 32. got: {}",
+                raw_addr.len()
+            ))
+        })
+    }
+
+    /// Inverse of `from_bech32`. 12 leading zero bytes are taken as the padding of an
+    /// account address, like `bech32_encode`
+    pub fn to_bech32(&self, hrp: &str) -> StdResult<Addr> {
+        bech32_encode(hrp, &self.0)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<CanonicalSender> for HexBinary {
+    fn from(v: CanonicalSender) -> Self {
+        v.0.to_vec().into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use cosmwasm_std::HexBinary;
+    use ibcx_test_utils::gen_bz;
     use rstest::rstest;
 
-    use crate::types::bech32_to_h256;
+    use crate::types::{bech32_to_h256, Message};
 
-    use super::{bech32_decode, bech32_encode};
+    use super::{
+        bech32_decode, bech32_encode, bech32_encode_with_len, pad32, unpad, CanonicalSender,
+    };
+
+    /// `TypeCasts.addressToBytes32(0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed)`
+    const EVM_ADDR: &str = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+    const EVM_BYTES32: &str = "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
 
     #[test]
     fn addr_conv() {
@@ -133,4 +225,140 @@ mod tests {
             }
         }
     }
+
+    #[rstest]
+    #[case(1)]
+    #[case(20)]
+    #[case(32)]
+    fn test_pad32(#[case] len: usize) {
+        let raw_addr = gen_bz(len);
+        let padded = pad32(&raw_addr).unwrap();
+
+        assert!(padded[..32 - len].iter().all(|v| *v == 0));
+        assert_eq!(&padded[32 - len..], raw_addr.as_slice());
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(33)]
+    fn test_invalid_length(#[case] len: usize) {
+        let raw_addr = gen_bz(len);
+        let expected =
+            format!("Generic error: invalid raw address length. expected: 1..=32. got: {len}");
+
+        assert_eq!(pad32(&raw_addr).unwrap_err().to_string(), expected);
+        assert_eq!(
+            bech32_encode("osmo", &raw_addr).unwrap_err().to_string(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_unpad() {
+        let mut raw_addr = [0u8; 32];
+        raw_addr[14..].copy_from_slice(&[1u8; 18]);
+
+        // only strips down to the canonical length
+        assert_eq!(unpad(&raw_addr, 20), &raw_addr[12..]);
+        assert_eq!(unpad(&raw_addr, 32), &raw_addr[..]);
+
+        // keeps meaningful leading zeros of 32 byte addresses
+        raw_addr[0] = 1;
+        assert_eq!(unpad(&raw_addr, 20), &raw_addr[..]);
+    }
+
+    #[rstest]
+    fn test_leading_zero_addr_roundtrip(
+        #[values("osmo", "neutron")] hrp: &str,
+        #[values(1, 5, 12)] zeros: usize,
+    ) {
+        let mut raw_addr = gen_bz(32).to_vec();
+        raw_addr[..zeros].fill(0);
+        raw_addr[zeros] = 1;
+
+        let enc_addr = bech32_encode_with_len(hrp, &raw_addr, 32).unwrap();
+        assert_eq!(bech32_decode(enc_addr.as_str()).unwrap(), raw_addr);
+    }
+
+    #[rstest]
+    // 20 byte account, same bytes as the evm address
+    #[case("osmo", "osmo1t2htvpfl862vnwdqnuekd9p4ulh3h6hdlvdhh0", EVM_BYTES32)]
+    #[case(
+        "neutron",
+        "neutron1t2htvpfl862vnwdqnuekd9p4ulh3h6hdngh9m6",
+        EVM_BYTES32
+    )]
+    // 32 byte contract, kept as is
+    #[case(
+        "osmo",
+        "osmo17w4q6efzym3p4c6umyp4cjf2ustjtmwfqdhd7rt2fpcpk9fmjzsqmymnqz",
+        "f3aa0d652226e21ae35cd9035c492ae41725edc9036edf0d6a48701b153b90a0"
+    )]
+    fn test_canonical_sender(#[case] hrp: &str, #[case] addr: &str, #[case] golden: &str) {
+        let sender = CanonicalSender::from_bech32(hrp, addr).unwrap();
+        assert_eq!(HexBinary::from(sender).to_hex(), golden);
+        assert_eq!(CanonicalSender::from_addr(addr).unwrap(), sender);
+
+        let sender = CanonicalSender::from_slice(&HexBinary::from_hex(golden).unwrap()).unwrap();
+        assert_eq!(sender.to_bech32(hrp).unwrap().as_str(), addr);
+    }
+
+    #[test]
+    fn test_canonical_sender_evm() {
+        // left-padded like `TypeCasts.addressToBytes32`, so the evm address is the last 20 bytes
+        let evm_addr = HexBinary::from_hex(EVM_ADDR).unwrap();
+        let sender =
+            CanonicalSender::from_addr(bech32_encode("osmo", &evm_addr).unwrap().as_str()).unwrap();
+
+        assert_eq!(&sender.as_bytes()[..12], &[0u8; 12]);
+        assert_eq!(&sender.as_bytes()[12..], evm_addr.as_slice());
+    }
+
+    #[test]
+    fn test_canonical_sender_invalid() {
+        let err =
+            CanonicalSender::from_bech32("neutron", "osmo1t2htvpfl862vnwdqnuekd9p4ulh3h6hdlvdhh0")
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: unexpected hrp. expected: neutron, got: osmo"
+        );
+
+        let err = CanonicalSender::from_slice(&gen_bz(20)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: invalid sender length. expected: 32. got: 20"
+        );
+    }
+
+    #[rstest]
+    fn test_message_addr_roundtrip(
+        #[values("osmo", "neutron")] hrp: &str,
+        #[values(20, 32)] len: usize,
+    ) {
+        for _ in 0..100 {
+            let raw_addr = gen_bz(len);
+
+            let enc_addr = bech32_encode(hrp, &raw_addr).unwrap();
+            let padded = bech32_to_h256(enc_addr.as_str()).unwrap();
+
+            let msg = Message {
+                version: 3,
+                nonce: 0,
+                origin_domain: 26657,
+                sender: padded.to_vec().into(),
+                dest_domain: 26657,
+                recipient: padded.to_vec().into(),
+                body: HexBinary::default(),
+            };
+
+            let recipient = msg.recipient_addr(hrp).unwrap();
+            assert_eq!(recipient, enc_addr);
+            assert_eq!(msg.sender_addr(hrp).unwrap(), enc_addr);
+            assert_eq!(
+                bech32_decode(recipient.as_str()).unwrap(),
+                raw_addr.to_vec()
+            );
+        }
+    }
 }
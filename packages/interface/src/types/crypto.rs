@@ -1,4 +1,4 @@
-use cosmwasm_std::{HexBinary, StdError, StdResult};
+use cosmwasm_std::{HexBinary, StdResult};
 
 const PREFIX: &str = "\x19Ethereum Signed Message:\n";
 
@@ -37,11 +37,7 @@ pub fn sha256_digest(bz: impl AsRef<[u8]>) -> StdResult<[u8; 32]> {
 
     hasher.update(bz);
 
-    hasher
-        .finalize()
-        .as_slice()
-        .try_into()
-        .map_err(|_| StdError::generic_err("wrong length"))
+    Ok(hasher.finalize().into())
 }
 
 pub fn ripemd160_digest(bz: impl AsRef<[u8]>) -> StdResult<[u8; 20]> {
@@ -51,11 +47,7 @@ pub fn ripemd160_digest(bz: impl AsRef<[u8]>) -> StdResult<[u8; 20]> {
 
     hasher.update(bz);
 
-    hasher
-        .finalize()
-        .as_slice()
-        .try_into()
-        .map_err(|_| StdError::generic_err("wrong length"))
+    Ok(hasher.finalize().into())
 }
 
 pub fn pub_to_addr(pub_key: HexBinary) -> StdResult<HexBinary> {
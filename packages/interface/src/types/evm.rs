@@ -0,0 +1,285 @@
+use std::{fmt, ops::Deref, str::FromStr};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Binary, HexBinary, StdError, StdResult};
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::types::{bech32_decode, keccak256_hash, unpad};
+
+const EVM_ADDRESS_LENGTH: usize = 20;
+
+/// Parses a 0x-prefixed EVM address.
+/// Mixed case input must carry a valid EIP-55 checksum, all lower or upper case is taken as is.
+pub fn evm_address_from_str(target: &str) -> StdResult<HexBinary> {
+    let raw = target.strip_prefix("0x").ok_or_else(|| {
+        StdError::generic_err(format!("evm address must start with 0x: {target}"))
+    })?;
+
+    let addr = HexBinary::from_hex(raw)?;
+    if addr.len() != EVM_ADDRESS_LENGTH {
+        return Err(StdError::generic_err(format!(
+            "evm address must be {EVM_ADDRESS_LENGTH} bytes. got: {}",
+            addr.len()
+        )));
+    }
+
+    let has_lower = raw.chars().any(|v| v.is_ascii_lowercase());
+    let has_upper = raw.chars().any(|v| v.is_ascii_uppercase());
+    if has_lower && has_upper && to_evm_checksum(&addr) != target {
+        return Err(StdError::generic_err(format!(
+            "invalid evm address checksum: {target}"
+        )));
+    }
+
+    Ok(addr)
+}
+
+/// Formats an address as a 0x-prefixed EIP-55 checksummed string.
+/// Zero padded 32 byte addresses are unpadded to 20 bytes first.
+pub fn to_evm_checksum(addr: &HexBinary) -> String {
+    let lower = HexBinary::from(unpad(addr, EVM_ADDRESS_LENGTH)).to_hex();
+    let hash = keccak256_hash(lower.as_bytes());
+
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    format!("0x{checksummed}")
+}
+
+/// Left pads an address with zeros to 32 bytes.
+/// Addresses of 32 bytes or more are returned unchanged.
+pub fn pad_to_h256(addr: &HexBinary) -> HexBinary {
+    if addr.len() >= 32 {
+        return addr.clone();
+    }
+
+    let mut padded = vec![0u8; 32 - addr.len()];
+    padded.extend_from_slice(addr);
+    padded.into()
+}
+
+/// Bytes that deserialize from a hex string (optionally 0x-prefixed) or a base64 `Binary`,
+/// and always serialize as plain hex like `HexBinary`.
+///
+/// Strings that are valid hex are read as hex. Base64 of 20 and 32 byte
+/// addresses always carries padding, so it never parses as hex.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, JsonSchema)]
+pub struct HexOrBinary(#[schemars(with = "String")] HexBinary);
+
+impl HexOrBinary {
+    pub fn into_inner(self) -> HexBinary {
+        self.0
+    }
+}
+
+impl FromStr for HexOrBinary {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(raw) = s.strip_prefix("0x") {
+            // evm addresses get their checksum verified
+            if raw.len() == EVM_ADDRESS_LENGTH * 2 {
+                return evm_address_from_str(s).map(Self);
+            }
+
+            return HexBinary::from_hex(raw).map(Self);
+        }
+
+        HexBinary::from_hex(s)
+            .or_else(|_| Binary::from_base64(s).map(|v| v.to_vec().into()))
+            .map(Self)
+    }
+}
+
+impl fmt::Display for HexOrBinary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Deref for HexOrBinary {
+    type Target = HexBinary;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<HexBinary> for HexOrBinary {
+    fn from(v: HexBinary) -> Self {
+        Self(v)
+    }
+}
+
+impl From<HexOrBinary> for HexBinary {
+    fn from(v: HexOrBinary) -> Self {
+        v.0
+    }
+}
+
+impl Serialize for HexOrBinary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for HexOrBinary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Address of a remote contract, in the format of the tooling it comes from
+#[cw_serde]
+pub enum RemoteAddress {
+    /// Optionally 0x-prefixed. 0x-prefixed evm addresses get their checksum verified
+    Hex(String),
+    Base64(Binary),
+    Bech32(String),
+}
+
+impl RemoteAddress {
+    /// Decodes the address, left padding 20 byte addresses to 32 bytes
+    pub fn to_h256(&self) -> StdResult<HexBinary> {
+        let raw = match self {
+            Self::Hex(v) => match v.strip_prefix("0x") {
+                Some(raw) if raw.len() == EVM_ADDRESS_LENGTH * 2 => evm_address_from_str(v)?,
+                Some(raw) => HexBinary::from_hex(raw)?,
+                None => HexBinary::from_hex(v)?,
+            },
+            Self::Base64(v) => v.to_vec().into(),
+            Self::Bech32(v) => bech32_decode(v)?.into(),
+        };
+
+        match raw.len() {
+            EVM_ADDRESS_LENGTH => Ok(pad_to_h256(&raw)),
+            32 => Ok(raw),
+            len => Err(StdError::generic_err(format!(
+                "remote address must be 20 or 32 bytes. got: {len}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{from_json, to_json_string};
+    use ibcx_test_utils::hex;
+    use rstest::rstest;
+
+    use super::*;
+
+    // reference vectors from EIP-55
+    #[rstest]
+    #[case("0x52908400098527886E0F7030069857D2E4169EE7")]
+    #[case("0x8617E340B3D01FA5F11F306F4090FD50E238070D")]
+    #[case("0xde709f2102306220921060314715629080e2fb77")]
+    #[case("0x27b1fdb04752bbc536007a920d24acb045561c26")]
+    #[case("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")]
+    #[case("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359")]
+    #[case("0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB")]
+    #[case("0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb")]
+    fn test_evm_checksum(#[case] target: &str) {
+        let addr = evm_address_from_str(target).unwrap();
+        assert_eq!(addr, hex(&target[2..].to_lowercase()));
+
+        // all upper or lower case vectors only differ in case from the checksum
+        assert!(to_evm_checksum(&addr).eq_ignore_ascii_case(target));
+        if target[2..].chars().any(|v| v.is_ascii_lowercase())
+            && target[2..].chars().any(|v| v.is_ascii_uppercase())
+        {
+            assert_eq!(to_evm_checksum(&addr), target);
+        }
+
+        // padded addresses format the same
+        assert_eq!(to_evm_checksum(&pad_to_h256(&addr)), to_evm_checksum(&addr));
+    }
+
+    #[rstest]
+    #[case::bad_checksum("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD")]
+    #[case::no_prefix("5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")]
+    #[case::short("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA")]
+    #[case::not_hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAgg")]
+    fn test_evm_address_invalid(#[case] target: &str) {
+        assert!(evm_address_from_str(target).is_err());
+    }
+
+    #[test]
+    fn test_pad_to_h256() {
+        let addr = hex("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+
+        let padded = pad_to_h256(&addr);
+        assert_eq!(padded.len(), 32);
+        assert_eq!(&padded[..12], &[0u8; 12]);
+        assert_eq!(&padded[12..], addr.as_slice());
+
+        assert_eq!(pad_to_h256(&padded), padded);
+    }
+
+    #[rstest]
+    #[case::hex("\"5aaeb6053f3e94c9b9a09f33669435e7ef1beaed\"")]
+    #[case::evm("\"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed\"")]
+    #[case::base64("\"Wq62BT8+lMm5oJ8zZpQ15+8b6u0=\"")]
+    fn test_serde(#[case] input: &str) {
+        let parsed: HexOrBinary = from_json(input).unwrap();
+        assert_eq!(*parsed, hex("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"));
+
+        // always written back as plain hex
+        assert_eq!(
+            to_json_string(&parsed).unwrap(),
+            "\"5aaeb6053f3e94c9b9a09f33669435e7ef1beaed\""
+        );
+    }
+
+    #[rstest]
+    #[case::bad_checksum("\"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD\"")]
+    #[case::garbage("\"not an address\"")]
+    fn test_serde_invalid(#[case] input: &str) {
+        assert!(from_json::<HexOrBinary>(input).is_err());
+    }
+
+    #[rstest]
+    #[case::evm(RemoteAddress::Hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".into()))]
+    #[case::hex(RemoteAddress::Hex("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".into()))]
+    #[case::hex_padded(RemoteAddress::Hex(
+        "0x0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed".into()
+    ))]
+    #[case::base64(RemoteAddress::Base64(
+        Binary::from_base64("Wq62BT8+lMm5oJ8zZpQ15+8b6u0=").unwrap()
+    ))]
+    #[case::bech32(RemoteAddress::Bech32(
+        "osmo1t2htvpfl862vnwdqnuekd9p4ulh3h6hdlvdhh0".into()
+    ))]
+    fn test_remote_address(#[case] address: RemoteAddress) {
+        assert_eq!(
+            address.to_h256().unwrap(),
+            hex("0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed")
+        );
+    }
+
+    #[rstest]
+    #[case::bad_checksum(
+        RemoteAddress::Hex("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".into()),
+        "invalid evm address checksum"
+    )]
+    #[case::short(RemoteAddress::Hex("5aaeb6".into()), "got: 3")]
+    #[case::long(RemoteAddress::Base64(vec![1; 33].into()), "got: 33")]
+    #[case::bech32(RemoteAddress::Bech32("not an address".into()), "invalid bech32")]
+    fn test_remote_address_invalid(#[case] address: RemoteAddress, #[case] reason: &str) {
+        let err = address.to_h256().unwrap_err();
+        assert!(err.to_string().contains(reason));
+    }
+}
@@ -5,6 +5,7 @@ use super::keccak256_hash;
 
 pub const HASH_LENGTH: usize = 32;
 pub const TREE_DEPTH: usize = 32;
+/// Capacity of a `TREE_DEPTH` tree, see `MerkleTree::max_leaves` for other depths
 pub const MAX_LEAVES: u128 = (2_u128.pow(TREE_DEPTH as u32)) - 1;
 
 pub const ZERO_BYTES: &str = "0000000000000000000000000000000000000000000000000000000000000000";
@@ -43,31 +44,48 @@ pub const ZERO_HASHES: [&str; TREE_DEPTH] = [
     "8448818bb4ae4562849e949e17ac16e0be16688e156b5cf15e098c627c0056a9",
 ];
 
+/// Incremental merkle tree, the depth is given by the length of `branch`
 #[cw_serde]
 pub struct MerkleTree {
-    pub branch: [HexBinary; TREE_DEPTH],
+    pub branch: Vec<HexBinary>,
     pub count: u128,
 }
 
 impl Default for MerkleTree {
     fn default() -> Self {
-        Self {
-            branch: ZERO_HASHES
-                .into_iter()
-                .map(HexBinary::from_hex)
-                .collect::<StdResult<Vec<HexBinary>>>()
-                .unwrap()
-                .try_into()
-                .unwrap(),
-            count: Default::default(),
-        }
+        Self::new(TREE_DEPTH as u8).unwrap()
     }
 }
 
 impl MerkleTree {
+    pub fn new(depth: u8) -> StdResult<Self> {
+        ensure!(
+            (1..=TREE_DEPTH as u8).contains(&depth),
+            StdError::generic_err(format!(
+                "invalid tree depth: {depth}. must be 1..={TREE_DEPTH}"
+            ))
+        );
+
+        Ok(Self {
+            branch: MerkleTree::zero_hashes(depth as usize)?
+                .into_iter()
+                .map(HexBinary::from)
+                .collect(),
+            count: Default::default(),
+        })
+    }
+
+    pub fn depth(&self) -> usize {
+        self.branch.len()
+    }
+
+    pub fn max_leaves(&self) -> u128 {
+        2_u128.pow(self.depth() as u32) - 1
+    }
+
     pub fn insert(&mut self, node: HexBinary) -> StdResult<()> {
         ensure!(
-            self.count < MAX_LEAVES,
+            self.count < self.max_leaves(),
             StdError::generic_err("tree is full")
         );
 
@@ -86,7 +104,12 @@ impl MerkleTree {
         panic!("unreachable code")
     }
 
-    pub fn root_with_ctx(&self, zeroes: [[u8; HASH_LENGTH]; TREE_DEPTH]) -> StdResult<HexBinary> {
+    pub fn root_with_ctx(&self, zeroes: &[[u8; HASH_LENGTH]]) -> StdResult<HexBinary> {
+        ensure!(
+            zeroes.len() == self.depth(),
+            StdError::generic_err("zero hashes do not match tree depth")
+        );
+
         let idx = self.count;
 
         Ok(zeroes
@@ -104,10 +127,10 @@ impl MerkleTree {
     }
 
     pub fn root(&self) -> StdResult<HexBinary> {
-        self.root_with_ctx(MerkleTree::zeroes()?)
+        self.root_with_ctx(&MerkleTree::zero_hashes(self.depth())?)
     }
 
-    pub fn branch_root(item: HexBinary, branch: [HexBinary; TREE_DEPTH], idx: u128) -> HexBinary {
+    pub fn branch_root(item: HexBinary, branch: Vec<HexBinary>, idx: u128) -> HexBinary {
         branch
             .into_iter()
             .enumerate()
@@ -124,18 +147,24 @@ impl MerkleTree {
             .expect("invalid length"))
     }
 
+    #[deprecated(note = "use `MerkleTree::zero_hashes`, which takes the tree depth")]
     pub fn zeroes() -> StdResult<[[u8; HASH_LENGTH]; TREE_DEPTH]> {
-        Ok(ZERO_HASHES
-            .into_iter()
+        Ok(MerkleTree::zero_hashes(TREE_DEPTH)?
+            .try_into()
+            .expect("invalid depth"))
+    }
+
+    /// roots of empty subtrees for each level below `depth`
+    pub fn zero_hashes(depth: usize) -> StdResult<Vec<[u8; HASH_LENGTH]>> {
+        ZERO_HASHES[..depth]
+            .iter()
             .map(|v| {
                 Ok(HexBinary::from_hex(v)?
                     .to_vec()
                     .try_into()
                     .expect("invalid hash"))
             })
-            .collect::<StdResult<Vec<_>>>()?
-            .try_into()
-            .expect("invalid depth"))
+            .collect()
     }
 }
 
@@ -143,13 +172,124 @@ impl MerkleTree {
 mod tests {
     use super::*;
 
+    use rstest::rstest;
+
+    /// builds the root level by level, padding each level with the empty subtree root
+    fn reference_root(leaves: &[HexBinary], depth: usize) -> HexBinary {
+        let zeroes = MerkleTree::zero_hashes(depth).unwrap();
+
+        let mut nodes = leaves.to_vec();
+        for zero in zeroes {
+            if nodes.is_empty() {
+                nodes.push(zero.into());
+            }
+            if nodes.len() % 2 == 1 {
+                nodes.push(zero.into());
+            }
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| keccak256_hash(&[pair[0].to_vec(), pair[1].to_vec()].concat()))
+                .collect();
+        }
+
+        nodes[0].clone()
+    }
+
+    fn leaf(i: usize) -> HexBinary {
+        keccak256_hash(format!("leaf_{i}").as_bytes())
+    }
+
     #[test]
     fn test_default_merkle_tree() {
-        for (i, branch) in MerkleTree::default().branch.into_iter().enumerate() {
+        let tree = MerkleTree::default();
+        assert_eq!(tree.depth(), TREE_DEPTH);
+        assert_eq!(tree.max_leaves(), MAX_LEAVES);
+
+        for (i, branch) in tree.branch.into_iter().enumerate() {
             assert_eq!(branch.to_hex(), super::ZERO_HASHES[i]);
         }
     }
 
+    #[test]
+    #[allow(deprecated)]
+    fn test_zeroes() {
+        assert_eq!(
+            MerkleTree::zeroes().unwrap().to_vec(),
+            MerkleTree::zero_hashes(TREE_DEPTH).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_zero_hashes() {
+        let zeroes = MerkleTree::zero_hashes(TREE_DEPTH).unwrap();
+
+        assert_eq!(zeroes[0], MerkleTree::zero().unwrap());
+        for i in 1..TREE_DEPTH {
+            assert_eq!(
+                keccak256_hash(&[zeroes[i - 1], zeroes[i - 1]].concat()).to_vec(),
+                zeroes[i]
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(4)]
+    #[case(32)]
+    #[should_panic(expected = "invalid tree depth: 0. must be 1..=32")]
+    #[case(0)]
+    #[should_panic(expected = "invalid tree depth: 33. must be 1..=32")]
+    #[case(33)]
+    fn test_new(#[case] depth: u8) {
+        let tree = MerkleTree::new(depth).map_err(|v| v.to_string()).unwrap();
+
+        assert_eq!(tree.depth(), depth as usize);
+        assert_eq!(tree.count, 0);
+    }
+
+    #[rstest]
+    #[case(4, 0)]
+    #[case(4, 1)]
+    #[case(4, 5)]
+    #[case(4, 15)]
+    #[case(32, 0)]
+    #[case(32, 1)]
+    #[case(32, 7)]
+    fn test_root(#[case] depth: u8, #[case] size: usize) {
+        let mut tree = MerkleTree::new(depth).unwrap();
+        let leaves = (0..size).map(leaf).collect::<Vec<_>>();
+
+        for leaf in leaves.iter() {
+            tree.insert(leaf.clone()).unwrap();
+        }
+
+        assert_eq!(
+            tree.root().unwrap(),
+            reference_root(&leaves, depth as usize)
+        );
+    }
+
+    #[test]
+    fn test_root_empty() {
+        // empty root of the 32-depth hyperlane tree
+        assert_eq!(
+            MerkleTree::default().root().unwrap().to_hex(),
+            "27ae5ba08d7291c96c8cbddcc148bf48a6d68c7974b94356f53754ef6171d757"
+        );
+    }
+
+    #[test]
+    fn test_tree_full() {
+        let mut tree = MerkleTree::new(4).unwrap();
+
+        for i in 0..15 {
+            tree.insert(leaf(i)).unwrap();
+        }
+
+        let err = tree.insert(leaf(15)).unwrap_err();
+        assert_eq!(err, StdError::generic_err("tree is full"));
+    }
+
     #[test]
     fn test_compatibility() {
         let digest: HexBinary = [
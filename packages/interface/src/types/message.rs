@@ -1,7 +1,10 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, HexBinary, StdResult};
+use cosmwasm_std::{Addr, HexBinary, StdError, StdResult};
 
-use super::bech32_encode;
+use super::{bech32_encode, CanonicalSender};
+
+/// Length of the fixed size fields preceding the body
+const MESSAGE_HEADER_LENGTH: usize = 77;
 
 #[cw_serde]
 pub struct Message {
@@ -19,9 +22,11 @@ impl Message {
         super::keccak256_hash(&HexBinary::from(self.clone()))
     }
 
+    /// Inverse of the sender encoding of `DispatchMsg::to_msg`
     pub fn sender_addr(&self, hrp: &str) -> StdResult<Addr> {
-        bech32_encode(hrp, &self.sender)
+        CanonicalSender::from_slice(&self.sender)?.to_bech32(hrp)
     }
+
     pub fn recipient_addr(&self, hrp: &str) -> StdResult<Addr> {
         bech32_encode(hrp, &self.recipient)
     }
@@ -44,9 +49,18 @@ impl From<Message> for HexBinary {
     }
 }
 
-impl From<HexBinary> for Message {
-    fn from(v: HexBinary) -> Self {
-        Self {
+impl TryFrom<HexBinary> for Message {
+    type Error = StdError;
+
+    fn try_from(v: HexBinary) -> Result<Self, Self::Error> {
+        if v.len() < MESSAGE_HEADER_LENGTH {
+            return Err(StdError::generic_err(format!(
+                "invalid message length. expected at least {MESSAGE_HEADER_LENGTH}, got: {}",
+                v.len()
+            )));
+        }
+
+        Ok(Self {
             version: v[0],
             nonce: u32::from_be_bytes(v[1..5].try_into().unwrap()),
             origin_domain: u32::from_be_bytes(v[5..9].try_into().unwrap()),
@@ -54,7 +68,7 @@ impl From<HexBinary> for Message {
             dest_domain: u32::from_be_bytes(v[41..45].try_into().unwrap()),
             recipient: v[45..77].to_vec().into(),
             body: v[77..].to_vec().into(),
-        }
+        })
     }
 }
 
@@ -70,7 +84,7 @@ mod tests {
 
         let hex = |v: &str| -> HexBinary { HexBinary::from_hex(v).unwrap() };
 
-        let decode_actual: Message = encode_expected.clone().into();
+        let decode_actual: Message = encode_expected.clone().try_into().unwrap();
         let decode_expected = Message {
             version: 0,
             nonce: 8528,
@@ -87,10 +101,19 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "range end index 77 out of range for slice of length 67")]
-    fn test_overflow() {
+    fn test_truncated() {
         let no = HexBinary::from_hex("00000021500000aef3000000000000000000000000477d860f8f41bc69ddd32821f2bf2c2af0243f1600aa36a70000000000000000000000005d56b8a669f50193b543").unwrap();
 
-        let _msg: Message = no.into();
+        let err = Message::try_from(no).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: invalid message length. expected at least 77, got: 67"
+        );
+
+        // the body may be empty
+        let header = HexBinary::from(vec![0u8; 77]);
+        let msg = Message::try_from(header.clone()).unwrap();
+        assert!(msg.body.is_empty());
+        assert_eq!(HexBinary::from(msg), header);
     }
 }
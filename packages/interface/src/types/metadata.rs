@@ -1,12 +1,77 @@
-use std::collections::BTreeMap;
-
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, HexBinary, Uint256};
+use cosmwasm_std::{ensure, Addr, HexBinary, StdError, StdResult, Uint256};
 
-use super::bech32_encode;
+use super::{bech32_encode, unpad};
 
 const SIGNATURE_LENGTH: usize = 65;
 
+/// One byte validator index followed by the signature
+const INDEXED_SIGNATURE_LENGTH: usize = SIGNATURE_LENGTH + 1;
+
+/// Trails indexed signatures. Standard metadata ends with the recovery id of a signature,
+/// which is never this value, so the two layouts can't be mistaken for each other.
+pub const INDEXED_SIGNATURES_MARKER: u8 = 0xff;
+
+pub const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// Carves each `(start, end)` range out of `metadata`.
+/// Fails instead of panicking when a range is inverted or runs past the end of the input.
+pub fn split_offsets(
+    metadata: &HexBinary,
+    offsets: &[(usize, usize)],
+) -> StdResult<Vec<HexBinary>> {
+    offsets
+        .iter()
+        .map(|&(start, end)| {
+            ensure!(
+                start <= end,
+                StdError::generic_err(format!(
+                    "invalid metadata range. start: {start}, end: {end}"
+                ))
+            );
+            ensure!(
+                end <= metadata.len(),
+                StdError::generic_err(format!(
+                    "metadata truncated. range: {start}..{end}, length: {}",
+                    metadata.len()
+                ))
+            );
+
+            Ok(metadata[start..end].to_vec().into())
+        })
+        .collect()
+}
+
+/// End of the `len` bytes from `start`, failing like a truncated range when it overflows.
+/// Lengths are read from the input, so this matters on 32-bit targets.
+fn checked_end(metadata: &HexBinary, start: usize, len: usize) -> StdResult<usize> {
+    start.checked_add(len).ok_or_else(|| {
+        StdError::generic_err(format!(
+            "metadata truncated. range: {start}..{start}+{len}, length: {}",
+            metadata.len()
+        ))
+    })
+}
+
+/// Splits `metadata` into consecutive sections, each prefixed with its length as a big-endian u32.
+pub fn split_length_prefixed(metadata: &HexBinary) -> StdResult<Vec<HexBinary>> {
+    let mut sections = vec![];
+    let mut cursor = 0;
+
+    while cursor < metadata.len() {
+        let prefix_end = checked_end(metadata, cursor, LENGTH_PREFIX_SIZE)?;
+        let prefix = &split_offsets(metadata, &[(cursor, prefix_end)])?[0];
+
+        let len = u32::from_be_bytes(prefix.as_slice().try_into().unwrap()) as usize;
+        let section_end = checked_end(metadata, prefix_end, len)?;
+
+        sections.extend(split_offsets(metadata, &[(prefix_end, section_end)])?);
+        cursor = section_end;
+    }
+
+    Ok(sections)
+}
+
 #[cw_serde]
 pub struct MerkleRootMultisigIsmMetadata {
     pub origin_mailbox: HexBinary,
@@ -48,7 +113,7 @@ impl From<HexBinary> for MerkleRootMultisigIsmMetadata {
 
 impl MerkleRootMultisigIsmMetadata {
     pub fn signatures_len(&self) -> Result<usize, &'static str> {
-        if self.signatures.len() % SIGNATURE_LENGTH != 0 {
+        if !self.signatures.len().is_multiple_of(SIGNATURE_LENGTH) {
             return Err("Invalid signatures length");
         }
 
@@ -72,6 +137,11 @@ pub struct MessageIdMultisigIsmMetadata {
     pub merkle_index: HexBinary,
 
     pub signatures: Vec<HexBinary>,
+
+    /// Position of each signer in the validator set, encoded as a one byte prefix of its signature.
+    /// Indexed signatures are followed by `INDEXED_SIGNATURES_MARKER`
+    #[serde(default)]
+    pub validator_indices: Option<Vec<u8>>,
 }
 
 impl From<MessageIdMultisigIsmMetadata> for HexBinary {
@@ -88,7 +158,16 @@ impl From<MessageIdMultisigIsmMetadata> for HexBinary {
             .chain(
                 v.signatures
                     .iter()
-                    .flat_map(|x| x.to_vec())
+                    .enumerate()
+                    .flat_map(|(i, x)| {
+                        let index = v.validator_indices.as_ref().map(|indices| indices[i]);
+                        index.into_iter().chain(x.to_vec())
+                    })
+                    .chain(
+                        v.validator_indices
+                            .as_ref()
+                            .map(|_| INDEXED_SIGNATURES_MARKER),
+                    )
                     .collect::<Vec<_>>()
                     .iter(),
             )
@@ -98,122 +177,288 @@ impl From<MessageIdMultisigIsmMetadata> for HexBinary {
     }
 }
 
+/// Signatures are read with validator indices when they end with `INDEXED_SIGNATURES_MARKER`
 impl From<HexBinary> for MessageIdMultisigIsmMetadata {
     fn from(v: HexBinary) -> Self {
-        let signatures = v[68..]
-            .to_vec()
-            .chunks_exact(SIGNATURE_LENGTH)
-            .map(|v| v.into())
-            .collect::<Vec<HexBinary>>();
+        let raw = &v[68..];
+
+        let (signatures, validator_indices) = if let Some((&INDEXED_SIGNATURES_MARKER, raw)) = raw
+            .split_last()
+            .filter(|(_, raw)| raw.len().is_multiple_of(INDEXED_SIGNATURE_LENGTH))
+        {
+            let (indices, signatures) = raw
+                .chunks_exact(INDEXED_SIGNATURE_LENGTH)
+                .map(|v| (v[0], HexBinary::from(&v[1..])))
+                .unzip();
+
+            (signatures, Some(indices))
+        } else {
+            let signatures = raw
+                .chunks_exact(SIGNATURE_LENGTH)
+                .map(|v| v.into())
+                .collect::<Vec<HexBinary>>();
+
+            (signatures, None)
+        };
 
         Self {
             origin_merkle_tree: v[0..32].to_vec().into(),
             merkle_root: v[32..64].to_vec().into(),
             merkle_index: v[64..68].to_vec().into(),
             signatures,
+            validator_indices,
         }
     }
 }
 
 impl MessageIdMultisigIsmMetadata {
+    /// Encoded length of metadata carrying `signatures` signatures
+    pub fn encoded_len(signatures: usize) -> usize {
+        68 + signatures * SIGNATURE_LENGTH
+    }
+
     pub fn merkle_index(&self) -> u32 {
         u32::from_be_bytes(self.merkle_index.to_vec().try_into().unwrap())
     }
 }
 
-use std::convert::AsMut;
+/// Pre-v3 multisig metadata.
+/// root || index || origin_mailbox || proof || threshold || signatures || validators
+#[cw_serde]
+pub struct LegacyMultisigIsmMetadata {
+    pub merkle_root: HexBinary,
+
+    pub merkle_index: u32,
+
+    pub origin_mailbox: HexBinary,
+
+    pub proof: Vec<HexBinary>,
+
+    pub threshold: u8,
+
+    pub signatures: Vec<HexBinary>,
+
+    // left padded to bytes32
+    pub validators: Vec<HexBinary>,
+}
+
+impl LegacyMultisigIsmMetadata {
+    const PROOF_OFFSET: usize = 68;
+    const THRESHOLD_OFFSET: usize = Self::PROOF_OFFSET + 32 * 32;
+    const SIGNATURES_OFFSET: usize = Self::THRESHOLD_OFFSET + 1;
+
+    pub fn try_from_hex(v: HexBinary) -> StdResult<Self> {
+        let head = split_offsets(
+            &v,
+            &[
+                (0, 32),
+                (32, 36),
+                (36, Self::PROOF_OFFSET),
+                (Self::PROOF_OFFSET, Self::THRESHOLD_OFFSET),
+                (Self::THRESHOLD_OFFSET, Self::SIGNATURES_OFFSET),
+            ],
+        )?;
+
+        let threshold = head[4][0];
+        let validators_offset = Self::SIGNATURES_OFFSET + threshold as usize * SIGNATURE_LENGTH;
+
+        let tail = split_offsets(
+            &v,
+            &[
+                (Self::SIGNATURES_OFFSET, validators_offset),
+                (validators_offset, v.len()),
+            ],
+        )?;
+        ensure!(
+            tail[1].len().is_multiple_of(32),
+            StdError::generic_err(format!("invalid validator set length: {}", tail[1].len()))
+        );
+
+        Ok(Self {
+            merkle_root: head[0].clone(),
+            merkle_index: u32::from_be_bytes(head[1].as_slice().try_into().unwrap()),
+            origin_mailbox: head[2].clone(),
+            proof: head[3].chunks_exact(32).map(|v| v.into()).collect(),
+            threshold,
+            signatures: tail[0]
+                .chunks_exact(SIGNATURE_LENGTH)
+                .map(|v| v.into())
+                .collect(),
+            validators: tail[1].chunks_exact(32).map(|v| v.into()).collect(),
+        })
+    }
+}
 
-fn clone_into_array<A, T>(slice: &[T]) -> A
-where
-    A: Sized + Default + AsMut<[T]>,
-    T: Clone,
-{
-    let mut a = Default::default();
-    <A as AsMut<[T]>>::as_mut(&mut a).clone_from_slice(slice);
-    a
+impl From<LegacyMultisigIsmMetadata> for HexBinary {
+    fn from(v: LegacyMultisigIsmMetadata) -> Self {
+        [
+            v.merkle_root.to_vec(),
+            v.merkle_index.to_be_bytes().to_vec(),
+            v.origin_mailbox.to_vec(),
+            v.proof.iter().flat_map(|x| x.to_vec()).collect(),
+            vec![v.threshold],
+            v.signatures.iter().flat_map(|x| x.to_vec()).collect(),
+            v.validators.iter().flat_map(|x| x.to_vec()).collect(),
+        ]
+        .concat()
+        .into()
+    }
 }
 
+/// Metadata of an aggregation ism in the hyperlane format.
+/// A header holds a big-endian u32 `(start, end)` range for each sub ism, in the order they
+/// are configured, followed by the metadata of every sub ism.
+/// A zero `start` means there is no metadata for that sub ism, so it's skipped.
 #[cw_serde]
-pub struct AggregateMetadata(BTreeMap<Addr, HexBinary>);
+#[derive(Default)]
+pub struct AggregateMetadata(Vec<Option<HexBinary>>);
 
 impl AggregateMetadata {
     pub const RANGE_SIZE: usize = 4;
 
-    pub fn new(set: Vec<(Addr, HexBinary)>) -> Self {
-        Self(set.into_iter().collect())
+    /// Metadata of each sub ism by position, `None` to skip it
+    pub fn new(metadata: Vec<Option<HexBinary>>) -> Self {
+        Self(metadata)
+    }
+
+    pub fn get(&self, index: usize) -> Option<&HexBinary> {
+        self.0.get(index).and_then(|v| v.as_ref())
     }
 }
 
-impl Iterator for AggregateMetadata {
-    type Item = (Addr, HexBinary);
+impl IntoIterator for AggregateMetadata {
+    type Item = Option<HexBinary>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.pop_first()
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
     }
 }
 
 impl AggregateMetadata {
-    pub fn from_hex(v: HexBinary, addrs: Vec<Addr>) -> Self {
-        Self(
-            addrs
-                .into_iter()
-                .enumerate()
-                .map(|(i, ism)| {
+    /// Reads the metadata of `count` sub isms.
+    /// Fails if a range isn't within the input past the header, overlapping ranges are allowed.
+    pub fn from_hex(v: HexBinary, count: usize) -> StdResult<Self> {
+        let header_end = count * Self::RANGE_SIZE * 2;
+
+        Ok(Self(
+            (0..count)
+                .map(|i| {
                     let start = i * Self::RANGE_SIZE * 2;
                     let mid = start + Self::RANGE_SIZE;
                     let end = mid + Self::RANGE_SIZE;
 
-                    let mut meta_start = [0u8; 4];
-                    meta_start.copy_from_slice(&v[start..mid]);
-                    let mut meta_end = [0u8; 4];
-                    meta_end.copy_from_slice(&v[mid..end]);
+                    let range = split_offsets(&v, &[(start, mid), (mid, end)])?;
+
+                    let meta_start =
+                        u32::from_be_bytes(range[0].as_slice().try_into().unwrap()) as usize;
+                    let meta_end =
+                        u32::from_be_bytes(range[1].as_slice().try_into().unwrap()) as usize;
 
-                    let meta_start = u32::from_be_bytes(meta_start) as usize;
-                    let meta_end = u32::from_be_bytes(meta_end) as usize;
+                    if meta_start == 0 {
+                        return Ok(None);
+                    }
 
-                    (ism, v[meta_start..meta_end].to_vec().into())
+                    ensure!(
+                        meta_start >= header_end,
+                        StdError::generic_err(format!(
+                            "metadata range overlaps header. range: {meta_start}..{meta_end}, \
+                             header length: {header_end}"
+                        ))
+                    );
+
+                    Ok(Some(
+                        split_offsets(&v, &[(meta_start, meta_end)])?.remove(0),
+                    ))
                 })
-                .collect(),
-        )
+                .collect::<StdResult<_>>()?,
+        ))
     }
 }
 
 impl From<AggregateMetadata> for HexBinary {
     fn from(v: AggregateMetadata) -> Self {
-        let pos_start = v.0.len() * AggregateMetadata::RANGE_SIZE * 2;
-
-        let ls: Vec<(
-            [u8; AggregateMetadata::RANGE_SIZE],
-            [u8; AggregateMetadata::RANGE_SIZE],
-            HexBinary,
-        )> =
-            v.0.values()
-                .fold(vec![] as Vec<(usize, usize, HexBinary)>, |mut acc, m| {
-                    let l = acc.last().map(|v| v.1).unwrap_or(pos_start);
-
-                    acc.push((l, l + m.len(), m.clone()));
-                    acc
-                })
-                .into_iter()
-                .map(|(start, end, metadata)| {
-                    (
-                        clone_into_array(&start.to_be_bytes()[AggregateMetadata::RANGE_SIZE..]),
-                        clone_into_array(&end.to_be_bytes()[AggregateMetadata::RANGE_SIZE..]),
-                        metadata,
-                    )
-                })
-                .collect();
+        let mut cursor = v.0.len() * AggregateMetadata::RANGE_SIZE * 2;
 
-        let mut pos = vec![];
+        let mut header = vec![];
         let mut metadata = vec![];
 
-        for (start, end, meta) in ls {
-            pos.extend_from_slice(&start);
-            pos.extend_from_slice(&end);
-            metadata.extend_from_slice(meta.as_slice());
+        for meta in v.0 {
+            let (start, end) = match meta {
+                Some(meta) => {
+                    let range = (cursor as u32, (cursor + meta.len()) as u32);
+                    cursor += meta.len();
+                    metadata.extend_from_slice(meta.as_slice());
+                    range
+                }
+                None => (0, 0),
+            };
+
+            header.extend_from_slice(&start.to_be_bytes());
+            header.extend_from_slice(&end.to_be_bytes());
+        }
+
+        [header, metadata].concat().into()
+    }
+}
+
+pub const STANDARD_HOOK_METADATA_VARIANT: u16 = 1;
+
+const STANDARD_HOOK_METADATA_LENGTH: usize = 2 + 32 + 32 + 20;
+
+/// Hook metadata in the standard layout:
+/// variant (2) | msg value (32) | gas limit (32) | refund address (20) | custom
+#[cw_serde]
+#[derive(Default)]
+pub struct StandardHookMetadata {
+    pub msg_value: Uint256,
+    /// zero falls back to the igp default
+    pub gas_limit: Uint256,
+    /// 20 bytes, or empty to refund the message sender. Written as zeros when empty
+    pub refund_address: HexBinary,
+    pub custom: HexBinary,
+}
+
+impl StandardHookMetadata {
+    /// `None` if `v` isn't in the standard layout
+    pub fn try_from_hex(v: &HexBinary) -> Option<Self> {
+        if v.len() < STANDARD_HOOK_METADATA_LENGTH
+            || v[..2] != STANDARD_HOOK_METADATA_VARIANT.to_be_bytes()
+        {
+            return None;
         }
 
-        [pos, metadata].concat().into()
+        let refund_address = &v[66..86];
+
+        Some(Self {
+            msg_value: Uint256::from_be_bytes(v[2..34].try_into().unwrap()),
+            gas_limit: Uint256::from_be_bytes(v[34..66].try_into().unwrap()),
+            refund_address: match refund_address.iter().all(|v| *v == 0) {
+                true => HexBinary::default(),
+                false => refund_address.to_vec().into(),
+            },
+            custom: v[86..].to_vec().into(),
+        })
+    }
+}
+
+impl From<StandardHookMetadata> for HexBinary {
+    fn from(v: StandardHookMetadata) -> Self {
+        // shorter addresses are left padded, longer ones keep their last 20 bytes
+        let raw_addr = unpad(&v.refund_address, 20);
+        let len = raw_addr.len().min(20);
+        let mut refund_address = [0u8; 20];
+        refund_address[20 - len..].copy_from_slice(&raw_addr[raw_addr.len() - len..]);
+
+        [
+            STANDARD_HOOK_METADATA_VARIANT.to_be_bytes().as_slice(),
+            v.msg_value.to_be_bytes().as_slice(),
+            v.gas_limit.to_be_bytes().as_slice(),
+            refund_address.as_slice(),
+            v.custom.as_slice(),
+        ]
+        .concat()
+        .into()
     }
 }
 
@@ -235,8 +480,17 @@ impl From<IGPMetadata> for HexBinary {
     }
 }
 
+/// Reads `gas_limit | refund_address` as well as the standard layout.
+/// A legacy gas limit would have to exceed 2^240 to be taken for the standard variant
 impl From<HexBinary> for IGPMetadata {
     fn from(v: HexBinary) -> Self {
+        if let Some(standard) = StandardHookMetadata::try_from_hex(&v) {
+            return Self {
+                gas_limit: standard.gas_limit,
+                refund_address: standard.refund_address,
+            };
+        }
+
         Self {
             gas_limit: Uint256::from_be_bytes(v[0..32].try_into().unwrap()),
             refund_address: v[32..].to_vec().into(),
@@ -245,10 +499,14 @@ impl From<HexBinary> for IGPMetadata {
 }
 
 impl IGPMetadata {
+    /// Refund address of the payment, `default` if it's unset or zero
     pub fn get_refund_address(&self, hrp: &str, default: Addr) -> Addr {
         if self.refund_address.to_vec().len() != 20 && self.refund_address.to_vec().len() != 32 {
             return default;
         }
+        if self.refund_address.iter().all(|&byte| byte == 0) {
+            return default;
+        }
 
         let raw_addr = match self
             .refund_address
@@ -267,25 +525,124 @@ impl IGPMetadata {
 
 #[cfg(test)]
 mod test {
-    use ibcx_test_utils::{addr, gen_bz, hex};
+    use ibcx_test_utils::{gen_bz, hex};
+    use rstest::rstest;
 
     use super::*;
 
     #[test]
     fn test_aggregate() {
-        let set = vec![
-            (addr("test1"), gen_bz(12)),
-            (addr("test2"), gen_bz(12)),
-            (addr("test3"), gen_bz(12)),
-        ];
-
-        let metadata = AggregateMetadata::new(set);
-        let isms = metadata.0.clone().into_keys().collect();
+        let metadata = AggregateMetadata::new(vec![Some(hex("aabbcc")), None, Some(gen_bz(70))]);
 
         let metadata_bz: HexBinary = metadata.clone().into();
+        // header of 3 ranges: 24..27, skipped, 27..97
+        assert_eq!(
+            &metadata_bz[..24],
+            hex(concat!(
+                "000000180000001b",
+                "0000000000000000",
+                "0000001b00000061"
+            ))
+            .as_slice()
+        );
+        assert_eq!(metadata_bz.len(), 97);
 
-        let new_metadata = AggregateMetadata::from_hex(metadata_bz, isms);
-        assert_eq!(metadata, new_metadata);
+        let new_metadata = AggregateMetadata::from_hex(metadata_bz, 3).unwrap();
+        assert_eq!(new_metadata, metadata);
+        assert_eq!(new_metadata.get(0), Some(&hex("aabbcc")));
+        assert_eq!(new_metadata.get(1), None);
+    }
+
+    #[test]
+    fn test_split_offsets() {
+        let metadata = hex("deadbeefcafe");
+
+        let sections = split_offsets(&metadata, &[(0, 2), (2, 4), (4, 6), (6, 6)]).unwrap();
+        assert_eq!(
+            sections,
+            vec![hex("dead"), hex("beef"), hex("cafe"), HexBinary::default()]
+        );
+
+        let err = split_offsets(&metadata, &[(0, 2), (4, 7)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: metadata truncated. range: 4..7, length: 6"
+        );
+
+        let err = split_offsets(&metadata, &[(3, 2)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: invalid metadata range. start: 3, end: 2"
+        );
+    }
+
+    #[test]
+    fn test_split_length_prefixed() {
+        let metadata = hex("00000002dead0000000000000003beefca");
+
+        let sections = split_length_prefixed(&metadata).unwrap();
+        assert_eq!(
+            sections,
+            vec![hex("dead"), HexBinary::default(), hex("beefca")]
+        );
+
+        assert!(split_length_prefixed(&HexBinary::default())
+            .unwrap()
+            .is_empty());
+
+        // truncated section body
+        let err = split_length_prefixed(&hex("00000004dead")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: metadata truncated. range: 4..8, length: 6"
+        );
+
+        // truncated length prefix
+        let err = split_length_prefixed(&hex("00000002dead0000")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: metadata truncated. range: 6..10, length: 8"
+        );
+
+        // max length prefix
+        let err = split_length_prefixed(&hex("ffffffffdead")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Generic error: metadata truncated. range: 4..{}, length: 6",
+                u32::MAX as usize + 4
+            )
+        );
+
+        // section end past usize::MAX
+        let err = checked_end(&hex("dead"), usize::MAX - 3, u32::MAX as usize).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Generic error: metadata truncated. range: {0}..{0}+{1}, length: 2",
+                usize::MAX - 3,
+                u32::MAX
+            )
+        );
+    }
+
+    #[rstest]
+    #[case::truncated_header("0000", "metadata truncated. range: 0..4, length: 2")]
+    #[case::truncated_metadata(
+        "0000001000000012000000120000001aaabb",
+        "metadata truncated. range: 18..26, length: 18"
+    )]
+    #[case::inverted(
+        "0000001000000011000000120000001100aabb",
+        "invalid metadata range. start: 18, end: 17"
+    )]
+    #[case::overlaps_header(
+        "0000000800000010000000100000001100",
+        "metadata range overlaps header. range: 8..16, header length: 16"
+    )]
+    fn test_aggregate_malformed(#[case] metadata: &str, #[case] expected: &str) {
+        let err = AggregateMetadata::from_hex(hex(metadata), 2).unwrap_err();
+        assert_eq!(err, StdError::generic_err(expected));
     }
 
     #[test]
@@ -304,4 +661,149 @@ mod test {
 
         assert_eq!(recovered, testdata);
     }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(64)]
+    // as long as 66 signatures without hints
+    #[case(65)]
+    fn test_message_id_multisig_metadata_indexed(#[case] signatures: usize) {
+        let metadata = MessageIdMultisigIsmMetadata {
+            origin_merkle_tree: gen_bz(32),
+            merkle_root: gen_bz(32),
+            merkle_index: gen_bz(4),
+            signatures: (0..signatures).map(|_| gen_bz(SIGNATURE_LENGTH)).collect(),
+            validator_indices: Some((0..signatures as u8).rev().collect()),
+        };
+
+        let raw: HexBinary = metadata.clone().into();
+        assert_eq!(raw.len(), 68 + signatures * INDEXED_SIGNATURE_LENGTH + 1);
+        assert_eq!(raw[68], signatures as u8 - 1);
+        assert_eq!(raw.last(), Some(&INDEXED_SIGNATURES_MARKER));
+
+        let recovered: MessageIdMultisigIsmMetadata = raw.into();
+        assert_eq!(recovered, metadata);
+
+        // the same signatures without hints
+        let plain = MessageIdMultisigIsmMetadata {
+            validator_indices: None,
+            ..metadata
+        };
+        let recovered: MessageIdMultisigIsmMetadata = HexBinary::from(plain.clone()).into();
+        assert_eq!(recovered, plain);
+    }
+
+    #[rstest]
+    // as long as 64 indexed signatures and the marker
+    #[case(65)]
+    // as long as 65 indexed signatures without the marker
+    #[case(66)]
+    fn test_message_id_multisig_metadata_ambiguous_length(#[case] signatures: usize) {
+        let metadata = MessageIdMultisigIsmMetadata {
+            origin_merkle_tree: gen_bz(32),
+            merkle_root: gen_bz(32),
+            merkle_index: gen_bz(4),
+            signatures: (0..signatures)
+                .map(|_| {
+                    let mut sig = gen_bz(SIGNATURE_LENGTH).to_vec();
+                    sig[SIGNATURE_LENGTH - 1] = 27;
+                    sig.into()
+                })
+                .collect(),
+            validator_indices: None,
+        };
+
+        let recovered: MessageIdMultisigIsmMetadata = HexBinary::from(metadata.clone()).into();
+        assert_eq!(recovered, metadata);
+    }
+
+    #[test]
+    fn test_legacy_multisig_metadata() {
+        let metadata = LegacyMultisigIsmMetadata {
+            merkle_root: gen_bz(32),
+            merkle_index: 7,
+            origin_mailbox: gen_bz(32),
+            proof: (0..32).map(|_| gen_bz(32)).collect(),
+            threshold: 2,
+            signatures: vec![gen_bz(SIGNATURE_LENGTH), gen_bz(SIGNATURE_LENGTH)],
+            validators: (0..3).map(|_| gen_bz(32)).collect(),
+        };
+
+        let metadata_bz: HexBinary = metadata.clone().into();
+        assert_eq!(metadata_bz.len(), 1093 + 2 * SIGNATURE_LENGTH + 3 * 32);
+
+        let recovered = LegacyMultisigIsmMetadata::try_from_hex(metadata_bz.clone()).unwrap();
+        assert_eq!(recovered, metadata);
+
+        // signatures cut short of the threshold
+        let truncated: HexBinary = metadata_bz[..1093 + SIGNATURE_LENGTH].to_vec().into();
+        assert!(LegacyMultisigIsmMetadata::try_from_hex(truncated).is_err());
+
+        // validator set not aligned to bytes32
+        let misaligned: HexBinary = metadata_bz[..metadata_bz.len() - 1].to_vec().into();
+        assert!(LegacyMultisigIsmMetadata::try_from_hex(misaligned).is_err());
+    }
+
+    #[test]
+    fn test_standard_hook_metadata() {
+        let metadata = StandardHookMetadata {
+            msg_value: Uint256::from_u128(10),
+            gas_limit: Uint256::from_u128(300_000),
+            refund_address: gen_bz(20),
+            custom: hex("beef"),
+        };
+
+        let metadata_bz: HexBinary = metadata.clone().into();
+        assert_eq!(metadata_bz.len(), STANDARD_HOOK_METADATA_LENGTH + 2);
+        assert_eq!(&metadata_bz[..2], &[0, 1]);
+        assert_eq!(
+            StandardHookMetadata::try_from_hex(&metadata_bz),
+            Some(metadata.clone())
+        );
+
+        // read by the igp
+        let igp_metadata = IGPMetadata::from(metadata_bz);
+        assert_eq!(igp_metadata.gas_limit, metadata.gas_limit);
+        assert_eq!(igp_metadata.refund_address, metadata.refund_address);
+
+        // empty refund address goes through zeros
+        let metadata_bz: HexBinary = StandardHookMetadata::default().into();
+        assert_eq!(&metadata_bz[66..], &[0; 20]);
+        let igp_metadata = IGPMetadata::from(metadata_bz);
+        assert_eq!(igp_metadata.refund_address, HexBinary::default());
+
+        // legacy layout is left alone
+        let legacy: HexBinary = IGPMetadata {
+            gas_limit: Uint256::from_u128(300_000),
+            refund_address: gen_bz(32),
+        }
+        .into();
+        assert_eq!(StandardHookMetadata::try_from_hex(&legacy), None);
+        assert_eq!(
+            IGPMetadata::from(legacy).gas_limit,
+            Uint256::from_u128(300_000)
+        );
+    }
+
+    #[rstest]
+    #[case::bytes20(hex("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"), true)]
+    #[case::bytes32(hex(&"ab".repeat(32)), true)]
+    #[case::unset(HexBinary::default(), false)]
+    #[case::zero20(vec![0; 20].into(), false)]
+    #[case::zero32(vec![0; 32].into(), false)]
+    #[case::invalid(gen_bz(21), false)]
+    fn test_igp_refund_address(#[case] refund_address: HexBinary, #[case] decoded: bool) {
+        let default = Addr::unchecked("default");
+        let metadata = IGPMetadata {
+            gas_limit: Uint256::from_u128(300_000),
+            refund_address: refund_address.clone(),
+        };
+
+        let expected = match decoded {
+            true => bech32_encode("osmo", &refund_address).unwrap(),
+            false => default.clone(),
+        };
+        assert_eq!(metadata.get_refund_address("osmo", default), expected);
+    }
 }
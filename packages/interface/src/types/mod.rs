@@ -1,11 +1,16 @@
 mod bech32;
 mod crypto;
+mod evm;
 mod merkle;
 mod message;
 mod metadata;
 
-pub use crate::types::bech32::{bech32_decode, bech32_encode, bech32_to_h256};
+pub use crate::types::bech32::{
+    bech32_decode, bech32_decode_with_hrp, bech32_encode, bech32_encode_with_len, bech32_to_h256,
+    pad32, unpad, CanonicalSender,
+};
 pub use crate::types::crypto::*;
-pub use crate::types::merkle::MerkleTree;
+pub use crate::types::evm::*;
+pub use crate::types::merkle::{MerkleTree, MAX_LEAVES};
 pub use crate::types::message::Message;
 pub use crate::types::metadata::*;
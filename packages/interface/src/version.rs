@@ -0,0 +1,48 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{CustomQuery, QuerierWrapper, StdResult};
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum VersionQueryMsg {
+    #[returns(ContractVersionResponse)]
+    ContractVersion {},
+}
+
+impl VersionQueryMsg {
+    pub fn wrap(self) -> ExpectedVersionQueryMsg {
+        ExpectedVersionQueryMsg::Version(self)
+    }
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[query_responses(nested)]
+pub enum ExpectedVersionQueryMsg {
+    Version(VersionQueryMsg),
+}
+
+#[cw_serde]
+pub struct ContractVersionResponse {
+    pub contract: String,
+    pub version: String,
+}
+
+pub fn contract_version<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    contract: impl Into<String>,
+) -> StdResult<ContractVersionResponse> {
+    querier.query_wasm_smart(contract, &VersionQueryMsg::ContractVersion {}.wrap())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{core, msg_checker};
+
+    #[test]
+    fn test_version_interface() {
+        let _checked: core::mailbox::QueryMsg =
+            msg_checker(VersionQueryMsg::ContractVersion {}.wrap());
+        let _checked: core::va::QueryMsg = msg_checker(VersionQueryMsg::ContractVersion {}.wrap());
+    }
+}
@@ -6,10 +6,16 @@ use crate::{
     core,
     ism::IsmSpecifierQueryMsg,
     ownable::{OwnableMsg, OwnableQueryMsg},
+    pausable::PausableMsg,
     router::{self, RouterQuery},
+    types::RemoteAddress,
+    version::VersionQueryMsg,
 };
 
-use super::{TokenModeMsg, TokenWarpDefaultQueryMsg};
+use super::{
+    payload::PayloadVersion, RecipientFormat, RemoteRouterConfig, RemoteRouterDecimals,
+    TokenModeMsg, TokenWarpDefaultQueryMsg,
+};
 
 pub use cw20_base::msg::InstantiateMsg as Cw20InitMsg;
 
@@ -28,6 +34,8 @@ pub enum TokenOption {
 pub struct Cw20ModeBridged {
     pub code_id: u64,
     pub init_msg: Box<cw20_base::msg::InstantiateMsg>,
+    /// instantiates the token at a predictable address with instantiate2
+    pub salt: Option<HexBinary>,
 }
 
 #[cw_serde]
@@ -44,20 +52,87 @@ pub struct InstantiateMsg {
     pub mailbox: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {
+    /// decimals of every remote router enrolled before decimal scaling
+    pub remote_decimals: Vec<RemoteRouterDecimals>,
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
-    Router(router::RouterMsg<HexBinary>),
+    Router(router::RouterMsg<RemoteRouterConfig>),
     Connection(ConnectionMsg),
 
+    // pause outbound transfers
+    Pausable(PausableMsg),
+
+    // pause inbound transfers
+    SetInboundPaused {
+        paused: bool,
+    },
+
     // handle transfer remote
     Handle(core::HandleMsg),
 
+    // enroll the remote router of domain from an address in any format,
+    // stored as 32 bytes like `Router` routes
+    EnrollRemoteRouter {
+        domain: u32,
+        address: RemoteAddress,
+        decimals: u8,
+    },
+
+    // set recipient validation for destination domain
+    SetRecipientFormat {
+        domain: u32,
+        format: RecipientFormat,
+    },
+
+    // require gas payment funds when transferring to destination domain
+    SetGasPaymentRequired {
+        domain: u32,
+        required: bool,
+    },
+
+    // payload version of outbound transfers, inbound transfers accept every version
+    SetPayloadVersion {
+        version: PayloadVersion,
+    },
+
+    // bech32 prefix of a cosmos domain, only used to decode its router for tooling.
+    // `None` removes it
+    SetDomainHrp {
+        domain: u32,
+        hrp: Option<String>,
+    },
+
+    // igp gas limit for transfers to destination domain, `None` falls back to the igp default
+    SetDefaultGas {
+        domain: u32,
+        gas: Option<u64>,
+    },
+
     // transfer to remote
+    // fields also accept the camelCase names the typescript sdk writes
     TransferRemote {
+        #[serde(alias = "destDomain")]
         dest_domain: u32,
         recipient: HexBinary,
         amount: Uint128,
+        /// the transfer is sent back to the sender if the recipient would receive less
+        #[serde(alias = "minAmount")]
+        min_amount: Option<Uint128>,
+    },
+
+    // burn from sender allowance and transfer to remote, bridged mode only
+    TransferRemoteBurn {
+        dest_domain: u32,
+        recipient: HexBinary,
+        amount: Uint128,
+        /// the transfer is sent back to the sender if the recipient would receive less
+        min_amount: Option<Uint128>,
     },
 }
 
@@ -67,11 +142,42 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Ownable(OwnableQueryMsg),
 
-    Router(RouterQuery<HexBinary>),
+    Router(RouterQuery<RemoteRouterConfig>),
 
     Connection(ConnectionQueryMsg),
 
     TokenDefault(TokenWarpDefaultQueryMsg),
 
     IsmSpecifier(IsmSpecifierQueryMsg),
+
+    TokenCw20(TokenCw20QueryMsg),
+
+    Version(VersionQueryMsg),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum TokenCw20QueryMsg {
+    /// Address the token code would be instantiated at by this contract with the salt
+    #[returns(ExpectedTokenAddressResponse)]
+    ExpectedTokenAddress { salt: HexBinary },
+
+    /// Mint cap of the token and how much of it is left, bridged mode only
+    #[returns(MintCapacityResponse)]
+    MintCapacity {},
+}
+
+#[cw_serde]
+pub struct ExpectedTokenAddressResponse {
+    pub address: String,
+}
+
+#[cw_serde]
+pub struct MintCapacityResponse {
+    /// `None` if the token is uncapped
+    pub cap: Option<Uint128>,
+    /// Total supply of the token, which the cap applies to
+    pub minted: Uint128,
+    /// `None` if the token is uncapped
+    pub remaining: Option<Uint128>,
 }
@@ -1,10 +1,26 @@
 pub mod cw20;
 pub mod native;
+pub mod payload;
 
 use std::fmt;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{HexBinary, Uint256};
+use cosmwasm_std::{ensure, Addr, Binary, Coin, HexBinary, StdError, StdResult, Uint128, Uint256};
+
+use crate::{
+    core::mailbox::DispatchMsg,
+    hook::QuoteDispatchResponse,
+    ism::InterchainSecurityModuleResponse,
+    router::{RouteResponse, RoutesResponse},
+    types::{bech32_encode, pad_to_h256, to_evm_checksum, HexOrBinary, IGPMetadata},
+};
+
+/// Set as the `instantiate` response data of every warp route
+#[cw_serde]
+pub struct InstantiateResponse {
+    pub mailbox: Addr,
+    pub owner: Addr,
+}
 
 #[cw_serde]
 pub enum TokenTypeNative {
@@ -72,6 +88,117 @@ impl From<Message> for HexBinary {
     }
 }
 
+/// Tags the IBC forward extension of a v1 payload, a json encoded `IbcForward`
+pub const IBC_FORWARD_EXTENSION_V1: u8 = 0x01;
+
+/// Tags the collateral denom extension of a v1 payload, sent by multi denom native routes.
+/// Transfers of the route's primary denom leave it out.
+pub const DENOM_EXTENSION_V1: u8 = 0x02;
+
+/// Tags the call extension of a v1 payload, handed to the recipient as `HandleCall`
+pub const CALL_EXTENSION_V1: u8 = 0x03;
+
+/// Forwards the received tokens over IBC instead of releasing them to the recipient.
+/// Transfers that fail or time out are released to the recipient once the ibc-hooks
+/// module reports them
+#[cw_serde]
+pub struct IbcForward {
+    pub ibc_channel: String,
+    pub final_recipient: String,
+    pub timeout_seconds: u64,
+}
+
+/// Executed on the recipient of a transfer-and-call once the tokens are transferred.
+/// Calls only reach this entry point, never an arbitrary message of the recipient
+#[cw_serde]
+pub struct HandleCallMsg {
+    pub origin: u32,
+    /// origin router of the transfer
+    pub sender: HexBinary,
+    /// received amount, in local decimals
+    pub amount: Uint256,
+    pub call: Binary,
+}
+
+impl HandleCallMsg {
+    pub fn wrap(self) -> ExpectedCallRecipientMsg {
+        ExpectedCallRecipientMsg::HandleCall(self)
+    }
+}
+
+#[cw_serde]
+pub enum ExpectedCallRecipientMsg {
+    HandleCall(HandleCallMsg),
+}
+
+/// Leading bytes of the metadata written by these routes, followed by its version.
+/// Metadata without them, e.g. from an EVM router, is left uninterpreted.
+pub const METADATA_PREFIX: [u8; 4] = *b"hplw";
+
+pub const METADATA_V1: u8 = 0x01;
+
+const METADATA_HEADER_LEN: usize = METADATA_PREFIX.len() + 1 + 32 + 32;
+
+/// Metadata layout: `METADATA_PREFIX || METADATA_V1 || min_amount (32 bytes)
+/// || refund recipient (32 bytes)`. Calls, IBC forwards and denoms are payload extensions.
+/// A zero min amount leaves the received amount unchecked, a zero refund recipient
+/// leaves transfers below the min amount undelivered.
+impl Message {
+    /// Encodes the minimum amount the recipient must receive
+    pub fn with_min_amount(self, min_amount: Uint256) -> Self {
+        let (_, refund_recipient) = self.fields();
+
+        self.with_fields(min_amount.to_be_bytes(), refund_recipient)
+    }
+
+    /// Encodes the origin address receiving the transfer back if it's below the min amount
+    pub fn with_refund_recipient(self, refund_recipient: [u8; 32]) -> Self {
+        let (min_amount, _) = self.fields();
+
+        self.with_fields(min_amount, refund_recipient)
+    }
+
+    pub fn min_amount(&self) -> Option<Uint256> {
+        self.header()
+            .map(|v| Uint256::from_be_bytes(v[5..37].try_into().unwrap()))
+    }
+
+    pub fn refund_recipient(&self) -> Option<HexBinary> {
+        self.header()
+            .map(|v| &v[37..69])
+            .filter(|v| v.iter().any(|b| *b != 0))
+            .map(|v| v.to_vec().into())
+    }
+
+    fn header(&self) -> Option<&[u8]> {
+        self.metadata
+            .get(..METADATA_HEADER_LEN)
+            .filter(|v| v[..4] == METADATA_PREFIX && v[4] == METADATA_V1)
+    }
+
+    fn fields(&self) -> ([u8; 32], [u8; 32]) {
+        match self.header() {
+            Some(header) => (
+                header[5..37].try_into().unwrap(),
+                header[37..69].try_into().unwrap(),
+            ),
+            None => ([0u8; 32], [0u8; 32]),
+        }
+    }
+
+    fn with_fields(mut self, min_amount: [u8; 32], refund_recipient: [u8; 32]) -> Self {
+        self.metadata = [
+            METADATA_PREFIX.as_slice(),
+            &[METADATA_V1],
+            &min_amount,
+            &refund_recipient,
+        ]
+        .concat()
+        .into();
+        self
+    }
+}
+
 impl From<HexBinary> for Message {
     fn from(v: HexBinary) -> Self {
         Self {
@@ -82,6 +209,120 @@ impl From<HexBinary> for Message {
     }
 }
 
+/// Expected layout of recipient bytes on a destination domain
+#[cw_serde]
+#[derive(Default, Eq)]
+pub enum RecipientFormat {
+    /// 20 byte address left-padded with 12 zero bytes
+    Evm20,
+    /// non-zero 32 byte address
+    Cosmos32,
+    /// any 32 bytes
+    Raw32,
+    /// no validation
+    #[default]
+    None,
+}
+
+impl RecipientFormat {
+    pub fn is_valid(&self, recipient: &[u8]) -> bool {
+        let is_zero = |v: &[u8]| v.iter().all(|b| *b == 0);
+
+        match self {
+            Self::Evm20 => {
+                recipient.len() == 32 && is_zero(&recipient[..12]) && !is_zero(&recipient[12..])
+            }
+            Self::Cosmos32 => recipient.len() == 32 && !is_zero(recipient),
+            Self::Raw32 => recipient.len() == 32,
+            Self::None => true,
+        }
+    }
+}
+
+impl fmt::Display for RecipientFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Evm20 => "evm20",
+                Self::Cosmos32 => "cosmos32",
+                Self::Raw32 => "raw32",
+                Self::None => "none",
+            }
+        )
+    }
+}
+
+/// Enrolled remote warp router with the decimals of its token
+#[cw_serde]
+#[derive(Eq)]
+pub struct RemoteRouterConfig {
+    /// hex (0x-prefixed evm addresses included) or base64
+    pub address: HexOrBinary,
+    pub decimals: u8,
+}
+
+impl RemoteRouterConfig {
+    /// Config of a router enrolled as a bare address before decimal scaling.
+    /// Its decimals must be given, as assuming the local ones would skip scaling silently.
+    pub fn from_legacy(
+        remote_decimals: &[RemoteRouterDecimals],
+        domain: u32,
+        address: HexBinary,
+    ) -> StdResult<Self> {
+        let decimals = remote_decimals
+            .iter()
+            .find(|v| v.domain == domain)
+            .map(|v| v.decimals)
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "decimals of the remote router on domain {domain} are required to migrate"
+                ))
+            })?;
+
+        Ok(Self {
+            address: address.into(),
+            decimals,
+        })
+    }
+
+    /// Scales a local amount to the remote decimals.
+    /// Fails if the amount can't be represented on the remote side without losing value.
+    pub fn to_remote_amount(&self, local_decimals: u8, amount: Uint256) -> StdResult<Uint256> {
+        let remote_amount = scale_amount(amount, local_decimals, self.decimals)?;
+
+        ensure!(
+            scale_amount(remote_amount, self.decimals, local_decimals)? == amount,
+            StdError::generic_err(format!(
+                "amount {amount} loses precision on remote. local decimals: {local_decimals}, remote decimals: {}",
+                self.decimals
+            ))
+        );
+
+        Ok(remote_amount)
+    }
+}
+
+/// Decimals of a remote router enrolled before decimal scaling
+#[cw_serde]
+pub struct RemoteRouterDecimals {
+    pub domain: u32,
+    pub decimals: u8,
+}
+
+pub fn scale_amount(amount: Uint256, from_decimals: u8, to_decimals: u8) -> StdResult<Uint256> {
+    let ten = Uint256::from(10u8);
+
+    if to_decimals >= from_decimals {
+        let factor = ten.checked_pow((to_decimals - from_decimals) as u32)?;
+        Ok(amount.checked_mul(factor)?)
+    } else {
+        let factor = ten.checked_pow((from_decimals - to_decimals) as u32)?;
+        Ok(amount.checked_div(factor)?)
+    }
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum TokenWarpDefaultQueryMsg {
@@ -90,6 +331,42 @@ pub enum TokenWarpDefaultQueryMsg {
 
     #[returns(TokenModeResponse)]
     TokenMode {},
+
+    #[returns(RecipientFormatResponse)]
+    RecipientFormat { domain: u32 },
+
+    #[returns(GasPaymentRequiredResponse)]
+    GasPaymentRequired { domain: u32 },
+
+    #[returns(PauseStateResponse)]
+    PauseState {},
+
+    #[returns(PayloadVersionResponse)]
+    PayloadVersion {},
+
+    /// Mailbox the route is bound to, with the local domain from the mailbox config
+    #[returns(MailboxInfoResponse)]
+    MailboxInfo {},
+
+    /// ISM set on the route, `None` if the mailbox default applies
+    #[returns(InterchainSecurityModuleResponse)]
+    Ism {},
+
+    /// Enrolled router of `domain` in readable forms
+    #[returns(RouteResponse<DecodedRoute>)]
+    RouterDecoded { domain: u32 },
+
+    /// Every enrolled router in readable forms
+    #[returns(RoutesResponse<DecodedRoute>)]
+    RoutersDecoded {},
+
+    /// Quotes the hook fees of a `TransferRemote` of `amount` in the primary denom
+    #[returns(QuoteTransferRemoteResponse)]
+    QuoteTransferRemote {
+        dest_domain: u32,
+        amount: Uint128,
+        recipient: HexBinary,
+    },
 }
 
 #[cw_serde]
@@ -102,3 +379,354 @@ pub struct TokenTypeResponse {
 pub struct TokenModeResponse {
     pub mode: TokenMode,
 }
+
+#[cw_serde]
+pub struct RecipientFormatResponse {
+    pub format: RecipientFormat,
+}
+
+#[cw_serde]
+pub struct GasPaymentRequiredResponse {
+    pub required: bool,
+}
+
+/// Outbound and inbound transfers are paused independently of each other
+#[cw_serde]
+pub struct PauseStateResponse {
+    /// `TransferRemote` is rejected
+    pub outbound: bool,
+    /// `Handle` is rejected, so deliveries stay on the mailbox until released
+    pub inbound: bool,
+}
+
+#[cw_serde]
+pub struct PayloadVersionResponse {
+    /// version of outbound payloads, inbound payloads of every version are accepted
+    pub version: payload::PayloadVersion,
+}
+
+/// Remote router address for tooling. Nothing here is used by the route itself
+#[cw_serde]
+pub struct DecodedRoute {
+    /// left-padded to 32 bytes, as it is encoded in messages
+    pub address: HexBinary,
+    /// set if an hrp is registered for the domain
+    pub bech32: Option<String>,
+    /// EIP-55 checksummed, set if the address has 12 leading zero bytes
+    pub evm: Option<String>,
+}
+
+impl DecodedRoute {
+    pub fn new(address: &HexBinary, hrp: Option<&str>) -> StdResult<Self> {
+        let address = pad_to_h256(address);
+
+        let is_zero = |v: &[u8]| v.iter().all(|b| *b == 0);
+        let evm = (is_zero(&address[..12]) && !is_zero(&address[12..]))
+            .then(|| to_evm_checksum(&address));
+
+        let bech32 = hrp
+            .map(|hrp| bech32_encode(hrp, &address))
+            .transpose()?
+            .map(String::from);
+
+        Ok(Self {
+            address,
+            bech32,
+            evm,
+        })
+    }
+}
+
+#[cw_serde]
+pub struct MailboxInfoResponse {
+    pub mailbox: Addr,
+    pub local_domain: u32,
+}
+
+#[cw_serde]
+pub struct QuoteTransferRemoteResponse {
+    /// gas limit passed to the igp in hook metadata, `None` if the igp's default applies
+    pub gas_amount: Option<Uint256>,
+    pub fees: Vec<Coin>,
+}
+
+impl QuoteTransferRemoteResponse {
+    pub fn new(msg: &DispatchMsg, quote: QuoteDispatchResponse) -> Self {
+        let gas_amount = msg
+            .metadata
+            .clone()
+            .filter(|v| v.len() >= 32)
+            .map(|v| IGPMetadata::from(v).gas_limit);
+
+        Self {
+            gas_amount,
+            fees: quote.fees,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{coin, from_json, to_json_string, HexBinary, Uint128, Uint256};
+    use rstest::rstest;
+
+    use ibcx_test_utils::gen_bz;
+
+    use crate::{
+        core::mailbox::DispatchMsg,
+        hook::QuoteDispatchResponse,
+        testing::padded,
+        types::{HexOrBinary, IGPMetadata},
+        warp::{cw20, native},
+    };
+
+    use super::{
+        scale_amount, DecodedRoute, Message, QuoteTransferRemoteResponse, RecipientFormat,
+        RemoteRouterConfig,
+    };
+
+    #[rstest]
+    #[case(100, 6, 6, 100)]
+    #[case(100, 6, 18, 100_000_000_000_000)]
+    #[case(100_000_000_000_000, 18, 6, 100)]
+    #[case(123_456, 6, 3, 123)]
+    #[case(0, 0, 77, 0)]
+    fn test_scale_amount(
+        #[case] amount: u128,
+        #[case] from: u8,
+        #[case] to: u8,
+        #[case] expected: u128,
+    ) {
+        assert_eq!(
+            scale_amount(amount.into(), from, to).unwrap(),
+            Uint256::from(expected)
+        );
+    }
+
+    #[test]
+    fn test_min_amount() {
+        let msg = Message {
+            recipient: gen_bz(32),
+            amount: Uint256::from(100u128),
+            metadata: HexBinary::default(),
+        };
+        assert_eq!(msg.min_amount(), None);
+
+        let msg = msg.with_min_amount(Uint256::from(90u128));
+        assert_eq!(msg.metadata.len(), 69);
+        assert_eq!(msg.refund_recipient(), None);
+
+        let decoded: Message = HexBinary::from(msg).into();
+        assert_eq!(decoded.min_amount(), Some(Uint256::from(90u128)));
+
+        let msg = decoded.with_refund_recipient([1; 32]);
+        assert_eq!(msg.min_amount(), Some(Uint256::from(90u128)));
+        assert_eq!(msg.refund_recipient(), Some(vec![1; 32].into()));
+    }
+
+    #[rstest]
+    #[case(gen_bz(31))]
+    #[case(gen_bz(32))]
+    #[case(gen_bz(100))]
+    #[case([b"hplw".to_vec(), vec![0x02], gen_bz(64).to_vec()].concat().into())]
+    fn test_foreign_metadata(#[case] metadata: HexBinary) {
+        // metadata of other routers, e.g. evm ones, is never read as these fields
+        let msg = Message {
+            recipient: gen_bz(32),
+            amount: Uint256::from(100u128),
+            metadata,
+        };
+
+        assert_eq!(msg.min_amount(), None);
+        assert_eq!(msg.refund_recipient(), None);
+    }
+
+    #[test]
+    fn test_scale_amount_overflow() {
+        assert!(scale_amount(Uint256::MAX, 0, 1).is_err());
+        assert!(scale_amount(Uint256::one(), 0, 78).is_err());
+    }
+
+    #[rstest]
+    #[case(6, 18, 100, Some(100_000_000_000_000))]
+    #[case(18, 6, 100_000_000_000_000, Some(100))]
+    #[case(18, 6, 100_000_000_000_001, None)]
+    fn test_remote_router_config(
+        #[case] local_decimals: u8,
+        #[case] remote_decimals: u8,
+        #[case] amount: u128,
+        #[case] expected: Option<u128>,
+    ) {
+        let router = RemoteRouterConfig {
+            address: HexOrBinary::default(),
+            decimals: remote_decimals,
+        };
+
+        let res = router.to_remote_amount(local_decimals, amount.into());
+        match expected {
+            Some(expected) => {
+                let remote_amount = res.unwrap();
+                assert_eq!(remote_amount, Uint256::from(expected));
+                assert_eq!(
+                    scale_amount(remote_amount, remote_decimals, local_decimals).unwrap(),
+                    Uint256::from(amount)
+                );
+            }
+            None => assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains("loses precision on remote")),
+        }
+    }
+
+    #[rstest]
+    #[case::evm("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")]
+    #[case::hex("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed")]
+    #[case::base64("Wq62BT8+lMm5oJ8zZpQ15+8b6u0=")]
+    fn test_remote_router_config_serde(#[case] address: &str) {
+        let router: RemoteRouterConfig =
+            from_json(format!(r#"{{"address":"{address}","decimals":6}}"#)).unwrap();
+
+        assert_eq!(
+            router.address.to_hex(),
+            "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+        );
+        assert_eq!(router.decimals, 6);
+    }
+
+    #[rstest]
+    #[case(RecipientFormat::Evm20, padded(12, &[1u8; 20]).to_vec(), true)]
+    #[case(RecipientFormat::Evm20, padded(11, &[1u8; 21]).to_vec(), false)]
+    #[case(RecipientFormat::Evm20, padded(32, &[]).to_vec(), false)]
+    #[case(RecipientFormat::Evm20, vec![1u8; 20], false)]
+    #[case(RecipientFormat::Cosmos32, gen_bz(32).to_vec(), true)]
+    #[case(RecipientFormat::Cosmos32, padded(12, &[1u8; 20]).to_vec(), true)]
+    #[case(RecipientFormat::Cosmos32, padded(32, &[]).to_vec(), false)]
+    #[case(RecipientFormat::Cosmos32, gen_bz(20).to_vec(), false)]
+    #[case(RecipientFormat::Raw32, padded(32, &[]).to_vec(), true)]
+    #[case(RecipientFormat::Raw32, gen_bz(33).to_vec(), false)]
+    #[case(RecipientFormat::None, vec![], true)]
+    #[case(RecipientFormat::None, gen_bz(33).to_vec(), true)]
+    fn test_recipient_format(
+        #[case] format: RecipientFormat,
+        #[case] recipient: Vec<u8>,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(format.is_valid(&recipient), expected);
+    }
+
+    const EVM: &str = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+
+    #[rstest]
+    #[case::evm(EVM, None, None, Some("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"))]
+    #[case::evm_with_hrp(
+        EVM,
+        Some("osmo"),
+        Some("osmo1t2htvpfl862vnwdqnuekd9p4ulh3h6hdlvdhh0"),
+        Some("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+    )]
+    #[case::cosmos(
+        "dbf0c3e2b3f0e4d5e4c6e4b0b1f8d0a1c3e9f2a4b6c8d0e2f4a6b8c0d2e4f6a8",
+        Some("neutron"),
+        Some("neutron1m0cv8c4n7rjdtexxujctr7xs58p7nu4ykmydpch556uvp5hy765q2mhy4k"),
+        None
+    )]
+    #[case::unknown(
+        "dbf0c3e2b3f0e4d5e4c6e4b0b1f8d0a1c3e9f2a4b6c8d0e2f4a6b8c0d2e4f6a8",
+        None,
+        None,
+        None
+    )]
+    fn test_decoded_route(
+        #[case] address: &str,
+        #[case] hrp: Option<&str>,
+        #[case] bech32: Option<&str>,
+        #[case] evm: Option<&str>,
+    ) {
+        let address = HexBinary::from_hex(address).unwrap();
+        let decoded = DecodedRoute::new(&address, hrp).unwrap();
+
+        assert_eq!(decoded.address.len(), 32);
+        assert!(decoded.address.ends_with(&address));
+        assert_eq!(decoded.bech32.as_deref(), bech32);
+        assert_eq!(decoded.evm.as_deref(), evm);
+    }
+
+    #[rstest]
+    #[case(None, None)]
+    #[case(Some(HexBinary::default()), None)]
+    #[case(
+        Some(
+            IGPMetadata {
+                gas_limit: Uint256::from_u128(300_000),
+                refund_address: gen_bz(32),
+            }
+            .into()
+        ),
+        Some(Uint256::from_u128(300_000))
+    )]
+    fn test_quote_transfer_remote_response(
+        #[case] metadata: Option<HexBinary>,
+        #[case] gas_amount: Option<Uint256>,
+    ) {
+        let mut msg = DispatchMsg::new(1, gen_bz(32), gen_bz(32));
+        msg.metadata = metadata;
+
+        let quote = QuoteDispatchResponse {
+            fees: vec![coin(10, "ugas")],
+        };
+
+        assert_eq!(
+            QuoteTransferRemoteResponse::new(&msg, quote),
+            QuoteTransferRemoteResponse {
+                gas_amount,
+                fees: vec![coin(10, "ugas")],
+            }
+        );
+    }
+    // as written by the typescript sdk
+    const SDK_TRANSFER_REMOTE_MSG: &str = r#"{
+        "transfer_remote": {
+            "destDomain": 11155111,
+            "recipient": "0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            "amount": "1000000",
+            "minAmount": "990000"
+        }
+    }"#;
+
+    #[test]
+    fn test_transfer_remote_camel_case() {
+        let recipient =
+            HexBinary::from_hex("0000000000000000000000005aaeb6053f3e94c9b9a09f33669435e7ef1beaed")
+                .unwrap();
+
+        let msg: cw20::ExecuteMsg = from_json(SDK_TRANSFER_REMOTE_MSG).unwrap();
+        assert_eq!(
+            msg,
+            cw20::ExecuteMsg::TransferRemote {
+                dest_domain: 11155111,
+                recipient: recipient.clone(),
+                amount: Uint128::new(1_000_000),
+                min_amount: Some(Uint128::new(990_000)),
+            }
+        );
+
+        // written back in snake_case
+        let written = to_json_string(&msg).unwrap();
+        assert!(written.contains("\"min_amount\":\"990000\""));
+        assert!(!written.contains("minAmount"));
+        assert_eq!(from_json::<cw20::ExecuteMsg>(&written).unwrap(), msg);
+
+        let msg: native::ExecuteMsg = from_json(SDK_TRANSFER_REMOTE_MSG).unwrap();
+        assert_eq!(
+            msg,
+            native::ExecuteMsg::TransferRemote {
+                dest_domain: 11155111,
+                recipient,
+                amount: Uint128::new(1_000_000),
+                min_amount: Some(Uint128::new(990_000)),
+                denom: None,
+            }
+        );
+    }
+}
@@ -1,15 +1,21 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{HexBinary, Uint128};
+use cosmwasm_std::{Coin, HexBinary, Uint128};
 
 use crate::{
     connection::{ConnectionMsg, ConnectionQueryMsg},
     core,
     ism::IsmSpecifierQueryMsg,
     ownable::{OwnableMsg, OwnableQueryMsg},
+    pausable::PausableMsg,
     router::{RouterMsg, RouterQuery},
+    types::RemoteAddress,
+    version::VersionQueryMsg,
 };
 
-use super::{TokenModeMsg, TokenWarpDefaultQueryMsg};
+use super::{
+    payload::PayloadVersion, RecipientFormat, RemoteRouterConfig, RemoteRouterDecimals,
+    TokenModeMsg, TokenWarpDefaultQueryMsg,
+};
 
 #[cw_serde]
 pub struct DenomUnit {
@@ -46,26 +52,126 @@ pub struct NativeModeCollateral {
 #[cw_serde]
 pub struct InstantiateMsg {
     pub token: TokenModeMsg<NativeModeBriged, NativeModeCollateral>,
+    pub decimals: u8,
 
     pub hrp: String,
     pub owner: String,
     pub mailbox: String,
 }
 
+#[cw_serde]
+#[derive(Default)]
+pub struct MigrateMsg {
+    /// decimals of the token, required to migrate routes deployed before decimal scaling
+    pub decimals: Option<u8>,
+    /// decimals of every remote router enrolled before decimal scaling
+    pub remote_decimals: Vec<RemoteRouterDecimals>,
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     Ownable(OwnableMsg),
-    Router(RouterMsg<HexBinary>),
+    Router(RouterMsg<RemoteRouterConfig>),
     Connection(ConnectionMsg),
 
+    // pause outbound transfers
+    Pausable(PausableMsg),
+
+    // pause inbound transfers
+    SetInboundPaused {
+        paused: bool,
+    },
+
     // handle transfer remote
     Handle(core::HandleMsg),
 
+    // enroll the remote router of domain from an address in any format,
+    // stored as 32 bytes like `Router` routes
+    EnrollRemoteRouter {
+        domain: u32,
+        address: RemoteAddress,
+        decimals: u8,
+    },
+
+    // set recipient validation for destination domain
+    SetRecipientFormat {
+        domain: u32,
+        format: RecipientFormat,
+    },
+
+    // require gas payment funds when transferring to destination domain
+    SetGasPaymentRequired {
+        domain: u32,
+        required: bool,
+    },
+
+    // payload version of outbound transfers, inbound transfers accept every version
+    SetPayloadVersion {
+        version: PayloadVersion,
+    },
+
+    // bech32 prefix of a cosmos domain, only used to decode its router for tooling.
+    // `None` removes it
+    SetDomainHrp {
+        domain: u32,
+        hrp: Option<String>,
+    },
+
+    // igp gas limit for transfers to destination domain, `None` falls back to the igp default
+    SetDefaultGas {
+        domain: u32,
+        gas: Option<u64>,
+    },
+
+    // allow received transfers to be forwarded over ibc
+    SetIbcForwardEnabled {
+        enabled: bool,
+    },
+
+    // allow or disallow ibc channel as a forward route
+    SetIbcForwardChannel {
+        channel: String,
+        allowed: bool,
+    },
+
+    // add or remove a collateral denom bridged alongside the primary denom
+    SetSupportedDenom {
+        denom: String,
+        supported: bool,
+    },
+
     // transfer to remote
+    // fields also accept the camelCase names the typescript sdk writes
     TransferRemote {
+        #[serde(alias = "destDomain")]
         dest_domain: u32,
         recipient: HexBinary,
         amount: Uint128,
+        /// the transfer is sent back to the sender if the recipient would receive less
+        #[serde(alias = "minAmount")]
+        min_amount: Option<Uint128>,
+        /// defaults to the primary denom
+        denom: Option<String>,
+    },
+}
+
+/// Callbacks of the ibc-hooks module for transfers forwarded over ibc
+#[cw_serde]
+pub enum SudoMsg {
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+#[cw_serde]
+pub enum IbcLifecycleComplete {
+    IbcAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    IbcTimeout {
+        channel: String,
+        sequence: u64,
     },
 }
 
@@ -75,13 +181,46 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     Ownable(OwnableQueryMsg),
 
-    Router(RouterQuery<HexBinary>),
+    Router(RouterQuery<RemoteRouterConfig>),
 
     Connection(ConnectionQueryMsg),
 
     TokenDefault(TokenWarpDefaultQueryMsg),
 
     IsmSpecifier(IsmSpecifierQueryMsg),
+
+    IbcForward(IbcForwardQueryMsg),
+
+    Denoms(DenomsQueryMsg),
+
+    Version(VersionQueryMsg),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum IbcForwardQueryMsg {
+    #[returns(IbcForwardConfigResponse)]
+    IbcForwardConfig {},
+}
+
+#[cw_serde]
+pub struct IbcForwardConfigResponse {
+    pub enabled: bool,
+    pub channels: Vec<String>,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum DenomsQueryMsg {
+    #[returns(SupportedDenomsResponse)]
+    SupportedDenoms {},
+}
+
+#[cw_serde]
+pub struct SupportedDenomsResponse {
+    pub denoms: Vec<String>,
+    /// collateral held per denom, empty for bridged routes
+    pub escrow: Vec<Coin>,
 }
 
 mod as_str {
@@ -0,0 +1,551 @@
+use std::fmt;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, to_json_vec, Binary, HexBinary, StdError, StdResult, Uint256};
+use thiserror::Error;
+
+use super::{IbcForward, Message, CALL_EXTENSION_V1, DENOM_EXTENSION_V1, IBC_FORWARD_EXTENSION_V1};
+
+/// Leading byte of a v1 payload
+pub const PAYLOAD_V1: u8 = 0x01;
+
+const HEADER_LEN: usize = 64;
+
+/// Layout of the warp message body
+#[cw_serde]
+#[derive(Copy, Default, Eq, PartialOrd, Ord)]
+pub enum PayloadVersion {
+    /// `recipient (32) || amount (32) || metadata`, without a version byte
+    #[default]
+    V0,
+    /// `PAYLOAD_V1 || recipient (32) || amount (32) || metadata length (u32) || metadata
+    /// || memo length (u32) || memo || extensions`
+    V1,
+}
+
+impl PayloadVersion {
+    pub const LATEST: Self = Self::V1;
+}
+
+impl fmt::Display for PayloadVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::V0 => "v0",
+                Self::V1 => "v1",
+            }
+        )
+    }
+}
+
+/// Tagged value of a v1 payload, encoded as `tag (1) || value length (u16) || value`.
+/// Tags unknown to the receiver are decoded but left unused.
+#[cw_serde]
+pub struct PayloadExtension {
+    pub tag: u8,
+    pub value: HexBinary,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PayloadError {
+    #[error("payload too short. length: {len}")]
+    TooShort { len: usize },
+
+    #[error("unsupported payload version {version}")]
+    UnsupportedVersion { version: u8 },
+
+    #[error("payload truncated in {field}")]
+    Truncated { field: &'static str },
+
+    #[error("payload {field} too long. length: {len}")]
+    TooLong { field: &'static str, len: usize },
+
+    #[error("{version} payloads can't carry extensions")]
+    ExtensionsUnsupported { version: PayloadVersion },
+}
+
+impl From<PayloadError> for StdError {
+    fn from(v: PayloadError) -> Self {
+        StdError::generic_err(v.to_string())
+    }
+}
+
+/// Warp message body of any version.
+///
+/// v0 has no version byte, so a body is only read as v1 if it starts with `PAYLOAD_V1`
+/// and its length matches the v1 framing exactly. Any other body of at least 64 bytes is v0.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Payload {
+    V0(Message),
+    V1 {
+        message: Message,
+        memo: HexBinary,
+        extensions: Vec<PayloadExtension>,
+    },
+}
+
+impl Payload {
+    /// Wraps the message in the given version, without memo or extensions
+    pub fn new(version: PayloadVersion, message: Message) -> Self {
+        match version {
+            PayloadVersion::V0 => Self::V0(message),
+            PayloadVersion::V1 => Self::V1 {
+                message,
+                memo: HexBinary::default(),
+                extensions: vec![],
+            },
+        }
+    }
+
+    pub fn version(&self) -> PayloadVersion {
+        match self {
+            Self::V0(_) => PayloadVersion::V0,
+            Self::V1 { .. } => PayloadVersion::V1,
+        }
+    }
+
+    pub fn message(&self) -> &Message {
+        match self {
+            Self::V0(message) | Self::V1 { message, .. } => message,
+        }
+    }
+
+    pub fn into_message(self) -> Message {
+        match self {
+            Self::V0(message) | Self::V1 { message, .. } => message,
+        }
+    }
+
+    pub fn extensions(&self) -> &[PayloadExtension] {
+        match self {
+            Self::V0(_) => &[],
+            Self::V1 { extensions, .. } => extensions,
+        }
+    }
+
+    /// Value of the first extension tagged `tag`
+    pub fn extension(&self, tag: u8) -> Option<&HexBinary> {
+        self.extensions()
+            .iter()
+            .find(|v| v.tag == tag)
+            .map(|v| &v.value)
+    }
+
+    /// Appends an extension. Only v1 payloads carry them
+    pub fn with_extension(
+        self,
+        tag: u8,
+        value: impl Into<HexBinary>,
+    ) -> Result<Self, PayloadError> {
+        match self {
+            Self::V0(_) => Err(PayloadError::ExtensionsUnsupported {
+                version: PayloadVersion::V0,
+            }),
+            Self::V1 {
+                message,
+                memo,
+                mut extensions,
+            } => {
+                extensions.push(PayloadExtension {
+                    tag,
+                    value: value.into(),
+                });
+
+                Ok(Self::V1 {
+                    message,
+                    memo,
+                    extensions,
+                })
+            }
+        }
+    }
+
+    /// Encodes a call handed to the recipient as `HandleCall` after the transfer
+    pub fn with_call(self, call: Binary) -> Result<Self, PayloadError> {
+        self.with_extension(CALL_EXTENSION_V1, call.to_vec())
+    }
+
+    /// Encodes an IBC forward of the received tokens
+    pub fn with_ibc_forward(self, forward: &IbcForward) -> StdResult<Self> {
+        Ok(self.with_extension(IBC_FORWARD_EXTENSION_V1, to_json_vec(forward)?)?)
+    }
+
+    /// Encodes the collateral denom sent over a multi denom route
+    pub fn with_denom(self, denom: &str) -> Result<Self, PayloadError> {
+        self.with_extension(DENOM_EXTENSION_V1, denom.as_bytes().to_vec())
+    }
+
+    pub fn call(&self) -> Option<Binary> {
+        self.extension(CALL_EXTENSION_V1).map(|v| v.to_vec().into())
+    }
+
+    /// `Some(Err(..))` if the payload carries a denom that isn't valid utf8
+    pub fn denom(&self) -> Option<StdResult<String>> {
+        self.extension(DENOM_EXTENSION_V1).map(|v| {
+            String::from_utf8(v.to_vec())
+                .map_err(|_| StdError::generic_err("invalid denom extension"))
+        })
+    }
+
+    /// `Some(Err(..))` if the payload carries an IBC forward that can't be decoded
+    pub fn ibc_forward(&self) -> Option<StdResult<IbcForward>> {
+        self.extension(IBC_FORWARD_EXTENSION_V1)
+            .map(from_json)
+    }
+
+    pub fn encode(&self) -> Result<HexBinary, PayloadError> {
+        let (message, memo, extensions) = match self {
+            Self::V0(message) => return Ok(message.clone().into()),
+            Self::V1 {
+                message,
+                memo,
+                extensions,
+            } => (message, memo, extensions),
+        };
+
+        let mut bz = vec![PAYLOAD_V1];
+        bz.extend_from_slice(&message.recipient);
+        bz.extend_from_slice(&message.amount.to_be_bytes());
+        bz.extend_from_slice(&u32_len("metadata", message.metadata.len())?);
+        bz.extend_from_slice(&message.metadata);
+        bz.extend_from_slice(&u32_len("memo", memo.len())?);
+        bz.extend_from_slice(memo);
+
+        for ext in extensions {
+            let len = u16::try_from(ext.value.len()).map_err(|_| PayloadError::TooLong {
+                field: "extension",
+                len: ext.value.len(),
+            })?;
+
+            bz.push(ext.tag);
+            bz.extend_from_slice(&len.to_be_bytes());
+            bz.extend_from_slice(&ext.value);
+        }
+
+        Ok(bz.into())
+    }
+
+    /// Decodes a body of any known version
+    pub fn try_decode(raw: &[u8]) -> Result<Self, PayloadError> {
+        Self::try_decode_max(raw, PayloadVersion::LATEST)
+    }
+
+    /// Decodes a body, rejecting versions above `max_version`
+    pub fn try_decode_max(raw: &[u8], max_version: PayloadVersion) -> Result<Self, PayloadError> {
+        if raw.len() < HEADER_LEN {
+            return Err(PayloadError::TooShort { len: raw.len() });
+        }
+
+        if raw[0] == PAYLOAD_V1 {
+            if let Ok(payload) = Self::try_decode_v1(raw) {
+                if max_version < PayloadVersion::V1 {
+                    return Err(PayloadError::UnsupportedVersion {
+                        version: PAYLOAD_V1,
+                    });
+                }
+
+                return Ok(payload);
+            }
+        }
+
+        Ok(Self::V0(Message {
+            recipient: raw[0..32].to_vec().into(),
+            amount: Uint256::from_be_bytes(raw[32..64].try_into().unwrap()),
+            metadata: raw[64..].to_vec().into(),
+        }))
+    }
+
+    /// Decodes a body that must be v1
+    pub fn try_decode_v1(raw: &[u8]) -> Result<Self, PayloadError> {
+        let (version, rest) = raw.split_first().ok_or(PayloadError::TooShort { len: 0 })?;
+        if *version != PAYLOAD_V1 {
+            return Err(PayloadError::UnsupportedVersion { version: *version });
+        }
+        if rest.len() < HEADER_LEN {
+            return Err(PayloadError::TooShort { len: raw.len() });
+        }
+
+        let (header, mut rest) = rest.split_at(HEADER_LEN);
+        let metadata = take_u32_prefixed(&mut rest, "metadata")?;
+        let memo = take_u32_prefixed(&mut rest, "memo")?;
+
+        let mut extensions = vec![];
+        while let Some((tag, tail)) = rest.split_first() {
+            rest = tail;
+
+            let len = take(&mut rest, 2, "extension")?;
+            let len = u16::from_be_bytes(len.try_into().unwrap()) as usize;
+            let value = take(&mut rest, len, "extension")?;
+
+            extensions.push(PayloadExtension {
+                tag: *tag,
+                value: value.to_vec().into(),
+            });
+        }
+
+        Ok(Self::V1 {
+            message: Message {
+                recipient: header[0..32].to_vec().into(),
+                amount: Uint256::from_be_bytes(header[32..64].try_into().unwrap()),
+                metadata: metadata.to_vec().into(),
+            },
+            memo: memo.to_vec().into(),
+            extensions,
+        })
+    }
+}
+
+fn u32_len(field: &'static str, len: usize) -> Result<[u8; 4], PayloadError> {
+    u32::try_from(len)
+        .map(u32::to_be_bytes)
+        .map_err(|_| PayloadError::TooLong { field, len })
+}
+
+fn take<'a>(
+    rest: &mut &'a [u8],
+    len: usize,
+    field: &'static str,
+) -> Result<&'a [u8], PayloadError> {
+    if rest.len() < len {
+        return Err(PayloadError::Truncated { field });
+    }
+
+    let (head, tail) = rest.split_at(len);
+    *rest = tail;
+
+    Ok(head)
+}
+
+fn take_u32_prefixed<'a>(
+    rest: &mut &'a [u8],
+    field: &'static str,
+) -> Result<&'a [u8], PayloadError> {
+    let len = take(rest, 4, field)?;
+    let len = u32::from_be_bytes(len.try_into().unwrap()) as usize;
+
+    take(rest, len, field)
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{Binary, HexBinary, Uint256};
+    use ibcx_test_utils::gen_bz;
+    use rstest::rstest;
+
+    use super::{Payload, PayloadError, PayloadExtension, PayloadVersion, PAYLOAD_V1};
+    use crate::warp::{
+        IbcForward, Message, CALL_EXTENSION_V1, DENOM_EXTENSION_V1, IBC_FORWARD_EXTENSION_V1,
+    };
+
+    fn message() -> Message {
+        Message {
+            recipient: vec![0xab; 32].into(),
+            amount: Uint256::from(100u128),
+            metadata: HexBinary::default(),
+        }
+        .with_min_amount(Uint256::from(90u128))
+    }
+
+    fn v1() -> Payload {
+        Payload::V1 {
+            message: message(),
+            memo: b"hello".to_vec().into(),
+            extensions: vec![
+                PayloadExtension {
+                    tag: 0x01,
+                    value: gen_bz(3),
+                },
+                PayloadExtension {
+                    tag: 0xff,
+                    value: HexBinary::default(),
+                },
+            ],
+        }
+    }
+
+    #[rstest]
+    #[case(Payload::V0(message()))]
+    #[case(Payload::new(PayloadVersion::V1, message()))]
+    #[case(v1())]
+    fn test_round_trip(#[case] payload: Payload) {
+        let encoded = payload.encode().unwrap();
+
+        assert_eq!(Payload::try_decode(&encoded).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_v0_layout() {
+        // v0 is the plain message encoding, so older routes read it as before
+        let encoded = Payload::V0(message()).encode().unwrap();
+        assert_eq!(encoded, HexBinary::from(message()));
+
+        let v1 = v1().encode().unwrap();
+        assert_eq!(v1[0], PAYLOAD_V1);
+        assert_eq!(&v1[1..65], &encoded[..64]);
+    }
+
+    #[test]
+    fn test_cross_version() {
+        // v1 sender to a receiver expecting v0
+        let encoded = v1().encode().unwrap();
+        assert_eq!(
+            Payload::try_decode_max(&encoded, PayloadVersion::V0).unwrap_err(),
+            PayloadError::UnsupportedVersion { version: 1 }
+        );
+
+        // v0 sender to a v1 capable receiver
+        let encoded = Payload::V0(message()).encode().unwrap();
+        let decoded = Payload::try_decode(&encoded).unwrap();
+        assert_eq!(decoded.version(), PayloadVersion::V0);
+        assert_eq!(decoded.into_message(), message());
+    }
+
+    #[test]
+    fn test_v0_with_version_byte() {
+        // a v0 recipient may start with the version byte, its length tells it apart
+        let mut recipient = vec![0xab; 32];
+        recipient[0] = PAYLOAD_V1;
+
+        let msg = Message {
+            recipient: recipient.into(),
+            ..message()
+        };
+        let decoded = Payload::try_decode(&HexBinary::from(msg.clone())).unwrap();
+        assert_eq!(decoded, Payload::V0(msg));
+    }
+
+    #[rstest]
+    #[case(vec![], PayloadError::TooShort { len: 0 })]
+    #[case(vec![PAYLOAD_V1; 64], PayloadError::TooShort { len: 64 })]
+    #[case(vec![0x02; 70], PayloadError::UnsupportedVersion { version: 2 })]
+    #[case(
+        [vec![PAYLOAD_V1; 65], vec![0, 0, 0]].concat(),
+        PayloadError::Truncated { field: "metadata" }
+    )]
+    #[case(
+        [vec![PAYLOAD_V1; 65], vec![0, 0, 0, 1]].concat(),
+        PayloadError::Truncated { field: "metadata" }
+    )]
+    #[case(
+        [vec![PAYLOAD_V1; 65], vec![0; 4], vec![0, 0, 0, 2, 0]].concat(),
+        PayloadError::Truncated { field: "memo" }
+    )]
+    #[case(
+        [vec![PAYLOAD_V1; 65], vec![0; 8], vec![0x01, 0]].concat(),
+        PayloadError::Truncated { field: "extension" }
+    )]
+    #[case(
+        [vec![PAYLOAD_V1; 65], vec![0; 8], vec![0x01, 0, 2, 0]].concat(),
+        PayloadError::Truncated { field: "extension" }
+    )]
+    fn test_decode_v1_invalid(#[case] raw: Vec<u8>, #[case] err: PayloadError) {
+        assert_eq!(Payload::try_decode_v1(&raw).unwrap_err(), err);
+    }
+
+    #[test]
+    fn test_decode_too_short() {
+        assert_eq!(
+            Payload::try_decode(&gen_bz(63)).unwrap_err(),
+            PayloadError::TooShort { len: 63 }
+        );
+    }
+
+    #[test]
+    fn test_encode_too_long() {
+        let payload = Payload::V1 {
+            message: message(),
+            memo: HexBinary::default(),
+            extensions: vec![PayloadExtension {
+                tag: 0x01,
+                value: vec![0; u16::MAX as usize + 1].into(),
+            }],
+        };
+
+        assert_eq!(
+            payload.encode().unwrap_err(),
+            PayloadError::TooLong {
+                field: "extension",
+                len: u16::MAX as usize + 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_call() {
+        let payload = Payload::new(PayloadVersion::V1, message());
+        assert_eq!(payload.call(), None);
+
+        let call = Binary::from(b"{\"deposit\":{}}".to_vec());
+
+        let payload = payload.with_call(call.clone()).unwrap();
+        let decoded = Payload::try_decode(&payload.encode().unwrap()).unwrap();
+        assert_eq!(decoded.call(), Some(call));
+        assert_eq!(decoded.ibc_forward(), None);
+        assert_eq!(decoded.denom(), None);
+        // the message keeps its own metadata
+        assert_eq!(decoded.message(), &message());
+    }
+
+    #[test]
+    fn test_ibc_forward() {
+        let forward = IbcForward {
+            ibc_channel: "channel-0".into(),
+            final_recipient: "osmo1recipient".into(),
+            timeout_seconds: 600,
+        };
+
+        let payload = Payload::new(PayloadVersion::V1, message())
+            .with_ibc_forward(&forward)
+            .unwrap();
+        let decoded = Payload::try_decode(&payload.encode().unwrap()).unwrap();
+        assert_eq!(decoded.ibc_forward().unwrap().unwrap(), forward);
+        assert_eq!(decoded.call(), None);
+
+        let invalid = Payload::new(PayloadVersion::V1, message())
+            .with_extension(IBC_FORWARD_EXTENSION_V1, vec![0xff])
+            .unwrap();
+        assert!(invalid.ibc_forward().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_denom() {
+        let payload = Payload::new(PayloadVersion::V1, message())
+            .with_denom("uatom")
+            .unwrap();
+        let decoded = Payload::try_decode(&payload.encode().unwrap()).unwrap();
+        assert_eq!(decoded.denom().unwrap().unwrap(), "uatom");
+        assert_eq!(decoded.call(), None);
+        assert_eq!(decoded.ibc_forward(), None);
+
+        let invalid = Payload::new(PayloadVersion::V1, message())
+            .with_extension(DENOM_EXTENSION_V1, vec![0xff])
+            .unwrap();
+        assert!(invalid.denom().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_extensions_v0() {
+        let payload = Payload::V0(message());
+        assert!(payload.extensions().is_empty());
+        assert_eq!(payload.extension(CALL_EXTENSION_V1), None);
+
+        assert_eq!(
+            payload.with_call(Binary::from(b"{}".to_vec())).unwrap_err(),
+            PayloadError::ExtensionsUnsupported {
+                version: PayloadVersion::V0
+            }
+        );
+    }
+
+    #[test]
+    fn test_extension_first_tag() {
+        // repeated tags resolve to the first one
+        let payload = Payload::new(PayloadVersion::V1, message())
+            .with_denom("uatom")
+            .and_then(|v| v.with_denom("uosmo"))
+            .unwrap();
+        assert_eq!(payload.denom().unwrap().unwrap(), "uatom");
+    }
+}
@@ -0,0 +1,188 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_quote, Arm, Expr, FnArg, Ident, ItemFn, Pat, Stmt, Type};
+
+/// Routes the ownable messages of a contract entry point to `hpl_ownable`.
+///
+/// The entry point must end with `match msg { .. }` over its message, which gets the arm
+/// delegating `Msg::Ownable(..)` to `hpl_ownable::handle` (execute) or
+/// `hpl_ownable::handle_query` (query). Pass the variant name for messages using another one.
+///
+/// ```ignore
+/// #[hpl_ownable::route_ownable]
+/// pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> .. {
+///     match msg {
+///         ExecuteMsg::SetFoo { .. } => ..,
+///     }
+/// }
+///
+/// #[hpl_ownable::route_ownable(Ownership)]
+/// pub fn execute(..)
+/// ```
+#[proc_macro_attribute]
+pub fn route_ownable(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let variant: Ident = if attr.is_empty() {
+        parse_quote!(Ownable)
+    } else {
+        syn::parse2(attr)?
+    };
+
+    let mut func: ItemFn = syn::parse2(item)?;
+
+    let args = func
+        .sig
+        .inputs
+        .iter()
+        .map(|arg| match arg {
+            FnArg::Typed(arg) => match arg.pat.as_ref() {
+                Pat::Ident(pat) => Ok((pat.ident.clone(), arg.ty.as_ref().clone())),
+                pat => Err(syn::Error::new_spanned(pat, "expected an identifier")),
+            },
+            FnArg::Receiver(arg) => Err(syn::Error::new_spanned(arg, "expected a free function")),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let (msg, msg_ty) = match args.last() {
+        Some((msg, Type::Path(ty))) => (msg, &ty.path),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &func.sig,
+                "expected the message as the last argument",
+            ))
+        }
+    };
+
+    let arm: Arm = match args.as_slice() {
+        [(deps, _), (env, _), (info, _), _] => parse_quote! {
+            #msg_ty::#variant(msg) => Ok(::hpl_ownable::handle(#deps, #env, #info, msg)?),
+        },
+        [(deps, _), (env, _), _] => parse_quote! {
+            #msg_ty::#variant(msg) => Ok(::hpl_ownable::handle_query(#deps, #env, msg)?),
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &func.sig.inputs,
+                "expected (deps, env, info, msg) for execute or (deps, env, msg) for query",
+            ))
+        }
+    };
+
+    let matches_msg = |expr: &Expr| matches!(expr, Expr::Path(path) if path.path.is_ident(msg));
+
+    let dispatch = match func.block.stmts.last_mut() {
+        Some(Stmt::Expr(Expr::Match(dispatch), None)) if matches_msg(&dispatch.expr) => dispatch,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &func.block,
+                format!("expected the body to end with `match {msg} {{ .. }}`"),
+            ))
+        }
+    };
+    dispatch.arms.insert(0, arm);
+
+    Ok(quote!(#func))
+}
+
+#[cfg(test)]
+mod test {
+    use quote::quote;
+
+    use super::expand;
+
+    #[test]
+    fn test_expand_execute() {
+        let item = quote! {
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Res {
+                use ExecuteMsg::*;
+
+                match msg {
+                    SetFoo {} => Ok(Response::new()),
+                }
+            }
+        };
+
+        let expected = quote! {
+            pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Res {
+                use ExecuteMsg::*;
+
+                match msg {
+                    ExecuteMsg::Ownable(msg) => Ok(::hpl_ownable::handle(deps, env, info, msg)?),
+                    SetFoo {} => Ok(Response::new()),
+                }
+            }
+        };
+
+        assert_eq!(
+            expand(quote!(), item).unwrap().to_string(),
+            expected.to_string()
+        );
+    }
+
+    #[test]
+    fn test_expand_query() {
+        let item = quote! {
+            pub fn query(deps: Deps<C>, _env: Env, msg: QueryMsg) -> Res {
+                match msg {
+                    QueryMsg::Foo {} => to_binary(get_foo(deps)),
+                }
+            }
+        };
+
+        let expected = quote! {
+            pub fn query(deps: Deps<C>, _env: Env, msg: QueryMsg) -> Res {
+                match msg {
+                    QueryMsg::Ownership(msg) => Ok(::hpl_ownable::handle_query(deps, _env, msg)?),
+                    QueryMsg::Foo {} => to_binary(get_foo(deps)),
+                }
+            }
+        };
+
+        assert_eq!(
+            expand(quote!(Ownership), item).unwrap().to_string(),
+            expected.to_string()
+        );
+    }
+
+    #[test]
+    fn test_expand_invalid() {
+        let cases = [
+            (
+                quote! {
+                    pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) {
+                        let res = match msg {};
+                        res
+                    }
+                },
+                "expected the body to end with `match msg { .. }`",
+            ),
+            (
+                quote! {
+                    pub fn execute(deps: DepsMut, msg: ExecuteMsg) {
+                        match msg {}
+                    }
+                },
+                "expected (deps, env, info, msg) for execute or (deps, env, msg) for query",
+            ),
+            (
+                quote! {
+                    pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: &ExecuteMsg) {
+                        match msg {}
+                    }
+                },
+                "expected the message as the last argument",
+            ),
+        ];
+
+        for (item, expected) in cases {
+            let err = expand(quote!(), item).unwrap_err();
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use cosmwasm_std::StdError;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum OwnableError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("unauthorized")]
+    Unauthorized {},
+
+    #[error("ownership is transferring")]
+    TransferInProgress {},
+
+    #[error("ownership transfer is expired")]
+    TransferExpired {},
+
+    #[error("ownership is not transferring")]
+    NotTransferring {},
+
+    #[error("no owner")]
+    NoOwner {},
+}
+
+impl From<OwnableError> for StdError {
+    fn from(err: OwnableError) -> Self {
+        match err {
+            OwnableError::Std(err) => err,
+            _ => StdError::generic_err(err.to_string()),
+        }
+    }
+}
@@ -1,11 +1,20 @@
+mod error;
 #[cfg(test)]
 mod test;
 
+// lets the tests use `route_ownable`, which expands to `::hpl_ownable` paths
+#[cfg(test)]
+extern crate self as hpl_ownable;
+
+pub use error::OwnableError;
+pub use hpl_ownable_macros::route_ownable;
+
 use cosmwasm_std::{
-    ensure, ensure_eq, to_json_binary, Addr, CustomQuery, Deps, DepsMut, Env, Event, MessageInfo,
-    QueryResponse, Response, StdError, StdResult, Storage,
+    ensure, ensure_eq, to_json_binary, Addr, BlockInfo, CustomQuery, Deps, DepsMut, Env, Event,
+    MessageInfo, QueryResponse, Response, StdResult, Storage,
 };
 use cw_storage_plus::Item;
+use cw_utils::Expiration;
 use hpl_interface::ownable::{OwnableMsg, OwnableQueryMsg, OwnerResponse, PendingOwnerResponse};
 
 const OWNER_KEY: &str = "owner";
@@ -14,6 +23,13 @@ const OWNER: Item<Addr> = Item::new(OWNER_KEY);
 const PENDING_OWNER_KEY: &str = "pending_owner";
 const PENDING_OWNER: Item<Addr> = Item::new(PENDING_OWNER_KEY);
 
+const PENDING_OWNER_DEADLINE_KEY: &str = "pending_owner_deadline";
+const PENDING_OWNER_DEADLINE: Item<Expiration> = Item::new(PENDING_OWNER_DEADLINE_KEY);
+
+/// Pending owner of the last revoked transfer, consumed by the next `init`
+const REVOKED_PENDING_OWNER_KEY: &str = "revoked_pending_owner";
+const REVOKED_PENDING_OWNER: Item<Addr> = Item::new(REVOKED_PENDING_OWNER_KEY);
+
 fn event_to_resp(event: Event) -> Response {
     Response::new().add_event(event)
 }
@@ -22,6 +38,10 @@ fn new_event(name: &str) -> Event {
     Event::new(format!("hpl_ownable::{}", name))
 }
 
+fn load_owner(storage: &dyn Storage) -> Result<Addr, OwnableError> {
+    OWNER.may_load(storage)?.ok_or(OwnableError::NoOwner {})
+}
+
 pub fn initialize(storage: &mut dyn Storage, owner: &Addr) -> StdResult<()> {
     OWNER.save(storage, owner)?;
 
@@ -30,18 +50,23 @@ pub fn initialize(storage: &mut dyn Storage, owner: &Addr) -> StdResult<()> {
 
 pub fn handle<C: CustomQuery>(
     deps: DepsMut<'_, C>,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: OwnableMsg,
-) -> StdResult<Response> {
+) -> Result<Response, OwnableError> {
     use OwnableMsg::*;
 
     match msg {
-        InitOwnershipTransfer { next_owner } => {
+        InitOwnershipTransfer {
+            next_owner,
+            deadline,
+        } => {
             let event = init_ownership_transfer(
                 deps.storage,
+                &env.block,
                 &info.sender,
                 &deps.api.addr_validate(&next_owner)?,
+                deadline,
             )?;
 
             Ok(event_to_resp(event))
@@ -52,67 +77,113 @@ pub fn handle<C: CustomQuery>(
             Ok(event_to_resp(event))
         }
         ClaimOwnership {} => {
-            let event = claim_ownership(deps.storage, &info.sender)?;
+            let event = claim_ownership(deps.storage, &env.block, &info.sender)?;
 
             Ok(event_to_resp(event))
         }
     }
 }
 
+fn is_expired(storage: &dyn Storage, block: &BlockInfo) -> StdResult<bool> {
+    Ok(PENDING_OWNER_DEADLINE
+        .may_load(storage)?
+        .is_some_and(|v| v.is_expired(block)))
+}
+
+/// A pending transfer past its deadline is overwritten as if it had been revoked
 pub fn init_ownership_transfer(
     storage: &mut dyn Storage,
+    block: &BlockInfo,
     sender: &Addr,
     next_owner: &Addr,
-) -> StdResult<Event> {
-    ensure_eq!(
-        sender,
-        OWNER.load(storage)?,
-        StdError::generic_err("unauthorized")
-    );
+    deadline: Option<Expiration>,
+) -> Result<Event, OwnableError> {
+    ensure_eq!(sender, load_owner(storage)?, OwnableError::Unauthorized {});
 
     ensure!(
-        !PENDING_OWNER.exists(storage),
-        StdError::generic_err("ownership is transferring")
+        !deadline.is_some_and(|v| v.is_expired(block)),
+        OwnableError::TransferExpired {}
     );
 
+    if let Some(pending_owner) = PENDING_OWNER.may_load(storage)? {
+        ensure!(
+            is_expired(storage, block)?,
+            OwnableError::TransferInProgress {}
+        );
+
+        REVOKED_PENDING_OWNER.save(storage, &pending_owner)?;
+    }
+
     PENDING_OWNER.save(storage, next_owner)?;
 
-    Ok(new_event("init")
+    let mut event = new_event("init")
         .add_attribute("owner", sender)
-        .add_attribute("next_owner", next_owner))
+        .add_attribute("next_owner", next_owner);
+
+    match deadline {
+        Some(deadline) => {
+            PENDING_OWNER_DEADLINE.save(storage, &deadline)?;
+            event = event.add_attribute("deadline", deadline.to_string());
+        }
+        None => PENDING_OWNER_DEADLINE.remove(storage),
+    }
+
+    match REVOKED_PENDING_OWNER.may_load(storage)? {
+        Some(prev_pending_owner) => {
+            REVOKED_PENDING_OWNER.remove(storage);
+
+            Ok(event
+                .add_attribute("overwrote", "true")
+                .add_attribute("prev_pending_owner", prev_pending_owner))
+        }
+        None => Ok(event.add_attribute("overwrote", "false")),
+    }
 }
 
-pub fn revoke_ownership_transfer(storage: &mut dyn Storage, sender: &Addr) -> StdResult<Event> {
-    ensure_eq!(
-        sender,
-        OWNER.load(storage)?,
-        StdError::generic_err("unauthorized")
-    );
+pub fn revoke_ownership_transfer(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+) -> Result<Event, OwnableError> {
+    ensure_eq!(sender, load_owner(storage)?, OwnableError::Unauthorized {});
 
-    ensure!(
-        PENDING_OWNER.exists(storage),
-        StdError::generic_err("ownership is not transferring")
-    );
+    let pending_owner = PENDING_OWNER
+        .may_load(storage)?
+        .ok_or(OwnableError::NotTransferring {})?;
 
     PENDING_OWNER.remove(storage);
+    PENDING_OWNER_DEADLINE.remove(storage);
+    REVOKED_PENDING_OWNER.save(storage, &pending_owner)?;
 
-    Ok(new_event("revoke").add_attribute("owner", sender))
+    Ok(new_event("revoke")
+        .add_attribute("owner", sender)
+        .add_attribute("pending_owner", pending_owner))
 }
 
-pub fn claim_ownership(storage: &mut dyn Storage, sender: &Addr) -> StdResult<Event> {
+pub fn claim_ownership(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    sender: &Addr,
+) -> Result<Event, OwnableError> {
     ensure!(
         PENDING_OWNER.exists(storage),
-        StdError::generic_err("ownership is not transferring")
+        OwnableError::NotTransferring {}
     );
 
     ensure_eq!(
         sender,
         PENDING_OWNER.load(storage)?,
-        StdError::generic_err("unauthorized")
+        OwnableError::Unauthorized {}
+    );
+
+    ensure!(
+        !is_expired(storage, block)?,
+        OwnableError::TransferExpired {}
     );
 
     OWNER.save(storage, sender)?;
     PENDING_OWNER.remove(storage);
+    PENDING_OWNER_DEADLINE.remove(storage);
+    REVOKED_PENDING_OWNER.remove(storage);
 
     Ok(new_event("claim").add_attribute("owner", sender))
 }
@@ -128,6 +199,7 @@ pub fn handle_query<C: CustomQuery>(
         }),
         OwnableQueryMsg::GetPendingOwner {} => to_json_binary(&PendingOwnerResponse {
             pending_owner: get_pending_owner(deps.storage)?,
+            deadline: get_pending_owner_deadline(deps.storage)?,
         }),
     }
 }
@@ -143,3 +215,9 @@ pub fn get_pending_owner(storage: &dyn Storage) -> StdResult<Option<Addr>> {
 
     Ok(pending_owner)
 }
+
+pub fn get_pending_owner_deadline(storage: &dyn Storage) -> StdResult<Option<Expiration>> {
+    let deadline = PENDING_OWNER_DEADLINE.may_load(storage)?;
+
+    Ok(deadline)
+}
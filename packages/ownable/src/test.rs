@@ -3,11 +3,12 @@ use cosmwasm_std::{
     testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
     Addr, CustomQuery, Empty, Env, MessageInfo, OwnedDeps, Response, StdError, StdResult,
 };
+use cw_utils::Expiration;
 use hpl_interface::ownable::{OwnableMsg, OwnableQueryMsg, OwnerResponse, PendingOwnerResponse};
 use rstest::rstest;
 use serde::de::DeserializeOwned;
 
-use crate::{handle, handle_query};
+use crate::{handle, handle_query, new_event, OwnableError};
 
 pub struct Ownable<C: CustomQuery = Empty> {
     pub deps: OwnedDeps<MockStorage, MockApi, MockQuerier, C>,
@@ -22,7 +23,7 @@ where
         Self { deps, env }
     }
 
-    fn handle(&mut self, info: MessageInfo, msg: OwnableMsg) -> StdResult<Response> {
+    fn handle(&mut self, info: MessageInfo, msg: OwnableMsg) -> Result<Response, OwnableError> {
         handle(self.deps.as_mut(), self.env.clone(), info, msg)
     }
 
@@ -30,23 +31,33 @@ where
         from_json(handle_query(self.deps.as_ref(), self.env.clone(), msg)?)
     }
 
-    pub fn init(&mut self, sender: &Addr, next_owner: &Addr) -> StdResult<Response> {
+    pub fn init(&mut self, sender: &Addr, next_owner: &Addr) -> Result<Response, OwnableError> {
+        self.init_with_deadline(sender, next_owner, None)
+    }
+
+    pub fn init_with_deadline(
+        &mut self,
+        sender: &Addr,
+        next_owner: &Addr,
+        deadline: Option<Expiration>,
+    ) -> Result<Response, OwnableError> {
         self.handle(
             mock_info(sender.as_str(), &[]),
             OwnableMsg::InitOwnershipTransfer {
                 next_owner: next_owner.to_string(),
+                deadline,
             },
         )
     }
 
-    pub fn revoke(&mut self, sender: &Addr) -> StdResult<Response> {
+    pub fn revoke(&mut self, sender: &Addr) -> Result<Response, OwnableError> {
         self.handle(
             mock_info(sender.as_str(), &[]),
             OwnableMsg::RevokeOwnershipTransfer {},
         )
     }
 
-    pub fn claim(&mut self, sender: &Addr) -> StdResult<Response> {
+    pub fn claim(&mut self, sender: &Addr) -> Result<Response, OwnableError> {
         self.handle(
             mock_info(sender.as_str(), &[]),
             OwnableMsg::ClaimOwnership {},
@@ -64,6 +75,12 @@ where
 
         Ok(resp.pending_owner)
     }
+
+    pub fn pending_deadline(&self) -> StdResult<Option<Expiration>> {
+        let resp: PendingOwnerResponse = self.query(OwnableQueryMsg::GetPendingOwner {})?;
+
+        Ok(resp.deadline)
+    }
 }
 
 fn ownable_default() -> Ownable {
@@ -130,11 +147,11 @@ fn test_init() -> anyhow::Result<()> {
 
     // fail - sender is not owner
     let err = ownable.init(&next_owner, &deployer).unwrap_err();
-    assert_eq!(err, StdError::generic_err("unauthorized"));
+    assert_eq!(err, OwnableError::Unauthorized {});
 
     // fail - pending_owner is not empty
     let err = ownable.init(&deployer, &next_owner).unwrap_err();
-    assert_eq!(err, StdError::generic_err("ownership is transferring"));
+    assert_eq!(err, OwnableError::TransferInProgress {});
 
     Ok(())
 }
@@ -151,14 +168,14 @@ fn test_revoke() -> anyhow::Result<()> {
 
     // fail - pending_owner is empty
     let err = ownable.revoke(&deployer).unwrap_err();
-    assert_eq!(err, StdError::generic_err("ownership is not transferring"));
+    assert_eq!(err, OwnableError::NotTransferring {});
 
     // initiate ownership transfer
     ownable.init(&deployer, &next_owner)?;
 
     // fail - sender is not owner
     let err = ownable.revoke(&next_owner).unwrap_err();
-    assert_eq!(err, StdError::generic_err("unauthorized"));
+    assert_eq!(err, OwnableError::Unauthorized {});
 
     // ok
     ownable.revoke(&deployer)?;
@@ -178,17 +195,269 @@ fn test_claim() -> anyhow::Result<()> {
 
     // fail - pending_owner is empty
     let err = ownable.claim(&next_owner).unwrap_err();
-    assert_eq!(err, StdError::generic_err("ownership is not transferring"));
+    assert_eq!(err, OwnableError::NotTransferring {});
 
     // initiate ownership transfer
     ownable.init(&deployer, &next_owner)?;
 
     // fail - sender is not pending_owner
     let err = ownable.claim(&deployer).unwrap_err();
-    assert_eq!(err, StdError::generic_err("unauthorized"));
+    assert_eq!(err, OwnableError::Unauthorized {});
+
+    // ok
+    ownable.claim(&next_owner)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_revoke_then_reinit_events() -> anyhow::Result<()> {
+    let deployer = Addr::unchecked("deployer");
+    let next_owner = Addr::unchecked("next_owner");
+    let other_owner = Addr::unchecked("other_owner");
+
+    let mut ownable = Ownable::new(mock_dependencies(), mock_env());
+
+    // initial setup
+    crate::initialize(ownable.deps.as_mut().storage, &deployer)?;
+
+    // nothing to overwrite on the first transfer
+    let res = ownable.init(&deployer, &next_owner)?;
+    assert_eq!(
+        res.events,
+        vec![new_event("init")
+            .add_attribute("owner", &deployer)
+            .add_attribute("next_owner", &next_owner)
+            .add_attribute("overwrote", "false")]
+    );
+
+    // revoke reports the pending owner it dropped
+    let res = ownable.revoke(&deployer)?;
+    assert_eq!(
+        res.events,
+        vec![new_event("revoke")
+            .add_attribute("owner", &deployer)
+            .add_attribute("pending_owner", &next_owner)]
+    );
+
+    // re-init links back to the revoked transfer
+    let res = ownable.init(&deployer, &other_owner)?;
+    assert_eq!(
+        res.events,
+        vec![new_event("init")
+            .add_attribute("owner", &deployer)
+            .add_attribute("next_owner", &other_owner)
+            .add_attribute("overwrote", "true")
+            .add_attribute("prev_pending_owner", &next_owner)]
+    );
+
+    // the link is consumed once reported
+    ownable.claim(&other_owner)?;
+    let res = ownable.init(&other_owner, &deployer)?;
+    assert_eq!(
+        res.events[0].attributes.last().map(|v| v.value.as_str()),
+        Some("false")
+    );
+
+    Ok(())
+}
+
+#[rstest]
+#[case::height(Expiration::AtHeight(mock_env().block.height + 10))]
+#[case::time(Expiration::AtTime(mock_env().block.time.plus_seconds(60)))]
+fn test_deadline(#[case] deadline: Expiration) -> anyhow::Result<()> {
+    let deployer = Addr::unchecked("deployer");
+    let next_owner = Addr::unchecked("next_owner");
+    let other_owner = Addr::unchecked("other_owner");
+
+    let mut ownable = ownable_default();
+
+    // initial setup
+    crate::initialize(ownable.deps.as_mut().storage, &deployer)?;
+
+    let res = ownable.init_with_deadline(&deployer, &next_owner, Some(deadline))?;
+    assert_eq!(
+        res.events[0].attributes[2].value,
+        deadline.to_string(),
+        "deadline is reported"
+    );
+    assert_eq!(ownable.pending_owner()?, Some(next_owner.clone()));
+    assert_eq!(ownable.pending_deadline()?, Some(deadline));
+
+    // fail - pending transfer is still live
+    let err = ownable.init(&deployer, &other_owner).unwrap_err();
+    assert_eq!(err, OwnableError::TransferInProgress {});
+
+    // move to the deadline
+    ownable.env.block.height += 10;
+    ownable.env.block.time = ownable.env.block.time.plus_seconds(60);
+
+    // fail - transfer is expired
+    let err = ownable.claim(&next_owner).unwrap_err();
+    assert_eq!(err, OwnableError::TransferExpired {});
+
+    // fail - deadline is already past
+    let err = ownable
+        .init_with_deadline(&deployer, &other_owner, Some(deadline))
+        .unwrap_err();
+    assert_eq!(err, OwnableError::TransferExpired {});
+
+    // expired transfer is overwritten without a revoke
+    let res = ownable.init(&deployer, &other_owner)?;
+    assert_eq!(
+        res.events,
+        vec![new_event("init")
+            .add_attribute("owner", &deployer)
+            .add_attribute("next_owner", &other_owner)
+            .add_attribute("overwrote", "true")
+            .add_attribute("prev_pending_owner", &next_owner)]
+    );
+    assert_eq!(ownable.pending_owner()?, Some(other_owner.clone()));
+    assert_eq!(ownable.pending_deadline()?, None);
 
     // ok
+    ownable.claim(&other_owner)?;
+    assert_eq!(ownable.owner()?, other_owner);
+    assert_eq!(ownable.pending_owner()?, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_claim_before_deadline() -> anyhow::Result<()> {
+    let deployer = Addr::unchecked("deployer");
+    let next_owner = Addr::unchecked("next_owner");
+
+    let mut ownable = ownable_default();
+
+    // initial setup
+    crate::initialize(ownable.deps.as_mut().storage, &deployer)?;
+
+    let deadline = Expiration::AtHeight(ownable.env.block.height + 10);
+    ownable.init_with_deadline(&deployer, &next_owner, Some(deadline))?;
+
+    // one block before the deadline
+    ownable.env.block.height += 9;
     ownable.claim(&next_owner)?;
 
+    assert_eq!(ownable.owner()?, next_owner);
+    assert_eq!(ownable.pending_deadline()?, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_no_owner() {
+    let deployer = Addr::unchecked("deployer");
+    let next_owner = Addr::unchecked("next_owner");
+
+    let mut ownable = ownable_default();
+
+    let err = ownable.init(&deployer, &next_owner).unwrap_err();
+    assert_eq!(err, OwnableError::NoOwner {});
+
+    let err = ownable.revoke(&deployer).unwrap_err();
+    assert_eq!(err, OwnableError::NoOwner {});
+
+    let err = ownable.claim(&next_owner).unwrap_err();
+    assert_eq!(err, OwnableError::NotTransferring {});
+}
+
+#[rstest]
+#[case(OwnableError::Unauthorized {}, "unauthorized")]
+#[case(OwnableError::TransferInProgress {}, "ownership is transferring")]
+#[case(OwnableError::TransferExpired {}, "ownership transfer is expired")]
+#[case(OwnableError::NotTransferring {}, "ownership is not transferring")]
+#[case(OwnableError::NoOwner {}, "no owner")]
+fn test_into_std_error(#[case] err: OwnableError, #[case] msg: &str) {
+    assert_eq!(StdError::from(err), StdError::generic_err(msg));
+}
+
+mod routed {
+    use cosmwasm_std::{to_json_binary, Deps, DepsMut, Env, MessageInfo, QueryResponse, Response};
+    use hpl_interface::ownable::{OwnableMsg, OwnableQueryMsg};
+
+    use crate::OwnableError;
+
+    pub enum ExecuteMsg {
+        Ownership(OwnableMsg),
+        Ping {},
+    }
+
+    pub enum QueryMsg {
+        Ownable(OwnableQueryMsg),
+        Ping {},
+    }
+
+    #[crate::route_ownable(Ownership)]
+    pub fn execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, OwnableError> {
+        match msg {
+            ExecuteMsg::Ping {} => Ok(Response::new().add_attribute("ping", "pong")),
+        }
+    }
+
+    #[crate::route_ownable]
+    pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<QueryResponse, OwnableError> {
+        match msg {
+            QueryMsg::Ping {} => Ok(to_json_binary("pong")?),
+        }
+    }
+}
+
+#[test]
+fn test_route_ownable() -> anyhow::Result<()> {
+    use routed::{execute, query, ExecuteMsg, QueryMsg};
+
+    let deployer = Addr::unchecked("deployer");
+    let next_owner = Addr::unchecked("next_owner");
+
+    let mut deps = mock_dependencies();
+    crate::initialize(deps.as_mut().storage, &deployer)?;
+
+    // ownable messages go to `handle` and `handle_query`
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(deployer.as_str(), &[]),
+        ExecuteMsg::Ownership(OwnableMsg::InitOwnershipTransfer {
+            next_owner: next_owner.to_string(),
+            deadline: None,
+        }),
+    )?;
+    assert_eq!(res.events[0].ty, "hpl_ownable::init");
+
+    let res: PendingOwnerResponse = from_json(query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Ownable(OwnableQueryMsg::GetPendingOwner {}),
+    )?)?;
+    assert_eq!(res.pending_owner, Some(next_owner));
+
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("someone", &[]),
+        ExecuteMsg::Ownership(OwnableMsg::RevokeOwnershipTransfer {}),
+    )
+    .unwrap_err();
+    assert_eq!(err, OwnableError::Unauthorized {});
+
+    // the rest to the contract's own arms
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("someone", &[]),
+        ExecuteMsg::Ping {},
+    )?;
+    assert_eq!(res.attributes[0].value, "pong");
+
+    let res: String = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Ping {})?)?;
+    assert_eq!(res, "pong");
+
     Ok(())
 }
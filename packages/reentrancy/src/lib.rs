@@ -0,0 +1,84 @@
+use cosmwasm_std::{ensure, CosmosMsg, StdError, StdResult, Storage, SubMsg};
+use cw_storage_plus::Item;
+
+/// Storage flag rejecting nested entry into the same execution path.
+///
+/// `enter` at the start of the guarded handler, send the outgoing call with
+/// [`exit_on_reply`] and `exit` from the matching reply. A failing call reverts
+/// the whole transaction, guard included, so only the success path needs clearing.
+pub struct ReentrancyGuard<'a> {
+    item: Item<'a, bool>,
+}
+
+impl<'a> ReentrancyGuard<'a> {
+    pub const fn new(key: &'a str) -> Self {
+        Self {
+            item: Item::new(key),
+        }
+    }
+
+    pub fn is_entered(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.item.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn enter(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        ensure!(
+            !self.is_entered(storage)?,
+            StdError::generic_err("reentrant call")
+        );
+
+        self.item.save(storage, &true)
+    }
+
+    pub fn exit(&self, storage: &mut dyn Storage) {
+        self.item.remove(storage)
+    }
+}
+
+/// wraps the guarded call so the contract gets a reply to `exit` from
+pub fn exit_on_reply<T>(msg: impl Into<CosmosMsg<T>>, reply_id: u64) -> SubMsg<T> {
+    SubMsg::reply_on_success(msg, reply_id)
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{testing::MockStorage, BankMsg, ReplyOn};
+
+    use super::*;
+
+    const GUARD: ReentrancyGuard = ReentrancyGuard::new("guard");
+
+    #[test]
+    fn test_enter_exit() {
+        let mut storage = MockStorage::new();
+        assert!(!GUARD.is_entered(&storage).unwrap());
+
+        GUARD.enter(&mut storage).unwrap();
+        assert!(GUARD.is_entered(&storage).unwrap());
+
+        GUARD.exit(&mut storage);
+        assert!(!GUARD.is_entered(&storage).unwrap());
+
+        // can be entered again once cleared
+        GUARD.enter(&mut storage).unwrap();
+    }
+
+    #[test]
+    fn test_reentrant() {
+        let mut storage = MockStorage::new();
+        GUARD.enter(&mut storage).unwrap();
+
+        let err = GUARD.enter(&mut storage).unwrap_err();
+        assert_eq!(err, StdError::generic_err("reentrant call"));
+    }
+
+    #[test]
+    fn test_exit_on_reply() {
+        let msg = BankMsg::Burn { amount: vec![] };
+        let sub: SubMsg = exit_on_reply(msg.clone(), 7);
+
+        assert_eq!(sub.id, 7);
+        assert_eq!(sub.reply_on, ReplyOn::Success);
+        assert_eq!(sub.msg, CosmosMsg::Bank(msg));
+    }
+}
@@ -0,0 +1,22 @@
+use cosmwasm_std::StdError;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum RouterError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("unauthorized")]
+    Unauthorized {},
+
+    #[error("encoding failed")]
+    EncodingFailed {},
+}
+
+impl From<RouterError> for StdError {
+    fn from(err: RouterError) -> Self {
+        match err {
+            RouterError::Std(err) => err,
+            _ => StdError::generic_err(err.to_string()),
+        }
+    }
+}
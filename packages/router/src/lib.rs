@@ -1,11 +1,14 @@
+mod error;
 #[cfg(test)]
 mod test;
 
+pub use error::RouterError;
+
 use cosmwasm_std::{
     ensure_eq, to_json_binary, Addr, CustomQuery, Deps, DepsMut, Env, Event, MessageInfo,
-    QueryResponse, Response, StdError, StdResult, Storage,
+    QueryResponse, Response, StdResult, Storage,
 };
-use cw_storage_plus::Map;
+use cw_storage_plus::{KeyDeserialize, Map};
 use hpl_interface::{
     range_option,
     router::{
@@ -37,7 +40,7 @@ pub fn handle<C: CustomQuery, T>(
     _env: Env,
     info: MessageInfo,
     msg: RouterMsg<T>,
-) -> StdResult<Response>
+) -> Result<Response, RouterError>
 where
     T: Serialize + DeserializeOwned + Clone + Eq,
 {
@@ -46,7 +49,7 @@ where
     ensure_eq!(
         hpl_ownable::get_owner(deps.storage)?,
         info.sender,
-        StdError::generic_err("unauthorized")
+        RouterError::Unauthorized {}
     );
 
     match msg {
@@ -56,9 +59,9 @@ where
             Ok(event_to_resp(event))
         }
         SetRoutes { set } => {
-            let event = set_routes(deps.storage, &info.sender, set)?;
+            let events = set_routes(deps.storage, &info.sender, set)?;
 
-            Ok(event_to_resp(event))
+            Ok(Response::new().add_events(events))
         }
     }
 }
@@ -67,44 +70,31 @@ pub fn set_route<T>(
     storage: &mut dyn Storage,
     sender: &Addr,
     set: DomainRouteSet<T>,
-) -> StdResult<Event>
+) -> Result<Event, RouterError>
 where
     T: Serialize + DeserializeOwned + Clone + Eq,
 {
     get_route_map().save(storage, set.domain, &set.route)?;
 
-    Ok(new_event("set_route")
-        .add_attribute("sender", sender)
-        .add_attribute(
-            "set",
-            serde_json_wasm::to_string(&set)
-                .map_err(|_| StdError::generic_err("encoding failed"))?,
-        ))
+    Ok(hpl_events::route_set(
+        new_event("set_route"),
+        sender,
+        set.domain,
+        serde_json_wasm::to_string(&set.route).map_err(|_| RouterError::EncodingFailed {})?,
+    ))
 }
 
 pub fn set_routes<T>(
     storage: &mut dyn Storage,
     sender: &Addr,
     set: Vec<DomainRouteSet<T>>,
-) -> StdResult<Event>
+) -> Result<Vec<Event>, RouterError>
 where
     T: Serialize + DeserializeOwned + Clone + Eq,
 {
-    for DomainRouteSet {
-        domain,
-        route: router,
-    } in set.clone()
-    {
-        get_route_map().save(storage, domain, &router)?;
-    }
-
-    Ok(new_event("set_routes")
-        .add_attribute("sender", sender)
-        .add_attribute(
-            "set",
-            serde_json_wasm::to_string(&set)
-                .map_err(|_| StdError::generic_err("encoding failed"))?,
-        ))
+    set.into_iter()
+        .map(|v| set_route(storage, sender, v))
+        .collect()
 }
 
 pub fn handle_query<C: CustomQuery, T>(
@@ -182,3 +172,36 @@ where
         })
         .collect()
 }
+
+/// Rewrites routes stored as `Old` with `convert`, called with the domain of each route,
+/// returning how many were rewritten.
+/// Routes that already decode as `T` are left as is, so migrating twice is a no-op
+pub fn migrate_routes<Old, T>(
+    storage: &mut dyn Storage,
+    convert: impl Fn(u32, Old) -> StdResult<T>,
+) -> StdResult<usize>
+where
+    Old: Serialize + DeserializeOwned + Clone + Eq,
+    T: Serialize + DeserializeOwned + Clone + Eq,
+{
+    // walk the raw keys, as iterating the typed map would decode the legacy values
+    let domains = get_route_map::<T>()
+        .keys_raw(storage, None, None, Order::Asc.into())
+        .map(u32::from_vec)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut migrated = 0;
+
+    for domain in domains {
+        if get_route_map::<T>().load(storage, domain).is_ok() {
+            continue;
+        }
+
+        if let Ok(old) = get_route_map::<Old>().load(storage, domain) {
+            get_route_map().save(storage, domain, &convert(domain, old)?)?;
+            migrated += 1;
+        }
+    }
+
+    Ok(migrated)
+}
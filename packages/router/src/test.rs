@@ -13,7 +13,7 @@ use hpl_interface::{
 };
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::{handle, handle_query, is_router};
+use crate::{get_route, handle, handle_query, is_router, migrate_routes, RouterError};
 
 pub struct Router<T>
 where
@@ -29,11 +29,16 @@ impl<T> Router<T>
 where
     T: Serialize + DeserializeOwned + Clone + Eq + Default,
 {
-    fn handle(&mut self, info: MessageInfo, msg: RouterMsg<T>) -> StdResult<Response> {
+    fn handle(&mut self, info: MessageInfo, msg: RouterMsg<T>) -> Result<Response, RouterError> {
         handle(self.deps.as_mut(), self.env.clone(), info, msg)
     }
 
-    pub fn set_route(&mut self, sender: &Addr, domain: u32, router: T) -> StdResult<Response> {
+    pub fn set_route(
+        &mut self,
+        sender: &Addr,
+        domain: u32,
+        router: T,
+    ) -> Result<Response, RouterError> {
         self.handle(
             mock_info(sender.as_str(), &[]),
             RouterMsg::SetRoute {
@@ -45,7 +50,7 @@ where
         )
     }
 
-    pub fn set_routes(&mut self, sender: &Addr, set: &[(u32, T)]) -> StdResult<Response> {
+    pub fn set_routes(&mut self, sender: &Addr, set: &[(u32, T)]) -> Result<Response, RouterError> {
         self.handle(
             mock_info(sender.as_str(), &[]),
             RouterMsg::SetRoutes {
@@ -119,6 +124,12 @@ fn test_handle() -> anyhow::Result<()> {
 
     hpl_ownable::initialize(router.deps.as_mut().storage, &owner)?;
 
+    // fail - sender is not owner
+    let err = router
+        .set_route(&Addr::unchecked("not_owner"), 1, Binary::default())
+        .unwrap_err();
+    assert_eq!(err, RouterError::Unauthorized {});
+
     router.set_route(&owner, set_a.domain, set_a.route.clone().unwrap())?;
     router.set_routes(&owner, &[(set_b.domain, set_b.route.clone().unwrap())])?;
 
@@ -159,3 +170,51 @@ fn test_check() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[derive(Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+struct ScaledRoute {
+    router: Binary,
+    decimals: u8,
+}
+
+#[test]
+fn test_migrate_routes() -> anyhow::Result<()> {
+    let owner = Addr::unchecked("owner");
+
+    let mut router = Router::<Binary>::default();
+
+    hpl_ownable::initialize(router.deps.as_mut().storage, &owner)?;
+    router.set_routes(
+        &owner,
+        &[(1, Binary(b"router_a".to_vec())), (2, Binary::default())],
+    )?;
+
+    let storage = router.deps.as_mut().storage;
+    let convert = |_, router: Binary| {
+        Ok(ScaledRoute {
+            router,
+            decimals: 6,
+        })
+    };
+
+    // migrating twice only rewrites the routes once
+    assert_eq!(migrate_routes(storage, convert)?, 2);
+    assert_eq!(migrate_routes(storage, convert)?, 0);
+
+    assert_eq!(
+        get_route::<ScaledRoute>(storage, 1)?.route,
+        Some(ScaledRoute {
+            router: Binary(b"router_a".to_vec()),
+            decimals: 6
+        })
+    );
+    assert_eq!(
+        get_route::<ScaledRoute>(storage, 2)?.route,
+        Some(ScaledRoute {
+            router: Binary::default(),
+            decimals: 6
+        })
+    );
+
+    Ok(())
+}
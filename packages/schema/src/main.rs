@@ -48,6 +48,18 @@ pub fn main() {
         });
     }
 
+    {
+        use hpl_hook::mock::*;
+
+        apis.push(generate_api! {
+            name: "hpl_hook_mock",
+            instantiate: InstantiateMsg,
+            migrate: Empty,
+            execute: ExecuteMsg,
+            query: QueryMsg,
+        });
+    }
+
     {
         use hpl_hook::pausable::*;
 
@@ -60,6 +72,18 @@ pub fn main() {
         });
     }
 
+    {
+        use hpl_hook::ratelimit::*;
+
+        apis.push(generate_api! {
+            name: "hpl_hook_ratelimit",
+            instantiate: InstantiateMsg,
+            migrate: Empty,
+            execute: ExecuteMsg,
+            query: QueryMsg,
+        });
+    }
+
     {
         use hpl_hook::routing::*;
 
@@ -132,6 +156,18 @@ pub fn main() {
         });
     }
 
+    {
+        use hpl_ism::multisig_factory::*;
+
+        apis.push(generate_api! {
+            name: "hpl_ism_multisig_factory",
+            instantiate: InstantiateMsg,
+            migrate: Empty,
+            execute: ExecuteMsg,
+            query: QueryMsg,
+        });
+    }
+
     {
         use hpl_ism::routing::*;
 
@@ -150,7 +186,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_warp_cw20",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
@@ -162,7 +198,7 @@ pub fn main() {
         apis.push(generate_api! {
             name: "hpl_warp_native",
             instantiate: InstantiateMsg,
-            migrate: Empty,
+            migrate: MigrateMsg,
             execute: ExecuteMsg,
             query: QueryMsg,
         });
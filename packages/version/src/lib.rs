@@ -0,0 +1,48 @@
+use cosmwasm_std::{to_json_binary, CustomQuery, Deps, Env, QueryResponse, StdResult, Storage};
+use hpl_interface::version::{ContractVersionResponse, VersionQueryMsg};
+
+pub fn handle_query<C: CustomQuery>(
+    deps: Deps<'_, C>,
+    _env: Env,
+    msg: VersionQueryMsg,
+) -> StdResult<QueryResponse> {
+    match msg {
+        VersionQueryMsg::ContractVersion {} => to_json_binary(&get_contract_version(deps.storage)?),
+    }
+}
+
+pub fn get_contract_version(storage: &dyn Storage) -> StdResult<ContractVersionResponse> {
+    let info = cw2::get_contract_version(storage)?;
+
+    Ok(ContractVersionResponse {
+        contract: info.contract,
+        version: info.version,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{
+        from_json,
+        testing::{mock_dependencies, mock_env},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_query() -> anyhow::Result<()> {
+        let mut deps = mock_dependencies();
+
+        let msg = VersionQueryMsg::ContractVersion {};
+        assert!(handle_query(deps.as_ref(), mock_env(), msg.clone()).is_err());
+
+        cw2::set_contract_version(deps.as_mut().storage, "hpl-test", "0.0.1")?;
+
+        let res: ContractVersionResponse =
+            from_json(handle_query(deps.as_ref(), mock_env(), msg)?)?;
+        assert_eq!(res.contract, "hpl-test");
+        assert_eq!(res.version, "0.0.1");
+
+        Ok(())
+    }
+}
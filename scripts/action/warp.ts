@@ -13,6 +13,7 @@ program.name("Warp CLI").version(version);
 program
   .command("new")
   .argument("<denom>", 'token denom, e.g. "untrn"')
+  .argument("<decimals>", "decimals of the token, e.g. 6")
   .option(
     "--token-mode <token_mode>",
     'token mode, e.g. "collateral" or "bridged"',
@@ -31,6 +32,7 @@ program
   .argument("<address>", "address of internal warp route")
   .argument("<domain>", "domain of external chain, e.g. 5 (goerli)")
   .argument("<external_route>", "address of external route")
+  .argument("<decimals>", "decimals of the token on external chain, e.g. 18")
   .action(link);
 
 program
@@ -56,6 +58,7 @@ const parseWasmEventLog = (res: ExecuteResult) => {
 
 async function create(
   denom: string,
+  decimals: string,
   { tokenMode }: { tokenMode: "collateral" | "bridged" }
 ) {
   const client = await getSigningClient(config);
@@ -75,6 +78,7 @@ async function create(
             denom,
           },
         },
+        decimals: Number(decimals),
         hrp: config.network.hrp,
         owner: client.signer,
         mailbox: mailbox.address!,
@@ -104,7 +108,12 @@ async function setIsm(address: string, ism: string) {
   console.log(parseWasmEventLog(resp));
   console.log(resp.transactionHash);
 }
-async function link(address: string, domain: string, external_route: string) {
+async function link(
+  address: string,
+  domain: string,
+  external_route: string,
+  decimals: string
+) {
   const client = await getSigningClient(config);
   const resp = await client.wasm.execute(
     client.signer,
@@ -114,7 +123,10 @@ async function link(address: string, domain: string, external_route: string) {
         set_route: {
           set: {
             domain: Number(domain),
-            route: addPad(external_route),
+            route: {
+              address: addPad(external_route),
+              decimals: Number(decimals),
+            },
           },
         },
       },
@@ -0,0 +1,7 @@
+import { injectable } from "inversify";
+import { BaseContract } from "../types";
+
+@injectable()
+export class HplHookRatelimit extends BaseContract {
+  contractName: string = "hpl_hook_ratelimit";
+}
@@ -1,6 +1,7 @@
 export * from "./hpl_hook_aggregate";
 export * from "./hpl_hook_merkle";
 export * from "./hpl_hook_pausable";
+export * from "./hpl_hook_ratelimit";
 export * from "./hpl_hook_routing";
 export * from "./hpl_hook_routing_custom";
 export * from "./hpl_hook_routing_fallback";